@@ -60,12 +60,14 @@ impl<'b, B: BlackBoxFunctionSolver> BrilligSolver<'b, B> {
     }
 
     /// Constructs a solver for a Brillig block given the bytecode and initial
-    /// witness.
+    /// witness. If `step_limit` is set to the number of steps taken by earlier Brillig opcodes
+    /// and the limit, execution fails once the limit is reached.
     pub(super) fn new(
         initial_witness: &mut WitnessMap,
         brillig: &'b Brillig,
         bb_solver: &'b B,
         acir_index: usize,
+        step_limit: Option<(usize, usize)>,
     ) -> Result<Self, OpcodeResolutionError> {
         // Set input values
         let mut input_register_values: Vec<Value> = Vec::new();
@@ -108,9 +110,19 @@ impl<'b, B: BlackBoxFunctionSolver> BrilligSolver<'b, B> {
         // along with the Brillig bytecode.
         let input_registers = Registers::load(input_register_values);
         let vm = VM::new(input_registers, input_memory, &brillig.bytecode, vec![], bb_solver);
+        let vm = match step_limit {
+            Some((steps_taken, step_limit)) => vm.with_step_limit(steps_taken, step_limit),
+            None => vm,
+        };
         Ok(Self { vm, acir_index })
     }
 
+    /// The number of Brillig opcodes executed so far, including those of earlier Brillig opcodes
+    /// if a step limit was set
+    pub(super) fn steps(&self) -> usize {
+        self.vm.steps()
+    }
+
     pub(super) fn solve(&mut self) -> Result<BrilligSolverStatus, OpcodeResolutionError> {
         let status = self.vm.process_opcodes();
         self.handle_vm_status(status)
@@ -144,6 +144,13 @@ pub struct ACVM<'a, B: BlackBoxFunctionSolver> {
     witness_map: WitnessMap,
 
     brillig_solver: Option<BrilligSolver<'a, B>>,
+
+    /// The number of opcodes executed by the Brillig VM across all Brillig opcodes solved so far,
+    /// which is only counted if there is a step limit
+    brillig_steps: usize,
+
+    /// The number of Brillig opcodes which may be executed before execution fails, if any
+    brillig_step_limit: Option<usize>,
 }
 
 impl<'a, B: BlackBoxFunctionSolver> ACVM<'a, B> {
@@ -157,9 +164,18 @@ impl<'a, B: BlackBoxFunctionSolver> ACVM<'a, B> {
             instruction_pointer: 0,
             witness_map: initial_witness,
             brillig_solver: None,
+            brillig_steps: 0,
+            brillig_step_limit: None,
         }
     }
 
+    /// Makes execution fail once the Brillig VM has executed `step_limit` opcodes in total,
+    /// so that unconstrained code which doesn't terminate can't run forever.
+    pub fn with_brillig_step_limit(mut self, step_limit: usize) -> Self {
+        self.brillig_step_limit = Some(step_limit);
+        self
+    }
+
     /// Returns a reference to the current state of the ACVM's [`WitnessMap`].
     ///
     /// Once execution has completed, the witness map can be extracted using [`ACVM::finalize`]
@@ -234,6 +250,7 @@ impl<'a, B: BlackBoxFunctionSolver> ACVM<'a, B> {
     /// 1. All opcodes have been executed successfully.
     /// 2. The circuit has been found to be unsatisfiable.
     /// 2. A Brillig [foreign call][`ForeignCallWaitInfo`] has been encountered and must be resolved.
+    #[tracing::instrument(level = "trace", skip_all)]
     pub fn solve(&mut self) -> ACVMStatus {
         while self.status == ACVMStatus::InProgress {
             self.solve_opcode();
@@ -311,7 +328,15 @@ impl<'a, B: BlackBoxFunctionSolver> ACVM<'a, B> {
             let mut solver: BrilligSolver<'_, B> = match self.brillig_solver.take() {
                 Some(solver) => solver,
                 None => {
-                    BrilligSolver::new(witness, brillig, self.backend, self.instruction_pointer)?
+                    let step_limit =
+                        self.brillig_step_limit.map(|step_limit| (self.brillig_steps, step_limit));
+                    BrilligSolver::new(
+                        witness,
+                        brillig,
+                        self.backend,
+                        self.instruction_pointer,
+                        step_limit,
+                    )?
                 }
             };
             match solver.solve()? {
@@ -324,6 +349,9 @@ impl<'a, B: BlackBoxFunctionSolver> ACVM<'a, B> {
                     unreachable!("Brillig solver still in progress")
                 }
                 BrilligSolverStatus::Finished => {
+                    if self.brillig_step_limit.is_some() {
+                        self.brillig_steps = solver.steps();
+                    }
                     // Write execution outputs
                     solver.finalize(witness, brillig)?;
                     Ok(None)
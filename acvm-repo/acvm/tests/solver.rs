@@ -602,6 +602,35 @@ fn unsatisfied_opcode_resolved_brillig() {
     );
 }
 
+#[test]
+fn brillig_step_limit_is_shared_by_all_brillig_opcodes() {
+    // Each Brillig opcode executes two jumps before stopping
+    let brillig_opcode = Opcode::Brillig(Brillig {
+        inputs: vec![],
+        outputs: vec![],
+        bytecode: vec![
+            BrilligOpcode::Jump { location: 1 },
+            BrilligOpcode::Jump { location: 2 },
+            BrilligOpcode::Stop,
+        ],
+        predicate: None,
+    });
+    let opcodes = vec![brillig_opcode.clone(), brillig_opcode];
+
+    let mut acvm = ACVM::new(&StubbedBackend, &opcodes, WitnessMap::new());
+    assert_eq!(acvm.solve(), ACVMStatus::Solved);
+
+    let mut acvm =
+        ACVM::new(&StubbedBackend, &opcodes, WitnessMap::new()).with_brillig_step_limit(3);
+    assert_eq!(
+        acvm.solve(),
+        ACVMStatus::Failure(OpcodeResolutionError::BrilligFunctionFailed {
+            message: "exceeded the step limit of 3 opcodes".to_string(),
+            call_stack: vec![OpcodeLocation::Brillig { acir_index: 1, brillig_index: 1 }]
+        })
+    );
+}
+
 #[test]
 fn memory_operations() {
     let initial_witness = WitnessMap::from(BTreeMap::from_iter([
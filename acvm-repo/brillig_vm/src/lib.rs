@@ -81,6 +81,10 @@ pub struct VM<'a, B: BlackBoxFunctionSolver> {
     call_stack: Vec<Value>,
     /// The solver for blackbox functions
     black_box_solver: &'a B,
+    /// The number of opcodes which have been executed
+    steps: usize,
+    /// The number of opcodes after which execution fails, if any
+    step_limit: Option<usize>,
 }
 
 impl<'a, B: BlackBoxFunctionSolver> VM<'a, B> {
@@ -102,9 +106,26 @@ impl<'a, B: BlackBoxFunctionSolver> VM<'a, B> {
             memory: memory.into(),
             call_stack: Vec::new(),
             black_box_solver,
+            steps: 0,
+            step_limit: None,
         }
     }
 
+    /// Makes execution fail once `step_limit` opcodes have been executed, so that a program
+    /// which doesn't terminate can't run forever. The `steps_taken` before this VM started,
+    /// such as by the VMs of earlier calls in the same program, count towards the limit.
+    pub fn with_step_limit(mut self, steps_taken: usize, step_limit: usize) -> Self {
+        self.steps = steps_taken;
+        self.step_limit = Some(step_limit);
+        self
+    }
+
+    /// Returns the number of opcodes which have been executed so far, including the steps
+    /// taken before this VM started.
+    pub fn steps(&self) -> usize {
+        self.steps
+    }
+
     /// Updates the current status of the VM.
     /// Returns the given status.
     fn status(&mut self, status: VMStatus) -> VMStatus {
@@ -170,6 +191,12 @@ impl<'a, B: BlackBoxFunctionSolver> VM<'a, B> {
 
     /// Process a single opcode and modify the program counter.
     pub fn process_opcode(&mut self) -> VMStatus {
+        if let Some(step_limit) = self.step_limit {
+            if self.steps >= step_limit {
+                return self.fail(format!("exceeded the step limit of {step_limit} opcodes"));
+            }
+        }
+
         let opcode = &self.bytecode[self.program_counter];
         match opcode {
             Opcode::BinaryFieldOp { op, lhs, rhs, destination: result } => {
@@ -339,6 +366,8 @@ impl<'a, B: BlackBoxFunctionSolver> VM<'a, B> {
     /// in the bytecode, then the VMStatus reports halted.
     fn set_program_counter(&mut self, value: usize) -> VMStatus {
         assert!(self.program_counter < self.bytecode.len());
+        // The program counter only moves once the current opcode has been executed
+        self.steps += 1;
         self.program_counter = value;
         if self.program_counter >= self.bytecode.len() {
             self.status = VMStatus::Finished;
@@ -473,6 +502,24 @@ mod tests {
         assert_eq!(output_value, Value::from(3u128));
     }
 
+    #[test]
+    fn step_limit_stops_infinite_loops() {
+        let opcodes = [Opcode::Jump { location: 0 }];
+        let mut vm =
+            VM::new(Registers::load(vec![]), vec![], &opcodes, vec![], &DummyBlackBoxSolver)
+                .with_step_limit(5, 15);
+
+        let status = vm.process_opcodes();
+        assert_eq!(
+            status,
+            VMStatus::Failure {
+                message: "exceeded the step limit of 15 opcodes".to_string(),
+                call_stack: vec![0]
+            }
+        );
+        assert_eq!(vm.steps(), 15);
+    }
+
     #[test]
     fn jmpif_opcode() {
         let mut registers = vec![];
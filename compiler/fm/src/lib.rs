@@ -94,21 +94,52 @@ impl FileManager {
         self.id_to_path.get(&file_id).unwrap().as_path()
     }
 
-    pub fn find_module(&mut self, anchor: FileId, mod_name: &str) -> Result<FileId, String> {
+    /// Finds the file of the module `mod_name` declared in `anchor`, within the inline modules
+    /// `inline_modules` (as in `mod a { mod b { mod mod_name; } }`), which are directories
+    /// on the way to the module's file.
+    pub fn find_module(
+        &mut self,
+        anchor: FileId,
+        inline_modules: &[String],
+        mod_name: &str,
+    ) -> Result<FileId, String> {
         let anchor_path = self.path(anchor).with_extension("");
         let anchor_dir = anchor_path.parent().unwrap();
 
         // if `anchor` is a `main.nr`, `lib.nr`, `mod.nr` or `{mod_name}.nr`, we check siblings of
         // the anchor at `base/mod_name.nr`.
-        let candidate = if should_check_siblings_for_module(&anchor_path, anchor_dir) {
-            anchor_dir.join(format!("{mod_name}.{FILE_EXTENSION}"))
+        let mut candidate = if should_check_siblings_for_module(&anchor_path, anchor_dir) {
+            anchor_dir.to_path_buf()
         } else {
             // Otherwise, we check for children of the anchor at `base/anchor/mod_name.nr`
-            anchor_path.join(format!("{mod_name}.{FILE_EXTENSION}"))
+            anchor_path.clone()
         };
+        candidate.extend(inline_modules);
+        candidate.push(format!("{mod_name}.{FILE_EXTENSION}"));
 
         self.add_file(&candidate).ok_or_else(|| candidate.as_os_str().to_string_lossy().to_string())
     }
+
+    /// Reads the file at `path`, relative to the directory of `anchor` (or the root if `anchor`
+    /// isn't a file on disk), for `include_bytes!` and `include_str!`. Unlike a module, the
+    /// file is not added to the file map, and it is read from disk as raw bytes rather than
+    /// through the file reader, which only reads source files as text.
+    ///
+    /// Returns the path of the file along with its contents, or the path which was looked up if
+    /// the file could not be read.
+    pub fn read_included_file(
+        &self,
+        anchor: FileId,
+        path: &str,
+    ) -> Result<(PathBuf, Vec<u8>), String> {
+        let anchor_dir = self.id_to_path.get(&anchor).and_then(|path| path.parent());
+        let resolved_path = anchor_dir.unwrap_or(&self.root).join(path).normalize();
+
+        match std::fs::read(&resolved_path) {
+            Ok(contents) => Ok((resolved_path, contents)),
+            Err(_) => Err(resolved_path.as_os_str().to_string_lossy().to_string()),
+        }
+    }
 }
 
 /// Returns true if a module's child module's are expected to be in the same directory.
@@ -230,7 +261,7 @@ mod tests {
 
         let dep_file_name = Path::new("foo.nr");
         create_dummy_file(&dir, dep_file_name);
-        fm.find_module(file_id, "foo").unwrap_err();
+        fm.find_module(file_id, &[], "foo").unwrap_err();
     }
 
     #[test]
@@ -279,10 +310,55 @@ mod tests {
         create_dummy_file(&dir, Path::new(&format!("{sub_dir_name}.nr")));
 
         // First check for the sub_dir.nr file and add it to the FileManager
-        let sub_dir_file_id = fm.find_module(file_id, sub_dir_name).unwrap();
+        let sub_dir_file_id = fm.find_module(file_id, &[], sub_dir_name).unwrap();
 
         // Now check for files in it's subdirectory
-        fm.find_module(sub_dir_file_id, "foo").unwrap();
+        fm.find_module(sub_dir_file_id, &[], "foo").unwrap();
+    }
+
+    #[test]
+    fn path_resolve_module_within_inline_module() {
+        let dir = tempdir().unwrap();
+        let mut fm = FileManager::new(dir.path(), Box::new(|path| std::fs::read_to_string(path)));
+
+        let file_name = Path::new("lib.nr");
+        create_dummy_file(&dir, file_name);
+        let file_id = fm.add_file(file_name).unwrap();
+
+        // `mod sub_dir { mod foo; }` in lib.nr refers to dir/sub_dir/foo.nr
+        let sub_dir = TempDir::new_in(&dir).unwrap();
+        let sub_dir_name = sub_dir.path().file_name().unwrap().to_str().unwrap().to_string();
+        create_dummy_file(&sub_dir, Path::new("foo.nr"));
+
+        fm.find_module(file_id, &[], "foo").unwrap_err();
+        let foo_file_id = fm.find_module(file_id, &[sub_dir_name], "foo").unwrap();
+        assert!(fm.path(foo_file_id).starts_with(sub_dir.path()));
+    }
+
+    #[test]
+    fn path_resolve_included_file() {
+        let dir = tempdir().unwrap();
+        let mut fm = FileManager::new(dir.path(), Box::new(|path| std::fs::read_to_string(path)));
+
+        let file_name = Path::new("main.nr");
+        create_dummy_file(&dir, file_name);
+        let file_id = fm.add_file(file_name).unwrap();
+
+        let sub_dir = TempDir::new_in(&dir).unwrap();
+        let sub_dir_name = sub_dir.path().file_name().unwrap().to_str().unwrap().to_string();
+        // Included files aren't necessarily valid UTF-8
+        let key = [0xff, 0x00, 0xfe];
+        std::fs::write(sub_dir.path().join("key.bin"), key).unwrap();
+
+        let included = fm.read_included_file(file_id, &format!("{sub_dir_name}/key.bin"));
+        let (path, contents) = included.unwrap();
+        assert_eq!(path, sub_dir.path().join("key.bin").normalize());
+        assert_eq!(contents, key);
+        let included = fm.read_included_file(file_id, &format!("./{sub_dir_name}/../missing.bin"));
+        assert!(included.unwrap_err().ends_with("missing.bin"));
+
+        // Included files are not source files
+        assert_eq!(fm.path_to_id.len(), 1);
     }
 
     /// Tests that two identical files that have different paths are treated as the same file
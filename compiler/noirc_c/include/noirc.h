@@ -0,0 +1,68 @@
+/*
+ * C bindings for compiling and executing Noir programs.
+ *
+ * All JSON payloads use the same formats as nargo: program artifacts match
+ * `target/<package>.json`, inputs are the JSON equivalent of `Prover.toml` and
+ * witnesses use the compressed format written by `nargo execute`.
+ *
+ * Every buffer returned by this library must be released with `noirc_buffer_free`.
+ */
+#ifndef NOIRC_H
+#define NOIRC_H
+
+#include <stddef.h>
+#include <stdint.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+/* These values are part of the stable ABI and will never be renumbered. */
+typedef enum NoircStatus {
+    NOIRC_OK = 0,
+    NOIRC_INVALID_ARGUMENT = 1,
+    NOIRC_COMPILE_ERROR = 2,
+    NOIRC_ABI_ERROR = 3,
+    NOIRC_EXECUTION_ERROR = 4,
+    NOIRC_PANIC = 5,
+} NoircStatus;
+
+typedef struct NoircBuffer {
+    uint8_t *data;
+    size_t len;
+} NoircBuffer;
+
+/* Returns the compiler version as a static null-terminated string. */
+const char *noirc_version(void);
+
+/*
+ * Compiles the binary package in `program_dir`, which must contain a Nargo.toml.
+ * On success `program_out` holds the JSON program artifact. On failure `error_out`
+ * holds a JSON error report:
+ *   { "message": string,
+ *     "diagnostics": [{ "message", "code", "file_path", "is_warning", "secondaries" }] }
+ */
+NoircStatus noirc_compile(const char *program_dir,
+                          NoircBuffer *program_out,
+                          NoircBuffer *error_out);
+
+/* Encodes JSON inputs against a JSON ABI into the program's initial witness. */
+NoircStatus noirc_abi_encode(const uint8_t *abi, size_t abi_len,
+                             const uint8_t *inputs, size_t inputs_len,
+                             NoircBuffer *witness_out,
+                             NoircBuffer *error_out);
+
+/* Executes a JSON program artifact with JSON inputs, producing the solved witness. */
+NoircStatus noirc_execute(const uint8_t *program, size_t program_len,
+                          const uint8_t *inputs, size_t inputs_len,
+                          NoircBuffer *witness_out,
+                          NoircBuffer *error_out);
+
+/* Releases a buffer returned by this library. Freeing an empty buffer is a no-op. */
+void noirc_buffer_free(NoircBuffer buffer);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif /* NOIRC_H */
@@ -0,0 +1,60 @@
+use std::path::Path;
+
+use nargo::{
+    artifacts::{hints::WitnessHints, program::PreprocessedProgram, ARTIFACT_VERSION},
+    prepare_package,
+};
+use nargo_toml::{get_package_manifest, resolve_workspace_from_toml, PackageSelection};
+use noirc_driver::{compile_main, CompileOptions};
+
+use crate::errors::NoircError;
+
+const BACKEND_IDENTIFIER: &str = "acvm-backend-barretenberg";
+
+/// Compiles the binary package in `program_dir`, returning its JSON program artifact.
+pub(crate) fn compile(program_dir: &str) -> Result<Vec<u8>, NoircError> {
+    let program = compile_program(Path::new(program_dir))?;
+    Ok(serde_json::to_vec(&program).expect("program artifacts are serializable"))
+}
+
+/// Compiles the binary package in `program_dir`, which must contain a `Nargo.toml`.
+pub fn compile_program(program_dir: &Path) -> Result<PreprocessedProgram, NoircError> {
+    let toml_path = get_package_manifest(program_dir)
+        .map_err(|error| NoircError::InvalidArgument(error.to_string()))?;
+    let workspace = resolve_workspace_from_toml(&toml_path, PackageSelection::DefaultOrAll)
+        .map_err(|error| NoircError::InvalidArgument(error.to_string()))?;
+    let package = workspace.into_iter().find(|package| package.is_binary()).ok_or_else(|| {
+        NoircError::InvalidArgument(format!("no binary package found in {}", program_dir.display()))
+    })?;
+
+    let (mut context, crate_id) =
+        prepare_package(package, Box::new(|path| std::fs::read_to_string(path)));
+
+    let compile_options = CompileOptions::default();
+    let compiled_program = compile_main(&mut context, crate_id, &compile_options, None, true)
+        .map_err(|errors| {
+            NoircError::compilation("Failed to compile program", errors, &context.file_manager)
+        })?
+        .0;
+
+    // For now we default to plonk width = 3, matching the WASM compiler
+    let np_language = acvm::Language::PLONKCSat { width: 3 };
+    #[allow(deprecated)]
+    let is_opcode_supported = acvm::pwg::default_is_opcode_supported(np_language);
+
+    let optimized_program =
+        nargo::ops::optimize_program(compiled_program, np_language, &is_opcode_supported).map_err(
+            |error| NoircError::Compile { message: error.to_string(), diagnostics: Vec::new() },
+        )?;
+
+    Ok(PreprocessedProgram {
+        artifact_version: ARTIFACT_VERSION,
+        hash: optimized_program.hash,
+        backend: String::from(BACKEND_IDENTIFIER),
+        abi: optimized_program.abi,
+        custom_attributes: optimized_program.custom_attributes,
+        environment: optimized_program.environment,
+        witness_hints: WitnessHints::from_circuit(&optimized_program.circuit),
+        bytecode: optimized_program.circuit,
+    })
+}
@@ -0,0 +1,111 @@
+use fm::FileManager;
+use noirc_errors::{ErrorCode, FileDiagnostic};
+use serde::Serialize;
+
+use crate::NoircStatus;
+
+/// An error raised by one of the exported functions, before it is reported to the caller.
+#[derive(Debug)]
+pub enum NoircError {
+    InvalidArgument(String),
+    Compile { message: String, diagnostics: Vec<DiagnosticReport> },
+    Abi(String),
+    Execution(String),
+    Panic,
+}
+
+impl NoircError {
+    pub(crate) fn compilation(
+        message: &str,
+        file_diagnostics: Vec<FileDiagnostic>,
+        file_manager: &FileManager,
+    ) -> NoircError {
+        let diagnostics = file_diagnostics
+            .iter()
+            .map(|diagnostic| {
+                let file_path = file_manager.path(diagnostic.file_id).to_string_lossy();
+                DiagnosticReport::new(diagnostic, file_path.into_owned())
+            })
+            .collect();
+
+        NoircError::Compile { message: message.to_string(), diagnostics }
+    }
+
+    pub fn status(&self) -> NoircStatus {
+        match self {
+            NoircError::InvalidArgument(_) => NoircStatus::InvalidArgument,
+            NoircError::Compile { .. } => NoircStatus::CompileError,
+            NoircError::Abi(_) => NoircStatus::AbiError,
+            NoircError::Execution(_) => NoircStatus::ExecutionError,
+            NoircError::Panic => NoircStatus::Panic,
+        }
+    }
+}
+
+/// The JSON error report written to the `error_out` buffer of a failed call.
+#[derive(Serialize)]
+pub(crate) struct ErrorReport {
+    message: String,
+    diagnostics: Vec<DiagnosticReport>,
+}
+
+impl ErrorReport {
+    pub(crate) fn to_json(&self) -> Vec<u8> {
+        serde_json::to_vec(self).expect("error reports are always serializable")
+    }
+}
+
+impl From<NoircError> for ErrorReport {
+    fn from(error: NoircError) -> ErrorReport {
+        let (message, diagnostics) = match error {
+            NoircError::Compile { message, diagnostics } => (message, diagnostics),
+            NoircError::InvalidArgument(message)
+            | NoircError::Abi(message)
+            | NoircError::Execution(message) => (message, Vec::new()),
+            NoircError::Panic => ("the compiler panicked".to_string(), Vec::new()),
+        };
+        ErrorReport { message, diagnostics }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub(crate) struct DiagnosticLabel {
+    message: String,
+    start: u32,
+    end: u32,
+}
+
+/// A diagnostic in the layout reported by the WASM compiler.
+#[derive(Debug, Serialize)]
+pub struct DiagnosticReport {
+    message: String,
+    code: Option<&'static str>,
+    file_path: String,
+    is_warning: bool,
+    secondaries: Vec<DiagnosticLabel>,
+}
+
+impl DiagnosticReport {
+    fn new(file_diagnostic: &FileDiagnostic, file_path: String) -> DiagnosticReport {
+        let diagnostic = &file_diagnostic.diagnostic;
+        let message = diagnostic.message.clone();
+
+        let secondaries = diagnostic
+            .secondaries
+            .iter()
+            .map(|label| DiagnosticLabel {
+                message: label.message.clone(),
+                start: label.span.start(),
+                end: label.span.end(),
+            })
+            .collect();
+
+        DiagnosticReport {
+            message,
+            code: diagnostic.code.map(ErrorCode::as_str),
+            file_path,
+            is_warning: diagnostic.is_warning(),
+            secondaries,
+        }
+    }
+}
@@ -0,0 +1,56 @@
+use acvm::acir::native_types::WitnessMap;
+use nargo::artifacts::{check_artifact_version, program::PreprocessedProgram};
+use noirc_abi::{input_parser::Format, Abi, MAIN_RETURN_NAME};
+
+use crate::errors::NoircError;
+
+/// Encodes JSON `inputs` against a JSON `abi`, returning the serialized initial witness.
+pub(crate) fn abi_encode(abi: &[u8], inputs: &[u8]) -> Result<Vec<u8>, NoircError> {
+    let abi: Abi = serde_json::from_slice(abi)
+        .map_err(|error| NoircError::Abi(format!("invalid ABI: {error}")))?;
+
+    let initial_witness = encode_inputs(&abi, inputs)?;
+    serialize_witness(initial_witness)
+}
+
+/// Executes a JSON program artifact with JSON `inputs`, returning the serialized solved witness.
+pub(crate) fn execute(program: &[u8], inputs: &[u8]) -> Result<Vec<u8>, NoircError> {
+    let program: PreprocessedProgram = serde_json::from_slice(program).map_err(|error| {
+        NoircError::InvalidArgument(format!("invalid program artifact: {error}"))
+    })?;
+    check_artifact_version(program.artifact_version).map_err(|error| {
+        NoircError::InvalidArgument(format!("invalid program artifact: {error}"))
+    })?;
+
+    let initial_witness = encode_inputs(&program.abi, inputs)?;
+    let solved_witness = execute_program(&program, initial_witness)?;
+    serialize_witness(solved_witness)
+}
+
+/// Solves the witness of `program`, starting from `initial_witness`.
+pub fn execute_program(
+    program: &PreprocessedProgram,
+    initial_witness: WitnessMap,
+) -> Result<WitnessMap, NoircError> {
+    #[allow(deprecated)]
+    let blackbox_solver = barretenberg_blackbox_solver::BarretenbergSolver::new();
+    nargo::ops::execute_circuit(&blackbox_solver, &program.bytecode, initial_witness, false)
+        .map_err(|error| NoircError::Execution(error.to_string()))
+}
+
+/// Encodes JSON `inputs` against `abi`. The expected return value may be given under the
+/// `return` key, as in a `Prover.toml` file.
+pub fn encode_inputs(abi: &Abi, inputs: &[u8]) -> Result<WitnessMap, NoircError> {
+    let inputs = std::str::from_utf8(inputs)
+        .map_err(|error| NoircError::InvalidArgument(format!("invalid UTF-8: {error}")))?;
+
+    let mut input_map =
+        Format::Json.parse(inputs, abi).map_err(|error| NoircError::Abi(error.to_string()))?;
+    let return_value = input_map.remove(MAIN_RETURN_NAME);
+
+    abi.encode(&input_map, return_value).map_err(|error| NoircError::Abi(error.to_string()))
+}
+
+fn serialize_witness(witness: WitnessMap) -> Result<Vec<u8>, NoircError> {
+    Vec::<u8>::try_from(witness).map_err(|error| NoircError::Execution(error.to_string()))
+}
@@ -0,0 +1,198 @@
+//! C bindings for compiling and executing Noir programs.
+//!
+//! This allows SDKs in languages such as Python, Go or Swift to embed the compiler through a
+//! plain C ABI instead of going through the WASM build. The matching declarations can be found
+//! in `include/noirc.h`.
+//!
+//! All structured data crosses the boundary as JSON using the same formats as `nargo`:
+//! - compiled programs use the artifact format written to `target/<package>.json`,
+//! - ABIs are the `abi` field of that artifact,
+//! - inputs are the JSON equivalent of a `Prover.toml` file,
+//! - witnesses use the compressed format written by `nargo execute`.
+//!
+//! The functions behind the C ABI are also exported as a Rust API so that bindings which don't
+//! go through C, such as `noir_py`, can share them.
+#![warn(unused_crate_dependencies, unused_extern_crates)]
+#![warn(unreachable_pub)]
+#![warn(clippy::semicolon_if_nothing_returned)]
+
+use std::ffi::{c_char, CStr};
+use std::panic::{catch_unwind, UnwindSafe};
+
+mod compile;
+mod errors;
+mod execute;
+
+use errors::ErrorReport;
+
+pub use compile::compile_program;
+pub use errors::{DiagnosticReport, NoircError};
+pub use execute::{encode_inputs, execute_program};
+
+/// The result of every fallible function in this crate.
+///
+/// These values are part of the stable ABI and will never be renumbered.
+#[repr(C)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum NoircStatus {
+    Ok = 0,
+    /// A null pointer, or a string which isn't valid UTF-8, was passed in.
+    InvalidArgument = 1,
+    /// The program failed to compile. The error report contains its diagnostics.
+    CompileError = 2,
+    /// The inputs or ABI could not be parsed, or the inputs don't match the ABI.
+    AbiError = 3,
+    /// The program failed while being executed, e.g. due to a failing constraint.
+    ExecutionError = 4,
+    /// The compiler panicked. This is always a bug and should be reported.
+    Panic = 5,
+}
+
+/// An owned byte buffer allocated by this library.
+///
+/// Buffers must be released with [`noirc_buffer_free`].
+#[repr(C)]
+#[derive(Debug)]
+pub struct NoircBuffer {
+    pub data: *mut u8,
+    pub len: usize,
+}
+
+impl NoircBuffer {
+    fn empty() -> NoircBuffer {
+        NoircBuffer { data: std::ptr::null_mut(), len: 0 }
+    }
+
+    fn from_vec(bytes: Vec<u8>) -> NoircBuffer {
+        let mut bytes = bytes.into_boxed_slice();
+        let buffer = NoircBuffer { data: bytes.as_mut_ptr(), len: bytes.len() };
+        std::mem::forget(bytes);
+        buffer
+    }
+}
+
+const VERSION: &str = concat!(env!("CARGO_PKG_VERSION"), "\0");
+
+/// Returns the version of the compiler as a null-terminated string with static lifetime.
+#[no_mangle]
+pub extern "C" fn noirc_version() -> *const c_char {
+    VERSION.as_ptr().cast()
+}
+
+/// Compiles the binary package in the directory `program_dir`, which must contain a
+/// `Nargo.toml`. On success `program_out` is set to the JSON program artifact.
+///
+/// On failure `error_out` is set to a JSON error report of the form
+/// `{ "message": string, "diagnostics": [{ "message", "code", "file_path", "secondaries" }] }`.
+///
+/// # Safety
+///
+/// `program_dir` must be a valid null-terminated string. `program_out` and `error_out` must be
+/// valid pointers to write a [`NoircBuffer`] to.
+#[no_mangle]
+pub unsafe extern "C" fn noirc_compile(
+    program_dir: *const c_char,
+    program_out: *mut NoircBuffer,
+    error_out: *mut NoircBuffer,
+) -> NoircStatus {
+    let program_dir = read_str(program_dir);
+    run(program_out, error_out, move || compile::compile(program_dir?))
+}
+
+/// Encodes the JSON `inputs` against the JSON `abi`, setting `witness_out` to the initial
+/// witness of the program.
+///
+/// # Safety
+///
+/// `abi` and `inputs` must point to `abi_len` and `inputs_len` readable bytes respectively.
+/// `witness_out` and `error_out` must be valid pointers to write a [`NoircBuffer`] to.
+#[no_mangle]
+pub unsafe extern "C" fn noirc_abi_encode(
+    abi: *const u8,
+    abi_len: usize,
+    inputs: *const u8,
+    inputs_len: usize,
+    witness_out: *mut NoircBuffer,
+    error_out: *mut NoircBuffer,
+) -> NoircStatus {
+    let abi = read_bytes(abi, abi_len);
+    let inputs = read_bytes(inputs, inputs_len);
+    run(witness_out, error_out, move || execute::abi_encode(abi?, inputs?))
+}
+
+/// Executes a JSON program artifact, as returned by [`noirc_compile`], with the JSON `inputs`.
+/// On success `witness_out` is set to the solved witness.
+///
+/// # Safety
+///
+/// `program` and `inputs` must point to `program_len` and `inputs_len` readable bytes
+/// respectively. `witness_out` and `error_out` must be valid pointers to write a
+/// [`NoircBuffer`] to.
+#[no_mangle]
+pub unsafe extern "C" fn noirc_execute(
+    program: *const u8,
+    program_len: usize,
+    inputs: *const u8,
+    inputs_len: usize,
+    witness_out: *mut NoircBuffer,
+    error_out: *mut NoircBuffer,
+) -> NoircStatus {
+    let program = read_bytes(program, program_len);
+    let inputs = read_bytes(inputs, inputs_len);
+    run(witness_out, error_out, move || execute::execute(program?, inputs?))
+}
+
+/// Releases a buffer returned by this library. Freeing an empty buffer is a no-op.
+///
+/// # Safety
+///
+/// `buffer` must have been returned by this library and not have been freed already.
+#[no_mangle]
+pub unsafe extern "C" fn noirc_buffer_free(buffer: NoircBuffer) {
+    if !buffer.data.is_null() {
+        let slice = std::ptr::slice_from_raw_parts_mut(buffer.data, buffer.len);
+        drop(Box::from_raw(slice));
+    }
+}
+
+/// Runs `f`, writing its output or error report into the given out pointers.
+unsafe fn run(
+    output: *mut NoircBuffer,
+    error_out: *mut NoircBuffer,
+    f: impl FnOnce() -> Result<Vec<u8>, NoircError> + UnwindSafe,
+) -> NoircStatus {
+    if output.is_null() || error_out.is_null() {
+        return NoircStatus::InvalidArgument;
+    }
+    *output = NoircBuffer::empty();
+    *error_out = NoircBuffer::empty();
+
+    let error = match catch_unwind(f) {
+        Ok(Ok(bytes)) => {
+            *output = NoircBuffer::from_vec(bytes);
+            return NoircStatus::Ok;
+        }
+        Ok(Err(error)) => error,
+        Err(_) => NoircError::Panic,
+    };
+
+    let status = error.status();
+    *error_out = NoircBuffer::from_vec(ErrorReport::from(error).to_json());
+    status
+}
+
+unsafe fn read_str<'a>(string: *const c_char) -> Result<&'a str, NoircError> {
+    if string.is_null() {
+        return Err(NoircError::InvalidArgument("unexpected null pointer".to_string()));
+    }
+    CStr::from_ptr(string)
+        .to_str()
+        .map_err(|error| NoircError::InvalidArgument(format!("invalid UTF-8: {error}")))
+}
+
+unsafe fn read_bytes<'a>(data: *const u8, len: usize) -> Result<&'a [u8], NoircError> {
+    if data.is_null() {
+        return Err(NoircError::InvalidArgument("unexpected null pointer".to_string()));
+    }
+    Ok(std::slice::from_raw_parts(data, len))
+}
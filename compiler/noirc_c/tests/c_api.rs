@@ -0,0 +1,175 @@
+//! Calls the exported functions the way a C caller would, through raw pointers and out
+//! parameters, checking both the buffers written on success and the JSON error reports.
+
+use std::ffi::{CStr, CString};
+use std::path::Path;
+
+use noirc_c::{
+    noirc_abi_encode, noirc_buffer_free, noirc_compile, noirc_execute, noirc_version, NoircBuffer,
+    NoircStatus,
+};
+use serde_json::Value;
+
+const MAIN: &str = r#"
+fn main(x: Field, y: pub Field) -> pub Field {
+    assert(x != y);
+    x * y
+}
+"#;
+
+/// Writes a binary package with the given `main.nr` into a temporary directory
+fn write_package(main: &str) -> tempfile::TempDir {
+    let dir = tempfile::tempdir().unwrap();
+    let manifest =
+        "[package]\nname = \"c_api\"\ntype = \"bin\"\nauthors = [\"\"]\n\n[dependencies]";
+    std::fs::write(dir.path().join("Nargo.toml"), manifest).unwrap();
+    std::fs::create_dir(dir.path().join("src")).unwrap();
+    std::fs::write(dir.path().join("src").join("main.nr"), main).unwrap();
+    dir
+}
+
+/// Copies the contents of a buffer returned by the library and frees it
+fn take(buffer: NoircBuffer) -> Vec<u8> {
+    let bytes = if buffer.data.is_null() {
+        Vec::new()
+    } else {
+        unsafe { std::slice::from_raw_parts(buffer.data, buffer.len) }.to_vec()
+    };
+    unsafe { noirc_buffer_free(buffer) };
+    bytes
+}
+
+fn empty_buffer() -> NoircBuffer {
+    NoircBuffer { data: std::ptr::null_mut(), len: 0 }
+}
+
+/// Compiles the package in `program_dir`, returning the status with the output and error buffers
+fn compile(program_dir: &Path) -> (NoircStatus, Vec<u8>, Vec<u8>) {
+    let program_dir = CString::new(program_dir.to_str().unwrap()).unwrap();
+    let (mut program, mut error) = (empty_buffer(), empty_buffer());
+    let status = unsafe { noirc_compile(program_dir.as_ptr(), &mut program, &mut error) };
+    (status, take(program), take(error))
+}
+
+fn execute(program: &[u8], inputs: &str) -> (NoircStatus, Vec<u8>, Vec<u8>) {
+    let (mut witness, mut error) = (empty_buffer(), empty_buffer());
+    let status = unsafe {
+        noirc_execute(
+            program.as_ptr(),
+            program.len(),
+            inputs.as_ptr(),
+            inputs.len(),
+            &mut witness,
+            &mut error,
+        )
+    };
+    (status, take(witness), take(error))
+}
+
+fn abi_encode(abi: &[u8], inputs: &str) -> (NoircStatus, Vec<u8>, Vec<u8>) {
+    let (mut witness, mut error) = (empty_buffer(), empty_buffer());
+    let status = unsafe {
+        noirc_abi_encode(
+            abi.as_ptr(),
+            abi.len(),
+            inputs.as_ptr(),
+            inputs.len(),
+            &mut witness,
+            &mut error,
+        )
+    };
+    (status, take(witness), take(error))
+}
+
+fn error_message(error: &[u8]) -> String {
+    let report: Value = serde_json::from_slice(error).unwrap();
+    report["message"].as_str().unwrap().to_owned()
+}
+
+#[test]
+fn version_matches_the_crate_version() {
+    let version = unsafe { CStr::from_ptr(noirc_version()) };
+    assert_eq!(version.to_str().unwrap(), env!("CARGO_PKG_VERSION"));
+}
+
+#[test]
+fn compiles_encodes_and_executes_a_program() {
+    let package = write_package(MAIN);
+    let (status, program, error) = compile(package.path());
+    assert_eq!(status, NoircStatus::Ok, "{}", String::from_utf8_lossy(&error));
+    assert!(error.is_empty());
+
+    let artifact: Value = serde_json::from_slice(&program).unwrap();
+    let abi = serde_json::to_vec(&artifact["abi"]).unwrap();
+    let (status, initial_witness, _) = abi_encode(&abi, r#"{ "x": "3", "y": "4" }"#);
+    assert_eq!(status, NoircStatus::Ok);
+    assert!(!initial_witness.is_empty());
+
+    let (status, solved_witness, error) = execute(&program, r#"{ "x": "3", "y": "4" }"#);
+    assert_eq!(status, NoircStatus::Ok, "{}", String::from_utf8_lossy(&error));
+    assert!(solved_witness.len() > initial_witness.len());
+
+    let (status, witness, error) = execute(&program, r#"{ "x": "3", "y": "3" }"#);
+    assert_eq!(status, NoircStatus::ExecutionError);
+    assert!(witness.is_empty());
+    assert!(!error_message(&error).is_empty());
+}
+
+#[test]
+fn reports_compilation_errors_with_their_diagnostics() {
+    let package = write_package("fn main(x: Field) { let y: u8 = x; }");
+    let (status, program, error) = compile(package.path());
+    assert_eq!(status, NoircStatus::CompileError);
+    assert!(program.is_empty());
+
+    let report: Value = serde_json::from_slice(&error).unwrap();
+    let diagnostics = report["diagnostics"].as_array().unwrap();
+    let errors: Vec<_> =
+        diagnostics.iter().filter(|diagnostic| diagnostic["is_warning"] == false).collect();
+    assert_eq!(errors.len(), 1, "{report}");
+    assert!(errors[0]["file_path"].as_str().unwrap().ends_with("main.nr"));
+}
+
+#[test]
+fn reports_inputs_which_do_not_match_the_abi() {
+    let package = write_package(MAIN);
+    let (_, program, _) = compile(package.path());
+    let artifact: Value = serde_json::from_slice(&program).unwrap();
+    let abi = serde_json::to_vec(&artifact["abi"]).unwrap();
+
+    let (status, witness, error) = abi_encode(&abi, r#"{ "x": "3" }"#);
+    assert_eq!(status, NoircStatus::AbiError);
+    assert!(witness.is_empty());
+    assert!(!error_message(&error).is_empty());
+
+    let (status, _, _) = abi_encode(b"not an abi", r#"{ "x": "3", "y": "4" }"#);
+    assert_eq!(status, NoircStatus::AbiError);
+}
+
+#[test]
+fn rejects_invalid_arguments() {
+    let (mut output, mut error) = (empty_buffer(), empty_buffer());
+    let status = unsafe { noirc_compile(std::ptr::null(), &mut output, &mut error) };
+    assert_eq!(status, NoircStatus::InvalidArgument);
+    assert_eq!(error_message(&take(error)), "unexpected null pointer");
+    assert!(take(output).is_empty());
+
+    let program_dir = CString::new("/does/not/exist").unwrap();
+    let mut error = empty_buffer();
+    let status = unsafe { noirc_compile(program_dir.as_ptr(), std::ptr::null_mut(), &mut error) };
+    assert_eq!(status, NoircStatus::InvalidArgument);
+    assert!(take(error).is_empty());
+
+    let (status, _, error) = compile(Path::new("/does/not/exist"));
+    assert_eq!(status, NoircStatus::InvalidArgument);
+    assert!(!error_message(&error).is_empty());
+
+    let (status, _, error) = execute(b"{}", "{}");
+    assert_eq!(status, NoircStatus::InvalidArgument);
+    assert!(error_message(&error).starts_with("invalid program artifact"));
+}
+
+#[test]
+fn freeing_an_empty_buffer_is_a_no_op() {
+    unsafe { noirc_buffer_free(empty_buffer()) };
+}
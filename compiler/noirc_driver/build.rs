@@ -0,0 +1,38 @@
+use std::path::{Path, PathBuf};
+
+use noirc_frontend::parser::preparsed::PreparsedFiles;
+use noirc_frontend::{parse_program, ParsedModule};
+
+/// Parses every stdlib file under `directory`, keying each by its path as seen by the
+/// `FileManager`, e.g. `std/hash.nr`. Files which fail to parse are left out so that their
+/// errors are reported as usual when they are parsed from source.
+fn parse_stdlib(directory: &Path, path_prefix: &str, modules: &mut Vec<(String, ParsedModule)>) {
+    for entry in std::fs::read_dir(directory).unwrap() {
+        let path = entry.unwrap().path();
+        let file_name = path.file_name().unwrap().to_str().unwrap();
+        let module_path = format!("{path_prefix}/{file_name}");
+
+        if path.is_dir() {
+            parse_stdlib(&path, &module_path, modules);
+        } else if path.extension().map_or(false, |extension| extension == "nr") {
+            println!("cargo:rerun-if-changed={}", path.to_string_lossy());
+
+            let source = std::fs::read_to_string(&path).unwrap();
+            let (module, errors) = parse_program(&source);
+            if errors.is_empty() {
+                modules.push((module_path, module));
+            }
+        }
+    }
+}
+
+fn main() {
+    let stdlib_src_dir = Path::new("../../noir_stdlib/src");
+    println!("cargo:rerun-if-changed={}", stdlib_src_dir.to_string_lossy());
+
+    let mut modules = Vec::new();
+    parse_stdlib(stdlib_src_dir, "std", &mut modules);
+
+    let out_dir = PathBuf::from(std::env::var("OUT_DIR").unwrap());
+    std::fs::write(out_dir.join("stdlib.bin"), PreparsedFiles::serialize(modules)).unwrap();
+}
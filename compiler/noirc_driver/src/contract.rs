@@ -1,10 +1,12 @@
 use serde::{Deserialize, Serialize};
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::PathBuf;
 
 use acvm::acir::circuit::Circuit;
 use fm::FileId;
 use noirc_abi::{Abi, ContractEvent};
 use noirc_errors::debug_info::DebugInfo;
+use noirc_frontend::token::CustomAttribute;
 
 use super::debug::DebugFile;
 use crate::program::{deserialize_circuit, serialize_circuit};
@@ -39,6 +41,14 @@ pub struct CompiledContract {
     /// by any contract function during execution.
     pub events: Vec<ContractEvent>,
 
+    /// The environment variables read by `std::env!` while compiling the contract, along with
+    /// the values they were compiled with
+    pub environment: BTreeMap<String, String>,
+
+    /// The files read by `std::include_bytes!` and `std::include_str!` while compiling the
+    /// contract
+    pub included_files: BTreeSet<PathBuf>,
+
     pub file_map: BTreeMap<FileId, DebugFile>,
 }
 
@@ -52,10 +62,17 @@ pub struct CompiledContract {
 pub struct ContractFunction {
     pub name: String,
 
+    /// The first four bytes of the keccak256 hash of the function's signature, used to call it
+    /// from other contracts
+    pub selector: u32,
+
     pub function_type: ContractFunctionType,
 
     pub is_internal: bool,
 
+    /// Attributes on the function which are not recognized by the compiler
+    pub custom_attributes: Vec<CustomAttribute>,
+
     pub abi: Abi,
 
     #[serde(serialize_with = "serialize_circuit", deserialize_with = "deserialize_circuit")]
@@ -3,40 +3,73 @@
 #![warn(unreachable_pub)]
 #![warn(clippy::semicolon_if_nothing_returned)]
 
-use clap::Args;
+use clap::{builder::PossibleValuesParser, Args};
 use debug::filter_relevant_files;
 use fm::FileId;
 use noirc_abi::{AbiParameter, AbiType, ContractEvent};
-use noirc_errors::{CustomDiagnostic, FileDiagnostic};
+use noirc_errors::{CustomDiagnostic, FileDiagnostic, Lint, LintLevel, MessageFormat};
 use noirc_evaluator::errors::RuntimeError;
-use noirc_evaluator::{create_circuit, into_abi_params};
+use noirc_evaluator::{
+    create_circuit, into_abi_params, CodegenOptions, SsaLogging, REQUIRED_SSA_PASSES,
+    SSA_PASS_NAMES,
+};
 use noirc_frontend::graph::{CrateId, CrateName};
 use noirc_frontend::hir::def_map::{Contract, CrateDefMap};
 use noirc_frontend::hir::Context;
-use noirc_frontend::monomorphization::monomorphize;
+use noirc_frontend::monomorphization::{errors::MonomorphizationError, monomorphize};
 use noirc_frontend::node_interner::FuncId;
+use noirc_frontend::parser::preparsed::PreparsedFiles;
+use noirc_printable_type::FieldDisplay;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::path::Path;
+use thiserror::Error;
 
 mod contract;
 mod debug;
+mod oracle;
+mod profile;
 mod program;
 
 pub use contract::{CompiledContract, ContractFunction, ContractFunctionType};
 pub use debug::DebugFile;
+pub use noirc_frontend::hir::cancellation::{CancellationToken, Cancelled, Interrupted};
+pub use oracle::{
+    compute_oracle_signatures, ForeignCallValueKind, ForeignCallValueSchema, OracleSignature,
+};
+pub use profile::{BuildProfile, DebugLevel};
 pub use program::CompiledProgram;
 
 const STD_CRATE_NAME: &str = "std";
 
+/// The stdlib, parsed by `build.rs` so that it isn't parsed again on every compilation.
+const PREPARSED_STDLIB: &[u8] = include_bytes!(concat!(env!("OUT_DIR"), "/stdlib.bin"));
+
 #[derive(Args, Clone, Debug, Default, Serialize, Deserialize)]
 pub struct CompileOptions {
     /// Emit debug information for the intermediate SSA IR
     #[arg(long, hide = true)]
     pub show_ssa: bool,
 
+    /// Print the SSA after each run of the named pass, or after every pass with `all`
+    #[arg(long, hide = true, value_name = "PASS", value_parser = ssa_pass_parser())]
+    pub show_ssa_pass: Option<String>,
+
     #[arg(long, hide = true)]
     pub show_brillig: bool,
 
+    /// Print the number of instructions of each kind in every SSA function after each SSA pass
+    #[arg(long, hide = true)]
+    pub show_ssa_metrics: bool,
+
+    /// How to display field elements in output: `decimal`, `hex` or `signed`
+    #[arg(long)]
+    pub field_display: Option<FieldDisplay>,
+
+    /// Insert a counter at the start of each block so that execution reports how often each block ran
+    #[arg(long)]
+    pub instrument_blocks: bool,
+
     /// Display the ACIR for compiled circuit
     #[arg(long)]
     pub print_acir: bool,
@@ -48,6 +81,111 @@ pub struct CompileOptions {
     /// Suppress warnings
     #[arg(long, conflicts_with = "deny_warnings")]
     pub silence_warnings: bool,
+
+    /// The format to report errors and warnings in, either `human` or `json`
+    #[arg(long, default_value_t)]
+    pub message_format: MessageFormat,
+
+    /// Don't report the warnings of a lint, unless an attribute says otherwise
+    #[arg(long, value_name = "LINT")]
+    pub allow: Vec<Lint>,
+
+    /// Report the warnings of a lint as warnings, unless an attribute says otherwise
+    #[arg(long, value_name = "LINT")]
+    pub warn: Vec<Lint>,
+
+    /// Report the warnings of a lint as errors, unless an attribute says otherwise
+    #[arg(long, value_name = "LINT")]
+    pub deny: Vec<Lint>,
+
+    /// Convert arguments whose type differs from their parameter's with the `From` impl
+    /// between the two types, if there is one
+    #[arg(long)]
+    pub implicit_conversions: bool,
+
+    /// Wrap the results of unconstrained calls from the constrained functions of the root crate
+    /// in `std::unchecked::Unchecked`, which must be checked before the value is used
+    #[arg(long)]
+    pub unchecked_hints: bool,
+
+    /// Report the values compared by a failing `assert_eq` or `assert_ne` without a message.
+    /// This adds an unconstrained call for each of them, so changes the compiled circuit
+    #[arg(long)]
+    pub report_assert_values: bool,
+
+    /// Build with the package's release profile instead of its dev profile
+    #[arg(long)]
+    pub release: bool,
+
+    /// Fail compilation if a circuit has more opcodes than this once optimized for the backend,
+    /// overriding the package's `max_opcodes`
+    #[arg(long, value_name = "COUNT")]
+    pub max_opcodes: Option<usize>,
+
+    /// The optimization level, overriding the `opt-level` of the package's build profile.
+    /// `0` skips the SSA passes which only simplify the program and `2` runs some of them twice
+    #[arg(short = 'O', long, value_name = "LEVEL", value_parser = opt_level_parser())]
+    pub opt_level: Option<u8>,
+
+    /// Skip the named SSA pass, for example to find out whether it miscompiles a program.
+    /// May be repeated, but the passes needed to generate code can't be skipped
+    #[arg(long, value_name = "PASS", value_parser = disabled_pass_parser())]
+    pub disable_pass: Vec<String>,
+
+    /// The build profile of the package being compiled
+    #[arg(skip)]
+    pub profile: BuildProfile,
+}
+
+/// Parses the argument of `--show-ssa-pass`, which is either the name of an SSA pass or `all`.
+fn ssa_pass_parser() -> PossibleValuesParser {
+    PossibleValuesParser::new(std::iter::once("all").chain(SSA_PASS_NAMES.iter().copied()))
+}
+
+/// Parses the argument of `--opt-level`, which is at most [`BuildProfile::MAX_OPT_LEVEL`].
+fn opt_level_parser() -> clap::builder::RangedI64ValueParser<u8> {
+    clap::value_parser!(u8).range(0..=i64::from(BuildProfile::MAX_OPT_LEVEL))
+}
+
+/// Parses the argument of `--disable-pass`, which is the name of an SSA pass that isn't required.
+fn disabled_pass_parser() -> PossibleValuesParser {
+    let optional_passes = SSA_PASS_NAMES
+        .iter()
+        .filter(|pass| **pass != "initial" && !REQUIRED_SSA_PASSES.contains(pass));
+    PossibleValuesParser::new(optional_passes.copied())
+}
+
+impl CompileOptions {
+    /// The codegen settings of the build profile, along with the optimization level and disabled
+    /// passes selected by `--opt-level` and `--disable-pass`.
+    pub fn codegen_options(&self) -> CodegenOptions {
+        let mut codegen = self.profile.codegen_options();
+        if let Some(opt_level) = self.opt_level {
+            codegen.opt_level = opt_level;
+        }
+        codegen.disabled_passes = self.disable_pass.clone();
+        codegen
+    }
+
+    /// The stages of the SSA pipeline to print the SSA after, as selected by `--show-ssa` and
+    /// `--show-ssa-pass`.
+    pub fn ssa_logging(&self) -> SsaLogging {
+        match self.show_ssa_pass.as_deref() {
+            _ if self.show_ssa => SsaLogging::All,
+            Some("all") => SsaLogging::All,
+            Some(pass) => SsaLogging::Pass(pass.to_owned()),
+            None => SsaLogging::None,
+        }
+    }
+
+    /// The lint levels set by `--allow`, `--warn` and `--deny`. If a lint is given to more than
+    /// one of them the strictest level is used.
+    pub fn lint_levels(&self) -> impl Iterator<Item = (Lint, LintLevel)> + '_ {
+        let allow = self.allow.iter().map(|lint| (*lint, LintLevel::Allow));
+        let warn = self.warn.iter().map(|lint| (*lint, LintLevel::Warn));
+        let deny = self.deny.iter().map(|lint| (*lint, LintLevel::Deny));
+        allow.chain(warn).chain(deny)
+    }
 }
 
 /// Helper type used to signify where only warnings are expected in file diagnostics
@@ -71,6 +209,7 @@ pub fn prepare_crate(context: &mut Context, file_name: &Path) -> CrateId {
     let path_to_std_lib_file = Path::new(STD_CRATE_NAME).join("lib.nr");
     let std_file_id = context.file_manager.add_file(&path_to_std_lib_file).unwrap();
     let std_crate_id = context.crate_graph.add_stdlib(std_file_id);
+    context.preparsed_files = PreparsedFiles::from_bytes(PREPARSED_STDLIB);
 
     let root_file_id = context.file_manager.add_file(file_name).unwrap();
 
@@ -107,10 +246,35 @@ pub fn add_dep(
         .expect("cyclic dependency triggered");
 }
 
+/// Sets the levels of the lints given to `--allow`, `--warn` and `--deny`, whether arguments
+/// are implicitly converted, whether unconstrained results are wrapped in `Unchecked`, whether
+/// failing comparisons report their values, and the environment variables `std::env!` can read,
+/// for [`check_crate`].
+pub fn configure_frontend(context: &mut Context, options: &CompileOptions) {
+    for (lint, level) in options.lint_levels() {
+        context.lint_levels.set_command_line_level(lint, level);
+    }
+    context.def_interner.set_implicit_conversions(options.implicit_conversions);
+    context.def_interner.set_unchecked_hints(options.unchecked_hints);
+    context.def_interner.set_report_assert_values(options.report_assert_values);
+    context.def_interner.set_environment(
+        std::env::vars_os().filter_map(|(name, value)| {
+            Some((name.into_string().ok()?, value.into_string().ok()?))
+        }),
+    );
+}
+
 /// Run the lexing, parsing, name resolution, and type checking passes.
 ///
+/// Warnings are reported at the level of their lint, so those which are allowed are left out.
+///
 /// This returns a (possibly empty) vector of any warnings found on success.
 /// On error, this returns a non-empty vector of warnings and error messages, with at least one error.
+///
+/// If the [`CancellationToken`] set on the context's interner is cancelled, this and the other
+/// compilation functions unwind with [`Cancelled`] instead, which can be caught with
+/// [`Cancelled::catch`].
+#[tracing::instrument(skip(context))]
 pub fn check_crate(
     context: &mut Context,
     crate_id: CrateId,
@@ -118,9 +282,9 @@ pub fn check_crate(
 ) -> CompilationResult<()> {
     let mut errors = vec![];
     let diagnostics = CrateDefMap::collect_defs(crate_id, context);
-    errors.extend(diagnostics.into_iter().map(|(error, file_id)| {
+    errors.extend(diagnostics.into_iter().filter_map(|(error, file_id)| {
         let diagnostic: CustomDiagnostic = error.into();
-        diagnostic.in_file(file_id)
+        context.lint_levels.apply(diagnostic.in_file(file_id))
     }));
 
     if has_errors(&errors, deny_warnings) {
@@ -148,6 +312,7 @@ pub fn compute_function_abi(
 ///
 /// On success this returns the compiled program alongside any warnings that were found.
 /// On error this returns the non-empty list of warnings and errors.
+#[tracing::instrument(skip(context, options, cached_program))]
 pub fn compile_main(
     context: &mut Context,
     crate_id: CrateId,
@@ -155,6 +320,7 @@ pub fn compile_main(
     cached_program: Option<CompiledProgram>,
     force_compile: bool,
 ) -> CompilationResult<CompiledProgram> {
+    configure_frontend(context, options);
     let (_, warnings) = check_crate(context, crate_id, options.deny_warnings)?;
 
     let main = match context.get_main_function(&crate_id) {
@@ -181,11 +347,13 @@ pub fn compile_main(
 }
 
 /// Run the frontend to check the crate for errors then compile all contracts if there were none
+#[tracing::instrument(skip(context, options))]
 pub fn compile_contract(
     context: &mut Context,
     crate_id: CrateId,
     options: &CompileOptions,
 ) -> CompilationResult<CompiledContract> {
+    configure_frontend(context, options);
     let (_, warnings) = check_crate(context, crate_id, options.deny_warnings)?;
 
     // TODO: We probably want to error if contracts is empty
@@ -250,6 +418,7 @@ fn compile_contract_inner(
 ) -> Result<CompiledContract, ErrorsAndWarnings> {
     let mut functions = Vec::new();
     let mut errors = Vec::new();
+    let mut selectors: HashMap<u32, String> = HashMap::new();
     for contract_function in &contract.functions {
         let function_id = contract_function.function_id;
         let is_entry_point = contract_function.is_entry_point;
@@ -264,6 +433,18 @@ fn compile_contract_inner(
             continue;
         }
 
+        // Other contracts call functions by their selector, so two functions can't share one
+        let meta = context.def_interner.function_meta(&function_id);
+        let selector = meta.selector(&name);
+        if let Some(other) = selectors.insert(selector, name.clone()) {
+            let error = CustomDiagnostic::simple_error(
+                format!("Function `{name}` has the same selector as `{other}`"),
+                format!("Both selectors are {selector:#010x}, rename one of the functions"),
+                meta.location.span,
+            );
+            errors.push(error.in_file(meta.location.file));
+        }
+
         let function = match compile_no_check(context, options, function_id, None, true) {
             Ok(function) => function,
             Err(new_error) => {
@@ -280,8 +461,10 @@ fn compile_contract_inner(
 
         functions.push(ContractFunction {
             name,
+            selector,
             function_type,
             is_internal: modifiers.is_internal.unwrap_or(false),
+            custom_attributes: modifiers.attributes.custom_attributes(),
             abi: function.abi,
             bytecode: function.circuit,
             debug: function.debug,
@@ -304,6 +487,8 @@ fn compile_contract_inner(
                 })
                 .collect(),
             functions,
+            environment: context.def_interner.environment_reads().clone(),
+            included_files: context.def_interner.included_files().clone(),
             file_map,
         })
     } else {
@@ -311,36 +496,93 @@ fn compile_contract_inner(
     }
 }
 
+/// An error found while compiling a crate which has already been type checked
+#[derive(Debug, Error)]
+pub enum CompileError {
+    #[error(transparent)]
+    MonomorphizationError(#[from] MonomorphizationError),
+
+    #[error(transparent)]
+    RuntimeError(#[from] RuntimeError),
+}
+
+impl From<CompileError> for FileDiagnostic {
+    fn from(error: CompileError) -> FileDiagnostic {
+        match error {
+            CompileError::MonomorphizationError(error) => error.into(),
+            CompileError::RuntimeError(error) => error.into(),
+        }
+    }
+}
+
 /// Compile the current crate. Assumes self.check_crate is called beforehand!
 ///
 /// This function also assumes all errors in experimental_create_circuit and create_circuit
 /// are not warnings.
 #[allow(deprecated)]
+#[tracing::instrument(skip(context, options, cached_program))]
 pub fn compile_no_check(
     context: &Context,
     options: &CompileOptions,
     main_function: FuncId,
     cached_program: Option<CompiledProgram>,
     force_compile: bool,
-) -> Result<CompiledProgram, RuntimeError> {
-    let program = monomorphize(main_function, &context.def_interner);
+) -> Result<CompiledProgram, CompileError> {
+    let program = monomorphize(main_function, &context.def_interner)?;
+    let custom_attributes =
+        context.def_interner.function_attributes(&main_function).custom_attributes();
+    let environment = context.def_interner.environment_reads().clone();
+    let included_files = context.def_interner.included_files().clone();
 
-    let hash = fxhash::hash64(&program);
+    // Programs built with different settings must not be mistaken for each other in the cache
+    let codegen = options.codegen_options();
+    let hash = fxhash::hash64(&(&program, options.instrument_blocks, &options.profile, &codegen));
 
     // If user has specified that they want to see intermediate steps printed then we should
     // force compilation even if the program hasn't changed.
-    if !(force_compile || options.print_acir || options.show_brillig || options.show_ssa) {
+    if !(force_compile
+        || options.print_acir
+        || options.show_brillig
+        || options.show_ssa
+        || options.show_ssa_pass.is_some()
+        || options.show_ssa_metrics)
+    {
         if let Some(cached_program) = cached_program {
             if hash == cached_program.hash {
-                return Ok(cached_program);
+                tracing::debug!("program is unchanged, reusing the cached artifact");
+                // Attributes don't affect the program so may have changed since it was cached
+                return Ok(CompiledProgram {
+                    custom_attributes,
+                    environment,
+                    included_files,
+                    ..cached_program
+                });
             }
         }
     }
 
-    let (circuit, debug, abi) =
-        create_circuit(context, program, options.show_ssa, options.show_brillig)?;
+    let (circuit, mut debug, abi) = create_circuit(
+        context,
+        program,
+        options.ssa_logging(),
+        options.field_display,
+        options.show_brillig,
+        options.show_ssa_metrics,
+        options.instrument_blocks,
+        codegen,
+    )?;
+    options.profile.strip_debug_info(&mut debug);
 
     let file_map = filter_relevant_files(&[debug.clone()], &context.file_manager);
 
-    Ok(CompiledProgram { hash, circuit, debug, abi, file_map })
+    Ok(CompiledProgram {
+        hash,
+        circuit,
+        abi,
+        custom_attributes,
+        environment,
+        included_files,
+        debug,
+        file_map,
+    })
 }
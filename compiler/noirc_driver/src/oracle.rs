@@ -0,0 +1,301 @@
+use noirc_frontend::{hir::Context, hir_def::stmt::HirPattern, node_interner::NodeInterner, Type};
+use serde::{Deserialize, Serialize};
+
+/// An oracle declared by a Noir function, described by the values of the foreign call made
+/// whenever the function is called.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct OracleSignature {
+    /// The name of the foreign call, given by the `#[oracle(...)]` attribute of the function.
+    pub name: String,
+    /// The fully qualified name of the Noir function declaring the oracle.
+    pub function: String,
+    /// The values passed to the foreign call, in order.
+    pub inputs: Vec<ForeignCallValueSchema>,
+    /// The values the foreign call is expected to return, in order.
+    pub outputs: Vec<ForeignCallValueSchema>,
+}
+
+/// A single input or output of a foreign call.
+///
+/// Structs and tuples are passed as one value per field, in the order of the struct's layout,
+/// while arrays and strings are passed as a single array of all of their flattened field elements.
+/// Structs with a packed layout, passed directly as an argument or returned, are passed as an
+/// array too. Slices are passed as their length followed by an array of their flattened elements.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct ForeignCallValueSchema {
+    /// The path of the value within the parameters or the return value of the oracle, such as
+    /// `point.x` or `return.0`.
+    pub path: String,
+    /// The Noir type of the value.
+    #[serde(rename = "type")]
+    pub typ: String,
+    #[serde(flatten)]
+    pub kind: ForeignCallValueKind,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum ForeignCallValueKind {
+    /// A single field element.
+    Single,
+    /// An array of field elements. Its length is unknown for slices and for arrays of generic
+    /// length.
+    Array { length: Option<u64> },
+    /// A value whose representation depends on the type the oracle is called with, such as a
+    /// value of a generic type.
+    Generic,
+}
+
+/// Returns the signatures of all oracles declared in the crates of the context, sorted by the
+/// name of their foreign call.
+///
+/// Expects `check_crate` to be called beforehand.
+pub fn compute_oracle_signatures(context: &Context) -> Vec<OracleSignature> {
+    let interner = &context.def_interner;
+    let mut signatures: Vec<_> = context
+        .crate_graph
+        .iter_keys()
+        .filter_map(|crate_id| Some((crate_id, context.def_map(&crate_id)?)))
+        .flat_map(|(crate_id, def_map)| {
+            def_map.get_all_oracle_functions(interner).map(move |(func_id, name)| {
+                let function = context.fully_qualified_function_name(&crate_id, &func_id);
+                let (parameters, return_type) =
+                    interner.function_meta(&func_id).into_function_signature();
+
+                let mut inputs = Vec::new();
+                for (index, (pattern, typ, _)) in parameters.iter().enumerate() {
+                    let path = parameter_name(pattern, interner)
+                        .map_or_else(|| format!("_{index}"), ToOwned::to_owned);
+                    flatten_argument(path, typ, &mut inputs);
+                }
+                let mut outputs = Vec::new();
+                if let Some(return_type) = return_type {
+                    flatten_argument("return".to_owned(), &return_type, &mut outputs);
+                }
+
+                OracleSignature { name, function, inputs, outputs }
+            })
+        })
+        .collect();
+
+    signatures.sort_by(|a, b| (&a.name, &a.function).cmp(&(&b.name, &b.function)));
+    signatures
+}
+
+fn parameter_name<'a>(pattern: &HirPattern, interner: &'a NodeInterner) -> Option<&'a str> {
+    match pattern {
+        HirPattern::Identifier(ident) => Some(interner.definition_name(ident.id)),
+        HirPattern::Mutable(pattern, _) => parameter_name(pattern, interner),
+        HirPattern::Tuple(..) | HirPattern::Struct(..) => None,
+    }
+}
+
+/// Appends the foreign call values an argument or return value of type `typ` is passed as to
+/// `values`. Only structs passed directly are packed, not those within other values.
+fn flatten_argument(path: String, typ: &Type, values: &mut Vec<ForeignCallValueSchema>) {
+    match typ.packed_length() {
+        Some(length) => values.push(ForeignCallValueSchema {
+            path,
+            typ: typ.follow_bindings().to_string(),
+            kind: ForeignCallValueKind::Array { length: Some(length) },
+        }),
+        None => flatten_value(path, typ, values),
+    }
+}
+
+/// Appends the foreign call values a value of type `typ` is passed as to `values`.
+fn flatten_value(path: String, typ: &Type, values: &mut Vec<ForeignCallValueSchema>) {
+    let typ = typ.follow_bindings();
+    let mut push = |path, typ: &Type, kind| {
+        values.push(ForeignCallValueSchema { path, typ: typ.to_string(), kind });
+    };
+
+    match &typ {
+        Type::Unit => (),
+        Type::FieldElement | Type::Integer(..) | Type::Bool => {
+            push(path, &typ, ForeignCallValueKind::Single);
+        }
+        Type::Array(size, _) if matches!(size.as_ref(), Type::NotConstant) => {
+            push(format!("{path}.len"), &Type::FieldElement, ForeignCallValueKind::Single);
+            push(path, &typ, ForeignCallValueKind::Array { length: None });
+        }
+        Type::Array(size, element) => {
+            let length = size.evaluate_to_u64().zip(field_count(element));
+            let length = length.map(|(length, element_size)| length * element_size);
+            push(path, &typ, ForeignCallValueKind::Array { length });
+        }
+        Type::String(size) => {
+            push(path, &typ, ForeignCallValueKind::Array { length: size.evaluate_to_u64() });
+        }
+        Type::Struct(def, args) => {
+            for (name, field) in def.borrow().get_fields_in_layout_order(args) {
+                flatten_value(format!("{path}.{name}"), &field, values);
+            }
+        }
+        Type::Tuple(fields) => {
+            for (index, field) in fields.iter().enumerate() {
+                flatten_value(format!("{path}.{index}"), field, values);
+            }
+        }
+        _ => push(path, &typ, ForeignCallValueKind::Generic),
+    }
+}
+
+/// Returns the number of field elements a value of type `typ` is flattened into, if known.
+fn field_count(typ: &Type) -> Option<u64> {
+    match typ.follow_bindings() {
+        Type::Unit => Some(0),
+        Type::FieldElement | Type::Integer(..) | Type::Bool => Some(1),
+        Type::Array(size, element) => Some(size.evaluate_to_u64()? * field_count(&element)?),
+        Type::String(size) => size.evaluate_to_u64(),
+        Type::Struct(def, args) => {
+            def.borrow().get_fields(&args).iter().map(|(_, field)| field_count(field)).sum()
+        }
+        Type::Tuple(fields) => fields.iter().map(field_count).sum(),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use fm::FileManager;
+    use noirc_frontend::{graph::CrateGraph, hir::Context};
+
+    use super::{
+        compute_oracle_signatures, ForeignCallValueKind, ForeignCallValueSchema, OracleSignature,
+    };
+    use crate::{check_crate, prepare_crate};
+
+    /// Checks a crate made of a single `main.nr` with the given source, returning the signatures
+    /// of the oracles it declares along with those of the standard library
+    fn oracle_signatures(src: &'static str) -> Vec<OracleSignature> {
+        let file_manager = FileManager::new(Path::new("/"), Box::new(|_| Ok(src.to_string())));
+        let mut context = Context::new(file_manager, CrateGraph::default());
+        let crate_id = prepare_crate(&mut context, Path::new("main.nr"));
+        check_crate(&mut context, crate_id, false).expect("Expected the crate to type check");
+        compute_oracle_signatures(&context)
+    }
+
+    fn find<'a>(signatures: &'a [OracleSignature], name: &str) -> &'a OracleSignature {
+        signatures.iter().find(|signature| signature.name == name).unwrap()
+    }
+
+    fn value(path: &str, typ: &str, kind: ForeignCallValueKind) -> ForeignCallValueSchema {
+        ForeignCallValueSchema { path: path.to_owned(), typ: typ.to_owned(), kind }
+    }
+
+    #[test]
+    fn values_are_flattened_by_type() {
+        let signatures = oracle_signatures(
+            "
+            struct Point { x: Field, y: u8 }
+
+            #[oracle(get_point)]
+            unconstrained fn get_point_oracle(
+                point: Point,
+                points: [Point; 2],
+                tag: str<3>,
+                _unit: (),
+            ) -> (bool, [u8]) {}
+            ",
+        );
+
+        let signature = find(&signatures, "get_point");
+        assert_eq!(signature.function, "get_point_oracle");
+        assert_eq!(
+            signature.inputs,
+            vec![
+                value("point.x", "Field", ForeignCallValueKind::Single),
+                value("point.y", "u8", ForeignCallValueKind::Single),
+                value("points", "[Point; 2]", ForeignCallValueKind::Array { length: Some(4) }),
+                value("tag", "str<3>", ForeignCallValueKind::Array { length: Some(3) }),
+            ]
+        );
+        assert_eq!(
+            signature.outputs,
+            vec![
+                value("return.0", "bool", ForeignCallValueKind::Single),
+                value("return.1.len", "Field", ForeignCallValueKind::Single),
+                value("return.1", "[u8]", ForeignCallValueKind::Array { length: None }),
+            ]
+        );
+    }
+
+    #[test]
+    fn generic_values_are_marked_generic() {
+        let signatures = oracle_signatures(
+            "
+            #[oracle(store)]
+            unconstrained fn store_oracle<T, N>(value: T, values: [Field; N]) {}
+            ",
+        );
+
+        let signature = find(&signatures, "store");
+        assert_eq!(
+            signature.inputs,
+            vec![
+                value("value", "T", ForeignCallValueKind::Generic),
+                value("values", "[Field; N]", ForeignCallValueKind::Array { length: None }),
+            ]
+        );
+        assert!(signature.outputs.is_empty());
+    }
+
+    #[test]
+    fn structs_are_flattened_in_the_order_of_their_layout() {
+        let signatures = oracle_signatures(
+            "
+            #[repr(sorted)]
+            struct Request { nonce: u64, amount: Field }
+
+            #[repr(packed)]
+            struct Note { owner: Field, flags: [bool; 2] }
+
+            #[oracle(submit)]
+            unconstrained fn submit_oracle(
+                request: Request,
+                (tag, inner): (u8, Note),
+                note: Note,
+            ) -> Note {}
+            ",
+        );
+
+        // Packed structs are only passed as arrays when they aren't within another value, and
+        // parameters bound to patterns are named by their position
+        let signature = find(&signatures, "submit");
+        assert_eq!(
+            signature.inputs,
+            vec![
+                value("request.amount", "Field", ForeignCallValueKind::Single),
+                value("request.nonce", "u64", ForeignCallValueKind::Single),
+                value("_1.0", "u8", ForeignCallValueKind::Single),
+                value("_1.1.owner", "Field", ForeignCallValueKind::Single),
+                value("_1.1.flags", "[bool; 2]", ForeignCallValueKind::Array { length: Some(2) }),
+                value("note", "Note", ForeignCallValueKind::Array { length: Some(3) }),
+            ]
+        );
+        assert_eq!(
+            signature.outputs,
+            vec![value("return", "Note", ForeignCallValueKind::Array { length: Some(3) })]
+        );
+    }
+
+    #[test]
+    fn signatures_are_sorted_by_name() {
+        let signatures = oracle_signatures(
+            "
+            #[oracle(second)]
+            unconstrained fn second_oracle() {}
+
+            #[oracle(first)]
+            unconstrained fn first_oracle() {}
+            ",
+        );
+
+        let position = |name| signatures.iter().position(|signature| signature.name == name);
+        assert!(position("first").unwrap() < position("second").unwrap());
+        assert!(signatures.windows(2).all(|pair| pair[0].name <= pair[1].name));
+    }
+}
@@ -0,0 +1,82 @@
+use noirc_errors::debug_info::DebugInfo;
+use noirc_evaluator::CodegenOptions;
+use serde::{Deserialize, Serialize};
+
+/// How much debug information is kept in compiled programs
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum DebugLevel {
+    /// No source locations are kept, so execution failures can't be traced back to the source
+    None,
+    /// Only the location of the code which failed is kept, without the calls leading to it
+    LineTablesOnly,
+    /// The full call stack of every opcode is kept
+    #[default]
+    Full,
+}
+
+/// The settings used to build a package, selected from the `[profile.dev]` or `[profile.release]`
+/// section of its `Nargo.toml` depending on whether `--release` is passed.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct BuildProfile {
+    /// `0` skips the SSA passes which only simplify the program, `1` runs all of them and `2`
+    /// runs some of them twice
+    pub opt_level: u8,
+    /// Whether unsigned integer addition, subtraction and multiplication fail on overflow
+    /// instead of wrapping around
+    pub overflow_checks: bool,
+    /// How much debug information is kept in the compiled program
+    pub debug: DebugLevel,
+    /// The maximum number of opcodes unconstrained functions may execute before execution fails.
+    /// This is enforced when nargo executes the program, so it doesn't change the compiled program.
+    pub brillig_step_limit: Option<u64>,
+}
+
+impl BuildProfile {
+    /// The highest supported `opt_level`
+    pub const MAX_OPT_LEVEL: u8 = 2;
+
+    /// The profile used when `--release` isn't passed
+    pub fn dev() -> Self {
+        BuildProfile {
+            opt_level: 1,
+            overflow_checks: false,
+            debug: DebugLevel::Full,
+            brillig_step_limit: None,
+        }
+    }
+
+    /// The profile used when `--release` is passed
+    pub fn release() -> Self {
+        BuildProfile { debug: DebugLevel::None, ..BuildProfile::dev() }
+    }
+
+    pub(crate) fn codegen_options(&self) -> CodegenOptions {
+        CodegenOptions {
+            opt_level: self.opt_level,
+            overflow_checks: self.overflow_checks,
+            disabled_passes: Vec::new(),
+        }
+    }
+
+    /// Removes the debug information this profile doesn't keep
+    pub(crate) fn strip_debug_info(&self, debug: &mut DebugInfo) {
+        match self.debug {
+            DebugLevel::None => debug.locations.clear(),
+            DebugLevel::LineTablesOnly => {
+                for call_stack in debug.locations.values_mut() {
+                    let innermost = call_stack.pop();
+                    call_stack.clear();
+                    call_stack.extend(innermost);
+                }
+            }
+            DebugLevel::Full => (),
+        }
+    }
+}
+
+impl Default for BuildProfile {
+    fn default() -> Self {
+        BuildProfile::dev()
+    }
+}
@@ -1,10 +1,12 @@
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::PathBuf;
 
 use acvm::acir::circuit::Circuit;
 use fm::FileId;
 
 use base64::Engine;
 use noirc_errors::debug_info::DebugInfo;
+use noirc_frontend::token::CustomAttribute;
 use serde::{de::Error as DeserializationError, ser::Error as SerializationError};
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
 
@@ -21,6 +23,14 @@ pub struct CompiledProgram {
     #[serde(serialize_with = "serialize_circuit", deserialize_with = "deserialize_circuit")]
     pub circuit: Circuit,
     pub abi: noirc_abi::Abi,
+    /// Attributes on the `main` function which are not recognized by the compiler
+    pub custom_attributes: Vec<CustomAttribute>,
+    /// The environment variables read by `std::env!` while compiling the program, along with
+    /// the values they were compiled with
+    pub environment: BTreeMap<String, String>,
+    /// The files read by `std::include_bytes!` and `std::include_str!` while compiling the
+    /// program
+    pub included_files: BTreeSet<PathBuf>,
     pub debug: DebugInfo,
     pub file_map: BTreeMap<FileId, DebugFile>,
 }
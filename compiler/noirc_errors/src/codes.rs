@@ -0,0 +1,228 @@
+//! Stable codes identifying each kind of diagnostic the compiler can emit.
+//!
+//! Codes are grouped by the compiler pass which emits them:
+//! - `E00xx`: lexing and parsing
+//! - `E01xx`: collecting definitions
+//! - `E02xx`: name resolution
+//! - `E03xx`: type checking
+//! - `E04xx`: monomorphization
+//! - `E05xx`: SSA and ACIR generation
+//! - `E06xx`: compile-time evaluation
+//!
+//! Once assigned, a code is never reused for a different kind of diagnostic. Each code has a
+//! longer explanation with examples in `explanations/<code>.md`, printed by `nargo explain`.
+
+/// A stable identifier for a kind of diagnostic, e.g. `E0202`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct ErrorCode(&'static str);
+
+impl ErrorCode {
+    pub fn as_str(self) -> &'static str {
+        self.0
+    }
+
+    /// Finds the code with the given name, ignoring case.
+    pub fn lookup(code: &str) -> Option<ErrorCode> {
+        ALL_CODES
+            .iter()
+            .find(|(error_code, _)| error_code.0.eq_ignore_ascii_case(code))
+            .map(|(error_code, _)| *error_code)
+    }
+
+    /// The longer description of this code, in markdown.
+    pub fn explanation(self) -> &'static str {
+        ALL_CODES
+            .iter()
+            .find(|(error_code, _)| *error_code == self)
+            .map(|(_, explanation)| *explanation)
+            .expect("ICE: every error code should have an explanation")
+    }
+
+    /// Every error code, in ascending order.
+    pub fn all() -> impl Iterator<Item = ErrorCode> {
+        ALL_CODES.iter().map(|(error_code, _)| *error_code)
+    }
+}
+
+impl std::fmt::Display for ErrorCode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+macro_rules! error_codes {
+    ($($code:ident,)*) => {
+        $(pub const $code: ErrorCode = ErrorCode(stringify!($code));)*
+
+        const ALL_CODES: &[(ErrorCode, &str)] = &[
+            $(($code, include_str!(concat!("explanations/", stringify!($code), ".md"))),)*
+        ];
+    };
+}
+
+error_codes! {
+    E0001,
+    E0002,
+    E0003,
+    E0004,
+    E0005,
+    E0006,
+    E0010,
+    E0011,
+    E0012,
+    E0013,
+    E0014,
+    E0015,
+    E0016,
+    E0017,
+    E0018,
+    E0019,
+    E0020,
+    E0021,
+    E0022,
+    E0023,
+    E0024,
+    E0025,
+    E0026,
+    E0027,
+    E0100,
+    E0101,
+    E0102,
+    E0103,
+    E0104,
+    E0105,
+    E0106,
+    E0107,
+    E0108,
+    E0109,
+    E0110,
+    E0111,
+    E0112,
+    E0113,
+    E0114,
+    E0115,
+    E0116,
+    E0117,
+    E0200,
+    E0201,
+    E0202,
+    E0203,
+    E0204,
+    E0205,
+    E0206,
+    E0207,
+    E0208,
+    E0209,
+    E0210,
+    E0211,
+    E0212,
+    E0213,
+    E0214,
+    E0215,
+    E0216,
+    E0217,
+    E0219,
+    E0220,
+    E0222,
+    E0223,
+    E0224,
+    E0225,
+    E0226,
+    E0227,
+    E0228,
+    E0229,
+    E0230,
+    E0231,
+    E0232,
+    E0233,
+    E0234,
+    E0235,
+    E0236,
+    E0237,
+    E0238,
+    E0239,
+    E0300,
+    E0301,
+    E0302,
+    E0303,
+    E0304,
+    E0305,
+    E0306,
+    E0307,
+    E0308,
+    E0309,
+    E0310,
+    E0311,
+    E0312,
+    E0313,
+    E0314,
+    E0315,
+    E0316,
+    E0317,
+    E0318,
+    E0319,
+    E0320,
+    E0321,
+    E0322,
+    E0400,
+    E0401,
+    E0402,
+    E0403,
+    E0500,
+    E0501,
+    E0502,
+    E0503,
+    E0504,
+    E0505,
+    E0506,
+    E0507,
+    E0508,
+    E0509,
+    E0510,
+    E0511,
+    E0512,
+    E0600,
+    E0601,
+    E0602,
+    E0603,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ErrorCode, ALL_CODES, E0202};
+
+    /// Codes which were assigned to a kind of diagnostic the compiler no longer emits, so they
+    /// must not be assigned again.
+    const RETIRED_CODES: &[&str] = &["E0218", "E0221"];
+
+    #[test]
+    fn codes_are_unique_and_sorted() {
+        let codes: Vec<_> = ErrorCode::all().collect();
+        let mut sorted = codes.clone();
+        sorted.sort();
+        sorted.dedup();
+        assert_eq!(codes, sorted);
+    }
+
+    #[test]
+    fn codes_are_well_formed_and_explained() {
+        for (code, explanation) in ALL_CODES {
+            let digits = code.as_str().strip_prefix('E').expect("codes should start with E");
+            assert!(digits.len() == 4 && digits.chars().all(|c| c.is_ascii_digit()));
+            assert!(!explanation.trim().is_empty(), "{code} has no explanation");
+        }
+    }
+
+    #[test]
+    fn retired_codes_are_not_reused() {
+        for code in RETIRED_CODES {
+            assert_eq!(ErrorCode::lookup(code), None, "{code} was retired");
+        }
+    }
+
+    #[test]
+    fn lookup_ignores_case() {
+        assert_eq!(ErrorCode::lookup("e0202"), Some(E0202));
+        assert_eq!(ErrorCode::lookup("E9999"), None);
+    }
+}
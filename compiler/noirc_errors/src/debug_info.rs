@@ -1,4 +1,5 @@
 use acvm::acir::circuit::OpcodeLocation;
+use acvm::acir::native_types::Witness;
 use acvm::compiler::AcirTransformationMap;
 
 use serde_with::serde_as;
@@ -17,11 +18,16 @@ pub struct DebugInfo {
     /// that they should be serialized to/from strings.
     #[serde_as(as = "BTreeMap<DisplayFromStr, _>")]
     pub locations: BTreeMap<OpcodeLocation, Vec<Location>>,
+
+    /// Witnesses holding the predicate of each instrumented ACIR block, along with the block's label.
+    /// After execution each witness is 1 if its block ran and 0 otherwise.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub block_tallies: Vec<(Witness, String)>,
 }
 
 impl DebugInfo {
     pub fn new(locations: BTreeMap<OpcodeLocation, Vec<Location>>) -> Self {
-        DebugInfo { locations }
+        DebugInfo { locations, block_tallies: Vec::new() }
     }
 
     /// Updates the locations map when the [`Circuit`][acvm::acir::circuit::Circuit] is modified.
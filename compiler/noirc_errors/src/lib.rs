@@ -3,11 +3,15 @@
 #![warn(unreachable_pub)]
 #![warn(clippy::semicolon_if_nothing_returned)]
 
+pub mod codes;
 pub mod debug_info;
+pub mod lints;
 mod position;
 pub mod reporter;
+pub use codes::ErrorCode;
+pub use lints::{Lint, LintLevel};
 pub use position::{Location, Position, Span, Spanned};
-pub use reporter::{CustomDiagnostic, DiagnosticKind};
+pub use reporter::{CustomDiagnostic, DiagnosticKind, Edit, Fix, MessageFormat};
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct FileDiagnostic {
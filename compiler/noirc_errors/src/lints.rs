@@ -0,0 +1,119 @@
+//! Named kinds of warnings whose level can be changed, either in source with the `#[allow(..)]`,
+//! `#[warn(..)]` and `#[deny(..)]` attributes or for a whole build with nargo's `--allow`,
+//! `--warn` and `--deny` options.
+//!
+//! Every lint is reported at its default level unless its level is changed, which is `warn`
+//! for all lints but the opt-in ones such as `shadowed_variables`.
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+/// A named kind of warning, e.g. `unused_variables`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Lint(&'static str, LintLevel);
+
+impl Lint {
+    pub fn name(self) -> &'static str {
+        self.0
+    }
+
+    /// The level of the lint where it isn't changed by an attribute or the command line.
+    pub fn default_level(self) -> LintLevel {
+        self.1
+    }
+
+    /// Finds the lint with the given name.
+    pub fn lookup(name: &str) -> Option<Lint> {
+        ALL_LINTS.iter().copied().find(|lint| lint.0 == name)
+    }
+
+    /// Every lint, in alphabetical order.
+    pub fn all() -> impl Iterator<Item = Lint> {
+        ALL_LINTS.iter().copied()
+    }
+}
+
+impl std::fmt::Display for Lint {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl std::str::FromStr for Lint {
+    type Err = String;
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        Lint::lookup(name).ok_or_else(|| format!("unknown lint `{name}`"))
+    }
+}
+
+impl Serialize for Lint {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.0)
+    }
+}
+
+impl<'de> Deserialize<'de> for Lint {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let name = String::deserialize(deserializer)?;
+        name.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+/// Functions which are never called from `main`, a test or a public function.
+pub const DEAD_CODE: Lint = Lint("dead_code", LintLevel::Warn);
+/// Uses of items marked `#[deprecated]` and of deprecated syntax.
+pub const DEPRECATED: Lint = Lint("deprecated", LintLevel::Warn);
+/// `let` statements which shadow an earlier binding of the same function. This lint is allowed
+/// unless it is enabled, as shadowing is often intended.
+pub const SHADOWED_VARIABLES: Lint = Lint("shadowed_variables", LintLevel::Allow);
+/// `pub` on the parameters or return type of a function which isn't an entry point.
+pub const UNNECESSARY_PUB: Lint = Lint("unnecessary_pub", LintLevel::Warn);
+/// Imports which are never used.
+pub const UNUSED_IMPORTS: Lint = Lint("unused_imports", LintLevel::Warn);
+/// Statements whose value is discarded without being used.
+pub const UNUSED_RESULTS: Lint = Lint("unused_results", LintLevel::Warn);
+/// Local variables which are never read.
+pub const UNUSED_VARIABLES: Lint = Lint("unused_variables", LintLevel::Warn);
+
+const ALL_LINTS: &[Lint] = &[
+    DEAD_CODE,
+    DEPRECATED,
+    SHADOWED_VARIABLES,
+    UNNECESSARY_PUB,
+    UNUSED_IMPORTS,
+    UNUSED_RESULTS,
+    UNUSED_VARIABLES,
+];
+
+/// How the warnings of a lint are reported.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum LintLevel {
+    /// The warnings are not reported.
+    Allow,
+    /// The warnings are reported as warnings.
+    Warn,
+    /// The warnings are reported as errors.
+    Deny,
+}
+
+impl LintLevel {
+    /// Finds the level set by the attribute with the given name, e.g. `allow`.
+    pub fn lookup(attribute: &str) -> Option<LintLevel> {
+        match attribute {
+            "allow" => Some(LintLevel::Allow),
+            "warn" => Some(LintLevel::Warn),
+            "deny" => Some(LintLevel::Deny),
+            _ => None,
+        }
+    }
+}
+
+impl std::fmt::Display for LintLevel {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LintLevel::Allow => write!(f, "allow"),
+            LintLevel::Warn => write!(f, "warn"),
+            LintLevel::Deny => write!(f, "deny"),
+        }
+    }
+}
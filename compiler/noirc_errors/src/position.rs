@@ -8,7 +8,7 @@ use std::{
 
 pub type Position = u32;
 
-#[derive(PartialOrd, Eq, Ord, Debug, Clone)]
+#[derive(PartialOrd, Eq, Ord, Debug, Clone, Serialize, Deserialize)]
 pub struct Spanned<T> {
     pub contents: T,
     span: Span,
@@ -1,8 +1,9 @@
-use crate::{FileDiagnostic, Location, Span};
+use crate::{ErrorCode, FileDiagnostic, Lint, LintLevel, Location, Span};
 use codespan_reporting::diagnostic::{Diagnostic, Label};
 use codespan_reporting::files::Files;
 use codespan_reporting::term;
 use codespan_reporting::term::termcolor::{ColorChoice, StandardStream};
+use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct CustomDiagnostic {
@@ -10,6 +11,16 @@ pub struct CustomDiagnostic {
     pub secondaries: Vec<CustomLabel>,
     notes: Vec<String>,
     pub kind: DiagnosticKind,
+
+    /// The stable code identifying this kind of diagnostic, e.g. `E0202`.
+    /// These are explained in more detail by `nargo explain`.
+    pub code: Option<ErrorCode>,
+
+    /// Changes to the source which would fix this diagnostic, offered as quick fixes by the LSP.
+    pub fixes: Vec<Fix>,
+
+    /// The lint this warning belongs to, if its level can be changed.
+    pub lint: Option<Lint>,
 }
 
 #[derive(Debug, Copy, Clone, PartialEq, Eq)]
@@ -18,6 +29,38 @@ pub enum DiagnosticKind {
     Warning,
 }
 
+/// How [`report_all`] writes out diagnostics.
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MessageFormat {
+    /// Rendered with source snippets to stderr.
+    #[default]
+    Human,
+    /// One JSON object per diagnostic and per line on stdout, for tools which don't use the LSP.
+    Json,
+}
+
+impl std::str::FromStr for MessageFormat {
+    type Err = String;
+
+    fn from_str(format: &str) -> Result<Self, Self::Err> {
+        match format {
+            "human" => Ok(MessageFormat::Human),
+            "json" => Ok(MessageFormat::Json),
+            _ => Err(format!("unknown message format `{format}`, expected `human` or `json`")),
+        }
+    }
+}
+
+impl std::fmt::Display for MessageFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MessageFormat::Human => write!(f, "human"),
+            MessageFormat::Json => write!(f, "json"),
+        }
+    }
+}
+
 /// A count of errors that have been already reported to stderr
 #[derive(Debug, Copy, Clone)]
 pub struct ReportedErrors {
@@ -31,6 +74,9 @@ impl CustomDiagnostic {
             secondaries: Vec::new(),
             notes: Vec::new(),
             kind: DiagnosticKind::Error,
+            code: None,
+            fixes: Vec::new(),
+            lint: None,
         }
     }
 
@@ -44,6 +90,9 @@ impl CustomDiagnostic {
             secondaries: vec![CustomLabel::new(secondary_message, secondary_span)],
             notes: Vec::new(),
             kind: DiagnosticKind::Error,
+            code: None,
+            fixes: Vec::new(),
+            lint: None,
         }
     }
 
@@ -57,6 +106,9 @@ impl CustomDiagnostic {
             secondaries: vec![CustomLabel::new(secondary_message, secondary_span)],
             notes: Vec::new(),
             kind: DiagnosticKind::Warning,
+            code: None,
+            fixes: Vec::new(),
+            lint: None,
         }
     }
 
@@ -64,6 +116,31 @@ impl CustomDiagnostic {
         FileDiagnostic::new(file_id, self)
     }
 
+    pub fn with_code(mut self, code: ErrorCode) -> CustomDiagnostic {
+        self.code = Some(code);
+        self
+    }
+
+    pub fn with_lint(mut self, lint: Lint) -> CustomDiagnostic {
+        self.lint = Some(lint);
+        self
+    }
+
+    /// Reports this diagnostic at the given level of its lint, returning `None` if it is allowed.
+    pub fn at_lint_level(mut self, level: LintLevel) -> Option<CustomDiagnostic> {
+        match level {
+            LintLevel::Allow => return None,
+            LintLevel::Warn => self.kind = DiagnosticKind::Warning,
+            LintLevel::Deny => {
+                self.kind = DiagnosticKind::Error;
+                if let Some(lint) = self.lint {
+                    self.add_note(format!("`{lint}` is set to deny"));
+                }
+            }
+        }
+        Some(self)
+    }
+
     pub fn add_note(&mut self, message: String) {
         self.notes.push(message);
     }
@@ -72,6 +149,10 @@ impl CustomDiagnostic {
         self.secondaries.push(CustomLabel::new(message, span));
     }
 
+    pub fn add_fix(&mut self, fix: Fix) {
+        self.fixes.push(fix);
+    }
+
     pub fn is_error(&self) -> bool {
         matches!(self.kind, DiagnosticKind::Error)
     }
@@ -109,13 +190,52 @@ impl CustomLabel {
     }
 }
 
-/// Writes the given diagnostics to stderr and returns the count
+/// A machine-applicable change to the source which fixes the diagnostic it is attached to.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Fix {
+    /// Describes the change, e.g. "make `x` mutable". Editors show this as the title of the fix.
+    pub message: String,
+    /// The edits making up this fix. These never overlap.
+    pub edits: Vec<Edit>,
+}
+
+/// Replaces the source code within `span` with `replacement`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Edit {
+    pub span: Span,
+    pub replacement: String,
+}
+
+impl Fix {
+    pub fn new(message: String, edits: Vec<Edit>) -> Fix {
+        Fix { message, edits }
+    }
+
+    /// A fix which inserts `text` before the byte at `position`.
+    pub fn insert(message: String, position: u32, text: String) -> Fix {
+        Fix::new(message, vec![Edit::insert(position, text)])
+    }
+
+    /// A fix which deletes the source code within `span`.
+    pub fn remove(message: String, span: Span) -> Fix {
+        Fix::new(message, vec![Edit { span, replacement: String::new() }])
+    }
+}
+
+impl Edit {
+    pub fn insert(position: u32, text: String) -> Edit {
+        Edit { span: Span::from(position..position), replacement: text }
+    }
+}
+
+/// Writes the given diagnostics out in the given format and returns the count
 /// of diagnostics that were errors.
 pub fn report_all<'files>(
     files: &'files impl Files<'files, FileId = fm::FileId>,
     diagnostics: &[FileDiagnostic],
     deny_warnings: bool,
     silence_warnings: bool,
+    message_format: MessageFormat,
 ) -> ReportedErrors {
     // Report warnings before any errors
     let (warnings, mut errors): (Vec<_>, _) =
@@ -124,8 +244,16 @@ pub fn report_all<'files>(
     let mut diagnostics = if silence_warnings { Vec::new() } else { warnings };
     diagnostics.append(&mut errors);
 
-    let error_count =
-        diagnostics.iter().map(|error| error.report(files, deny_warnings) as u32).sum();
+    let error_count = diagnostics
+        .iter()
+        .map(|error| {
+            let is_error = match message_format {
+                MessageFormat::Human => error.report(files, deny_warnings),
+                MessageFormat::Json => error.report_json(files, deny_warnings),
+            };
+            is_error as u32
+        })
+        .sum();
 
     ReportedErrors { error_count }
 }
@@ -138,6 +266,124 @@ impl FileDiagnostic {
     ) -> bool {
         report(files, &self.diagnostic, Some(self.file_id), &self.call_stack, deny_warnings)
     }
+
+    /// Writes this diagnostic to stdout as a single line of JSON, and returns true if it was an error.
+    pub fn report_json<'files>(
+        &self,
+        files: &'files impl Files<'files, FileId = fm::FileId>,
+        deny_warnings: bool,
+    ) -> bool {
+        let json = JsonDiagnostic::new(files, self, deny_warnings);
+        println!("{}", serde_json::to_string(&json).expect("diagnostics are always serializable"));
+
+        deny_warnings || self.diagnostic.is_error()
+    }
+}
+
+/// The JSON representation of a diagnostic written by [`FileDiagnostic::report_json`].
+///
+/// This format is relied upon by external tools, so fields should only ever be added to it.
+#[derive(Serialize)]
+struct JsonDiagnostic<'a> {
+    message: &'a str,
+    code: Option<&'static str>,
+    severity: &'static str,
+    file: String,
+    /// The byte span of the first label, which is where the diagnostic is reported.
+    span: Option<JsonSpan>,
+    labels: Vec<JsonLabel<'a>>,
+    notes: Vec<&'a str>,
+    /// The "help: ..." notes, such as suggested fixes, without their prefix.
+    suggestions: Vec<&'a str>,
+    fixes: Vec<JsonFix<'a>>,
+    /// The lint the diagnostic belongs to, if any.
+    lint: Option<&'static str>,
+}
+
+#[derive(Serialize)]
+struct JsonFix<'a> {
+    message: &'a str,
+    edits: Vec<JsonEdit<'a>>,
+}
+
+#[derive(Serialize)]
+struct JsonEdit<'a> {
+    span: JsonSpan,
+    replacement: &'a str,
+}
+
+#[derive(Serialize)]
+struct JsonLabel<'a> {
+    message: &'a str,
+    span: JsonSpan,
+}
+
+#[derive(Serialize)]
+struct JsonSpan {
+    start: u32,
+    end: u32,
+}
+
+impl From<Span> for JsonSpan {
+    fn from(span: Span) -> JsonSpan {
+        JsonSpan { start: span.start(), end: span.end() }
+    }
+}
+
+impl<'a> JsonDiagnostic<'a> {
+    fn new<'files>(
+        files: &'files impl Files<'files, FileId = fm::FileId>,
+        file_diagnostic: &'a FileDiagnostic,
+        deny_warnings: bool,
+    ) -> JsonDiagnostic<'a> {
+        let diagnostic = &file_diagnostic.diagnostic;
+        let severity = match (diagnostic.kind, deny_warnings) {
+            (DiagnosticKind::Warning, false) => "warning",
+            _ => "error",
+        };
+        let file = files.name(file_diagnostic.file_id).expect("should get file path").to_string();
+
+        let labels: Vec<_> = diagnostic
+            .secondaries
+            .iter()
+            .map(|label| JsonLabel { message: &label.message, span: label.span.into() })
+            .collect();
+        let span = diagnostic.secondaries.first().map(|label| label.span.into());
+
+        let (suggestions, notes): (Vec<_>, Vec<_>) = diagnostic
+            .notes
+            .iter()
+            .map(String::as_str)
+            .partition(|note| note.starts_with("help: "));
+        let suggestions =
+            suggestions.into_iter().map(|note| note.trim_start_matches("help: ")).collect();
+
+        let fixes = diagnostic
+            .fixes
+            .iter()
+            .map(|fix| JsonFix {
+                message: &fix.message,
+                edits: fix
+                    .edits
+                    .iter()
+                    .map(|edit| JsonEdit { span: edit.span.into(), replacement: &edit.replacement })
+                    .collect(),
+            })
+            .collect();
+
+        JsonDiagnostic {
+            message: &diagnostic.message,
+            code: diagnostic.code.map(ErrorCode::as_str),
+            severity,
+            file,
+            span,
+            labels,
+            notes,
+            suggestions,
+            fixes,
+            lint: diagnostic.lint.map(Lint::name),
+        }
+    }
 }
 
 /// Report the given diagnostic, and return true if it was an error
@@ -185,6 +431,11 @@ fn convert_diagnostic(
     let mut notes = cd.notes.clone();
     notes.push(stack_trace);
 
+    let diagnostic = match cd.code {
+        Some(code) => diagnostic.with_code(code.as_str()),
+        None => diagnostic,
+    };
+
     diagnostic.with_message(&cd.message).with_labels(secondary_labels).with_notes(notes)
 }
 
@@ -228,3 +479,48 @@ fn location(source: &str, span_start: u32) -> (u32, u32) {
 
     (line, column)
 }
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use super::{Fix, JsonDiagnostic};
+    use crate::{codes::E0202, CustomDiagnostic, Span};
+
+    #[test]
+    fn json_diagnostics_separate_suggestions_from_notes() {
+        let mut files = fm::FileMap::default();
+        let file_id = files.add_file(PathBuf::from("src/main.nr").into(), "fn main() {}".into());
+
+        let mut diagnostic = CustomDiagnostic::simple_warning(
+            "cannot find `vlue` in this scope".to_string(),
+            "not found in this scope".to_string(),
+            Span::from(3..7),
+        )
+        .with_code(E0202);
+        diagnostic.add_note("help: did you mean `value`?".to_string());
+        diagnostic.add_note("some other note".to_string());
+        diagnostic.add_fix(Fix::insert("insert `a`".to_string(), 4, "a".to_string()));
+        let diagnostic = diagnostic.in_file(file_id);
+
+        let json = serde_json::to_value(JsonDiagnostic::new(&files, &diagnostic, true)).unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!({
+                "message": "cannot find `vlue` in this scope",
+                "code": "E0202",
+                "severity": "error",
+                "file": "src/main.nr",
+                "span": { "start": 3, "end": 7 },
+                "labels": [{ "message": "not found in this scope", "span": { "start": 3, "end": 7 } }],
+                "notes": ["some other note"],
+                "suggestions": ["did you mean `value`?"],
+                "fixes": [{
+                    "message": "insert `a`",
+                    "edits": [{ "span": { "start": 4, "end": 4 }, "replacement": "a" }],
+                }],
+                "lint": null,
+            })
+        );
+    }
+}
@@ -3,6 +3,7 @@ pub(crate) mod brillig_block;
 pub(crate) mod brillig_block_variables;
 pub(crate) mod brillig_directive;
 pub(crate) mod brillig_fn;
+pub(crate) mod brillig_foreign_call;
 pub(crate) mod brillig_slice_ops;
 mod variable_liveness;
 
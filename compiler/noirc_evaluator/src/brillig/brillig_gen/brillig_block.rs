@@ -298,10 +298,11 @@ impl<'block> BrilligBlock<'block> {
                 Value::ForeignFunction(func_name) => {
                     let result_ids = dfg.instruction_results(instruction_id);
 
-                    let input_registers =
-                        vecmap(arguments, |value_id| self.convert_ssa_value(*value_id, dfg));
+                    let input_registers = vecmap(arguments, |value_id| {
+                        self.convert_foreign_call_input(*value_id, dfg)
+                    });
                     let output_registers = vecmap(result_ids, |value_id| {
-                        self.allocate_external_call_result(*value_id, dfg)
+                        self.allocate_foreign_call_result(*value_id, dfg)
                     });
                     self.brillig_context.foreign_call_instruction(
                         func_name.to_owned(),
@@ -309,20 +310,30 @@ impl<'block> BrilligBlock<'block> {
                         &output_registers,
                     );
 
-                    for (i, output_register) in output_registers.iter().enumerate() {
+                    for output_register in &output_registers {
                         if let RegisterOrMemory::HeapVector(HeapVector { size, .. }) =
                             output_register
                         {
                             // Update the stack pointer so that we do not overwrite
                             // dynamic memory returned from other external calls
                             self.brillig_context.update_stack_pointer(*size);
+                        }
+                    }
+
+                    for (i, (result_id, output_register)) in
+                        result_ids.iter().zip(&output_registers).enumerate()
+                    {
+                        // Arrays with nested arrays are returned flattened
+                        let variable =
+                            self.convert_foreign_call_output(*result_id, *output_register, dfg);
 
+                        if let RegisterOrMemory::HeapVector(HeapVector { size, .. }) = variable {
                             // Update the dynamic slice length maintained in SSA
                             if let RegisterOrMemory::RegisterIndex(len_index) =
                                 output_registers[i - 1]
                             {
-                                let element_size = dfg[result_ids[i]].get_type().element_size();
-                                self.brillig_context.mov_instruction(len_index, *size);
+                                let element_size = dfg[*result_id].get_type().element_size();
+                                self.brillig_context.mov_instruction(len_index, size);
                                 self.brillig_context.usize_op_in_place(
                                     len_index,
                                     BinaryIntOp::UnsignedDiv,
@@ -333,7 +344,7 @@ impl<'block> BrilligBlock<'block> {
                             }
                         }
                         // Single values and allocation of fixed sized arrays has already been handled
-                        // inside of `allocate_external_call_result`
+                        // inside of `allocate_foreign_call_result`
                     }
                 }
                 Value::Function(func_id) => {
@@ -1044,7 +1055,11 @@ impl<'block> BrilligBlock<'block> {
     }
 
     /// Converts an SSA `ValueId` into a `RegisterOrMemory`. Initializes if necessary.
-    fn convert_ssa_value(&mut self, value_id: ValueId, dfg: &DataFlowGraph) -> RegisterOrMemory {
+    pub(crate) fn convert_ssa_value(
+        &mut self,
+        value_id: ValueId,
+        dfg: &DataFlowGraph,
+    ) -> RegisterOrMemory {
         let value_id = dfg.resolve(value_id);
         let value = &dfg[value_id];
 
@@ -1134,7 +1149,7 @@ impl<'block> BrilligBlock<'block> {
         extract_register(variable)
     }
 
-    fn allocate_external_call_result(
+    pub(crate) fn allocate_external_call_result(
         &mut self,
         result: ValueId,
         dfg: &DataFlowGraph,
@@ -0,0 +1,270 @@
+//! Foreign calls pass and return arrays as all of their flattened field elements, while Brillig
+//! stores the nested arrays of an array as pointers to them. The arrays with nested arrays which
+//! are passed to a foreign call are flattened into a copy beforehand, and those it returns are
+//! written to a flat array which is then unflattened into nested arrays.
+use acvm::acir::brillig::{BinaryIntOp, HeapArray, HeapVector, RegisterIndex, RegisterOrMemory};
+
+use crate::brillig::brillig_ir::{extract_heap_array, BrilligContext};
+use crate::ssa::ir::{dfg::DataFlowGraph, types::Type, value::ValueId};
+
+use super::brillig_block::BrilligBlock;
+
+impl<'block> BrilligBlock<'block> {
+    /// Converts an argument of a foreign call, copying arrays with nested arrays into a flat
+    /// array of their field elements.
+    pub(crate) fn convert_foreign_call_input(
+        &mut self,
+        value_id: ValueId,
+        dfg: &DataFlowGraph,
+    ) -> RegisterOrMemory {
+        let variable = self.convert_ssa_value(value_id, dfg);
+        let typ = dfg.type_of_value(value_id);
+        if !has_nested_arrays(&typ) {
+            return variable;
+        }
+
+        let context = &mut *self.brillig_context;
+        match (variable, &typ) {
+            (RegisterOrMemory::HeapArray(array), Type::Array(elements, length)) => {
+                let flattened =
+                    HeapArray { pointer: context.allocate_register(), size: typ.flattened_size() };
+                context.allocate_fixed_length_array(flattened.pointer, flattened.size);
+
+                let element_count = context.make_constant((*length).into());
+                let cursor = context.make_constant(0_usize.into());
+                flatten_elements(
+                    context,
+                    array.pointer,
+                    flattened.pointer,
+                    element_count,
+                    elements,
+                    cursor,
+                );
+                context.deallocate_register(cursor);
+                context.deallocate_register(element_count);
+                RegisterOrMemory::HeapArray(flattened)
+            }
+            (RegisterOrMemory::HeapVector(vector), Type::Slice(elements)) => {
+                let element_count = context.allocate_register();
+                context.usize_op(
+                    vector.size,
+                    element_count,
+                    BinaryIntOp::UnsignedDiv,
+                    elements.len(),
+                );
+                let flattened = HeapVector {
+                    pointer: context.allocate_register(),
+                    size: context.allocate_register(),
+                };
+                context.usize_op(
+                    element_count,
+                    flattened.size,
+                    BinaryIntOp::Mul,
+                    flattened_size(elements),
+                );
+                context.allocate_array_instruction(flattened.pointer, flattened.size);
+
+                let cursor = context.make_constant(0_usize.into());
+                flatten_elements(
+                    context,
+                    vector.pointer,
+                    flattened.pointer,
+                    element_count,
+                    elements,
+                    cursor,
+                );
+                context.deallocate_register(cursor);
+                context.deallocate_register(element_count);
+                RegisterOrMemory::HeapVector(flattened)
+            }
+            _ => unreachable!("ICE: expected an array or a slice, found {typ}"),
+        }
+    }
+
+    /// Allocates the destination of a result of a foreign call. Arrays with nested arrays are
+    /// written to a flat array, to be unflattened by [`Self::convert_foreign_call_output`].
+    pub(crate) fn allocate_foreign_call_result(
+        &mut self,
+        result: ValueId,
+        dfg: &DataFlowGraph,
+    ) -> RegisterOrMemory {
+        let typ = dfg.type_of_value(result);
+        if !has_nested_arrays(&typ) {
+            return self.allocate_external_call_result(result, dfg);
+        }
+
+        let context = &mut *self.brillig_context;
+        match &typ {
+            Type::Array(..) => {
+                let flattened =
+                    HeapArray { pointer: context.allocate_register(), size: typ.flattened_size() };
+                context.allocate_fixed_length_array(flattened.pointer, flattened.size);
+                RegisterOrMemory::HeapArray(flattened)
+            }
+            Type::Slice(_) => {
+                let flattened = HeapVector {
+                    pointer: context.allocate_register(),
+                    size: context.allocate_register(),
+                };
+                // As for other vectors, the stack pointer is updated by the caller once the size
+                // of the result is known
+                context.set_array_pointer(flattened.pointer);
+                RegisterOrMemory::HeapVector(flattened)
+            }
+            _ => unreachable!("ICE: expected an array or a slice, found {typ}"),
+        }
+    }
+
+    /// Returns the variable holding a result of a foreign call written to `destination`,
+    /// unflattening arrays with nested arrays into a newly defined variable.
+    pub(crate) fn convert_foreign_call_output(
+        &mut self,
+        result: ValueId,
+        destination: RegisterOrMemory,
+        dfg: &DataFlowGraph,
+    ) -> RegisterOrMemory {
+        let typ = dfg.type_of_value(result);
+        if !has_nested_arrays(&typ) {
+            return destination;
+        }
+
+        let variable = self.variables.define_variable(
+            self.function_context,
+            self.brillig_context,
+            result,
+            dfg,
+        );
+        let context = &mut *self.brillig_context;
+        match (destination, &typ) {
+            (RegisterOrMemory::HeapArray(flattened), Type::Array(elements, length)) => {
+                let array = extract_heap_array(variable);
+                context.allocate_fixed_length_array(array.pointer, array.size);
+
+                let element_count = context.make_constant((*length).into());
+                let cursor = context.make_constant(0_usize.into());
+                unflatten_elements(
+                    context,
+                    flattened.pointer,
+                    array.pointer,
+                    element_count,
+                    elements,
+                    cursor,
+                );
+                context.deallocate_register(cursor);
+                context.deallocate_register(element_count);
+                context.deallocate_register(flattened.pointer);
+            }
+            (RegisterOrMemory::HeapVector(flattened), Type::Slice(elements)) => {
+                let vector = context.extract_heap_vector(variable);
+                let element_count = context.allocate_register();
+                context.usize_op(
+                    flattened.size,
+                    element_count,
+                    BinaryIntOp::UnsignedDiv,
+                    flattened_size(elements),
+                );
+                context.usize_op(element_count, vector.size, BinaryIntOp::Mul, elements.len());
+                context.allocate_array_instruction(vector.pointer, vector.size);
+
+                let cursor = context.make_constant(0_usize.into());
+                unflatten_elements(
+                    context,
+                    flattened.pointer,
+                    vector.pointer,
+                    element_count,
+                    elements,
+                    cursor,
+                );
+                context.deallocate_register(cursor);
+                context.deallocate_register(element_count);
+                context.deallocate_register(flattened.pointer);
+                context.deallocate_register(flattened.size);
+            }
+            _ => unreachable!("ICE: expected an array or a slice, found {typ}"),
+        }
+        variable
+    }
+}
+
+/// Returns whether `typ` is an array or a slice whose elements contain arrays, which are stored
+/// as pointers rather than as their field elements.
+fn has_nested_arrays(typ: &Type) -> bool {
+    match typ {
+        Type::Array(elements, _) | Type::Slice(elements) => {
+            elements.iter().any(|element| matches!(element, Type::Array(..)))
+        }
+        _ => false,
+    }
+}
+
+/// Returns the number of field elements a single element of an array is flattened into.
+fn flattened_size(elements: &[Type]) -> usize {
+    elements.iter().map(Type::flattened_size).sum()
+}
+
+/// Writes the field elements of the first `element_count` elements of the array at `source`, whose
+/// elements have the types `elements`, to the flat array at `destination` starting at the index
+/// held by `cursor`. The cursor is advanced past the written field elements.
+fn flatten_elements(
+    context: &mut BrilligContext,
+    source: RegisterIndex,
+    destination: RegisterIndex,
+    element_count: RegisterIndex,
+    elements: &[Type],
+    cursor: RegisterIndex,
+) {
+    let value = context.allocate_register();
+    let source_index = context.allocate_register();
+    context.loop_instruction(element_count, |context, iterator| {
+        for (field_index, element) in elements.iter().enumerate() {
+            context.usize_op(iterator, source_index, BinaryIntOp::Mul, elements.len());
+            context.usize_op_in_place(source_index, BinaryIntOp::Add, field_index);
+            context.array_get(source, source_index, value);
+
+            if let Type::Array(inner_elements, inner_length) = element {
+                let inner_count = context.make_constant((*inner_length).into());
+                flatten_elements(context, value, destination, inner_count, inner_elements, cursor);
+                context.deallocate_register(inner_count);
+            } else {
+                context.array_set(destination, cursor, value);
+                context.usize_op_in_place(cursor, BinaryIntOp::Add, 1);
+            }
+        }
+    });
+    context.deallocate_register(source_index);
+    context.deallocate_register(value);
+}
+
+/// Reads `element_count` elements of the types `elements` from the flat array at `source` starting
+/// at the index held by `cursor` into the array at `destination`, allocating each of their nested
+/// arrays. The cursor is advanced past the read field elements.
+fn unflatten_elements(
+    context: &mut BrilligContext,
+    source: RegisterIndex,
+    destination: RegisterIndex,
+    element_count: RegisterIndex,
+    elements: &[Type],
+    cursor: RegisterIndex,
+) {
+    let value = context.allocate_register();
+    let destination_index = context.allocate_register();
+    context.loop_instruction(element_count, |context, iterator| {
+        for (field_index, element) in elements.iter().enumerate() {
+            if let Type::Array(inner_elements, inner_length) = element {
+                context.allocate_fixed_length_array(value, inner_elements.len() * inner_length);
+                let inner_count = context.make_constant((*inner_length).into());
+                unflatten_elements(context, source, value, inner_count, inner_elements, cursor);
+                context.deallocate_register(inner_count);
+            } else {
+                context.array_get(source, cursor, value);
+                context.usize_op_in_place(cursor, BinaryIntOp::Add, 1);
+            }
+
+            context.usize_op(iterator, destination_index, BinaryIntOp::Mul, elements.len());
+            context.usize_op_in_place(destination_index, BinaryIntOp::Add, field_index);
+            context.array_set(destination, destination_index, value);
+        }
+    });
+    context.deallocate_register(destination_index);
+    context.deallocate_register(value);
+}
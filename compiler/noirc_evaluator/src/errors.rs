@@ -9,7 +9,8 @@
 //! An Error of the latter is an error in the implementation of the compiler
 use acvm::acir::native_types::Expression;
 use iter_extended::vecmap;
-use noirc_errors::{CustomDiagnostic as Diagnostic, FileDiagnostic};
+use noirc_errors::codes::*;
+use noirc_errors::{CustomDiagnostic as Diagnostic, ErrorCode, FileDiagnostic};
 use thiserror::Error;
 
 use crate::ssa::ir::dfg::CallStack;
@@ -39,6 +40,14 @@ pub enum RuntimeError {
     UnknownLoopBound { call_stack: CallStack },
     #[error("Argument is not constant")]
     AssertConstantFailed { call_stack: CallStack },
+    #[error("Static assertion failed: '{message}'")]
+    StaticAssertFailed { message: String, call_stack: CallStack },
+    #[error("Static assertion could not be evaluated at compile-time")]
+    StaticAssertDynamicPredicate { call_stack: CallStack },
+    #[error("No function with signature `{signature}` is used as a value, so this call has no possible target")]
+    UnknownDynamicCallTarget { signature: String, call_stack: CallStack },
+    #[error("`{name}` is marked `#[inline(always)]` but calls itself, so it can't be inlined")]
+    RecursiveInlineAlways { name: String, call_stack: CallStack },
 }
 
 // We avoid showing the actual lhs and rhs since most of the time they are just 0
@@ -91,9 +100,33 @@ impl RuntimeError {
             | RuntimeError::UnInitialized { call_stack, .. }
             | RuntimeError::UnknownLoopBound { call_stack }
             | RuntimeError::AssertConstantFailed { call_stack }
+            | RuntimeError::StaticAssertFailed { call_stack, .. }
+            | RuntimeError::StaticAssertDynamicPredicate { call_stack }
+            | RuntimeError::UnknownDynamicCallTarget { call_stack, .. }
+            | RuntimeError::RecursiveInlineAlways { call_stack, .. }
             | RuntimeError::UnsupportedIntegerSize { call_stack, .. } => call_stack,
         }
     }
+
+    pub fn code(&self) -> ErrorCode {
+        match self {
+            RuntimeError::FailedConstraint { .. } | RuntimeError::AssertConstantFailed { .. } => {
+                E0500
+            }
+            RuntimeError::IndexOutOfBounds { .. } => E0501,
+            RuntimeError::InvalidRangeConstraint { .. } => E0502,
+            RuntimeError::UnsupportedIntegerSize { .. } => E0511,
+            RuntimeError::TypeConversion { .. } => E0503,
+            RuntimeError::UnInitialized { .. } => E0504,
+            RuntimeError::UnknownLoopBound { .. } => E0505,
+            RuntimeError::UnknownDynamicCallTarget { .. } => E0506,
+            RuntimeError::StaticAssertFailed { .. } => E0509,
+            RuntimeError::StaticAssertDynamicPredicate { .. } => E0510,
+            RuntimeError::RecursiveInlineAlways { .. } => E0512,
+            RuntimeError::InternalError(InternalError::ReturnConstant { .. }) => E0507,
+            RuntimeError::InternalError(_) => E0508,
+        }
+    }
 }
 
 impl From<RuntimeError> for FileDiagnostic {
@@ -107,7 +140,8 @@ impl From<RuntimeError> for FileDiagnostic {
 
 impl RuntimeError {
     fn into_diagnostic(self) -> Diagnostic {
-        match self {
+        let code = self.code();
+        let diagnostic = match self {
             RuntimeError::InternalError(InternalError::ReturnConstant { ref call_stack }) => {
                 let message = self.to_string();
                 let location =
@@ -129,6 +163,7 @@ impl RuntimeError {
 
                 Diagnostic::simple_error(message, String::new(), location.span)
             }
-        }
+        };
+        diagnostic.with_code(code)
     }
 }
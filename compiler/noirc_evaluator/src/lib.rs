@@ -12,4 +12,4 @@ pub mod ssa;
 pub mod brillig;
 
 pub use ssa::abi_gen::into_abi_params;
-pub use ssa::create_circuit;
+pub use ssa::{create_circuit, CodegenOptions, SsaLogging, REQUIRED_SSA_PASSES, SSA_PASS_NAMES};
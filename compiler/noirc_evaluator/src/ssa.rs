@@ -18,75 +18,368 @@ use acvm::acir::{
 use noirc_errors::debug_info::DebugInfo;
 
 use noirc_abi::Abi;
+use noirc_printable_type::FieldDisplay;
 
-use noirc_frontend::{hir::Context, monomorphization::ast::Program};
+use noirc_frontend::{
+    hir::{cancellation::CancellationToken, Context},
+    monomorphization::ast::Program,
+};
 
-use self::{abi_gen::gen_abi, acir_gen::GeneratedAcir, ssa_gen::Ssa};
+use self::{abi_gen::gen_abi, acir_gen::GeneratedAcir, metrics::PassMetrics, ssa_gen::Ssa};
 
 pub mod abi_gen;
 mod acir_gen;
 pub(super) mod function_builder;
 pub mod ir;
+pub mod metrics;
 mod opt;
+#[cfg(test)]
+mod parser;
 pub mod ssa_gen;
 
+pub use opt::instrument::BLOCK_COUNTER_ORACLE;
+
+/// Settings of the build profile which change the SSA that is generated and how it is optimized
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct CodegenOptions {
+    /// `0` only runs the SSA passes needed to generate code, `1` also runs the passes which only
+    /// simplify the program, such as constant folding, and `2` runs some of them more than once
+    pub opt_level: u8,
+    /// Whether unsigned integer addition, subtraction and multiplication fail on overflow
+    /// instead of wrapping around
+    pub overflow_checks: bool,
+    /// The names of SSA passes which are skipped even if `opt_level` would run them
+    pub disabled_passes: Vec<String>,
+}
+
+impl Default for CodegenOptions {
+    fn default() -> Self {
+        CodegenOptions { opt_level: 1, overflow_checks: false, disabled_passes: Vec::new() }
+    }
+}
+
+/// The names of the stages of the SSA pipeline, in the order they first run. `initial` is the SSA
+/// before any pass has run, and passes such as `mem2reg` or `die` run several times.
+pub const SSA_PASS_NAMES: &[&str] = &[
+    "initial",
+    "merge_functions",
+    "defunctionalize",
+    "inline",
+    "die",
+    "mem2reg",
+    "assert_constant",
+    "unroll",
+    "static_assert",
+    "sccp",
+    "simplify_cfg",
+    "instrument",
+    "fold",
+    "flatten",
+    "simplify_arithmetic",
+    "cse",
+    "bounds_checks",
+    "fold_constants",
+    "range_checks",
+    "reduce_strength",
+];
+
+/// The stages of the SSA pipeline after which the SSA is printed
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum SsaLogging {
+    #[default]
+    None,
+    /// Print the SSA after every stage
+    All,
+    /// Print the SSA after each run of the pass with this name, one of [`SSA_PASS_NAMES`]
+    Pass(String),
+}
+
+impl SsaLogging {
+    fn should_print(&self, pass_name: &str) -> bool {
+        match self {
+            SsaLogging::None => false,
+            SsaLogging::All => true,
+            SsaLogging::Pass(name) => name == pass_name,
+        }
+    }
+}
+
 /// Optimize the given program by converting it into SSA
 /// form and performing optimizations there. When finished,
 /// convert the final SSA into ACIR and return it.
+#[allow(clippy::too_many_arguments)]
 pub(crate) fn optimize_into_acir(
     program: Program,
-    print_ssa_passes: bool,
+    ssa_logging: SsaLogging,
+    field_display: Option<FieldDisplay>,
     print_brillig_trace: bool,
+    print_ssa_metrics: bool,
+    instrument_blocks: bool,
+    codegen: CodegenOptions,
+    cancellation: CancellationToken,
 ) -> Result<GeneratedAcir, RuntimeError> {
     let abi_distinctness = program.return_distinctness;
-    let ssa = SsaBuilder::new(program, print_ssa_passes)
-        .run_pass(Ssa::defunctionalize, "After Defunctionalization:")
-        .run_pass(Ssa::inline_functions, "After Inlining:")
+    let builder = SsaBuilder::new(
+        program,
+        &codegen,
+        ssa_logging,
+        field_display,
+        print_ssa_metrics,
+        cancellation,
+    );
+    let builder = optimize_ssa(builder, instrument_blocks, &codegen)?;
+
+    if let Some(pass_metrics) = &builder.pass_metrics {
+        for PassMetrics { pass, metrics } in pass_metrics {
+            println!("{pass}\n{metrics}");
+        }
+    }
+
+    builder.cancellation.check();
+    let ssa = builder.finish();
+    let brillig =
+        tracing::info_span!("brillig_gen").in_scope(|| ssa.to_brillig(print_brillig_trace));
+    let _span = tracing::info_span!("acir_gen").entered();
+    let last_array_uses = ssa.find_last_array_uses();
+    ssa.into_acir(brillig, abi_distinctness, &last_array_uses)
+}
+
+/// Converts the given program into SSA form and runs the optimization passes over it
+/// without generating ACIR. Returns the metrics of the initial SSA followed by the metrics
+/// of the SSA after each pass, for use by reports and external benchmarking tools.
+pub fn ssa_metrics(program: Program) -> Result<Vec<PassMetrics>, RuntimeError> {
+    let codegen = CodegenOptions::default();
+    let cancellation = CancellationToken::default();
+    let builder = SsaBuilder::new(program, &codegen, SsaLogging::None, None, true, cancellation);
+    let builder = optimize_ssa(builder, false, &codegen)?;
+    Ok(builder.pass_metrics.unwrap_or_default())
+}
+
+/// The SSA passes which are always run because code can't be generated without them, or because
+/// the program's assertions would go unchecked. They can't be disabled with
+/// [`CodegenOptions::disabled_passes`].
+pub const REQUIRED_SSA_PASSES: &[&str] = &[
+    "defunctionalize",
+    "inline",
+    "assert_constant",
+    "unroll",
+    "static_assert",
+    "instrument",
+    "fold",
+    "flatten",
+];
+
+/// The function which runs an SSA pass
+#[derive(Clone, Copy)]
+enum PassFn {
+    Infallible(fn(Ssa) -> Ssa),
+    Fallible(fn(Ssa) -> Result<Ssa, RuntimeError>),
+}
+
+/// A single step of the SSA pipeline
+struct SsaPass {
+    /// One of the [`SSA_PASS_NAMES`], which selects the pass for printing or disabling it
+    name: &'static str,
+    /// The message printed before the SSA once the pass has run
+    msg: &'static str,
+    run: PassFn,
+    /// Whether the pass runs with the given codegen options, before any of them are disabled
+    enabled: bool,
+    /// Whether this run of the pass is needed to generate code, so that it isn't skipped when
+    /// its name is disabled although other runs of the same pass are
+    required: bool,
+}
+
+impl SsaPass {
+    fn new(pass: fn(Ssa) -> Ssa, name: &'static str, msg: &'static str) -> Self {
+        SsaPass { name, msg, run: PassFn::Infallible(pass), enabled: true, required: false }
+    }
+
+    /// A pass which may fail
+    fn fallible(
+        pass: fn(Ssa) -> Result<Ssa, RuntimeError>,
+        name: &'static str,
+        msg: &'static str,
+    ) -> Self {
+        SsaPass { name, msg, run: PassFn::Fallible(pass), enabled: true, required: false }
+    }
+
+    /// Runs the pass only if `condition` is true
+    fn enabled_if(self, condition: bool) -> Self {
+        SsaPass { enabled: self.enabled && condition, ..self }
+    }
+
+    /// Runs the pass even if its name is disabled
+    fn required(self) -> Self {
+        SsaPass { required: true, ..self }
+    }
+
+    fn is_enabled(&self, codegen: &CodegenOptions) -> bool {
+        self.enabled
+            && (self.required
+                || REQUIRED_SSA_PASSES.contains(&self.name)
+                || !codegen.disabled_passes.iter().any(|pass| pass == self.name))
+    }
+}
+
+fn dead_instruction_elimination() -> SsaPass {
+    SsaPass::new(Ssa::dead_instruction_elimination, "die", "After Dead Instruction Elimination:")
+}
+
+/// The passes of the SSA pipeline, in the order they run.
+///
+/// Dead instructions are removed after each major pass when optimizing, so that later passes
+/// never spend time on instructions whose results are unused.
+///
+/// If `instrument_blocks` is set, a counter is inserted at the start of each block
+/// once loops have been unrolled, but before the blocks of ACIR functions are flattened.
+///
+/// Fold boundaries are constrained functions compiled on their own up to that point, which are
+/// then inlined into `main` so that its CFG can be flattened into a single circuit. The runs of
+/// `mem2reg` around flattening and the final run of `die` are required, as references can't be
+/// merged by flattening nor converted into ACIR.
+///
+/// The passes which only simplify the program are skipped at `opt_level` 0. At `opt_level` 2
+/// constants are folded and common subexpressions eliminated once more at the end, as removing
+/// redundant checks and reducing strength often leaves more to simplify.
+fn ssa_pipeline(instrument_blocks: bool, codegen: &CodegenOptions) -> Vec<SsaPass> {
+    let optimize = codegen.opt_level > 0;
+    let instrument =
+        SsaPass::new(Ssa::instrument_blocks, "instrument", "After Instrumenting Blocks:")
+            .enabled_if(instrument_blocks);
+    let fold_constants =
+        || SsaPass::new(Ssa::fold_constants, "fold_constants", "After Constant Folding:");
+    let cse = || {
+        SsaPass::new(
+            Ssa::common_subexpression_elimination,
+            "cse",
+            "After Common Subexpression Elimination:",
+        )
+    };
+
+    vec![
+        SsaPass::new(
+            Ssa::merge_identical_functions,
+            "merge_functions",
+            "After Merging Identical Functions:",
+        )
+        .enabled_if(optimize),
+        SsaPass::fallible(Ssa::defunctionalize, "defunctionalize", "After Defunctionalization:"),
+        SsaPass::fallible(Ssa::inline_functions, "inline", "After Inlining:"),
+        dead_instruction_elimination().enabled_if(optimize),
         // Run mem2reg with the CFG separated into blocks
-        .run_pass(Ssa::mem2reg, "After Mem2Reg:")
-        .try_run_pass(Ssa::evaluate_assert_constant, "After Assert Constant:")?
-        .try_run_pass(Ssa::unroll_loops, "After Unrolling:")?
-        .run_pass(Ssa::simplify_cfg, "After Simplifying:")
+        SsaPass::new(Ssa::mem2reg, "mem2reg", "After Mem2Reg:"),
+        SsaPass::fallible(
+            Ssa::evaluate_assert_constant,
+            "assert_constant",
+            "After Assert Constant:",
+        ),
+        SsaPass::fallible(Ssa::unroll_loops, "unroll", "After Unrolling:"),
+        dead_instruction_elimination().enabled_if(optimize),
+        SsaPass::fallible(Ssa::evaluate_static_assert, "static_assert", "After Static Assert:"),
+        SsaPass::new(
+            Ssa::sparse_conditional_constant_propagation,
+            "sccp",
+            "After Sparse Conditional Constant Propagation:",
+        )
+        .enabled_if(optimize),
+        SsaPass::new(Ssa::simplify_cfg, "simplify_cfg", "After Simplifying:").enabled_if(optimize),
+        dead_instruction_elimination().enabled_if(optimize),
+        instrument,
+        SsaPass::fallible(Ssa::inline_fold_boundaries, "fold", "After Inlining Fold Boundaries:"),
         // Run mem2reg before flattening to handle any promotion
         // of values that can be accessed after loop unrolling.
         // If there are slice mergers uncovered by loop unrolling
         // and this pass is missed, slice merging will fail inside of flattening.
-        .run_pass(Ssa::mem2reg, "After Mem2Reg:")
-        .run_pass(Ssa::flatten_cfg, "After Flattening:")
+        SsaPass::new(Ssa::mem2reg, "mem2reg", "After Mem2Reg:").required(),
+        SsaPass::new(Ssa::flatten_cfg, "flatten", "After Flattening:"),
         // Run mem2reg once more with the flattened CFG to catch any remaining loads/stores
-        .run_pass(Ssa::mem2reg, "After Mem2Reg:")
-        .run_pass(Ssa::fold_constants, "After Constant Folding:")
-        .run_pass(Ssa::dead_instruction_elimination, "After Dead Instruction Elimination:")
-        .finish();
+        SsaPass::new(Ssa::mem2reg, "mem2reg", "After Mem2Reg:").required(),
+        dead_instruction_elimination().enabled_if(optimize),
+        SsaPass::new(
+            Ssa::simplify_arithmetic,
+            "simplify_arithmetic",
+            "After Algebraic Simplification:",
+        )
+        .enabled_if(optimize),
+        cse().enabled_if(optimize),
+        SsaPass::new(
+            Ssa::remove_redundant_bounds_checks,
+            "bounds_checks",
+            "After Removing Redundant Bounds Checks:",
+        )
+        .enabled_if(optimize),
+        fold_constants().enabled_if(optimize),
+        SsaPass::new(
+            Ssa::remove_redundant_range_checks,
+            "range_checks",
+            "After Removing Redundant Range Checks:",
+        )
+        .enabled_if(optimize),
+        SsaPass::new(Ssa::reduce_strength, "reduce_strength", "After Strength Reduction:")
+            .enabled_if(optimize),
+        fold_constants().enabled_if(codegen.opt_level > 1),
+        cse().enabled_if(codegen.opt_level > 1),
+        // Remove the allocations left unused by mem2reg, which can't be converted into ACIR
+        dead_instruction_elimination().required(),
+    ]
+}
 
-    let brillig = ssa.to_brillig(print_brillig_trace);
-    let last_array_uses = ssa.find_last_array_uses();
-    ssa.into_acir(brillig, abi_distinctness, &last_array_uses)
+/// Runs each enabled pass of the [`ssa_pipeline`], in order. Passes named in
+/// `codegen.disabled_passes` are skipped unless they are [`REQUIRED_SSA_PASSES`].
+fn optimize_ssa(
+    mut builder: SsaBuilder,
+    instrument_blocks: bool,
+    codegen: &CodegenOptions,
+) -> Result<SsaBuilder, RuntimeError> {
+    for pass in ssa_pipeline(instrument_blocks, codegen) {
+        if pass.is_enabled(codegen) {
+            builder = builder.run_pass(&pass)?;
+        }
+    }
+    Ok(builder)
 }
 
 /// Compiles the [`Program`] into [`ACIR`][acvm::acir::circuit::Circuit].
 ///
 /// The output ACIR is is backend-agnostic and so must go through a transformation pass before usage in proof generation.
+#[tracing::instrument(skip_all)]
+#[allow(clippy::too_many_arguments)]
 pub fn create_circuit(
     context: &Context,
     program: Program,
-    enable_ssa_logging: bool,
+    ssa_logging: SsaLogging,
+    field_display: Option<FieldDisplay>,
     enable_brillig_logging: bool,
+    enable_ssa_metrics: bool,
+    instrument_blocks: bool,
+    codegen: CodegenOptions,
 ) -> Result<(Circuit, DebugInfo, Abi), RuntimeError> {
     let func_sig = program.main_function_signature.clone();
-    let mut generated_acir =
-        optimize_into_acir(program, enable_ssa_logging, enable_brillig_logging)?;
+    let mut generated_acir = optimize_into_acir(
+        program,
+        ssa_logging,
+        field_display,
+        enable_brillig_logging,
+        enable_ssa_metrics,
+        instrument_blocks,
+        codegen,
+        context.def_interner.cancellation_token().clone(),
+    )?;
     let opcodes = generated_acir.take_opcodes();
     let GeneratedAcir {
         current_witness_index,
         return_witnesses,
+        return_constants,
         locations,
         input_witnesses,
         assert_messages,
+        block_tallies,
         ..
     } = generated_acir;
 
-    let abi = gen_abi(context, func_sig, &input_witnesses, return_witnesses.clone());
+    let abi =
+        gen_abi(context, func_sig, &input_witnesses, return_witnesses.clone(), return_constants);
     let public_abi = abi.clone().public_abi();
 
     let public_parameters =
@@ -114,49 +407,127 @@ pub fn create_circuit(
         .collect();
 
     let mut debug_info = DebugInfo::new(locations);
+    debug_info.block_tallies = block_tallies;
 
     // Perform any ACIR-level optimizations
-    let (optimized_circuit, transformation_map) = acvm::compiler::optimize(circuit);
+    let (optimized_circuit, transformation_map) =
+        tracing::info_span!("acir_optimize").in_scope(|| acvm::compiler::optimize(circuit));
     debug_info.update_acir(transformation_map);
 
     Ok((optimized_circuit, debug_info, abi))
 }
 
-// This is just a convenience object to bundle the ssa with `print_ssa_passes` for debug printing.
+// This is just a convenience object to bundle the ssa with `ssa_logging` for debug printing,
+// along with the metrics recorded after each pass if they are being collected.
 struct SsaBuilder {
     ssa: Ssa,
-    print_ssa_passes: bool,
+    ssa_logging: SsaLogging,
+    /// How to show numeric constants in the printed SSA
+    field_display: Option<FieldDisplay>,
+    pass_metrics: Option<Vec<PassMetrics>>,
+    cancellation: CancellationToken,
 }
 
 impl SsaBuilder {
-    fn new(program: Program, print_ssa_passes: bool) -> SsaBuilder {
-        SsaBuilder { print_ssa_passes, ssa: ssa_gen::generate_ssa(program) }.print("Initial SSA:")
+    fn new(
+        program: Program,
+        codegen: &CodegenOptions,
+        ssa_logging: SsaLogging,
+        field_display: Option<FieldDisplay>,
+        collect_metrics: bool,
+        cancellation: CancellationToken,
+    ) -> SsaBuilder {
+        let pass_metrics = collect_metrics.then(Vec::new);
+        let ssa = tracing::info_span!("ssa_gen")
+            .in_scope(|| ssa_gen::generate_ssa(program, codegen.overflow_checks));
+        SsaBuilder { ssa_logging, field_display, pass_metrics, ssa, cancellation }
+            .print("initial", "Initial SSA:")
     }
 
     fn finish(self) -> Ssa {
         self.ssa
     }
 
-    /// Runs the given SSA pass and prints the SSA afterward if `print_ssa_passes` is true.
-    fn run_pass(mut self, pass: fn(Ssa) -> Ssa, msg: &str) -> Self {
-        self.ssa = pass(self.ssa);
-        self.print(msg)
+    /// Runs the given SSA pass and prints the SSA afterward if `ssa_logging` selects its name.
+    /// Unwinds before running the pass if the compilation has been cancelled.
+    fn run_pass(mut self, pass: &SsaPass) -> Result<Self, RuntimeError> {
+        self.cancellation.check();
+        let span = pass_span(pass.msg);
+        self.ssa = match pass.run {
+            PassFn::Infallible(run) => span.in_scope(|| run(self.ssa)),
+            PassFn::Fallible(run) => span.in_scope(|| run(self.ssa))?,
+        };
+        Ok(self.print(pass.name, pass.msg))
     }
 
-    /// The same as `run_pass` but for passes that may fail
-    fn try_run_pass(
-        mut self,
-        pass: fn(Ssa) -> Result<Ssa, RuntimeError>,
-        msg: &str,
-    ) -> Result<Self, RuntimeError> {
-        self.ssa = pass(self.ssa)?;
-        Ok(self.print(msg))
+    /// Prints the SSA if `ssa_logging` selects the stage `name` and records its metrics if they
+    /// are being collected.
+    fn print(mut self, name: &str, msg: &str) -> Self {
+        debug_assert!(SSA_PASS_NAMES.contains(&name), "SSA pass `{name}` has no listed name");
+        if self.ssa_logging.should_print(name) {
+            println!("{msg}\n{}", self.ssa.display(self.field_display));
+        }
+        if let Some(pass_metrics) = &mut self.pass_metrics {
+            let pass = msg.trim_end_matches(':').to_owned();
+            pass_metrics.push(PassMetrics { pass, metrics: self.ssa.metrics() });
+        }
+        self
+    }
+}
+
+/// A span covering a single SSA pass, named after the pass rather than the message printed after it.
+fn pass_span(msg: &str) -> tracing::Span {
+    let pass = msg.trim_start_matches("After ").trim_end_matches(':');
+    tracing::info_span!("ssa_pass", pass)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ssa_pipeline, CodegenOptions, REQUIRED_SSA_PASSES, SSA_PASS_NAMES};
+
+    fn enabled_passes(codegen: &CodegenOptions) -> Vec<&'static str> {
+        let pipeline = ssa_pipeline(false, codegen).into_iter();
+        pipeline.filter(|pass| pass.is_enabled(codegen)).map(|pass| pass.name).collect()
+    }
+
+    fn count(passes: &[&str], name: &str) -> usize {
+        passes.iter().filter(|pass| **pass == name).count()
     }
 
-    fn print(self, msg: &str) -> Self {
-        if self.print_ssa_passes {
-            println!("{msg}\n{}", self.ssa);
+    #[test]
+    fn pipeline_passes_are_listed() {
+        let codegen = CodegenOptions { opt_level: 2, ..CodegenOptions::default() };
+        for pass in ssa_pipeline(true, &codegen) {
+            assert!(SSA_PASS_NAMES.contains(&pass.name), "`{}` isn't listed", pass.name);
         }
-        self
+        for pass in REQUIRED_SSA_PASSES {
+            assert!(SSA_PASS_NAMES.contains(pass), "`{pass}` isn't listed");
+        }
+    }
+
+    #[test]
+    fn opt_level_selects_simplifying_passes() {
+        let passes =
+            |opt_level| enabled_passes(&CodegenOptions { opt_level, ..Default::default() });
+
+        let unoptimized = passes(0);
+        assert_eq!(count(&unoptimized, "cse"), 0);
+        assert_eq!(count(&unoptimized, "mem2reg"), 3);
+        assert_eq!(count(&unoptimized, "die"), 1);
+
+        assert_eq!(count(&passes(1), "cse"), 1);
+        assert_eq!(count(&passes(2), "cse"), 2);
+        assert_eq!(count(&passes(2), "fold_constants"), 2);
+    }
+
+    #[test]
+    fn required_passes_cannot_be_disabled() {
+        let disabled_passes = vec!["mem2reg".to_owned(), "die".to_owned(), "flatten".to_owned()];
+        let passes = enabled_passes(&CodegenOptions { disabled_passes, ..Default::default() });
+
+        // Only the run of mem2reg before unrolling and the intermediate runs of die can be skipped
+        assert_eq!(count(&passes, "mem2reg"), 2);
+        assert_eq!(count(&passes, "die"), 1);
+        assert_eq!(count(&passes, "flatten"), 1);
     }
 }
@@ -1,6 +1,6 @@
 use std::collections::BTreeMap;
 
-use acvm::acir::native_types::Witness;
+use acvm::{acir::native_types::Witness, FieldElement};
 use iter_extended::{btree_map, vecmap};
 use noirc_abi::{Abi, AbiParameter, AbiType};
 use noirc_frontend::{
@@ -33,19 +33,20 @@ pub fn into_abi_params(context: &Context, params: Vec<Param>) -> Vec<AbiParamete
     })
 }
 
-/// Arranges a function signature and a generated circuit's return witnesses into a
-/// `noirc_abi::Abi`.
+/// Arranges a function signature and a generated circuit's return witnesses and constants into
+/// a `noirc_abi::Abi`.
 pub(crate) fn gen_abi(
     context: &Context,
     func_sig: FunctionSignature,
     input_witnesses: &[Witness],
     return_witnesses: Vec<Witness>,
+    return_constants: BTreeMap<u32, FieldElement>,
 ) -> Abi {
     let (parameters, return_type) = func_sig;
     let parameters = into_abi_params(context, parameters);
     let return_type = return_type.map(|typ| AbiType::from_type(context, &typ));
     let param_witnesses = param_witnesses_from_abi_param(&parameters, input_witnesses);
-    Abi { parameters, return_type, param_witnesses, return_witnesses }
+    Abi { parameters, return_type, param_witnesses, return_witnesses, return_constants }
 }
 
 // Takes each abi parameter and shallowly maps to the expected witness range in which the
@@ -226,6 +226,17 @@ impl AcirContext {
         Ok(witness_var)
     }
 
+    /// Records the witness holding `predicate` as the execution count of the block with the given label.
+    pub(crate) fn add_block_tally(
+        &mut self,
+        predicate: AcirVar,
+        label: String,
+    ) -> Result<(), InternalError> {
+        let witness = self.var_to_witness(predicate)?;
+        self.acir_ir.block_tallies.push((witness, label));
+        Ok(())
+    }
+
     /// Converts an [`AcirVar`] to a [`Witness`]
     fn var_to_witness(&mut self, var: AcirVar) -> Result<Witness, InternalError> {
         let expression = self.var_to_expression(var)?;
@@ -602,7 +613,14 @@ impl AcirContext {
 
     /// Converts the `AcirVar` to a `Witness` if it hasn't been already, and appends it to the
     /// `GeneratedAcir`'s return witnesses.
+    ///
+    /// Constant values are recorded directly rather than being assigned a witness, so that they
+    /// do not need to be passed to the verifier as public inputs.
     pub(crate) fn return_var(&mut self, acir_var: AcirVar) -> Result<(), InternalError> {
+        if let Some(constant) = self.var_to_expression(acir_var)?.to_const() {
+            self.acir_ir.push_return_constant(constant);
+            return Ok(());
+        }
         let witness = self.var_to_witness(acir_var)?;
         self.acir_ir.push_return_witness(witness);
         Ok(())
@@ -41,6 +41,12 @@ pub(crate) struct GeneratedAcir {
     /// abi's return type.
     pub(crate) return_witnesses: Vec<Witness>,
 
+    /// Elements of the return value which are known at compile time, keyed by their position
+    /// within the flattened return value.
+    ///
+    /// These are not assigned a witness and so are not part of the circuit's public inputs.
+    pub(crate) return_constants: BTreeMap<u32, FieldElement>,
+
     /// All witness indices which are inputs to the main function
     pub(crate) input_witnesses: Vec<Witness>,
 
@@ -53,6 +59,10 @@ pub(crate) struct GeneratedAcir {
 
     /// Correspondence between an opcode index and the error message associated with it.
     pub(crate) assert_messages: BTreeMap<OpcodeLocation, String>,
+
+    /// Witnesses holding the predicate of each instrumented block, along with the block's label.
+    /// These are only collected when compiling with block instrumentation.
+    pub(crate) block_tallies: Vec<(Witness, String)>,
 }
 
 impl GeneratedAcir {
@@ -120,6 +130,12 @@ impl GeneratedAcir {
     pub(crate) fn push_return_witness(&mut self, witness: Witness) {
         self.return_witnesses.push(witness);
     }
+
+    /// Adds a constant to the program's return value in place of a witness.
+    pub(crate) fn push_return_constant(&mut self, constant: FieldElement) {
+        let position = self.return_witnesses.len() + self.return_constants.len();
+        self.return_constants.insert(position as u32, constant);
+    }
 }
 
 impl GeneratedAcir {
@@ -18,6 +18,7 @@ use super::{
         types::{NumericType, Type},
         value::{Value, ValueId},
     },
+    opt::instrument::{decode_label, BLOCK_COUNTER_ORACLE},
     ssa_gen::Ssa,
 };
 use crate::brillig::brillig_ir::artifact::GeneratedBrillig;
@@ -494,6 +495,14 @@ impl Context {
                             self.ssa_values.insert(*result, output);
                         }
                     }
+                    Value::ForeignFunction(name) if name == BLOCK_COUNTER_ORACLE => {
+                        // Flattened ACIR cannot count how often a block runs, but the predicate
+                        // active at the start of the block tells us whether it ran at all.
+                        let label = decode_label(dfg, arguments[0])
+                            .expect("ICE: block counters should be given a constant label");
+                        self.acir_context
+                            .add_block_tally(self.current_side_effects_enabled_var, label)?;
+                    }
                     Value::ForeignFunction(_) => unreachable!(
                         "All `oracle` methods should be wrapped in an unconstrained fn"
                     ),
@@ -1259,12 +1268,7 @@ impl Context {
         // will expand the array if there is one.
         let return_acir_vars = self.flatten_value_list(return_values, dfg);
         for acir_var in return_acir_vars {
-            // TODO(Guillaume) -- disabled as it has shown to break
-            // TODO with important programs. We will add it back once
-            // TODO we change it to a warning.
-            // if self.acir_context.is_constant(&acir_var) {
-            //     return Err(InternalError::ReturnConstant { call_stack: call_stack.clone() });
-            // }
+            // Constant return values are embedded in the ABI rather than becoming public inputs.
             self.acir_context.return_var(acir_var)?;
         }
         Ok(())
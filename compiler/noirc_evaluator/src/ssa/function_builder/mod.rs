@@ -2,6 +2,7 @@ use std::borrow::Cow;
 
 use acvm::FieldElement;
 use noirc_errors::Location;
+use noirc_frontend::token::InlineType;
 
 use crate::ssa::ir::{
     basic_block::BasicBlockId,
@@ -86,6 +87,11 @@ impl FunctionBuilder {
         self.new_function_with_type(name, function_id, RuntimeType::Brillig);
     }
 
+    /// Set whether calls to the current function should be inlined.
+    pub(crate) fn set_inline_type(&mut self, inline_type: InlineType) {
+        self.current_function.set_inline_type(inline_type);
+    }
+
     /// Consume the FunctionBuilder returning all the functions it has generated.
     pub(crate) fn finish(mut self) -> Ssa {
         self.finished_functions.push(self.current_function);
@@ -385,6 +385,17 @@ impl DataFlowGraph {
         }
     }
 
+    /// Returns the string represented by this value if it is an array of constant bytes, such as
+    /// a string literal. Otherwise, this returns None.
+    pub(crate) fn get_string_constant(&self, value: ValueId) -> Option<String> {
+        let (elements, _) = self.get_array_constant(value)?;
+        let bytes = elements.iter().map(|element| {
+            let byte = self.get_numeric_constant(*element)?.try_to_u64()?;
+            u8::try_from(byte).ok()
+        });
+        String::from_utf8(bytes.collect::<Option<Vec<u8>>>()?).ok()
+    }
+
     /// If this value is an array, return the length of the array as indicated by its type.
     /// Otherwise, return None.
     pub(crate) fn try_get_array_length(&self, value: ValueId) -> Option<usize> {
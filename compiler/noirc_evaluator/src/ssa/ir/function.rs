@@ -1,6 +1,7 @@
 use std::collections::BTreeSet;
 
 use iter_extended::vecmap;
+use noirc_frontend::token::InlineType;
 
 use super::basic_block::BasicBlockId;
 use super::dfg::DataFlowGraph;
@@ -35,6 +36,9 @@ pub(crate) struct Function {
 
     runtime: RuntimeType,
 
+    /// Whether calls to this function should be inlined, as set by its `#[inline(...)]` attribute
+    inline_type: InlineType,
+
     /// The DataFlowGraph holds the majority of data pertaining to the function
     /// including its blocks, instructions, and values.
     pub(crate) dfg: DataFlowGraph,
@@ -47,7 +51,14 @@ impl Function {
     pub(crate) fn new(name: String, id: FunctionId) -> Self {
         let mut dfg = DataFlowGraph::default();
         let entry_block = dfg.make_block();
-        Self { name, id, entry_block, dfg, runtime: RuntimeType::Acir }
+        Self {
+            name,
+            id,
+            entry_block,
+            dfg,
+            runtime: RuntimeType::Acir,
+            inline_type: InlineType::Default,
+        }
     }
 
     /// The name of the function.
@@ -71,6 +82,16 @@ impl Function {
         self.runtime = runtime;
     }
 
+    /// Whether calls to the function should be inlined.
+    pub(crate) fn inline_type(&self) -> InlineType {
+        self.inline_type
+    }
+
+    /// Set whether calls to the function should be inlined.
+    pub(crate) fn set_inline_type(&mut self, inline_type: InlineType) {
+        self.inline_type = inline_type;
+    }
+
     /// Retrieves the entry block of a function.
     ///
     /// A function's entry block contains the instructions
@@ -147,9 +168,17 @@ pub(crate) struct Signature {
     pub(crate) returns: Vec<Type>,
 }
 
+impl std::fmt::Display for Signature {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let params = vecmap(&self.params, ToString::to_string).join(", ");
+        let returns = vecmap(&self.returns, ToString::to_string).join(", ");
+        write!(f, "fn({params}) -> ({returns})")
+    }
+}
+
 impl std::fmt::Display for Function {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        super::printer::display_function(self, f)
+        super::printer::display_function(self, None, f)
     }
 }
 
@@ -34,6 +34,7 @@ pub(crate) enum Intrinsic {
     Sort,
     ArrayLen,
     AssertConstant,
+    StaticAssert,
     SlicePushBack,
     SlicePushFront,
     SlicePopBack,
@@ -41,6 +42,7 @@ pub(crate) enum Intrinsic {
     SliceInsert,
     SliceRemove,
     StrAsBytes,
+    StrFromBytes,
     ToBits(Endian),
     ToRadix(Endian),
     BlackBox(BlackBoxFunc),
@@ -54,6 +56,7 @@ impl std::fmt::Display for Intrinsic {
             Intrinsic::Sort => write!(f, "arraysort"),
             Intrinsic::ArrayLen => write!(f, "array_len"),
             Intrinsic::AssertConstant => write!(f, "assert_constant"),
+            Intrinsic::StaticAssert => write!(f, "static_assert"),
             Intrinsic::SlicePushBack => write!(f, "slice_push_back"),
             Intrinsic::SlicePushFront => write!(f, "slice_push_front"),
             Intrinsic::SlicePopBack => write!(f, "slice_pop_back"),
@@ -61,6 +64,7 @@ impl std::fmt::Display for Intrinsic {
             Intrinsic::SliceInsert => write!(f, "slice_insert"),
             Intrinsic::SliceRemove => write!(f, "slice_remove"),
             Intrinsic::StrAsBytes => write!(f, "str_as_bytes"),
+            Intrinsic::StrFromBytes => write!(f, "str_from_bytes"),
             Intrinsic::ToBits(Endian::Big) => write!(f, "to_be_bits"),
             Intrinsic::ToBits(Endian::Little) => write!(f, "to_le_bits"),
             Intrinsic::ToRadix(Endian::Big) => write!(f, "to_be_radix"),
@@ -78,7 +82,7 @@ impl Intrinsic {
     /// If there are no side effects then the `Intrinsic` can be removed if the result is unused.
     pub(crate) fn has_side_effects(&self) -> bool {
         match self {
-            Intrinsic::AssertConstant => true,
+            Intrinsic::AssertConstant | Intrinsic::StaticAssert => true,
 
             Intrinsic::Sort
             | Intrinsic::ArrayLen
@@ -89,6 +93,7 @@ impl Intrinsic {
             | Intrinsic::SliceInsert
             | Intrinsic::SliceRemove
             | Intrinsic::StrAsBytes
+            | Intrinsic::StrFromBytes
             | Intrinsic::ToBits(_)
             | Intrinsic::ToRadix(_)
             | Intrinsic::FromField
@@ -106,6 +111,7 @@ impl Intrinsic {
             "arraysort" => Some(Intrinsic::Sort),
             "array_len" => Some(Intrinsic::ArrayLen),
             "assert_constant" => Some(Intrinsic::AssertConstant),
+            "static_assert" => Some(Intrinsic::StaticAssert),
             "slice_push_back" => Some(Intrinsic::SlicePushBack),
             "slice_push_front" => Some(Intrinsic::SlicePushFront),
             "slice_pop_back" => Some(Intrinsic::SlicePopBack),
@@ -113,6 +119,7 @@ impl Intrinsic {
             "slice_insert" => Some(Intrinsic::SliceInsert),
             "slice_remove" => Some(Intrinsic::SliceRemove),
             "str_as_bytes" => Some(Intrinsic::StrAsBytes),
+            "str_from_bytes" => Some(Intrinsic::StrFromBytes),
             "to_le_radix" => Some(Intrinsic::ToRadix(Endian::Little)),
             "to_be_radix" => Some(Intrinsic::ToRadix(Endian::Big)),
             "to_le_bits" => Some(Intrinsic::ToBits(Endian::Little)),
@@ -212,72 +219,62 @@ impl Instruction {
         matches!(self.result_type(), InstructionResultType::Unknown)
     }
 
-    /// Pure `Instructions` are instructions which have no side-effects and results are a function of the inputs only,
-    /// i.e. there are no interactions with memory.
-    ///
-    /// Pure instructions can be replaced with the results of another pure instruction with the same inputs.
-    pub(crate) fn is_pure(&self, dfg: &DataFlowGraph) -> bool {
+    /// Classifies how this instruction affects the program other than through its results.
+    pub(crate) fn effects(&self, dfg: &DataFlowGraph) -> Effects {
         use Instruction::*;
 
-        match self {
-            Binary(_) | Cast(_, _) | Not(_) | ArrayGet { .. } | ArraySet { .. } => true,
-
-            // Unclear why this instruction causes problems.
-            Truncate { .. } => false,
-
-            // These either have side-effects or interact with memory
-            Constrain(..) | EnableSideEffects { .. } | Allocate | Load { .. } | Store { .. } => {
-                false
-            }
-
-            Call { func, .. } => match dfg[*func] {
-                Value::Intrinsic(intrinsic) => !intrinsic.has_side_effects(),
-                _ => false,
-            },
-        }
-    }
-
-    pub(crate) fn has_side_effects(&self, dfg: &DataFlowGraph) -> bool {
-        use Instruction::*;
         match self {
             Binary(binary) => {
                 if matches!(binary.operator, BinaryOp::Div | BinaryOp::Mod) {
-                    if let Some(rhs) = dfg.get_numeric_constant(binary.rhs) {
-                        rhs == FieldElement::zero()
-                    } else {
-                        true
+                    match dfg.get_numeric_constant(binary.rhs) {
+                        Some(rhs) if !rhs.is_zero() => Effects::Pure,
+                        _ => Effects::MayFail,
                     }
                 } else {
-                    false
+                    Effects::Pure
                 }
             }
-            Cast(_, _)
-            | Not(_)
-            | Truncate { .. }
-            | Allocate
-            | Load { .. }
-            | ArrayGet { .. }
-            | ArraySet { .. } => false,
+            Cast(_, _) | Not(_) | ArrayGet { .. } | ArraySet { .. } => Effects::Pure,
+
+            // Unclear why deduplicating this instruction causes problems.
+            Truncate { .. } => Effects::Impure,
 
-            Constrain(..) | Store { .. } | EnableSideEffects { .. } => true,
+            // Each allocation returns a new reference, and loads depend on the stores before them
+            Allocate | Load { .. } => Effects::Impure,
+
+            Constrain(..) | Store { .. } | EnableSideEffects { .. } => Effects::SideEffects,
 
             // Some `Intrinsic`s have side effects so we must check what kind of `Call` this is.
             Call { func, .. } => match dfg[*func] {
-                Value::Intrinsic(intrinsic) => intrinsic.has_side_effects(),
+                Value::Intrinsic(intrinsic) if intrinsic.has_side_effects() => Effects::SideEffects,
+                Value::Intrinsic(_) => Effects::Pure,
 
                 // All foreign functions are treated as having side effects.
                 // This is because they can be used to pass information
                 // from the ACVM to the external world during execution.
-                Value::ForeignFunction(_) => true,
+                Value::ForeignFunction(_) => Effects::SideEffects,
 
                 // We must assume that functions contain a side effect as we cannot inspect more deeply.
-                Value::Function(_) => true,
+                Value::Function(_) => Effects::SideEffects,
 
-                _ => false,
+                _ => Effects::Impure,
             },
         }
     }
 
+    /// Pure `Instructions` are instructions which have no side-effects and results are a function of the inputs only,
+    /// i.e. there are no interactions with memory.
+    ///
+    /// Pure instructions can be replaced with the results of another pure instruction with the same inputs.
+    pub(crate) fn is_pure(&self, dfg: &DataFlowGraph) -> bool {
+        self.effects(dfg).can_be_deduplicated()
+    }
+
+    /// Returns whether the instruction must be kept even if its results are unused.
+    pub(crate) fn has_side_effects(&self, dfg: &DataFlowGraph) -> bool {
+        !self.effects(dfg).can_be_removed_if_unused()
+    }
+
     /// Maps each ValueId inside this instruction to a new ValueId, returning the new instruction.
     /// Note that the returned instruction is fresh and will not have an assigned InstructionId
     /// until it is manually inserted in a DataFlowGraph later.
@@ -919,6 +916,37 @@ impl std::fmt::Display for BinaryOp {
     }
 }
 
+/// How an instruction affects the program other than through its results, which decides which
+/// optimizations may remove or deduplicate it.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub(crate) enum Effects {
+    /// The results are a function of the arguments only, and the instruction has no other
+    /// effects.
+    Pure,
+    /// The results are a function of the arguments only, but the instruction may fail, such as a
+    /// division by a value which may be zero.
+    MayFail,
+    /// The instruction has no effects besides its results, but they may differ between
+    /// instructions with the same arguments, such as loads from memory.
+    Impure,
+    /// The instruction affects the program beyond its results, such as by writing to memory,
+    /// constraining values or calling functions which may do either.
+    SideEffects,
+}
+
+impl Effects {
+    /// Returns whether the instruction may be replaced with the results of an earlier instruction
+    /// with the same arguments.
+    pub(crate) fn can_be_deduplicated(self) -> bool {
+        matches!(self, Effects::Pure | Effects::MayFail)
+    }
+
+    /// Returns whether the instruction may be removed if its results are unused.
+    pub(crate) fn can_be_removed_if_unused(self) -> bool {
+        matches!(self, Effects::Pure | Effects::Impure)
+    }
+}
+
 /// Contains the result to Instruction::simplify, specifying how the instruction
 /// should be simplified.
 pub(crate) enum SimplifyResult {
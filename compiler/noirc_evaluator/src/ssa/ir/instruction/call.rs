@@ -221,7 +221,7 @@ pub(super) fn simplify_call(
                 SimplifyResult::None
             }
         }
-        Intrinsic::StrAsBytes => {
+        Intrinsic::StrAsBytes | Intrinsic::StrFromBytes => {
             // Strings are already represented as bytes internally
             SimplifyResult::SimplifiedTo(arguments[0])
         }
@@ -232,6 +232,15 @@ pub(super) fn simplify_call(
                 SimplifyResult::None
             }
         }
+        Intrinsic::StaticAssert => {
+            // A false predicate is left for `evaluate_static_assert` to report
+            if dfg.get_numeric_constant(arguments[0]).map_or(false, |predicate| predicate.is_one())
+            {
+                SimplifyResult::Remove
+            } else {
+                SimplifyResult::None
+            }
+        }
         Intrinsic::BlackBox(bb_func) => simplify_black_box_func(bb_func, arguments, dfg),
         Intrinsic::Sort => simplify_sort(dfg, arguments),
         Intrinsic::AsField => {
@@ -5,6 +5,7 @@ use std::{
 };
 
 use iter_extended::vecmap;
+use noirc_printable_type::FieldDisplay;
 
 use super::{
     basic_block::BasicBlockId,
@@ -14,9 +15,15 @@ use super::{
 };
 
 /// Helper function for Function's Display impl to pretty-print the function with the given formatter.
-pub(crate) fn display_function(function: &Function, f: &mut Formatter) -> Result {
+/// Numeric constants are shown with `field_display` if given, or with their usual display otherwise.
+pub(crate) fn display_function(
+    function: &Function,
+    field_display: Option<FieldDisplay>,
+    f: &mut Formatter,
+) -> Result {
     writeln!(f, "{} fn {} {} {{", function.runtime(), function.name(), function.id())?;
-    display_block_with_successors(function, function.entry_block(), &mut HashSet::new(), f)?;
+    let entry_block = function.entry_block();
+    display_block_with_successors(function, field_display, entry_block, &mut HashSet::new(), f)?;
     write!(f, "}}")
 }
 
@@ -25,16 +32,17 @@ pub(crate) fn display_function(function: &Function, f: &mut Formatter) -> Result
 /// there would be infinite recursion for any loops in the IR.
 pub(crate) fn display_block_with_successors(
     function: &Function,
+    field_display: Option<FieldDisplay>,
     block_id: BasicBlockId,
     visited: &mut HashSet<BasicBlockId>,
     f: &mut Formatter,
 ) -> Result {
-    display_block(function, block_id, f)?;
+    display_block(function, field_display, block_id, f)?;
     visited.insert(block_id);
 
     for successor in function.dfg[block_id].successors() {
         if !visited.contains(&successor) {
-            display_block_with_successors(function, successor, visited, f)?;
+            display_block_with_successors(function, field_display, successor, visited, f)?;
         }
     }
     Ok(())
@@ -43,45 +51,55 @@ pub(crate) fn display_block_with_successors(
 /// Display a single block. This will not display the block's successors.
 pub(crate) fn display_block(
     function: &Function,
+    field_display: Option<FieldDisplay>,
     block_id: BasicBlockId,
     f: &mut Formatter,
 ) -> Result {
     let block = &function.dfg[block_id];
 
-    writeln!(f, "  {}({}):", block_id, value_list_with_types(function, block.parameters()))?;
+    writeln!(
+        f,
+        "  {}({}):",
+        block_id,
+        value_list_with_types(function, field_display, block.parameters())
+    )?;
 
     for instruction in block.instructions() {
-        display_instruction(function, *instruction, f)?;
+        display_instruction(function, field_display, *instruction, f)?;
     }
 
-    display_terminator(function, block.terminator(), f)
+    display_terminator(function, field_display, block.terminator(), f)
 }
 
-/// Specialize displaying value ids so that if they refer to a numeric
-/// constant or a function we print those directly.
-fn value(function: &Function, id: ValueId) -> String {
+/// Specialize displaying value ids so that if they refer to a constant, a function, an
+/// intrinsic or a foreign function we print those directly.
+fn value(function: &Function, field_display: Option<FieldDisplay>, id: ValueId) -> String {
     use super::value::Value;
     let id = function.dfg.resolve(id);
     match &function.dfg[id] {
-        Value::NumericConstant { constant, typ } => {
-            format!("{typ} {constant}")
-        }
+        Value::NumericConstant { constant, typ } => match field_display {
+            Some(display) => format!("{typ} {}", display.format(*constant)),
+            None => format!("{typ} {constant}"),
+        },
         Value::Function(id) => id.to_string(),
         Value::Intrinsic(intrinsic) => intrinsic.to_string(),
-        Value::Array { array, .. } => {
-            let elements = vecmap(array, |element| value(function, *element));
-            format!("[{}]", elements.join(", "))
-        }
-        Value::Param { .. } | Value::Instruction { .. } | Value::ForeignFunction(_) => {
-            id.to_string()
+        Value::ForeignFunction(name) => name.clone(),
+        Value::Array { array, typ } => {
+            let elements = vecmap(array, |element| value(function, field_display, *element));
+            format!("{typ} [{}]", elements.join(", "))
         }
+        Value::Param { .. } | Value::Instruction { .. } => id.to_string(),
     }
 }
 
 /// Display each value along with its type. E.g. `v0: Field, v1: u64, v2: u1`
-fn value_list_with_types(function: &Function, values: &[ValueId]) -> String {
+fn value_list_with_types(
+    function: &Function,
+    field_display: Option<FieldDisplay>,
+    values: &[ValueId],
+) -> String {
     vecmap(values, |id| {
-        let value = value(function, *id);
+        let value = value(function, field_display, *id);
         let typ = function.dfg.type_of_value(*id);
         format!("{value}: {typ}")
     })
@@ -89,31 +107,51 @@ fn value_list_with_types(function: &Function, values: &[ValueId]) -> String {
 }
 
 /// Display each value separated by a comma
-fn value_list(function: &Function, values: &[ValueId]) -> String {
-    vecmap(values, |id| value(function, *id)).join(", ")
+fn value_list(
+    function: &Function,
+    field_display: Option<FieldDisplay>,
+    values: &[ValueId],
+) -> String {
+    vecmap(values, |id| value(function, field_display, *id)).join(", ")
+}
+
+/// Display the types of an instruction's results as ` -> Field, u32`, for the instructions whose
+/// result types can't be inferred from their arguments. Nothing is displayed without results.
+fn result_types(function: &Function, results: &[ValueId]) -> String {
+    if results.is_empty() {
+        return String::new();
+    }
+    let types = vecmap(results, |result| function.dfg.type_of_value(*result).to_string());
+    format!(" -> {}", types.join(", "))
 }
 
 /// Display a terminator instruction
 pub(crate) fn display_terminator(
     function: &Function,
+    field_display: Option<FieldDisplay>,
     terminator: Option<&TerminatorInstruction>,
     f: &mut Formatter,
 ) -> Result {
     match terminator {
         Some(TerminatorInstruction::Jmp { destination, arguments, call_stack: _ }) => {
-            writeln!(f, "    jmp {}({})", destination, value_list(function, arguments))
+            writeln!(
+                f,
+                "    jmp {}({})",
+                destination,
+                value_list(function, field_display, arguments)
+            )
         }
         Some(TerminatorInstruction::JmpIf { condition, then_destination, else_destination }) => {
             writeln!(
                 f,
                 "    jmpif {} then: {}, else: {}",
-                value(function, *condition),
+                value(function, field_display, *condition),
                 then_destination,
                 else_destination
             )
         }
         Some(TerminatorInstruction::Return { return_values, .. }) => {
-            writeln!(f, "    return {}", value_list(function, return_values))
+            writeln!(f, "    return {}", value_list(function, field_display, return_values))
         }
         None => writeln!(f, "    (no terminator instruction)"),
     }
@@ -122,6 +160,7 @@ pub(crate) fn display_terminator(
 /// Display an arbitrary instruction
 pub(crate) fn display_instruction(
     function: &Function,
+    field_display: Option<FieldDisplay>,
     instruction: InstructionId,
     f: &mut Formatter,
 ) -> Result {
@@ -130,10 +169,10 @@ pub(crate) fn display_instruction(
 
     let results = function.dfg.instruction_results(instruction);
     if !results.is_empty() {
-        write!(f, "{} = ", value_list(function, results))?;
+        write!(f, "{} = ", value_list(function, field_display, results))?;
     }
 
-    let show = |id| value(function, id);
+    let show = |id| value(function, field_display, id);
 
     match &function.dfg[instruction] {
         Instruction::Binary(binary) => {
@@ -150,10 +189,13 @@ pub(crate) fn display_instruction(
             None => writeln!(f, "constrain {} == {}", show(*lhs), show(*rhs)),
         },
         Instruction::Call { func, arguments } => {
-            writeln!(f, "call {}({})", show(*func), value_list(function, arguments))
+            write!(f, "call {}({})", show(*func), value_list(function, field_display, arguments))?;
+            writeln!(f, "{}", result_types(function, results))
         }
         Instruction::Allocate => writeln!(f, "allocate"),
-        Instruction::Load { address } => writeln!(f, "load {}", show(*address)),
+        Instruction::Load { address } => {
+            writeln!(f, "load {}{}", show(*address), result_types(function, results))
+        }
         Instruction::Store { address, value } => {
             writeln!(f, "store {} at {}", show(*value), show(*address))
         }
@@ -161,7 +203,8 @@ pub(crate) fn display_instruction(
             writeln!(f, "enable_side_effects {}", show(*condition))
         }
         Instruction::ArrayGet { array, index } => {
-            writeln!(f, "array_get {}, index {}", show(*array), show(*index))
+            let result_types = result_types(function, results);
+            writeln!(f, "array_get {}, index {}{result_types}", show(*array), show(*index))
         }
         Instruction::ArraySet { array, index, value } => {
             writeln!(
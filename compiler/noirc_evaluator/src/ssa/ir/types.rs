@@ -67,6 +67,17 @@ impl Type {
         Type::Numeric(NumericType::NativeField)
     }
 
+    /// Returns whether values of this type are references or arrays holding references.
+    pub(crate) fn contains_reference(&self) -> bool {
+        match self {
+            Type::Numeric(_) | Type::Function => false,
+            Type::Reference => true,
+            Type::Array(elements, _) | Type::Slice(elements) => {
+                elements.iter().any(Type::contains_reference)
+            }
+        }
+    }
+
     /// Returns the size of the element type for this array/slice.
     /// The size of a type is defined as representing how many Fields are needed
     /// to represent the type. This is 1 for every primitive type, and is the number of fields
@@ -0,0 +1,196 @@
+//! Metrics describing the size of a program's SSA, such as how many instructions of each kind
+//! every function contains. These are recorded on the initial SSA and after each optimization
+//! pass, so the effect of a pass can be measured by comparing against the metrics before it.
+use std::{collections::BTreeMap, fmt::Display};
+
+use super::{
+    ir::{function::Function, instruction::Instruction},
+    ssa_gen::Ssa,
+};
+
+/// Instruction counts for a single SSA function.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FunctionMetrics {
+    /// The id of the function as shown when printing the SSA, e.g. `f0`
+    pub id: String,
+    pub name: String,
+    /// The number of blocks reachable from the function's entry block
+    pub blocks: usize,
+    /// The number of instructions of each kind, keyed by the name the SSA printer uses for them.
+    /// Binary instructions are counted by their operator, e.g. `add` or `lt`.
+    pub instructions: BTreeMap<String, usize>,
+    /// The number of `allocate`, `load` and `store` instructions
+    pub memory_ops: usize,
+    /// The number of `call` instructions, including calls to intrinsics and foreign functions
+    pub calls: usize,
+}
+
+impl FunctionMetrics {
+    fn new(function: &Function) -> FunctionMetrics {
+        let mut metrics = FunctionMetrics {
+            id: function.id().to_string(),
+            name: function.name().to_owned(),
+            ..FunctionMetrics::default()
+        };
+
+        for block_id in function.reachable_blocks() {
+            metrics.blocks += 1;
+
+            for instruction_id in function.dfg[block_id].instructions() {
+                let instruction = &function.dfg[*instruction_id];
+                match instruction {
+                    Instruction::Allocate
+                    | Instruction::Load { .. }
+                    | Instruction::Store { .. } => metrics.memory_ops += 1,
+                    Instruction::Call { .. } => metrics.calls += 1,
+                    _ => (),
+                }
+                *metrics.instructions.entry(instruction_kind(instruction)).or_default() += 1;
+            }
+        }
+        metrics
+    }
+
+    pub fn total_instructions(&self) -> usize {
+        self.instructions.values().sum()
+    }
+}
+
+/// Instruction counts for every function of a program's SSA, ordered by function id.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SsaMetrics {
+    pub functions: Vec<FunctionMetrics>,
+}
+
+impl SsaMetrics {
+    pub fn total_instructions(&self) -> usize {
+        self.functions.iter().map(FunctionMetrics::total_instructions).sum()
+    }
+
+    pub fn memory_ops(&self) -> usize {
+        self.functions.iter().map(|function| function.memory_ops).sum()
+    }
+
+    pub fn calls(&self) -> usize {
+        self.functions.iter().map(|function| function.calls).sum()
+    }
+}
+
+/// The metrics of a program's SSA after running the pass named `pass`.
+/// The first entry of a pipeline's metrics describes the initial SSA.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PassMetrics {
+    pub pass: String,
+    pub metrics: SsaMetrics,
+}
+
+impl Ssa {
+    /// Counts the instructions in each function of this SSA.
+    pub(crate) fn metrics(&self) -> SsaMetrics {
+        SsaMetrics { functions: self.functions.values().map(FunctionMetrics::new).collect() }
+    }
+}
+
+fn instruction_kind(instruction: &Instruction) -> String {
+    let kind = match instruction {
+        Instruction::Binary(binary) => return binary.operator.to_string(),
+        Instruction::Cast(..) => "cast",
+        Instruction::Not(_) => "not",
+        Instruction::Truncate { .. } => "truncate",
+        Instruction::Constrain(..) => "constrain",
+        Instruction::Call { .. } => "call",
+        Instruction::Allocate => "allocate",
+        Instruction::Load { .. } => "load",
+        Instruction::Store { .. } => "store",
+        Instruction::EnableSideEffects { .. } => "enable_side_effects",
+        Instruction::ArrayGet { .. } => "array_get",
+        Instruction::ArraySet { .. } => "array_set",
+    };
+    kind.to_owned()
+}
+
+impl Display for FunctionMetrics {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} {}: {} instructions in {} blocks, {} memory ops, {} calls",
+            self.id,
+            self.name,
+            self.total_instructions(),
+            self.blocks,
+            self.memory_ops,
+            self.calls
+        )?;
+
+        let counts = self.instructions.iter().map(|(kind, count)| format!("{kind}: {count}"));
+        write!(f, " ({})", counts.collect::<Vec<_>>().join(", "))
+    }
+}
+
+impl Display for SsaMetrics {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for function in &self.functions {
+            writeln!(f, "  {function}")?;
+        }
+        writeln!(
+            f,
+            "  total: {} instructions, {} memory ops, {} calls",
+            self.total_instructions(),
+            self.memory_ops(),
+            self.calls()
+        )
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::ssa::{
+        function_builder::FunctionBuilder,
+        ir::{
+            function::RuntimeType,
+            instruction::{BinaryOp, Intrinsic},
+            map::Id,
+            types::Type,
+        },
+    };
+
+    #[test]
+    fn counts_instructions_by_kind() {
+        // acir fn main f0 {
+        //   b0(v0: Field):
+        //     v1 = allocate
+        //     store v0 at v1
+        //     v2 = load v1
+        //     v3 = add v2, Field 1
+        //     v4 = add v3, Field 1
+        //     call assert_constant(v4)
+        //     return v4
+        // }
+        let main_id = Id::test_new(0);
+        let mut builder = FunctionBuilder::new("main".into(), main_id, RuntimeType::Acir);
+        let v0 = builder.add_parameter(Type::field());
+        let v1 = builder.insert_allocate();
+        builder.insert_store(v1, v0);
+        let v2 = builder.insert_load(v1, Type::field());
+        let one = builder.field_constant(1u128);
+        let v3 = builder.insert_binary(v2, BinaryOp::Add, one);
+        let v4 = builder.insert_binary(v3, BinaryOp::Add, one);
+        let assert_constant = builder.import_intrinsic_id(Intrinsic::AssertConstant);
+        builder.insert_call(assert_constant, vec![v4], vec![]);
+        builder.terminate_with_return(vec![v4]);
+
+        let metrics = builder.finish().metrics();
+        assert_eq!(metrics.functions.len(), 1);
+
+        let main = &metrics.functions[0];
+        assert_eq!(main.id, "f0");
+        assert_eq!(main.name, "main");
+        assert_eq!(main.blocks, 1);
+        assert_eq!(main.total_instructions(), 6);
+        assert_eq!(main.memory_ops, 3);
+        assert_eq!(main.calls, 1);
+        assert_eq!(main.instructions["add"], 2);
+        assert_eq!(main.instructions["load"], 1);
+        assert_eq!(metrics.total_instructions(), 6);
+    }
+}
@@ -0,0 +1,331 @@
+//! This pass rewrites arithmetic into a canonical form, so that equivalent expressions are
+//! recognized by [common subexpression elimination][super::cse] and chains of operations on
+//! constants are folded into a single instruction before ACIR generation.
+//!
+//! It complements the simplifications applied whenever an instruction is inserted
+//! (see [`Instruction::simplify`][crate::ssa::ir::instruction::Instruction::simplify]), such as
+//! `x + 0 => x` or `x * 1 => x`, which only look at the instruction itself. The rewrites here
+//! also look at the instructions defining its operands:
+//! - constants are moved to the right hand side of commutative operations, e.g.
+//!   `add Field 2, v0 => add v0, Field 2`,
+//! - `sub v0, v0 => 0`,
+//! - subtracting a constant from a field is turned into an addition, e.g.
+//!   `sub v0, Field 1 => add v0, Field -1`, so that it can be folded with other additions,
+//! - additions and multiplications of fields by constants are folded together, e.g.
+//!   `v1 = add v0, Field 2; v2 = add v1, Field 3` turns `v2` into `add v0, Field 5`,
+//! - double negations of fields are removed, e.g. `v1 = sub Field 0, v0; v2 = sub Field 0, v1`
+//!   turns `v2` into `v0`.
+//!
+//! Constants are only folded for fields as integer arithmetic is truncated between operations.
+//! Instructions left unused by the rewrites are removed by dead instruction elimination.
+use acvm::FieldElement;
+
+use crate::ssa::{
+    ir::{
+        basic_block::BasicBlockId,
+        dfg::DataFlowGraph,
+        function::Function,
+        instruction::{Binary, BinaryOp, Instruction, InstructionId},
+        post_order::PostOrder,
+        types::Type,
+        value::{Value, ValueId},
+    },
+    ssa_gen::Ssa,
+};
+
+impl Ssa {
+    /// Rewrites the arithmetic of each function into a canonical form.
+    ///
+    /// See [`algebraic_simplification`][self] module for more information.
+    pub(crate) fn simplify_arithmetic(mut self) -> Ssa {
+        for function in self.functions.values_mut() {
+            simplify_function(function);
+        }
+        self
+    }
+}
+
+fn simplify_function(function: &mut Function) {
+    // Iterating in reverse post order visits each instruction after the instructions defining
+    // its arguments, so that chains of operations are folded from their start.
+    let post_order = PostOrder::with_function(function);
+    for block in post_order.as_slice().iter().rev() {
+        let instructions = function.dfg[*block].take_instructions();
+        for instruction in instructions {
+            simplify_instruction(&mut function.dfg, *block, instruction);
+        }
+    }
+}
+
+/// The canonical form of a binary instruction.
+enum Rewrite {
+    /// The instruction always evaluates to this value.
+    Value(ValueId),
+    /// The instruction is equivalent to this binary instruction.
+    Binary(Binary),
+}
+
+/// Pushes `instruction_id` back into `block`, or the canonical form of the instruction if it has
+/// a different one.
+fn simplify_instruction(
+    dfg: &mut DataFlowGraph,
+    block: BasicBlockId,
+    instruction_id: InstructionId,
+) {
+    let Instruction::Binary(binary) = &dfg[instruction_id] else {
+        dfg[block].insert_instruction(instruction_id);
+        return;
+    };
+    let binary = Binary {
+        lhs: dfg.resolve(binary.lhs),
+        rhs: dfg.resolve(binary.rhs),
+        operator: binary.operator,
+    };
+
+    let new_result = match canonicalize(binary, dfg) {
+        Some(Rewrite::Value(value)) => value,
+        Some(Rewrite::Binary(binary)) => {
+            let call_stack = dfg.get_call_stack(instruction_id);
+            let instruction = Instruction::Binary(binary);
+            dfg.insert_instruction_and_results(instruction, block, None, call_stack).first()
+        }
+        None => {
+            dfg[block].insert_instruction(instruction_id);
+            return;
+        }
+    };
+    let old_result = dfg.instruction_results(instruction_id)[0];
+    dfg.set_value_from_id(old_result, new_result);
+}
+
+/// Returns the canonical form of `binary`, or `None` if it is already canonical.
+fn canonicalize(mut binary: Binary, dfg: &mut DataFlowGraph) -> Option<Rewrite> {
+    let mut changed = false;
+
+    let is_commutative = matches!(
+        binary.operator,
+        BinaryOp::Add | BinaryOp::Mul | BinaryOp::Eq | BinaryOp::And | BinaryOp::Or | BinaryOp::Xor
+    );
+    let is_constant = |value| dfg.get_numeric_constant(value).is_some();
+    if is_commutative && is_constant(binary.lhs) && !is_constant(binary.rhs) {
+        std::mem::swap(&mut binary.lhs, &mut binary.rhs);
+        changed = true;
+    }
+
+    let operand_type = dfg.type_of_value(binary.lhs);
+    if binary.operator == BinaryOp::Sub && binary.lhs == binary.rhs {
+        let zero = dfg.make_constant(FieldElement::zero(), operand_type);
+        return Some(Rewrite::Value(zero));
+    }
+    if operand_type != Type::field() {
+        return changed.then_some(Rewrite::Binary(binary));
+    }
+
+    let rhs_constant = dfg.get_numeric_constant(binary.rhs);
+    match binary.operator {
+        BinaryOp::Sub => {
+            // -(-v0) => v0
+            let lhs_is_zero = dfg.get_numeric_constant(binary.lhs).map_or(false, |c| c.is_zero());
+            if let Some(negated) = as_negation(binary.rhs, dfg).filter(|_| lhs_is_zero) {
+                return Some(Rewrite::Value(negated));
+            }
+            // v0 - c => v0 + (-c)
+            if let Some(constant) = rhs_constant {
+                let rhs = dfg.make_constant(-constant, Type::field());
+                binary = Binary { lhs: binary.lhs, rhs, operator: BinaryOp::Add };
+                changed = true;
+            }
+        }
+        BinaryOp::Add | BinaryOp::Mul => (),
+        _ => return changed.then_some(Rewrite::Binary(binary)),
+    }
+
+    // (v0 + c1) + c2 => v0 + (c1 + c2) and (v0 * c1) * c2 => v0 * (c1 * c2)
+    let rhs_constant = dfg.get_numeric_constant(binary.rhs);
+    let inner = as_binary(binary.lhs, dfg).filter(|inner| inner.operator == binary.operator);
+    if let (Some(outer), Some(inner)) = (rhs_constant, inner) {
+        if let Some(inner_constant) = dfg.get_numeric_constant(inner.rhs) {
+            let folded = match binary.operator {
+                BinaryOp::Add => inner_constant + outer,
+                _ => inner_constant * outer,
+            };
+            let rhs = dfg.make_constant(folded, Type::field());
+            binary = Binary { lhs: dfg.resolve(inner.lhs), rhs, operator: binary.operator };
+            changed = true;
+        }
+    }
+
+    changed.then_some(Rewrite::Binary(binary))
+}
+
+/// Returns the binary instruction defining `value`, if any.
+fn as_binary(value: ValueId, dfg: &DataFlowGraph) -> Option<Binary> {
+    match &dfg[value] {
+        Value::Instruction { instruction, .. } => match &dfg[*instruction] {
+            Instruction::Binary(binary) => Some(binary.clone()),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// Returns `v0` if `value` is defined by `sub Field 0, v0`.
+fn as_negation(value: ValueId, dfg: &DataFlowGraph) -> Option<ValueId> {
+    let binary = as_binary(value, dfg)?;
+    let lhs_is_zero = dfg.get_numeric_constant(binary.lhs).map_or(false, |c| c.is_zero());
+    (binary.operator == BinaryOp::Sub && lhs_is_zero).then(|| dfg.resolve(binary.rhs))
+}
+
+#[cfg(test)]
+mod test {
+    use acvm::FieldElement;
+
+    use crate::ssa::{
+        function_builder::FunctionBuilder,
+        ir::{
+            function::RuntimeType,
+            instruction::{Binary, BinaryOp, Instruction, TerminatorInstruction},
+            map::Id,
+            types::Type,
+            value::{Value, ValueId},
+        },
+        ssa_gen::Ssa,
+    };
+
+    /// Returns the values returned by main once resolved.
+    fn main_return_values(ssa: &Ssa) -> Vec<ValueId> {
+        let main = ssa.main();
+        match main.dfg[main.entry_block()].terminator() {
+            Some(TerminatorInstruction::Return { return_values, .. }) => {
+                return_values.iter().map(|value| main.dfg.resolve(*value)).collect()
+            }
+            _ => unreachable!("Should have terminator instruction"),
+        }
+    }
+
+    /// Returns the operator, lhs and constant rhs of the binary instruction defining `value`.
+    fn as_binary_with_constant(ssa: &Ssa, value: ValueId) -> (BinaryOp, ValueId, FieldElement) {
+        let dfg = &ssa.main().dfg;
+        let Value::Instruction { instruction, .. } = &dfg[value] else {
+            panic!("Expected {value:?} to be the result of an instruction");
+        };
+        let Instruction::Binary(Binary { lhs, rhs, operator }) = &dfg[*instruction] else {
+            panic!("Expected {value:?} to be the result of a binary instruction");
+        };
+        let constant = dfg.get_numeric_constant(*rhs).expect("Expected a constant rhs");
+        (*operator, dfg.resolve(*lhs), constant)
+    }
+
+    #[test]
+    fn folds_chains_of_additions_and_multiplications() {
+        // fn main f0 {
+        //   b0(v0: Field):
+        //     v1 = add Field 2, v0
+        //     v2 = add v1, Field 3
+        //     v3 = sub v2, Field 1
+        //     v4 = mul Field 3, v0
+        //     v5 = mul v4, Field 5
+        //     return v3, v5
+        // }
+        let main_id = Id::test_new(0);
+        let mut builder = FunctionBuilder::new("main".into(), main_id, RuntimeType::Acir);
+        let v0 = builder.add_parameter(Type::field());
+
+        let one = builder.numeric_constant(1u128, Type::field());
+        let two = builder.numeric_constant(2u128, Type::field());
+        let three = builder.numeric_constant(3u128, Type::field());
+        let five = builder.numeric_constant(5u128, Type::field());
+        let v1 = builder.insert_binary(two, BinaryOp::Add, v0);
+        let v2 = builder.insert_binary(v1, BinaryOp::Add, three);
+        let v3 = builder.insert_binary(v2, BinaryOp::Sub, one);
+        let v4 = builder.insert_binary(three, BinaryOp::Mul, v0);
+        let v5 = builder.insert_binary(v4, BinaryOp::Mul, five);
+        builder.terminate_with_return(vec![v3, v5]);
+
+        // Expected output:
+        //
+        // fn main f0 {
+        //   b0(v0: Field):
+        //     v6 = add v0, Field 2
+        //     v7 = add v0, Field 5
+        //     v8 = add v0, Field 4
+        //     v9 = mul v0, Field 3
+        //     v10 = mul v0, Field 15
+        //     return v8, v10
+        // }
+        let ssa = builder.finish().simplify_arithmetic();
+        let returned = main_return_values(&ssa);
+
+        let sum = as_binary_with_constant(&ssa, returned[0]);
+        assert_eq!(sum, (BinaryOp::Add, v0, FieldElement::from(4u128)));
+        let product = as_binary_with_constant(&ssa, returned[1]);
+        assert_eq!(product, (BinaryOp::Mul, v0, FieldElement::from(15u128)));
+    }
+
+    #[test]
+    fn removes_self_subtractions_and_double_negations() {
+        // fn main f0 {
+        //   b0(v0: Field, v1: u32):
+        //     v2 = sub v0, v0
+        //     v3 = sub v1, v1
+        //     v4 = sub Field 0, v0
+        //     v5 = sub Field 0, v4
+        //     return v2, v3, v5
+        // }
+        let main_id = Id::test_new(0);
+        let mut builder = FunctionBuilder::new("main".into(), main_id, RuntimeType::Acir);
+        let v0 = builder.add_parameter(Type::field());
+        let v1 = builder.add_parameter(Type::unsigned(32));
+
+        let zero = builder.numeric_constant(0u128, Type::field());
+        let v2 = builder.insert_binary(v0, BinaryOp::Sub, v0);
+        let v3 = builder.insert_binary(v1, BinaryOp::Sub, v1);
+        let v4 = builder.insert_binary(zero, BinaryOp::Sub, v0);
+        let v5 = builder.insert_binary(zero, BinaryOp::Sub, v4);
+        builder.terminate_with_return(vec![v2, v3, v5]);
+
+        let ssa = builder.finish().simplify_arithmetic();
+        let returned = main_return_values(&ssa);
+        let dfg = &ssa.main().dfg;
+
+        assert_eq!(
+            dfg.get_numeric_constant_with_type(returned[0]),
+            Some((0u128.into(), Type::field()))
+        );
+        assert_eq!(
+            dfg.get_numeric_constant_with_type(returned[1]),
+            Some((0u128.into(), Type::unsigned(32)))
+        );
+        assert_eq!(returned[2], v0);
+    }
+
+    #[test]
+    fn does_not_fold_integer_constants() {
+        // fn main f0 {
+        //   b0(v0: u8):
+        //     v1 = add u8 2, v0
+        //     v2 = truncate v1 to 8 bits, max_bit_size: 9
+        //     v3 = add v2, u8 3
+        //     return v3
+        // }
+        let main_id = Id::test_new(0);
+        let mut builder = FunctionBuilder::new("main".into(), main_id, RuntimeType::Acir);
+        let v0 = builder.add_parameter(Type::unsigned(8));
+
+        let two = builder.numeric_constant(2u128, Type::unsigned(8));
+        let three = builder.numeric_constant(3u128, Type::unsigned(8));
+        let v1 = builder.insert_binary(two, BinaryOp::Add, v0);
+        let v2 = builder.insert_truncate(v1, 8, 9);
+        let v3 = builder.insert_binary(v2, BinaryOp::Add, three);
+        builder.terminate_with_return(vec![v3]);
+
+        let ssa = builder.finish().simplify_arithmetic();
+        let returned = main_return_values(&ssa);
+
+        // Only the operands of the first addition are swapped
+        assert_eq!(returned[0], v3);
+        let main = ssa.main();
+        assert_eq!(main.dfg[main.entry_block()].instructions().len(), 3);
+        assert_eq!(as_binary_with_constant(&ssa, main.dfg.resolve(v1)).2, 2u128.into());
+    }
+}
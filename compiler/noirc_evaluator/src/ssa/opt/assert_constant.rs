@@ -40,6 +40,35 @@ impl Ssa {
         }
         Ok(self)
     }
+
+    /// Evaluates each call to `static_assert`, issuing an error if its predicate is false or is
+    /// not a constant, and removing it otherwise.
+    ///
+    /// This pass must be placed after loop unrolling so that assertions involving loop indices
+    /// can be evaluated.
+    pub(crate) fn evaluate_static_assert(mut self) -> Result<Ssa, RuntimeError> {
+        for function in self.functions.values_mut() {
+            let static_assert_id = function.dfg.get_intrinsic(Intrinsic::StaticAssert).copied();
+            let Some(static_assert_id) = static_assert_id else { continue };
+
+            for block in function.reachable_blocks() {
+                let instructions = function.dfg[block].take_instructions();
+                let mut filtered_instructions = Vec::with_capacity(instructions.len());
+
+                for instruction in instructions {
+                    match &function.dfg[instruction] {
+                        Instruction::Call { func, arguments } if *func == static_assert_id => {
+                            evaluate_static_assert(function, instruction, arguments)?;
+                        }
+                        _ => filtered_instructions.push(instruction),
+                    }
+                }
+
+                *function.dfg[block].instructions_mut() = filtered_instructions;
+            }
+        }
+        Ok(self)
+    }
 }
 
 /// During the loop unrolling pass we also evaluate calls to `assert_constant`.
@@ -81,3 +110,23 @@ fn evaluate_assert_constant(
         Err(RuntimeError::AssertConstantFailed { call_stack })
     }
 }
+
+/// Evaluate a call to `static_assert`, returning an error if its predicate is not the constant
+/// `true`.
+fn evaluate_static_assert(
+    function: &Function,
+    instruction: InstructionId,
+    arguments: &[ValueId],
+) -> Result<(), RuntimeError> {
+    let call_stack = function.dfg.get_call_stack(instruction);
+    let (predicate, message) = (arguments[0], arguments[1]);
+
+    match function.dfg.get_numeric_constant(predicate) {
+        Some(predicate) if predicate.is_one() => Ok(()),
+        Some(_) => {
+            let message = function.dfg.get_string_constant(message).unwrap_or_default();
+            Err(RuntimeError::StaticAssertFailed { message, call_stack })
+        }
+        None => Err(RuntimeError::StaticAssertDynamicPredicate { call_stack }),
+    }
+}
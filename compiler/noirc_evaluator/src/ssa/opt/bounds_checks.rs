@@ -0,0 +1,301 @@
+//! This pass removes bounds checks which are implied by an assertion of a dominating block, such
+//! as the check of a slice index following an explicit `assert(index < slice.len())`.
+//!
+//! A bounds check is any constraint of the form `constrain (lt value, bound) == u1 1`. Each block
+//! is visited after every block dominating it, recording the upper bounds asserted for each
+//! value. Since a dominating block is always executed before the blocks it dominates, a check is
+//! redundant if an earlier check of its block or a check of one of its dominators asserted that
+//! the same value is below the same bound, or below a smaller constant.
+//!
+//! Constraints within conditional code are multiplied by their condition when the CFG is
+//! flattened, so they no longer have this form and are neither recorded nor removed.
+use std::collections::HashSet;
+
+use crate::ssa::{
+    ir::{
+        basic_block::BasicBlockId,
+        cfg::ControlFlowGraph,
+        dfg::DataFlowGraph,
+        dom::DominatorTree,
+        function::Function,
+        instruction::{Binary, BinaryOp, Instruction},
+        post_order::PostOrder,
+        types::{NumericType, Type},
+        value::{Value, ValueId},
+    },
+    ssa_gen::Ssa,
+};
+use fxhash::FxHashMap as HashMap;
+
+impl Ssa {
+    /// Removes each bounds check which is implied by an earlier assertion.
+    ///
+    /// See [`bounds_checks`][self] module for more information.
+    pub(crate) fn remove_redundant_bounds_checks(mut self) -> Ssa {
+        for function in self.functions.values_mut() {
+            remove_redundant_bounds_checks(function);
+        }
+        self
+    }
+}
+
+fn remove_redundant_bounds_checks(function: &mut Function) {
+    let cfg = ControlFlowGraph::with_function(function);
+    let post_order = PostOrder::with_function(function);
+    let dom_tree = DominatorTree::with_cfg_and_post_order(&cfg, &post_order);
+
+    let mut context = Context::default();
+
+    // Iterating in reverse post order visits each block after every block dominating it
+    for block in post_order.as_slice().iter().rev() {
+        context.remove_checks_in_block(function, &dom_tree, *block);
+    }
+}
+
+#[derive(Default)]
+struct Context {
+    /// The upper bounds asserted for each value by the checks of each visited block.
+    asserted_bounds: HashMap<BasicBlockId, HashMap<ValueId, Vec<ValueId>>>,
+}
+
+impl Context {
+    /// Removes each bounds check of the given block which is implied by an earlier check of the
+    /// block or by a check of one of its dominators.
+    fn remove_checks_in_block(
+        &mut self,
+        function: &mut Function,
+        dom_tree: &DominatorTree,
+        block: BasicBlockId,
+    ) {
+        let dfg = &function.dfg;
+        let mut block_bounds: HashMap<ValueId, Vec<ValueId>> = HashMap::default();
+        let mut checks_to_remove = HashSet::new();
+
+        for instruction_id in dfg[block].instructions() {
+            let Some((value, bound)) = as_bounds_check(&dfg[*instruction_id], dfg) else {
+                continue;
+            };
+
+            let mut asserted_bounds = block_bounds
+                .get(&value)
+                .into_iter()
+                .chain(self.dominator_bounds(dom_tree, block, value))
+                .flatten();
+
+            if asserted_bounds.any(|asserted| implies_bound(*asserted, bound, value, dfg)) {
+                checks_to_remove.insert(*instruction_id);
+            } else {
+                let bounds = block_bounds.entry(value).or_default();
+                bounds.push(bound);
+                bounds.extend(original_bound(bound, dfg));
+            }
+        }
+
+        function.dfg[block]
+            .instructions_mut()
+            .retain(|instruction| !checks_to_remove.contains(instruction));
+
+        self.asserted_bounds.insert(block, block_bounds);
+    }
+
+    /// Returns the upper bounds of `value` asserted by each dominator of `block`.
+    fn dominator_bounds<'a>(
+        &'a self,
+        dom_tree: &'a DominatorTree,
+        block: BasicBlockId,
+        value: ValueId,
+    ) -> impl Iterator<Item = &'a Vec<ValueId>> {
+        std::iter::successors(dom_tree.immediate_dominator(block), |dominator| {
+            dom_tree.immediate_dominator(*dominator)
+        })
+        .filter_map(move |dominator| {
+            self.asserted_bounds.get(&dominator).and_then(|bounds| bounds.get(&value))
+        })
+    }
+}
+
+/// Returns the value and its upper bound if `instruction` is a bounds check.
+fn as_bounds_check(instruction: &Instruction, dfg: &DataFlowGraph) -> Option<(ValueId, ValueId)> {
+    let Instruction::Constrain(lhs, rhs, _) = instruction else {
+        return None;
+    };
+    let comparison = if is_true(*rhs, dfg) {
+        *lhs
+    } else if is_true(*lhs, dfg) {
+        *rhs
+    } else {
+        return None;
+    };
+
+    match &dfg[dfg.resolve(comparison)] {
+        Value::Instruction { instruction, .. } => match &dfg[*instruction] {
+            Instruction::Binary(Binary { lhs, rhs, operator: BinaryOp::Lt }) => {
+                Some((dfg.resolve(*lhs), dfg.resolve(*rhs)))
+            }
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+fn is_true(value: ValueId, dfg: &DataFlowGraph) -> bool {
+    dfg.get_numeric_constant_with_type(value)
+        .map_or(false, |(constant, typ)| typ == Type::bool() && constant.is_one())
+}
+
+/// Returns true if asserting that `value` is below `asserted` implies it is below `bound`.
+fn implies_bound(asserted: ValueId, bound: ValueId, value: ValueId, dfg: &DataFlowGraph) -> bool {
+    if asserted == bound {
+        return true;
+    }
+    // Negative constants of signed types are larger than positive ones as field elements
+    if !dfg.type_of_value(value).is_unsigned() {
+        return false;
+    }
+    let asserted = dfg.get_numeric_constant(asserted).and_then(|constant| constant.try_to_u64());
+    let bound = dfg.get_numeric_constant(bound).and_then(|constant| constant.try_to_u64());
+    matches!((asserted, bound), (Some(asserted), Some(bound)) if asserted <= bound)
+}
+
+/// Returns the value `bound` was truncated from if it is a cast to an unsigned type, such as the
+/// cast of a slice length in `assert(index < slice.len() as u64)`. A truncated bound is never
+/// larger than the original one, so a value below it is also below the original bound.
+fn original_bound(bound: ValueId, dfg: &DataFlowGraph) -> Option<ValueId> {
+    let Value::Instruction { instruction, .. } = &dfg[bound] else {
+        return None;
+    };
+    match &dfg[*instruction] {
+        Instruction::Cast(original, typ) if typ.is_unsigned() => {
+            let original = dfg.resolve(*original);
+            let original_type = dfg.type_of_value(original);
+            let is_truncated = original_type.is_unsigned()
+                || matches!(original_type, Type::Numeric(NumericType::NativeField));
+            is_truncated.then_some(original)
+        }
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::ssa::{
+        function_builder::FunctionBuilder,
+        ir::{
+            basic_block::BasicBlockId,
+            function::RuntimeType,
+            instruction::{BinaryOp, Instruction},
+            map::Id,
+            types::Type,
+        },
+    };
+
+    #[test]
+    fn removes_checks_implied_by_dominating_checks() {
+        // fn main f0 {
+        //   b0(v0: u64, v1: u64, v2: u1):
+        //     v3 = lt v0, v1
+        //     constrain v3 == u1 1
+        //     v4 = lt v0, u64 4
+        //     constrain v4 == u1 1
+        //     jmpif v2 then: b1, else: b2
+        //   b1():
+        //     v5 = lt v0, v1
+        //     constrain v5 == u1 1
+        //     v6 = lt v0, u64 8
+        //     constrain v6 == u1 1
+        //     v7 = lt v0, u64 2
+        //     constrain v7 == u1 1
+        //     v8 = lt v0, u64 2
+        //     constrain v8 == u1 1
+        //     return
+        //   b2():
+        //     return
+        // }
+        let main_id = Id::test_new(0);
+        let mut builder = FunctionBuilder::new("main".into(), main_id, RuntimeType::Brillig);
+        let v0 = builder.add_parameter(Type::unsigned(64));
+        let v1 = builder.add_parameter(Type::unsigned(64));
+        let v2 = builder.add_parameter(Type::bool());
+        let b1 = builder.insert_block();
+        let b2 = builder.insert_block();
+
+        let true_const = builder.numeric_constant(true, Type::bool());
+        let insert_check = |builder: &mut FunctionBuilder, bound| {
+            let is_in_bounds = builder.insert_binary(v0, BinaryOp::Lt, bound);
+            builder.insert_constrain(is_in_bounds, true_const, None);
+        };
+
+        let four = builder.numeric_constant(4u128, Type::unsigned(64));
+        insert_check(&mut builder, v1);
+        insert_check(&mut builder, four);
+        builder.terminate_with_jmpif(v2, b1, b2);
+
+        builder.switch_to_block(b1);
+        let eight = builder.numeric_constant(8u128, Type::unsigned(64));
+        let two = builder.numeric_constant(2u128, Type::unsigned(64));
+        insert_check(&mut builder, v1);
+        insert_check(&mut builder, eight);
+        insert_check(&mut builder, two);
+        insert_check(&mut builder, two);
+        builder.terminate_with_return(vec![]);
+
+        builder.switch_to_block(b2);
+        builder.terminate_with_return(vec![]);
+
+        let ssa = builder.finish().remove_redundant_bounds_checks();
+        let main = ssa.main();
+
+        let count_constraints = |block: BasicBlockId| {
+            main.dfg[block]
+                .instructions()
+                .iter()
+                .filter(|instruction| matches!(main.dfg[**instruction], Instruction::Constrain(..)))
+                .count()
+        };
+        assert_eq!(count_constraints(main.entry_block()), 2);
+        // Only the first check that `v0 < 2` remains, since 2 is below every earlier bound
+        assert_eq!(count_constraints(b1), 1);
+    }
+
+    #[test]
+    fn truncated_bounds_imply_their_original_bound() {
+        // fn main f0 {
+        //   b0(v0: u64, v1: Field):
+        //     v2 = cast v1 as u64
+        //     v3 = lt v0, v2
+        //     constrain v3 == u1 1
+        //     v4 = lt v0, v1
+        //     constrain v4 == u1 1
+        //     v5 = lt v1, v0
+        //     constrain v5 == u1 1
+        //     return
+        // }
+        let main_id = Id::test_new(0);
+        let mut builder = FunctionBuilder::new("main".into(), main_id, RuntimeType::Brillig);
+        let v0 = builder.add_parameter(Type::unsigned(64));
+        let v1 = builder.add_parameter(Type::field());
+
+        let true_const = builder.numeric_constant(true, Type::bool());
+        let v2 = builder.insert_cast(v1, Type::unsigned(64));
+        let v3 = builder.insert_binary(v0, BinaryOp::Lt, v2);
+        builder.insert_constrain(v3, true_const, None);
+        let v4 = builder.insert_binary(v0, BinaryOp::Lt, v1);
+        builder.insert_constrain(v4, true_const, None);
+        let v5 = builder.insert_binary(v1, BinaryOp::Lt, v0);
+        builder.insert_constrain(v5, true_const, None);
+        builder.terminate_with_return(vec![]);
+
+        let ssa = builder.finish().remove_redundant_bounds_checks();
+        let main = ssa.main();
+
+        let constrained_values = main.dfg[main.entry_block()]
+            .instructions()
+            .iter()
+            .filter_map(|instruction| match main.dfg[*instruction] {
+                Instruction::Constrain(lhs, _, _) => Some(lhs),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(constrained_values, vec![v3, v5]);
+    }
+}
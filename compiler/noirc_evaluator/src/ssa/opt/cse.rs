@@ -0,0 +1,281 @@
+//! Common Subexpression Elimination (CSE) pass: Replaces the results of any pure instruction
+//! which repeats an instruction of a block dominating it with the results of that earlier
+//! instruction.
+//!
+//! Unlike [constant folding][super::constant_folding], which only reuses the results of
+//! instructions earlier in the same block, this pass reuses results across blocks. Each block is
+//! visited after every block dominating it, and an instruction is looked up among those of its
+//! own block first, then among those of each of its dominators in turn. Since a dominating block
+//! is always executed before the blocks it dominates, its results are available to them.
+//!
+//! Array reads are only reused from arrays which are never the input of an `ArraySet`, since an
+//! array set may be performed in place once it is the last use of its input array.
+use std::collections::HashSet;
+
+use crate::ssa::{
+    ir::{
+        basic_block::BasicBlockId,
+        cfg::ControlFlowGraph,
+        dfg::DataFlowGraph,
+        dom::DominatorTree,
+        function::Function,
+        instruction::{Instruction, InstructionId},
+        post_order::PostOrder,
+        value::ValueId,
+    },
+    ssa_gen::Ssa,
+};
+use fxhash::FxHashMap as HashMap;
+
+impl Ssa {
+    /// Performs Common Subexpression Elimination (CSE) to reuse the results of pure instructions
+    /// computed in dominating blocks.
+    ///
+    /// See [`cse`][self] module for more information.
+    pub(crate) fn common_subexpression_elimination(mut self) -> Ssa {
+        for function in self.functions.values_mut() {
+            common_subexpression_elimination(function);
+        }
+        self
+    }
+}
+
+fn common_subexpression_elimination(function: &mut Function) {
+    let cfg = ControlFlowGraph::with_function(function);
+    let post_order = PostOrder::with_function(function);
+    let dom_tree = DominatorTree::with_cfg_and_post_order(&cfg, &post_order);
+
+    let mut context = Context::new(function);
+
+    // Iterating in reverse post order visits each block after every block dominating it
+    for block in post_order.as_slice().iter().rev() {
+        context.eliminate_instructions_in_block(function, &dom_tree, *block);
+    }
+}
+
+/// Per function context for tracking the results of the instructions in each block.
+struct Context {
+    /// The results of the pure instructions of each visited block, keyed by the instruction with
+    /// its inputs resolved.
+    cached_instruction_results: HashMap<BasicBlockId, HashMap<Instruction, Vec<ValueId>>>,
+
+    /// Arrays which are the input of an `ArraySet` instruction, and so may be written to.
+    written_arrays: HashSet<ValueId>,
+}
+
+impl Context {
+    fn new(function: &Function) -> Self {
+        let dfg = &function.dfg;
+        let mut written_arrays = HashSet::new();
+
+        for block in function.reachable_blocks() {
+            for instruction in dfg[block].instructions() {
+                if let Instruction::ArraySet { array, .. } = &dfg[*instruction] {
+                    written_arrays.insert(dfg.resolve(*array));
+                }
+            }
+        }
+
+        Context { cached_instruction_results: HashMap::default(), written_arrays }
+    }
+
+    /// Removes each instruction of the given block which repeats an earlier instruction of the
+    /// block or of one of its dominators, replacing its results with those of the earlier one.
+    fn eliminate_instructions_in_block(
+        &mut self,
+        function: &mut Function,
+        dom_tree: &DominatorTree,
+        block: BasicBlockId,
+    ) {
+        let mut block_cache = HashMap::default();
+        let mut instructions_to_remove = HashSet::new();
+
+        for instruction_id in function.dfg[block].instructions().to_vec() {
+            let instruction = Self::resolve_instruction(instruction_id, &function.dfg);
+            if !self.can_reuse_results(&instruction, &function.dfg) {
+                continue;
+            }
+
+            let cached_results = block_cache
+                .get(&instruction)
+                .cloned()
+                .or_else(|| self.find_in_dominators(dom_tree, block, &instruction));
+
+            let results = function.dfg.instruction_results(instruction_id).to_vec();
+            if let Some(cached_results) = cached_results {
+                for (old_result, new_result) in results.iter().zip(cached_results) {
+                    if self.written_arrays.contains(old_result) {
+                        self.written_arrays.insert(new_result);
+                    }
+                    function.dfg.set_value_from_id(*old_result, new_result);
+                }
+                instructions_to_remove.insert(instruction_id);
+            } else {
+                block_cache.insert(instruction, results);
+            }
+        }
+
+        function.dfg[block]
+            .instructions_mut()
+            .retain(|instruction| !instructions_to_remove.contains(instruction));
+
+        self.cached_instruction_results.insert(block, block_cache);
+    }
+
+    /// Fetches an [`Instruction`] by its [`InstructionId`] and fully resolves its inputs.
+    fn resolve_instruction(instruction_id: InstructionId, dfg: &DataFlowGraph) -> Instruction {
+        dfg[instruction_id].map_values(|value_id| dfg.resolve(value_id))
+    }
+
+    /// Returns the results of a copy of `instruction` in the closest dominator of `block` with one.
+    fn find_in_dominators(
+        &self,
+        dom_tree: &DominatorTree,
+        block: BasicBlockId,
+        instruction: &Instruction,
+    ) -> Option<Vec<ValueId>> {
+        let mut dominator = dom_tree.immediate_dominator(block);
+
+        while let Some(block) = dominator {
+            let cache = self.cached_instruction_results.get(&block);
+            if let Some(results) = cache.and_then(|cache| cache.get(instruction)) {
+                return Some(results.clone());
+            }
+            dominator = dom_tree.immediate_dominator(block);
+        }
+        None
+    }
+
+    /// Returns true if the results of a copy of `instruction` can stand in for its own results.
+    fn can_reuse_results(&self, instruction: &Instruction, dfg: &DataFlowGraph) -> bool {
+        match instruction {
+            Instruction::ArrayGet { array, .. } => !self.written_arrays.contains(array),
+            // Each array set creates a new array, which may then be written to in place
+            Instruction::ArraySet { .. } => false,
+            _ => instruction.is_pure(dfg),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::rc::Rc;
+
+    use crate::ssa::{
+        function_builder::FunctionBuilder,
+        ir::{
+            function::RuntimeType,
+            instruction::{BinaryOp, Instruction, TerminatorInstruction},
+            map::Id,
+            types::Type,
+        },
+    };
+
+    #[test]
+    fn reuses_results_of_dominating_blocks() {
+        // fn main f0 {
+        //   b0(v0: Field, v1: u1):
+        //     v2 = add v0, Field 1
+        //     jmpif v1 then: b1, else: b2
+        //   b1():
+        //     v3 = add v0, Field 1
+        //     v4 = mul v3, v3
+        //     jmp b3(v4)
+        //   b2():
+        //     v5 = mul v2, v2
+        //     jmp b3(v5)
+        //   b3(v6: Field):
+        //     v7 = mul v2, v2
+        //     return v6, v7
+        // }
+        let main_id = Id::test_new(0);
+        let mut builder = FunctionBuilder::new("main".into(), main_id, RuntimeType::Brillig);
+        let v0 = builder.add_parameter(Type::field());
+        let v1 = builder.add_parameter(Type::bool());
+        let b1 = builder.insert_block();
+        let b2 = builder.insert_block();
+        let b3 = builder.insert_block();
+
+        let one = builder.field_constant(1u128);
+        let v2 = builder.insert_binary(v0, BinaryOp::Add, one);
+        builder.terminate_with_jmpif(v1, b1, b2);
+
+        builder.switch_to_block(b1);
+        let v3 = builder.insert_binary(v0, BinaryOp::Add, one);
+        let v4 = builder.insert_binary(v3, BinaryOp::Mul, v3);
+        builder.terminate_with_jmp(b3, vec![v4]);
+
+        builder.switch_to_block(b2);
+        let v5 = builder.insert_binary(v2, BinaryOp::Mul, v2);
+        builder.terminate_with_jmp(b3, vec![v5]);
+
+        builder.switch_to_block(b3);
+        let v6 = builder.add_block_parameter(b3, Type::field());
+        let v7 = builder.insert_binary(v2, BinaryOp::Mul, v2);
+        builder.terminate_with_return(vec![v6, v7]);
+
+        let ssa = builder.finish().common_subexpression_elimination();
+        let main = ssa.main();
+
+        // The addition in b1 repeats the one in b0, which dominates it
+        assert_eq!(main.dfg[main.entry_block()].instructions().len(), 1);
+        assert_eq!(main.dfg[b1].instructions().len(), 1);
+        assert_eq!(main.dfg.resolve(v3), v2);
+
+        // b2 doesn't dominate b3, so the multiplication in b3 isn't replaced by the one in b2
+        assert_eq!(main.dfg[b2].instructions().len(), 1);
+        assert_eq!(main.dfg[b3].instructions().len(), 1);
+        assert_ne!(main.dfg.resolve(v7), v5);
+    }
+
+    #[test]
+    fn only_reuses_reads_of_unwritten_arrays() {
+        // fn main f0 {
+        //   b0(v0: [Field; 2], v1: [Field; 2], v2: u32):
+        //     v3 = array_get v0, index v2
+        //     v4 = array_get v1, index v2
+        //     v5 = array_set v1, index v2, value Field 1
+        //     jmp b1()
+        //   b1():
+        //     v6 = array_get v0, index v2
+        //     v7 = array_get v1, index v2
+        //     return v3, v4, v5, v6, v7
+        // }
+        let main_id = Id::test_new(0);
+        let mut builder = FunctionBuilder::new("main".into(), main_id, RuntimeType::Brillig);
+        let array_type = Type::Array(Rc::new(vec![Type::field()]), 2);
+        let v0 = builder.add_parameter(array_type.clone());
+        let v1 = builder.add_parameter(array_type);
+        let v2 = builder.add_parameter(Type::unsigned(32));
+        let b1 = builder.insert_block();
+
+        let v3 = builder.insert_array_get(v0, v2, Type::field());
+        let v4 = builder.insert_array_get(v1, v2, Type::field());
+        let one = builder.field_constant(1u128);
+        let v5 = builder.insert_array_set(v1, v2, one);
+        builder.terminate_with_jmp(b1, vec![]);
+
+        builder.switch_to_block(b1);
+        let v6 = builder.insert_array_get(v0, v2, Type::field());
+        let v7 = builder.insert_array_get(v1, v2, Type::field());
+        builder.terminate_with_return(vec![v3, v4, v5, v6, v7]);
+
+        let ssa = builder.finish().common_subexpression_elimination();
+        let main = ssa.main();
+
+        let b1_instructions = main.dfg[b1].instructions();
+        assert_eq!(b1_instructions.len(), 1);
+        let Instruction::ArrayGet { array, .. } = main.dfg[b1_instructions[0]] else {
+            panic!("Expected an array get");
+        };
+        assert_eq!(array, v1);
+
+        match main.dfg[b1].terminator() {
+            Some(TerminatorInstruction::Return { return_values, .. }) => {
+                assert_eq!(main.dfg.resolve(return_values[3]), v3);
+                assert_ne!(main.dfg.resolve(return_values[4]), v4);
+            }
+            _ => unreachable!("Should have terminator instruction"),
+        }
+    }
+}
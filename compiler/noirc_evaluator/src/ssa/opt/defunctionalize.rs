@@ -3,22 +3,27 @@
 //! constant numbers (fields) that represent the function id. That way all calls
 //! with a non-literal target can be replaced with a call to an apply function.
 //! The apply function is a dispatch function that takes the function id as a parameter
-//! and dispatches to the correct target.
+//! and dispatches to the correct target. Each apply function only dispatches to the functions
+//! which can actually reach the calls replaced with it, so calls reached by different functions
+//! of the same signature may use different apply functions.
 use std::collections::{BTreeMap, BTreeSet, HashSet};
 
 use acvm::FieldElement;
 use iter_extended::vecmap;
 
-use crate::ssa::{
-    function_builder::FunctionBuilder,
-    ir::{
-        basic_block::BasicBlockId,
-        function::{Function, FunctionId, RuntimeType, Signature},
-        instruction::{BinaryOp, Instruction},
-        types::{NumericType, Type},
-        value::{Value, ValueId},
+use crate::{
+    errors::RuntimeError,
+    ssa::{
+        function_builder::FunctionBuilder,
+        ir::{
+            basic_block::BasicBlockId,
+            function::{Function, FunctionId, RuntimeType, Signature},
+            instruction::{BinaryOp, Instruction, InstructionId, TerminatorInstruction},
+            types::{NumericType, Type},
+            value::{Value, ValueId},
+        },
+        ssa_gen::Ssa,
     },
-    ssa_gen::Ssa,
 };
 use fxhash::FxHashMap as HashMap;
 
@@ -43,39 +48,44 @@ struct ApplyFunction {
     dispatches_to_multiple_functions: bool,
 }
 
+/// A dynamic call, identified by the function containing it and its call instruction
+type CallSite = (FunctionId, InstructionId);
+
 /// Performs defunctionalization on all functions
 /// This is done by changing all functions as value to be a number (FieldElement)
 /// And creating apply functions that dispatch to the correct target by runtime comparisons with constants
 #[derive(Debug, Clone)]
 struct DefunctionalizationContext {
-    apply_functions: HashMap<Signature, ApplyFunction>,
+    apply_functions: HashMap<CallSite, ApplyFunction>,
 }
 
 impl Ssa {
-    pub(crate) fn defunctionalize(mut self) -> Ssa {
-        // Find all functions used as value that share the same signature
+    pub(crate) fn defunctionalize(mut self) -> Result<Ssa, RuntimeError> {
+        // Find the functions which can reach each dynamic call
         let variants = find_variants(&self);
 
         let apply_functions = create_apply_functions(&mut self, variants);
 
         let context = DefunctionalizationContext { apply_functions };
 
-        context.defunctionalize_all(&mut self);
-        self
+        context.defunctionalize_all(&mut self)?;
+        Ok(self)
     }
 }
 
 impl DefunctionalizationContext {
     /// Defunctionalize all functions in the Ssa
-    fn defunctionalize_all(mut self, ssa: &mut Ssa) {
+    fn defunctionalize_all(mut self, ssa: &mut Ssa) -> Result<(), RuntimeError> {
         for function in ssa.functions.values_mut() {
-            self.defunctionalize(function);
+            self.defunctionalize(function)?;
         }
+        Ok(())
     }
 
     /// Defunctionalize a single function
-    fn defunctionalize(&mut self, func: &mut Function) {
+    fn defunctionalize(&mut self, func: &mut Function) -> Result<(), RuntimeError> {
         let mut call_target_values = HashSet::new();
+        let func_id = func.id();
 
         for block_id in func.reachable_blocks() {
             let block = &func.dfg[block_id];
@@ -95,14 +105,9 @@ impl DefunctionalizationContext {
                 match func.dfg[target_func_id] {
                     // If the target is a function used as value
                     Value::Param { .. } | Value::Instruction { .. } => {
-                        let results = func.dfg.instruction_results(instruction_id);
-                        let signature = Signature {
-                            params: vecmap(&arguments, |param| func.dfg.type_of_value(*param)),
-                            returns: vecmap(results, |result| func.dfg.type_of_value(*result)),
-                        };
-
                         // Find the correct apply function
-                        let apply_function = self.get_apply_function(&signature);
+                        let call_site = (func_id, instruction_id);
+                        let apply_function = self.get_apply_function(func, call_site)?;
 
                         // Replace the instruction with a call to apply
                         let apply_function_value_id = func.dfg.import_function(apply_function.id);
@@ -146,55 +151,247 @@ impl DefunctionalizationContext {
                 }
             }
         }
+        Ok(())
     }
 
-    /// Returns the apply function for the given signature
-    fn get_apply_function(&self, signature: &Signature) -> ApplyFunction {
-        *self.apply_functions.get(signature).expect("Could not find apply function")
+    /// Returns the apply function for the dynamic call at `call_site` in `func`.
+    /// There is none if no function with the call's signature is ever used as a value,
+    /// in which case the call can never be resolved.
+    fn get_apply_function(
+        &self,
+        func: &Function,
+        (func_id, instruction_id): CallSite,
+    ) -> Result<ApplyFunction, RuntimeError> {
+        self.apply_functions.get(&(func_id, instruction_id)).copied().ok_or_else(|| {
+            let signature = call_signature(func, instruction_id).to_string();
+            let call_stack = func.dfg.get_call_stack(instruction_id);
+            RuntimeError::UnknownDynamicCallTarget { signature, call_stack }
+        })
     }
 }
 
-/// Collects all functions used as values that can be called by their signatures
-fn find_variants(ssa: &Ssa) -> BTreeMap<Signature, Vec<FunctionId>> {
-    let mut dynamic_dispatches: BTreeSet<Signature> = BTreeSet::new();
-    let mut functions_as_values: BTreeSet<FunctionId> = BTreeSet::new();
+/// Finds the functions each dynamic call can dispatch to, along with the call's signature.
+///
+/// These are the functions with the call's signature which can flow into its target, found by
+/// [`FunctionValueFlow`]. A call which no function flows into, e.g. in a function which is never
+/// called, falls back to every function with its signature which is used as a value.
+fn find_variants(ssa: &Ssa) -> BTreeMap<CallSite, (Signature, Vec<FunctionId>)> {
+    let signatures: BTreeMap<FunctionId, Signature> =
+        ssa.functions.iter().map(|(id, function)| (*id, function.signature())).collect();
+    let flow = FunctionValueFlow::new(ssa, &signatures);
 
+    let mut functions_as_values: BTreeSet<FunctionId> = BTreeSet::new();
     for function in ssa.functions.values() {
         functions_as_values.extend(find_functions_as_values(function));
-        dynamic_dispatches.extend(find_dynamic_dispatches(function));
     }
 
-    let mut signature_to_functions_as_value: BTreeMap<Signature, Vec<FunctionId>> = BTreeMap::new();
+    let mut variants = BTreeMap::new();
 
-    for function_id in functions_as_values {
-        let signature = ssa.functions[&function_id].signature();
-        signature_to_functions_as_value.entry(signature).or_default().push(function_id);
+    for function in ssa.functions.values() {
+        for (instruction_id, target) in find_dynamic_dispatches(function) {
+            let signature = call_signature(function, instruction_id);
+            let has_signature = |id: &FunctionId| signatures.get(id) == Some(&signature);
+
+            let mut target_fns: Vec<_> = flow.functions_in(function, target).into_iter().collect();
+            target_fns.retain(has_signature);
+            if target_fns.is_empty() {
+                target_fns = functions_as_values.iter().copied().filter(has_signature).collect();
+            }
+            variants.insert((function.id(), instruction_id), (signature, target_fns));
+        }
     }
 
-    let mut variants = BTreeMap::new();
+    variants
+}
+
+/// A flow-insensitive analysis of which functions each value in the program may hold.
+///
+/// Function values flow from the functions they refer to through arrays, block parameters,
+/// and the arguments and results of calls, including calls through other function values,
+/// until a fixed point is reached. References aren't told apart, so a load may produce any
+/// function stored in any reference.
+#[derive(Default)]
+struct FunctionValueFlow {
+    values: HashMap<(FunctionId, ValueId), BTreeSet<FunctionId>>,
+    /// The functions each function may return, by the index of the return value
+    returns: HashMap<(FunctionId, usize), BTreeSet<FunctionId>>,
+    /// The functions stored in any reference
+    memory: BTreeSet<FunctionId>,
+    changed: bool,
+}
 
-    for dispatch_signature in dynamic_dispatches {
-        let mut target_fns = vec![];
-        for (target_signature, functions) in &signature_to_functions_as_value {
-            if &dispatch_signature == target_signature {
-                target_fns.extend(functions);
+impl FunctionValueFlow {
+    fn new(ssa: &Ssa, signatures: &BTreeMap<FunctionId, Signature>) -> Self {
+        let mut flow = FunctionValueFlow::default();
+        loop {
+            flow.changed = false;
+            for function in ssa.functions.values() {
+                flow.analyze_function(ssa, function, signatures);
+            }
+            if !flow.changed {
+                return flow;
             }
         }
-        variants.insert(dispatch_signature, target_fns);
     }
 
-    variants
+    /// The functions `value` in `func` may hold
+    fn functions_in(&self, func: &Function, value: ValueId) -> BTreeSet<FunctionId> {
+        let value = func.dfg.resolve(value);
+        match &func.dfg[value] {
+            Value::Function(id) => BTreeSet::from([*id]),
+            Value::Array { array, .. } => {
+                array.iter().flat_map(|element| self.functions_in(func, *element)).collect()
+            }
+            _ => self.values.get(&(func.id(), value)).cloned().unwrap_or_default(),
+        }
+    }
+
+    /// Records that `value` in `func` may hold any of `functions`
+    fn flow_into(&mut self, func: &Function, value: ValueId, functions: &BTreeSet<FunctionId>) {
+        if functions.is_empty() {
+            return;
+        }
+        let value = func.dfg.resolve(value);
+        let known_functions = self.values.entry((func.id(), value)).or_default();
+        self.changed |= union(known_functions, functions);
+    }
+
+    fn analyze_function(
+        &mut self,
+        ssa: &Ssa,
+        func: &Function,
+        signatures: &BTreeMap<FunctionId, Signature>,
+    ) {
+        for block_id in func.reachable_blocks() {
+            let block = &func.dfg[block_id];
+            for instruction_id in block.instructions() {
+                let results = func.dfg.instruction_results(*instruction_id);
+                let functions = match &func.dfg[*instruction_id] {
+                    Instruction::Call { func: target, arguments } => {
+                        self.analyze_call(
+                            ssa,
+                            func,
+                            *instruction_id,
+                            *target,
+                            arguments,
+                            signatures,
+                        );
+                        continue;
+                    }
+                    Instruction::Store { value, .. } => {
+                        let functions = self.functions_in(func, *value);
+                        self.changed |= union(&mut self.memory, &functions);
+                        continue;
+                    }
+                    Instruction::Load { .. } => self.memory.clone(),
+                    // Any other instruction, such as reading out of an array,
+                    // may produce any function among its operands
+                    instruction => {
+                        let mut functions = BTreeSet::new();
+                        instruction.for_each_value(|value| {
+                            functions.extend(self.functions_in(func, value));
+                        });
+                        functions
+                    }
+                };
+                for result in results {
+                    self.flow_into(func, *result, &functions);
+                }
+            }
+
+            match block.unwrap_terminator() {
+                TerminatorInstruction::Jmp { destination, arguments, .. } => {
+                    let parameters = func.dfg.block_parameters(*destination);
+                    for (parameter, argument) in parameters.iter().zip(arguments) {
+                        let functions = self.functions_in(func, *argument);
+                        self.flow_into(func, *parameter, &functions);
+                    }
+                }
+                TerminatorInstruction::Return { return_values, .. } => {
+                    for (index, value) in return_values.iter().enumerate() {
+                        let functions = self.functions_in(func, *value);
+                        let returned_functions =
+                            self.returns.entry((func.id(), index)).or_default();
+                        self.changed |= union(returned_functions, &functions);
+                    }
+                }
+                TerminatorInstruction::JmpIf { .. } => (),
+            }
+        }
+    }
+
+    /// Passes the functions held by the arguments of a call to the parameters of each function
+    /// it may call, and the functions they return to the results of the call.
+    fn analyze_call(
+        &mut self,
+        ssa: &Ssa,
+        func: &Function,
+        instruction_id: InstructionId,
+        target: ValueId,
+        arguments: &[ValueId],
+        signatures: &BTreeMap<FunctionId, Signature>,
+    ) {
+        let results = func.dfg.instruction_results(instruction_id);
+        let callees = match &func.dfg[target] {
+            Value::Function(id) => BTreeSet::from([*id]),
+            Value::Param { .. } | Value::Instruction { .. } => {
+                let signature = call_signature(func, instruction_id);
+                let mut callees = self.functions_in(func, target);
+                callees.retain(|id| signatures.get(id) == Some(&signature));
+                callees
+            }
+            // Intrinsics, such as pushing onto a slice, may return any function passed to them
+            _ => {
+                let functions: BTreeSet<_> = arguments
+                    .iter()
+                    .flat_map(|argument| self.functions_in(func, *argument))
+                    .collect();
+                for result in results {
+                    self.flow_into(func, *result, &functions);
+                }
+                return;
+            }
+        };
+
+        for callee_id in callees {
+            let callee = &ssa.functions[&callee_id];
+            for (parameter, argument) in callee.parameters().iter().zip(arguments) {
+                let functions = self.functions_in(func, *argument);
+                self.flow_into(callee, *parameter, &functions);
+            }
+            for (index, result) in results.iter().enumerate() {
+                let functions = self.returns.get(&(callee_id, index)).cloned().unwrap_or_default();
+                self.flow_into(func, *result, &functions);
+            }
+        }
+    }
+}
+
+/// Adds `functions` to `known_functions`, returning true if any were new
+fn union(known_functions: &mut BTreeSet<FunctionId>, functions: &BTreeSet<FunctionId>) -> bool {
+    let known = known_functions.len();
+    known_functions.extend(functions);
+    known_functions.len() != known
+}
+
+/// The signature of the function called by the call instruction `instruction_id`
+fn call_signature(func: &Function, instruction_id: InstructionId) -> Signature {
+    let Instruction::Call { arguments, .. } = &func.dfg[instruction_id] else {
+        unreachable!("ICE: expected a call instruction");
+    };
+    let results = func.dfg.instruction_results(instruction_id);
+    Signature {
+        params: vecmap(arguments, |param| func.dfg.type_of_value(*param)),
+        returns: vecmap(results, |result| func.dfg.type_of_value(*result)),
+    }
 }
 
 /// Finds all literal functions used as values in the given function
 fn find_functions_as_values(func: &Function) -> BTreeSet<FunctionId> {
     let mut functions_as_values: BTreeSet<FunctionId> = BTreeSet::new();
 
-    let mut process_value = |value_id: ValueId| {
-        if let Value::Function(id) = func.dfg[value_id] {
-            functions_as_values.insert(id);
-        }
-    };
+    let mut process_value =
+        |value_id: ValueId| collect_functions_in_value(func, value_id, &mut functions_as_values);
 
     for block_id in func.reachable_blocks() {
         let block = &func.dfg[block_id];
@@ -204,10 +401,9 @@ fn find_functions_as_values(func: &Function) -> BTreeSet<FunctionId> {
                 Instruction::Call { arguments, .. } => {
                     arguments.iter().for_each(|value_id| process_value(*value_id));
                 }
-                Instruction::Store { value, .. } => {
-                    process_value(*value);
-                }
-                _ => continue,
+                // Function values may also be stored in or read out of arrays,
+                // so every other operand needs to be checked as well.
+                _ => instruction.for_each_value(&mut process_value),
             };
         }
 
@@ -217,51 +413,72 @@ fn find_functions_as_values(func: &Function) -> BTreeSet<FunctionId> {
     functions_as_values
 }
 
-/// Finds all dynamic dispatch signatures in the given function
-fn find_dynamic_dispatches(func: &Function) -> BTreeSet<Signature> {
-    let mut dispatches = BTreeSet::new();
+/// Collects the literal functions contained in the given value.
+/// Constant arrays are searched recursively since function values stored in them,
+/// e.g. a table of trait impl methods, can later be read out and called dynamically.
+fn collect_functions_in_value(
+    func: &Function,
+    value_id: ValueId,
+    functions_as_values: &mut BTreeSet<FunctionId>,
+) {
+    match &func.dfg[value_id] {
+        Value::Function(id) => {
+            functions_as_values.insert(*id);
+        }
+        Value::Array { array, .. } => {
+            for element in array {
+                collect_functions_in_value(func, *element, functions_as_values);
+            }
+        }
+        _ => (),
+    }
+}
+
+/// Finds all dynamic dispatches in the given function, along with the value they call
+fn find_dynamic_dispatches(func: &Function) -> Vec<(InstructionId, ValueId)> {
+    let mut dispatches = Vec::new();
 
     for block_id in func.reachable_blocks() {
         let block = &func.dfg[block_id];
         for instruction_id in block.instructions() {
-            let instruction = &func.dfg[*instruction_id];
-            match instruction {
-                Instruction::Call { func: target, arguments } => {
-                    if let Value::Param { .. } | Value::Instruction { .. } = &func.dfg[*target] {
-                        let results = func.dfg.instruction_results(*instruction_id);
-                        dispatches.insert(Signature {
-                            params: vecmap(arguments, |param| func.dfg.type_of_value(*param)),
-                            returns: vecmap(results, |result| func.dfg.type_of_value(*result)),
-                        });
-                    }
+            if let Instruction::Call { func: target, .. } = &func.dfg[*instruction_id] {
+                if let Value::Param { .. } | Value::Instruction { .. } = &func.dfg[*target] {
+                    dispatches.push((*instruction_id, *target));
                 }
-                _ => continue,
-            };
+            }
         }
     }
     dispatches
 }
 
+/// Creates an apply function for each distinct signature and set of variants among the dynamic
+/// calls, returning the apply function each call should be replaced with.
 fn create_apply_functions(
     ssa: &mut Ssa,
-    variants_map: BTreeMap<Signature, Vec<FunctionId>>,
-) -> HashMap<Signature, ApplyFunction> {
-    let mut apply_functions = HashMap::default();
-    for (signature, variants) in variants_map.into_iter() {
-        assert!(
-            !variants.is_empty(),
-            "ICE: at least one variant should exist for a dynamic call {signature:?}"
-        );
-        let dispatches_to_multiple_functions = variants.len() > 1;
-
-        let id = if dispatches_to_multiple_functions {
-            create_apply_function(ssa, signature.clone(), variants)
-        } else {
-            variants[0]
-        };
-        apply_functions.insert(signature, ApplyFunction { id, dispatches_to_multiple_functions });
+    variants_map: BTreeMap<CallSite, (Signature, Vec<FunctionId>)>,
+) -> HashMap<CallSite, ApplyFunction> {
+    let mut apply_functions: HashMap<(Signature, Vec<FunctionId>), ApplyFunction> =
+        HashMap::default();
+    let mut call_apply_functions = HashMap::default();
+    for (call_site, (signature, variants)) in variants_map.into_iter() {
+        if variants.is_empty() {
+            // Calls with this signature have no possible target. This is reported as an error
+            // at each call site when the calls are replaced.
+            continue;
+        }
+        let apply_function =
+            *apply_functions.entry((signature.clone(), variants.clone())).or_insert_with(|| {
+                let dispatches_to_multiple_functions = variants.len() > 1;
+                let id = if dispatches_to_multiple_functions {
+                    create_apply_function(ssa, signature, variants)
+                } else {
+                    variants[0]
+                };
+                ApplyFunction { id, dispatches_to_multiple_functions }
+            });
+        call_apply_functions.insert(call_site, apply_function);
     }
-    apply_functions
+    call_apply_functions
 }
 
 fn function_id_to_field(function_id: FunctionId) -> FieldElement {
@@ -355,3 +572,136 @@ fn build_return_block(
     builder.switch_to_block(previous_block);
     return_block
 }
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        errors::RuntimeError,
+        ssa::{
+            function_builder::FunctionBuilder,
+            ir::{
+                function::{Function, FunctionId, RuntimeType},
+                instruction::{BinaryOp, Instruction},
+                map::Id,
+                types::Type,
+                value::Value,
+            },
+        },
+    };
+
+    /// Returns the functions called directly by `function`
+    fn called_functions(function: &Function) -> Vec<FunctionId> {
+        let mut called_functions = Vec::new();
+        for block_id in function.reachable_blocks() {
+            for instruction_id in function.dfg[block_id].instructions() {
+                if let Instruction::Call { func, .. } = &function.dfg[*instruction_id] {
+                    if let Value::Function(id) = function.dfg[*func] {
+                        called_functions.push(id);
+                    }
+                }
+            }
+        }
+        called_functions.sort();
+        called_functions
+    }
+
+    #[test]
+    fn dispatches_only_to_reachable_functions() {
+        // fn main f0 {
+        //   b0(v0: Field):
+        //     v1 = call call_first(add_one, v0)
+        //     v2 = call call_second(add_two, v1)
+        //     v3 = call call_second(add_three, v2)
+        //     return v3
+        // }
+        // fn call_first f1 {
+        //   b0(v0: function, v1: Field):
+        //     v2 = call v0(v1)
+        //     return v2
+        // }
+        // fn call_second f2, the same as call_first
+        // fn add_one f3 {
+        //   b0(v0: Field):
+        //     v1 = add v0, Field 1
+        //     return v1
+        // }
+        // fn add_two f4 and fn add_three f5, the same as add_one but adding 2 and 3
+        let main_id = Id::test_new(0);
+        let call_first_id = Id::test_new(1);
+        let call_second_id = Id::test_new(2);
+        let add_one_id = Id::test_new(3);
+        let add_two_id = Id::test_new(4);
+        let add_three_id = Id::test_new(5);
+
+        let mut builder = FunctionBuilder::new("main".into(), main_id, RuntimeType::Acir);
+        let mut result = builder.add_parameter(Type::field());
+        let calls = [
+            (call_first_id, add_one_id),
+            (call_second_id, add_two_id),
+            (call_second_id, add_three_id),
+        ];
+        for (caller_id, callee_id) in calls {
+            let caller = builder.import_function(caller_id);
+            let callee = builder.import_function(callee_id);
+            result = builder.insert_call(caller, vec![callee, result], vec![Type::field()])[0];
+        }
+        builder.terminate_with_return(vec![result]);
+
+        for (name, id) in [("call_first", call_first_id), ("call_second", call_second_id)] {
+            builder.new_function(name.into(), id);
+            let v0 = builder.add_parameter(Type::Function);
+            let v1 = builder.add_parameter(Type::field());
+            let v2 = builder.insert_call(v0, vec![v1], vec![Type::field()]).to_vec();
+            builder.terminate_with_return(v2);
+        }
+
+        let add = [
+            ("add_one", add_one_id, 1u128),
+            ("add_two", add_two_id, 2),
+            ("add_three", add_three_id, 3),
+        ];
+        for (name, id, constant) in add {
+            builder.new_function(name.into(), id);
+            let v0 = builder.add_parameter(Type::field());
+            let constant = builder.field_constant(constant);
+            let v1 = builder.insert_binary(v0, BinaryOp::Add, constant);
+            builder.terminate_with_return(vec![v1]);
+        }
+
+        let ssa = builder.finish().defunctionalize().unwrap();
+
+        // Only `add_one` reaches the call in `call_first`, so it is called directly
+        assert_eq!(called_functions(&ssa.functions[&call_first_id]), vec![add_one_id]);
+
+        // The call in `call_second` dispatches to `add_two` and `add_three` but not `add_one`
+        let apply_ids = called_functions(&ssa.functions[&call_second_id]);
+        assert_eq!(apply_ids.len(), 1);
+        assert_eq!(ssa.functions.len(), 7);
+        assert_eq!(called_functions(&ssa.functions[&apply_ids[0]]), vec![add_two_id, add_three_id]);
+    }
+
+    #[test]
+    fn dynamic_call_without_target_is_an_error() {
+        // fn main f0 {
+        //   b0(v0: function, v1: Field):
+        //     v2 = call v0(v1)
+        //     return v2
+        // }
+        // No function is ever used as a value, so `v0` has nothing to dispatch to.
+        let main_id = Id::test_new(0);
+        let mut builder = FunctionBuilder::new("main".into(), main_id, RuntimeType::Acir);
+        let v0 = builder.add_parameter(Type::Function);
+        let v1 = builder.add_parameter(Type::field());
+        let v2 = builder.insert_call(v0, vec![v1], vec![Type::field()]).to_vec();
+        builder.terminate_with_return(v2);
+
+        let ssa = builder.finish();
+        match ssa.defunctionalize() {
+            Err(RuntimeError::UnknownDynamicCallTarget { signature, .. }) => {
+                assert_eq!(signature, "fn(Field) -> (Field)");
+            }
+            Err(other) => panic!("Expected an unknown dynamic call target error, got {other:?}"),
+            Ok(_) => panic!("Expected defunctionalization to fail"),
+        }
+    }
+}
@@ -1,25 +1,34 @@
 //! Dead Instruction Elimination (DIE) pass: Removes any instruction without side-effects for
 //! which the results are unused.
+//!
+//! Whether an instruction can be removed follows its
+//! [effects][crate::ssa::ir::instruction::Effects], so unused arithmetic, array sets, loads and
+//! calls to intrinsics such as `to_le_bits` are all removed. Calls to functions are only removed
+//! if the function is itself free of side-effects: every instruction of the function could be
+//! removed if unused, it only calls other such functions, and it has no loops, so that removing a
+//! call can never remove a non-terminating computation.
 use std::collections::HashSet;
 
 use crate::ssa::{
     ir::{
         basic_block::{BasicBlock, BasicBlockId},
         dfg::DataFlowGraph,
-        function::Function,
-        instruction::InstructionId,
+        function::{Function, FunctionId},
+        instruction::{Instruction, InstructionId},
         post_order::PostOrder,
         value::{Value, ValueId},
     },
     ssa_gen::Ssa,
 };
+use fxhash::FxHashMap as HashMap;
 
 impl Ssa {
     /// Performs Dead Instruction Elimination (DIE) to remove any instructions with
     /// unused results.
     pub(crate) fn dead_instruction_elimination(mut self) -> Ssa {
+        let removable_functions = find_removable_functions(&self);
         for function in self.functions.values_mut() {
-            dead_instruction_elimination(function);
+            dead_instruction_elimination(function, &removable_functions);
         }
         self
     }
@@ -31,8 +40,11 @@ impl Ssa {
 /// instructions that reference results from an instruction in another block are evaluated first.
 /// If we did not iterate blocks in this order we could not safely say whether or not the results
 /// of its instructions are needed elsewhere.
-fn dead_instruction_elimination(function: &mut Function) {
-    let mut context = Context::default();
+fn dead_instruction_elimination(
+    function: &mut Function,
+    removable_functions: &HashSet<FunctionId>,
+) {
+    let mut context = Context::new(removable_functions);
     let blocks = PostOrder::with_function(function);
 
     for block in blocks.as_slice() {
@@ -40,14 +52,85 @@ fn dead_instruction_elimination(function: &mut Function) {
     }
 }
 
+/// Returns the functions whose calls can be removed if their results are unused.
+///
+/// Functions are only found to be removable once each function they call has been, so functions
+/// which call themselves, directly or through other functions, are never removable.
+fn find_removable_functions(ssa: &Ssa) -> HashSet<FunctionId> {
+    let mut removable_functions = HashSet::new();
+    loop {
+        let new_functions: Vec<_> = ssa
+            .functions
+            .iter()
+            .filter(|(id, function)| {
+                !removable_functions.contains(*id)
+                    && is_removable_function(function, &removable_functions)
+            })
+            .map(|(id, _)| *id)
+            .collect();
+
+        if new_functions.is_empty() {
+            return removable_functions;
+        }
+        removable_functions.extend(new_functions);
+    }
+}
+
+/// Returns true if `function` has no loops and each of its instructions could be removed if
+/// its results were unused.
+fn is_removable_function(function: &Function, removable_functions: &HashSet<FunctionId>) -> bool {
+    let post_order = PostOrder::with_function(function);
+    let positions: HashMap<BasicBlockId, usize> =
+        post_order.as_slice().iter().enumerate().map(|(index, block)| (*block, index)).collect();
+
+    post_order.as_slice().iter().all(|block| {
+        // Every loop has an edge to a block which is not after it in reverse post order
+        let has_back_edge = function.dfg[*block]
+            .successors()
+            .any(|successor| positions[&successor] >= positions[block]);
+
+        !has_back_edge
+            && function.dfg[*block].instructions().iter().all(|instruction| {
+                can_be_removed_if_unused(
+                    &function.dfg[*instruction],
+                    &function.dfg,
+                    removable_functions,
+                )
+            })
+    })
+}
+
+/// Returns true if `instruction` may be removed if its results are unused.
+fn can_be_removed_if_unused(
+    instruction: &Instruction,
+    dfg: &DataFlowGraph,
+    removable_functions: &HashSet<FunctionId>,
+) -> bool {
+    if let Instruction::Call { func, .. } = instruction {
+        if let Value::Function(id) = dfg[*func] {
+            return removable_functions.contains(&id);
+        }
+    }
+    instruction.effects(dfg).can_be_removed_if_unused()
+}
+
 /// Per function context for tracking unused values and which instructions to remove.
-#[derive(Default)]
-struct Context {
+struct Context<'a> {
     used_values: HashSet<ValueId>,
     instructions_to_remove: HashSet<InstructionId>,
+    /// The functions whose calls can be removed if their results are unused
+    removable_functions: &'a HashSet<FunctionId>,
 }
 
-impl Context {
+impl<'a> Context<'a> {
+    fn new(removable_functions: &'a HashSet<FunctionId>) -> Self {
+        Context {
+            used_values: HashSet::new(),
+            instructions_to_remove: HashSet::new(),
+            removable_functions,
+        }
+    }
+
     /// Steps backwards through the instruction of the given block, amassing a set of used values
     /// as it goes, and at the same time marking instructions for removal if they haven't appeared
     /// in the set thus far.
@@ -90,12 +173,12 @@ impl Context {
     fn is_unused(&self, instruction_id: InstructionId, function: &Function) -> bool {
         let instruction = &function.dfg[instruction_id];
 
-        if instruction.has_side_effects(&function.dfg) {
-            // If the instruction has side effects we should never remove it.
-            false
-        } else {
+        if can_be_removed_if_unused(instruction, &function.dfg, self.removable_functions) {
             let results = function.dfg.instruction_results(instruction_id);
             results.iter().all(|result| !self.used_values.contains(result))
+        } else {
+            // If the instruction has side effects we should never remove it.
+            false
         }
     }
 
@@ -132,9 +215,10 @@ mod test {
         function_builder::FunctionBuilder,
         ir::{
             function::RuntimeType,
-            instruction::{BinaryOp, Intrinsic},
+            instruction::{BinaryOp, Instruction, Intrinsic},
             map::Id,
             types::Type,
+            value::Value,
         },
     };
 
@@ -219,4 +303,76 @@ mod test {
         assert_eq!(main.dfg[main.entry_block()].instructions().len(), 1);
         assert_eq!(main.dfg[b1].instructions().len(), 6);
     }
+
+    #[test]
+    fn removes_unused_calls_to_functions_without_side_effects() {
+        // fn main f0 {
+        //   b0(v0: Field):
+        //     v1 = call f1(v0)
+        //     v2 = call f2(v0)
+        //     v3 = call f3(v0)
+        //     return
+        // }
+        // brillig fn pure f1 {
+        //   b0(v0: Field):
+        //     v1 = add v0, Field 1
+        //     return v1
+        // }
+        // brillig fn constrained f2 {
+        //   b0(v0: Field):
+        //     constrain v0 == Field 1
+        //     return v0
+        // }
+        // brillig fn recursive f3 {
+        //   b0(v0: Field):
+        //     v1 = call f3(v0)
+        //     return v1
+        // }
+        let main_id = Id::test_new(0);
+        let pure_id = Id::test_new(1);
+        let constrained_id = Id::test_new(2);
+        let recursive_id = Id::test_new(3);
+
+        let mut builder = FunctionBuilder::new("main".into(), main_id, RuntimeType::Acir);
+        let v0 = builder.add_parameter(Type::field());
+        for function_id in [pure_id, constrained_id, recursive_id] {
+            let function = builder.import_function(function_id);
+            builder.insert_call(function, vec![v0], vec![Type::field()]);
+        }
+        builder.terminate_with_return(vec![]);
+
+        builder.new_brillig_function("pure".into(), pure_id);
+        let v0 = builder.add_parameter(Type::field());
+        let one = builder.field_constant(1u128);
+        let v1 = builder.insert_binary(v0, BinaryOp::Add, one);
+        builder.terminate_with_return(vec![v1]);
+
+        builder.new_brillig_function("constrained".into(), constrained_id);
+        let v0 = builder.add_parameter(Type::field());
+        let one = builder.field_constant(1u128);
+        builder.insert_constrain(v0, one, None);
+        builder.terminate_with_return(vec![v0]);
+
+        builder.new_brillig_function("recursive".into(), recursive_id);
+        let v0 = builder.add_parameter(Type::field());
+        let recursive = builder.import_function(recursive_id);
+        let v1 = builder.insert_call(recursive, vec![v0], vec![Type::field()])[0];
+        builder.terminate_with_return(vec![v1]);
+
+        let ssa = builder.finish().dead_instruction_elimination();
+        let main = ssa.main();
+
+        let called_functions: Vec<_> = main.dfg[main.entry_block()]
+            .instructions()
+            .iter()
+            .map(|instruction| match &main.dfg[*instruction] {
+                Instruction::Call { func, .. } => main.dfg[*func].clone(),
+                other => panic!("Expected only calls, found {other:?}"),
+            })
+            .collect();
+        assert_eq!(
+            called_functions,
+            vec![Value::Function(constrained_id), Value::Function(recursive_id)]
+        );
+    }
 }
@@ -2,17 +2,25 @@
 //! The purpose of this pass is to inline the instructions of each function call
 //! within the function caller. If all function calls are known, there will only
 //! be a single function remaining when the pass finishes.
-use std::collections::{BTreeSet, HashSet};
+//!
+//! Constrained functions marked `#[inline(never)]` are fold boundaries, as are large ones called
+//! often enough for the cost model of [`InlineCosts`] to keep them. They are kept as functions of
+//! their own by the first inlining pass, and only inlined into `main` by
+//! [`Ssa::inline_fold_boundaries`] just before its CFG is flattened into a circuit.
+use std::collections::{BTreeMap, BTreeSet, HashSet};
 
-use iter_extended::{btree_map, vecmap};
+use iter_extended::vecmap;
+use noirc_frontend::token::InlineType;
 
+use crate::errors::RuntimeError;
 use crate::ssa::{
     function_builder::FunctionBuilder,
     ir::{
         basic_block::BasicBlockId,
         dfg::{CallStack, InsertInstructionResult},
         function::{Function, FunctionId, RuntimeType},
-        instruction::{Instruction, InstructionId, TerminatorInstruction},
+        instruction::{BinaryOp, Instruction, InstructionId, Intrinsic, TerminatorInstruction},
+        types::Type,
         value::{Value, ValueId},
     },
     ssa_gen::Ssa,
@@ -23,6 +31,14 @@ use fxhash::FxHashMap as HashMap;
 /// frames at any point in time.
 const RECURSION_LIMIT: u32 = 1000;
 
+/// Calls to unconstrained functions whose estimated cost is at most this are inlined into other
+/// unconstrained functions however many times they are called.
+const SMALL_FUNCTION_COST: usize = 10;
+
+/// The maximum estimated cost that inlining every call to an unconstrained function may add to
+/// the program, on top of the single copy of the function which is needed either way.
+const MAX_INLINING_GROWTH: usize = 100;
+
 impl Ssa {
     /// Inline all functions within the IR.
     ///
@@ -36,13 +52,36 @@ impl Ssa {
     /// changes. This is because if the function's id later becomes known by a later
     /// pass, we would need to re-run all of inlining anyway to inline it, so we might
     /// as well save the work for later instead of performing it twice.
-    pub(crate) fn inline_functions(mut self) -> Ssa {
-        self.functions = btree_map(get_entry_point_functions(&self), |entry_point| {
-            let new_function = InlineContext::new(&self, entry_point).inline_all(&self);
-            (entry_point, new_function)
-        });
+    ///
+    /// Calls to fold boundaries from constrained code are kept as well, and each fold boundary is
+    /// an entry point of its own, so that it is compiled once however many times it is called.
+    ///
+    /// Fails if a function marked `#[inline(always)]` would have to be inlined into itself.
+    pub(crate) fn inline_functions(mut self) -> Result<Ssa, RuntimeError> {
+        let costs = InlineCosts::new(&self);
+        let mut functions = BTreeMap::new();
+        for entry_point in get_entry_point_functions(&self, &costs) {
+            let context = InlineContext::new(&self, entry_point, &costs, false);
+            functions.insert(entry_point, context.inline_all(&self)?);
+        }
+        self.functions = functions;
+        Ok(self)
+    }
 
-        self
+    /// Inlines the fold boundaries kept by [`Ssa::inline_functions`] into `main`, as a circuit
+    /// can't call functions, along with any constrained function they call. Only `main` and the
+    /// unconstrained functions are left afterwards.
+    pub(crate) fn inline_fold_boundaries(mut self) -> Result<Ssa, RuntimeError> {
+        if self.main().runtime() == RuntimeType::Acir {
+            let costs = InlineCosts::new(&self);
+            let main = InlineContext::new(&self, self.main_id, &costs, true).inline_all(&self)?;
+            self.functions.insert(self.main_id, main);
+        }
+
+        let main_id = self.main_id;
+        self.functions
+            .retain(|id, function| *id == main_id || function.runtime() == RuntimeType::Brillig);
+        Ok(self)
     }
 }
 
@@ -51,7 +90,7 @@ impl Ssa {
 /// This works using an internal FunctionBuilder to build a new main function from scratch.
 /// Doing it this way properly handles importing instructions between functions and lets us
 /// reuse the existing API at the cost of essentially cloning each of main's instructions.
-struct InlineContext {
+struct InlineContext<'costs> {
     recursion_level: u32,
     builder: FunctionBuilder,
 
@@ -59,6 +98,26 @@ struct InlineContext {
 
     // The FunctionId of the entry point function we're inlining into in the old, unmodified Ssa.
     entry_point: FunctionId,
+
+    // The functions currently being inlined, innermost last.
+    inline_stack: Vec<FunctionId>,
+
+    costs: &'costs InlineCosts,
+
+    // Whether calls to fold boundaries are inlined, which they only are by the second pass.
+    inline_fold_boundaries: bool,
+
+    // The first call which had to be inlined but couldn't be.
+    error: Option<RuntimeError>,
+}
+
+/// The estimated cost of each function in the original Ssa along with the number of call sites
+/// of each function reachable from `main`, which together decide whether calls to unconstrained
+/// functions are inlined, and which constrained functions are fold boundaries.
+struct InlineCosts {
+    costs: HashMap<FunctionId, usize>,
+    call_counts: HashMap<FunctionId, usize>,
+    fold_boundaries: BTreeSet<FunctionId>,
 }
 
 /// The per-function inlining context contains information that is only valid for one function.
@@ -66,13 +125,13 @@ struct InlineContext {
 /// layer to translate between BlockId to BlockId for the current function and the function to
 /// inline into. The same goes for ValueIds, InstructionIds, and for storing other data like
 /// parameter to argument mappings.
-struct PerFunctionContext<'function> {
+struct PerFunctionContext<'function, 'costs> {
     /// The source function is the function we're currently inlining into the function being built.
     source_function: &'function Function,
 
     /// The shared inlining context for all functions. This notably contains the FunctionBuilder used
     /// to build the function we're inlining into.
-    context: &'function mut InlineContext,
+    context: &'function mut InlineContext<'costs>,
 
     /// Maps ValueIds in the function being inlined to the new ValueIds to use in the function
     /// being inlined into. This mapping also contains the mapping from parameter values to
@@ -93,12 +152,14 @@ struct PerFunctionContext<'function> {
 }
 
 /// The entry point functions are each function we should inline into - and each function that
-/// should be left in the final program. This is usually just `main` but also includes any
-/// brillig functions used.
-fn get_entry_point_functions(ssa: &Ssa) -> BTreeSet<FunctionId> {
+/// should be left in the program until fold boundaries are inlined. This is usually just `main`
+/// but also includes any brillig functions used and any fold boundaries.
+fn get_entry_point_functions(ssa: &Ssa, costs: &InlineCosts) -> BTreeSet<FunctionId> {
     let functions = ssa.functions.iter();
     let mut entry_points = functions
-        .filter(|(_, function)| function.runtime() == RuntimeType::Brillig)
+        .filter(|(id, function)| {
+            function.runtime() == RuntimeType::Brillig || costs.fold_boundaries.contains(id)
+        })
         .map(|(id, _)| *id)
         .collect::<BTreeSet<_>>();
 
@@ -106,20 +167,224 @@ fn get_entry_point_functions(ssa: &Ssa) -> BTreeSet<FunctionId> {
     entry_points
 }
 
-impl InlineContext {
+impl InlineCosts {
+    fn new(ssa: &Ssa) -> InlineCosts {
+        let mut costs = HashMap::default();
+        let mut callees: HashMap<FunctionId, Vec<FunctionId>> = HashMap::default();
+
+        for (id, function) in &ssa.functions {
+            let mut cost = 0;
+            let called = callees.entry(*id).or_default();
+            for block in function.reachable_blocks() {
+                for instruction in function.dfg[block].instructions() {
+                    let instruction = &function.dfg[*instruction];
+                    if let Instruction::Call { func, .. } = instruction {
+                        if let Value::Function(callee) = function.dfg[*func] {
+                            called.push(callee);
+                        }
+                    }
+                    cost += instruction_cost(instruction, function.runtime());
+                }
+            }
+            costs.insert(*id, cost);
+        }
+
+        // Calls from functions which `main` never reaches don't make it into the program
+        let mut call_counts = HashMap::default();
+        let mut reachable = BTreeSet::from([ssa.main_id]);
+        let mut stack = vec![ssa.main_id];
+        while let Some(function) = stack.pop() {
+            for callee in callees.get(&function).into_iter().flatten() {
+                *call_counts.entry(*callee).or_default() += 1;
+                if reachable.insert(*callee) {
+                    stack.push(*callee);
+                }
+            }
+        }
+
+        let mut costs = InlineCosts { costs, call_counts, fold_boundaries: BTreeSet::new() };
+        let mut compilable = HashMap::default();
+        let fold_boundaries = ssa
+            .functions
+            .iter()
+            .filter(|(id, function)| {
+                **id != ssa.main_id
+                    && function.runtime() == RuntimeType::Acir
+                    && match function.inline_type() {
+                        InlineType::Always => false,
+                        InlineType::Never => true,
+                        InlineType::Default => {
+                            !costs.is_worth_inlining(**id)
+                                && compiles_without_arguments(ssa, **id, &mut compilable)
+                        }
+                    }
+            })
+            .map(|(id, _)| *id)
+            .collect();
+        costs.fold_boundaries = fold_boundaries;
+        costs
+    }
+
+    /// Small functions are always worth inlining. Larger ones are only worth it while the copies
+    /// of their body made for their call sites don't grow the program too much, so a function
+    /// called once is always inlined, since it doesn't need to be kept as well.
+    fn is_worth_inlining(&self, function: FunctionId) -> bool {
+        let cost = self.costs[&function];
+        let calls = self.call_counts.get(&function).copied().unwrap_or(1);
+        cost <= SMALL_FUNCTION_COST
+            || cost.saturating_mul(calls.saturating_sub(1)) <= MAX_INLINING_GROWTH
+    }
+}
+
+/// Whether a constrained function can be compiled before it is inlined into its callers, which
+/// is what lets it be a fold boundary. Its loops can only be unrolled and its `assert_constant`
+/// and `static_assert` calls only be evaluated once its arguments are known, and references
+/// passed in or out of it can only be resolved by mem2reg across the call. The same holds for the
+/// constrained functions it calls, which are inlined into it. Recursive functions never qualify.
+fn compiles_without_arguments(
+    ssa: &Ssa,
+    id: FunctionId,
+    compilable: &mut HashMap<FunctionId, bool>,
+) -> bool {
+    if let Some(result) = compilable.get(&id) {
+        return *result;
+    }
+    // Assume the function doesn't qualify while checking its callees, which rejects cycles
+    compilable.insert(id, false);
+
+    let function = &ssa.functions[&id];
+    let signature = function.signature();
+    let result = !has_loop(function)
+        && !signature
+            .params
+            .iter()
+            .chain(&signature.returns)
+            .any(|typ| typ.contains_reference() || matches!(typ, Type::Slice(_)))
+        && function.reachable_blocks().into_iter().all(|block| {
+            let instructions = function.dfg[block].instructions();
+            instructions.iter().all(|instruction| match &function.dfg[*instruction] {
+                Instruction::Call { func, .. } => match function.dfg[*func] {
+                    Value::Intrinsic(Intrinsic::AssertConstant | Intrinsic::StaticAssert) => false,
+                    Value::Function(callee) => {
+                        ssa.functions[&callee].runtime() == RuntimeType::Brillig
+                            || compiles_without_arguments(ssa, callee, compilable)
+                    }
+                    _ => true,
+                },
+                _ => true,
+            })
+        });
+
+    compilable.insert(id, result);
+    result
+}
+
+/// Returns whether the CFG of `function` has a cycle, which is a loop still to be unrolled.
+fn has_loop(function: &Function) -> bool {
+    fn visit(
+        function: &Function,
+        block: BasicBlockId,
+        visiting: &mut BTreeSet<BasicBlockId>,
+        visited: &mut BTreeSet<BasicBlockId>,
+    ) -> bool {
+        if visiting.contains(&block) {
+            return true;
+        }
+        if !visited.insert(block) {
+            return false;
+        }
+        visiting.insert(block);
+        let found = function.dfg[block]
+            .successors()
+            .any(|successor| visit(function, successor, visiting, visited));
+        visiting.remove(&block);
+        found
+    }
+    visit(function, function.entry_block(), &mut BTreeSet::new(), &mut BTreeSet::new())
+}
+
+/// Estimates the cost an instruction adds to a function in the given runtime.
+fn instruction_cost(instruction: &Instruction, runtime: RuntimeType) -> usize {
+    match runtime {
+        RuntimeType::Acir => acir_instruction_cost(instruction),
+        RuntimeType::Brillig => brillig_instruction_cost(instruction),
+    }
+}
+
+/// Estimates the number of ACIR opcodes an instruction contributes to a circuit. Memory
+/// instructions and side effect conditions are removed by the later passes, while comparisons,
+/// bitwise operations and integer division need range checks on top of their arithmetic.
+fn acir_instruction_cost(instruction: &Instruction) -> usize {
+    match instruction {
+        Instruction::Binary(binary) => match binary.operator {
+            BinaryOp::Add | BinaryOp::Sub | BinaryOp::Mul | BinaryOp::Eq => 1,
+            BinaryOp::Lt | BinaryOp::And | BinaryOp::Or | BinaryOp::Xor => 4,
+            BinaryOp::Div | BinaryOp::Mod => 8,
+        },
+        Instruction::Not(_)
+        | Instruction::Truncate { .. }
+        | Instruction::Constrain(..)
+        | Instruction::Call { .. }
+        | Instruction::ArrayGet { .. }
+        | Instruction::ArraySet { .. } => 1,
+        Instruction::Cast(..)
+        | Instruction::Allocate
+        | Instruction::Load { .. }
+        | Instruction::Store { .. }
+        | Instruction::EnableSideEffects { .. } => 0,
+    }
+}
+
+/// Estimates the number of Brillig opcodes an instruction is compiled into. Unconstrained code
+/// keeps its memory instructions, calls move their arguments into place, and array accesses
+/// compute the address of the element first, while side effect conditions are ignored.
+fn brillig_instruction_cost(instruction: &Instruction) -> usize {
+    match instruction {
+        Instruction::Call { arguments, .. } => 2 + arguments.len(),
+        Instruction::ArrayGet { .. } => 2,
+        Instruction::ArraySet { .. } => 3,
+        Instruction::EnableSideEffects { .. } => 0,
+        Instruction::Binary(_)
+        | Instruction::Not(_)
+        | Instruction::Truncate { .. }
+        | Instruction::Constrain(..)
+        | Instruction::Cast(..)
+        | Instruction::Allocate
+        | Instruction::Load { .. }
+        | Instruction::Store { .. } => 1,
+    }
+}
+
+impl<'costs> InlineContext<'costs> {
     /// Create a new context object for the function inlining pass.
     /// This starts off with an empty mapping of instructions for main's parameters.
     /// The function being inlined into will always be the main function, although it is
     /// actually a copy that is created in case the original main is still needed from a function
     /// that could not be inlined calling it.
-    fn new(ssa: &Ssa, entry_point: FunctionId) -> InlineContext {
+    fn new(
+        ssa: &Ssa,
+        entry_point: FunctionId,
+        costs: &'costs InlineCosts,
+        inline_fold_boundaries: bool,
+    ) -> Self {
         let source = &ssa.functions[&entry_point];
-        let builder = FunctionBuilder::new(source.name().to_owned(), entry_point, source.runtime());
-        Self { builder, recursion_level: 0, entry_point, call_stack: CallStack::new() }
+        let mut builder =
+            FunctionBuilder::new(source.name().to_owned(), entry_point, source.runtime());
+        builder.set_inline_type(source.inline_type());
+        Self {
+            builder,
+            recursion_level: 0,
+            entry_point,
+            call_stack: CallStack::new(),
+            inline_stack: Vec::new(),
+            costs,
+            inline_fold_boundaries,
+            error: None,
+        }
     }
 
     /// Start inlining the entry point function and all functions reachable from it.
-    fn inline_all(mut self, ssa: &Ssa) -> Function {
+    fn inline_all(mut self, ssa: &Ssa) -> Result<Function, RuntimeError> {
         let entry_point = &ssa.functions[&self.entry_point];
 
         let mut context = PerFunctionContext::new(&mut self, entry_point);
@@ -140,10 +405,14 @@ impl InlineContext {
         context.blocks.insert(context.source_function.entry_block(), entry_block);
         context.inline_blocks(ssa);
 
+        if let Some(error) = self.error {
+            return Err(error);
+        }
+
         // Finally, we should have 1 function left representing the inlined version of the target function.
         let mut new_ssa = self.builder.finish();
         assert_eq!(new_ssa.functions.len(), 1);
-        new_ssa.functions.pop_first().unwrap().1
+        Ok(new_ssa.functions.pop_first().unwrap().1)
     }
 
     /// Inlines a function into the current function and returns the translated return values
@@ -162,6 +431,7 @@ impl InlineContext {
             );
         }
 
+        self.inline_stack.push(id);
         let source_function = &ssa.functions[&id];
         let mut context = PerFunctionContext::new(self, source_function);
 
@@ -173,17 +443,21 @@ impl InlineContext {
         context.blocks.insert(source_function.entry_block(), current_block);
 
         let return_values = context.inline_blocks(ssa);
+        self.inline_stack.pop();
         self.recursion_level -= 1;
         return_values
     }
 }
 
-impl<'function> PerFunctionContext<'function> {
+impl<'function, 'costs> PerFunctionContext<'function, 'costs> {
     /// Create a new PerFunctionContext from the source function.
     /// The value and block mappings for this context are initially empty except
     /// for containing the mapping between parameters in the source_function and
     /// the arguments of the destination function.
-    fn new(context: &'function mut InlineContext, source_function: &'function Function) -> Self {
+    fn new(
+        context: &'function mut InlineContext<'costs>,
+        source_function: &'function Function,
+    ) -> Self {
         Self {
             context,
             source_function,
@@ -344,17 +618,57 @@ impl<'function> PerFunctionContext<'function> {
         for id in block.instructions() {
             match &self.source_function.dfg[*id] {
                 Instruction::Call { func, arguments } => match self.get_function(*func) {
-                    Some(function) => match ssa.functions[&function].runtime() {
-                        RuntimeType::Acir => self.inline_function(ssa, *id, function, arguments),
-                        RuntimeType::Brillig => self.push_instruction(*id),
-                    },
-                    None => self.push_instruction(*id),
+                    Some(function) if self.should_inline_call(ssa, function, *id) => {
+                        self.inline_function(ssa, *id, function, arguments);
+                    }
+                    _ => self.push_instruction(*id),
                 },
                 _ => self.push_instruction(*id),
             }
         }
     }
 
+    /// Calls to constrained functions are inlined, as a circuit can't call functions, although
+    /// calls to fold boundaries from constrained code are only inlined by the second pass.
+    /// Calls to unconstrained functions from constrained code are kept so that they run in the
+    /// Brillig VM. Between unconstrained functions, calls to a callee marked `#[inline(always)]`
+    /// must be inlined, those to one marked `#[inline(never)]` are kept, and otherwise the cost
+    /// model of [`InlineCosts`] decides. Recursive calls to an unconstrained callee are kept, as
+    /// inlining them would never finish, which is an error if the callee must be inlined.
+    fn should_inline_call(&mut self, ssa: &Ssa, callee: FunctionId, call: InstructionId) -> bool {
+        let function = &ssa.functions[&callee];
+        let entry_point = self.context.entry_point;
+        match (ssa.functions[&entry_point].runtime(), function.runtime()) {
+            (RuntimeType::Acir, RuntimeType::Acir) => {
+                self.context.inline_fold_boundaries
+                    || !self.context.costs.fold_boundaries.contains(&callee)
+            }
+            // Unconstrained code can't keep calls to constrained functions, whose fold boundaries
+            // are only needed by constrained code
+            (RuntimeType::Brillig, RuntimeType::Acir) => true,
+            (RuntimeType::Acir, RuntimeType::Brillig) => false,
+            (RuntimeType::Brillig, RuntimeType::Brillig) => {
+                let recursive =
+                    callee == entry_point || self.context.inline_stack.contains(&callee);
+                match function.inline_type() {
+                    InlineType::Always if recursive => {
+                        let mut call_stack = self.context.call_stack.clone();
+                        call_stack.append(self.source_function.dfg.get_call_stack(call));
+                        let name = function.name().to_owned();
+                        let error = RuntimeError::RecursiveInlineAlways { name, call_stack };
+                        self.context.error.get_or_insert(error);
+                        false
+                    }
+                    InlineType::Always => true,
+                    InlineType::Never => false,
+                    InlineType::Default => {
+                        !recursive && self.context.costs.is_worth_inlining(callee)
+                    }
+                }
+            }
+        }
+    }
+
     /// Inline a function call and remember the inlined return values in the values map
     fn inline_function(
         &mut self,
@@ -504,15 +818,18 @@ impl<'function> PerFunctionContext<'function> {
 #[cfg(test)]
 mod test {
     use acvm::FieldElement;
+    use noirc_frontend::token::InlineType;
 
+    use crate::errors::RuntimeError;
     use crate::ssa::{
         function_builder::FunctionBuilder,
         ir::{
             basic_block::BasicBlockId,
-            function::RuntimeType,
-            instruction::{BinaryOp, Intrinsic, TerminatorInstruction},
+            function::{Function, FunctionId, RuntimeType},
+            instruction::{BinaryOp, Instruction, Intrinsic, TerminatorInstruction},
             map::Id,
             types::Type,
+            value::Value,
         },
     };
 
@@ -543,7 +860,7 @@ mod test {
         let ssa = builder.finish();
         assert_eq!(ssa.functions.len(), 2);
 
-        let inlined = ssa.inline_functions();
+        let inlined = ssa.inline_functions().unwrap();
         assert_eq!(inlined.functions.len(), 1);
     }
 
@@ -609,7 +926,7 @@ mod test {
         let ssa = builder.finish();
         assert_eq!(ssa.functions.len(), 4);
 
-        let inlined = ssa.inline_functions();
+        let inlined = ssa.inline_functions().unwrap();
         assert_eq!(inlined.functions.len(), 1);
     }
 
@@ -683,7 +1000,7 @@ mod test {
         //   b6():
         //     return Field 120
         // }
-        let inlined = ssa.inline_functions();
+        let inlined = ssa.inline_functions().unwrap();
         assert_eq!(inlined.functions.len(), 1);
 
         let main = inlined.main();
@@ -766,7 +1083,7 @@ mod test {
         builder.switch_to_block(join_block);
         builder.terminate_with_return(vec![join_param]);
 
-        let ssa = builder.finish().inline_functions();
+        let ssa = builder.finish().inline_functions().unwrap();
         // Expected result:
         // fn main f3 {
         //   b0(v0: u1):
@@ -782,4 +1099,401 @@ mod test {
         let main = ssa.main();
         assert_eq!(main.reachable_blocks().len(), 4);
     }
+
+    /// Returns the functions called by `function`, in the order they are called
+    fn called_functions(function: &Function) -> Vec<FunctionId> {
+        let block = &function.dfg[function.entry_block()];
+        let calls = block.instructions().iter().filter_map(|id| match &function.dfg[*id] {
+            Instruction::Call { func, .. } => match function.dfg[*func] {
+                Value::Function(id) => Some(id),
+                _ => None,
+            },
+            _ => None,
+        });
+        calls.collect()
+    }
+
+    #[test]
+    fn inline_hints_in_unconstrained_code() {
+        // brillig fn main f0 {
+        //   b0(v0: Field):
+        //     v1 = call always(v0)
+        //     v2 = call never(v1)
+        //     return v2
+        // }
+        // #[inline(always)]
+        // brillig fn always f1 {
+        //   b0(v0: Field):
+        //     v1 = add v0, Field 1
+        //     return v1
+        // }
+        // #[inline(never)]
+        // brillig fn never f2 {
+        //   b0(v0: Field):
+        //     v1 = add v0, Field 2
+        //     return v1
+        // }
+        let main_id = Id::test_new(0);
+        let always_id = Id::test_new(1);
+        let never_id = Id::test_new(2);
+        let mut builder = FunctionBuilder::new("main".into(), main_id, RuntimeType::Brillig);
+        let v0 = builder.add_parameter(Type::field());
+        let mut result = v0;
+        for id in [always_id, never_id] {
+            let function = builder.import_function(id);
+            result = builder.insert_call(function, vec![result], vec![Type::field()])[0];
+        }
+        builder.terminate_with_return(vec![result]);
+
+        let functions = [
+            ("always", always_id, InlineType::Always, 1u128),
+            ("never", never_id, InlineType::Never, 2),
+        ];
+        for (name, id, inline_type, constant) in functions {
+            builder.new_brillig_function(name.into(), id);
+            builder.set_inline_type(inline_type);
+            let v0 = builder.add_parameter(Type::field());
+            let constant = builder.field_constant(constant);
+            let v1 = builder.insert_binary(v0, BinaryOp::Add, constant);
+            builder.terminate_with_return(vec![v1]);
+        }
+
+        // Expected result:
+        // brillig fn main f0 {
+        //   b0(v0: Field):
+        //     v4 = add v0, Field 1
+        //     v5 = call never(v4)
+        //     return v5
+        // }
+        let ssa = builder.finish().inline_functions().unwrap();
+        assert_eq!(ssa.functions.len(), 3);
+        assert_eq!(called_functions(ssa.main()), vec![never_id]);
+    }
+
+    #[test]
+    fn recursive_inline_always_functions_are_an_error() {
+        // brillig fn main f0 {
+        //   b0(v0: Field):
+        //     v1 = call recursive(v0)
+        //     return v1
+        // }
+        // #[inline(always)]
+        // brillig fn recursive f1 {
+        //   b0(v0: Field):
+        //     v1 = call recursive(v0)
+        //     return v1
+        // }
+        let main_id = Id::test_new(0);
+        let recursive_id = Id::test_new(1);
+        let mut builder = FunctionBuilder::new("main".into(), main_id, RuntimeType::Brillig);
+        let v0 = builder.add_parameter(Type::field());
+        let recursive = builder.import_function(recursive_id);
+        let v1 = builder.insert_call(recursive, vec![v0], vec![Type::field()])[0];
+        builder.terminate_with_return(vec![v1]);
+
+        builder.new_brillig_function("recursive".into(), recursive_id);
+        builder.set_inline_type(InlineType::Always);
+        let v0 = builder.add_parameter(Type::field());
+        let recursive = builder.import_function(recursive_id);
+        let v1 = builder.insert_call(recursive, vec![v0], vec![Type::field()])[0];
+        builder.terminate_with_return(vec![v1]);
+
+        let error = builder.finish().inline_functions().unwrap_err();
+        assert!(matches!(
+            error,
+            RuntimeError::RecursiveInlineAlways { name, .. } if name == "recursive"
+        ));
+    }
+
+    #[test]
+    fn fold_boundaries_are_inlined_once_compiled() {
+        // acir fn main f0 {
+        //   b0(v0: Field):
+        //     v1 = call double(v0)
+        //     v2 = call double(v1)
+        //     return v2
+        // }
+        // #[inline(never)]
+        // acir fn double f1 {
+        //   b0(v0: Field):
+        //     v1 = call add(v0, v0)
+        //     return v1
+        // }
+        // acir fn add f2 {
+        //   b0(v0: Field, v1: Field):
+        //     v2 = add v0, v1
+        //     return v2
+        // }
+        let main_id = Id::test_new(0);
+        let double_id = Id::test_new(1);
+        let add_id = Id::test_new(2);
+        let mut builder = FunctionBuilder::new("main".into(), main_id, RuntimeType::Acir);
+        let v0 = builder.add_parameter(Type::field());
+        let double = builder.import_function(double_id);
+        let v1 = builder.insert_call(double, vec![v0], vec![Type::field()])[0];
+        let v2 = builder.insert_call(double, vec![v1], vec![Type::field()])[0];
+        builder.terminate_with_return(vec![v2]);
+
+        builder.new_function("double".into(), double_id);
+        builder.set_inline_type(InlineType::Never);
+        let v0 = builder.add_parameter(Type::field());
+        let add = builder.import_function(add_id);
+        let v1 = builder.insert_call(add, vec![v0, v0], vec![Type::field()])[0];
+        builder.terminate_with_return(vec![v1]);
+
+        builder.new_function("add".into(), add_id);
+        let v0 = builder.add_parameter(Type::field());
+        let v1 = builder.add_parameter(Type::field());
+        let v2 = builder.insert_binary(v0, BinaryOp::Add, v1);
+        builder.terminate_with_return(vec![v2]);
+
+        // `double` is kept, with `add` inlined into it, until fold boundaries are inlined
+        let ssa = builder.finish().inline_functions().unwrap();
+        assert_eq!(ssa.functions.len(), 2);
+        assert_eq!(called_functions(ssa.main()), vec![double_id, double_id]);
+        assert!(called_functions(&ssa.functions[&double_id]).is_empty());
+
+        // Expected result:
+        // acir fn main f0 {
+        //   b0(v0: Field):
+        //     v4 = add v0, v0
+        //     v5 = add v4, v4
+        //     return v5
+        // }
+        let ssa = ssa.inline_fold_boundaries().unwrap();
+        assert_eq!(ssa.functions.len(), 1);
+        let main = ssa.main();
+        let instructions = main.dfg[main.entry_block()].instructions();
+        assert_eq!(instructions.len(), 2);
+        assert!(instructions.iter().all(|id| matches!(main.dfg[*id], Instruction::Binary(_))));
+    }
+
+    #[test]
+    fn fold_boundaries_are_inlined_into_unconstrained_code() {
+        // brillig fn main f0 {
+        //   b0(v0: Field):
+        //     v1 = call double(v0)
+        //     return v1
+        // }
+        // #[inline(never)]
+        // acir fn double f1 {
+        //   b0(v0: Field):
+        //     v1 = add v0, v0
+        //     return v1
+        // }
+        let main_id = Id::test_new(0);
+        let double_id = Id::test_new(1);
+        let mut builder = FunctionBuilder::new("main".into(), main_id, RuntimeType::Brillig);
+        let v0 = builder.add_parameter(Type::field());
+        let double = builder.import_function(double_id);
+        let v1 = builder.insert_call(double, vec![v0], vec![Type::field()])[0];
+        builder.terminate_with_return(vec![v1]);
+
+        builder.new_function("double".into(), double_id);
+        builder.set_inline_type(InlineType::Never);
+        let v0 = builder.add_parameter(Type::field());
+        let v1 = builder.insert_binary(v0, BinaryOp::Add, v0);
+        builder.terminate_with_return(vec![v1]);
+
+        let ssa = builder.finish().inline_functions().unwrap();
+        assert!(called_functions(ssa.main()).is_empty());
+        let ssa = ssa.inline_fold_boundaries().unwrap();
+        assert_eq!(ssa.functions.len(), 1);
+    }
+
+    #[test]
+    fn inline_always_is_ignored_across_runtimes() {
+        // acir fn main f0 {
+        //   b0(v0: Field):
+        //     v1 = call always(v0)
+        //     return v1
+        // }
+        // #[inline(always)]
+        // brillig fn always f1 {
+        //   b0(v0: Field):
+        //     return v0
+        // }
+        let main_id = Id::test_new(0);
+        let always_id = Id::test_new(1);
+        let mut builder = FunctionBuilder::new("main".into(), main_id, RuntimeType::Acir);
+        let v0 = builder.add_parameter(Type::field());
+        let always = builder.import_function(always_id);
+        let v1 = builder.insert_call(always, vec![v0], vec![Type::field()])[0];
+        builder.terminate_with_return(vec![v1]);
+
+        builder.new_brillig_function("always".into(), always_id);
+        builder.set_inline_type(InlineType::Always);
+        let v0 = builder.add_parameter(Type::field());
+        builder.terminate_with_return(vec![v0]);
+
+        // The call from constrained code must be kept for the function to run in the Brillig VM
+        let ssa = builder.finish().inline_functions().unwrap();
+        assert_eq!(called_functions(ssa.main()), vec![always_id]);
+    }
+
+    #[test]
+    fn inlining_cost_model_in_unconstrained_code() {
+        // brillig fn main f0 {
+        //   b0(v0: Field):
+        //     v1 = call small(v0)
+        //     v2 = call small(v1)
+        //     v3 = call large(v2)
+        //     v4 = call large(v3)
+        //     v5 = call large_once(v4)
+        //     return v5
+        // }
+        // brillig fn small f1 {
+        //   b0(v0: Field):
+        //     v1 = mul v0, v0
+        //     return v1
+        // }
+        // brillig fn large f2 {
+        //   b0(v0: Field):
+        //     v1 = div v0, Field 3
+        //     ... 120 divisions in total
+        //     return v20
+        // }
+        // brillig fn large_once f3 (the same as large)
+        let main_id = Id::test_new(0);
+        let small_id = Id::test_new(1);
+        let large_id = Id::test_new(2);
+        let large_once_id = Id::test_new(3);
+        let mut builder = FunctionBuilder::new("main".into(), main_id, RuntimeType::Brillig);
+        let v0 = builder.add_parameter(Type::field());
+        let mut result = v0;
+        for id in [small_id, small_id, large_id, large_id, large_once_id] {
+            let function = builder.import_function(id);
+            result = builder.insert_call(function, vec![result], vec![Type::field()])[0];
+        }
+        builder.terminate_with_return(vec![result]);
+
+        builder.new_brillig_function("small".into(), small_id);
+        let v0 = builder.add_parameter(Type::field());
+        let v1 = builder.insert_binary(v0, BinaryOp::Mul, v0);
+        builder.terminate_with_return(vec![v1]);
+
+        for (name, id) in [("large", large_id), ("large_once", large_once_id)] {
+            builder.new_brillig_function(name.into(), id);
+            let mut value = builder.add_parameter(Type::field());
+            let three = builder.field_constant(3u128);
+            for _ in 0..120 {
+                value = builder.insert_binary(value, BinaryOp::Div, three);
+            }
+            builder.terminate_with_return(vec![value]);
+        }
+
+        // Duplicating `large` would grow the program more than keeping its calls, while `small` is
+        // cheap enough to copy everywhere and `large_once` has no other caller
+        let ssa = builder.finish().inline_functions().unwrap();
+        assert_eq!(called_functions(ssa.main()), vec![large_id, large_id]);
+    }
+
+    #[test]
+    fn inlining_cost_model_in_constrained_code() {
+        // acir fn main f0 {
+        //   b0(v0: Field):
+        //     v1 = call large(v0)
+        //     v2 = call large(v1)
+        //     v3 = call asserted(v2)
+        //     v4 = call asserted(v3)
+        //     return v4
+        // }
+        // acir fn large f1 {
+        //   b0(v0: Field):
+        //     v1 = div v0, Field 3
+        //     ... 20 divisions in total
+        //     return v20
+        // }
+        // acir fn asserted f2 {
+        //   b0(v0: Field):
+        //     call assert_constant(v0)
+        //     v1 = div v0, Field 3
+        //     ... 20 divisions in total
+        //     return v20
+        // }
+        let main_id = Id::test_new(0);
+        let large_id = Id::test_new(1);
+        let asserted_id = Id::test_new(2);
+        let mut builder = FunctionBuilder::new("main".into(), main_id, RuntimeType::Acir);
+        let v0 = builder.add_parameter(Type::field());
+        let mut result = v0;
+        for id in [large_id, large_id, asserted_id, asserted_id] {
+            let function = builder.import_function(id);
+            result = builder.insert_call(function, vec![result], vec![Type::field()])[0];
+        }
+        builder.terminate_with_return(vec![result]);
+
+        for (name, id) in [("large", large_id), ("asserted", asserted_id)] {
+            builder.new_function(name.into(), id);
+            let mut value = builder.add_parameter(Type::field());
+            if id == asserted_id {
+                let assert_constant = builder.import_intrinsic_id(Intrinsic::AssertConstant);
+                builder.insert_call(assert_constant, vec![value], vec![]);
+            }
+            let three = builder.field_constant(3u128);
+            for _ in 0..20 {
+                value = builder.insert_binary(value, BinaryOp::Div, three);
+            }
+            builder.terminate_with_return(vec![value]);
+        }
+
+        // `large` is compiled once as a fold boundary rather than copied into each caller, while
+        // `asserted` can only be compiled once its argument is known
+        let ssa = builder.finish().inline_functions().unwrap();
+        assert_eq!(ssa.functions.len(), 2);
+        assert_eq!(called_functions(ssa.main()), vec![large_id, large_id]);
+
+        let ssa = ssa.inline_fold_boundaries().unwrap();
+        assert_eq!(ssa.functions.len(), 1);
+        assert!(called_functions(ssa.main()).is_empty());
+    }
+
+    #[test]
+    fn unreachable_call_sites_are_not_counted() {
+        // brillig fn main f0 {
+        //   b0(v0: Field):
+        //     v1 = call large(v0)
+        //     return v1
+        // }
+        // brillig fn unused f1 {
+        //   b0(v0: Field):
+        //     v1 = call large(v0)
+        //     v2 = call large(v1)
+        //     return v2
+        // }
+        // brillig fn large f2 {
+        //   b0(v0: Field):
+        //     v1 = div v0, Field 3
+        //     ... 120 divisions in total
+        //     return v120
+        // }
+        let main_id = Id::test_new(0);
+        let unused_id = Id::test_new(1);
+        let large_id = Id::test_new(2);
+        let mut builder = FunctionBuilder::new("main".into(), main_id, RuntimeType::Brillig);
+        let v0 = builder.add_parameter(Type::field());
+        let large = builder.import_function(large_id);
+        let v1 = builder.insert_call(large, vec![v0], vec![Type::field()])[0];
+        builder.terminate_with_return(vec![v1]);
+
+        builder.new_brillig_function("unused".into(), unused_id);
+        let mut result = builder.add_parameter(Type::field());
+        for _ in 0..2 {
+            let large = builder.import_function(large_id);
+            result = builder.insert_call(large, vec![result], vec![Type::field()])[0];
+        }
+        builder.terminate_with_return(vec![result]);
+
+        builder.new_brillig_function("large".into(), large_id);
+        let mut value = builder.add_parameter(Type::field());
+        let three = builder.field_constant(3u128);
+        for _ in 0..120 {
+            value = builder.insert_binary(value, BinaryOp::Div, three);
+        }
+        builder.terminate_with_return(vec![value]);
+
+        // `main` is the only caller of `large` in the program, so its call is inlined
+        let ssa = builder.finish().inline_functions().unwrap();
+        assert!(called_functions(ssa.main()).is_empty());
+    }
 }
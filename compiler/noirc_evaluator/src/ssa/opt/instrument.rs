@@ -0,0 +1,74 @@
+//! The block instrumentation pass inserts a call to the [`BLOCK_COUNTER_ORACLE`] foreign function
+//! at the start of each reachable block, passing it a label of the form `function:block`.
+//!
+//! In Brillig functions each of these calls is resolved by the executor every time its block is
+//! entered, counting how often the block ran. ACIR functions cannot make foreign calls, so once
+//! they have been flattened these calls are instead replaced during ACIR generation with a witness
+//! holding the predicate under which the block's instructions were executed.
+use std::rc::Rc;
+
+use acvm::FieldElement;
+
+use crate::ssa::{
+    ir::{
+        dfg::{CallStack, DataFlowGraph},
+        function::Function,
+        instruction::Instruction,
+        types::Type,
+        value::ValueId,
+    },
+    ssa_gen::Ssa,
+};
+
+/// The name of the foreign function called at the start of every instrumented block
+pub const BLOCK_COUNTER_ORACLE: &str = "__block_counter";
+
+impl Ssa {
+    /// Inserts a block counter at the start of each reachable block in every function.
+    ///
+    /// This must be run before flattening so that the counters of ACIR functions are
+    /// attributed to the blocks in which they were originally written.
+    pub(crate) fn instrument_blocks(mut self) -> Ssa {
+        for function in self.functions.values_mut() {
+            instrument_blocks(function);
+        }
+        self
+    }
+}
+
+fn instrument_blocks(function: &mut Function) {
+    let counter = function.dfg.import_foreign_function(BLOCK_COUNTER_ORACLE);
+
+    for block in function.reachable_blocks() {
+        let label = format!("{}:{block}", function.name());
+        let label = make_label(function, &label);
+
+        let call = Instruction::Call { func: counter, arguments: vec![label] };
+        function.dfg.insert_instruction_and_results(call, block, Some(vec![]), CallStack::new());
+
+        // The call was appended to the end of the block so it must be moved to the front
+        let instructions = function.dfg[block].instructions_mut();
+        let call = instructions.pop().expect("ICE: the counter call was just inserted");
+        instructions.insert(0, call);
+    }
+}
+
+/// Creates a constant byte array holding the given label
+fn make_label(function: &mut Function, label: &str) -> ValueId {
+    let bytes = label
+        .bytes()
+        .map(|byte| function.dfg.make_constant(FieldElement::from(byte as u128), Type::unsigned(8)))
+        .collect();
+    let typ = Type::Array(Rc::new(vec![Type::unsigned(8)]), label.len());
+    function.dfg.make_array(bytes, typ)
+}
+
+/// Reads back a label created by the instrumentation pass from its constant byte array
+pub(crate) fn decode_label(dfg: &DataFlowGraph, label: ValueId) -> Option<String> {
+    let (bytes, _) = dfg.get_array_constant(label)?;
+    let bytes = bytes
+        .iter()
+        .map(|byte| dfg.get_numeric_constant(*byte).map(|byte| byte.to_u128() as u8))
+        .collect::<Option<Vec<_>>>()?;
+    String::from_utf8(bytes).ok()
+}
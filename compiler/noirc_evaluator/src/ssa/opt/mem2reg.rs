@@ -40,9 +40,11 @@
 //!     - Conservatively mark every alias in the block to `Unknown`.
 //!   - Additionally, if there were no Loads to any alias of the address between this Store and
 //!     the previous Store to the same address, the previous store can be removed.
+//! - On `Instruction::ArrayGet { array, .. }`:
+//!   - If the result is a reference, mark it as an alias of each reference the array may hold.
 //! - On `Instruction::Call { arguments }`:
-//!   - If any argument of the call is a reference, set the value of each alias of that
-//!     reference to `Unknown`
+//!   - If any argument of the call is a reference, or an array holding references, set the value
+//!     of each alias of those references to `Unknown`
 //!   - Any builtin functions that may return aliases if their input also contains a
 //!     reference should be tracked. Examples: `slice_push_back`, `slice_insert`, `slice_remove`, etc.
 //!
@@ -74,7 +76,6 @@ use crate::ssa::{
         function_inserter::FunctionInserter,
         instruction::{Instruction, InstructionId, TerminatorInstruction},
         post_order::PostOrder,
-        types::Type,
         value::ValueId,
     },
     ssa_gen::Ssa,
@@ -270,14 +271,24 @@ impl<'f> PerFunctionContext<'f> {
 
                     if let Some(aliases) = references.aliases.get_mut(&expression) {
                         aliases.insert(result);
+                    } else if let Some((elements, _)) =
+                        self.inserter.function.dfg.get_array_constant(array)
+                    {
+                        let mut aliases = references.collect_all_aliases(elements);
+                        aliases.insert(result);
+                        references.aliases.insert(expression.clone(), aliases);
                     }
+
+                    // The element may be any of the references held by the array, so storing to
+                    // it must invalidate each of them.
+                    references.expressions.insert(result, expression);
                 }
             }
             Instruction::ArraySet { array, value, .. } => {
                 references.mark_value_used(*array, self.inserter.function);
                 let element_type = self.inserter.function.dfg.type_of_value(*value);
 
-                if Self::contains_references(&element_type) {
+                if element_type.contains_reference() {
                     let result = self.inserter.function.dfg.instruction_results(instruction)[0];
                     let array = self.inserter.function.dfg.resolve(*array);
 
@@ -309,7 +320,7 @@ impl<'f> PerFunctionContext<'f> {
 
     fn check_array_aliasing(&self, references: &mut Block, array: ValueId) {
         if let Some((elements, typ)) = self.inserter.function.dfg.get_array_constant(array) {
-            if Self::contains_references(&typ) {
+            if typ.contains_reference() {
                 // TODO: Check if type directly holds references or holds arrays that hold references
                 let expr = Expression::ArrayElement(Box::new(Expression::Other(array)));
                 references.expressions.insert(array, expr.clone());
@@ -322,17 +333,6 @@ impl<'f> PerFunctionContext<'f> {
         }
     }
 
-    fn contains_references(typ: &Type) -> bool {
-        match typ {
-            Type::Numeric(_) => false,
-            Type::Function => false,
-            Type::Reference => true,
-            Type::Array(elements, _) | Type::Slice(elements) => {
-                elements.iter().any(Self::contains_references)
-            }
-        }
-    }
-
     fn set_aliases(&self, references: &mut Block, address: ValueId, new_aliases: AliasSet) {
         let expression =
             references.expressions.entry(address).or_insert(Expression::Other(address));
@@ -340,10 +340,23 @@ impl<'f> PerFunctionContext<'f> {
         *aliases = new_aliases;
     }
 
+    /// Mark each reference in `values`, including those held by arrays, as having an unknown
+    /// value since they may be stored to by a call or after a return.
     fn mark_all_unknown(&self, values: &[ValueId], references: &mut Block) {
         for value in values {
-            if self.inserter.function.dfg.value_is_reference(*value) {
-                let value = self.inserter.function.dfg.resolve(*value);
+            let value = self.inserter.function.dfg.resolve(*value);
+            let typ = self.inserter.function.dfg.type_of_value(value);
+
+            if !typ.contains_reference() {
+                continue;
+            }
+
+            if let Some((elements, _)) = self.inserter.function.dfg.get_array_constant(value) {
+                let elements: Vec<_> = elements.into_iter().collect();
+                self.mark_all_unknown(&elements, references);
+            } else {
+                // For an array which isn't a constant this invalidates the references it is
+                // known to hold, or every reference if those aren't known.
                 references.set_unknown(value);
                 references.mark_value_used(value, self.inserter.function);
             }
@@ -528,6 +541,67 @@ mod tests {
         assert_eq!(ret_val_id, allocate_id);
     }
 
+    #[test]
+    fn calls_invalidate_references_held_by_arrays() {
+        // fn func {
+        //   b0():
+        //     v0 = allocate
+        //     store Field 1 at v0
+        //     call f0([v0])
+        //     v1 = load v0
+        //     return v1
+        // }
+
+        let func_id = Id::test_new(0);
+        let mut builder = FunctionBuilder::new("func".into(), func_id, RuntimeType::Brillig);
+        let v0 = builder.insert_allocate();
+        let one = builder.field_constant(FieldElement::one());
+        builder.insert_store(v0, one);
+
+        let array_type = Type::Array(Rc::new(vec![Type::Reference]), 1);
+        let array = builder.array_constant(vector![v0], array_type);
+        let f0 = builder.import_intrinsic_id(Intrinsic::AssertConstant);
+        builder.insert_call(f0, vec![array], vec![]);
+
+        let v1 = builder.insert_load(v0, Type::field());
+        builder.terminate_with_return(vec![v1]);
+
+        let ssa = builder.finish().mem2reg();
+
+        let func = ssa.main();
+        let block_id = func.entry_block();
+
+        // The call may have stored to v0 through the array, so neither the store before it nor
+        // the load after it can be removed
+        assert_eq!(count_stores(block_id, &func.dfg), 1);
+        assert_eq!(count_loads(block_id, &func.dfg), 1);
+    }
+
+    #[test]
+    fn returned_arrays_keep_stores_to_their_references() {
+        // fn func {
+        //   b0():
+        //     v0 = allocate
+        //     store Field 1 at v0
+        //     return [v0]
+        // }
+
+        let func_id = Id::test_new(0);
+        let mut builder = FunctionBuilder::new("func".into(), func_id, RuntimeType::Brillig);
+        let v0 = builder.insert_allocate();
+        let one = builder.field_constant(FieldElement::one());
+        builder.insert_store(v0, one);
+
+        let array_type = Type::Array(Rc::new(vec![Type::Reference]), 1);
+        let array = builder.array_constant(vector![v0], array_type);
+        builder.terminate_with_return(vec![array]);
+
+        let ssa = builder.finish().mem2reg();
+
+        let func = ssa.main();
+        assert_eq!(count_stores(func.entry_block(), &func.dfg), 1);
+    }
+
     fn count_stores(block: BasicBlockId, dfg: &DataFlowGraph) -> usize {
         dfg[block]
             .instructions()
@@ -0,0 +1,390 @@
+//! Identical function merging pass: replaces each function whose body is identical to that of
+//! another function with a single canonical copy.
+//!
+//! Monomorphization creates a function for each instantiation of a generic function, and these are
+//! often identical once converted to SSA. Two functions are identical when they have the same
+//! runtime and inline hint, and their reachable blocks have the same instructions, operating on
+//! values of the same types, regardless of their names and of the ids their values and blocks
+//! have. Functions which only differ by calling two functions which are identical themselves are
+//! identical as well, so functions are compared until no more of them are merged.
+//!
+//! Each call to a merged function is redirected to its canonical copy, which is `main` if `main`
+//! is one of the merged functions or the function with the lowest id otherwise. Running this pass
+//! before defunctionalization leaves fewer variants for each `apply` function to dispatch over.
+use noirc_frontend::token::InlineType;
+
+use crate::ssa::{
+    ir::{
+        basic_block::BasicBlockId,
+        function::{Function, FunctionId, RuntimeType},
+        instruction::{BinaryOp, Instruction, Intrinsic, TerminatorInstruction},
+        types::Type,
+        value::{Value, ValueId},
+    },
+    ssa_gen::Ssa,
+};
+use acvm::FieldElement;
+use fxhash::FxHashMap as HashMap;
+
+impl Ssa {
+    /// Merges the functions which are identical to one another into a single function.
+    pub(crate) fn merge_identical_functions(mut self) -> Ssa {
+        let merged = find_identical_functions(&self);
+        if merged.is_empty() {
+            return self;
+        }
+
+        for function in merged.keys() {
+            self.functions.remove(function);
+        }
+        for function in self.functions.values_mut() {
+            redirect_function_values(function, &merged);
+        }
+        self
+    }
+}
+
+/// Returns a map from each function which should be merged into another one to the canonical
+/// function it is merged into.
+fn find_identical_functions(ssa: &Ssa) -> HashMap<FunctionId, FunctionId> {
+    let mut merged = HashMap::default();
+    loop {
+        let mut canonical_functions: HashMap<FunctionShape, FunctionId> = HashMap::default();
+        let mut new_merged = HashMap::default();
+
+        // Visiting main first makes it the canonical copy of any function identical to it
+        let main = std::iter::once(ssa.main_id);
+        let others = ssa.functions.keys().copied().filter(|id| *id != ssa.main_id);
+        for id in main.chain(others) {
+            let shape = FunctionShape::new(&ssa.functions[&id], &merged);
+            let canonical = *canonical_functions.entry(shape).or_insert(id);
+            if canonical != id {
+                new_merged.insert(id, canonical);
+            }
+        }
+
+        // Merging functions can only make more functions identical, so once a round merges no
+        // more functions than the previous one every identical function has been found
+        if new_merged.len() == merged.len() {
+            return merged;
+        }
+        merged = new_merged;
+    }
+}
+
+/// Redirects each reference to a merged function in `function` to its canonical function.
+fn redirect_function_values(function: &mut Function, merged: &HashMap<FunctionId, FunctionId>) {
+    let redirected: Vec<_> = function
+        .dfg
+        .values_iter()
+        .filter_map(|(value, kind)| match kind {
+            Value::Function(id) => merged.get(id).map(|canonical| (value, *canonical)),
+            _ => None,
+        })
+        .collect();
+
+    for (value, canonical) in redirected {
+        let canonical = function.dfg.import_function(canonical);
+        function.dfg.set_value_from_id(value, canonical);
+    }
+}
+
+/// The structure of a function's body, in which its blocks and the values defined by them are
+/// numbered in the order they are first reached from the entry block instead of by their ids.
+#[derive(PartialEq, Eq, Hash)]
+struct FunctionShape {
+    runtime: RuntimeType,
+    inline_type: InlineType,
+    blocks: Vec<BlockShape>,
+}
+
+#[derive(PartialEq, Eq, Hash)]
+struct BlockShape {
+    parameters: Vec<(ValueShape, Type)>,
+    instructions: Vec<InstructionShape>,
+    terminator: TerminatorShape,
+    terminator_arguments: Vec<ValueShape>,
+}
+
+#[derive(PartialEq, Eq, Hash)]
+struct InstructionShape {
+    operation: Operation,
+    arguments: Vec<ValueShape>,
+    results: Vec<(ValueShape, Type)>,
+}
+
+/// An instruction without its arguments
+#[derive(PartialEq, Eq, Hash)]
+enum Operation {
+    Binary(BinaryOp),
+    Cast(Type),
+    Not,
+    Truncate { bit_size: u32, max_bit_size: u32 },
+    Constrain(Option<String>),
+    Call,
+    Allocate,
+    Load,
+    Store,
+    EnableSideEffects,
+    ArrayGet,
+    ArraySet,
+}
+
+/// A terminator instruction without its arguments, jumping to blocks given by their number
+#[derive(PartialEq, Eq, Hash)]
+enum TerminatorShape {
+    Jmp { destination: usize },
+    JmpIf { then_destination: usize, else_destination: usize },
+    Return,
+}
+
+#[derive(PartialEq, Eq, Hash)]
+enum ValueShape {
+    /// A block parameter or an instruction result, given by its number
+    Local(usize),
+    Constant(FieldElement, Type),
+    Array(Vec<ValueShape>, Type),
+    Function(FunctionId),
+    Intrinsic(Intrinsic),
+    ForeignFunction(String),
+}
+
+impl FunctionShape {
+    /// Returns the shape of `function`, in which calls to the merged functions are replaced with
+    /// calls to their canonical functions.
+    fn new(function: &Function, merged: &HashMap<FunctionId, FunctionId>) -> FunctionShape {
+        let mut context = ShapeContext {
+            function,
+            merged,
+            values: HashMap::default(),
+            blocks: HashMap::default(),
+            block_order: Vec::new(),
+        };
+        context.block_number(function.entry_block());
+
+        let mut blocks = Vec::new();
+        while let Some(block) = context.block_order.get(blocks.len()).copied() {
+            blocks.push(context.block_shape(block));
+        }
+
+        FunctionShape { runtime: function.runtime(), inline_type: function.inline_type(), blocks }
+    }
+}
+
+struct ShapeContext<'f> {
+    function: &'f Function,
+    merged: &'f HashMap<FunctionId, FunctionId>,
+
+    /// The number of each value defined in the function which has been reached
+    values: HashMap<ValueId, usize>,
+
+    /// The number of each block which has been reached
+    blocks: HashMap<BasicBlockId, usize>,
+
+    /// The blocks which have been reached, in the order they were reached
+    block_order: Vec<BasicBlockId>,
+}
+
+impl<'f> ShapeContext<'f> {
+    fn block_number(&mut self, block: BasicBlockId) -> usize {
+        if let Some(number) = self.blocks.get(&block) {
+            return *number;
+        }
+        let number = self.block_order.len();
+        self.blocks.insert(block, number);
+        self.block_order.push(block);
+        number
+    }
+
+    fn block_shape(&mut self, block: BasicBlockId) -> BlockShape {
+        let function = self.function;
+        let dfg = &function.dfg;
+        let parameters = dfg.block_parameters(block).iter();
+        let parameters = parameters.map(|parameter| self.typed_value_shape(*parameter)).collect();
+
+        let instructions = dfg[block].instructions().iter();
+        let instructions = instructions.map(|instruction| {
+            let results = dfg.instruction_results(*instruction).iter();
+            let results = results.map(|result| self.typed_value_shape(*result)).collect();
+
+            let instruction = &dfg[*instruction];
+            let mut arguments = Vec::new();
+            instruction.for_each_value(|value| arguments.push(self.value_shape(value)));
+            InstructionShape { operation: Operation::new(instruction), arguments, results }
+        });
+        let instructions = instructions.collect();
+
+        let terminator = dfg[block].unwrap_terminator();
+        let mut terminator_arguments = Vec::new();
+        terminator.for_each_value(|value| terminator_arguments.push(self.value_shape(value)));
+        let terminator = match terminator {
+            TerminatorInstruction::Jmp { destination, .. } => {
+                TerminatorShape::Jmp { destination: self.block_number(*destination) }
+            }
+            TerminatorInstruction::JmpIf { then_destination, else_destination, .. } => {
+                TerminatorShape::JmpIf {
+                    then_destination: self.block_number(*then_destination),
+                    else_destination: self.block_number(*else_destination),
+                }
+            }
+            TerminatorInstruction::Return { .. } => TerminatorShape::Return,
+        };
+
+        BlockShape { parameters, instructions, terminator, terminator_arguments }
+    }
+
+    /// Returns the shape of a value defined by a block or an instruction, along with its type
+    fn typed_value_shape(&mut self, value: ValueId) -> (ValueShape, Type) {
+        (self.value_shape(value), self.function.dfg.type_of_value(value))
+    }
+
+    fn value_shape(&mut self, value: ValueId) -> ValueShape {
+        let function = self.function;
+        let dfg = &function.dfg;
+        let value = dfg.resolve(value);
+        match &dfg[value] {
+            Value::Instruction { .. } | Value::Param { .. } => {
+                let next_number = self.values.len();
+                ValueShape::Local(*self.values.entry(value).or_insert(next_number))
+            }
+            Value::NumericConstant { constant, typ } => {
+                ValueShape::Constant(*constant, typ.clone())
+            }
+            Value::Array { array, typ } => {
+                let elements = array.iter().map(|element| self.value_shape(*element)).collect();
+                ValueShape::Array(elements, typ.clone())
+            }
+            Value::Function(id) => ValueShape::Function(*self.merged.get(id).unwrap_or(id)),
+            Value::Intrinsic(intrinsic) => ValueShape::Intrinsic(*intrinsic),
+            Value::ForeignFunction(name) => ValueShape::ForeignFunction(name.clone()),
+        }
+    }
+}
+
+impl Operation {
+    fn new(instruction: &Instruction) -> Operation {
+        match instruction {
+            Instruction::Binary(binary) => Operation::Binary(binary.operator),
+            Instruction::Cast(_, typ) => Operation::Cast(typ.clone()),
+            Instruction::Not(_) => Operation::Not,
+            Instruction::Truncate { bit_size, max_bit_size, .. } => {
+                Operation::Truncate { bit_size: *bit_size, max_bit_size: *max_bit_size }
+            }
+            Instruction::Constrain(_, _, message) => Operation::Constrain(message.clone()),
+            Instruction::Call { .. } => Operation::Call,
+            Instruction::Allocate => Operation::Allocate,
+            Instruction::Load { .. } => Operation::Load,
+            Instruction::Store { .. } => Operation::Store,
+            Instruction::EnableSideEffects { .. } => Operation::EnableSideEffects,
+            Instruction::ArrayGet { .. } => Operation::ArrayGet,
+            Instruction::ArraySet { .. } => Operation::ArraySet,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::ssa::{
+        function_builder::FunctionBuilder,
+        ir::{
+            function::{Function, FunctionId, RuntimeType},
+            instruction::{BinaryOp, Instruction},
+            map::Id,
+            types::Type,
+            value::Value,
+        },
+    };
+
+    /// Returns the functions called by `function`, in the order they are called
+    fn called_functions(function: &Function) -> Vec<FunctionId> {
+        let block = &function.dfg[function.entry_block()];
+        let calls = block.instructions().iter().filter_map(|id| match &function.dfg[*id] {
+            Instruction::Call { func, .. } => match function.dfg[*func] {
+                Value::Function(id) => Some(id),
+                _ => None,
+            },
+            _ => None,
+        });
+        calls.collect()
+    }
+
+    #[test]
+    fn merges_identical_functions() {
+        // fn main f0 {
+        //   b0(v0: Field):
+        //     v1 = call twice_u8(v0)
+        //     v2 = call twice_field(v1)
+        //     v3 = call add_one_u8(v2)
+        //     v4 = call add_one_field(v3)
+        //     v5 = call add_two(v4)
+        //     return v5
+        // }
+        // fn twice_u8 f1 {
+        //   b0(v0: Field):
+        //     v1 = call add_one_u8(v0)
+        //     v2 = call add_one_u8(v1)
+        //     return v2
+        // }
+        // fn twice_field f2, the same as twice_u8 but calling add_one_field
+        // fn add_one_u8 f3 {
+        //   b0(v0: Field):
+        //     v1 = add v0, Field 1
+        //     return v1
+        // }
+        // fn add_one_field f4, the same as add_one_u8
+        // fn add_two f5, the same as add_one_u8 but adding Field 2
+        let main_id = Id::test_new(0);
+        let twice_u8_id = Id::test_new(1);
+        let twice_field_id = Id::test_new(2);
+        let add_one_u8_id = Id::test_new(3);
+        let add_one_field_id = Id::test_new(4);
+        let add_two_id = Id::test_new(5);
+
+        let mut builder = FunctionBuilder::new("main".into(), main_id, RuntimeType::Acir);
+        let v0 = builder.add_parameter(Type::field());
+        let mut result = v0;
+        let callees = [twice_u8_id, twice_field_id, add_one_u8_id, add_one_field_id, add_two_id];
+        for id in callees {
+            let function = builder.import_function(id);
+            result = builder.insert_call(function, vec![result], vec![Type::field()])[0];
+        }
+        builder.terminate_with_return(vec![result]);
+
+        let twice = [
+            ("twice_u8", twice_u8_id, add_one_u8_id),
+            ("twice_field", twice_field_id, add_one_field_id),
+        ];
+        for (name, id, add_one_id) in twice {
+            builder.new_function(name.into(), id);
+            let mut result = builder.add_parameter(Type::field());
+            let add_one = builder.import_function(add_one_id);
+            for _ in 0..2 {
+                result = builder.insert_call(add_one, vec![result], vec![Type::field()])[0];
+            }
+            builder.terminate_with_return(vec![result]);
+        }
+
+        let add = [
+            ("add_one_u8", add_one_u8_id, 1u128),
+            ("add_one_field", add_one_field_id, 1),
+            ("add_two", add_two_id, 2),
+        ];
+        for (name, id, constant) in add {
+            builder.new_function(name.into(), id);
+            let v0 = builder.add_parameter(Type::field());
+            let constant = builder.field_constant(constant);
+            let v1 = builder.insert_binary(v0, BinaryOp::Add, constant);
+            builder.terminate_with_return(vec![v1]);
+        }
+
+        // Both versions of `add_one` are merged, after which both versions of `twice` are as well
+        let ssa = builder.finish().merge_identical_functions();
+        let functions: Vec<_> = ssa.functions.keys().copied().collect();
+        assert_eq!(functions, vec![main_id, twice_u8_id, add_one_u8_id, add_two_id]);
+        assert_eq!(
+            called_functions(ssa.main()),
+            vec![twice_u8_id, twice_u8_id, add_one_u8_id, add_one_u8_id, add_two_id]
+        );
+        assert_eq!(called_functions(&ssa.functions[&twice_u8_id]), vec![add_one_u8_id; 2]);
+    }
+}
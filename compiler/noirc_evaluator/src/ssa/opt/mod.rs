@@ -3,13 +3,21 @@
 //! Each pass is generally expected to mutate the SSA IR into a gradually
 //! simpler form until the IR only has a single function remaining with 1 block within it.
 //! Generally, these passes are also expected to minimize the final amount of instructions.
+mod algebraic_simplification;
 mod array_use;
 mod assert_constant;
+mod bounds_checks;
 mod constant_folding;
+mod cse;
 mod defunctionalize;
 mod die;
 pub(crate) mod flatten_cfg;
 mod inlining;
+pub(crate) mod instrument;
 mod mem2reg;
+mod merge_functions;
+mod range_analysis;
+mod sccp;
 mod simplify_cfg;
+mod strength_reduction;
 mod unrolling;
@@ -0,0 +1,464 @@
+//! This pass tracks an upper bound for the unsigned integer values of the main ACIR function in
+//! order to remove the range checks which they are known to satisfy.
+//!
+//! Unsigned arithmetic is truncated back to the bit size of its type after each operation, and
+//! each truncation is implemented in ACIR as a euclidean division constraining the quotient and
+//! remainder to a number of bits. These truncations dominate the size of circuits using `u8` or
+//! `u32` arithmetic, yet many of them can't change the value they truncate, such as the
+//! truncation of `x + 1` for a `u8` which is asserted to be less than 100.
+//!
+//! The bounds of a value come from:
+//! - constants,
+//! - the parameters of main, which are range constrained when they are read from the ABI,
+//! - truncations and casts to smaller unsigned types, which constrain their result,
+//! - arithmetic on values which are bounded themselves,
+//! - assertions of the form `constrain (lt v0, v1) == u1 1` or `constrain v0 == c`, which bound
+//!   `v0` for the instructions following them in the same block.
+//!
+//! Values which may come from unconstrained code, such as the results of calls, array reads and
+//! loads, have no known bound even when their type is an unsigned integer.
+//!
+//! With these bounds, this pass:
+//! - removes truncations of values already fitting into the truncated bit size,
+//! - lowers the maximum bit size of the remaining truncations, which reduces the number of bits
+//!   range constrained by the division implementing them,
+//! - removes assertions `constrain (lt v0, v1) == u1 1` which are implied by the bounds of `v0`
+//!   and `v1`, such as the check of a `u8` against 256,
+//! - removes overflow checks `constrain (lt (add v0, v1), v0) == u1 0` of additions which are
+//!   known not to overflow once their truncation has been removed.
+//!
+//! Brillig functions are left untouched, as range checks only cost a few opcodes there.
+use std::collections::HashSet;
+
+use fxhash::FxHashMap as HashMap;
+
+use crate::ssa::{
+    ir::{
+        basic_block::BasicBlockId,
+        dfg::DataFlowGraph,
+        function::{Function, RuntimeType},
+        instruction::{Binary, BinaryOp, Instruction, InstructionId},
+        post_order::PostOrder,
+        types::{NumericType, Type},
+        value::{Value, ValueId},
+    },
+    ssa_gen::Ssa,
+};
+
+impl Ssa {
+    /// Removes the truncations and assertions of the main ACIR function which are implied by the
+    /// bounds of the values they check.
+    ///
+    /// See [`range_analysis`][self] module for more information.
+    pub(crate) fn remove_redundant_range_checks(mut self) -> Ssa {
+        let main = self.main_mut();
+        if main.runtime() == RuntimeType::Acir {
+            Context::default().remove_redundant_range_checks(main);
+        }
+        self
+    }
+}
+
+#[derive(Default)]
+struct Context {
+    /// The largest value each value may have, for the values whose bound is known.
+    bounds: HashMap<ValueId, u128>,
+
+    /// Bounds implied by the assertions of the current block, which only hold after them.
+    asserted_bounds: HashMap<ValueId, u128>,
+}
+
+impl Context {
+    fn remove_redundant_range_checks(&mut self, function: &mut Function) {
+        for parameter in function.parameters() {
+            if let Some(bound) = type_bound(&function.dfg.type_of_value(*parameter)) {
+                self.bounds.insert(*parameter, bound);
+            }
+        }
+
+        // Iterating in reverse post order visits each instruction after the instructions defining
+        // its arguments.
+        let post_order = PostOrder::with_function(function);
+        for block in post_order.as_slice().iter().rev() {
+            self.simplify_block(function, *block);
+        }
+    }
+
+    fn simplify_block(&mut self, function: &mut Function, block: BasicBlockId) {
+        self.asserted_bounds.clear();
+        let mut instructions_to_remove = HashSet::new();
+
+        for instruction_id in function.dfg[block].instructions().to_vec() {
+            let instruction =
+                function.dfg[instruction_id].map_values(|value| function.dfg.resolve(value));
+
+            match &instruction {
+                Instruction::Truncate { value, bit_size, max_bit_size } => {
+                    if let Some(value_bound) = self.bound(*value, &function.dfg) {
+                        if value_bound <= max_for_bits(*bit_size) {
+                            let result = function.dfg.instruction_results(instruction_id)[0];
+                            function.dfg.set_value_from_id(result, *value);
+                            instructions_to_remove.insert(instruction_id);
+                            continue;
+                        }
+
+                        let value_bits = bits_of(value_bound);
+                        if value_bits < *max_bit_size {
+                            function.dfg[instruction_id] = Instruction::Truncate {
+                                value: *value,
+                                bit_size: *bit_size,
+                                max_bit_size: value_bits,
+                            };
+                        }
+                    }
+                }
+                Instruction::Constrain(lhs, rhs, _) => {
+                    if self.is_implied(*lhs, *rhs, &function.dfg) {
+                        instructions_to_remove.insert(instruction_id);
+                    } else {
+                        self.record_assertion(*lhs, *rhs, &function.dfg);
+                    }
+                    continue;
+                }
+                _ => (),
+            }
+
+            self.define_result_bound(&function.dfg, instruction_id, &instruction);
+        }
+
+        function.dfg[block]
+            .instructions_mut()
+            .retain(|instruction| !instructions_to_remove.contains(instruction));
+    }
+
+    /// Returns the largest value `value` may have at the current instruction, if it is known.
+    fn bound(&self, value: ValueId, dfg: &DataFlowGraph) -> Option<u128> {
+        if let Some(constant) = dfg.get_numeric_constant(value) {
+            return constant.try_into_u128();
+        }
+        let asserted = self.asserted_bounds.get(&value).copied();
+        let defined = self.bounds.get(&value).copied();
+        match (asserted, defined) {
+            (Some(asserted), Some(defined)) => Some(asserted.min(defined)),
+            (asserted, defined) => asserted.or(defined),
+        }
+    }
+
+    /// Records the bound of the result of `instruction`, if it has one.
+    fn define_result_bound(
+        &mut self,
+        dfg: &DataFlowGraph,
+        instruction_id: InstructionId,
+        instruction: &Instruction,
+    ) {
+        let [result] = dfg.instruction_results(instruction_id) else {
+            return;
+        };
+        let Some(type_bound) = type_bound(&dfg.type_of_value(*result)) else {
+            return;
+        };
+
+        let result_bound = match instruction {
+            // Arithmetic isn't reduced to the bit size of its type until it is truncated, so its
+            // bound may exceed the bound of the type.
+            Instruction::Binary(binary) => self.binary_bound(binary, dfg),
+            Instruction::Truncate { value, bit_size, .. } => {
+                let truncated = max_for_bits(*bit_size);
+                Some(self.bound(*value, dfg).map_or(truncated, |bound| bound.min(truncated)))
+            }
+            // Casts to a smaller type truncate their value, while other casts leave it unchanged
+            Instruction::Cast(value, _) => match dfg.type_of_value(*value) {
+                Type::Numeric(NumericType::Unsigned { bit_size })
+                    if max_for_bits(bit_size) <= type_bound =>
+                {
+                    self.bound(*value, dfg)
+                }
+                Type::Numeric(NumericType::Signed { bit_size })
+                    if max_for_bits(bit_size) <= type_bound =>
+                {
+                    None
+                }
+                _ => {
+                    let value_bound = self.bound(*value, dfg);
+                    Some(value_bound.map_or(type_bound, |bound| bound.min(type_bound)))
+                }
+            },
+            // `not` subtracts its operand from the largest value of its type, which only stays
+            // within the type if the operand does.
+            Instruction::Not(value) => {
+                self.bound(*value, dfg).filter(|bound| *bound <= type_bound).map(|_| type_bound)
+            }
+            _ => None,
+        };
+
+        if let Some(result_bound) = result_bound {
+            self.bounds.insert(*result, result_bound);
+        }
+    }
+
+    /// Returns the bound of a binary operation on two bounded values.
+    fn binary_bound(&self, binary: &Binary, dfg: &DataFlowGraph) -> Option<u128> {
+        if matches!(binary.operator, BinaryOp::Eq | BinaryOp::Lt) {
+            return Some(1);
+        }
+        let lhs = self.bound(binary.lhs, dfg)?;
+        let rhs = self.bound(binary.rhs, dfg)?;
+        match binary.operator {
+            BinaryOp::Add => lhs.checked_add(rhs),
+            BinaryOp::Mul => lhs.checked_mul(rhs),
+            BinaryOp::Div => Some(lhs),
+            BinaryOp::Mod => Some(lhs.min(rhs.saturating_sub(1))),
+            BinaryOp::And => Some(lhs.min(rhs)),
+            BinaryOp::Or | BinaryOp::Xor => Some(max_for_bits(bits_of(lhs.max(rhs)))),
+            // Subtractions are computed over the field, so they may wrap around
+            BinaryOp::Sub | BinaryOp::Eq | BinaryOp::Lt => None,
+        }
+    }
+
+    /// Returns true if the assertion `constrain lhs == rhs` is implied by the bounds of its values.
+    fn is_implied(&self, lhs: ValueId, rhs: ValueId, dfg: &DataFlowGraph) -> bool {
+        match as_comparison(lhs, rhs, dfg) {
+            // `v0 < c` holds if the largest possible `v0` is below `c`
+            Some((Binary { lhs, rhs, .. }, true)) => {
+                let rhs = dfg.get_numeric_constant(rhs).and_then(|rhs| rhs.try_into_u128());
+                matches!((self.bound(lhs, dfg), rhs), (Some(lhs), Some(rhs)) if lhs < rhs)
+            }
+            // `v0 + v1 < v0` never holds when the addition doesn't exceed its type
+            Some((Binary { lhs, rhs, .. }, false)) => self.is_sum_including(lhs, rhs, dfg),
+            None => false,
+        }
+    }
+
+    /// Returns true if `sum` is an addition of `term` to another value, which is known to fit
+    /// into the type of the addition without being truncated.
+    fn is_sum_including(&self, sum: ValueId, term: ValueId, dfg: &DataFlowGraph) -> bool {
+        let Value::Instruction { instruction, .. } = &dfg[sum] else {
+            return false;
+        };
+        let Instruction::Binary(Binary { lhs, rhs, operator: BinaryOp::Add }) = &dfg[*instruction]
+        else {
+            return false;
+        };
+        let includes_term = dfg.resolve(*lhs) == term || dfg.resolve(*rhs) == term;
+        let fits_type = match (self.bound(sum, dfg), type_bound(&dfg.type_of_value(sum))) {
+            (Some(sum_bound), Some(type_bound)) => sum_bound <= type_bound,
+            _ => false,
+        };
+        includes_term && fits_type
+    }
+
+    /// Records the bound asserted by `constrain lhs == rhs`, if any.
+    fn record_assertion(&mut self, lhs: ValueId, rhs: ValueId, dfg: &DataFlowGraph) {
+        let asserted = match as_comparison(lhs, rhs, dfg) {
+            // `v0 < v1` bounds `v0` by the largest possible `v1`
+            Some((Binary { lhs, rhs, .. }, true)) => {
+                self.bound(rhs, dfg).filter(|rhs| *rhs > 0).map(|rhs| (lhs, rhs - 1))
+            }
+            Some(_) => None,
+            // `v0 == c` bounds `v0` by `c`
+            None => {
+                let constant =
+                    |value| dfg.get_numeric_constant(value).and_then(|c| c.try_into_u128());
+                match (constant(lhs), constant(rhs)) {
+                    (None, Some(rhs_constant)) => Some((lhs, rhs_constant)),
+                    (Some(lhs_constant), None) => Some((rhs, lhs_constant)),
+                    _ => None,
+                }
+            }
+        };
+
+        if let Some((value, asserted)) = asserted {
+            if dfg.type_of_value(value).is_unsigned() {
+                let asserted = self.bound(value, dfg).map_or(asserted, |bound| bound.min(asserted));
+                self.asserted_bounds.insert(value, asserted);
+            }
+        }
+    }
+}
+
+/// Returns the comparison asserted by `constrain lhs == rhs` if one side is a comparison and
+/// the other is a boolean constant, along with the value the comparison must have.
+fn as_comparison(lhs: ValueId, rhs: ValueId, dfg: &DataFlowGraph) -> Option<(Binary, bool)> {
+    let as_bool = |value| match dfg.get_numeric_constant_with_type(value) {
+        Some((constant, typ)) if typ == Type::bool() => Some(constant.is_one()),
+        _ => None,
+    };
+    let (comparison, expected) = match (as_bool(lhs), as_bool(rhs)) {
+        (None, Some(expected)) => (lhs, expected),
+        (Some(expected), None) => (rhs, expected),
+        _ => return None,
+    };
+
+    let Value::Instruction { instruction, .. } = &dfg[comparison] else {
+        return None;
+    };
+    match &dfg[*instruction] {
+        Instruction::Binary(binary @ Binary { operator: BinaryOp::Lt, .. }) => {
+            let binary = Binary {
+                lhs: dfg.resolve(binary.lhs),
+                rhs: dfg.resolve(binary.rhs),
+                operator: binary.operator,
+            };
+            Some((binary, expected))
+        }
+        _ => None,
+    }
+}
+
+/// Returns the largest value of an unsigned integer type.
+fn type_bound(typ: &Type) -> Option<u128> {
+    match typ {
+        Type::Numeric(NumericType::Unsigned { bit_size }) if *bit_size <= 128 => {
+            Some(max_for_bits(*bit_size))
+        }
+        _ => None,
+    }
+}
+
+/// Returns the largest value fitting into `bits` bits.
+fn max_for_bits(bits: u32) -> u128 {
+    if bits >= 128 {
+        u128::MAX
+    } else {
+        (1 << bits) - 1
+    }
+}
+
+/// Returns the number of bits needed to represent `value`.
+fn bits_of(value: u128) -> u32 {
+    u128::BITS - value.leading_zeros()
+}
+
+#[cfg(test)]
+mod test {
+    use crate::ssa::{
+        function_builder::FunctionBuilder,
+        ir::{
+            function::RuntimeType,
+            instruction::{BinaryOp, Instruction},
+            map::Id,
+            types::Type,
+        },
+    };
+
+    #[test]
+    fn removes_truncations_of_values_within_bounds() {
+        // fn main f0 {
+        //   b0(v0: u8, v1: u8):
+        //     v2 = lt v0, u8 100
+        //     constrain v2 == u1 1
+        //     v3 = add v0, u8 1
+        //     v4 = truncate v3 to 8 bits, max_bit_size: 9
+        //     v5 = mul v1, v4
+        //     v6 = truncate v5 to 8 bits, max_bit_size: 16
+        //     return v4, v6
+        // }
+        let main_id = Id::test_new(0);
+        let mut builder = FunctionBuilder::new("main".into(), main_id, RuntimeType::Acir);
+        let v0 = builder.add_parameter(Type::unsigned(8));
+        let v1 = builder.add_parameter(Type::unsigned(8));
+
+        let true_const = builder.numeric_constant(true, Type::bool());
+        let hundred = builder.numeric_constant(100u128, Type::unsigned(8));
+        let one = builder.numeric_constant(1u128, Type::unsigned(8));
+        let v2 = builder.insert_binary(v0, BinaryOp::Lt, hundred);
+        builder.insert_constrain(v2, true_const, None);
+        let v3 = builder.insert_binary(v0, BinaryOp::Add, one);
+        let v4 = builder.insert_truncate(v3, 8, 9);
+        let v5 = builder.insert_binary(v1, BinaryOp::Mul, v4);
+        let v6 = builder.insert_truncate(v5, 8, 16);
+        builder.terminate_with_return(vec![v4, v6]);
+
+        let ssa = builder.finish().remove_redundant_range_checks();
+        let main = ssa.main();
+
+        // The addition can't exceed 100, but the product may exceed 255 and is still truncated,
+        // although its quotient needs one bit less since the product is below 255 * 100.
+        let truncations = main.dfg[main.entry_block()]
+            .instructions()
+            .iter()
+            .filter_map(|instruction| match main.dfg[*instruction] {
+                Instruction::Truncate { value, bit_size, max_bit_size } => {
+                    Some((value, bit_size, max_bit_size))
+                }
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(truncations, vec![(v5, 8, 15)]);
+        assert_eq!(main.dfg.resolve(v4), v3);
+    }
+
+    #[test]
+    fn removes_implied_assertions() {
+        // fn main f0 {
+        //   b0(v0: u8, v1: u16):
+        //     v2 = cast v0 as u16
+        //     v3 = lt v2, u16 256
+        //     constrain v3 == u1 1
+        //     v4 = add v2, u16 5
+        //     v5 = truncate v4 to 16 bits, max_bit_size: 17
+        //     v6 = lt v5, v2
+        //     constrain v6 == u1 0
+        //     v7 = add v1, u16 5
+        //     v8 = truncate v7 to 16 bits, max_bit_size: 17
+        //     v9 = lt v8, v1
+        //     constrain v9 == u1 0
+        //     v10 = lt v1, u16 256
+        //     constrain v10 == u1 1
+        //     return
+        // }
+        let main_id = Id::test_new(0);
+        let mut builder = FunctionBuilder::new("main".into(), main_id, RuntimeType::Acir);
+        let v0 = builder.add_parameter(Type::unsigned(8));
+        let v1 = builder.add_parameter(Type::unsigned(16));
+
+        let true_const = builder.numeric_constant(true, Type::bool());
+        let false_const = builder.numeric_constant(false, Type::bool());
+        let limit = builder.numeric_constant(256u128, Type::unsigned(16));
+        let five = builder.numeric_constant(5u128, Type::unsigned(16));
+
+        let v2 = builder.insert_cast(v0, Type::unsigned(16));
+        let v3 = builder.insert_binary(v2, BinaryOp::Lt, limit);
+        builder.insert_constrain(v3, true_const, None);
+        let v4 = builder.insert_binary(v2, BinaryOp::Add, five);
+        let v5 = builder.insert_truncate(v4, 16, 17);
+        let v6 = builder.insert_binary(v5, BinaryOp::Lt, v2);
+        builder.insert_constrain(v6, false_const, None);
+        let v7 = builder.insert_binary(v1, BinaryOp::Add, five);
+        let v8 = builder.insert_truncate(v7, 16, 17);
+        let v9 = builder.insert_binary(v8, BinaryOp::Lt, v1);
+        builder.insert_constrain(v9, false_const, None);
+        let v10 = builder.insert_binary(v1, BinaryOp::Lt, limit);
+        builder.insert_constrain(v10, true_const, None);
+        builder.terminate_with_return(vec![]);
+
+        let ssa = builder.finish().remove_redundant_range_checks();
+        let main = ssa.main();
+
+        // Only the overflow check of `v1 + 5` and the check of `v1` against 256 remain
+        let constrained_values = main.dfg[main.entry_block()]
+            .instructions()
+            .iter()
+            .filter_map(|instruction| match main.dfg[*instruction] {
+                Instruction::Constrain(lhs, _, _) => Some(lhs),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(constrained_values, vec![v9, v10]);
+    }
+
+    #[test]
+    fn leaves_brillig_functions_untouched() {
+        let main_id = Id::test_new(0);
+        let mut builder = FunctionBuilder::new("main".into(), main_id, RuntimeType::Brillig);
+        let v0 = builder.add_parameter(Type::unsigned(8));
+        let one = builder.numeric_constant(1u128, Type::unsigned(8));
+        let v1 = builder.insert_binary(v0, BinaryOp::Div, one);
+        let v2 = builder.insert_truncate(v1, 8, 9);
+        builder.terminate_with_return(vec![v2]);
+
+        let ssa = builder.finish().remove_redundant_range_checks();
+        let main = ssa.main();
+        assert_eq!(main.dfg[main.entry_block()].instructions().len(), 2);
+    }
+}
@@ -0,0 +1,435 @@
+//! Sparse Conditional Constant Propagation (SCCP) pass: Replaces each value which is the same
+//! constant on every path through a function that can be taken with that constant, and replaces
+//! each `jmpif` on such a value with a `jmp` to the destination it always takes.
+//!
+//! Each value is given a lattice value, which starts as unknown and may only be lowered to a
+//! constant and then to overdefined, while the edges of the CFG start as not executable. The
+//! blocks which are the destination of an executable edge are visited until neither changes:
+//! the instructions of a block are evaluated using the lattice values of their arguments, block
+//! parameters are the meet of the arguments passed along executable edges only, and a `jmpif`
+//! only marks the edge it takes as executable if its condition is a constant.
+//!
+//! Tracking both together finds more constants than simplifying each instruction as it is
+//! inserted: a block parameter whose arguments differ only along edges which are never taken,
+//! such as a value which is only changed within a branch on a constant condition, is still
+//! constant.
+use std::collections::HashSet;
+
+use acvm::FieldElement;
+
+use crate::ssa::{
+    ir::{
+        basic_block::BasicBlockId,
+        cfg::ControlFlowGraph,
+        dfg::{CallStack, DataFlowGraph},
+        function::Function,
+        instruction::{Instruction, InstructionId, SimplifyResult, TerminatorInstruction},
+        post_order::PostOrder,
+        types::Type,
+        value::{Value, ValueId},
+    },
+    ssa_gen::Ssa,
+};
+use fxhash::FxHashMap as HashMap;
+
+impl Ssa {
+    /// Performs Sparse Conditional Constant Propagation (SCCP) to propagate the values which are
+    /// constant on every executable path and remove branches on constant conditions.
+    ///
+    /// See [`sccp`][self] module for more information.
+    pub(crate) fn sparse_conditional_constant_propagation(mut self) -> Ssa {
+        for function in self.functions.values_mut() {
+            sparse_conditional_constant_propagation(function);
+        }
+        self
+    }
+}
+
+fn sparse_conditional_constant_propagation(function: &mut Function) {
+    let cfg = ControlFlowGraph::with_function(function);
+    let blocks = PostOrder::with_function(function).as_slice().to_vec();
+
+    let entry_block = function.entry_block();
+
+    let mut context = Context::default();
+    context.executable_blocks.insert(entry_block);
+    for parameter in function.parameters() {
+        context.lattice.insert(*parameter, LatticeValue::Overdefined);
+    }
+
+    // Iterating in reverse post order visits each block after its predecessors, other than
+    // those reaching it through a back edge.
+    loop {
+        context.changed = false;
+        for block in blocks.iter().rev() {
+            if context.executable_blocks.contains(block) {
+                context.visit_block(&mut function.dfg, &cfg, entry_block, *block);
+            }
+        }
+        if !context.changed {
+            break;
+        }
+    }
+
+    context.rewrite(function, &cfg, &blocks);
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum LatticeValue {
+    /// Nothing is known about the value yet, either because its definition has not been visited
+    /// or because it is only defined by code which is never executed.
+    Unknown,
+    /// The value is this constant on every executable path.
+    Constant(FieldElement, Type),
+    /// The value may differ between executions.
+    Overdefined,
+}
+
+impl LatticeValue {
+    fn meet(self, other: LatticeValue) -> LatticeValue {
+        match (self, other) {
+            (LatticeValue::Unknown, other) | (other, LatticeValue::Unknown) => other,
+            (lhs, rhs) if lhs == rhs => lhs,
+            _ => LatticeValue::Overdefined,
+        }
+    }
+}
+
+#[derive(Default)]
+struct Context {
+    /// The lattice value of each block parameter and instruction result visited so far.
+    lattice: HashMap<ValueId, LatticeValue>,
+    executable_edges: HashSet<(BasicBlockId, BasicBlockId)>,
+    executable_blocks: HashSet<BasicBlockId>,
+    /// Whether the current visit of the blocks lowered a lattice value or found a new
+    /// executable edge.
+    changed: bool,
+}
+
+impl Context {
+    fn visit_block(
+        &mut self,
+        dfg: &mut DataFlowGraph,
+        cfg: &ControlFlowGraph,
+        entry_block: BasicBlockId,
+        block: BasicBlockId,
+    ) {
+        // The parameters of the entry block are those of the function, which are overdefined
+        if block != entry_block {
+            for (index, parameter) in dfg[block].parameters().iter().enumerate() {
+                let incoming = cfg
+                    .predecessors(block)
+                    .filter(|predecessor| self.executable_edges.contains(&(*predecessor, block)))
+                    .filter_map(|predecessor| match dfg[predecessor].terminator() {
+                        Some(TerminatorInstruction::Jmp { arguments, .. }) => {
+                            Some(self.lattice_value(dfg, arguments[index]))
+                        }
+                        _ => None,
+                    })
+                    .fold(LatticeValue::Unknown, LatticeValue::meet);
+                self.lower(*parameter, incoming);
+            }
+        }
+
+        for instruction_id in dfg[block].instructions().to_vec() {
+            let value = self.evaluate_instruction(dfg, block, instruction_id);
+            for result in dfg.instruction_results(instruction_id).to_vec() {
+                self.lower(result, value.clone());
+            }
+        }
+
+        match dfg[block].unwrap_terminator() {
+            TerminatorInstruction::Jmp { destination, .. } => {
+                self.mark_executable(block, *destination);
+            }
+            TerminatorInstruction::JmpIf { condition, then_destination, else_destination } => {
+                let (then_destination, else_destination) = (*then_destination, *else_destination);
+                match self.lattice_value(dfg, *condition) {
+                    LatticeValue::Unknown => (),
+                    LatticeValue::Constant(constant, _) if constant.is_zero() => {
+                        self.mark_executable(block, else_destination);
+                    }
+                    LatticeValue::Constant(..) => self.mark_executable(block, then_destination),
+                    LatticeValue::Overdefined => {
+                        self.mark_executable(block, then_destination);
+                        self.mark_executable(block, else_destination);
+                    }
+                }
+            }
+            TerminatorInstruction::Return { .. } => (),
+        }
+    }
+
+    /// Evaluates the single result of an instruction by simplifying it with each argument which
+    /// is a known constant replaced by that constant.
+    fn evaluate_instruction(
+        &self,
+        dfg: &mut DataFlowGraph,
+        block: BasicBlockId,
+        instruction_id: InstructionId,
+    ) -> LatticeValue {
+        let instruction = dfg[instruction_id].clone();
+        let is_evaluated = matches!(
+            instruction,
+            Instruction::Binary(_)
+                | Instruction::Cast(..)
+                | Instruction::Not(_)
+                | Instruction::Truncate { .. }
+                | Instruction::ArrayGet { .. }
+        );
+        if !is_evaluated || dfg.instruction_results(instruction_id).len() != 1 {
+            return LatticeValue::Overdefined;
+        }
+
+        let mut arguments_known = true;
+        instruction.for_each_value(|value| {
+            arguments_known &= self.lattice_value(dfg, value) != LatticeValue::Unknown;
+        });
+        if !arguments_known {
+            return LatticeValue::Unknown;
+        }
+
+        let instruction = instruction.map_values(|value| match self.lattice_value(dfg, value) {
+            LatticeValue::Constant(constant, typ) => dfg.make_constant(constant, typ),
+            _ => value,
+        });
+        match instruction.simplify(dfg, block, None) {
+            SimplifyResult::SimplifiedTo(value) => self.lattice_value(dfg, value),
+            _ => LatticeValue::Overdefined,
+        }
+    }
+
+    fn lattice_value(&self, dfg: &DataFlowGraph, value: ValueId) -> LatticeValue {
+        let value = dfg.resolve(value);
+        match &dfg[value] {
+            Value::NumericConstant { constant, typ } => {
+                LatticeValue::Constant(*constant, typ.clone())
+            }
+            Value::Instruction { .. } | Value::Param { .. } => {
+                self.lattice.get(&value).cloned().unwrap_or(LatticeValue::Unknown)
+            }
+            _ => LatticeValue::Overdefined,
+        }
+    }
+
+    /// Lowers the lattice value of `value` to its meet with `new_value`.
+    fn lower(&mut self, value: ValueId, new_value: LatticeValue) {
+        let old_value = self.lattice.get(&value).cloned().unwrap_or(LatticeValue::Unknown);
+        let new_value = old_value.clone().meet(new_value);
+        if new_value != old_value {
+            self.lattice.insert(value, new_value);
+            self.changed = true;
+        }
+    }
+
+    fn mark_executable(&mut self, block: BasicBlockId, destination: BasicBlockId) {
+        if self.executable_edges.insert((block, destination)) {
+            self.executable_blocks.insert(destination);
+            self.changed = true;
+        }
+    }
+
+    /// Replaces each constant block parameter and instruction result with its constant, and
+    /// each `jmpif` on a constant condition with a `jmp`.
+    ///
+    /// Blocks which are never executed become unreachable once the `jmpif`s leading to them are
+    /// replaced.
+    fn rewrite(self, function: &mut Function, cfg: &ControlFlowGraph, blocks: &[BasicBlockId]) {
+        let entry_block = function.entry_block();
+        let dfg = &mut function.dfg;
+        let is_constant =
+            |value: &ValueId| matches!(self.lattice.get(value), Some(LatticeValue::Constant(..)));
+
+        for block in blocks {
+            let parameters = dfg[*block].parameters().to_vec();
+            if *block == entry_block || !parameters.iter().any(is_constant) {
+                continue;
+            }
+
+            for predecessor in cfg.predecessors(*block) {
+                if let TerminatorInstruction::Jmp { destination, arguments, .. } =
+                    dfg[predecessor].unwrap_terminator_mut()
+                {
+                    if *destination == *block {
+                        let mut parameters = parameters.iter();
+                        arguments.retain(|_| !parameters.next().map_or(false, is_constant));
+                    }
+                }
+            }
+
+            let (constants, parameters): (Vec<_>, Vec<_>) =
+                parameters.into_iter().partition(is_constant);
+            dfg[*block].set_parameters(parameters);
+            for parameter in constants {
+                self.replace_with_constant(dfg, parameter);
+            }
+        }
+
+        for block in blocks {
+            if !self.executable_blocks.contains(block) {
+                continue;
+            }
+
+            // The instructions whose result is a constant are pure, so they can be removed
+            let mut instructions = dfg[*block].take_instructions();
+            instructions.retain(|instruction| {
+                let results = dfg.instruction_results(*instruction).to_vec();
+                !(results.len() == 1 && self.replace_with_constant(dfg, results[0]))
+            });
+            *dfg[*block].instructions_mut() = instructions;
+
+            if let TerminatorInstruction::JmpIf { condition, then_destination, else_destination } =
+                dfg[*block].unwrap_terminator()
+            {
+                if let LatticeValue::Constant(constant, _) = self.lattice_value(dfg, *condition) {
+                    let destination =
+                        if constant.is_zero() { *else_destination } else { *then_destination };
+                    let arguments = Vec::new();
+                    let jmp = TerminatorInstruction::Jmp {
+                        destination,
+                        arguments,
+                        call_stack: CallStack::new(),
+                    };
+                    dfg[*block].set_terminator(jmp);
+                }
+            }
+        }
+    }
+
+    /// Replaces `value` with its constant if it has one, returning whether it was replaced.
+    fn replace_with_constant(&self, dfg: &mut DataFlowGraph, value: ValueId) -> bool {
+        match self.lattice.get(&value) {
+            Some(LatticeValue::Constant(constant, typ)) => {
+                let constant = dfg.make_constant(*constant, typ.clone());
+                dfg.set_value_from_id(value, constant);
+                true
+            }
+            _ => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::ssa::{
+        function_builder::FunctionBuilder,
+        ir::{
+            function::RuntimeType,
+            instruction::{BinaryOp, TerminatorInstruction},
+            map::Id,
+            types::Type,
+        },
+    };
+
+    #[test]
+    fn removes_branches_on_propagated_constants() {
+        // fn main f0 {
+        //   b0(v0: Field):
+        //     jmp b1(u1 1)
+        //   b1(v1: u1):
+        //     v2 = not v1
+        //     jmpif v2 then: b2, else: b3
+        //   b2():
+        //     v3 = add v0, Field 1
+        //     jmp b4(v3)
+        //   b3():
+        //     jmp b4(Field 5)
+        //   b4(v4: Field):
+        //     v5 = mul v4, Field 2
+        //     return v5
+        // }
+        let main_id = Id::test_new(0);
+        let mut builder = FunctionBuilder::new("main".into(), main_id, RuntimeType::Acir);
+        let v0 = builder.add_parameter(Type::field());
+        let b1 = builder.insert_block();
+        let b2 = builder.insert_block();
+        let b3 = builder.insert_block();
+        let b4 = builder.insert_block();
+        let v1 = builder.add_block_parameter(b1, Type::bool());
+        let v4 = builder.add_block_parameter(b4, Type::field());
+
+        let true_const = builder.numeric_constant(true, Type::bool());
+        builder.terminate_with_jmp(b1, vec![true_const]);
+
+        builder.switch_to_block(b1);
+        let v2 = builder.insert_not(v1);
+        builder.terminate_with_jmpif(v2, b2, b3);
+
+        builder.switch_to_block(b2);
+        let one = builder.field_constant(1u128);
+        let v3 = builder.insert_binary(v0, BinaryOp::Add, one);
+        builder.terminate_with_jmp(b4, vec![v3]);
+
+        builder.switch_to_block(b3);
+        let five = builder.field_constant(5u128);
+        builder.terminate_with_jmp(b4, vec![five]);
+
+        builder.switch_to_block(b4);
+        let two = builder.field_constant(2u128);
+        let v5 = builder.insert_binary(v4, BinaryOp::Mul, two);
+        builder.terminate_with_return(vec![v5]);
+
+        let ssa = builder.finish().sparse_conditional_constant_propagation();
+        let main = ssa.main();
+
+        // b2 is never executed, so `v4` is always 5
+        assert_eq!(main.dfg.get_numeric_constant(v5), Some(10u128.into()));
+        assert!(main.dfg[b1].parameters().is_empty());
+        assert!(main.dfg[b1].instructions().is_empty());
+        assert!(matches!(
+            main.dfg[b1].terminator(),
+            Some(TerminatorInstruction::Jmp { destination, .. }) if *destination == b3
+        ));
+        assert_eq!(main.reachable_blocks().len(), 4);
+    }
+
+    #[test]
+    fn propagates_constants_through_loops() {
+        // brillig fn main f0 {
+        //   b0(v0: u32):
+        //     jmp b1(u32 0, Field 7)
+        //   b1(v1: u32, v2: Field):
+        //     v3 = lt v1, v0
+        //     jmpif v3 then: b2, else: b3
+        //   b2():
+        //     v4 = add v1, u32 1
+        //     jmp b1(v4, v2)
+        //   b3():
+        //     return v2
+        // }
+        let main_id = Id::test_new(0);
+        let mut builder = FunctionBuilder::new("main".into(), main_id, RuntimeType::Brillig);
+        let v0 = builder.add_parameter(Type::unsigned(32));
+        let b1 = builder.insert_block();
+        let b2 = builder.insert_block();
+        let b3 = builder.insert_block();
+        let v1 = builder.add_block_parameter(b1, Type::unsigned(32));
+        let v2 = builder.add_block_parameter(b1, Type::field());
+
+        let zero = builder.numeric_constant(0u128, Type::unsigned(32));
+        let seven = builder.field_constant(7u128);
+        builder.terminate_with_jmp(b1, vec![zero, seven]);
+
+        builder.switch_to_block(b1);
+        let v3 = builder.insert_binary(v1, BinaryOp::Lt, v0);
+        builder.terminate_with_jmpif(v3, b2, b3);
+
+        builder.switch_to_block(b2);
+        let one = builder.numeric_constant(1u128, Type::unsigned(32));
+        let v4 = builder.insert_binary(v1, BinaryOp::Add, one);
+        builder.terminate_with_jmp(b1, vec![v4, v2]);
+
+        builder.switch_to_block(b3);
+        builder.terminate_with_return(vec![v2]);
+
+        let ssa = builder.finish().sparse_conditional_constant_propagation();
+        let main = ssa.main();
+
+        // The loop counter differs between iterations, while `v2` is never changed
+        assert_eq!(main.dfg.get_numeric_constant(v2), Some(7u128.into()));
+        assert_eq!(main.dfg[b1].parameters(), &[v1]);
+        assert_eq!(main.dfg[main.entry_block()].terminator_arguments(), &[zero]);
+        assert_eq!(main.dfg[b2].terminator_arguments(), &[v4]);
+        assert_eq!(main.dfg[b1].instructions().len(), 1);
+    }
+}
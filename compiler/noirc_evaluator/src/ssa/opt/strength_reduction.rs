@@ -0,0 +1,246 @@
+//! This pass rewrites the divisions and remainders of unsigned integers by constant powers of
+//! two in ACIR functions into truncations, which lower to fewer constraints.
+//!
+//! SSA has no shift instructions: `x << k` and `x >> k` are already generated as a
+//! multiplication and division by `2^k`. In ACIR, a multiplication by a constant is free as it
+//! only scales a linear expression, so multiplications are left as they are. Divisions and
+//! remainders however are lowered to a euclidean division which is made conditional on the
+//! current side effects predicate, as the divisor may be zero. When the divisor is a non-zero
+//! constant, the division can't fail, and the same result is obtained from a truncation which
+//! doesn't multiply its constraints by the predicate:
+//! - `mod v0, u32 2^k => truncate v0 to k bits`,
+//! - `div v0, u32 2^k => mul (sub v0, truncate v0 to k bits), u32 2^-k`. The multiplication by
+//!   the inverse of `2^k` in the field is exact as `v0` minus its lowest `k` bits is a multiple
+//!   of `2^k`, so the quotient is a linear expression of the truncation's witness.
+//!
+//! Signed integers are left untouched as their division rounds towards zero. Brillig functions
+//! are also left untouched, since their divisions by a constant are a single opcode.
+use acvm::FieldElement;
+
+use crate::ssa::{
+    ir::{
+        basic_block::BasicBlockId,
+        dfg::DataFlowGraph,
+        function::{Function, RuntimeType},
+        instruction::{Binary, BinaryOp, Instruction, InstructionId},
+        types::{NumericType, Type},
+    },
+    ssa_gen::Ssa,
+};
+
+impl Ssa {
+    /// Rewrites the unsigned divisions and remainders by powers of two of each ACIR function
+    /// into truncations.
+    ///
+    /// See [`strength_reduction`][self] module for more information.
+    pub(crate) fn reduce_strength(mut self) -> Ssa {
+        for function in self.functions.values_mut() {
+            if function.runtime() == RuntimeType::Acir {
+                reduce_function(function);
+            }
+        }
+        self
+    }
+}
+
+fn reduce_function(function: &mut Function) {
+    for block in function.reachable_blocks() {
+        let instructions = function.dfg[block].take_instructions();
+        for instruction in instructions {
+            reduce_instruction(&mut function.dfg, block, instruction);
+        }
+    }
+}
+
+/// Pushes `instruction_id` back into `block`, or the instructions computing the same result with
+/// a truncation if it is a division or remainder by a power of two.
+fn reduce_instruction(dfg: &mut DataFlowGraph, block: BasicBlockId, instruction_id: InstructionId) {
+    let Some((binary, shift, bit_size)) = power_of_two_division(dfg, instruction_id) else {
+        dfg[block].insert_instruction(instruction_id);
+        return;
+    };
+
+    let call_stack = dfg.get_call_stack(instruction_id);
+    let insert = |dfg: &mut DataFlowGraph, instruction| {
+        dfg.insert_instruction_and_results(instruction, block, None, call_stack.clone()).first()
+    };
+
+    let truncate =
+        Instruction::Truncate { value: binary.lhs, bit_size: shift, max_bit_size: bit_size };
+    let remainder = insert(dfg, truncate);
+    let new_result = if binary.operator == BinaryOp::Mod {
+        remainder
+    } else {
+        let sub = Binary { lhs: binary.lhs, rhs: remainder, operator: BinaryOp::Sub };
+        let multiple = insert(dfg, Instruction::Binary(sub));
+        let inverse = FieldElement::from(1_u128 << shift).inverse();
+        let inverse = dfg.make_constant(inverse, Type::unsigned(bit_size));
+        let mul = Binary { lhs: multiple, rhs: inverse, operator: BinaryOp::Mul };
+        insert(dfg, Instruction::Binary(mul))
+    };
+
+    let old_result = dfg.instruction_results(instruction_id)[0];
+    dfg.set_value_from_id(old_result, new_result);
+}
+
+/// Returns the instruction if it is a division or remainder of an unsigned integer by `2^k`,
+/// along with `k` and the bit size of the integer.
+fn power_of_two_division(
+    dfg: &DataFlowGraph,
+    instruction_id: InstructionId,
+) -> Option<(Binary, u32, u32)> {
+    let Instruction::Binary(binary) = &dfg[instruction_id] else {
+        return None;
+    };
+    if !matches!(binary.operator, BinaryOp::Div | BinaryOp::Mod) {
+        return None;
+    }
+    let Type::Numeric(NumericType::Unsigned { bit_size }) = dfg.type_of_value(binary.lhs) else {
+        return None;
+    };
+
+    let divisor = dfg.get_numeric_constant(binary.rhs)?.try_into_u128()?;
+    if !divisor.is_power_of_two() {
+        return None;
+    }
+    // Divisions by one are simplified when the instruction is inserted, and divisors which don't
+    // fit into the type are left for ACIR generation to handle.
+    let shift = divisor.trailing_zeros();
+    if shift == 0 || shift >= bit_size {
+        return None;
+    }
+    let binary =
+        Binary { lhs: dfg.resolve(binary.lhs), rhs: binary.rhs, operator: binary.operator };
+    Some((binary, shift, bit_size))
+}
+
+#[cfg(test)]
+mod test {
+    use std::str::FromStr;
+
+    use acvm::FieldElement;
+
+    use crate::ssa::{
+        function_builder::FunctionBuilder,
+        ir::{
+            function::RuntimeType,
+            instruction::{BinaryOp, Instruction},
+            map::Id,
+            types::Type,
+        },
+        parser::assert_ssa_equals,
+        ssa_gen::Ssa,
+    };
+
+    #[test]
+    fn rewrites_unsigned_division_and_remainder() {
+        // fn main f0 {
+        //   b0(v0: u32):
+        //     v1 = div v0, u32 8
+        //     v2 = mod v0, u32 8
+        //     return v1, v2
+        // }
+        let main_id = Id::test_new(0);
+        let mut builder = FunctionBuilder::new("main".into(), main_id, RuntimeType::Acir);
+        let v0 = builder.add_parameter(Type::unsigned(32));
+        let eight = builder.numeric_constant(8_u128, Type::unsigned(32));
+        let v1 = builder.insert_binary(v0, BinaryOp::Div, eight);
+        let v2 = builder.insert_binary(v0, BinaryOp::Mod, eight);
+        builder.terminate_with_return(vec![v1, v2]);
+
+        let ssa = builder.finish().reduce_strength();
+        let main = ssa.main();
+
+        // fn main f0 {
+        //   b0(v0: u32):
+        //     v3 = truncate v0 to 3 bits, max_bit_size: 32
+        //     v4 = sub v0, v3
+        //     v6 = mul v4, u32 2^-3
+        //     v7 = truncate v0 to 3 bits, max_bit_size: 32
+        //     return v6, v7
+        // }
+        let instructions = main.dfg[main.entry_block()].instructions();
+        assert_eq!(instructions.len(), 4);
+        assert!(instructions.iter().all(|instruction| !matches!(
+            &main.dfg[*instruction],
+            Instruction::Binary(binary) if matches!(binary.operator, BinaryOp::Div | BinaryOp::Mod)
+        )));
+        assert!(matches!(
+            main.dfg[instructions[0]],
+            Instruction::Truncate { bit_size: 3, max_bit_size: 32, .. }
+        ));
+    }
+
+    #[test]
+    fn keeps_divisions_which_are_not_by_powers_of_two() {
+        // fn main f0 {
+        //   b0(v0: u32, v1: i32, v2: Field):
+        //     v3 = div v0, u32 6
+        //     v4 = div v1, i32 8
+        //     v5 = div v2, Field 8
+        //     return v3, v4, v5
+        // }
+        let main_id = Id::test_new(0);
+        let mut builder = FunctionBuilder::new("main".into(), main_id, RuntimeType::Acir);
+        let v0 = builder.add_parameter(Type::unsigned(32));
+        let v1 = builder.add_parameter(Type::signed(32));
+        let v2 = builder.add_parameter(Type::field());
+        let six = builder.numeric_constant(6_u128, Type::unsigned(32));
+        let signed_eight = builder.numeric_constant(8_u128, Type::signed(32));
+        let field_eight = builder.field_constant(8_u128);
+        let v3 = builder.insert_binary(v0, BinaryOp::Div, six);
+        let v4 = builder.insert_binary(v1, BinaryOp::Div, signed_eight);
+        let v5 = builder.insert_binary(v2, BinaryOp::Div, field_eight);
+        builder.terminate_with_return(vec![v3, v4, v5]);
+
+        let ssa = builder.finish().reduce_strength();
+        let main = ssa.main();
+        let instructions = main.dfg[main.entry_block()].instructions();
+        assert_eq!(instructions.len(), 3);
+        assert!(instructions
+            .iter()
+            .all(|instruction| matches!(main.dfg[*instruction], Instruction::Binary(_))));
+    }
+
+    #[test]
+    fn rewrites_division_of_parsed_ssa() {
+        let src = "
+            acir fn main f0 {
+              b0(v0: u8):
+                v1 = div v0, u8 16
+                return v1
+            }
+            ";
+        let ssa = Ssa::from_str(src).unwrap().reduce_strength();
+
+        let inverse = FieldElement::from(16_u128).inverse();
+        let expected = format!(
+            "
+            acir fn main f0 {{
+              b0(v0: u8):
+                v1 = truncate v0 to 4 bits, max_bit_size: 8
+                v2 = sub v0, v1
+                v3 = mul v2, u8 {inverse}
+                return v3
+            }}
+            "
+        );
+        assert_ssa_equals(&ssa, &expected);
+    }
+
+    #[test]
+    fn leaves_brillig_functions_untouched() {
+        let main_id = Id::test_new(0);
+        let mut builder = FunctionBuilder::new("main".into(), main_id, RuntimeType::Brillig);
+        let v0 = builder.add_parameter(Type::unsigned(32));
+        let eight = builder.numeric_constant(8_u128, Type::unsigned(32));
+        let v1 = builder.insert_binary(v0, BinaryOp::Div, eight);
+        builder.terminate_with_return(vec![v1]);
+
+        let ssa = builder.finish().reduce_strength();
+        let main = ssa.main();
+        let instructions = main.dfg[main.entry_block()].instructions();
+        assert_eq!(instructions.len(), 1);
+        assert!(matches!(main.dfg[instructions[0]], Instruction::Binary(_)));
+    }
+}
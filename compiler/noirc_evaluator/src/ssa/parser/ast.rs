@@ -0,0 +1,81 @@
+use acvm::FieldElement;
+
+use crate::ssa::ir::{function::RuntimeType, instruction::BinaryOp, types::Type};
+
+/// The functions of a textual SSA program, in the order they appear in. The first one is the
+/// program's `main`.
+#[derive(Debug)]
+pub(super) struct ParsedSsa {
+    pub(super) functions: Vec<ParsedFunction>,
+}
+
+#[derive(Debug)]
+pub(super) struct ParsedFunction {
+    pub(super) runtime_type: RuntimeType,
+    pub(super) name: String,
+    pub(super) id: usize,
+    /// The blocks of the function, starting with its entry block
+    pub(super) blocks: Vec<ParsedBlock>,
+}
+
+#[derive(Debug)]
+pub(super) struct ParsedBlock {
+    pub(super) name: Identifier,
+    pub(super) parameters: Vec<(Identifier, Type)>,
+    pub(super) instructions: Vec<ParsedInstruction>,
+    pub(super) terminator: ParsedTerminator,
+}
+
+/// The name of a value or block, along with the line it appears on.
+#[derive(Debug, Clone)]
+pub(super) struct Identifier {
+    pub(super) name: String,
+    pub(super) line: usize,
+}
+
+#[derive(Debug)]
+pub(super) enum ParsedValue {
+    Variable(Identifier),
+    NumericConstant {
+        constant: FieldElement,
+        typ: Type,
+    },
+    Array {
+        elements: Vec<ParsedValue>,
+        typ: Type,
+    },
+    Function(usize),
+    /// An intrinsic, or a foreign function if no intrinsic has this name
+    Builtin(String),
+}
+
+/// An instruction along with the names it binds its results to.
+#[derive(Debug)]
+pub(super) struct ParsedInstruction {
+    pub(super) results: Vec<Identifier>,
+    pub(super) kind: ParsedInstructionKind,
+    pub(super) line: usize,
+}
+
+#[derive(Debug)]
+pub(super) enum ParsedInstructionKind {
+    Binary { lhs: ParsedValue, operator: BinaryOp, rhs: ParsedValue },
+    Cast { value: ParsedValue, typ: Type },
+    Not { value: ParsedValue },
+    Truncate { value: ParsedValue, bit_size: u32, max_bit_size: u32 },
+    Constrain { lhs: ParsedValue, rhs: ParsedValue, message: Option<String> },
+    Call { function: ParsedValue, arguments: Vec<ParsedValue>, result_types: Vec<Type> },
+    Allocate,
+    Load { address: ParsedValue, typ: Type },
+    Store { address: ParsedValue, value: ParsedValue },
+    EnableSideEffects { condition: ParsedValue },
+    ArrayGet { array: ParsedValue, index: ParsedValue, element_type: Type },
+    ArraySet { array: ParsedValue, index: ParsedValue, value: ParsedValue },
+}
+
+#[derive(Debug)]
+pub(super) enum ParsedTerminator {
+    Jmp { destination: Identifier, arguments: Vec<ParsedValue> },
+    JmpIf { condition: ParsedValue, then_destination: Identifier, else_destination: Identifier },
+    Return(Vec<ParsedValue>),
+}
@@ -0,0 +1,171 @@
+use acvm::FieldElement;
+
+use super::SsaParseError;
+
+/// A token of the textual SSA, along with the line it starts on.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(super) struct SpannedToken {
+    pub(super) token: Token,
+    pub(super) line: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(super) enum Token {
+    /// Keywords, types and the names of values, blocks, functions and intrinsics
+    Ident(String),
+    /// A numeric literal, as displayed by `FieldElement`
+    Int(FieldElement),
+    /// An assertion message, quoted with single quotes
+    Str(String),
+    LeftParen,
+    RightParen,
+    LeftBrace,
+    RightBrace,
+    LeftBracket,
+    RightBracket,
+    Comma,
+    Colon,
+    Semicolon,
+    Assign,
+    Equal,
+    Arrow,
+}
+
+impl std::fmt::Display for Token {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Token::Ident(ident) => write!(f, "`{ident}`"),
+            Token::Int(int) => write!(f, "`{int}`"),
+            Token::Str(string) => write!(f, "'{string}'"),
+            Token::LeftParen => write!(f, "`(`"),
+            Token::RightParen => write!(f, "`)`"),
+            Token::LeftBrace => write!(f, "`{{`"),
+            Token::RightBrace => write!(f, "`}}`"),
+            Token::LeftBracket => write!(f, "`[`"),
+            Token::RightBracket => write!(f, "`]`"),
+            Token::Comma => write!(f, "`,`"),
+            Token::Colon => write!(f, "`:`"),
+            Token::Semicolon => write!(f, "`;`"),
+            Token::Assign => write!(f, "`=`"),
+            Token::Equal => write!(f, "`==`"),
+            Token::Arrow => write!(f, "`->`"),
+        }
+    }
+}
+
+/// Splits `src` into tokens. Line comments starting with `//` are skipped.
+pub(super) fn tokenize(src: &str) -> Result<Vec<SpannedToken>, SsaParseError> {
+    let mut tokens = Vec::new();
+    for (index, line_src) in src.lines().enumerate() {
+        let line = index + 1;
+        let line_src = line_src.split("//").next().unwrap_or_default();
+        let mut chars = line_src.chars().peekable();
+
+        while let Some(&char) = chars.peek() {
+            let token = match char {
+                _ if char.is_whitespace() => {
+                    chars.next();
+                    continue;
+                }
+                '(' => Token::LeftParen,
+                ')' => Token::RightParen,
+                '{' => Token::LeftBrace,
+                '}' => Token::RightBrace,
+                '[' => Token::LeftBracket,
+                ']' => Token::RightBracket,
+                ',' => Token::Comma,
+                ':' => Token::Colon,
+                ';' => Token::Semicolon,
+                '=' => {
+                    chars.next();
+                    if chars.next_if_eq(&'=').is_some() {
+                        tokens.push(SpannedToken { token: Token::Equal, line });
+                    } else {
+                        tokens.push(SpannedToken { token: Token::Assign, line });
+                    }
+                    continue;
+                }
+                '\'' => {
+                    chars.next();
+                    let string: String = chars.by_ref().take_while(|char| *char != '\'').collect();
+                    tokens.push(SpannedToken { token: Token::Str(string), line });
+                    continue;
+                }
+                '-' => {
+                    chars.next();
+                    if chars.next_if_eq(&'>').is_some() {
+                        tokens.push(SpannedToken { token: Token::Arrow, line });
+                    } else {
+                        let number = take_number(&mut chars);
+                        let int = parse_number(&number, line)?;
+                        tokens.push(SpannedToken { token: Token::Int(-int), line });
+                    }
+                    continue;
+                }
+                _ if char.is_ascii_digit() => {
+                    let number = take_number(&mut chars);
+                    tokens.push(SpannedToken {
+                        token: Token::Int(parse_number(&number, line)?),
+                        line,
+                    });
+                    continue;
+                }
+                _ if char.is_alphabetic() || char == '_' => {
+                    let mut ident = String::new();
+                    while let Some(char) =
+                        chars.next_if(|char| char.is_alphanumeric() || *char == '_')
+                    {
+                        ident.push(char);
+                    }
+                    tokens.push(SpannedToken { token: Token::Ident(ident), line });
+                    continue;
+                }
+                _ => return Err(SsaParseError::UnexpectedCharacter { line, char }),
+            };
+            chars.next();
+            tokens.push(SpannedToken { token, line });
+        }
+    }
+    Ok(tokens)
+}
+
+/// Takes the characters of a number, which may be written in decimal or in hexadecimal, or be
+/// abbreviated as `2⁶⁴` or `2⁶⁴×3` as done by the `Display` impl of `FieldElement`.
+fn take_number(chars: &mut std::iter::Peekable<std::str::Chars>) -> String {
+    let mut number = String::new();
+    while let Some(char) = chars.next_if(|char| {
+        char.is_ascii_alphanumeric() || *char == '×' || superscript(*char).is_some()
+    }) {
+        number.push(char);
+    }
+    number
+}
+
+fn parse_number(number: &str, line: usize) -> Result<FieldElement, SsaParseError> {
+    let invalid = || SsaParseError::InvalidNumber { line, number: number.to_owned() };
+
+    let Some(exponent_start) = number.find(|char| superscript(char).is_some()) else {
+        return FieldElement::try_from_str(number).ok_or_else(invalid);
+    };
+    if &number[..exponent_start] != "2" {
+        return Err(invalid());
+    }
+    let (exponent, factor) = match number[exponent_start..].split_once('×') {
+        Some((exponent, factor)) => {
+            (exponent, FieldElement::try_from_str(factor).ok_or_else(invalid)?)
+        }
+        None => (&number[exponent_start..], FieldElement::one()),
+    };
+    let exponent = exponent
+        .chars()
+        .try_fold(0_u32, |exponent, char| Some(exponent * 10 + superscript(char)?))
+        .ok_or_else(invalid)?;
+
+    let two = FieldElement::from(2_u128);
+    Ok((0..exponent).fold(factor, |value, _| value * two))
+}
+
+/// Returns the digit written as the superscript `char`.
+fn superscript(char: char) -> Option<u32> {
+    "⁰¹²³⁴⁵⁶⁷⁸⁹".chars().position(|digit| digit == char).map(|digit| digit as u32)
+}
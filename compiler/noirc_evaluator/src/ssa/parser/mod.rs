@@ -0,0 +1,536 @@
+//! A parser for the textual form of the SSA, as displayed by `--show-ssa`, so that the unit
+//! tests of SSA passes can be written as small snippets of SSA rather than by building the IR
+//! with a [`FunctionBuilder`][crate::ssa::function_builder::FunctionBuilder]:
+//!
+//! ```text
+//! acir fn main f0 {
+//!   b0(v0: Field):
+//!     v1 = add v0, Field 1
+//!     return v1
+//! }
+//! ```
+//!
+//! The names of values and blocks are only used to refer to them within their function, so the
+//! parsed SSA is renumbered and displays differently from its source. [`assert_ssa_equals`]
+//! compares the SSA produced by a pass with its expected form regardless of this numbering.
+//!
+//! Instructions are inserted with a `FunctionBuilder`, so the same simplifications are applied
+//! to them as when the SSA is generated from a program.
+use std::{collections::HashMap, str::FromStr};
+
+use acvm::FieldElement;
+use thiserror::Error;
+
+use self::{
+    ast::{
+        Identifier, ParsedBlock, ParsedFunction, ParsedInstruction, ParsedInstructionKind,
+        ParsedSsa, ParsedTerminator, ParsedValue,
+    },
+    lexer::{SpannedToken, Token},
+};
+use super::{
+    ir::{function::RuntimeType, instruction::BinaryOp, types::Type},
+    ssa_gen::Ssa,
+};
+
+mod ast;
+mod lexer;
+mod translator;
+
+#[derive(Debug, Error, PartialEq, Eq)]
+pub(crate) enum SsaParseError {
+    #[error("line {line}: unexpected character `{char}`")]
+    UnexpectedCharacter { line: usize, char: char },
+    #[error("line {line}: invalid number `{number}`")]
+    InvalidNumber { line: usize, number: String },
+    #[error("line {line}: expected {expected}, found {found}")]
+    UnexpectedToken { line: usize, expected: String, found: String },
+    #[error("line {line}: unknown type `{name}`")]
+    UnknownType { line: usize, name: String },
+    #[error("line {line}: `{name}` is not defined")]
+    UndefinedName { line: usize, name: String },
+    #[error("line {line}: `{name}` is defined twice")]
+    DuplicateName { line: usize, name: String },
+    #[error("line {line}: the instruction has {actual} results but {expected} names are given")]
+    MismatchedResults { line: usize, expected: usize, actual: usize },
+    #[error("no function was found")]
+    NoFunctions,
+}
+
+impl FromStr for Ssa {
+    type Err = SsaParseError;
+
+    fn from_str(src: &str) -> Result<Ssa, SsaParseError> {
+        let tokens = lexer::tokenize(src)?;
+        let parsed_ssa = Parser { tokens, position: 0 }.parse_ssa()?;
+        translator::translate(parsed_ssa)
+    }
+}
+
+/// Asserts that `ssa` displays as the textual SSA `expected` once both are renumbered in the
+/// order their values and blocks are displayed in.
+#[track_caller]
+pub(crate) fn assert_ssa_equals(ssa: &Ssa, expected: &str) {
+    let expected = Ssa::from_str(expected).unwrap_or_else(|error| {
+        panic!("Expected SSA doesn't parse: {error}\n{expected}");
+    });
+    let actual = renumber(&ssa.to_string());
+    let expected = renumber(&expected.to_string());
+    assert_eq!(actual, expected, "\nActual SSA:\n{actual}\nExpected SSA:\n{expected}");
+}
+
+/// Renames the values `vN` and blocks `bN` of each function of a displayed SSA in the order
+/// they first appear in.
+fn renumber(ssa: &str) -> String {
+    let mut renumbered = String::new();
+    let mut names: HashMap<String, usize> = HashMap::new();
+    let mut counts = [0, 0];
+
+    let mut chars = ssa.chars().peekable();
+    let mut previous = ' ';
+    while let Some(char) = chars.next() {
+        let is_name_start = (char == 'v' || char == 'b')
+            && !(previous.is_alphanumeric() || previous == '_')
+            && matches!(chars.peek(), Some(next) if next.is_ascii_digit());
+        if !is_name_start {
+            if char == '}' {
+                // Each function is numbered separately
+                names.clear();
+                counts = [0, 0];
+            }
+            renumbered.push(char);
+            previous = char;
+            continue;
+        }
+
+        let mut name = char.to_string();
+        while let Some(digit) = chars.next_if(char::is_ascii_digit) {
+            name.push(digit);
+        }
+        if matches!(chars.peek(), Some(next) if next.is_alphanumeric() || *next == '_') {
+            renumbered.push_str(&name);
+        } else {
+            let kind = usize::from(char == 'b');
+            let index = *names.entry(name).or_insert_with(|| {
+                counts[kind] += 1;
+                counts[kind] - 1
+            });
+            renumbered.push_str(&format!("{char}{index}"));
+        }
+        previous = '0';
+    }
+    renumbered
+}
+
+struct Parser {
+    tokens: Vec<SpannedToken>,
+    position: usize,
+}
+
+impl Parser {
+    fn parse_ssa(mut self) -> Result<ParsedSsa, SsaParseError> {
+        let mut functions = Vec::new();
+        while self.peek().is_some() {
+            functions.push(self.parse_function()?);
+        }
+        if functions.is_empty() {
+            return Err(SsaParseError::NoFunctions);
+        }
+        Ok(ParsedSsa { functions })
+    }
+
+    fn parse_function(&mut self) -> Result<ParsedFunction, SsaParseError> {
+        let runtime_type = match self.expect_ident("`acir` or `brillig`")?.as_str() {
+            "acir" => RuntimeType::Acir,
+            "brillig" => RuntimeType::Brillig,
+            _ => return self.unexpected_previous("`acir` or `brillig`"),
+        };
+        self.expect_keyword("fn")?;
+        let name = self.expect_ident("a function name")?;
+        let id = self.expect_ident("a function id")?;
+        let Some(id) = numbered_name(&id, 'f') else {
+            return self.unexpected_previous("a function id");
+        };
+        self.expect(Token::LeftBrace)?;
+
+        let mut blocks = Vec::new();
+        while !self.eat(&Token::RightBrace) {
+            blocks.push(self.parse_block()?);
+        }
+        if blocks.is_empty() {
+            return self.unexpected_previous("a block");
+        }
+        Ok(ParsedFunction { runtime_type, name, id, blocks })
+    }
+
+    fn parse_block(&mut self) -> Result<ParsedBlock, SsaParseError> {
+        let name = self.expect_identifier("a block")?;
+        self.expect(Token::LeftParen)?;
+        let mut parameters = Vec::new();
+        if !self.eat(&Token::RightParen) {
+            loop {
+                let parameter = self.expect_identifier("a block parameter")?;
+                self.expect(Token::Colon)?;
+                parameters.push((parameter, self.parse_type()?));
+                if self.eat(&Token::RightParen) {
+                    break;
+                }
+                self.expect(Token::Comma)?;
+            }
+        }
+        self.expect(Token::Colon)?;
+
+        let mut instructions = Vec::new();
+        loop {
+            if let Some(terminator) = self.parse_terminator()? {
+                return Ok(ParsedBlock { name, parameters, instructions, terminator });
+            }
+            instructions.push(self.parse_instruction()?);
+        }
+    }
+
+    fn parse_terminator(&mut self) -> Result<Option<ParsedTerminator>, SsaParseError> {
+        let terminator = if self.eat_keyword("jmp") {
+            let destination = self.expect_identifier("a block")?;
+            self.expect(Token::LeftParen)?;
+            let arguments = self.parse_values_until(Token::RightParen)?;
+            ParsedTerminator::Jmp { destination, arguments }
+        } else if self.eat_keyword("jmpif") {
+            let condition = self.parse_value()?;
+            self.expect_keyword("then")?;
+            self.expect(Token::Colon)?;
+            let then_destination = self.expect_identifier("a block")?;
+            self.expect(Token::Comma)?;
+            self.expect_keyword("else")?;
+            self.expect(Token::Colon)?;
+            let else_destination = self.expect_identifier("a block")?;
+            ParsedTerminator::JmpIf { condition, then_destination, else_destination }
+        } else if self.eat_keyword("return") {
+            // The returned values are on the same line as the `return`
+            let line = self.previous_line();
+            let mut return_values = Vec::new();
+            if self.tokens.get(self.position).map_or(false, |token| token.line == line) {
+                return_values.push(self.parse_value()?);
+                while self.eat(&Token::Comma) {
+                    return_values.push(self.parse_value()?);
+                }
+            }
+            ParsedTerminator::Return(return_values)
+        } else {
+            return Ok(None);
+        };
+        Ok(Some(terminator))
+    }
+
+    fn parse_instruction(&mut self) -> Result<ParsedInstruction, SsaParseError> {
+        let line = self.line();
+        let mut results = Vec::new();
+        if matches!(self.peek_nth(1), Some(Token::Assign | Token::Comma)) {
+            results.push(self.expect_identifier("a value")?);
+            while self.eat(&Token::Comma) {
+                results.push(self.expect_identifier("a value")?);
+            }
+            self.expect(Token::Assign)?;
+        }
+
+        let instruction = self.expect_ident("an instruction")?;
+        let kind = match instruction.as_str() {
+            "cast" => {
+                let value = self.parse_value()?;
+                self.expect_keyword("as")?;
+                ParsedInstructionKind::Cast { value, typ: self.parse_type()? }
+            }
+            "not" => ParsedInstructionKind::Not { value: self.parse_value()? },
+            "truncate" => {
+                let value = self.parse_value()?;
+                self.expect_keyword("to")?;
+                let bit_size = self.expect_u32()?;
+                self.expect_keyword("bits")?;
+                self.expect(Token::Comma)?;
+                self.expect_keyword("max_bit_size")?;
+                self.expect(Token::Colon)?;
+                let max_bit_size = self.expect_u32()?;
+                ParsedInstructionKind::Truncate { value, bit_size, max_bit_size }
+            }
+            "constrain" => {
+                let lhs = self.parse_value()?;
+                self.expect(Token::Equal)?;
+                let rhs = self.parse_value()?;
+                let message = match self.peek() {
+                    Some(Token::Str(message)) => Some(message.clone()),
+                    _ => None,
+                };
+                if message.is_some() {
+                    self.position += 1;
+                }
+                ParsedInstructionKind::Constrain { lhs, rhs, message }
+            }
+            "call" => {
+                let function = self.parse_value()?;
+                self.expect(Token::LeftParen)?;
+                let arguments = self.parse_values_until(Token::RightParen)?;
+                let mut result_types = Vec::new();
+                if self.eat(&Token::Arrow) {
+                    result_types.push(self.parse_type()?);
+                    while self.eat(&Token::Comma) {
+                        result_types.push(self.parse_type()?);
+                    }
+                }
+                ParsedInstructionKind::Call { function, arguments, result_types }
+            }
+            "allocate" => ParsedInstructionKind::Allocate,
+            "load" => {
+                let address = self.parse_value()?;
+                self.expect(Token::Arrow)?;
+                ParsedInstructionKind::Load { address, typ: self.parse_type()? }
+            }
+            "store" => {
+                let value = self.parse_value()?;
+                self.expect_keyword("at")?;
+                ParsedInstructionKind::Store { address: self.parse_value()?, value }
+            }
+            "enable_side_effects" => {
+                ParsedInstructionKind::EnableSideEffects { condition: self.parse_value()? }
+            }
+            "array_get" => {
+                let array = self.parse_value()?;
+                self.expect(Token::Comma)?;
+                self.expect_keyword("index")?;
+                let index = self.parse_value()?;
+                self.expect(Token::Arrow)?;
+                ParsedInstructionKind::ArrayGet { array, index, element_type: self.parse_type()? }
+            }
+            "array_set" => {
+                let array = self.parse_value()?;
+                self.expect(Token::Comma)?;
+                self.expect_keyword("index")?;
+                let index = self.parse_value()?;
+                self.expect(Token::Comma)?;
+                self.expect_keyword("value")?;
+                ParsedInstructionKind::ArraySet { array, index, value: self.parse_value()? }
+            }
+            other => {
+                let Some(operator) = binary_operator(other) else {
+                    return self.unexpected_previous("an instruction");
+                };
+                let lhs = self.parse_value()?;
+                self.expect(Token::Comma)?;
+                ParsedInstructionKind::Binary { lhs, operator, rhs: self.parse_value()? }
+            }
+        };
+        Ok(ParsedInstruction { results, kind, line })
+    }
+
+    fn parse_values_until(&mut self, end: Token) -> Result<Vec<ParsedValue>, SsaParseError> {
+        let mut values = Vec::new();
+        if self.eat(&end) {
+            return Ok(values);
+        }
+        loop {
+            values.push(self.parse_value()?);
+            if self.eat(&end) {
+                return Ok(values);
+            }
+            self.expect(Token::Comma)?;
+        }
+    }
+
+    fn parse_value(&mut self) -> Result<ParsedValue, SsaParseError> {
+        match self.peek() {
+            Some(Token::LeftBracket) => {
+                let typ = self.parse_type()?;
+                self.expect(Token::LeftBracket)?;
+                let elements = self.parse_values_until(Token::RightBracket)?;
+                Ok(ParsedValue::Array { elements, typ })
+            }
+            Some(Token::Ident(name)) if numeric_type(name).is_some() => {
+                let typ = self.parse_type()?;
+                let constant = self.expect_int()?;
+                Ok(ParsedValue::NumericConstant { constant, typ })
+            }
+            Some(Token::Ident(_)) => {
+                let identifier = self.expect_identifier("a value")?;
+                if numbered_name(&identifier.name, 'v').is_some() {
+                    Ok(ParsedValue::Variable(identifier))
+                } else if let Some(id) = numbered_name(&identifier.name, 'f') {
+                    Ok(ParsedValue::Function(id))
+                } else {
+                    Ok(ParsedValue::Builtin(identifier.name))
+                }
+            }
+            _ => self.unexpected("a value"),
+        }
+    }
+
+    fn parse_type(&mut self) -> Result<Type, SsaParseError> {
+        if self.eat(&Token::LeftBracket) {
+            let mut element_types = vec![self.parse_type()?];
+            while self.eat(&Token::Comma) {
+                element_types.push(self.parse_type()?);
+            }
+            if self.eat(&Token::RightBracket) {
+                return Ok(Type::Slice(element_types.into()));
+            }
+            self.expect(Token::Semicolon)?;
+            let length = self.expect_u32()? as usize;
+            self.expect(Token::RightBracket)?;
+            return Ok(Type::Array(element_types.into(), length));
+        }
+
+        let name = self.expect_ident("a type")?;
+        match name.as_str() {
+            "reference" => Ok(Type::Reference),
+            "function" => Ok(Type::Function),
+            _ => numeric_type(&name).ok_or_else(|| SsaParseError::UnknownType {
+                line: self.previous_line(),
+                name: name.clone(),
+            }),
+        }
+    }
+
+    fn peek(&self) -> Option<&Token> {
+        self.peek_nth(0)
+    }
+
+    fn peek_nth(&self, n: usize) -> Option<&Token> {
+        self.tokens.get(self.position + n).map(|token| &token.token)
+    }
+
+    /// The line of the next token
+    fn line(&self) -> usize {
+        self.tokens.get(self.position).map_or_else(|| self.previous_line(), |token| token.line)
+    }
+
+    /// The line of the last token which was consumed
+    fn previous_line(&self) -> usize {
+        self.position
+            .checked_sub(1)
+            .and_then(|index| self.tokens.get(index))
+            .map_or(1, |token| token.line)
+    }
+
+    fn eat(&mut self, token: &Token) -> bool {
+        let is_next = self.peek() == Some(token);
+        if is_next {
+            self.position += 1;
+        }
+        is_next
+    }
+
+    fn eat_keyword(&mut self, keyword: &str) -> bool {
+        let is_next = matches!(self.peek(), Some(Token::Ident(ident)) if ident == keyword);
+        if is_next {
+            self.position += 1;
+        }
+        is_next
+    }
+
+    fn expect(&mut self, token: Token) -> Result<(), SsaParseError> {
+        if self.eat(&token) {
+            Ok(())
+        } else {
+            self.unexpected(&token.to_string())
+        }
+    }
+
+    fn expect_keyword(&mut self, keyword: &str) -> Result<(), SsaParseError> {
+        if self.eat_keyword(keyword) {
+            Ok(())
+        } else {
+            self.unexpected(&format!("`{keyword}`"))
+        }
+    }
+
+    fn expect_ident(&mut self, expected: &str) -> Result<String, SsaParseError> {
+        match self.peek() {
+            Some(Token::Ident(ident)) => {
+                let ident = ident.clone();
+                self.position += 1;
+                Ok(ident)
+            }
+            _ => self.unexpected(expected),
+        }
+    }
+
+    fn expect_identifier(&mut self, expected: &str) -> Result<Identifier, SsaParseError> {
+        let line = self.line();
+        let name = self.expect_ident(expected)?;
+        Ok(Identifier { name, line })
+    }
+
+    fn expect_int(&mut self) -> Result<FieldElement, SsaParseError> {
+        match self.peek() {
+            Some(Token::Int(int)) => {
+                let int = *int;
+                self.position += 1;
+                Ok(int)
+            }
+            _ => self.unexpected("a number"),
+        }
+    }
+
+    fn expect_u32(&mut self) -> Result<u32, SsaParseError> {
+        let int = self.expect_int()?;
+        match int.try_to_u64().and_then(|int| u32::try_from(int).ok()) {
+            Some(int) => Ok(int),
+            None => self.unexpected_previous("a 32 bits integer"),
+        }
+    }
+
+    /// Fails because the next token isn't the `expected` one.
+    fn unexpected<T>(&self, expected: &str) -> Result<T, SsaParseError> {
+        let found = self.peek().map_or_else(|| "the end of the input".to_owned(), Token::to_string);
+        Err(SsaParseError::UnexpectedToken {
+            line: self.line(),
+            expected: expected.to_owned(),
+            found,
+        })
+    }
+
+    /// Fails because the last consumed token isn't the `expected` one.
+    fn unexpected_previous<T>(&mut self, expected: &str) -> Result<T, SsaParseError> {
+        self.position -= 1;
+        self.unexpected(expected)
+    }
+}
+
+/// Returns `N` if `name` is `prefix` followed by the number `N`, such as `v3` for values.
+fn numbered_name(name: &str, prefix: char) -> Option<usize> {
+    let number = name.strip_prefix(prefix)?;
+    if number.is_empty() || !number.chars().all(|char| char.is_ascii_digit()) {
+        return None;
+    }
+    number.parse().ok()
+}
+
+fn numeric_type(name: &str) -> Option<Type> {
+    if name == "Field" {
+        return Some(Type::field());
+    }
+    let bit_size = name.get(1..).filter(|bits| bits.chars().all(|char| char.is_ascii_digit()));
+    let bit_size: u32 = bit_size?.parse().ok()?;
+    match name.chars().next()? {
+        'u' => Some(Type::unsigned(bit_size)),
+        'i' => Some(Type::signed(bit_size)),
+        _ => None,
+    }
+}
+
+fn binary_operator(name: &str) -> Option<BinaryOp> {
+    let operator = match name {
+        "add" => BinaryOp::Add,
+        "sub" => BinaryOp::Sub,
+        "mul" => BinaryOp::Mul,
+        "div" => BinaryOp::Div,
+        "mod" => BinaryOp::Mod,
+        "eq" => BinaryOp::Eq,
+        "lt" => BinaryOp::Lt,
+        "and" => BinaryOp::And,
+        "or" => BinaryOp::Or,
+        "xor" => BinaryOp::Xor,
+        _ => return None,
+    };
+    Some(operator)
+}
+
+mod tests;
@@ -0,0 +1,155 @@
+use std::str::FromStr;
+
+use crate::ssa::ssa_gen::Ssa;
+
+use super::{assert_ssa_equals, renumber, SsaParseError};
+
+/// Asserts that `src` parses into SSA which displays as `src` once renumbered.
+fn assert_round_trip(src: &str) {
+    let ssa = Ssa::from_str(src).unwrap();
+    assert_eq!(trim_lines(&renumber(&ssa.to_string())), trim_lines(&renumber(src)));
+}
+
+fn trim_lines(src: &str) -> String {
+    let lines = src.lines().map(str::trim).filter(|line| !line.is_empty());
+    lines.collect::<Vec<_>>().join("\n")
+}
+
+#[test]
+fn parses_arithmetic_instructions() {
+    let src = "
+        acir fn main f0 {
+          b0(v0: Field, v1: u32, v2: u1):
+            v3 = add v0, Field 2⁶⁴×3
+            v4 = mul v3, Field -1
+            v5 = cast v0 as u32
+            v6 = lt v5, v1
+            v7 = not v2
+            v8 = truncate v1 to 8 bits, max_bit_size: 32
+            v9 = div v8, u32 3
+            return v4, v6, v7, v9
+        }
+        ";
+    assert_round_trip(src);
+}
+
+#[test]
+fn parses_memory_and_array_instructions() {
+    let src = "
+        acir fn main f0 {
+          b0(v0: Field, v1: u32, v2: u1):
+            enable_side_effects v2
+            v3 = allocate
+            store v0 at v3
+            v4 = load v3 -> Field
+            v5 = array_set [Field; 2] [Field 1, v4], index v1, value v0
+            v6 = array_get v5, index v1 -> Field
+            constrain v6 == Field 1 'unexpected element'
+            return v6
+        }
+        ";
+    assert_round_trip(src);
+}
+
+#[test]
+fn parses_calls() {
+    let src = "
+        acir fn main f0 {
+          b0(v0: Field, v1: [u8; 4]):
+            v2 = call f1(v0) -> Field
+            v3 = call sha256(v1) -> [u8; 32]
+            call print(v2)
+            return v2, v3
+        }
+        brillig fn double f1 {
+          b0(v0: Field):
+            v1 = mul v0, Field 2
+            return v1
+        }
+        ";
+    assert_round_trip(src);
+}
+
+#[test]
+fn parses_control_flow() {
+    let src = "
+        acir fn main f0 {
+          b0(v0: u32):
+            jmp b1(u32 0)
+          b1(v1: u32):
+            v2 = lt v1, v0
+            jmpif v2 then: b3, else: b2
+          b3():
+            v3 = add v1, u32 1
+            jmp b1(v3)
+          b2():
+            return
+        }
+        ";
+    assert_round_trip(src);
+}
+
+#[test]
+fn simplifies_instructions_when_inserting_them() {
+    let src = "
+        acir fn main f0 {
+          b0(v0: Field):
+            v1 = add Field 1, Field 2
+            v2 = add v0, v1
+            return v2
+        }
+        ";
+    let expected = "
+        acir fn main f0 {
+          b0(v0: Field):
+            v1 = add v0, Field 3
+            return v1
+        }
+        ";
+    assert_ssa_equals(&Ssa::from_str(src).unwrap(), expected);
+}
+
+#[test]
+fn reports_undefined_values() {
+    let src = "
+        acir fn main f0 {
+          b0(v0: Field):
+            return v1
+        }
+        ";
+    let error = Ssa::from_str(src).err().unwrap();
+    assert_eq!(error, SsaParseError::UndefinedName { line: 4, name: "v1".to_owned() });
+}
+
+#[test]
+fn reports_mismatched_results() {
+    let src = "
+        acir fn main f0 {
+          b0(v0: Field):
+            v1, v2 = add v0, v0
+            return v1
+        }
+        ";
+    let error = Ssa::from_str(src).err().unwrap();
+    assert_eq!(error, SsaParseError::MismatchedResults { line: 4, expected: 2, actual: 1 });
+}
+
+#[test]
+fn reports_unexpected_tokens() {
+    let src = "
+        acir fn main f0 {
+          b0(v0: Field):
+            v1 = add v0 Field 1
+            return v1
+        }
+        ";
+    let error = Ssa::from_str(src).err().unwrap();
+    assert_eq!(
+        error,
+        SsaParseError::UnexpectedToken {
+            line: 4,
+            expected: "`,`".to_owned(),
+            found: "`Field`".to_owned()
+        }
+    );
+}
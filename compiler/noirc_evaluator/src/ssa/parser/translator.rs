@@ -0,0 +1,212 @@
+use std::collections::HashMap;
+
+use crate::ssa::{
+    function_builder::FunctionBuilder,
+    ir::{
+        basic_block::BasicBlockId,
+        function::{FunctionId, RuntimeType},
+        instruction::{Binary, Instruction, Intrinsic},
+        map::Id,
+        value::ValueId,
+    },
+    ssa_gen::Ssa,
+};
+
+use super::{
+    ast::{
+        Identifier, ParsedFunction, ParsedInstruction, ParsedInstructionKind, ParsedSsa,
+        ParsedTerminator, ParsedValue,
+    },
+    SsaParseError,
+};
+
+/// Builds the SSA described by `parsed_ssa`.
+pub(super) fn translate(parsed_ssa: ParsedSsa) -> Result<Ssa, SsaParseError> {
+    let mut functions = parsed_ssa.functions.into_iter();
+    let main = functions.next().ok_or(SsaParseError::NoFunctions)?;
+
+    let mut builder =
+        FunctionBuilder::new(main.name.clone(), function_id(main.id), main.runtime_type);
+    translate_function(&mut builder, main)?;
+    for function in functions {
+        let id = function_id(function.id);
+        match function.runtime_type {
+            RuntimeType::Acir => builder.new_function(function.name.clone(), id),
+            RuntimeType::Brillig => builder.new_brillig_function(function.name.clone(), id),
+        }
+        translate_function(&mut builder, function)?;
+    }
+    Ok(builder.finish())
+}
+
+fn function_id(id: usize) -> FunctionId {
+    Id::test_new(id)
+}
+
+/// Translates `function` into the function currently built by `builder`.
+fn translate_function(
+    builder: &mut FunctionBuilder,
+    function: ParsedFunction,
+) -> Result<(), SsaParseError> {
+    let mut translator =
+        FunctionTranslator { builder, variables: HashMap::new(), blocks: HashMap::new() };
+
+    // Blocks may jump to blocks displayed after them, so they are all created upfront
+    let entry_block = translator.builder.current_block();
+    for (index, block) in function.blocks.iter().enumerate() {
+        let id = if index == 0 { entry_block } else { translator.builder.insert_block() };
+        if translator.blocks.insert(block.name.name.clone(), id).is_some() {
+            return Err(duplicate_name(&block.name));
+        }
+    }
+
+    for block in function.blocks {
+        let block_id = translator.blocks[&block.name.name];
+        translator.builder.switch_to_block(block_id);
+        for (parameter, typ) in block.parameters {
+            let value = translator.builder.add_block_parameter(block_id, typ);
+            translator.define(&parameter, value)?;
+        }
+        for instruction in block.instructions {
+            translator.translate_instruction(instruction)?;
+        }
+        translator.translate_terminator(block.terminator)?;
+    }
+    Ok(())
+}
+
+struct FunctionTranslator<'a> {
+    builder: &'a mut FunctionBuilder,
+    variables: HashMap<String, ValueId>,
+    blocks: HashMap<String, BasicBlockId>,
+}
+
+impl FunctionTranslator<'_> {
+    fn translate_instruction(
+        &mut self,
+        instruction: ParsedInstruction,
+    ) -> Result<(), SsaParseError> {
+        let (instruction_kind, result_types) = match instruction.kind {
+            ParsedInstructionKind::Binary { lhs, operator, rhs } => {
+                let binary = Binary { lhs: self.value(lhs)?, rhs: self.value(rhs)?, operator };
+                (Instruction::Binary(binary), None)
+            }
+            ParsedInstructionKind::Cast { value, typ } => {
+                (Instruction::Cast(self.value(value)?, typ), None)
+            }
+            ParsedInstructionKind::Not { value } => (Instruction::Not(self.value(value)?), None),
+            ParsedInstructionKind::Truncate { value, bit_size, max_bit_size } => {
+                let value = self.value(value)?;
+                (Instruction::Truncate { value, bit_size, max_bit_size }, None)
+            }
+            ParsedInstructionKind::Constrain { lhs, rhs, message } => {
+                (Instruction::Constrain(self.value(lhs)?, self.value(rhs)?, message), None)
+            }
+            ParsedInstructionKind::Call { function, arguments, result_types } => {
+                let func = self.value(function)?;
+                let arguments = self.values(arguments)?;
+                (Instruction::Call { func, arguments }, Some(result_types))
+            }
+            ParsedInstructionKind::Allocate => (Instruction::Allocate, None),
+            ParsedInstructionKind::Load { address, typ } => {
+                (Instruction::Load { address: self.value(address)? }, Some(vec![typ]))
+            }
+            ParsedInstructionKind::Store { address, value } => {
+                let address = self.value(address)?;
+                (Instruction::Store { address, value: self.value(value)? }, None)
+            }
+            ParsedInstructionKind::EnableSideEffects { condition } => {
+                (Instruction::EnableSideEffects { condition: self.value(condition)? }, None)
+            }
+            ParsedInstructionKind::ArrayGet { array, index, element_type } => {
+                let array = self.value(array)?;
+                let index = self.value(index)?;
+                (Instruction::ArrayGet { array, index }, Some(vec![element_type]))
+            }
+            ParsedInstructionKind::ArraySet { array, index, value } => {
+                let array = self.value(array)?;
+                let index = self.value(index)?;
+                (Instruction::ArraySet { array, index, value: self.value(value)? }, None)
+            }
+        };
+
+        let results =
+            self.builder.insert_instruction(instruction_kind, result_types).results().into_owned();
+        if results.len() != instruction.results.len() {
+            return Err(SsaParseError::MismatchedResults {
+                line: instruction.line,
+                expected: instruction.results.len(),
+                actual: results.len(),
+            });
+        }
+        for (name, result) in instruction.results.iter().zip(results) {
+            self.define(name, result)?;
+        }
+        Ok(())
+    }
+
+    fn translate_terminator(&mut self, terminator: ParsedTerminator) -> Result<(), SsaParseError> {
+        match terminator {
+            ParsedTerminator::Jmp { destination, arguments } => {
+                let destination = self.block(&destination)?;
+                let arguments = self.values(arguments)?;
+                self.builder.terminate_with_jmp(destination, arguments);
+            }
+            ParsedTerminator::JmpIf { condition, then_destination, else_destination } => {
+                let condition = self.value(condition)?;
+                let then_destination = self.block(&then_destination)?;
+                let else_destination = self.block(&else_destination)?;
+                self.builder.terminate_with_jmpif(condition, then_destination, else_destination);
+            }
+            ParsedTerminator::Return(return_values) => {
+                let return_values = self.values(return_values)?;
+                self.builder.terminate_with_return(return_values);
+            }
+        }
+        Ok(())
+    }
+
+    fn value(&mut self, value: ParsedValue) -> Result<ValueId, SsaParseError> {
+        match value {
+            ParsedValue::Variable(identifier) => {
+                self.variables.get(&identifier.name).copied().ok_or_else(|| {
+                    SsaParseError::UndefinedName { line: identifier.line, name: identifier.name }
+                })
+            }
+            ParsedValue::NumericConstant { constant, typ } => {
+                Ok(self.builder.numeric_constant(constant, typ))
+            }
+            ParsedValue::Array { elements, typ } => {
+                let elements = self.values(elements)?;
+                Ok(self.builder.array_constant(elements.into(), typ))
+            }
+            ParsedValue::Function(id) => Ok(self.builder.import_function(function_id(id))),
+            ParsedValue::Builtin(name) => match Intrinsic::lookup(&name) {
+                Some(intrinsic) => Ok(self.builder.import_intrinsic_id(intrinsic)),
+                None => Ok(self.builder.import_foreign_function(&name)),
+            },
+        }
+    }
+
+    fn values(&mut self, values: Vec<ParsedValue>) -> Result<Vec<ValueId>, SsaParseError> {
+        values.into_iter().map(|value| self.value(value)).collect()
+    }
+
+    fn block(&self, identifier: &Identifier) -> Result<BasicBlockId, SsaParseError> {
+        self.blocks.get(&identifier.name).copied().ok_or_else(|| SsaParseError::UndefinedName {
+            line: identifier.line,
+            name: identifier.name.clone(),
+        })
+    }
+
+    fn define(&mut self, identifier: &Identifier, value: ValueId) -> Result<(), SsaParseError> {
+        if self.variables.insert(identifier.name.clone(), value).is_some() {
+            return Err(duplicate_name(identifier));
+        }
+        Ok(())
+    }
+}
+
+fn duplicate_name(identifier: &Identifier) -> SsaParseError {
+    SsaParseError::DuplicateName { line: identifier.line, name: identifier.name.clone() }
+}
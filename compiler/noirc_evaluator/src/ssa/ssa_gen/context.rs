@@ -9,13 +9,14 @@ use noirc_frontend::monomorphization::ast::{FuncId, Program};
 use noirc_frontend::{BinaryOpKind, Signedness};
 
 use crate::ssa::function_builder::FunctionBuilder;
+use crate::ssa::ir::basic_block::BasicBlockId;
 use crate::ssa::ir::dfg::DataFlowGraph;
 use crate::ssa::ir::function::FunctionId as IrFunctionId;
 use crate::ssa::ir::function::{Function, RuntimeType};
 use crate::ssa::ir::instruction::{BinaryOp, Endian, Intrinsic};
 use crate::ssa::ir::map::AtomicCounter;
 use crate::ssa::ir::types::{NumericType, Type};
-use crate::ssa::ir::value::ValueId;
+use crate::ssa::ir::value::{Value as IrValue, ValueId};
 
 use super::value::{Tree, Value, Values};
 use fxhash::FxHashMap as HashMap;
@@ -36,6 +37,13 @@ pub(super) struct FunctionContext<'a> {
 
     pub(super) builder: FunctionBuilder,
     shared_context: &'a SharedContext,
+
+    /// The block following each `loop` being compiled, innermost last, which `break` jumps to
+    pub(super) loop_ends: Vec<BasicBlockId>,
+
+    /// The references to each of the program's mutable globals, if the function being compiled
+    /// takes them as parameters
+    mutable_globals: Option<Vec<Values>>,
 }
 
 /// Shared context for all functions during ssa codegen. This is the only
@@ -52,7 +60,7 @@ pub(super) struct SharedContext {
     /// All currently known functions which have already been assigned function ids.
     /// These functions are all either currently having their SSA generated or are
     /// already finished.
-    functions: RwLock<HashMap<FuncId, IrFunctionId>>,
+    functions: RwLock<HashMap<(FuncId, FunctionVariant), IrFunctionId>>,
 
     /// Queue of which functions still need to be compiled.
     ///
@@ -67,10 +75,29 @@ pub(super) struct SharedContext {
 
     /// The entire monomorphized source program
     pub(super) program: Program,
+
+    /// Whether unsigned integer addition, subtraction and multiplication are constrained
+    /// not to overflow, instead of wrapping around
+    overflow_checks: bool,
 }
 
 /// The queue of functions remaining to compile
-type FunctionQueue = Vec<(ast::FuncId, IrFunctionId)>;
+type FunctionQueue = Vec<(ast::FuncId, FunctionVariant, IrFunctionId)>;
+
+/// Which version of a function is compiled. Programs without mutable globals only have
+/// `Plain` functions.
+///
+/// Mutable globals live in the memory of unconstrained code, so every function run by it
+/// takes references to them as hidden trailing parameters. Constrained code calls
+/// unconstrained functions through an entry function which initializes the globals instead.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub(super) enum FunctionVariant {
+    Plain,
+    /// The function as run by unconstrained code, taking the mutable globals as parameters
+    WithGlobals,
+    /// The entry point of an unconstrained function called from constrained code
+    GlobalsEntry,
+}
 
 impl<'a> FunctionContext<'a> {
     /// Create a new FunctionContext to compile the first function in the shared_context's
@@ -91,10 +118,16 @@ impl<'a> FunctionContext<'a> {
         let function_id = shared_context
             .pop_next_function_in_queue()
             .expect("No function in queue for the FunctionContext to compile")
-            .1;
+            .2;
 
         let builder = FunctionBuilder::new(function_name, function_id, runtime);
-        let mut this = Self { definitions: HashMap::default(), builder, shared_context };
+        let mut this = Self {
+            definitions: HashMap::default(),
+            builder,
+            shared_context,
+            loop_ends: Vec::new(),
+            mutable_globals: None,
+        };
         this.add_parameters_to_scope(parameters);
         this
     }
@@ -104,14 +137,86 @@ impl<'a> FunctionContext<'a> {
     ///
     /// Note that the previous function cannot be resumed after calling this. Developers should
     /// avoid calling new_function until the previous function is completely finished with ssa-gen.
-    pub(super) fn new_function(&mut self, id: IrFunctionId, func: &ast::Function) {
+    pub(super) fn new_function(
+        &mut self,
+        id: IrFunctionId,
+        func: &ast::Function,
+        variant: FunctionVariant,
+    ) {
         self.definitions.clear();
         if func.unconstrained {
             self.builder.new_brillig_function(func.name.clone(), id);
         } else {
             self.builder.new_function(func.name.clone(), id);
         }
+        self.builder.set_inline_type(func.inline_type);
         self.add_parameters_to_scope(&func.parameters);
+
+        self.mutable_globals = None;
+        if variant == FunctionVariant::WithGlobals {
+            self.add_mutable_globals_parameters();
+        }
+    }
+
+    /// Adds a parameter for the reference to each mutable global, or to each of its fields
+    /// if it's a tuple
+    fn add_mutable_globals_parameters(&mut self) {
+        let globals = vecmap(&self.shared_context.program.mutable_globals, |global| {
+            Self::map_type(&global.typ, |typ| {
+                let reference = self.builder.add_parameter(Type::Reference);
+                Value::Mutable(reference, typ)
+            })
+        });
+        self.mutable_globals = Some(globals);
+    }
+
+    /// Codegens the body of the entry point through which constrained code calls the
+    /// unconstrained function `id`. It initializes the mutable globals and passes them along
+    /// with its own parameters to the function's `WithGlobals` version.
+    pub(super) fn codegen_globals_entry(&mut self, id: FuncId, func: &ast::Function) {
+        let mut arguments = Vec::new();
+        for (parameter, ..) in &func.parameters {
+            let parameter = self.lookup(*parameter);
+            arguments.extend(parameter.into_value_list(self));
+        }
+
+        let shared_context = self.shared_context;
+        for global in &shared_context.program.mutable_globals {
+            let initial_value = self.codegen_expression(&global.initial_value);
+            for value in initial_value.into_value_list(self) {
+                let reference = self.builder.insert_allocate();
+                self.builder.insert_store(reference, value);
+                arguments.push(reference);
+            }
+        }
+
+        let function = shared_context.get_or_queue_function(id, FunctionVariant::WithGlobals);
+        let function = self.builder.import_function(function);
+        let result_types = Self::convert_type(&func.return_type).flatten();
+        let results = self.builder.insert_call(function, arguments, result_types).to_vec();
+        self.builder.terminate_with_return(results);
+    }
+
+    /// Passes the mutable globals to the given function if the function being compiled
+    /// takes them as parameters. Intrinsics and oracles don't take them.
+    pub(super) fn append_mutable_globals(&self, function: ValueId, arguments: &mut Vec<ValueId>) {
+        let Some(globals) = &self.mutable_globals else {
+            return;
+        };
+        let dfg = &self.builder.current_function.dfg;
+        if matches!(dfg[function], IrValue::Intrinsic(_) | IrValue::ForeignFunction(_)) {
+            return;
+        }
+        for global in globals {
+            global.clone().for_each(|value| arguments.push(value.eval_reference()));
+        }
+    }
+
+    /// The references to the given mutable global, which are loaded from when it's used
+    pub(super) fn lookup_mutable_global(&self, index: usize) -> Values {
+        let globals = self.mutable_globals.as_ref();
+        let globals = globals.expect("Mutable globals are only used by unconstrained code");
+        globals[index].clone()
     }
 
     /// Add each parameter to the current scope, and return the list of parameter types.
@@ -327,7 +432,11 @@ impl<'a> FunctionContext<'a> {
                     unreachable!("ICE: Truncation attempted on non-integer");
                 }
             };
-            result = self.builder.insert_truncate(result, bit_size, max_bit_size);
+            let truncated = self.builder.insert_truncate(result, bit_size, max_bit_size);
+            if self.shared_context.overflow_checks {
+                self.check_overflow(operator, lhs, rhs, truncated, max_bit_size);
+            }
+            result = truncated;
         }
 
         if operator_requires_not(operator) {
@@ -336,6 +445,48 @@ impl<'a> FunctionContext<'a> {
         result.into()
     }
 
+    /// Constrains the result of an unsigned addition, subtraction or multiplication not to have
+    /// overflowed, so that it doesn't silently wrap around.
+    ///
+    /// Additions and subtractions are checked by comparing the truncated result to the operands.
+    /// Multiplications are redone on fields and compared to the truncated result, so they are
+    /// only checked if the full product always fits into a field element.
+    fn check_overflow(
+        &mut self,
+        operator: BinaryOpKind,
+        lhs: ValueId,
+        rhs: ValueId,
+        truncated: ValueId,
+        max_bit_size: u32,
+    ) {
+        let result_type = self.builder.current_function.dfg.type_of_value(truncated);
+        if !matches!(result_type, Type::Numeric(NumericType::Unsigned { .. })) {
+            return;
+        }
+
+        let (overflowed, operation) = match operator {
+            // An addition overflowed if its result is less than one of the operands
+            BinaryOpKind::Add => (self.builder.insert_binary(truncated, BinaryOp::Lt, lhs), "add"),
+            // A subtraction overflowed if the value subtracted is larger than the other operand
+            BinaryOpKind::Subtract => {
+                (self.builder.insert_binary(lhs, BinaryOp::Lt, rhs), "subtract")
+            }
+            BinaryOpKind::Multiply if max_bit_size < FieldElement::max_num_bits() => {
+                let lhs = self.builder.insert_cast(lhs, Type::field());
+                let rhs = self.builder.insert_cast(rhs, Type::field());
+                let product = self.builder.insert_binary(lhs, BinaryOp::Mul, rhs);
+                let truncated = self.builder.insert_cast(truncated, Type::field());
+                let unchanged = self.builder.insert_binary(product, BinaryOp::Eq, truncated);
+                (self.builder.insert_not(unchanged), "multiply")
+            }
+            _ => return,
+        };
+
+        let no_overflow = self.builder.numeric_constant(0u128, Type::bool());
+        let message = format!("attempt to {operation} with overflow");
+        self.builder.insert_constrain(overflowed, no_overflow, Some(message));
+    }
+
     /// The frontend claims to support equality (==) on arrays, so we must support it in SSA here.
     /// The actual BinaryOp::Eq in SSA is meant only for primitive numeric types so we encode an
     /// entire equality loop on each array element. The generated IR is as follows:
@@ -511,10 +662,25 @@ impl<'a> FunctionContext<'a> {
     /// Retrieves the given function, adding it to the function queue
     /// if it is not yet compiled.
     pub(super) fn get_or_queue_function(&mut self, id: FuncId) -> Values {
-        let function = self.shared_context.get_or_queue_function(id);
+        let variant = self.function_variant(id);
+        let function = self.shared_context.get_or_queue_function(id, variant);
         self.builder.import_function(function).into()
     }
 
+    /// The version of the given function referred to by the function being compiled
+    fn function_variant(&self, id: FuncId) -> FunctionVariant {
+        let program = &self.shared_context.program;
+        if program.mutable_globals.is_empty() {
+            FunctionVariant::Plain
+        } else if self.mutable_globals.is_some() {
+            FunctionVariant::WithGlobals
+        } else if program[id].unconstrained {
+            FunctionVariant::GlobalsEntry
+        } else {
+            FunctionVariant::Plain
+        }
+    }
+
     /// Extracts the current value out of an LValue.
     ///
     /// Goal: Handle the case of assigning to nested expressions such as `foo.bar[i1].baz[i2] = e`
@@ -582,6 +748,7 @@ impl<'a> FunctionContext<'a> {
     fn ident_lvalue(&self, ident: &ast::Ident) -> (Values, bool) {
         match &ident.definition {
             ast::Definition::Local(id) => (self.lookup(*id), ident.mutable),
+            ast::Definition::MutableGlobal(index) => (self.lookup_mutable_global(*index), true),
             other => panic!("Unexpected definition found for mutable value: {other}"),
         }
     }
@@ -844,29 +1011,36 @@ fn convert_operator(op: noirc_frontend::BinaryOpKind) -> BinaryOp {
 
 impl SharedContext {
     /// Create a new SharedContext for the given monomorphized program.
-    pub(super) fn new(program: Program) -> Self {
+    pub(super) fn new(program: Program, overflow_checks: bool) -> Self {
         Self {
             functions: Default::default(),
             function_queue: Default::default(),
             function_counter: Default::default(),
             program,
+            overflow_checks,
         }
     }
 
     /// Pops the next function from the shared function queue, returning None if the queue is empty.
-    pub(super) fn pop_next_function_in_queue(&self) -> Option<(ast::FuncId, IrFunctionId)> {
+    pub(super) fn pop_next_function_in_queue(
+        &self,
+    ) -> Option<(ast::FuncId, FunctionVariant, IrFunctionId)> {
         self.function_queue.lock().expect("Failed to lock function_queue").pop()
     }
 
     /// Return the matching id for the given function if known. If it is not known this
     /// will add the function to the queue of functions to compile, assign it a new id,
     /// and return this new id.
-    pub(super) fn get_or_queue_function(&self, id: ast::FuncId) -> IrFunctionId {
+    pub(super) fn get_or_queue_function(
+        &self,
+        id: ast::FuncId,
+        variant: FunctionVariant,
+    ) -> IrFunctionId {
         // Start a new block to guarantee the destructor for the map lock is released
         // before map needs to be acquired again in self.functions.write() below
         {
             let map = self.functions.read().expect("Failed to read self.functions");
-            if let Some(existing_id) = map.get(&id) {
+            if let Some(existing_id) = map.get(&(id, variant)) {
                 return *existing_id;
             }
         }
@@ -874,9 +1048,10 @@ impl SharedContext {
         let next_id = self.function_counter.next();
 
         let mut queue = self.function_queue.lock().expect("Failed to lock function queue");
-        queue.push((id, next_id));
+        queue.push((id, variant, next_id));
 
-        self.functions.write().expect("Failed to write to self.functions").insert(id, next_id);
+        let mut functions = self.functions.write().expect("Failed to write to self.functions");
+        functions.insert((id, variant), next_id);
 
         next_id
     }
@@ -4,7 +4,7 @@ mod value;
 
 pub(crate) use program::Ssa;
 
-use context::SharedContext;
+use context::{FunctionVariant, SharedContext};
 use iter_extended::vecmap;
 use noirc_errors::Location;
 use noirc_frontend::{
@@ -29,15 +29,24 @@ use super::ir::{
 /// Generates SSA for the given monomorphized program.
 ///
 /// This function will generate the SSA but does not perform any optimizations on it.
-pub(crate) fn generate_ssa(program: Program) -> Ssa {
+/// If `overflow_checks` is set, unsigned integer arithmetic is constrained not to overflow.
+pub(crate) fn generate_ssa(program: Program, overflow_checks: bool) -> Ssa {
     let return_location = program.return_location;
-    let context = SharedContext::new(program);
+    let context = SharedContext::new(program, overflow_checks);
 
     let main_id = Program::main_id();
     let main = context.program.main();
 
+    // An unconstrained main is called from outside the program, so it initializes the
+    // mutable globals like any other call to unconstrained code
+    let main_variant = if main.unconstrained && !context.program.mutable_globals.is_empty() {
+        FunctionVariant::GlobalsEntry
+    } else {
+        FunctionVariant::Plain
+    };
+
     // Queue the main function for compilation
-    context.get_or_queue_function(main_id);
+    context.get_or_queue_function(main_id, main_variant);
 
     let mut function_context = FunctionContext::new(
         main.name.clone(),
@@ -45,7 +54,7 @@ pub(crate) fn generate_ssa(program: Program) -> Ssa {
         if main.unconstrained { RuntimeType::Brillig } else { RuntimeType::Acir },
         &context,
     );
-    function_context.codegen_function_body(&main.body);
+    function_context.codegen_function_variant(main_id, main, main_variant);
 
     if let Some(return_location) = return_location {
         let block = function_context.builder.current_block();
@@ -66,16 +75,31 @@ pub(crate) fn generate_ssa(program: Program) -> Ssa {
     // function queue as they were found in codegen_ident. This queueing will happen each time a
     // previously-unseen function is found so we need now only continue popping from this queue
     // to generate SSA for each function used within the program.
-    while let Some((src_function_id, dest_id)) = context.pop_next_function_in_queue() {
+    while let Some((src_function_id, variant, dest_id)) = context.pop_next_function_in_queue() {
         let function = &context.program[src_function_id];
-        function_context.new_function(dest_id, function);
-        function_context.codegen_function_body(&function.body);
+        function_context.new_function(dest_id, function, variant);
+        function_context.codegen_function_variant(src_function_id, function, variant);
     }
 
     function_context.builder.finish()
 }
 
 impl<'a> FunctionContext<'a> {
+    /// Codegen the body of the given version of a function, whose parameters are in scope
+    fn codegen_function_variant(
+        &mut self,
+        id: ast::FuncId,
+        function: &ast::Function,
+        variant: FunctionVariant,
+    ) {
+        match variant {
+            FunctionVariant::Plain | FunctionVariant::WithGlobals => {
+                self.codegen_function_body(&function.body);
+            }
+            FunctionVariant::GlobalsEntry => self.codegen_globals_entry(id, function),
+        }
+    }
+
     /// Codegen a function's body and set its return value to that of its last parameter.
     /// For functions returning nothing, this will be an empty list.
     fn codegen_function_body(&mut self, body: &Expression) {
@@ -94,6 +118,8 @@ impl<'a> FunctionContext<'a> {
             Expression::Index(index) => self.codegen_index(index),
             Expression::Cast(cast) => self.codegen_cast(cast),
             Expression::For(for_expr) => self.codegen_for(for_expr),
+            Expression::Loop(block) => self.codegen_loop(block),
+            Expression::Break => self.codegen_break(),
             Expression::If(if_expr) => self.codegen_if(if_expr),
             Expression::Tuple(tuple) => self.codegen_tuple(tuple),
             Expression::ExtractTupleField(tuple, index) => {
@@ -124,6 +150,7 @@ impl<'a> FunctionContext<'a> {
     fn codegen_ident_reference(&mut self, ident: &ast::Ident) -> Values {
         match &ident.definition {
             ast::Definition::Local(id) => self.lookup(*id),
+            ast::Definition::MutableGlobal(index) => self.lookup_mutable_global(*index),
             ast::Definition::Function(id) => self.get_or_queue_function(*id),
             ast::Definition::Oracle(name) => self.builder.import_foreign_function(name).into(),
             ast::Definition::Builtin(name) | ast::Definition::LowLevel(name) => {
@@ -294,6 +321,8 @@ impl<'a> FunctionContext<'a> {
         } else {
             (array_or_slice[0], None)
         };
+        // An unchecked index is not compared against the length of the slice
+        let slice_length = slice_length.filter(|_| index.checked);
         self.codegen_array_index(
             array,
             index_value,
@@ -309,6 +338,8 @@ impl<'a> FunctionContext<'a> {
     /// Set load_result to true to load from each relevant index of the array
     /// (it may be multiple in the case of tuples). Set it to false to instead
     /// return a reference to each element, for use with the store instruction.
+    ///
+    /// The index of a slice is checked against its `length`, unless no length is given.
     fn codegen_array_index(
         &mut self,
         array: super::ir::value::ValueId,
@@ -331,7 +362,9 @@ impl<'a> FunctionContext<'a> {
             let array_type = &self.builder.type_of_value(array);
             match array_type {
                 Type::Slice(_) => {
-                    self.codegen_slice_access_check(index, length);
+                    if length.is_some() {
+                        self.codegen_slice_access_check(index, length);
+                    }
                 }
                 Type::Array(..) => {
                     // Nothing needs to done to prepare an array access on an array
@@ -440,6 +473,44 @@ impl<'a> FunctionContext<'a> {
         Self::unit_value()
     }
 
+    /// Codegens a `loop`, which is only used in unconstrained code and thus never unrolled.
+    /// The expression `loop { block }` is codegen'd as:
+    ///
+    ///   br loop_body()
+    /// loop_body():
+    ///   v0 = ... codegen block ...
+    ///   br loop_body()
+    /// loop_end():
+    ///   ... This is the current insert point after codegen_loop finishes ...
+    ///
+    /// Each `break` within the block jumps to loop_end, which is unreachable if there is none.
+    fn codegen_loop(&mut self, block: &Expression) -> Values {
+        let loop_body = self.builder.insert_block();
+        let loop_end = self.builder.insert_block();
+
+        self.builder.terminate_with_jmp(loop_body, vec![]);
+        self.builder.switch_to_block(loop_body);
+
+        self.loop_ends.push(loop_end);
+        self.codegen_expression(block);
+        self.loop_ends.pop();
+        self.builder.terminate_with_jmp(loop_body, vec![]);
+
+        self.builder.switch_to_block(loop_end);
+        Self::unit_value()
+    }
+
+    /// Codegens a `break` as a jump to the end of the innermost `loop`. Any code following it
+    /// is codegen'd into a fresh block, which is unreachable.
+    fn codegen_break(&mut self) -> Values {
+        let loop_end = *self.loop_ends.last().expect("`break` is only allowed within a `loop`");
+        self.builder.terminate_with_jmp(loop_end, vec![]);
+
+        let unreachable = self.builder.insert_block();
+        self.builder.switch_to_block(unreachable);
+        Self::unit_value()
+    }
+
     /// Codegens an if expression, handling the case of what to do if there is no 'else'.
     ///
     /// For example, the expression `if cond { a } else { b }` is codegen'd as:
@@ -517,11 +588,12 @@ impl<'a> FunctionContext<'a> {
     /// and intrinsics are also represented by the function call instruction.
     fn codegen_call(&mut self, call: &ast::Call) -> Values {
         let function = self.codegen_non_tuple_expression(&call.func);
-        let arguments = call
+        let mut arguments = call
             .arguments
             .iter()
             .flat_map(|argument| self.codegen_expression(argument).into_value_list(self))
             .collect::<Vec<_>>();
+        self.append_mutable_globals(function, &mut arguments);
 
         self.codegen_intrinsic_call_checks(function, &arguments, call.location);
 
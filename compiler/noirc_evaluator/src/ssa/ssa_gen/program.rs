@@ -1,10 +1,12 @@
 use std::{collections::BTreeMap, fmt::Display};
 
 use iter_extended::btree_map;
+use noirc_printable_type::FieldDisplay;
 
 use crate::ssa::ir::{
     function::{Function, FunctionId},
     map::AtomicCounter,
+    printer::display_function,
 };
 
 /// Contains the entire SSA representation of the program.
@@ -49,12 +51,30 @@ impl Ssa {
         self.functions.insert(new_id, function);
         new_id
     }
+
+    /// Displays the SSA with its numeric constants shown with `field_display`, if given
+    pub(crate) fn display(&self, field_display: Option<FieldDisplay>) -> SsaDisplay<'_> {
+        SsaDisplay { ssa: self, field_display }
+    }
 }
 
 impl Display for Ssa {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        for function in self.functions.values() {
-            writeln!(f, "{function}")?;
+        self.display(None).fmt(f)
+    }
+}
+
+/// The display of an [`Ssa`] returned by [`Ssa::display`]
+pub(crate) struct SsaDisplay<'ssa> {
+    ssa: &'ssa Ssa,
+    field_display: Option<FieldDisplay>,
+}
+
+impl Display for SsaDisplay<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for function in self.ssa.functions.values() {
+            display_function(function, self.field_display, f)?;
+            writeln!(f)?;
         }
         Ok(())
     }
@@ -0,0 +1,111 @@
+//! A small, stable facade over the frontend for tools which embed the compiler, such as
+//! linters and code generators.
+//!
+//! The internal modules of this crate change from release to release. Everything exposed
+//! here is kept backwards compatible, so embedders should prefer it to reaching into
+//! `hir`, `node_interner` or the def collector directly. Only the [`Context`] and [`CrateId`]
+//! created by `noirc_driver` cross this boundary, as opaque handles to the crate being checked.
+//! The AST, HIR and types are never exposed: types are given as they would be written in Noir.
+use fm::{FileId, FileManager};
+use iter_extended::vecmap;
+use noirc_errors::{CustomDiagnostic, FileDiagnostic, Location};
+
+use crate::graph::CrateId;
+use crate::hir::def_map::{CrateDefMap, ModuleDefId};
+use crate::hir::Context;
+
+/// The kind of definition an [`Item`] refers to.
+#[non_exhaustive]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum ItemKind {
+    Module,
+    Function,
+    Struct,
+    TypeAlias,
+    Trait,
+    Global,
+}
+
+/// A single definition within a checked crate.
+#[non_exhaustive]
+#[derive(Debug, Clone)]
+pub struct Item {
+    pub name: String,
+    pub kind: ItemKind,
+    pub location: Location,
+
+    /// The type of the item as it would be written in Noir, for items which have one:
+    /// functions, type aliases and globals.
+    pub typ: Option<String>,
+}
+
+/// Parses a single file without resolving any of its imports or submodules, returning the
+/// syntax errors found in it.
+pub fn parse_file(file_manager: &FileManager, file_id: FileId) -> Vec<FileDiagnostic> {
+    let (_, errors) = crate::hir::def_map::parse_file(file_manager, file_id);
+    vecmap(errors, |error| CustomDiagnostic::from(error).in_file(file_id))
+}
+
+/// Collects, resolves and type checks the given crate along with all of its dependencies.
+///
+/// Returns every warning and error which was found, leaving out the warnings of allowed lints.
+/// The crate can be inspected with [`items`] afterward, although if any errors were returned
+/// its items may be incomplete.
+pub fn check_crate(context: &mut Context, crate_id: CrateId) -> Vec<FileDiagnostic> {
+    let errors = CrateDefMap::collect_defs(crate_id, context);
+    errors
+        .into_iter()
+        .filter_map(|(error, file_id)| {
+            context.lint_levels.apply(CustomDiagnostic::from(error).in_file(file_id))
+        })
+        .collect()
+}
+
+/// Returns each item defined in the given crate, module by module in declaration order.
+///
+/// Imported names are not included, only the definitions themselves.
+pub fn items(context: &Context, crate_id: CrateId) -> Vec<Item> {
+    let Some(def_map) = context.def_map(&crate_id) else {
+        return Vec::new();
+    };
+    let interner = &context.def_interner;
+
+    let mut items = Vec::new();
+    for (_, module) in def_map.modules().iter() {
+        let file = module.location.file;
+
+        for (name, definition) in module.named_definitions() {
+            let (kind, location, typ) = match definition {
+                ModuleDefId::ModuleId(child) => {
+                    let location = def_map.modules()[child.local_id.0].location;
+                    (ItemKind::Module, location, None)
+                }
+                ModuleDefId::FunctionId(func_id) => {
+                    let meta = interner.function_meta(&func_id);
+                    (ItemKind::Function, meta.location, Some(meta.typ.to_string()))
+                }
+                ModuleDefId::TypeId(struct_id) => {
+                    let span = interner.get_struct(struct_id).borrow().span;
+                    (ItemKind::Struct, Location::new(span, file), None)
+                }
+                ModuleDefId::TypeAliasId(alias_id) => {
+                    let alias = interner.get_type_alias(alias_id);
+                    let location = Location::new(alias.span, file);
+                    (ItemKind::TypeAlias, location, Some(alias.typ.to_string()))
+                }
+                ModuleDefId::TraitId(trait_id) => {
+                    let span = interner.get_trait(trait_id).span;
+                    (ItemKind::Trait, Location::new(span, file), None)
+                }
+                ModuleDefId::GlobalId(stmt_id) => {
+                    let let_statement = interner.let_statement(&stmt_id);
+                    let typ = interner.id_type(let_statement.expression);
+                    (ItemKind::Global, Location::new(name.span(), file), Some(typ.to_string()))
+                }
+            };
+
+            items.push(Item { name: name.0.contents.clone(), kind, location, typ });
+        }
+    }
+    items
+}
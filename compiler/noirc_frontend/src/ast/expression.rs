@@ -4,13 +4,16 @@ use std::fmt::Display;
 use crate::token::{Attributes, Token};
 use crate::{
     Distinctness, Ident, Path, Pattern, Recoverable, Statement, StatementKind,
-    UnresolvedTraitConstraint, UnresolvedType, UnresolvedTypeData, Visibility,
+    UnresolvedNumericConstraint, UnresolvedTraitConstraint, UnresolvedType, UnresolvedTypeData,
+    Visibility,
 };
 use acvm::FieldElement;
 use iter_extended::vecmap;
 use noirc_errors::{Span, Spanned};
+use noirc_printable_type::FieldDisplay;
+use serde::{Deserialize, Serialize};
 
-#[derive(Debug, PartialEq, Eq, Clone)]
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
 pub enum ExpressionKind {
     Literal(Literal),
     Block(BlockExpression),
@@ -23,10 +26,24 @@ pub enum ExpressionKind {
     Cast(Box<CastExpression>),
     Infix(Box<InfixExpression>),
     If(Box<IfExpression>),
-    Variable(Path),
+    /// A path, optionally followed by explicit generics such as `foo::<Field>`
+    Variable(Path, Option<Vec<UnresolvedType>>),
     Tuple(Vec<Expression>),
     Lambda(Box<Lambda>),
     Parenthesized(Box<Expression>),
+    /// A range `start..end`, which constructs a `std::ops::Range`
+    Range(Box<RangeExpression>),
+    /// A `comptime { .. }` block, which is evaluated while the program is compiled
+    Comptime(BlockExpression),
+    /// `std::env!("NAME")`, which is replaced with the value of the environment variable `NAME`
+    /// given to the compiler
+    Env(String),
+    /// `std::include_bytes!("path")`, which is replaced with the contents of the file at `path`,
+    /// relative to the current file, as a `[u8; N]` array
+    IncludeBytes(String),
+    /// `std::include_str!("path")`, which is replaced with the contents of the file at `path`,
+    /// relative to the current file, as a string
+    IncludeStr(String),
     Error,
 }
 
@@ -37,7 +54,7 @@ pub type UnresolvedGenerics = Vec<Ident>;
 impl ExpressionKind {
     pub fn into_path(self) -> Option<Path> {
         match self {
-            ExpressionKind::Variable(path) => Some(path),
+            ExpressionKind::Variable(path, _) => Some(path),
             _ => None,
         }
     }
@@ -65,7 +82,7 @@ impl ExpressionKind {
     }
 
     pub fn integer(contents: FieldElement) -> ExpressionKind {
-        ExpressionKind::Literal(Literal::Integer(contents))
+        ExpressionKind::Literal(Literal::Integer(contents, false))
     }
 
     pub fn boolean(contents: bool) -> ExpressionKind {
@@ -96,7 +113,7 @@ impl ExpressionKind {
         };
 
         match literal {
-            Literal::Integer(integer) => Some(*integer),
+            Literal::Integer(integer, _) => Some(*integer),
             _ => None,
         }
     }
@@ -120,7 +137,7 @@ impl Recoverable for Option<Expression> {
     }
 }
 
-#[derive(Debug, Eq, Clone)]
+#[derive(Debug, Eq, Clone, Serialize, Deserialize)]
 pub struct Expression {
     pub kind: ExpressionKind,
     pub span: Span,
@@ -146,6 +163,14 @@ impl Expression {
     ) -> Expression {
         let kind = match args {
             None => ExpressionKind::MemberAccess(Box::new(MemberAccessExpression { lhs, rhs })),
+            // Methods can't be named by a number, so `tuple.0(args)` calls the tuple's first field
+            Some(arguments) if rhs.0.contents.parse::<usize>().is_ok() => {
+                let field_span = lhs.span.merge(rhs.span());
+                let field = MemberAccessExpression { lhs, rhs };
+                let field =
+                    Expression::new(ExpressionKind::MemberAccess(Box::new(field)), field_span);
+                return Expression::call(field, arguments, span);
+            }
             Some(arguments) => ExpressionKind::MethodCall(Box::new(MethodCallExpression {
                 object: lhs,
                 method_name: rhs,
@@ -189,7 +214,7 @@ impl Expression {
 
 pub type BinaryOp = Spanned<BinaryOpKind>;
 
-#[derive(PartialEq, PartialOrd, Eq, Ord, Hash, Debug, Copy, Clone)]
+#[derive(PartialEq, PartialOrd, Eq, Ord, Hash, Debug, Copy, Clone, Serialize, Deserialize)]
 pub enum BinaryOpKind {
     Add,
     Subtract,
@@ -280,7 +305,7 @@ impl BinaryOpKind {
     }
 }
 
-#[derive(PartialEq, PartialOrd, Eq, Ord, Hash, Debug, Copy, Clone)]
+#[derive(PartialEq, PartialOrd, Eq, Ord, Hash, Debug, Copy, Clone, Serialize, Deserialize)]
 pub enum UnaryOp {
     Minus,
     Not,
@@ -306,23 +331,26 @@ impl UnaryOp {
         }
     }
 }
-#[derive(Debug, PartialEq, Eq, Clone)]
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
 pub enum Literal {
     Array(ArrayLiteral),
     Bool(bool),
-    Integer(FieldElement),
+    /// The magnitude of an integer literal, and whether it is negated, e.g. `-5`
+    Integer(FieldElement, bool),
     Str(String),
     FmtStr(String),
+    /// A character literal such as `'a'`, holding its ASCII code
+    Char(u8),
     Unit,
 }
 
-#[derive(Debug, PartialEq, Eq, Clone)]
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
 pub struct PrefixExpression {
     pub operator: UnaryOp,
     pub rhs: Expression,
 }
 
-#[derive(Debug, PartialEq, Eq, Clone)]
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
 pub struct InfixExpression {
     pub lhs: Expression,
     pub operator: BinaryOp,
@@ -330,27 +358,27 @@ pub struct InfixExpression {
 }
 
 // This is an infix expression with 'as' as the binary operator
-#[derive(Debug, PartialEq, Eq, Clone)]
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
 pub struct CastExpression {
     pub lhs: Expression,
     pub r#type: UnresolvedType,
 }
 
-#[derive(Debug, PartialEq, Eq, Clone)]
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
 pub struct IfExpression {
     pub condition: Expression,
     pub consequence: Expression,
     pub alternative: Option<Expression>,
 }
 
-#[derive(Debug, PartialEq, Eq, Clone)]
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
 pub struct Lambda {
     pub parameters: Vec<(Pattern, UnresolvedType)>,
     pub return_type: UnresolvedType,
     pub body: Expression,
 }
 
-#[derive(Debug, PartialEq, Eq, Clone)]
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
 pub struct FunctionDefinition {
     pub name: Ident,
 
@@ -366,6 +394,12 @@ pub struct FunctionDefinition {
     /// True if this function was defined with the 'unconstrained' keyword
     pub is_unconstrained: bool,
 
+    /// True if this function was defined with the 'const' keyword
+    pub is_const: bool,
+
+    /// True if this function was defined with the 'comptime' keyword
+    pub is_comptime: bool,
+
     /// True if this function was defined with the 'pub' keyword
     pub is_public: bool,
 
@@ -374,12 +408,13 @@ pub struct FunctionDefinition {
     pub body: BlockExpression,
     pub span: Span,
     pub where_clause: Vec<UnresolvedTraitConstraint>,
+    pub numeric_constraints: Vec<UnresolvedNumericConstraint>,
     pub return_type: FunctionReturnType,
     pub return_visibility: Visibility,
     pub return_distinctness: Distinctness,
 }
 
-#[derive(Debug, PartialEq, Eq, Clone)]
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
 pub enum FunctionReturnType {
     /// Returns type is not specified.
     Default(Span),
@@ -389,7 +424,7 @@ pub enum FunctionReturnType {
 
 /// Describes the types of smart contract functions that are allowed.
 /// - All Noir programs in the non-contract context can be seen as `Secret`.
-#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub enum ContractFunctionType {
     /// This function will be executed in a private
     /// context.
@@ -399,44 +434,60 @@ pub enum ContractFunctionType {
     Open,
 }
 
-#[derive(Debug, PartialEq, Eq, Clone)]
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
 pub enum ArrayLiteral {
     Standard(Vec<Expression>),
-    Repeated { repeated_element: Box<Expression>, length: Box<Expression> },
+    Repeated {
+        repeated_element: Box<Expression>,
+        length: Box<Expression>,
+    },
+    /// `[element for identifier in start_range..end_range]`
+    Comprehension {
+        element: Box<Expression>,
+        identifier: Ident,
+        start_range: Box<Expression>,
+        end_range: Box<Expression>,
+    },
 }
 
-#[derive(Debug, PartialEq, Eq, Clone)]
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
 pub struct CallExpression {
     pub func: Box<Expression>,
     pub arguments: Vec<Expression>,
 }
 
-#[derive(Debug, PartialEq, Eq, Clone)]
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
 pub struct MethodCallExpression {
     pub object: Expression,
     pub method_name: Ident,
     pub arguments: Vec<Expression>,
 }
 
-#[derive(Debug, PartialEq, Eq, Clone)]
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
 pub struct ConstructorExpression {
     pub type_name: Path,
     pub fields: Vec<(Ident, Expression)>,
 }
 
-#[derive(Debug, PartialEq, Eq, Clone)]
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
+pub struct RangeExpression {
+    pub start: Expression,
+    pub end: Expression,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
 pub struct MemberAccessExpression {
     pub lhs: Expression,
     pub rhs: Ident,
 }
 
-#[derive(Debug, PartialEq, Eq, Clone)]
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
 pub struct IndexExpression {
     pub collection: Expression, // XXX: For now, this will be the name of the array, as we do not support other collections
     pub index: Expression, // XXX: We accept two types of indices, either a normal integer or a constant
 }
 
-#[derive(Debug, PartialEq, Eq, Clone)]
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
 pub struct BlockExpression(pub Vec<Statement>);
 
 impl BlockExpression {
@@ -472,7 +523,11 @@ impl Display for ExpressionKind {
             Cast(cast) => cast.fmt(f),
             Infix(infix) => infix.fmt(f),
             If(if_expr) => if_expr.fmt(f),
-            Variable(path) => path.fmt(f),
+            Variable(path, None) => path.fmt(f),
+            Variable(path, Some(generics)) => {
+                let generics = vecmap(generics, ToString::to_string);
+                write!(f, "{path}::<{}>", generics.join(", "))
+            }
             Constructor(constructor) => constructor.fmt(f),
             MemberAccess(access) => access.fmt(f),
             Tuple(elements) => {
@@ -481,6 +536,11 @@ impl Display for ExpressionKind {
             }
             Lambda(lambda) => lambda.fmt(f),
             Parenthesized(sub_expr) => write!(f, "({sub_expr})"),
+            Range(range) => write!(f, "({}..{})", range.start, range.end),
+            Comptime(block) => write!(f, "comptime {block}"),
+            Env(name) => write!(f, "std::env!({})", Literal::Str(name.clone())),
+            IncludeBytes(path) => write!(f, "std::include_bytes!({})", Literal::Str(path.clone())),
+            IncludeStr(path) => write!(f, "std::include_str!({})", Literal::Str(path.clone())),
             Error => write!(f, "Error"),
         }
     }
@@ -496,15 +556,45 @@ impl Display for Literal {
             Literal::Array(ArrayLiteral::Repeated { repeated_element, length }) => {
                 write!(f, "[{repeated_element}; {length}]")
             }
+            Literal::Array(ArrayLiteral::Comprehension {
+                element,
+                identifier,
+                start_range,
+                end_range,
+            }) => write!(f, "[{element} for {identifier} in {start_range}..{end_range}]"),
             Literal::Bool(boolean) => write!(f, "{}", if *boolean { "true" } else { "false" }),
-            Literal::Integer(integer) => write!(f, "{}", integer.to_u128()),
-            Literal::Str(string) => write!(f, "\"{string}\""),
+            Literal::Integer(integer, negative) => {
+                let sign = if *negative { "-" } else { "" };
+                write!(f, "{sign}{}", FieldDisplay::Decimal.format(*integer))
+            }
+            Literal::Str(string) => write!(f, "\"{}\"", escape_string(string)),
             Literal::FmtStr(string) => write!(f, "f\"{string}\""),
+            Literal::Char(byte) => write!(f, "'{}'", (*byte as char).escape_default()),
             Literal::Unit => write!(f, "()"),
         }
     }
 }
 
+/// Escapes the contents of a string literal so that it lexes back into the same string
+pub fn escape_string(string: &str) -> String {
+    let mut escaped = String::with_capacity(string.len());
+    for char in string.chars() {
+        match char {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            '\t' => escaped.push_str("\\t"),
+            '\r' => escaped.push_str("\\r"),
+            '\0' => escaped.push_str("\\0"),
+            other if other.is_control() => {
+                escaped.push_str(&format!("\\u{{{:x}}}", other as u32));
+            }
+            other => escaped.push(other),
+        }
+    }
+    escaped
+}
+
 impl Display for BlockExpression {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         writeln!(f, "{{")?;
@@ -644,12 +734,15 @@ impl FunctionDefinition {
             is_open: false,
             is_internal: false,
             is_unconstrained: false,
+            is_const: false,
+            is_comptime: false,
             is_public: false,
             generics: generics.clone(),
             parameters: p,
             body: body.clone(),
             span: name.span(),
             where_clause: where_clause.to_vec(),
+            numeric_constraints: Vec::new(),
             return_type: return_type.clone(),
             return_visibility: Visibility::Private,
             return_distinctness: Distinctness::DuplicationAllowed,
@@ -665,7 +758,8 @@ impl Display for FunctionDefinition {
             format!("{name}: {visibility} {type}")
         });
 
-        let where_clause = vecmap(&self.where_clause, ToString::to_string);
+        let mut where_clause = vecmap(&self.where_clause, ToString::to_string);
+        where_clause.extend(self.numeric_constraints.iter().map(ToString::to_string));
         let where_clause_str = if !where_clause.is_empty() {
             format!("where {}", where_clause.join(", "))
         } else {
@@ -1,6 +1,7 @@
 use std::fmt::Display;
 
 use noirc_errors::Span;
+use serde::{Deserialize, Serialize};
 
 use crate::{
     token::{Attributes, FunctionAttribute, SecondaryAttribute},
@@ -13,7 +14,7 @@ use super::{FunctionDefinition, UnresolvedType, UnresolvedTypeData};
 // A closure / function definition will be stored under a name, so we do not differentiate between their variants
 // The name for function literal will be the variable it is bound to, and the name for a function definition will
 // be the function name itself.
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub struct NoirFunction {
     pub kind: FunctionKind,
     pub def: FunctionDefinition,
@@ -23,7 +24,7 @@ pub struct NoirFunction {
 /// - Normal functions
 /// - LowLevel/Foreign which link to an OPCODE in ACIR
 /// - BuiltIn which are provided by the runtime
-#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub enum FunctionKind {
     LowLevel,
     Builtin,
@@ -26,11 +26,12 @@ use crate::{
     BinaryTypeOperator,
 };
 use iter_extended::vecmap;
+use serde::{Deserialize, Serialize};
 
 /// The parser parses types as 'UnresolvedType's which
 /// require name resolution to resolve any type names used
 /// for structs within, but are otherwise identical to Types.
-#[derive(Debug, PartialEq, Eq, Clone, Hash)]
+#[derive(Debug, PartialEq, Eq, Clone, Hash, Serialize, Deserialize)]
 pub enum UnresolvedTypeData {
     FieldElement,
     Array(Option<UnresolvedTypeExpression>, Box<UnresolvedType>), // [4]Witness = Array(4, Witness)
@@ -47,6 +48,9 @@ pub enum UnresolvedTypeData {
     /// A Trait as return type or parameter of function, including its generics
     TraitAsType(Path, Vec<UnresolvedType>),
 
+    /// A trait object, `dyn Trait`, whose methods are dispatched at runtime
+    TraitObject(Path),
+
     /// &mut T
     MutableReference(Box<UnresolvedType>),
 
@@ -63,7 +67,7 @@ pub enum UnresolvedTypeData {
     Error,
 }
 
-#[derive(Debug, PartialEq, Eq, Clone, Hash)]
+#[derive(Debug, PartialEq, Eq, Clone, Hash, Serialize, Deserialize)]
 pub struct UnresolvedType {
     pub typ: UnresolvedTypeData,
 
@@ -76,7 +80,7 @@ pub struct UnresolvedType {
 /// The precursor to TypeExpression, this is the type that the parser allows
 /// to be used in the length position of an array type. Only constants, variables,
 /// and numeric binary operators are allowed here.
-#[derive(Debug, PartialEq, Eq, Clone, Hash)]
+#[derive(Debug, PartialEq, Eq, Clone, Hash, Serialize, Deserialize)]
 pub enum UnresolvedTypeExpression {
     Variable(Path),
     Constant(u64, Span),
@@ -88,6 +92,16 @@ pub enum UnresolvedTypeExpression {
     ),
 }
 
+/// A bound on numeric generics in a function's where clause, such as `where N <= 32`.
+/// `comparison` is always one of the comparison operators.
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
+pub struct UnresolvedNumericConstraint {
+    pub lhs: UnresolvedTypeExpression,
+    pub comparison: BinaryOpKind,
+    pub rhs: UnresolvedTypeExpression,
+    pub span: Span,
+}
+
 impl Recoverable for UnresolvedType {
     fn error(span: Span) -> Self {
         UnresolvedType { typ: UnresolvedTypeData::Error, span: Some(span) }
@@ -123,6 +137,7 @@ impl std::fmt::Display for UnresolvedTypeData {
                     write!(f, "impl {}<{}>", s, args.join(", "))
                 }
             }
+            TraitObject(path) => write!(f, "dyn {path}"),
             Tuple(elements) => {
                 let elements = vecmap(elements, ToString::to_string);
                 write!(f, "({})", elements.join(", "))
@@ -174,6 +189,12 @@ impl std::fmt::Display for UnresolvedTypeExpression {
     }
 }
 
+impl std::fmt::Display for UnresolvedNumericConstraint {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} {} {}", self.lhs, self.comparison, self.rhs)
+    }
+}
+
 impl UnresolvedType {
     pub fn without_span(typ: UnresolvedTypeData) -> UnresolvedType {
         UnresolvedType { typ, span: None }
@@ -198,7 +219,7 @@ impl UnresolvedTypeData {
     }
 }
 
-#[derive(Debug, PartialEq, Eq, Copy, Clone, Hash)]
+#[derive(Debug, PartialEq, Eq, Copy, Clone, Hash, Serialize, Deserialize)]
 pub enum Signedness {
     Unsigned,
     Signed,
@@ -230,11 +251,17 @@ impl UnresolvedTypeExpression {
 
     fn from_expr_helper(expr: Expression) -> Result<UnresolvedTypeExpression, Expression> {
         match expr.kind {
-            ExpressionKind::Literal(Literal::Integer(int)) => match int.try_to_u64() {
+            ExpressionKind::Literal(Literal::Integer(int, negative)) => match int.try_to_u64() {
+                Some(int) if negative => {
+                    let lhs = Box::new(UnresolvedTypeExpression::Constant(0, expr.span));
+                    let rhs = Box::new(UnresolvedTypeExpression::Constant(int, expr.span));
+                    let op = BinaryTypeOperator::Subtraction;
+                    Ok(UnresolvedTypeExpression::BinaryOperation(lhs, op, rhs, expr.span))
+                }
                 Some(int) => Ok(UnresolvedTypeExpression::Constant(int, expr.span)),
                 None => Err(expr),
             },
-            ExpressionKind::Variable(path) => Ok(UnresolvedTypeExpression::Variable(path)),
+            ExpressionKind::Variable(path, None) => Ok(UnresolvedTypeExpression::Variable(path)),
             ExpressionKind::Prefix(prefix) if prefix.operator == UnaryOp::Minus => {
                 let lhs = Box::new(UnresolvedTypeExpression::Constant(0, expr.span));
                 let rhs = Box::new(UnresolvedTypeExpression::from_expr_helper(prefix.rhs)?);
@@ -270,7 +297,7 @@ impl UnresolvedTypeExpression {
     }
 }
 
-#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
 /// Represents whether the parameter is public or known only to the prover.
 pub enum Visibility {
     Public,
@@ -288,7 +315,7 @@ impl std::fmt::Display for Visibility {
     }
 }
 
-#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
 /// Represents whether the return value should compromise of unique witness indices such that no
 /// index occurs within the program's abi more than once.
 ///
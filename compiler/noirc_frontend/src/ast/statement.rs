@@ -6,6 +6,7 @@ use crate::token::Token;
 use crate::{Expression, ExpressionKind, IndexExpression, MemberAccessExpression, UnresolvedType};
 use iter_extended::vecmap;
 use noirc_errors::{Span, Spanned};
+use serde::{Deserialize, Serialize};
 
 /// This is used when an identifier fails to parse in the parser.
 /// Instead of failing the parse, we can often recover using this
@@ -14,7 +15,7 @@ use noirc_errors::{Span, Spanned};
 /// for an identifier that already failed to parse.
 pub const ERROR_IDENT: &str = "$error";
 
-#[derive(Debug, PartialEq, Eq, Clone)]
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
 pub struct Statement {
     pub kind: StatementKind,
     pub span: Span,
@@ -23,13 +24,16 @@ pub struct Statement {
 /// Ast node for statements in noir. Statements are always within a block { }
 /// of some kind and are terminated via a Semicolon, except if the statement
 /// ends in a block, such as a Statement::Expression containing an if expression.
-#[derive(Debug, PartialEq, Eq, Clone)]
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
 pub enum StatementKind {
     Let(LetStatement),
     Constrain(ConstrainStatement),
     Expression(Expression),
     Assign(AssignStatement),
     For(ForLoopStatement),
+    /// `loop { .. }`, which repeats its block until a `break` is reached
+    Loop(Expression),
+    Break,
     // This is an expression with a trailing semi-colon
     Semi(Expression),
     // This statement is the result of a recovered parse error.
@@ -61,13 +65,22 @@ impl Statement {
                 }
                 self.kind
             }
-            // A semicolon on a for loop is optional and does nothing
-            StatementKind::For(_) => self.kind,
+            // A semicolon on a loop is optional and does nothing
+            StatementKind::For(_) | StatementKind::Loop(_) => self.kind,
+            // `break` may leave out its semicolon at the end of a block, as in `if done { break }`
+            StatementKind::Break => {
+                if semi.is_none() && !last_statement_in_block {
+                    emit_error(missing_semicolon);
+                }
+                self.kind
+            }
 
             StatementKind::Expression(expr) => {
                 match (&expr.kind, semi, last_statement_in_block) {
                     // Semicolons are optional for these expressions
-                    (ExpressionKind::Block(_), semi, _) | (ExpressionKind::If(_), semi, _) => {
+                    (ExpressionKind::Block(_), semi, _)
+                    | (ExpressionKind::Comptime(_), semi, _)
+                    | (ExpressionKind::If(_), semi, _) => {
                         if semi.is_some() {
                             StatementKind::Semi(expr)
                         } else {
@@ -126,7 +139,7 @@ impl StatementKind {
     }
 }
 
-#[derive(Eq, Debug, Clone)]
+#[derive(Eq, Debug, Clone, Serialize, Deserialize)]
 pub struct Ident(pub Spanned<String>);
 
 impl PartialEq<Ident> for Ident {
@@ -193,7 +206,7 @@ impl From<Ident> for Expression {
     fn from(i: Ident) -> Expression {
         Expression {
             span: i.0.span(),
-            kind: ExpressionKind::Variable(Path { segments: vec![i], kind: PathKind::Plain }),
+            kind: ExpressionKind::Variable(Path { segments: vec![i], kind: PathKind::Plain }, None),
         }
     }
 }
@@ -231,20 +244,24 @@ pub trait Recoverable {
     fn error(span: Span) -> Self;
 }
 
-#[derive(Debug, PartialEq, Eq, Clone)]
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
 pub struct ImportStatement {
     pub path: Path,
     pub alias: Option<Ident>,
+
+    /// The span of the whole `use` statement if this is its only import, so that
+    /// the statement can be removed if the import turns out to be unused.
+    pub statement_span: Option<Span>,
 }
 
-#[derive(Debug, PartialEq, Eq, Copy, Clone, Hash)]
+#[derive(Debug, PartialEq, Eq, Copy, Clone, Hash, Serialize, Deserialize)]
 pub enum PathKind {
     Crate,
     Dep,
     Plain,
 }
 
-#[derive(Debug, PartialEq, Eq, Clone)]
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
 pub struct UseTree {
     pub prefix: Path,
     pub kind: UseTreeKind,
@@ -273,7 +290,7 @@ impl Display for UseTree {
     }
 }
 
-#[derive(Debug, PartialEq, Eq, Clone)]
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
 pub enum UseTreeKind {
     Path(Ident, Option<Ident>),
     List(Vec<UseTree>),
@@ -290,7 +307,7 @@ impl UseTree {
 
         match self.kind {
             UseTreeKind::Path(name, alias) => {
-                vec![ImportStatement { path: prefix.join(name), alias }]
+                vec![ImportStatement { path: prefix.join(name), alias, statement_span: None }]
             }
             UseTreeKind::List(trees) => {
                 trees.into_iter().flat_map(|tree| tree.desugar(Some(prefix.clone()))).collect()
@@ -302,7 +319,7 @@ impl UseTree {
 // Note: Path deliberately doesn't implement Recoverable.
 // No matter which default value we could give in Recoverable::error,
 // it would most likely cause further errors during name resolution
-#[derive(Debug, PartialEq, Eq, Clone, Hash)]
+#[derive(Debug, PartialEq, Eq, Clone, Hash, Serialize, Deserialize)]
 pub struct Path {
     pub segments: Vec<Ident>,
     pub kind: PathKind,
@@ -382,7 +399,7 @@ impl Path {
     }
 }
 
-#[derive(Debug, PartialEq, Eq, Clone)]
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
 pub struct LetStatement {
     pub pattern: Pattern,
     pub r#type: UnresolvedType,
@@ -397,14 +414,14 @@ impl LetStatement {
     }
 }
 
-#[derive(Debug, PartialEq, Eq, Clone)]
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
 pub struct AssignStatement {
     pub lvalue: LValue,
     pub expression: Expression,
 }
 
 /// Represents an Ast form that can be assigned to
-#[derive(Debug, PartialEq, Eq, Clone)]
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
 pub enum LValue {
     Ident(Ident),
     MemberAccess { object: Box<LValue>, field_name: Ident },
@@ -412,10 +429,39 @@ pub enum LValue {
     Dereference(Box<LValue>),
 }
 
-#[derive(Debug, PartialEq, Eq, Clone)]
-pub struct ConstrainStatement(pub Expression, pub Option<String>);
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
+pub struct ConstrainStatement(pub Expression, pub Option<AssertMessage>, pub ConstrainKind);
+
+/// The statement a [ConstrainStatement] was written as
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Serialize, Deserialize)]
+pub enum ConstrainKind {
+    Assert,
+    /// `assert_eq(lhs, rhs)`, whose condition is `lhs == rhs`
+    AssertEq,
+    /// A call to `std::assert_ne(lhs, rhs)`, whose condition is `lhs != rhs`
+    AssertNe,
+    Constrain,
+}
+
+/// A `static_assert(condition, "message");` item, whose condition is evaluated when the program
+/// is compiled, failing compilation with the message if it is false
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
+pub struct StaticAssert {
+    pub condition: Expression,
+    pub message: String,
+    pub span: Span,
+}
+
+/// The message reported when an `assert` fails
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
+pub enum AssertMessage {
+    /// A string literal, known when the program is compiled
+    Static(String),
+    /// A format string, whose interpolated values are resolved when the assertion fails
+    Formatted(Expression),
+}
 
-#[derive(Debug, PartialEq, Eq, Clone)]
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
 pub enum Pattern {
     Identifier(Ident),
     Mutable(Box<Pattern>, Span),
@@ -427,10 +473,20 @@ impl Pattern {
     pub fn name_ident(&self) -> &Ident {
         match self {
             Pattern::Identifier(name_ident) => name_ident,
+            Pattern::Mutable(pattern, _) => pattern.name_ident(),
             _ => panic!("only the identifier pattern can return a name"),
         }
     }
 
+    pub fn span(&self) -> Span {
+        match self {
+            Pattern::Identifier(ident) => ident.span(),
+            Pattern::Mutable(_, span) | Pattern::Tuple(_, span) | Pattern::Struct(_, _, span) => {
+                *span
+            }
+        }
+    }
+
     pub(crate) fn into_ident(self) -> Ident {
         match self {
             Pattern::Identifier(ident) => ident,
@@ -449,7 +505,7 @@ impl Recoverable for Pattern {
 impl LValue {
     fn as_expression(&self, span: Span) -> Expression {
         let kind = match self {
-            LValue::Ident(ident) => ExpressionKind::Variable(Path::from_ident(ident.clone())),
+            LValue::Ident(ident) => ExpressionKind::Variable(Path::from_ident(ident.clone()), None),
             LValue::MemberAccess { object, field_name } => {
                 ExpressionKind::MemberAccess(Box::new(MemberAccessExpression {
                     lhs: object.as_expression(span),
@@ -471,7 +527,7 @@ impl LValue {
     }
 }
 
-#[derive(Debug, PartialEq, Eq, Clone)]
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
 pub struct ForLoopStatement {
     pub identifier: Ident,
     pub start_range: Expression,
@@ -487,6 +543,8 @@ impl Display for StatementKind {
             StatementKind::Expression(expression) => expression.fmt(f),
             StatementKind::Assign(assign) => assign.fmt(f),
             StatementKind::For(for_loop) => for_loop.fmt(f),
+            StatementKind::Loop(block) => write!(f, "loop {block}"),
+            StatementKind::Break => write!(f, "break"),
             StatementKind::Semi(semi) => write!(f, "{semi};"),
             StatementKind::Error => write!(f, "Error"),
         }
@@ -505,6 +563,12 @@ impl Display for ConstrainStatement {
     }
 }
 
+impl Display for StaticAssert {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "static_assert({}, \"{}\")", self.condition, self.message)
+    }
+}
+
 impl Display for AssignStatement {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "{} = {}", self.lvalue, self.expression)
@@ -1,11 +1,15 @@
 use std::fmt::Display;
 
-use crate::{token::SecondaryAttribute, Ident, UnresolvedGenerics, UnresolvedType};
+use crate::{
+    token::{SecondaryAttribute, StructLayout},
+    Ident, UnresolvedGenerics, UnresolvedType,
+};
 use iter_extended::vecmap;
 use noirc_errors::Span;
+use serde::{Deserialize, Serialize};
 
 /// Ast node for a struct
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub struct NoirStruct {
     pub name: Ident,
     pub attributes: Vec<SecondaryAttribute>,
@@ -24,6 +28,30 @@ impl NoirStruct {
     ) -> NoirStruct {
         NoirStruct { name, attributes, generics, fields, span }
     }
+
+    /// The names of the traits listed in this struct's `#[derive(...)]` attributes
+    pub fn derived_traits(&self) -> impl Iterator<Item = &String> {
+        self.attributes.iter().flat_map(|attribute| match attribute {
+            SecondaryAttribute::Derive(names) => names.as_slice(),
+            _ => [].as_slice(),
+        })
+    }
+
+    /// The layout set by this struct's last `#[repr(...)]` attribute, if it has one
+    pub fn layout(&self) -> StructLayout {
+        let layouts = self.attributes.iter().filter_map(|attribute| match attribute {
+            SecondaryAttribute::Repr(layout) => Some(*layout),
+            _ => None,
+        });
+        layouts.last().unwrap_or_default()
+    }
+
+    /// The fields of this struct in the order given by its layout
+    pub fn fields_in_layout_order(&self) -> Vec<&(Ident, UnresolvedType)> {
+        let mut fields: Vec<_> = self.fields.iter().collect();
+        self.layout().arrange(&mut fields, |(name, _)| &name.0.contents);
+        fields
+    }
 }
 
 impl Display for NoirStruct {
@@ -2,17 +2,19 @@ use std::fmt::Display;
 
 use iter_extended::vecmap;
 use noirc_errors::Span;
+use serde::{Deserialize, Serialize};
 
 use crate::{
-    node_interner::TraitId, BlockExpression, Expression, FunctionReturnType, Ident, NoirFunction,
-    Path, UnresolvedGenerics, UnresolvedType,
+    node_interner::TraitId, token::SecondaryAttribute, BlockExpression, Expression,
+    FunctionReturnType, Ident, NoirFunction, Path, UnresolvedGenerics, UnresolvedType,
 };
 
 /// AST node for trait definitions:
 /// `trait name<generics> { ... items ... }`
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct NoirTrait {
     pub name: Ident,
+    pub attributes: Vec<SecondaryAttribute>,
     pub generics: Vec<Ident>,
     pub where_clause: Vec<UnresolvedTraitConstraint>,
     pub span: Span,
@@ -21,7 +23,7 @@ pub struct NoirTrait {
 
 /// Any declaration inside the body of a trait that a user is required to
 /// specify when implementing the trait.
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub enum TraitItem {
     Function {
         name: Ident,
@@ -42,18 +44,19 @@ pub enum TraitItem {
 }
 
 /// Ast node for an impl of a concrete type
-/// `impl object_type<generics> { ... methods ... }`
-#[derive(Clone, Debug)]
+/// `impl object_type<generics> where where_clauses { ... methods ... }`
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct TypeImpl {
     pub object_type: UnresolvedType,
     pub type_span: Span,
     pub generics: UnresolvedGenerics,
+    pub where_clause: Vec<UnresolvedTraitConstraint>,
     pub methods: Vec<NoirFunction>,
 }
 
 /// Ast node for an implementation of a trait for a particular type
 /// `impl trait_name<trait_generics> for object_type where where_clauses { ... items ... }`
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct NoirTraitImpl {
     pub impl_generics: UnresolvedGenerics,
 
@@ -73,21 +76,22 @@ pub struct NoirTraitImpl {
 ///   `Foo: Display`
 ///   `Foo: TraitX`
 ///   `Foo: TraitY<U, V>`
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub struct UnresolvedTraitConstraint {
     pub typ: UnresolvedType,
     pub trait_bound: TraitBound,
 }
 
 /// Represents a single trait bound, such as `TraitX` or `TraitY<U, V>`
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub struct TraitBound {
     pub trait_path: Path,
+    #[serde(skip)]
     pub trait_id: Option<TraitId>, // initially None, gets assigned during DC
     pub trait_generics: Vec<UnresolvedType>,
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub enum TraitImplItem {
     Function(NoirFunction),
     Constant(Ident, UnresolvedType, Expression),
@@ -99,7 +103,14 @@ impl Display for TypeImpl {
         let generics = vecmap(&self.generics, |generic| generic.to_string());
         let generics = if generics.is_empty() { "".into() } else { generics.join(", ") };
 
-        writeln!(f, "impl{} {} {{", generics, self.object_type)?;
+        let where_clause = vecmap(&self.where_clause, ToString::to_string);
+        let where_clause = if where_clause.is_empty() {
+            "".into()
+        } else {
+            format!(" where {}", where_clause.join(", "))
+        };
+
+        writeln!(f, "impl{} {}{} {{", generics, self.object_type, where_clause)?;
 
         for method in self.methods.iter() {
             let method = method.to_string();
@@ -189,7 +200,18 @@ impl Display for NoirTraitImpl {
         let generics = vecmap(&self.trait_generics, |generic| generic.to_string());
         let generics = generics.join(", ");
 
-        writeln!(f, "impl {}<{}> for {} {{", self.trait_name, generics, self.object_type)?;
+        let where_clause = vecmap(&self.where_clause, ToString::to_string);
+        let where_clause = if where_clause.is_empty() {
+            "".into()
+        } else {
+            format!(" where {}", where_clause.join(", "))
+        };
+
+        writeln!(
+            f,
+            "impl {}<{}> for {}{} {{",
+            self.trait_name, generics, self.object_type, where_clause
+        )?;
 
         for item in self.items.iter() {
             let item = item.to_string();
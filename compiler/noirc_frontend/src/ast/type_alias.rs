@@ -1,10 +1,11 @@
 use crate::{Ident, UnresolvedGenerics, UnresolvedType};
 use iter_extended::vecmap;
 use noirc_errors::Span;
+use serde::{Deserialize, Serialize};
 use std::fmt::Display;
 
 /// Ast node for type aliases
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct NoirTypeAlias {
     pub name: Ident,
     pub generics: UnresolvedGenerics,
@@ -43,15 +43,15 @@ fn expression(kind: ExpressionKind) -> Expression {
 }
 
 fn variable(name: &str) -> Expression {
-    expression(ExpressionKind::Variable(ident_path(name)))
+    expression(ExpressionKind::Variable(ident_path(name), None))
 }
 
 fn variable_ident(identifier: Ident) -> Expression {
-    expression(ExpressionKind::Variable(path(identifier)))
+    expression(ExpressionKind::Variable(path(identifier), None))
 }
 
 fn variable_path(path: Path) -> Expression {
-    expression(ExpressionKind::Variable(path))
+    expression(ExpressionKind::Variable(path, None))
 }
 
 fn method_call(object: Expression, method_name: &str, arguments: Vec<Expression>) -> Expression {
@@ -152,7 +152,7 @@ fn index_array_variable(array: Expression, index: &str) -> Expression {
 }
 
 fn import(path: Path) -> ImportStatement {
-    ImportStatement { path, alias: None }
+    ImportStatement { path, alias: None, statement_span: None }
 }
 
 //
@@ -227,7 +227,7 @@ fn check_for_storage_definition(module: &SortedModule) -> bool {
 /// Checks if an attribute is a custom attribute with a specific name
 fn is_custom_attribute(attr: &SecondaryAttribute, attribute_name: &str) -> bool {
     if let SecondaryAttribute::Custom(custom_attr) = attr {
-        custom_attr.as_str() == attribute_name
+        custom_attr.to_string() == attribute_name
     } else {
         false
     }
@@ -443,6 +443,7 @@ fn generate_selector_impl(structure: &NoirStruct) -> TypeImpl {
         object_type: struct_type,
         type_span: structure.span,
         generics: vec![],
+        where_clause: vec![],
         methods: vec![NoirFunction::normal(selector_fn_def)],
     }
 }
@@ -837,9 +838,10 @@ fn create_loop_over(var: Expression, loop_body: Vec<Statement>) -> Statement {
     // `for i in 0..{ident}.len()`
     make_statement(StatementKind::For(ForLoopStatement {
         identifier: ident("i"),
-        start_range: expression(ExpressionKind::Literal(Literal::Integer(FieldElement::from(
-            i128::from(0),
-        )))),
+        start_range: expression(ExpressionKind::Literal(Literal::Integer(
+            FieldElement::from(i128::from(0)),
+            false,
+        ))),
         end_range: end_range_expression,
         block: for_loop_block,
     }))
@@ -0,0 +1,104 @@
+//! Cooperative cancellation of in-flight compilations.
+//!
+//! Long running passes call [`CancellationToken::check`] periodically, e.g. once per function.
+//! Rather than threading a `Result` through every pass, a cancelled check unwinds the stack
+//! with a [`Cancelled`] payload which is caught again by [`Cancelled::catch`]. Any state the
+//! cancelled compilation was working on, such as its `Context`, should then be discarded.
+//! The same applies to a compilation which panicked, which `catch` reports as an error too so
+//! that long-lived callers like the language server keep running.
+use std::panic::{catch_unwind, resume_unwind, AssertUnwindSafe};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+/// A handle which can be cloned and sent to another thread to cancel the compilation
+/// holding it. A default token is never cancelled.
+#[derive(Debug, Clone, Default)]
+pub struct CancellationToken(Arc<AtomicBool>);
+
+impl CancellationToken {
+    pub fn new() -> CancellationToken {
+        CancellationToken::default()
+    }
+
+    /// Requests that the compilation holding this token stops at its next check.
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+
+    /// Unwinds with [`Cancelled`] if this token has been cancelled.
+    pub fn check(&self) {
+        if self.is_cancelled() {
+            // `resume_unwind` skips the panic hook, so nothing is printed for cancellations.
+            resume_unwind(Box::new(Cancelled));
+        }
+    }
+}
+
+/// The payload a cancelled compilation unwinds with.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Cancelled;
+
+impl Cancelled {
+    /// Runs `f`, returning an error if it was cancelled or panicked part way through.
+    pub fn catch<T>(f: impl FnOnce() -> T) -> Result<T, Interrupted> {
+        catch_unwind(AssertUnwindSafe(f)).map_err(|payload| {
+            if payload.is::<Cancelled>() {
+                return Interrupted::Cancelled;
+            }
+            let message = match payload.downcast::<String>() {
+                Ok(message) => *message,
+                Err(payload) => match payload.downcast::<&'static str>() {
+                    Ok(message) => message.to_string(),
+                    Err(_) => "unknown panic".to_string(),
+                },
+            };
+            Interrupted::Panicked(message)
+        })
+    }
+}
+
+/// Why a compilation run with [`Cancelled::catch`] stopped before it finished.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Interrupted {
+    /// The compilation's [`CancellationToken`] was cancelled.
+    Cancelled,
+    /// The compiler panicked with the given message. This is always a bug in the compiler.
+    Panicked(String),
+}
+
+impl std::fmt::Display for Cancelled {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "compilation was cancelled")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{CancellationToken, Cancelled, Interrupted};
+
+    #[test]
+    fn cancelled_checks_unwind() {
+        let token = CancellationToken::new();
+        assert_eq!(Cancelled::catch(|| token.check()), Ok(()));
+
+        token.clone().cancel();
+        assert_eq!(Cancelled::catch(|| token.check()), Err(Interrupted::Cancelled));
+    }
+
+    #[test]
+    fn other_panics_are_returned_with_their_message() {
+        let message = "not a cancellation".to_string();
+        assert_eq!(
+            Cancelled::catch(|| panic!("not a cancellation")),
+            Err::<(), _>(Interrupted::Panicked(message.clone()))
+        );
+        assert_eq!(
+            Cancelled::catch(|| panic!("{message}")),
+            Err::<(), _>(Interrupted::Panicked(message))
+        );
+    }
+}
@@ -0,0 +1,74 @@
+use noirc_errors::codes::{E0600, E0601, E0602, E0603};
+use noirc_errors::CustomDiagnostic as Diagnostic;
+use noirc_errors::{ErrorCode, Location};
+use thiserror::Error;
+
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum ComptimeError {
+    #[error("Value is not known at compile time")]
+    NonComptimeValue { name: String, location: Location },
+    #[error("Expression cannot be evaluated at compile time")]
+    Unsupported { expression: &'static str, location: Location },
+    #[error("Assertion failed at compile time")]
+    AssertionFailed { message: Option<String>, location: Location },
+    #[error("Compile-time evaluation failed")]
+    EvaluationFailed { reason: String, location: Location },
+}
+
+impl ComptimeError {
+    pub fn location(&self) -> Location {
+        match self {
+            ComptimeError::NonComptimeValue { location, .. }
+            | ComptimeError::Unsupported { location, .. }
+            | ComptimeError::AssertionFailed { location, .. }
+            | ComptimeError::EvaluationFailed { location, .. } => *location,
+        }
+    }
+
+    pub fn code(&self) -> ErrorCode {
+        match self {
+            ComptimeError::NonComptimeValue { .. } => E0600,
+            ComptimeError::Unsupported { .. } => E0601,
+            ComptimeError::AssertionFailed { .. } => E0602,
+            ComptimeError::EvaluationFailed { .. } => E0603,
+        }
+    }
+}
+
+impl From<ComptimeError> for Diagnostic {
+    fn from(error: ComptimeError) -> Diagnostic {
+        let code = error.code();
+        let diagnostic = match error {
+            ComptimeError::NonComptimeValue { name, location } => {
+                let mut diagnostic = Diagnostic::simple_error(
+                    format!("The value of `{name}` is not known at compile time"),
+                    "used in code which is evaluated at compile time".to_string(),
+                    location.span,
+                );
+                diagnostic.add_note(
+                    "Only globals and variables declared in compile-time code can be used"
+                        .to_string(),
+                );
+                diagnostic
+            }
+            ComptimeError::Unsupported { expression, location } => Diagnostic::simple_error(
+                format!("{expression} cannot be evaluated at compile time"),
+                String::new(),
+                location.span,
+            ),
+            ComptimeError::AssertionFailed { message, location } => {
+                let message = match message {
+                    Some(message) => format!("Assertion failed at compile time: '{message}'"),
+                    None => "Assertion failed at compile time".to_string(),
+                };
+                Diagnostic::simple_error(message, String::new(), location.span)
+            }
+            ComptimeError::EvaluationFailed { reason, location } => Diagnostic::simple_error(
+                format!("Compile-time evaluation failed: {reason}"),
+                String::new(),
+                location.span,
+            ),
+        };
+        diagnostic.with_code(code)
+    }
+}
@@ -0,0 +1,986 @@
+use std::collections::HashMap;
+
+use acvm::FieldElement;
+use iter_extended::{try_vecmap, vecmap};
+use noirc_errors::Location;
+
+use crate::hir_def::expr::{
+    HirArrayLiteral, HirCallExpression, HirCastExpression, HirConstructorExpression, HirExpression,
+    HirIdent, HirIfExpression, HirIndexExpression, HirInfixExpression, HirLambda, HirLiteral,
+    HirMemberAccess, HirPrefixExpression,
+};
+use crate::hir_def::stmt::{
+    HirAssertMessage, HirAssignStatement, HirConstrainStatement, HirForStatement, HirLValue,
+    HirLetStatement, HirPattern, HirStatement,
+};
+use crate::hir_def::types::TypeBindings;
+use crate::node_interner::{
+    DefinitionId, DefinitionKind, ExprId, FuncId, NodeInterner, StmtId, TraitMethodId,
+};
+use crate::{BinaryOpKind, FunctionKind, Signedness, Type, TypeBinding, UnaryOp};
+
+use super::errors::ComptimeError;
+use super::value::Value;
+
+/// The maximum depth of function calls while evaluating compile-time code, which stops
+/// unbounded recursion from overflowing the compiler's stack.
+const MAX_CALL_DEPTH: usize = 128;
+
+type IResult<T> = Result<T, ComptimeError>;
+
+/// A call to a builtin function, whose arguments are evaluated separately
+struct BuiltinCall {
+    func_id: FuncId,
+    /// The bindings of the function's generics
+    bindings: TypeBindings,
+    return_type: Type,
+    location: Location,
+}
+
+/// Evaluates expressions of the HIR of a program after it has been type checked.
+///
+/// Only code which doesn't depend on the program's inputs can be evaluated, so the only
+/// variables visible are globals and those defined by the code being evaluated.
+pub(super) struct Interpreter<'interner> {
+    interner: &'interner NodeInterner,
+
+    /// The variables of each function call being evaluated, with the innermost call last.
+    frames: Vec<HashMap<DefinitionId, Value>>,
+
+    /// The values of globals which have already been evaluated.
+    globals: HashMap<DefinitionId, Value>,
+}
+
+impl<'interner> Interpreter<'interner> {
+    pub(super) fn new(interner: &'interner NodeInterner) -> Self {
+        Self { interner, frames: Vec::new(), globals: HashMap::new() }
+    }
+
+    /// Evaluates an expression outside of any function call.
+    pub(super) fn evaluate_root(&mut self, expr: ExprId) -> IResult<Value> {
+        self.frames.push(HashMap::new());
+        let result = self.evaluate(expr);
+        self.frames.pop();
+        result
+    }
+
+    fn evaluate(&mut self, expr: ExprId) -> IResult<Value> {
+        let value = self.evaluate_unconverted(expr)?;
+        match self.interner.get_conversion(expr).cloned() {
+            Some(conversion) => {
+                let function =
+                    Value::Function(conversion.function, follow_bindings(&conversion.bindings));
+                let return_type = conversion.target.follow_bindings();
+                self.call_function(function, vec![value], return_type, self.location(expr))
+            }
+            None => Ok(value),
+        }
+    }
+
+    /// Evaluates an expression without the conversion of its value with a `From` impl, if any.
+    fn evaluate_unconverted(&mut self, expr: ExprId) -> IResult<Value> {
+        match self.interner.expression(&expr) {
+            HirExpression::Ident(ident, _) => self.evaluate_ident(ident, expr),
+            HirExpression::Literal(literal) => self.evaluate_literal(literal, expr),
+            HirExpression::Block(block) => self.evaluate_block(block.0),
+            HirExpression::Prefix(prefix) => self.evaluate_prefix(prefix, expr),
+            HirExpression::Infix(infix) => self.evaluate_infix(infix, expr),
+            HirExpression::Index(index) => self.evaluate_index(index),
+            HirExpression::Constructor(constructor) => self.evaluate_constructor(constructor, expr),
+            HirExpression::MemberAccess(access) => self.evaluate_member_access(access, expr),
+            HirExpression::Call(call) => self.evaluate_call(call, expr),
+            HirExpression::Cast(cast) => self.evaluate_cast(cast, expr),
+            HirExpression::If(if_expr) => self.evaluate_if(if_expr),
+            HirExpression::Tuple(fields) => {
+                Ok(Value::Tuple(try_vecmap(fields, |field| self.evaluate(field))?))
+            }
+            HirExpression::Lambda(lambda) => self.evaluate_lambda(lambda),
+            HirExpression::TraitMethodReference(self_type, method) => {
+                self.trait_method(&self_type, method, expr, self.location(expr))
+            }
+            HirExpression::MethodCall(_) => {
+                unreachable!("Method calls are replaced with function calls when type checking")
+            }
+            HirExpression::Error => unreachable!("Programs with errors are not evaluated"),
+        }
+    }
+
+    fn evaluate_ident(&mut self, ident: HirIdent, expr: ExprId) -> IResult<Value> {
+        let definition = self.interner.definition(ident.id);
+        match &definition.kind {
+            DefinitionKind::Function(func_id) => {
+                let bindings = self.interner.get_instantiation_bindings(expr);
+                Ok(Value::Function(*func_id, follow_bindings(bindings)))
+            }
+            // Mutable globals only exist at runtime, in unconstrained code
+            DefinitionKind::Global(_) if definition.mutable => Err(self.non_comptime_value(&ident)),
+            DefinitionKind::Global(global) => {
+                if let Some(value) = self.globals.get(&ident.id) {
+                    return Ok(value.clone());
+                }
+                let value = self.evaluate_root(*global)?;
+                self.globals.insert(ident.id, value.clone());
+                Ok(value)
+            }
+            DefinitionKind::Local(_) => match self.frame().get(&ident.id) {
+                Some(value) => Ok(value.clone()),
+                None => Err(self.non_comptime_value(&ident)),
+            },
+            DefinitionKind::GenericType(type_variable) => match &*type_variable.borrow() {
+                TypeBinding::Bound(binding) => match binding.evaluate_to_u64() {
+                    Some(value) => Ok(Value::Field(FieldElement::from(value as u128))),
+                    None => Err(self.non_comptime_value(&ident)),
+                },
+                TypeBinding::Unbound(_) => Err(self.non_comptime_value(&ident)),
+            },
+        }
+    }
+
+    fn evaluate_literal(&mut self, literal: HirLiteral, expr: ExprId) -> IResult<Value> {
+        let location = self.location(expr);
+        match literal {
+            HirLiteral::Unit => Ok(Value::Unit),
+            HirLiteral::Bool(boolean) => Ok(Value::Bool(boolean)),
+            HirLiteral::Integer(magnitude, negative) => {
+                let typ = self.interner.id_type(expr);
+                check_integer_type(&typ, location)?;
+                Value::integer(magnitude, negative, &typ).ok_or_else(|| {
+                    let sign = if negative { "-" } else { "" };
+                    let reason = format!("the literal {sign}{magnitude} doesn't fit in `{typ}`");
+                    ComptimeError::EvaluationFailed { reason, location }
+                })
+            }
+            HirLiteral::Str(string) => Ok(Value::String(string)),
+            HirLiteral::FmtStr(..) => {
+                Err(ComptimeError::Unsupported { expression: "A format string", location })
+            }
+            HirLiteral::Array(HirArrayLiteral::Standard(elements)) => {
+                let elements = try_vecmap(elements, |element| self.evaluate(element))?;
+                Ok(Value::Array(elements, self.interner.id_type(expr).follow_bindings()))
+            }
+            HirLiteral::Array(HirArrayLiteral::Repeated { repeated_element, length }) => {
+                let length = match length.evaluate_to_u64() {
+                    Some(length) => length as usize,
+                    None => {
+                        let reason = format!("the array length `{length}` is not known");
+                        return Err(ComptimeError::EvaluationFailed { reason, location });
+                    }
+                };
+                let element = self.evaluate(repeated_element)?;
+                let typ = self.interner.id_type(expr).follow_bindings();
+                Ok(Value::Array(vec![element; length], typ))
+            }
+            HirLiteral::Array(HirArrayLiteral::Comprehension {
+                element,
+                identifier,
+                start,
+                end,
+                length: _,
+            }) => self.evaluate_comprehension(element, identifier, start, end, expr),
+        }
+    }
+
+    fn evaluate_comprehension(
+        &mut self,
+        element: ExprId,
+        identifier: HirIdent,
+        start_range: ExprId,
+        end_range: ExprId,
+        expr: ExprId,
+    ) -> IResult<Value> {
+        let location = self.location(start_range);
+        let start = self.evaluate(start_range)?;
+        let end = self.evaluate(end_range)?;
+
+        let (start_index, end_index) =
+            loop_bound(&start).zip(loop_bound(&end)).ok_or_else(|| {
+                let reason = "the bounds of the array comprehension are too large".to_string();
+                ComptimeError::EvaluationFailed { reason, location }
+            })?;
+
+        if end_index < start_index {
+            let reason = format!("the range {start_index}..{end_index} ends before it starts");
+            return Err(ComptimeError::EvaluationFailed { reason, location });
+        }
+
+        let mut elements = Vec::with_capacity((end_index - start_index) as usize);
+        for index in start_index..end_index {
+            self.interner.cancellation_token().check();
+            self.frame_mut().insert(identifier.id, loop_index(&start, index));
+            elements.push(self.evaluate(element)?);
+        }
+
+        Ok(Value::Array(elements, self.interner.id_type(expr).follow_bindings()))
+    }
+
+    fn evaluate_block(&mut self, statements: Vec<StmtId>) -> IResult<Value> {
+        let mut result = Value::Unit;
+        for statement in statements {
+            result = self.evaluate_statement(statement)?;
+        }
+        Ok(result)
+    }
+
+    /// Evaluates a statement, returning the value of the block it ends if it is an expression.
+    fn evaluate_statement(&mut self, statement: StmtId) -> IResult<Value> {
+        match self.interner.statement(&statement) {
+            HirStatement::Let(let_statement) => self.evaluate_let(let_statement),
+            HirStatement::Constrain(constrain) => self.evaluate_constrain(constrain),
+            HirStatement::Assign(assign) => self.evaluate_assign(assign),
+            HirStatement::For(for_loop) => self.evaluate_for(for_loop),
+            HirStatement::Loop(block) => {
+                let location = self.location(block);
+                Err(ComptimeError::Unsupported { expression: "A `loop`", location })
+            }
+            HirStatement::Break => unreachable!("`break` is only allowed within a `loop`"),
+            HirStatement::Expression(expr) => self.evaluate(expr),
+            HirStatement::Semi(expr) => {
+                self.evaluate(expr)?;
+                Ok(Value::Unit)
+            }
+            HirStatement::Error => unreachable!("Programs with errors are not evaluated"),
+        }
+    }
+
+    fn evaluate_let(&mut self, let_statement: HirLetStatement) -> IResult<Value> {
+        let value = self.evaluate(let_statement.expression)?;
+        self.bind_pattern(&let_statement.pattern, value);
+        Ok(Value::Unit)
+    }
+
+    fn evaluate_constrain(&mut self, constrain: HirConstrainStatement) -> IResult<Value> {
+        let HirConstrainStatement(condition, _, message) = constrain;
+        // Format strings can't be evaluated at compile time, so only static messages are reported
+        let message = match message {
+            Some(HirAssertMessage::Static(message)) => Some(message),
+            Some(HirAssertMessage::Dynamic(_)) | None => None,
+        };
+        match self.evaluate(condition)? {
+            Value::Bool(true) => Ok(Value::Unit),
+            _ => {
+                Err(ComptimeError::AssertionFailed { message, location: self.location(condition) })
+            }
+        }
+    }
+
+    fn evaluate_assign(&mut self, assign: HirAssignStatement) -> IResult<Value> {
+        let value = self.evaluate(assign.expression)?;
+        let (ident, path) = self.lvalue_path(assign.lvalue)?;
+
+        if !self.frame().contains_key(&ident.id) {
+            return Err(self.non_comptime_value(&ident));
+        }
+        let mut target = self.frame_mut().get_mut(&ident.id).expect("Checked above");
+        for (index, location) in path {
+            target = match target {
+                Value::Array(elements, _) => {
+                    let length = elements.len();
+                    match elements.get_mut(index) {
+                        Some(element) => element,
+                        None => return Err(index_out_of_bounds(index, length, location)),
+                    }
+                }
+                Value::Tuple(fields) | Value::Struct(fields, _) => &mut fields[index],
+                other => unreachable!("Cannot assign to a field or element of {other:?}"),
+            };
+        }
+        *target = value;
+        Ok(Value::Unit)
+    }
+
+    /// Returns the variable being assigned to, along with the index and location of each
+    /// field or element accessed within it to reach the part being assigned.
+    fn lvalue_path(&mut self, lvalue: HirLValue) -> IResult<(HirIdent, Vec<(usize, Location)>)> {
+        match lvalue {
+            HirLValue::Ident(ident, _) => Ok((ident, Vec::new())),
+            HirLValue::MemberAccess { object, field_name, field_index, .. } => {
+                let (ident, mut path) = self.lvalue_path(*object)?;
+                let field_index = field_index.expect("Field indices are set when type checking");
+                let location = Location::new(field_name.span(), ident.location.file);
+                path.push((field_index, location));
+                Ok((ident, path))
+            }
+            HirLValue::Index { array, index, .. } => {
+                let (ident, mut path) = self.lvalue_path(*array)?;
+                let location = self.location(index);
+                let index = self.evaluate(index)?;
+                path.push((to_index(&index, location)?, location));
+                Ok((ident, path))
+            }
+            HirLValue::Dereference { lvalue, .. } => {
+                let (ident, _) = self.lvalue_path(*lvalue)?;
+                let location = ident.location;
+                Err(ComptimeError::Unsupported { expression: "A mutable reference", location })
+            }
+        }
+    }
+
+    fn evaluate_for(&mut self, for_loop: HirForStatement) -> IResult<Value> {
+        let start_location = self.location(for_loop.start_range);
+        let start = self.evaluate(for_loop.start_range)?;
+        let end = self.evaluate(for_loop.end_range)?;
+
+        let bounds = loop_bound(&start).zip(loop_bound(&end));
+        let (start_index, end_index) = bounds.ok_or_else(|| {
+            let reason = "the bounds of the loop are too large".to_string();
+            ComptimeError::EvaluationFailed { reason, location: start_location }
+        })?;
+
+        for index in start_index..end_index {
+            self.interner.cancellation_token().check();
+            self.frame_mut().insert(for_loop.identifier.id, loop_index(&start, index));
+            self.evaluate(for_loop.block)?;
+        }
+        Ok(Value::Unit)
+    }
+
+    fn evaluate_prefix(&mut self, prefix: HirPrefixExpression, expr: ExprId) -> IResult<Value> {
+        let location = self.location(expr);
+        let rhs = self.evaluate(prefix.rhs)?;
+        match (prefix.operator, rhs) {
+            (UnaryOp::Minus, Value::Field(field)) => Ok(Value::Field(-field)),
+            (UnaryOp::Minus, Value::Integer(bits, signedness, bit_size)) => {
+                let value = Value::integer_to_i128(bits, signedness, bit_size);
+                Value::from_i128(-value, signedness, bit_size)
+                    .ok_or_else(|| overflow("negate", location))
+            }
+            (UnaryOp::Not, Value::Bool(boolean)) => Ok(Value::Bool(!boolean)),
+            (UnaryOp::Not, Value::Integer(bits, signedness, bit_size)) => {
+                Ok(Value::wrapping_integer(!bits, signedness, bit_size))
+            }
+            (UnaryOp::MutableReference, _) | (UnaryOp::Dereference { .. }, _) => {
+                Err(ComptimeError::Unsupported { expression: "A mutable reference", location })
+            }
+            (operator, rhs) => unreachable!("Cannot apply {operator:?} to {rhs:?}"),
+        }
+    }
+
+    fn evaluate_infix(&mut self, infix: HirInfixExpression, expr: ExprId) -> IResult<Value> {
+        let location = self.location(expr);
+        let lhs = self.evaluate(infix.lhs)?;
+        let rhs = self.evaluate(infix.rhs)?;
+
+        let operator = infix.operator.kind;
+        if let Some((method, function_type)) = self.interner.get_operator_overload(expr).cloned() {
+            let return_type = match function_type {
+                Type::Function(_, return_type, _) => return_type.follow_bindings(),
+                _ => unreachable!("Operator trait methods should always have a function type"),
+            };
+            let self_type = self.interner.id_type(infix.lhs);
+            let function = self.trait_method(&self_type, method, expr, location)?;
+            let result = self.call_function(function, vec![lhs, rhs], return_type, location)?;
+            return Ok(operator_overload_result(operator, result));
+        }
+
+        use BinaryOpKind::*;
+        match (lhs, rhs) {
+            (Value::Field(lhs), Value::Field(rhs)) => {
+                let result = match operator {
+                    Add => lhs + rhs,
+                    Subtract => lhs - rhs,
+                    Multiply => lhs * rhs,
+                    Divide if rhs.is_zero() => return Err(division_by_zero(location)),
+                    Divide => lhs / rhs,
+                    Equal => return Ok(Value::Bool(lhs == rhs)),
+                    NotEqual => return Ok(Value::Bool(lhs != rhs)),
+                    Less => return Ok(Value::Bool(lhs < rhs)),
+                    LessEqual => return Ok(Value::Bool(lhs <= rhs)),
+                    Greater => return Ok(Value::Bool(lhs > rhs)),
+                    GreaterEqual => return Ok(Value::Bool(lhs >= rhs)),
+                    And | Or | Xor | ShiftRight | ShiftLeft | Modulo => {
+                        unreachable!("Cannot apply {operator} to fields")
+                    }
+                };
+                Ok(Value::Field(result))
+            }
+            (Value::Integer(lhs, signedness, bit_size), Value::Integer(rhs, ..)) => {
+                evaluate_integer_infix(lhs, operator, rhs, signedness, bit_size, location)
+            }
+            (Value::Bool(lhs), Value::Bool(rhs)) => match operator {
+                And => Ok(Value::Bool(lhs & rhs)),
+                Or => Ok(Value::Bool(lhs | rhs)),
+                Xor | NotEqual => Ok(Value::Bool(lhs != rhs)),
+                Equal => Ok(Value::Bool(lhs == rhs)),
+                _ => unreachable!("Cannot apply {operator} to booleans"),
+            },
+            (lhs, rhs) => match operator {
+                Equal => Ok(Value::Bool(lhs.structurally_equal(&rhs))),
+                NotEqual => Ok(Value::Bool(!lhs.structurally_equal(&rhs))),
+                _ => unreachable!("Cannot apply {operator} to {lhs:?} and {rhs:?}"),
+            },
+        }
+    }
+
+    fn evaluate_index(&mut self, index: HirIndexExpression) -> IResult<Value> {
+        let collection = self.evaluate(index.collection)?;
+        let location = self.location(index.index);
+        let index = to_index(&self.evaluate(index.index)?, location)?;
+
+        match collection {
+            Value::Array(mut elements, _) => {
+                let length = elements.len();
+                if index < length {
+                    Ok(elements.swap_remove(index))
+                } else {
+                    Err(index_out_of_bounds(index, length, location))
+                }
+            }
+            Value::String(string) => match string.as_bytes().get(index) {
+                Some(byte) => Ok(Value::Integer(*byte as u128, Signedness::Unsigned, 8)),
+                None => Err(index_out_of_bounds(index, string.len(), location)),
+            },
+            other => unreachable!("Cannot index {other:?}"),
+        }
+    }
+
+    fn evaluate_constructor(
+        &mut self,
+        constructor: HirConstructorExpression,
+        expr: ExprId,
+    ) -> IResult<Value> {
+        // Fields are evaluated in the order they're written but stored in the order they're declared
+        let mut fields = HashMap::new();
+        for (name, field) in constructor.fields {
+            fields.insert(name.0.contents, self.evaluate(field)?);
+        }
+
+        let fields = constructor
+            .r#type
+            .borrow()
+            .get_fields(&constructor.struct_generics)
+            .into_iter()
+            .map(|(name, _)| fields.remove(&name).expect("Constructors define every field"))
+            .collect();
+        Ok(Value::Struct(fields, self.interner.id_type(expr).follow_bindings()))
+    }
+
+    fn evaluate_member_access(&mut self, access: HirMemberAccess, expr: ExprId) -> IResult<Value> {
+        let field_index = self.interner.get_field_index(expr);
+        match self.evaluate(access.lhs)? {
+            Value::Tuple(mut fields) | Value::Struct(mut fields, _) => {
+                Ok(fields.swap_remove(field_index))
+            }
+            other => unreachable!("Cannot access field {} of {other:?}", access.rhs),
+        }
+    }
+
+    fn evaluate_call(&mut self, call: HirCallExpression, expr: ExprId) -> IResult<Value> {
+        let function = self.evaluate(call.func)?;
+        let arguments = try_vecmap(call.arguments, |argument| self.evaluate(argument))?;
+        let return_type = self.interner.id_type(expr).follow_bindings();
+        self.call_function(function, arguments, return_type, call.location)
+    }
+
+    fn call_function(
+        &mut self,
+        function: Value,
+        arguments: Vec<Value>,
+        return_type: Type,
+        location: Location,
+    ) -> IResult<Value> {
+        if self.frames.len() > MAX_CALL_DEPTH {
+            let reason = format!("calls were nested more than {MAX_CALL_DEPTH} deep");
+            return Err(ComptimeError::EvaluationFailed { reason, location });
+        }
+        self.interner.cancellation_token().check();
+
+        match function {
+            Value::Function(func_id, bindings) => {
+                let meta = self.interner.function_meta(&func_id);
+                match meta.kind {
+                    FunctionKind::Normal => (),
+                    FunctionKind::Builtin => {
+                        let call = BuiltinCall { func_id, bindings, return_type, location };
+                        return self.call_builtin(call, arguments);
+                    }
+                    FunctionKind::LowLevel => {
+                        let expression = "A call to a foreign function";
+                        return Err(ComptimeError::Unsupported { expression, location });
+                    }
+                    FunctionKind::Oracle => {
+                        let expression = "A call to an oracle";
+                        return Err(ComptimeError::Unsupported { expression, location });
+                    }
+                }
+
+                // The bindings of the caller are restored afterward so that recursive calls
+                // to generic functions work.
+                let previous_bindings = vecmap(bindings.values(), |(variable, _)| {
+                    (variable.clone(), variable.borrow().clone())
+                });
+                for (variable, binding) in bindings.values() {
+                    *variable.borrow_mut() = TypeBinding::Bound(binding.clone());
+                }
+
+                let parameters = vecmap(meta.parameters.0, |(pattern, _, _)| pattern);
+                let body = *self.interner.function(&func_id).as_expr();
+                let result = self.call_body(&parameters, arguments, Vec::new(), body);
+
+                for (variable, binding) in previous_bindings {
+                    *variable.borrow_mut() = binding;
+                }
+                result
+            }
+            Value::Closure(lambda, captures) => {
+                let parameters = vecmap(lambda.parameters, |(pattern, _)| pattern);
+                self.call_body(&parameters, arguments, captures, lambda.body)
+            }
+            other => unreachable!("Cannot call {other:?}"),
+        }
+    }
+
+    fn call_body(
+        &mut self,
+        parameters: &[HirPattern],
+        arguments: Vec<Value>,
+        captures: Vec<(DefinitionId, Value)>,
+        body: ExprId,
+    ) -> IResult<Value> {
+        self.frames.push(captures.into_iter().collect());
+        for (parameter, argument) in parameters.iter().zip(arguments) {
+            self.bind_pattern(parameter, argument);
+        }
+        let result = self.evaluate(body);
+        self.frames.pop();
+        result
+    }
+
+    fn call_builtin(&mut self, call: BuiltinCall, mut arguments: Vec<Value>) -> IResult<Value> {
+        let BuiltinCall { func_id, bindings, return_type, location } = call;
+        let attribute = self.interner.function_attributes(&func_id).function.clone();
+        let name = attribute.and_then(|attribute| attribute.builtin()).unwrap_or_default();
+
+        let mut argument = |index: usize| std::mem::replace(&mut arguments[index], Value::Unit);
+        match name.as_str() {
+            "array_len" => match argument(0) {
+                Value::Array(elements, _) => cast(
+                    &Value::Field(FieldElement::from(elements.len() as u128)),
+                    &return_type,
+                    location,
+                ),
+                other => unreachable!("array_len called on {other:?}"),
+            },
+            // Reading past the end of an array is still reported when evaluated at compile-time
+            "array_get_unchecked" => match (argument(0), argument(1)) {
+                (Value::Array(mut elements, _), index) => {
+                    let index = to_index(&index, location)?;
+                    if index < elements.len() {
+                        Ok(elements.swap_remove(index))
+                    } else {
+                        Err(index_out_of_bounds(index, elements.len(), location))
+                    }
+                }
+                (other, _) => unreachable!("array_get_unchecked called on {other:?}"),
+            },
+            "assert_constant" => Ok(Value::Unit),
+            "static_assert" => match (argument(0), argument(1)) {
+                (Value::Bool(true), _) => Ok(Value::Unit),
+                (_, Value::String(message)) => {
+                    Err(ComptimeError::AssertionFailed { message: Some(message), location })
+                }
+                (_, _) => Err(ComptimeError::AssertionFailed { message: None, location }),
+            },
+            "selector_of" => match argument(0) {
+                Value::Function(func_id, _) => {
+                    let name = self.interner.function_name(&func_id);
+                    let selector = self.interner.function_meta(&func_id).selector(name);
+                    Ok(Value::Integer(selector as u128, Signedness::Unsigned, 32))
+                }
+                _ => {
+                    let expression = "The selector of a closure";
+                    Err(ComptimeError::Unsupported { expression, location })
+                }
+            },
+            "fields_of" => {
+                let meta = self.interner.function_meta(&func_id);
+                let typ = match &meta.typ {
+                    Type::Forall(generics, _) => &bindings[&generics[0].0].1,
+                    _ => unreachable!("fields_of is generic"),
+                };
+                fields_of(typ, return_type, location)
+            }
+            "as_field" => cast(&argument(0), &Type::FieldElement, location),
+            "from_field" => cast(&argument(0), &return_type, location),
+            "modulus_num_bits" => {
+                let bits = FieldElement::from(FieldElement::max_num_bits() as u128);
+                cast(&Value::Field(bits), &return_type, location)
+            }
+            "str_as_bytes" => match argument(0) {
+                Value::String(string) => {
+                    let bytes = vecmap(string.bytes(), |byte| {
+                        Value::Integer(byte as u128, Signedness::Unsigned, 8)
+                    });
+                    Ok(Value::Array(bytes, return_type))
+                }
+                other => unreachable!("str_as_bytes called on {other:?}"),
+            },
+            "str_from_bytes" => {
+                let bytes = match argument(0) {
+                    Value::Array(bytes, _) => vecmap(bytes, |byte| match byte {
+                        Value::Integer(byte, ..) => byte as u8,
+                        other => unreachable!("str_from_bytes called with {other:?}"),
+                    }),
+                    other => unreachable!("str_from_bytes called on {other:?}"),
+                };
+                // Strings are stored as Rust strings, so they must be valid UTF-8 here
+                match String::from_utf8(bytes) {
+                    Ok(string) => Ok(Value::String(string)),
+                    Err(_) => {
+                        let expression = "A string which isn't valid UTF-8";
+                        Err(ComptimeError::Unsupported { expression, location })
+                    }
+                }
+            }
+            "slice_push_back" | "slice_push_front" | "slice_pop_back" | "slice_pop_front"
+            | "slice_insert" | "slice_remove" => {
+                let (mut elements, typ) = match argument(0) {
+                    Value::Array(elements, typ) => (elements, typ),
+                    other => unreachable!("{name} called on {other:?}"),
+                };
+                let slice_index = |index: Value, length: usize| {
+                    let index = to_index(&index, location)?;
+                    if index < length {
+                        Ok(index)
+                    } else {
+                        Err(index_out_of_bounds(index, length, location))
+                    }
+                };
+                let empty_slice = || {
+                    let reason = "cannot remove an element from an empty slice".to_string();
+                    ComptimeError::EvaluationFailed { reason, location }
+                };
+
+                match name.as_str() {
+                    "slice_push_back" => {
+                        elements.push(argument(1));
+                        Ok(Value::Array(elements, typ))
+                    }
+                    "slice_push_front" => {
+                        elements.insert(0, argument(1));
+                        Ok(Value::Array(elements, typ))
+                    }
+                    "slice_pop_back" => {
+                        let element = elements.pop().ok_or_else(empty_slice)?;
+                        Ok(Value::Tuple(vec![Value::Array(elements, typ), element]))
+                    }
+                    "slice_pop_front" => {
+                        if elements.is_empty() {
+                            return Err(empty_slice());
+                        }
+                        let element = elements.remove(0);
+                        Ok(Value::Tuple(vec![element, Value::Array(elements, typ)]))
+                    }
+                    "slice_insert" => {
+                        let index = slice_index(argument(1), elements.len() + 1)?;
+                        elements.insert(index, argument(2));
+                        Ok(Value::Array(elements, typ))
+                    }
+                    _ => {
+                        let index = slice_index(argument(1), elements.len())?;
+                        let element = elements.remove(index);
+                        Ok(Value::Tuple(vec![Value::Array(elements, typ), element]))
+                    }
+                }
+            }
+            _ => {
+                let expression = "A call to this builtin function";
+                Err(ComptimeError::Unsupported { expression, location })
+            }
+        }
+    }
+
+    fn evaluate_cast(&mut self, cast_expr: HirCastExpression, expr: ExprId) -> IResult<Value> {
+        let location = self.location(expr);
+        let value = self.evaluate(cast_expr.lhs)?;
+        cast(&value, &cast_expr.r#type.follow_bindings(), location)
+    }
+
+    fn evaluate_if(&mut self, if_expr: HirIfExpression) -> IResult<Value> {
+        match self.evaluate(if_expr.condition)? {
+            Value::Bool(true) => self.evaluate(if_expr.consequence),
+            Value::Bool(false) => match if_expr.alternative {
+                Some(alternative) => self.evaluate(alternative),
+                None => Ok(Value::Unit),
+            },
+            other => unreachable!("If condition evaluated to {other:?}"),
+        }
+    }
+
+    fn evaluate_lambda(&mut self, lambda: HirLambda) -> IResult<Value> {
+        let captures = try_vecmap(&lambda.captures, |capture| {
+            let ident = &capture.ident;
+            match self.frame().get(&ident.id) {
+                Some(value) => Ok((ident.id, value.clone())),
+                None => Err(self.non_comptime_value(ident)),
+            }
+        })?;
+        Ok(Value::Closure(lambda, captures))
+    }
+
+    /// Looks up the method of the impl of a trait for `self_type`.
+    fn trait_method(
+        &self,
+        self_type: &Type,
+        method: TraitMethodId,
+        expr: ExprId,
+        location: Location,
+    ) -> IResult<Value> {
+        let self_type = self_type.follow_bindings();
+        if let Type::TraitObject(_) = self_type {
+            let expression = "A call through a trait object";
+            return Err(ComptimeError::Unsupported { expression, location });
+        }
+
+        let function_type = self.interner.id_type(expr);
+        if let Some(conversion) = self.interner.lookup_from_method(method, &function_type) {
+            return Ok(Value::Function(conversion.function, follow_bindings(&conversion.bindings)));
+        }
+
+        let (trait_impl, impl_bindings) =
+            match self.interner.lookup_trait_implementation(&self_type, method.trait_id, &[]) {
+                Some(found) => found,
+                None => {
+                    let reason =
+                        format!("no implementation of the trait was found for `{self_type}`");
+                    return Err(ComptimeError::EvaluationFailed { reason, location });
+                }
+            };
+        let func_id = trait_impl.borrow().methods[method.method_index];
+
+        let mut bindings = follow_bindings(self.interner.get_instantiation_bindings(expr));
+        bindings.extend(follow_bindings(&impl_bindings));
+        Ok(Value::Function(func_id, bindings))
+    }
+
+    fn bind_pattern(&mut self, pattern: &HirPattern, value: Value) {
+        match (pattern, value) {
+            (HirPattern::Identifier(ident), value) => {
+                self.frame_mut().insert(ident.id, value);
+            }
+            (HirPattern::Mutable(pattern, _), value) => self.bind_pattern(pattern, value),
+            (HirPattern::Tuple(patterns, _), Value::Tuple(fields)) => {
+                for (pattern, field) in patterns.iter().zip(fields) {
+                    self.bind_pattern(pattern, field);
+                }
+            }
+            (HirPattern::Struct(_, patterns, _), Value::Struct(mut fields, typ)) => {
+                let (struct_type, generics) = match typ {
+                    Type::Struct(struct_type, generics) => (struct_type, generics),
+                    other => unreachable!("Struct value has non-struct type {other}"),
+                };
+                for (name, pattern) in patterns {
+                    let (_, index) = struct_type
+                        .borrow()
+                        .get_field(&name.0.contents, &generics)
+                        .expect("Struct patterns only contain fields of the struct");
+                    let field = std::mem::replace(&mut fields[index], Value::Unit);
+                    self.bind_pattern(pattern, field);
+                }
+            }
+            (pattern, value) => unreachable!("Cannot bind {value:?} to {pattern:?}"),
+        }
+    }
+
+    fn frame(&self) -> &HashMap<DefinitionId, Value> {
+        self.frames.last().expect("Code is always evaluated within a frame")
+    }
+
+    fn frame_mut(&mut self) -> &mut HashMap<DefinitionId, Value> {
+        self.frames.last_mut().expect("Code is always evaluated within a frame")
+    }
+
+    fn location(&self, expr: ExprId) -> Location {
+        self.interner.expr_location(&expr)
+    }
+
+    fn non_comptime_value(&self, ident: &HirIdent) -> ComptimeError {
+        let name = self.interner.definition_name(ident.id).to_string();
+        ComptimeError::NonComptimeValue { name, location: ident.location }
+    }
+}
+
+fn evaluate_integer_infix(
+    lhs: u128,
+    operator: BinaryOpKind,
+    rhs: u128,
+    signedness: Signedness,
+    bit_size: u32,
+    location: Location,
+) -> IResult<Value> {
+    use BinaryOpKind::*;
+    let lhs_value = Value::integer_to_i128(lhs, signedness, bit_size);
+    let rhs_value = Value::integer_to_i128(rhs, signedness, bit_size);
+
+    let checked = |result: Option<i128>, verb| {
+        result
+            .and_then(|result| Value::from_i128(result, signedness, bit_size))
+            .ok_or_else(|| overflow(verb, location))
+    };
+
+    match operator {
+        Add => checked(lhs_value.checked_add(rhs_value), "add"),
+        Subtract => checked(lhs_value.checked_sub(rhs_value), "subtract"),
+        Multiply => checked(lhs_value.checked_mul(rhs_value), "multiply"),
+        Divide | Modulo if rhs_value == 0 => Err(division_by_zero(location)),
+        Divide => checked(lhs_value.checked_div(rhs_value), "divide"),
+        Modulo => checked(lhs_value.checked_rem(rhs_value), "calculate the remainder"),
+        Equal => Ok(Value::Bool(lhs_value == rhs_value)),
+        NotEqual => Ok(Value::Bool(lhs_value != rhs_value)),
+        Less => Ok(Value::Bool(lhs_value < rhs_value)),
+        LessEqual => Ok(Value::Bool(lhs_value <= rhs_value)),
+        Greater => Ok(Value::Bool(lhs_value > rhs_value)),
+        GreaterEqual => Ok(Value::Bool(lhs_value >= rhs_value)),
+        And => Ok(Value::wrapping_integer(lhs & rhs, signedness, bit_size)),
+        Or => Ok(Value::wrapping_integer(lhs | rhs, signedness, bit_size)),
+        Xor => Ok(Value::wrapping_integer(lhs ^ rhs, signedness, bit_size)),
+        ShiftLeft | ShiftRight => {
+            // Shifting by the bit size or more shifts out every bit
+            let shift = u32::try_from(rhs_value).unwrap_or(u32::MAX);
+            let result = match operator {
+                ShiftLeft if shift >= bit_size => 0,
+                ShiftLeft => lhs << shift,
+                _ => (lhs_value >> shift.min(127)) as u128,
+            };
+            Ok(Value::wrapping_integer(result, signedness, bit_size))
+        }
+    }
+}
+
+/// Returns the result of an overloaded operator given the result of the trait method called.
+/// `!=` negates the result of `Eq::eq`, while `<`, `<=`, `>` and `>=` check the `Ordering`
+/// returned by `Ord::cmp`.
+fn operator_overload_result(operator: BinaryOpKind, result: Value) -> Value {
+    use BinaryOpKind::*;
+    match (operator, result) {
+        (NotEqual, Value::Bool(equal)) => Value::Bool(!equal),
+        (Less | LessEqual | Greater | GreaterEqual, Value::Struct(fields, _)) => {
+            // `Ordering::less()`, `Ordering::equal()` and `Ordering::greater()`
+            // hold 0, 1 and 2 respectively in their only field.
+            let ordering = fields[0].to_u128().expect("Ordering holds an integer");
+            Value::Bool(match operator {
+                Less => ordering == 0,
+                LessEqual => ordering != 2,
+                Greater => ordering == 2,
+                _ => ordering != 0,
+            })
+        }
+        (_, result) => result,
+    }
+}
+
+/// Converts a primitive value to the given type, truncating it if it is converted to a smaller
+/// integer type.
+fn cast(value: &Value, typ: &Type, location: Location) -> IResult<Value> {
+    let field = match value.to_field() {
+        Some(field) => field,
+        None => unreachable!("Cannot cast {value:?}"),
+    };
+    match typ {
+        Type::FieldElement => Ok(Value::Field(field)),
+        Type::Integer(signedness, bit_size) => {
+            check_integer_type(typ, location)?;
+            let bytes = field.to_be_bytes();
+            let mut low_bytes = [0; 16];
+            low_bytes.copy_from_slice(&bytes[bytes.len() - 16..]);
+            let bits = u128::from_be_bytes(low_bytes);
+            Ok(Value::wrapping_integer(bits, *signedness, *bit_size))
+        }
+        Type::Bool => Ok(Value::Bool(!field.is_zero())),
+        _ => Err(ComptimeError::Unsupported { expression: "This cast", location }),
+    }
+}
+
+/// Implements `std::meta::fields_of`, describing each field of the struct type `typ` with a
+/// `StructField`, which is the element type of the slice `return_type`.
+fn fields_of(typ: &Type, return_type: Type, location: Location) -> IResult<Value> {
+    let (definition, generics) = match typ.follow_bindings() {
+        Type::Struct(definition, generics) => (definition, generics),
+        other => {
+            let reason = format!("`fields_of` requires a struct type, but was given `{other}`");
+            return Err(ComptimeError::EvaluationFailed { reason, location });
+        }
+    };
+    let struct_field = match return_type.follow_bindings() {
+        Type::Array(_, element) => element.follow_bindings(),
+        other => unreachable!("fields_of returns a slice, not {other}"),
+    };
+    let bytes_type = match &struct_field {
+        Type::Struct(definition, _) => definition.borrow().get_fields(&[])[0].1.clone(),
+        other => unreachable!("fields_of returns a slice of structs, not {other}"),
+    };
+    let bytes = |string: String| {
+        let bytes =
+            vecmap(string.bytes(), |byte| Value::Integer(byte as u128, Signedness::Unsigned, 8));
+        Value::Array(bytes, bytes_type.clone())
+    };
+
+    let fields = definition.borrow().get_fields(&generics);
+    let fields = try_vecmap(fields, |(name, typ)| {
+        let Some(size) = typ.field_count() else {
+            let reason = format!("the field `{name}` of type `{typ}` doesn't have a fixed size");
+            return Err(ComptimeError::EvaluationFailed { reason, location });
+        };
+        let size = Value::Integer(size as u128, Signedness::Unsigned, 32);
+        Ok(Value::Struct(vec![bytes(name), bytes(typ.to_string()), size], struct_field.clone()))
+    })?;
+    Ok(Value::Array(fields, return_type))
+}
+
+/// Integers are evaluated using 128-bit arithmetic, so larger integer types can't be represented.
+fn check_integer_type(typ: &Type, location: Location) -> IResult<()> {
+    match typ.follow_bindings() {
+        Type::Integer(_, bit_size) if bit_size >= 128 => {
+            let expression = "An integer of 128 bits or more";
+            Err(ComptimeError::Unsupported { expression, location })
+        }
+        _ => Ok(()),
+    }
+}
+
+fn loop_bound(value: &Value) -> Option<i128> {
+    match value {
+        Value::Field(field) => i128::try_from(field.try_into_u128()?).ok(),
+        Value::Integer(bits, signedness, bit_size) => {
+            Some(Value::integer_to_i128(*bits, *signedness, *bit_size))
+        }
+        _ => None,
+    }
+}
+
+/// The value of the loop variable at `index`, which has the same type as the loop's start bound.
+fn loop_index(start: &Value, index: i128) -> Value {
+    match start {
+        Value::Integer(_, signedness, bit_size) => Value::from_i128(index, *signedness, *bit_size)
+            .expect("Loop indices are between the loop's bounds"),
+        _ => Value::Field(FieldElement::from(index)),
+    }
+}
+
+fn to_index(value: &Value, location: Location) -> IResult<usize> {
+    value.to_u128().and_then(|index| usize::try_from(index).ok()).ok_or_else(|| {
+        let reason = "the index is too large".to_string();
+        ComptimeError::EvaluationFailed { reason, location }
+    })
+}
+
+fn follow_bindings(bindings: &TypeBindings) -> TypeBindings {
+    bindings
+        .iter()
+        .map(|(id, (variable, binding))| (*id, (variable.clone(), binding.follow_bindings())))
+        .collect()
+}
+
+fn index_out_of_bounds(index: usize, length: usize, location: Location) -> ComptimeError {
+    let reason = format!("index {index} is out of bounds for a length of {length}");
+    ComptimeError::EvaluationFailed { reason, location }
+}
+
+fn overflow(verb: &str, location: Location) -> ComptimeError {
+    let reason = format!("attempt to {verb} with overflow");
+    ComptimeError::EvaluationFailed { reason, location }
+}
+
+fn division_by_zero(location: Location) -> ComptimeError {
+    let reason = "attempt to divide by zero".to_string();
+    ComptimeError::EvaluationFailed { reason, location }
+}
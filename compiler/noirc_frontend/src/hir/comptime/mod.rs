@@ -0,0 +1,46 @@
+//! Evaluates `comptime` code while the program is compiled.
+//!
+//! A `comptime { .. }` block, or a call to a `comptime fn` outside of other compile-time code,
+//! is evaluated by interpreting its HIR once the crate has been type checked. The value it
+//! produces is then converted back into an expression which replaces the original code, so
+//! later passes only see the result, e.g. a literal array instead of the loop computing it.
+//!
+//! Compile-time code can only use globals and the variables it defines itself, since nothing
+//! else is known until the program is executed.
+mod errors;
+mod interpreter;
+mod value;
+
+pub use errors::ComptimeError;
+
+use fm::FileId;
+
+use crate::hir::def_collector::dc_crate::CompilationError;
+use crate::node_interner::NodeInterner;
+
+use interpreter::Interpreter;
+
+/// Evaluates every `comptime` expression found while resolving the crate, replacing each
+/// with the value it evaluates to.
+pub(crate) fn evaluate_comptime_expressions(
+    interner: &mut NodeInterner,
+) -> Vec<(CompilationError, FileId)> {
+    let expressions = interner.take_unevaluated_comptime_expressions();
+
+    let mut interpreter = Interpreter::new(interner);
+    let results: Vec<_> =
+        expressions.into_iter().map(|expr| (expr, interpreter.evaluate_root(expr))).collect();
+
+    let mut errors = Vec::new();
+    for (expr, result) in results {
+        let location = interner.expr_location(&expr);
+        match result.and_then(|value| value.into_expression(interner, location)) {
+            Ok(expression) => interner.replace_expr(&expr, expression),
+            Err(error) => {
+                let file = error.location().file;
+                errors.push((error.into(), file));
+            }
+        }
+    }
+    errors
+}
@@ -0,0 +1,187 @@
+use acvm::FieldElement;
+use iter_extended::{try_vecmap, vecmap};
+use noirc_errors::Location;
+
+use crate::hir_def::expr::{
+    HirArrayLiteral, HirConstructorExpression, HirExpression, HirLambda, HirLiteral,
+};
+use crate::hir_def::types::TypeBindings;
+use crate::node_interner::{DefinitionId, ExprId, FuncId, NodeInterner};
+use crate::{Ident, Signedness, Type};
+
+use super::errors::ComptimeError;
+
+/// A value computed at compile time
+#[derive(Debug, Clone)]
+pub(super) enum Value {
+    Unit,
+    Bool(bool),
+    Field(FieldElement),
+    /// An integer of the given signedness and bit size, stored as its two's complement bit pattern
+    Integer(u128, Signedness, u32),
+    String(String),
+    /// An array or slice, along with its type
+    Array(Vec<Value>, Type),
+    Tuple(Vec<Value>),
+    /// The fields of a struct in the order they are declared, along with the struct's type
+    Struct(Vec<Value>, Type),
+    /// A function along with the bindings of its generics
+    Function(FuncId, TypeBindings),
+    /// A lambda along with the values of the variables it captures
+    Closure(HirLambda, Vec<(DefinitionId, Value)>),
+}
+
+impl Value {
+    /// Creates the value of an integer literal of the given type, or `None` if it doesn't fit
+    pub(super) fn integer(magnitude: FieldElement, negative: bool, typ: &Type) -> Option<Value> {
+        match typ.follow_bindings() {
+            Type::Integer(signedness, bit_size) => {
+                let magnitude = magnitude.try_into_u128()?;
+                let value = i128::try_from(magnitude).ok()?;
+                let value = if negative { -value } else { value };
+                Value::from_i128(value, signedness, bit_size)
+            }
+            // Integer literals default to fields when their type isn't otherwise constrained
+            _ => Some(Value::Field(if negative { -magnitude } else { magnitude })),
+        }
+    }
+
+    /// Creates an integer from its numeric value, or returns `None` if it is out of range
+    pub(super) fn from_i128(value: i128, signedness: Signedness, bit_size: u32) -> Option<Value> {
+        let in_range = match signedness {
+            Signedness::Unsigned => 0 <= value && (value as u128) < 1 << bit_size,
+            Signedness::Signed => {
+                let half = 1 << (bit_size - 1);
+                -half <= value && value < half
+            }
+        };
+        in_range.then(|| Value::Integer(value as u128 & mask(bit_size), signedness, bit_size))
+    }
+
+    /// Creates an integer from the low bits of `bits`, wrapping around if it doesn't fit
+    pub(super) fn wrapping_integer(bits: u128, signedness: Signedness, bit_size: u32) -> Value {
+        Value::Integer(bits & mask(bit_size), signedness, bit_size)
+    }
+
+    /// The numeric value of an integer, interpreting its bit pattern according to its sign
+    pub(super) fn integer_to_i128(bits: u128, signedness: Signedness, bit_size: u32) -> i128 {
+        let is_negative = signedness == Signedness::Signed && bits >> (bit_size - 1) == 1;
+        if is_negative {
+            // Sign extend the bit pattern to 128 bits
+            (bits | !mask(bit_size)) as i128
+        } else {
+            bits as i128
+        }
+    }
+
+    /// Converts an integer or field used as an index or loop bound into a `u128`
+    pub(super) fn to_u128(&self) -> Option<u128> {
+        match self {
+            Value::Field(field) => field.try_into_u128(),
+            Value::Integer(bits, signedness, bit_size) => {
+                u128::try_from(Value::integer_to_i128(*bits, *signedness, *bit_size)).ok()
+            }
+            _ => None,
+        }
+    }
+
+    pub(super) fn to_field(&self) -> Option<FieldElement> {
+        match self {
+            Value::Field(field) => Some(*field),
+            Value::Integer(bits, _, _) => Some(FieldElement::from(*bits)),
+            Value::Bool(boolean) => Some(FieldElement::from(*boolean)),
+            _ => None,
+        }
+    }
+
+    /// Compares two values which have no `Eq` implementation of their own
+    pub(super) fn structurally_equal(&self, other: &Value) -> bool {
+        match (self, other) {
+            (Value::Unit, Value::Unit) => true,
+            (Value::Bool(lhs), Value::Bool(rhs)) => lhs == rhs,
+            (Value::Field(lhs), Value::Field(rhs)) => lhs == rhs,
+            (Value::Integer(lhs, ..), Value::Integer(rhs, ..)) => lhs == rhs,
+            (Value::String(lhs), Value::String(rhs)) => lhs == rhs,
+            (Value::Array(lhs, _), Value::Array(rhs, _))
+            | (Value::Tuple(lhs), Value::Tuple(rhs))
+            | (Value::Struct(lhs, _), Value::Struct(rhs, _)) => {
+                lhs.len() == rhs.len()
+                    && lhs.iter().zip(rhs).all(|(lhs, rhs)| lhs.structurally_equal(rhs))
+            }
+            _ => false,
+        }
+    }
+
+    fn typ(&self) -> Type {
+        match self {
+            Value::Unit => Type::Unit,
+            Value::Bool(_) => Type::Bool,
+            Value::Field(_) => Type::FieldElement,
+            Value::Integer(_, signedness, bit_size) => Type::Integer(*signedness, *bit_size),
+            Value::String(string) => Type::String(Box::new(Type::Constant(string.len() as u64))),
+            Value::Array(_, typ) | Value::Struct(_, typ) => typ.clone(),
+            Value::Tuple(fields) => Type::Tuple(vecmap(fields, Value::typ)),
+            Value::Function(..) | Value::Closure(..) => {
+                unreachable!("Functions cannot be converted into expressions")
+            }
+        }
+    }
+
+    /// Converts this value back into an expression which can replace the code that computed it.
+    pub(super) fn into_expression(
+        self,
+        interner: &mut NodeInterner,
+        location: Location,
+    ) -> Result<HirExpression, ComptimeError> {
+        let mut push_value = |value: Value| {
+            let typ = value.typ();
+            let expression = value.into_expression(interner, location)?;
+            let expr_id = interner.push_expr(expression);
+            interner.push_expr_location(expr_id, location.span, location.file);
+            interner.push_expr_type(&expr_id, typ);
+            Ok::<ExprId, ComptimeError>(expr_id)
+        };
+
+        let expression = match self {
+            Value::Unit => HirExpression::Literal(HirLiteral::Unit),
+            Value::Bool(boolean) => HirExpression::Literal(HirLiteral::Bool(boolean)),
+            Value::Field(field) => HirExpression::Literal(HirLiteral::Integer(field, false)),
+            Value::Integer(bits, signedness, bit_size) => {
+                let value = Value::integer_to_i128(bits, signedness, bit_size);
+                let magnitude = FieldElement::from(value.unsigned_abs());
+                HirExpression::Literal(HirLiteral::Integer(magnitude, value < 0))
+            }
+            Value::String(string) => HirExpression::Literal(HirLiteral::Str(string)),
+            Value::Array(elements, _) => {
+                let elements = try_vecmap(elements, push_value)?;
+                HirExpression::Literal(HirLiteral::Array(HirArrayLiteral::Standard(elements)))
+            }
+            Value::Tuple(fields) => HirExpression::Tuple(try_vecmap(fields, push_value)?),
+            Value::Struct(fields, typ) => {
+                let (r#type, struct_generics) = match typ {
+                    Type::Struct(r#type, generics) => (r#type, generics),
+                    other => unreachable!("Struct value has non-struct type {other}"),
+                };
+                let names = vecmap(r#type.borrow().get_fields(&struct_generics), |(name, _)| name);
+                let fields = try_vecmap(names.into_iter().zip(fields), |(name, field)| {
+                    let name = Ident::new(name, location.span);
+                    Ok::<_, ComptimeError>((name, push_value(field)?))
+                })?;
+                HirExpression::Constructor(HirConstructorExpression {
+                    r#type,
+                    struct_generics,
+                    fields,
+                })
+            }
+            Value::Function(..) | Value::Closure(..) => {
+                let expression = "A function";
+                return Err(ComptimeError::Unsupported { expression, location });
+            }
+        };
+        Ok(expression)
+    }
+}
+
+fn mask(bit_size: u32) -> u128 {
+    (1 << bit_size) - 1
+}
@@ -1,9 +1,12 @@
 use super::dc_mod::collect_defs;
 use super::errors::{DefCollectorErrorKind, DuplicateType};
 use crate::graph::CrateId;
+use crate::hir::comptime::{self, ComptimeError};
 use crate::hir::def_map::{CrateDefMap, LocalModuleId, ModuleDefId, ModuleId};
 use crate::hir::resolution::errors::ResolverError;
-use crate::hir::resolution::import::PathResolutionError;
+use crate::hir::resolution::import::{
+    path_segment_scopes, resolve_path_to_ns, PathResolutionError,
+};
 use crate::hir::resolution::path_resolver::PathResolver;
 use crate::hir::resolution::resolver::Resolver;
 use crate::hir::resolution::{
@@ -12,21 +15,26 @@ use crate::hir::resolution::{
 };
 use crate::hir::type_check::{type_check_func, TypeCheckError, TypeChecker};
 use crate::hir::Context;
-use crate::hir_def::traits::{Trait, TraitConstant, TraitFunction, TraitImpl, TraitType};
+use crate::hir_def::traits::{
+    Trait, TraitConstant, TraitConstraint, TraitFunction, TraitImpl, TraitType,
+};
 use crate::node_interner::{
-    FuncId, NodeInterner, StmtId, StructId, TraitId, TraitImplKey, TypeAliasId,
+    ExprId, FuncId, NodeInterner, StmtId, StructId, TraitId, TraitImplKey, TypeAliasId,
 };
 
 use crate::parser::{ParserError, SortedModule};
+use crate::token::SecondaryAttribute;
 use crate::{
     ExpressionKind, Generics, Ident, LetStatement, Literal, NoirFunction, NoirStruct, NoirTrait,
-    NoirTypeAlias, Path, Shared, StructType, TraitItem, Type, TypeBinding, TypeVariableKind,
-    UnresolvedGenerics, UnresolvedType,
+    NoirTypeAlias, Path, PathKind, Shared, StaticAssert, StructType, TraitItem, Type, TypeBinding,
+    TypeBindings, TypeVariableKind, UnresolvedGenerics, UnresolvedTraitConstraint, UnresolvedType,
+    Visibility,
 };
-use fm::FileId;
+use fm::{FileId, FileManager};
+use indexmap::IndexMap;
 use iter_extended::vecmap;
 use noirc_errors::{CustomDiagnostic, Span};
-use std::collections::{BTreeMap, HashMap, HashSet};
+use std::collections::{BTreeMap, HashSet};
 use std::rc::Rc;
 use std::vec;
 
@@ -46,6 +54,7 @@ impl UnresolvedFunctions {
     pub fn resolve_trait_bounds_trait_ids(
         &mut self,
         def_maps: &BTreeMap<CrateId, CrateDefMap>,
+        interner: &mut NodeInterner,
         crate_id: CrateId,
     ) -> Vec<DefCollectorErrorKind> {
         let mut errors = Vec::new();
@@ -54,8 +63,8 @@ impl UnresolvedFunctions {
             let module = ModuleId { krate: crate_id, local_id: *local_id };
 
             for bound in &mut func.def.where_clause {
-                match resolve_trait_by_path(def_maps, module, bound.trait_bound.trait_path.clone())
-                {
+                let trait_path = bound.trait_bound.trait_path.clone();
+                match resolve_trait_by_path(def_maps, interner, module, trait_path) {
                     Ok(trait_id) => {
                         bound.trait_bound.trait_id = Some(trait_id);
                     }
@@ -92,6 +101,9 @@ pub struct UnresolvedTraitImpl {
     pub trait_path: Path,
     pub object_type: UnresolvedType,
     pub methods: UnresolvedFunctions,
+    pub generics: UnresolvedGenerics,
+    pub trait_generics: Vec<UnresolvedType>,
+    pub where_clause: Vec<UnresolvedTraitConstraint>,
 }
 
 #[derive(Clone)]
@@ -109,6 +121,13 @@ pub struct UnresolvedGlobal {
     pub stmt_def: LetStatement,
 }
 
+#[derive(Clone)]
+pub struct UnresolvedStaticAssert {
+    pub file_id: FileId,
+    pub module_id: LocalModuleId,
+    pub assertion: StaticAssert,
+}
+
 /// Given a Crate root, collect all definitions in that crate
 pub struct DefCollector {
     pub(crate) def_map: CrateDefMap,
@@ -118,6 +137,7 @@ pub struct DefCollector {
     pub(crate) collected_type_aliases: BTreeMap<TypeAliasId, UnresolvedTypeAlias>,
     pub(crate) collected_traits: BTreeMap<TraitId, UnresolvedTrait>,
     pub(crate) collected_globals: Vec<UnresolvedGlobal>,
+    pub(crate) collected_static_asserts: Vec<UnresolvedStaticAssert>,
     pub(crate) collected_impls: ImplMap,
     pub(crate) collected_traits_impls: Vec<UnresolvedTraitImpl>,
 }
@@ -128,6 +148,7 @@ pub enum CompilationError {
     DefinitionError(DefCollectorErrorKind),
     ResolveError(ResolverError),
     TypeError(TypeCheckError),
+    ComptimeError(ComptimeError),
 }
 
 impl From<CompilationError> for CustomDiagnostic {
@@ -137,6 +158,7 @@ impl From<CompilationError> for CustomDiagnostic {
             CompilationError::DefinitionError(error) => error.into(),
             CompilationError::ResolveError(error) => error.into(),
             CompilationError::TypeError(error) => error.into(),
+            CompilationError::ComptimeError(error) => error.into(),
         }
     }
 }
@@ -164,15 +186,21 @@ impl From<TypeCheckError> for CompilationError {
     }
 }
 
+impl From<ComptimeError> for CompilationError {
+    fn from(value: ComptimeError) -> Self {
+        CompilationError::ComptimeError(value)
+    }
+}
+
 /// Maps the type and the module id in which the impl is defined to the functions contained in that
 /// impl along with the generics declared on the impl itself. This also contains the Span
 /// of the object_type of the impl, used to issue an error if the object type fails to resolve.
 ///
-/// Note that because these are keyed by unresolved types, the impl map is one of the few instances
-/// of HashMap rather than BTreeMap. For this reason, we should be careful not to iterate over it
-/// since it would be non-deterministic.
+/// Note that because these are keyed by unresolved types, which are not ordered, the impl map is
+/// an IndexMap rather than a BTreeMap. Iterating over it visits the impls in the order they were
+/// collected, which keeps the ids assigned while resolving them deterministic.
 type ImplMap =
-    HashMap<(UnresolvedType, LocalModuleId), Vec<(UnresolvedGenerics, Span, UnresolvedFunctions)>>;
+    IndexMap<(UnresolvedType, LocalModuleId), Vec<(UnresolvedGenerics, Span, UnresolvedFunctions)>>;
 
 impl DefCollector {
     fn new(def_map: CrateDefMap) -> DefCollector {
@@ -183,8 +211,9 @@ impl DefCollector {
             collected_types: BTreeMap::new(),
             collected_type_aliases: BTreeMap::new(),
             collected_traits: BTreeMap::new(),
-            collected_impls: HashMap::new(),
+            collected_impls: IndexMap::new(),
             collected_globals: vec![],
+            collected_static_asserts: vec![],
             collected_traits_impls: vec![],
         }
     }
@@ -224,6 +253,10 @@ impl DefCollector {
         let crate_root = def_map.root;
         let mut def_collector = DefCollector::new(def_map);
 
+        // Attributes in the root file, such as `#![allow(dead_code)]`, apply to the whole crate
+        context.lint_levels.push_file(root_file_id, None, &ast.inner_attributes);
+        let crate_attributes = ast.inner_attributes.clone();
+
         // Collecting module declarations with ModCollector
         // and lowering the functions
         // i.e. Use a mod collector to collect the nodes at the root module
@@ -233,6 +266,7 @@ impl DefCollector {
             ast,
             root_file_id,
             crate_root,
+            Vec::new(),
             crate_id,
             context,
         ));
@@ -240,7 +274,22 @@ impl DefCollector {
         // Add the current crate to the collection of DefMaps
         context.def_maps.insert(crate_id, def_collector.def_map);
 
+        // The prelude is known before imports are resolved so that they can refer to its items
+        match resolve_prelude(&context.def_maps, crate_id, &crate_attributes) {
+            Ok(prelude) => context.def_maps.get_mut(&crate_id).unwrap().prelude = prelude,
+            Err(error) => errors.push((error.into(), root_file_id)),
+        }
+
         // Resolve unresolved imports collected from the crate
+        // Imports may refer to names brought into scope by other imports
+        for import in &def_collector.collected_imports {
+            let module_id = ModuleId { krate: crate_id, local_id: import.module_id };
+            for (module, segment) in path_segment_scopes(&context.def_maps, module_id, &import.path)
+            {
+                context.def_interner.mark_name_used(module, &segment.0.contents);
+            }
+        }
+
         let (resolved, unresolved_imports) =
             resolve_imports(crate_id, def_collector.collected_imports, &context.def_maps);
 
@@ -255,8 +304,14 @@ impl DefCollector {
 
         // Populate module namespaces according to the imports used
         let current_def_map = context.def_maps.get_mut(&crate_id).unwrap();
+        let mut imports = Vec::new();
         for resolved_import in resolved {
             let name = resolved_import.name;
+            imports.push((
+                resolved_import.module_scope,
+                name.clone(),
+                resolved_import.statement_span,
+            ));
             for ns in resolved_import.resolved_namespace.iter_defs() {
                 let result = current_def_map.modules[resolved_import.module_scope.0]
                     .import(name.clone(), ns);
@@ -272,6 +327,8 @@ impl DefCollector {
             }
         }
 
+        let resolve_span = tracing::info_span!("resolve").entered();
+
         // We must first resolve and intern the globals before we can resolve any stmts inside each function.
         // Each function uses its own resolver with a newly created ScopeForest, and must be resolved again to be within a function's scope
         //
@@ -310,20 +367,35 @@ impl DefCollector {
             &mut def_collector.collected_traits_impls,
         ));
 
-        // Lower each function in the crate. This is now possible since imports have been resolved
-        let file_func_ids = resolve_free_functions(
+        // Lower each function in the crate. This is now possible since imports have been resolved.
+        // `const fn`s are lowered first since they may be evaluated when resolving the array
+        // lengths in the signatures of the other functions.
+        let (const_functions, other_functions) =
+            filter_const_functions(def_collector.collected_functions);
+        let mut file_func_ids = resolve_free_functions(
             &mut context.def_interner,
             crate_id,
             &context.def_maps,
-            def_collector.collected_functions,
+            &context.file_manager,
+            const_functions,
             None,
             &mut errors,
         );
+        file_func_ids.extend(resolve_free_functions(
+            &mut context.def_interner,
+            crate_id,
+            &context.def_maps,
+            &context.file_manager,
+            other_functions,
+            None,
+            &mut errors,
+        ));
 
         let file_method_ids = resolve_impls(
             &mut context.def_interner,
             crate_id,
             &context.def_maps,
+            &context.file_manager,
             def_collector.collected_impls,
             &mut errors,
         );
@@ -335,18 +407,57 @@ impl DefCollector {
             &mut errors,
         );
 
+        // Static assertions may call any function, so are resolved once every function is known
+        let static_asserts =
+            resolve_static_asserts(context, def_collector.collected_static_asserts, crate_id);
+
         errors.extend(resolved_globals.errors);
+        errors.extend(static_asserts.errors);
+        drop(resolve_span);
+
+        // Dependencies are not linted since their authors are the ones who can fix them
+        if crate_id.is_root() {
+            errors.extend(check_for_unused_imports(context, crate_id, imports));
+        }
 
         // We run hir transformations before type checks
         #[cfg(feature = "aztec")]
         crate::hir::aztec_library::transform_hir(&crate_id, context);
 
+        let type_check_span = tracing::info_span!("type_check").entered();
         errors.extend(type_check_globals(&mut context.def_interner, resolved_globals.globals));
+        errors.extend(type_check_static_asserts(
+            &mut context.def_interner,
+            static_asserts.assertions,
+        ));
+
+        // Functions reached through method calls are only known once the functions are type checked
+        let mut functions = file_func_ids.clone();
+        functions.extend(file_method_ids.iter().copied());
+        let trait_impl_functions = vecmap(&file_trait_impls_ids, |(_, func_id)| *func_id);
 
         // Type check all of the functions in the crate
         errors.extend(type_check_functions(&mut context.def_interner, file_func_ids));
         errors.extend(type_check_functions(&mut context.def_interner, file_method_ids));
         errors.extend(type_check_functions(&mut context.def_interner, file_trait_impls_ids));
+        drop(type_check_span);
+
+        // Compile-time code is only evaluated once the whole crate is known to be well typed
+        let has_errors =
+            errors.iter().any(|(error, _)| CustomDiagnostic::from(error.clone()).is_error());
+        if !has_errors {
+            let _span = tracing::info_span!("comptime").entered();
+            errors.extend(comptime::evaluate_comptime_expressions(&mut context.def_interner));
+        }
+
+        if crate_id.is_root() {
+            errors.extend(check_for_unreachable_functions(
+                context,
+                crate_id,
+                functions,
+                trait_impl_functions,
+            ));
+        }
         errors
     }
 }
@@ -524,7 +635,7 @@ fn collect_trait_impl(
     let unresolved_type = trait_impl.object_type.clone();
     let module = ModuleId { local_id: trait_impl.module_id, krate: crate_id };
     trait_impl.trait_id =
-        match resolve_trait_by_path(def_maps, module, trait_impl.trait_path.clone()) {
+        match resolve_trait_by_path(def_maps, interner, module, trait_impl.trait_path.clone()) {
             Ok(trait_id) => Some(trait_id),
             Err(error) => {
                 errors.push((error.into(), trait_impl.file_id));
@@ -533,6 +644,14 @@ fn collect_trait_impl(
         };
 
     if let Some(trait_id) = trait_impl.trait_id {
+        let attributes = interner.trait_attributes(&trait_id);
+        if let Some(note) = SecondaryAttribute::get_deprecated_note(attributes) {
+            let name = interner.get_trait(trait_id).name.to_string();
+            let span = trait_impl.trait_path.span();
+            let error = ResolverError::UseOfDeprecated { kind: "trait", name, note, span };
+            errors.push((error.into(), trait_impl.file_id));
+        }
+
         errors
             .extend(collect_trait_impl_methods(interner, def_maps, crate_id, trait_id, trait_impl));
         for (_, func_id, ast) in &trait_impl.methods.functions {
@@ -540,6 +659,7 @@ fn collect_trait_impl(
 
             let path_resolver = StandardPathResolver::new(module);
             let mut resolver = Resolver::new(interner, &path_resolver, def_maps, file);
+            resolver.add_generics(&trait_impl.generics);
             resolver.add_generics(&ast.def.generics);
             let typ = resolver.resolve_type(unresolved_type.clone());
 
@@ -588,6 +708,7 @@ fn check_trait_impl_crate_coherence(
     let file = def_maps[&current_crate].file_id(trait_impl.module_id);
     let path_resolver = StandardPathResolver::new(module);
     let mut resolver = Resolver::new(interner, &path_resolver, def_maps, file);
+    resolver.add_generics(&trait_impl.generics);
 
     let object_crate = match resolver.resolve_type(trait_impl.object_type.clone()) {
         Type::Struct(struct_type, _) => struct_type.borrow().id.krate(),
@@ -604,11 +725,17 @@ fn check_trait_impl_crate_coherence(
     errors
 }
 
+/// Resolves the trait `path` refers to from `module`, recording the names it uses so that
+/// imports used only by trait paths aren't reported as unused
 fn resolve_trait_by_path(
     def_maps: &BTreeMap<CrateId, CrateDefMap>,
+    interner: &mut NodeInterner,
     module: ModuleId,
     path: Path,
 ) -> Result<TraitId, DefCollectorErrorKind> {
+    for (module, segment) in path_segment_scopes(def_maps, module, &path) {
+        interner.mark_name_used(module, &segment.0.contents);
+    }
     let path_resolver = StandardPathResolver::new(module);
 
     match path_resolver.resolve(def_maps, path.clone()) {
@@ -637,6 +764,130 @@ fn filter_literal_globals(
     })
 }
 
+/// Finds the prelude of a crate given the inner attributes of its root: the module named by
+/// `#![prelude(path)]`, none with `#![no_std_prelude]` and otherwise the `prelude` module of
+/// the standard library if the crate depends on it.
+fn resolve_prelude(
+    def_maps: &BTreeMap<CrateId, CrateDefMap>,
+    crate_id: CrateId,
+    attributes: &[SecondaryAttribute],
+) -> Result<Option<ModuleId>, DefCollectorErrorKind> {
+    let def_map = &def_maps[&crate_id];
+
+    let custom_prelude = attributes.iter().find_map(|attribute| match attribute {
+        SecondaryAttribute::Prelude(path, span) => Some((path, *span)),
+        _ => None,
+    });
+    if let Some((path_string, span)) = custom_prelude {
+        let segments = vecmap(path_string.split("::"), |name| Ident::new(name.to_string(), span));
+        let (kind, segments) = match segments[0].0.contents.as_str() {
+            "crate" => (PathKind::Crate, segments[1..].to_vec()),
+            "dep" if segments.len() > 1 => (PathKind::Dep, segments[1..].to_vec()),
+            _ => (PathKind::Plain, segments),
+        };
+        let path = Path { segments, kind };
+        let directive =
+            ImportDirective { module_id: def_map.root, path, alias: None, statement_span: None };
+
+        let namespace = resolve_path_to_ns(&directive, def_map, def_maps, true)
+            .map_err(DefCollectorErrorKind::PathResolutionError)?;
+        return match namespace.take_types() {
+            Some(ModuleDefId::ModuleId(module)) => Ok(Some(module)),
+            _ => Err(DefCollectorErrorKind::PreludeNotAModule { path: path_string.clone(), span }),
+        };
+    }
+
+    if attributes.contains(&SecondaryAttribute::NoStdPrelude) {
+        return Ok(None);
+    }
+    let Some(std_root) = def_map.extern_prelude.get("std") else {
+        return Ok(None);
+    };
+    let prelude = std_root.module(def_maps).find_name(&Ident::from("prelude")).take_types();
+    Ok(match prelude {
+        Some(ModuleDefId::ModuleId(module)) => Some(module),
+        _ => None,
+    })
+}
+
+/// Issues a warning for each import whose name is never referred to by a path in its module
+fn check_for_unused_imports(
+    context: &Context,
+    crate_id: CrateId,
+    imports: Vec<(LocalModuleId, Ident, Option<Span>)>,
+) -> Vec<(CompilationError, FileId)> {
+    let def_map = &context.def_maps[&crate_id];
+    let unused_imports = imports.into_iter().filter(|(local_id, name, _)| {
+        let module = ModuleId { krate: crate_id, local_id: *local_id };
+        !context.def_interner.is_name_used(module, &name.0.contents)
+    });
+
+    vecmap(unused_imports, |(local_id, ident, statement_span)| {
+        let error = DefCollectorErrorKind::UnusedImport { ident, statement_span };
+        (error.into(), def_map.file_id(local_id))
+    })
+}
+
+/// Issues a warning for each private function which can't be reached from the crate's entry
+/// points: `main`, tests, contract functions, public functions, trait impls and globals.
+fn check_for_unreachable_functions(
+    context: &Context,
+    crate_id: CrateId,
+    functions: Vec<(FileId, FuncId)>,
+    trait_impl_functions: Vec<FuncId>,
+) -> Vec<(CompilationError, FileId)> {
+    let interner = &context.def_interner;
+    let main_function = context.def_maps[&crate_id].main_function();
+
+    let is_entry_point = |func_id: &FuncId| {
+        let modifiers = interner.function_modifiers(func_id);
+        let in_contract = interner.function_module(*func_id).module(&context.def_maps).is_contract;
+
+        Some(*func_id) == main_function
+            || modifiers.visibility == Visibility::Public
+            || modifiers.attributes.is_test_function()
+            || (in_contract && modifiers.attributes.is_contract_entry_point())
+    };
+
+    let mut reachable = HashSet::new();
+    let mut queue: Vec<FuncId> =
+        functions.iter().map(|(_, func_id)| *func_id).filter(is_entry_point).collect();
+    queue.extend(trait_impl_functions);
+    queue.extend(interner.function_references(None));
+
+    while let Some(func_id) = queue.pop() {
+        if reachable.insert(func_id) {
+            queue.extend(interner.function_references(Some(func_id)));
+        }
+    }
+
+    let unreachable = functions.into_iter().filter(|(_, func_id)| !reachable.contains(func_id));
+    vecmap(unreachable, |(file_id, func_id)| {
+        let error =
+            DefCollectorErrorKind::UnreachableFunction { ident: interner.function_ident(&func_id) };
+        (error.into(), file_id)
+    })
+}
+
+/// Separates the `const fn`s out of each set of collected functions
+fn filter_const_functions(
+    collected_functions: Vec<UnresolvedFunctions>,
+) -> (Vec<UnresolvedFunctions>, Vec<UnresolvedFunctions>) {
+    collected_functions
+        .into_iter()
+        .map(|unresolved| {
+            let (const_functions, other_functions) =
+                unresolved.functions.into_iter().partition(|(_, _, func)| func.def.is_const);
+            let file_id = unresolved.file_id;
+            let trait_id = unresolved.trait_id;
+            (
+                UnresolvedFunctions { file_id, functions: const_functions, trait_id },
+                UnresolvedFunctions { file_id, functions: other_functions, trait_id },
+            )
+        })
+        .unzip()
+}
+
 pub struct ResolvedGlobals {
     pub globals: Vec<(FileId, StmtId)>,
     pub errors: Vec<(CompilationError, FileId)>,
@@ -666,6 +917,7 @@ fn resolve_globals(
             &context.def_maps,
             global.file_id,
         );
+        resolver.set_file_manager(&context.file_manager);
 
         let name = global.stmt_def.pattern.name_ident().clone();
 
@@ -697,6 +949,53 @@ fn type_check_globals(
         .collect()
 }
 
+/// The compile-time assertions `static_assert` items were resolved to, which are evaluated
+/// along with the rest of the crate's compile-time code.
+struct ResolvedStaticAsserts {
+    assertions: Vec<(FileId, ExprId)>,
+    errors: Vec<(CompilationError, FileId)>,
+}
+
+fn resolve_static_asserts(
+    context: &mut Context,
+    static_asserts: Vec<UnresolvedStaticAssert>,
+    crate_id: CrateId,
+) -> ResolvedStaticAsserts {
+    let mut errors = vec![];
+    let assertions = vecmap(static_asserts, |static_assert| {
+        let module_id = ModuleId { local_id: static_assert.module_id, krate: crate_id };
+        let path_resolver = StandardPathResolver::new(module_id);
+
+        let mut resolver = Resolver::new(
+            &mut context.def_interner,
+            &path_resolver,
+            &context.def_maps,
+            static_assert.file_id,
+        );
+        resolver.set_file_manager(&context.file_manager);
+        let expr_id = resolver.resolve_static_assert(static_assert.assertion);
+        errors.extend(take_errors(static_assert.file_id, resolver));
+
+        (static_assert.file_id, expr_id)
+    });
+    ResolvedStaticAsserts { assertions, errors }
+}
+
+fn type_check_static_asserts(
+    interner: &mut NodeInterner,
+    assertions: Vec<(FileId, ExprId)>,
+) -> Vec<(CompilationError, fm::FileId)> {
+    assertions
+        .iter()
+        .flat_map(|(file_id, expr_id)| {
+            TypeChecker::check_static_assert(expr_id, interner)
+                .into_iter()
+                .map(|e| (e.into(), *file_id))
+                .collect::<Vec<_>>()
+        })
+        .collect()
+}
+
 fn type_check_functions(
     interner: &mut NodeInterner,
     file_func_ids: Vec<(FileId, FuncId)>,
@@ -725,12 +1024,21 @@ fn resolve_structs(
     // Each struct should already be present in the NodeInterner after def collection.
     for (type_id, typ) in structs {
         let file_id = typ.file_id;
+        let local_id = typ.module_id;
+        let layout = typ.struct_def.layout();
         let (generics, fields, resolver_errors) = resolve_struct_fields(context, crate_id, typ);
         errors.extend(vecmap(resolver_errors, |err| (err.into(), file_id)));
         context.def_interner.update_struct(type_id, |struct_def| {
-            struct_def.set_fields(fields);
+            struct_def.set_fields(fields, layout);
             struct_def.generics = generics;
         });
+
+        if crate_id.is_stdlib() {
+            let def_map = &context.def_maps[&crate_id];
+            let parent = def_map.modules()[local_id.0].parent;
+            let module_path = def_map.get_module_path_with_separator(local_id.0, parent, "::");
+            context.def_interner.try_add_unchecked_struct(type_id, &module_path);
+        }
     }
     errors
 }
@@ -767,8 +1075,22 @@ fn resolve_trait_methods(
     });
     let file = def_maps[&crate_id].file_id(unresolved_trait.module_id);
 
+    // The trait's generics are shared by all of its methods
+    let mut resolver = Resolver::new(interner, &path_resolver, def_maps, file);
+    resolver.add_generics(&unresolved_trait.trait_def.generics);
+    let trait_generics = resolver.get_generics().to_vec();
+    let mut resolver_errors = take_errors_filter_self_not_resolved(file, resolver);
+
+    interner.update_trait(trait_id, |trait_def| {
+        trait_def.generics = vecmap(&trait_generics, |(_, typevar, _)| match &*typevar.borrow() {
+            TypeBinding::Unbound(id) => (*id, typevar.clone()),
+            TypeBinding::Bound(binding) => {
+                unreachable!("Expected trait generic to be unbound, but it is bound to {binding}")
+            }
+        });
+    });
+
     let mut res = vec![];
-    let mut resolver_errors = vec![];
     for item in &unresolved_trait.trait_def.items {
         if let TraitItem::Function {
             name,
@@ -785,6 +1107,7 @@ fn resolve_trait_methods(
 
             let mut resolver = Resolver::new(interner, &path_resolver, def_maps, file);
             resolver.set_self_type(Some(self_type));
+            resolver.set_generics(trait_generics.clone());
 
             let arguments = vecmap(parameters, |param| resolver.resolve_type(param.1.clone()));
             let resolved_return_type = resolver.resolve_type(return_type.get_type().into_owned());
@@ -868,6 +1191,14 @@ fn resolve_traits(
         context.def_interner.update_trait(trait_id, |trait_def| {
             trait_def.set_methods(methods);
         });
+
+        if crate_id.is_stdlib() {
+            let def_map = &context.def_maps[&crate_id];
+            let local_id = unresolved_trait.module_id;
+            let parent = def_map.modules()[local_id.0].parent;
+            let module_path = def_map.get_module_path_with_separator(local_id.0, parent, "::");
+            context.def_interner.try_add_operator_trait(trait_id, &module_path);
+        }
     }
     res
 }
@@ -911,6 +1242,7 @@ fn resolve_impls(
     interner: &mut NodeInterner,
     crate_id: CrateId,
     def_maps: &BTreeMap<CrateId, CrateDefMap>,
+    file_manager: &FileManager,
     collected_impls: ImplMap,
     errors: &mut Vec<(CompilationError, FileId)>,
 ) -> Vec<(FileId, FuncId)> {
@@ -932,6 +1264,7 @@ fn resolve_impls(
                 interner,
                 crate_id,
                 def_maps,
+                file_manager,
                 functions,
                 Some(self_type.clone()),
                 generics,
@@ -978,11 +1311,31 @@ fn resolve_trait_impls(
 
         let self_type_span = unresolved_type.span;
 
-        let self_type = {
-            let mut resolver =
-                Resolver::new(interner, &path_resolver, &context.def_maps, trait_impl.file_id);
-            resolver.resolve_type(unresolved_type.clone())
-        };
+        // Unknown traits are reported when resolving each method's where clause
+        let where_clause_trait_ids = vecmap(&trait_impl.where_clause, |constraint| {
+            let trait_path = constraint.trait_bound.trait_path.clone();
+            resolve_trait_by_path(&context.def_maps, interner, module_id, trait_path).ok()
+        });
+
+        let mut resolver =
+            Resolver::new(interner, &path_resolver, &context.def_maps, trait_impl.file_id);
+        resolver.add_generics(&trait_impl.generics);
+        let impl_generics = resolver.get_generics().to_vec();
+        let self_type = resolver.resolve_type(unresolved_type.clone());
+
+        let where_clause = trait_impl
+            .where_clause
+            .iter()
+            .zip(where_clause_trait_ids)
+            .filter_map(|(constraint, trait_id)| {
+                let trait_id = trait_id?;
+                let typ = resolver.resolve_type(constraint.typ.clone());
+                let trait_generics = vecmap(&constraint.trait_bound.trait_generics, |typ| {
+                    resolver.resolve_type(typ.clone())
+                });
+                Some(TraitConstraint { typ, trait_id, trait_generics })
+            })
+            .collect();
 
         let maybe_trait_id = trait_impl.trait_id;
 
@@ -990,9 +1343,10 @@ fn resolve_trait_impls(
             interner,
             crate_id,
             &context.def_maps,
+            &context.file_manager,
             trait_impl.methods.clone(),
             Some(self_type.clone()),
-            vec![], // TODO
+            impl_generics.clone(),
             errors,
         );
 
@@ -1004,12 +1358,35 @@ fn resolve_trait_impls(
 
         let mut new_resolver =
             Resolver::new(interner, &path_resolver, &context.def_maps, trait_impl.file_id);
+        new_resolver.set_generics(impl_generics.clone());
         new_resolver.set_self_type(Some(self_type.clone()));
 
         if let Some(trait_id) = maybe_trait_id {
-            check_methods_signatures(&mut new_resolver, &impl_methods, trait_id, errors);
+            let trait_generics =
+                vecmap(&trait_impl.trait_generics, |typ| new_resolver.resolve_type(typ.clone()));
+
+            // The trait's generics may be left out entirely to have them inferred
+            let expected_generics = new_resolver.interner.get_trait(trait_id).generics.len();
+            if !trait_generics.is_empty() && trait_generics.len() != expected_generics {
+                let error = DefCollectorErrorKind::IncorrectTraitGenericCount {
+                    trait_name: trait_definition_ident.to_string(),
+                    expected: expected_generics,
+                    actual: trait_generics.len(),
+                    span: trait_impl.trait_path.span(),
+                };
+                errors.push((error.into(), trait_impl.file_id));
+            }
+
+            check_methods_signatures(
+                &mut new_resolver,
+                &impl_methods,
+                trait_id,
+                &trait_generics,
+                errors,
+            );
 
-            let key = TraitImplKey { typ: self_type.clone(), trait_id };
+            let key =
+                TraitImplKey { typ: self_type.clone(), trait_id, generics: trait_generics.clone() };
             if let Some(prev_trait_impl_ident) = interner.get_trait_implementation(&key) {
                 let err = DefCollectorErrorKind::Duplicate {
                     typ: DuplicateType::TraitImplementation,
@@ -1023,8 +1400,32 @@ fn resolve_trait_impls(
                     typ: self_type.clone(),
                     trait_id,
                     methods: vecmap(&impl_methods, |(_, func_id)| *func_id),
+                    trait_generics,
+                    generics: vecmap(&impl_generics, |(_, typevar, _)| match &*typevar.borrow() {
+                        TypeBinding::Unbound(id) => (*id, typevar.clone()),
+                        TypeBinding::Bound(binding) => {
+                            unreachable!(
+                                "Expected impl generic to be unbound, but it is bound to {binding}"
+                            )
+                        }
+                    }),
+                    where_clause,
+                    span: self_type_span.map_or(trait_impl.trait_path.span(), |span| {
+                        trait_impl.trait_path.span().merge(span)
+                    }),
                 });
-                if !interner.add_trait_implementation(&key, resolved_trait_impl.clone()) {
+
+                let overlapping_impl =
+                    interner.find_overlapping_trait_impl(&resolved_trait_impl.borrow());
+                if let Some(overlapping_impl) = overlapping_impl {
+                    let error = DefCollectorErrorKind::OverlappingImpl {
+                        trait_name: trait_definition_ident.to_string(),
+                        typ: self_type.to_string(),
+                        first_span: overlapping_impl.borrow().span,
+                        second_span: resolved_trait_impl.borrow().span,
+                    };
+                    errors.push((error.into(), trait_impl.file_id));
+                } else if !interner.add_trait_implementation(&key, resolved_trait_impl.clone()) {
                     let error = DefCollectorErrorKind::TraitImplNotAllowedFor {
                         trait_path: trait_impl.trait_path.clone(),
                         span: self_type_span.unwrap_or_else(|| trait_impl.trait_path.span()),
@@ -1045,6 +1446,7 @@ fn check_methods_signatures(
     resolver: &mut Resolver,
     impl_methods: &Vec<(FileId, FuncId)>,
     trait_id: TraitId,
+    trait_generics: &[Type],
     errors: &mut Vec<(CompilationError, FileId)>,
 ) {
     let the_trait = resolver.interner.get_trait(trait_id);
@@ -1054,6 +1456,21 @@ fn check_methods_signatures(
     // Temporarily bind the trait's Self type to self_type so we can type check
     let _ = the_trait.self_type_typevar.borrow_mut().bind_to(self_type.clone(), the_trait.span);
 
+    // The trait's generics are replaced by the generics given in `impl Trait<...> for`, if any.
+    // Otherwise they are inferred from the impl's methods.
+    let trait_generic_bindings: TypeBindings = the_trait
+        .generics
+        .iter()
+        .enumerate()
+        .map(|(index, (id, typevar))| {
+            let binding = match trait_generics.get(index) {
+                Some(typ) if trait_generics.len() == the_trait.generics.len() => typ.clone(),
+                _ => resolver.interner.next_type_variable(),
+            };
+            (*id, (typevar.clone(), binding))
+        })
+        .collect();
+
     for (file_id, func_id) in impl_methods {
         let meta = resolver.interner.function_meta(func_id);
         let func_name = resolver.interner.function_name(func_id).to_owned();
@@ -1074,6 +1491,7 @@ fn check_methods_signatures(
                     for (parameter_index, ((expected, actual), (hir_pattern, _, _))) in
                         method.arguments.iter().zip(&params).zip(&meta.parameters.0).enumerate()
                     {
+                        let expected = expected.substitute(&trait_generic_bindings);
                         expected.unify(actual, &mut typecheck_errors, || {
                             TypeCheckError::TraitMethodParameterTypeMismatch {
                                 method_name: func_name.to_string(),
@@ -1103,12 +1521,13 @@ fn check_methods_signatures(
             let resolved_return_type =
                 resolver.resolve_type(meta.return_type.get_type().into_owned());
 
-            method.return_type.unify(&resolved_return_type, &mut typecheck_errors, || {
+            let expected_return_type = method.return_type.substitute(&trait_generic_bindings);
+            expected_return_type.unify(&resolved_return_type, &mut typecheck_errors, || {
                 let ret_type_span =
                     meta.return_type.get_type().span.expect("return type must always have a span");
 
                 TypeCheckError::TypeMismatch {
-                    expected_typ: method.return_type.to_string(),
+                    expected_typ: expected_return_type.to_string(),
                     expr_typ: meta.return_type().to_string(),
                     expr_span: ret_type_span,
                 }
@@ -1125,6 +1544,7 @@ fn resolve_free_functions(
     interner: &mut NodeInterner,
     crate_id: CrateId,
     def_maps: &BTreeMap<CrateId, CrateDefMap>,
+    file_manager: &FileManager,
     collected_functions: Vec<UnresolvedFunctions>,
     self_type: Option<Type>,
     errors: &mut Vec<(CompilationError, FileId)>,
@@ -1137,6 +1557,7 @@ fn resolve_free_functions(
                 interner,
                 crate_id,
                 def_maps,
+                file_manager,
                 unresolved_functions,
                 self_type.clone(),
                 vec![], // no impl generics
@@ -1150,6 +1571,7 @@ fn resolve_function_set(
     interner: &mut NodeInterner,
     crate_id: CrateId,
     def_maps: &BTreeMap<CrateId, CrateDefMap>,
+    file_manager: &FileManager,
     mut unresolved_functions: UnresolvedFunctions,
     self_type: Option<Type>,
     impl_generics: Vec<(Rc<String>, Shared<TypeBinding>, Span)>,
@@ -1158,7 +1580,7 @@ fn resolve_function_set(
     let file_id = unresolved_functions.file_id;
 
     let where_clause_errors =
-        unresolved_functions.resolve_trait_bounds_trait_ids(def_maps, crate_id);
+        unresolved_functions.resolve_trait_bounds_trait_ids(def_maps, interner, crate_id);
     errors.extend(where_clause_errors.iter().cloned().map(|e| (e.into(), file_id)));
 
     vecmap(unresolved_functions.functions, |(mod_id, func_id, func)| {
@@ -1166,6 +1588,7 @@ fn resolve_function_set(
         let path_resolver = StandardPathResolver::new(module_id);
 
         let mut resolver = Resolver::new(interner, &path_resolver, def_maps, file_id);
+        resolver.set_file_manager(file_manager);
         // Must use set_generics here to ensure we re-use the same generics from when
         // the impl was originally collected. Otherwise the function will be using different
         // TypeVariables for the same generic, causing it to instantiate incorrectly.
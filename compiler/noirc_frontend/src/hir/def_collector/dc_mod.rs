@@ -10,17 +10,18 @@ use crate::{
     node_interner::{TraitId, TypeAliasId},
     parser::{SortedModule, SortedSubModule},
     FunctionDefinition, Ident, LetStatement, NoirFunction, NoirStruct, NoirTrait, NoirTraitImpl,
-    NoirTypeAlias, TraitImplItem, TraitItem, TypeImpl,
+    NoirTypeAlias, StaticAssert, TraitImplItem, TraitItem, TypeImpl,
 };
 
 use super::{
     dc_crate::{
-        CompilationError, DefCollector, UnresolvedFunctions, UnresolvedGlobal, UnresolvedTraitImpl,
-        UnresolvedTypeAlias,
+        CompilationError, DefCollector, UnresolvedFunctions, UnresolvedGlobal,
+        UnresolvedStaticAssert, UnresolvedTraitImpl, UnresolvedTypeAlias,
     },
+    derive::derive_trait_impls,
     errors::{DefCollectorErrorKind, DuplicateType},
 };
-use crate::hir::def_map::{parse_file, LocalModuleId, ModuleData, ModuleId};
+use crate::hir::def_map::{LocalModuleId, ModuleData, ModuleId};
 use crate::hir::resolution::import::ImportDirective;
 use crate::hir::Context;
 
@@ -29,6 +30,9 @@ struct ModCollector<'a> {
     pub(crate) def_collector: &'a mut DefCollector,
     pub(crate) file_id: FileId,
     pub(crate) module_id: LocalModuleId,
+    /// The names of the inline modules, as in `mod foo { ... }`, from the module of `file_id`
+    /// down to this module. The files of child modules are looked up within them.
+    pub(crate) inline_modules: Vec<String>,
 }
 
 /// Walk a module and collect its definitions.
@@ -39,14 +43,21 @@ pub fn collect_defs(
     ast: SortedModule,
     file_id: FileId,
     module_id: LocalModuleId,
+    inline_modules: Vec<String>,
     crate_id: CrateId,
     context: &mut Context,
 ) -> Vec<(CompilationError, FileId)> {
-    let mut collector = ModCollector { def_collector, file_id, module_id };
+    let mut collector = ModCollector { def_collector, file_id, module_id, inline_modules };
     let mut errors: Vec<(CompilationError, FileId)> = vec![];
-    // First resolve the module declarations
+
+    record_function_lint_levels(context, &ast, file_id);
+
+    // First resolve the module declarations, skipping test modules unless the crate is tested
+    let include_test_modules = context.tested_crate == Some(crate_id);
     for decl in ast.module_decls {
-        errors.extend(collector.parse_module_declaration(context, &decl, crate_id));
+        if !decl.is_test_only || include_test_modules {
+            errors.extend(collector.parse_module_declaration(context, &decl.name, crate_id));
+        }
     }
 
     errors.extend(collector.collect_submodules(context, crate_id, ast.submodules, file_id));
@@ -57,26 +68,47 @@ pub fn collect_defs(
             module_id: collector.module_id,
             path: import.path,
             alias: import.alias,
+            statement_span: import.statement_span,
         });
     }
 
     errors.extend(collector.collect_globals(context, ast.globals));
 
+    collector.collect_static_asserts(ast.static_asserts);
+
     errors.extend(collector.collect_traits(context, ast.traits, crate_id));
 
-    errors.extend(collector.collect_structs(context, ast.types, crate_id));
+    let mut trait_impls = ast.trait_impls;
+    errors.extend(collector.collect_structs(context, ast.types, crate_id, &mut trait_impls));
 
     errors.extend(collector.collect_type_aliases(context, ast.type_aliases));
 
     errors.extend(collector.collect_functions(context, ast.functions, crate_id));
 
-    errors.extend(collector.collect_trait_impls(context, ast.trait_impls, crate_id));
+    errors.extend(collector.collect_trait_impls(context, trait_impls, crate_id));
 
     collector.collect_impls(context, ast.impls, crate_id);
 
     errors
 }
 
+/// Records the lint levels set by the attributes of each function in the module so that
+/// warnings within them can be reported at those levels.
+fn record_function_lint_levels(context: &mut Context, ast: &SortedModule, file_id: FileId) {
+    let impl_methods = ast.impls.iter().flat_map(|r#impl| &r#impl.methods);
+    let trait_impl_methods = ast.trait_impls.iter().flat_map(|trait_impl| {
+        trait_impl.items.iter().filter_map(|item| match item {
+            TraitImplItem::Function(function) => Some(function),
+            _ => None,
+        })
+    });
+
+    for function in ast.functions.iter().chain(impl_methods).chain(trait_impl_methods) {
+        let span = function.name_ident().span().merge(function.span());
+        context.lint_levels.push_item(file_id, span, function.secondary_attributes());
+    }
+}
+
 impl<'a> ModCollector<'a> {
     fn collect_globals(
         &mut self,
@@ -114,6 +146,16 @@ impl<'a> ModCollector<'a> {
         errors
     }
 
+    fn collect_static_asserts(&mut self, static_asserts: Vec<StaticAssert>) {
+        for assertion in static_asserts {
+            self.def_collector.collected_static_asserts.push(UnresolvedStaticAssert {
+                file_id: self.file_id,
+                module_id: self.module_id,
+                assertion,
+            });
+        }
+    }
+
     fn collect_impls(&mut self, context: &mut Context, impls: Vec<TypeImpl>, krate: CrateId) {
         let module_id = ModuleId { krate, local_id: self.module_id };
 
@@ -124,7 +166,10 @@ impl<'a> ModCollector<'a> {
                 trait_id: None,
             };
 
-            for method in r#impl.methods {
+            for mut method in r#impl.methods {
+                // Constraints on the impl apply to each of its methods
+                method.def.where_clause.extend(r#impl.where_clause.iter().cloned());
+
                 let func_id = context.def_interner.push_empty_fn();
                 context.def_interner.push_function(func_id, &method.def, module_id);
                 unresolved_functions.push_fn(self.module_id, func_id, method);
@@ -160,6 +205,9 @@ impl<'a> ModCollector<'a> {
                 trait_path: trait_name,
                 methods: unresolved_functions,
                 object_type: trait_impl.object_type,
+                generics: trait_impl.impl_generics,
+                trait_generics: trait_impl.trait_generics,
+                where_clause: trait_impl.where_clause,
                 trait_id: None, // will be filled later
             };
 
@@ -181,9 +229,12 @@ impl<'a> ModCollector<'a> {
 
         for item in &trait_impl.items {
             if let TraitImplItem::Function(impl_method) = item {
+                let mut impl_method = impl_method.clone();
+                impl_method.def.where_clause.extend(trait_impl.where_clause.iter().cloned());
+
                 let func_id = context.def_interner.push_empty_fn();
                 context.def_interner.push_function(func_id, &impl_method.def, module);
-                unresolved_functions.push_fn(self.module_id, func_id, impl_method.clone());
+                unresolved_functions.push_fn(self.module_id, func_id, impl_method);
             }
         }
 
@@ -244,17 +295,24 @@ impl<'a> ModCollector<'a> {
     }
 
     /// Collect any struct definitions declared within the ast.
+    /// Impls generated by `#[derive(...)]` attributes are added to `trait_impls`.
     /// Returns a vector of errors if any structs were already defined.
     fn collect_structs(
         &mut self,
         context: &mut Context,
         types: Vec<NoirStruct>,
         krate: CrateId,
+        trait_impls: &mut Vec<NoirTraitImpl>,
     ) -> Vec<(CompilationError, FileId)> {
         let mut definition_errors = vec![];
         for struct_definition in types {
             let name = struct_definition.name.clone();
 
+            let (derived_impls, derive_errors) = derive_trait_impls(&struct_definition, krate);
+            trait_impls.extend(derived_impls);
+            definition_errors
+                .extend(derive_errors.into_iter().map(|error| (error.into(), self.file_id)));
+
             let unresolved = UnresolvedStruct {
                 file_id: self.file_id,
                 module_id: self.module_id,
@@ -463,14 +521,25 @@ impl<'a> ModCollector<'a> {
         file_id: FileId,
     ) -> Vec<(CompilationError, FileId)> {
         let mut errors: Vec<(CompilationError, FileId)> = vec![];
+        let include_test_modules = context.tested_crate == Some(crate_id);
         for submodule in submodules {
+            if submodule.is_test_only && !include_test_modules {
+                continue;
+            }
+
+            let attributes = &submodule.contents.inner_attributes;
+            context.lint_levels.push_item(file_id, submodule.span, attributes);
+
             match self.push_child_module(&submodule.name, file_id, true, submodule.is_contract) {
                 Ok(child) => {
+                    let mut inline_modules = self.inline_modules.clone();
+                    inline_modules.push(submodule.name.0.contents.clone());
                     errors.extend(collect_defs(
                         self.def_collector,
                         submodule.contents,
                         file_id,
                         child,
+                        inline_modules,
                         crate_id,
                         context,
                     ));
@@ -493,8 +562,9 @@ impl<'a> ModCollector<'a> {
         crate_id: CrateId,
     ) -> Vec<(CompilationError, FileId)> {
         let mut errors: Vec<(CompilationError, FileId)> = vec![];
+        let name = &mod_name.0.contents;
         let child_file_id =
-            match context.file_manager.find_module(self.file_id, &mod_name.0.contents) {
+            match context.file_manager.find_module(self.file_id, &self.inline_modules, name) {
                 Ok(child_file_id) => child_file_id,
                 Err(expected_path) => {
                     let mod_name = mod_name.clone();
@@ -525,8 +595,9 @@ impl<'a> ModCollector<'a> {
         context.visited_files.insert(child_file_id, location);
 
         // Parse the AST for the module we just found and then recursively look for it's defs
-        let (ast, parsing_errors) = parse_file(&context.file_manager, child_file_id);
+        let (ast, parsing_errors) = context.parse_file(child_file_id);
         let ast = ast.into_sorted();
+        context.lint_levels.push_file(child_file_id, Some(location), &ast.inner_attributes);
 
         errors.extend(
             parsing_errors.iter().map(|e| (e.clone().into(), child_file_id)).collect::<Vec<_>>(),
@@ -540,6 +611,7 @@ impl<'a> ModCollector<'a> {
                     ast,
                     child_file_id,
                     child_mod_id,
+                    Vec::new(),
                     crate_id,
                     context,
                 ));
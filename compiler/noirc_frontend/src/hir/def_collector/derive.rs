@@ -0,0 +1,507 @@
+//! Generates trait impls for structs annotated with `#[derive(...)]`.
+//!
+//! A derived impl is built as AST from the fields of the struct while its definition is being
+//! collected. From then on it is collected, resolved and type checked like a handwritten impl,
+//! so a field whose type does not implement the derived trait is reported at the struct.
+use acvm::FieldElement;
+use noirc_errors::{Span, Spanned};
+
+use crate::graph::CrateId;
+use crate::{
+    BinaryOpKind, BinaryTypeOperator, BlockExpression, CastExpression, Expression, ExpressionKind,
+    ForLoopStatement, FunctionDefinition, FunctionReturnType, Ident, IndexExpression,
+    InfixExpression, LValue, Literal, MemberAccessExpression, MethodCallExpression, NoirFunction,
+    NoirStruct, NoirTraitImpl, Path, PathKind, Pattern, Statement, StatementKind, TraitBound,
+    TraitImplItem, UnresolvedTraitConstraint, UnresolvedType, UnresolvedTypeData,
+    UnresolvedTypeExpression,
+};
+
+use super::errors::DefCollectorErrorKind;
+
+/// The traits which may be listed in a `#[derive(...)]` attribute.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum DerivableTrait {
+    Eq,
+    Default,
+    Serialize,
+}
+
+impl DerivableTrait {
+    fn lookup(name: &str) -> Option<DerivableTrait> {
+        match name {
+            "Eq" => Some(DerivableTrait::Eq),
+            "Default" => Some(DerivableTrait::Default),
+            "Serialize" => Some(DerivableTrait::Serialize),
+            _ => None,
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            DerivableTrait::Eq => "Eq",
+            DerivableTrait::Default => "Default",
+            DerivableTrait::Serialize => "Serialize",
+        }
+    }
+
+    /// The stdlib module the trait is declared in
+    fn module(self) -> &'static str {
+        match self {
+            DerivableTrait::Eq => "cmp",
+            DerivableTrait::Default => "default",
+            DerivableTrait::Serialize => "serialize",
+        }
+    }
+}
+
+/// Returns an impl for each trait listed in the `#[derive(...)]` attributes of `struct_def`.
+pub(super) fn derive_trait_impls(
+    struct_def: &NoirStruct,
+    krate: CrateId,
+) -> (Vec<NoirTraitImpl>, Vec<DefCollectorErrorKind>) {
+    let mut impls = Vec::new();
+    let mut errors = Vec::new();
+
+    for name in struct_def.derived_traits() {
+        let Some(derived) = DerivableTrait::lookup(name) else {
+            let span = struct_def.name.span();
+            errors.push(DefCollectorErrorKind::UnknownDeriveTrait { name: name.clone(), span });
+            continue;
+        };
+
+        let builder = Builder { span: struct_def.span };
+        match builder.trait_impl(struct_def, derived, krate) {
+            Ok(trait_impl) => impls.push(trait_impl),
+            Err((field, typ)) => errors.push(DefCollectorErrorKind::CannotDerive {
+                trait_name: derived.name().to_string(),
+                field,
+                typ: typ.to_string(),
+            }),
+        }
+    }
+
+    (impls, errors)
+}
+
+/// A field which cannot be used by a derived impl, along with its type
+type UnsupportedField = (Ident, UnresolvedType);
+
+/// Builds the AST of a derived impl. Every node is given the span of the struct.
+struct Builder {
+    span: Span,
+}
+
+impl Builder {
+    fn trait_impl(
+        &self,
+        struct_def: &NoirStruct,
+        derived: DerivableTrait,
+        krate: CrateId,
+    ) -> Result<NoirTraitImpl, UnsupportedField> {
+        let trait_path = self.trait_path(derived, krate);
+
+        let generic_args = struct_def.generics.iter().map(|generic| self.named_type(generic));
+        let object_type = self
+            .typ(UnresolvedTypeData::Named(self.path(&struct_def.name), generic_args.collect()));
+
+        // Only generics used as field types need to implement the trait, numeric generics
+        // used as array lengths are left unconstrained.
+        let mut used_generics = Vec::new();
+        for (_, typ) in &struct_def.fields {
+            collect_type_generics(typ, &struct_def.generics, &mut used_generics);
+        }
+        let where_clause = used_generics
+            .into_iter()
+            .map(|generic| UnresolvedTraitConstraint {
+                typ: self.named_type(&generic),
+                trait_bound: TraitBound {
+                    trait_path: trait_path.clone(),
+                    trait_id: None,
+                    trait_generics: Vec::new(),
+                },
+            })
+            .collect();
+
+        let method = match derived {
+            DerivableTrait::Eq => self.eq_method(struct_def)?,
+            DerivableTrait::Default => self.default_method(struct_def)?,
+            DerivableTrait::Serialize => self.serialize_method(struct_def)?,
+        };
+
+        Ok(NoirTraitImpl {
+            impl_generics: struct_def.generics.clone(),
+            trait_name: trait_path,
+            trait_generics: Vec::new(),
+            object_type,
+            where_clause,
+            items: vec![TraitImplItem::Function(NoirFunction::normal(method))],
+        })
+    }
+
+    /// `fn eq(self, other: Self) -> bool { self.a.eq(other.a) & ... }`
+    fn eq_method(&self, struct_def: &NoirStruct) -> Result<FunctionDefinition, UnsupportedField> {
+        let mut result: Option<Expression> = None;
+        for (field, typ) in &struct_def.fields {
+            let lhs = self.member_access(self.variable("self"), field.clone());
+            let rhs = self.member_access(self.variable("other"), field.clone());
+            let equal = self.eq_expression(lhs, rhs, typ, 0).ok_or((field.clone(), typ.clone()))?;
+            result = Some(match result {
+                Some(result) => self.infix(result, BinaryOpKind::And, equal),
+                None => equal,
+            });
+        }
+        let body = result.unwrap_or_else(|| self.expression(ExpressionKind::boolean(true)));
+
+        let parameters =
+            [(self.ident("self"), self.self_type()), (self.ident("other"), self.self_type())];
+        let return_type = self.typ(UnresolvedTypeData::Bool);
+        Ok(self.method("eq", &parameters, vec![self.tail(body)], return_type))
+    }
+
+    fn eq_expression(
+        &self,
+        lhs: Expression,
+        rhs: Expression,
+        typ: &UnresolvedType,
+        depth: usize,
+    ) -> Option<Expression> {
+        match &typ.typ {
+            UnresolvedTypeData::FieldElement
+            | UnresolvedTypeData::Integer(..)
+            | UnresolvedTypeData::Bool
+            | UnresolvedTypeData::Unit
+            | UnresolvedTypeData::String(_) => Some(self.infix(lhs, BinaryOpKind::Equal, rhs)),
+            UnresolvedTypeData::Named(..) => Some(self.method_call(lhs, "eq", vec![rhs])),
+            // Arrays of primitives can use the builtin `==`, otherwise each element is compared
+            UnresolvedTypeData::Array(_, element) if !contains_named_type(element) => {
+                Some(self.infix(lhs, BinaryOpKind::Equal, rhs))
+            }
+            UnresolvedTypeData::Array(_, element) => {
+                // { let mut equal = true; for i in 0..lhs.len() { equal = equal & ... } equal }
+                let equal = format!("equal{depth}");
+                let index = format!("i{depth}");
+                let lhs_element = self.index(lhs.clone(), self.variable(&index));
+                let rhs_element = self.index(rhs, self.variable(&index));
+                let element_equal =
+                    self.eq_expression(lhs_element, rhs_element, element, depth + 1)?;
+                let update = self.assign(
+                    &equal,
+                    self.infix(self.variable(&equal), BinaryOpKind::And, element_equal),
+                );
+                let statements = vec![
+                    self.let_mut(&equal, self.expression(ExpressionKind::boolean(true))),
+                    self.for_each_index(&index, lhs, vec![update]),
+                    self.tail(self.variable(&equal)),
+                ];
+                Some(self.block(statements))
+            }
+            UnresolvedTypeData::Tuple(elements) => {
+                let mut result = self.expression(ExpressionKind::boolean(true));
+                for (i, element) in elements.iter().enumerate() {
+                    let lhs = self.member_access(lhs.clone(), self.ident(&i.to_string()));
+                    let rhs = self.member_access(rhs.clone(), self.ident(&i.to_string()));
+                    let equal = self.eq_expression(lhs, rhs, element, depth)?;
+                    result = self.infix(result, BinaryOpKind::And, equal);
+                }
+                Some(result)
+            }
+            _ => None,
+        }
+    }
+
+    /// `fn default() -> Self { Foo { a: Default::default(), ... } }`
+    fn default_method(
+        &self,
+        struct_def: &NoirStruct,
+    ) -> Result<FunctionDefinition, UnsupportedField> {
+        let mut fields = Vec::new();
+        for (field, typ) in &struct_def.fields {
+            let value = self.default_expression(typ).ok_or((field.clone(), typ.clone()))?;
+            fields.push((field.clone(), value));
+        }
+        let type_name = self.path(&struct_def.name);
+        let body = self.expression(ExpressionKind::constructor((type_name, fields)));
+
+        Ok(self.method("default", &[], vec![self.tail(body)], self.self_type()))
+    }
+
+    fn default_expression(&self, typ: &UnresolvedType) -> Option<Expression> {
+        match &typ.typ {
+            UnresolvedTypeData::FieldElement | UnresolvedTypeData::Integer(..) => {
+                Some(self.expression(ExpressionKind::integer(FieldElement::zero())))
+            }
+            UnresolvedTypeData::Bool => Some(self.expression(ExpressionKind::boolean(false))),
+            UnresolvedTypeData::Unit => {
+                Some(self.expression(ExpressionKind::Literal(Literal::Unit)))
+            }
+            // Struct and generic types defer to their own `default`, e.g. `T::default()`
+            UnresolvedTypeData::Named(path, _) => {
+                let mut path = path.clone();
+                path.segments.push(self.ident("default"));
+                Some(self.call(self.expression(ExpressionKind::Variable(path, None)), Vec::new()))
+            }
+            UnresolvedTypeData::Array(Some(length), element) => {
+                let element = self.default_expression(element)?;
+                let length = self.type_expression(length);
+                Some(self.expression(ExpressionKind::repeated_array(element, length)))
+            }
+            UnresolvedTypeData::Array(None, _) => {
+                Some(self.expression(ExpressionKind::array(Vec::new())))
+            }
+            UnresolvedTypeData::Tuple(elements) => {
+                let elements = elements.iter().map(|element| self.default_expression(element));
+                let elements = elements.collect::<Option<Vec<_>>>()?;
+                Some(self.expression(ExpressionKind::Tuple(elements)))
+            }
+            _ => None,
+        }
+    }
+
+    /// `fn serialize(self) -> [Field] { let mut fields = []; ...; fields }`
+    fn serialize_method(
+        &self,
+        struct_def: &NoirStruct,
+    ) -> Result<FunctionDefinition, UnsupportedField> {
+        let mut statements =
+            vec![self.let_mut("fields", self.expression(ExpressionKind::array(Vec::new())))];
+        for (field, typ) in struct_def.fields_in_layout_order() {
+            let value = self.member_access(self.variable("self"), field.clone());
+            self.serialize_statements(value, typ, 0, &mut statements)
+                .ok_or((field.clone(), typ.clone()))?;
+        }
+        statements.push(self.tail(self.variable("fields")));
+
+        let parameters = [(self.ident("self"), self.self_type())];
+        let field = self.typ(UnresolvedTypeData::FieldElement);
+        let return_type = self.typ(UnresolvedTypeData::Array(None, Box::new(field)));
+        Ok(self.method("serialize", &parameters, statements, return_type))
+    }
+
+    /// Pushes the statements appending the serialization of `value` onto `fields`
+    fn serialize_statements(
+        &self,
+        value: Expression,
+        typ: &UnresolvedType,
+        depth: usize,
+        statements: &mut Vec<Statement>,
+    ) -> Option<()> {
+        match &typ.typ {
+            UnresolvedTypeData::FieldElement => {
+                let push = self.method_call(self.variable("fields"), "push_back", vec![value]);
+                statements.push(self.assign("fields", push));
+            }
+            UnresolvedTypeData::Integer(..) | UnresolvedTypeData::Bool => {
+                let value = self.expression(ExpressionKind::Cast(Box::new(CastExpression {
+                    lhs: value,
+                    r#type: self.typ(UnresolvedTypeData::FieldElement),
+                })));
+                let push = self.method_call(self.variable("fields"), "push_back", vec![value]);
+                statements.push(self.assign("fields", push));
+            }
+            UnresolvedTypeData::Unit => (),
+            UnresolvedTypeData::Named(..) => {
+                let serialized = self.method_call(value, "serialize", Vec::new());
+                let append = self.method_call(self.variable("fields"), "append", vec![serialized]);
+                statements.push(self.assign("fields", append));
+            }
+            UnresolvedTypeData::Array(_, element) => {
+                let index = format!("i{depth}");
+                let element_value = self.index(value.clone(), self.variable(&index));
+                let mut body = Vec::new();
+                self.serialize_statements(element_value, element, depth + 1, &mut body)?;
+                statements.push(self.for_each_index(&index, value, body));
+            }
+            UnresolvedTypeData::Tuple(elements) => {
+                for (i, element) in elements.iter().enumerate() {
+                    let value = self.member_access(value.clone(), self.ident(&i.to_string()));
+                    self.serialize_statements(value, element, depth, statements)?;
+                }
+            }
+            _ => return None,
+        }
+        Some(())
+    }
+
+    fn method(
+        &self,
+        name: &str,
+        parameters: &[(Ident, UnresolvedType)],
+        statements: Vec<Statement>,
+        return_type: UnresolvedType,
+    ) -> FunctionDefinition {
+        FunctionDefinition::normal(
+            &self.ident(name),
+            &Vec::new(),
+            parameters,
+            &BlockExpression(statements),
+            &[],
+            &FunctionReturnType::Ty(return_type),
+        )
+    }
+
+    fn trait_path(&self, derived: DerivableTrait, krate: CrateId) -> Path {
+        let mut segments = vec![self.ident(derived.module()), self.ident(derived.name())];
+        if krate.is_stdlib() {
+            Path { segments, kind: PathKind::Crate }
+        } else {
+            segments.insert(0, self.ident("std"));
+            Path { segments, kind: PathKind::Dep }
+        }
+    }
+
+    fn ident(&self, name: &str) -> Ident {
+        Ident::new(name.to_string(), self.span)
+    }
+
+    fn path(&self, name: &Ident) -> Path {
+        Path::from_single(name.0.contents.clone(), self.span)
+    }
+
+    fn typ(&self, typ: UnresolvedTypeData) -> UnresolvedType {
+        typ.with_span(self.span)
+    }
+
+    fn named_type(&self, name: &Ident) -> UnresolvedType {
+        self.typ(UnresolvedTypeData::Named(self.path(name), Vec::new()))
+    }
+
+    fn self_type(&self) -> UnresolvedType {
+        self.named_type(&self.ident("Self"))
+    }
+
+    fn expression(&self, kind: ExpressionKind) -> Expression {
+        Expression::new(kind, self.span)
+    }
+
+    fn variable(&self, name: &str) -> Expression {
+        let path = Path::from_single(name.to_string(), self.span);
+        self.expression(ExpressionKind::Variable(path, None))
+    }
+
+    fn member_access(&self, lhs: Expression, rhs: Ident) -> Expression {
+        self.expression(ExpressionKind::MemberAccess(Box::new(MemberAccessExpression { lhs, rhs })))
+    }
+
+    fn method_call(
+        &self,
+        object: Expression,
+        name: &str,
+        arguments: Vec<Expression>,
+    ) -> Expression {
+        let method_name = self.ident(name);
+        let call = MethodCallExpression { object, method_name, arguments };
+        self.expression(ExpressionKind::MethodCall(Box::new(call)))
+    }
+
+    fn call(&self, func: Expression, arguments: Vec<Expression>) -> Expression {
+        Expression::call(func, arguments, self.span)
+    }
+
+    fn index(&self, collection: Expression, index: Expression) -> Expression {
+        self.expression(ExpressionKind::Index(Box::new(IndexExpression { collection, index })))
+    }
+
+    fn infix(&self, lhs: Expression, operator: BinaryOpKind, rhs: Expression) -> Expression {
+        let operator = Spanned::from(self.span, operator);
+        self.expression(ExpressionKind::Infix(Box::new(InfixExpression { lhs, operator, rhs })))
+    }
+
+    fn block(&self, statements: Vec<Statement>) -> Expression {
+        self.expression(ExpressionKind::Block(BlockExpression(statements)))
+    }
+
+    fn statement(&self, kind: StatementKind) -> Statement {
+        Statement { kind, span: self.span }
+    }
+
+    /// The final expression of a block, which is its value
+    fn tail(&self, expression: Expression) -> Statement {
+        self.statement(StatementKind::Expression(expression))
+    }
+
+    fn let_mut(&self, name: &str, expression: Expression) -> Statement {
+        let pattern = Pattern::Mutable(Box::new(Pattern::Identifier(self.ident(name))), self.span);
+        let r#type = UnresolvedTypeData::Unspecified.with_span(self.span);
+        self.statement(StatementKind::new_let(((pattern, r#type), expression)))
+    }
+
+    fn assign(&self, name: &str, expression: Expression) -> Statement {
+        let lvalue = LValue::Ident(self.ident(name));
+        self.statement(StatementKind::assign(
+            lvalue,
+            crate::token::Token::Assign,
+            expression,
+            self.span,
+        ))
+    }
+
+    /// `for index in 0..array.len() { body }`
+    fn for_each_index(&self, index: &str, array: Expression, body: Vec<Statement>) -> Statement {
+        let start_range = self.expression(ExpressionKind::integer(FieldElement::zero()));
+        let end_range = self.method_call(array, "len", Vec::new());
+        self.statement(StatementKind::For(ForLoopStatement {
+            identifier: self.ident(index),
+            start_range,
+            end_range,
+            block: self.block(body),
+        }))
+    }
+
+    /// Converts an array length back into an expression, for use in an array literal
+    fn type_expression(&self, length: &UnresolvedTypeExpression) -> Expression {
+        match length {
+            UnresolvedTypeExpression::Variable(path) => {
+                self.expression(ExpressionKind::Variable(path.clone(), None))
+            }
+            UnresolvedTypeExpression::Constant(value, _) => {
+                self.expression(ExpressionKind::integer(FieldElement::from(*value as u128)))
+            }
+            UnresolvedTypeExpression::BinaryOperation(lhs, operator, rhs, _) => {
+                let operator = match operator {
+                    BinaryTypeOperator::Addition => BinaryOpKind::Add,
+                    BinaryTypeOperator::Subtraction => BinaryOpKind::Subtract,
+                    BinaryTypeOperator::Multiplication => BinaryOpKind::Multiply,
+                    BinaryTypeOperator::Division => BinaryOpKind::Divide,
+                    BinaryTypeOperator::Modulo => BinaryOpKind::Modulo,
+                };
+                self.infix(self.type_expression(lhs), operator, self.type_expression(rhs))
+            }
+        }
+    }
+}
+
+/// Collects the generics of a struct which are used as (part of) the type of a field
+fn collect_type_generics(typ: &UnresolvedType, generics: &[Ident], used: &mut Vec<Ident>) {
+    match &typ.typ {
+        UnresolvedTypeData::Named(path, args) => {
+            if let Some(name) = path.as_ident() {
+                if generics.contains(name) && !used.contains(name) {
+                    used.push(name.clone());
+                }
+            }
+            for arg in args {
+                collect_type_generics(arg, generics, used);
+            }
+        }
+        UnresolvedTypeData::Array(_, element)
+        | UnresolvedTypeData::FormatString(_, element)
+        | UnresolvedTypeData::MutableReference(element) => {
+            collect_type_generics(element, generics, used);
+        }
+        UnresolvedTypeData::Tuple(elements) => {
+            for element in elements {
+                collect_type_generics(element, generics, used);
+            }
+        }
+        _ => (),
+    }
+}
+
+fn contains_named_type(typ: &UnresolvedType) -> bool {
+    match &typ.typ {
+        UnresolvedTypeData::Named(..) => true,
+        UnresolvedTypeData::Array(_, element) => contains_named_type(element),
+        UnresolvedTypeData::Tuple(elements) => elements.iter().any(contains_named_type),
+        _ => false,
+    }
+}
@@ -2,9 +2,10 @@ use crate::hir::resolution::import::PathResolutionError;
 use crate::Ident;
 use crate::Path;
 
+use noirc_errors::codes::*;
 use noirc_errors::CustomDiagnostic as Diagnostic;
 use noirc_errors::FileDiagnostic;
-use noirc_errors::Span;
+use noirc_errors::{lints, ErrorCode, Fix, Span};
 use thiserror::Error;
 
 use std::fmt;
@@ -64,12 +65,54 @@ pub enum DefCollectorErrorKind {
         "Either the type or the trait must be from the same crate as the trait implementation"
     )]
     TraitImplOrphaned { span: Span },
+    #[error("Conflicting implementations of trait")]
+    OverlappingImpl { trait_name: String, typ: String, first_span: Span, second_span: Span },
+    #[error("Trait cannot be derived")]
+    UnknownDeriveTrait { name: String, span: Span },
+    #[error("Field type does not support deriving the trait")]
+    CannotDerive { trait_name: String, field: Ident, typ: String },
+    #[error("unused import")]
+    UnusedImport { ident: Ident, statement_span: Option<Span> },
+    #[error("function is never used")]
+    UnreachableFunction { ident: Ident },
+    #[error("Prelude is not a module")]
+    PreludeNotAModule { path: String, span: Span },
+    #[error("Incorrect number of generics given to trait")]
+    IncorrectTraitGenericCount { trait_name: String, expected: usize, actual: usize, span: Span },
 }
 
 impl DefCollectorErrorKind {
     pub fn into_file_diagnostic(self, file: fm::FileId) -> FileDiagnostic {
         Diagnostic::from(self).in_file(file)
     }
+
+    pub fn code(&self) -> ErrorCode {
+        match self {
+            DefCollectorErrorKind::Duplicate { .. } => E0100,
+            DefCollectorErrorKind::UnresolvedModuleDecl { .. } => E0101,
+            DefCollectorErrorKind::ModuleAlreadyPartOfCrate { .. }
+            | DefCollectorErrorKind::ModuleOriginallyDefined { .. } => E0112,
+            DefCollectorErrorKind::PathResolutionError(error) => error.code(),
+            DefCollectorErrorKind::NonStructTypeInImpl { .. }
+            | DefCollectorErrorKind::TraitImplNotAllowedFor { .. } => E0102,
+            DefCollectorErrorKind::ForeignImpl { .. }
+            | DefCollectorErrorKind::TraitImplOrphaned { .. } => E0113,
+            DefCollectorErrorKind::MismatchTraitImplementationNumParameters { .. } => E0103,
+            DefCollectorErrorKind::MethodNotInTrait { .. } => E0114,
+            DefCollectorErrorKind::TraitMissingMethod { .. } => E0115,
+            DefCollectorErrorKind::NotATrait { .. } => E0104,
+            DefCollectorErrorKind::TraitNotFound { .. } => E0116,
+            DefCollectorErrorKind::OverlappingImpl { .. } => E0105,
+            #[cfg(feature = "aztec")]
+            DefCollectorErrorKind::AztecNotFound {} => E0106,
+            DefCollectorErrorKind::UnknownDeriveTrait { .. } => E0107,
+            DefCollectorErrorKind::CannotDerive { .. } => E0117,
+            DefCollectorErrorKind::UnusedImport { .. } => E0108,
+            DefCollectorErrorKind::UnreachableFunction { .. } => E0109,
+            DefCollectorErrorKind::PreludeNotAModule { .. } => E0110,
+            DefCollectorErrorKind::IncorrectTraitGenericCount { .. } => E0111,
+        }
+    }
 }
 
 impl fmt::Display for DuplicateType {
@@ -91,7 +134,8 @@ impl fmt::Display for DuplicateType {
 
 impl From<DefCollectorErrorKind> for Diagnostic {
     fn from(error: DefCollectorErrorKind) -> Diagnostic {
-        match error {
+        let code = error.code();
+        let diagnostic = match error {
             DefCollectorErrorKind::Duplicate { typ, first_def, second_def } => {
                 let primary_message = format!(
                     "Duplicate definitions of {} with name {} found",
@@ -203,6 +247,70 @@ impl From<DefCollectorErrorKind> for Diagnostic {
                 "Either the type or the trait must be from the same crate as the trait implementation".into(),
                 span,
             ),
-        }
+            DefCollectorErrorKind::OverlappingImpl { trait_name, typ, first_span, second_span } => {
+                let mut diag = Diagnostic::simple_error(
+                    format!("Conflicting implementations of trait `{trait_name}` for `{typ}`"),
+                    "Overlaps with an existing implementation".into(),
+                    second_span,
+                );
+                diag.add_secondary("First implementation found here".into(), first_span);
+                diag
+            }
+            DefCollectorErrorKind::UnknownDeriveTrait { name, span } => Diagnostic::simple_error(
+                format!("`{name}` cannot be derived"),
+                "Only `Eq`, `Default` and `Serialize` can be derived".into(),
+                span,
+            ),
+            DefCollectorErrorKind::CannotDerive { trait_name, field, typ } => {
+                Diagnostic::simple_error(
+                    format!("Cannot derive `{trait_name}` for a struct with a field of type `{typ}`"),
+                    format!("`{field}` cannot be used in a derived `{trait_name}` impl"),
+                    field.span(),
+                )
+            }
+            DefCollectorErrorKind::UnusedImport { ident, statement_span } => {
+                let name = &ident.0.contents;
+
+                let mut diagnostic = Diagnostic::simple_warning(
+                    format!("unused import {name}"),
+                    "unused import".to_string(),
+                    ident.span(),
+                );
+                if let Some(span) = statement_span {
+                    diagnostic.add_fix(Fix::remove("remove the unused import".to_string(), span));
+                }
+                diagnostic.with_lint(lints::UNUSED_IMPORTS)
+            }
+            DefCollectorErrorKind::UnreachableFunction { ident } => {
+                let name = &ident.0.contents;
+
+                Diagnostic::simple_warning(
+                    format!("function {name} is never used"),
+                    "not reachable from main, tests or any public function".to_string(),
+                    ident.span(),
+                )
+                .with_lint(lints::DEAD_CODE)
+            }
+            DefCollectorErrorKind::PreludeNotAModule { path, span } => Diagnostic::simple_error(
+                format!("Prelude `{path}` is not a module"),
+                "The items of the module at this path are brought into scope".to_string(),
+                span,
+            ),
+            DefCollectorErrorKind::IncorrectTraitGenericCount {
+                trait_name,
+                expected,
+                actual,
+                span,
+            } => {
+                let expected_plural = if expected == 1 { "" } else { "s" };
+                let actual_plural = if actual == 1 { "is" } else { "are" };
+                Diagnostic::simple_error(
+                    format!("The trait {trait_name} has {expected} generic{expected_plural} but {actual} {actual_plural} given here"),
+                    "Incorrect number of generic arguments".to_string(),
+                    span,
+                )
+            }
+        };
+        diagnostic.with_code(code)
     }
 }
@@ -3,7 +3,12 @@ use crate::{
     node_interner::{FuncId, TraitId},
     Ident,
 };
-use std::collections::{hash_map::Entry, HashMap};
+use indexmap::{map::Entry, IndexMap};
+
+/// The items declared under each name, keyed by the trait they come from (if any).
+/// These are kept in declaration order so that iterating over a module's definitions,
+/// e.g. to find a contract's functions, is deterministic.
+type ItemMap = IndexMap<Ident, IndexMap<Option<TraitId>, (ModuleDefId, Visibility)>>;
 
 #[derive(Debug, PartialEq, Eq, Copy, Clone)]
 pub enum Visibility {
@@ -12,8 +17,8 @@ pub enum Visibility {
 
 #[derive(Default, Debug, PartialEq, Eq)]
 pub struct ItemScope {
-    types: HashMap<Ident, HashMap<Option<TraitId>, (ModuleDefId, Visibility)>>,
-    values: HashMap<Ident, HashMap<Option<TraitId>, (ModuleDefId, Visibility)>>,
+    types: ItemMap,
+    values: ItemMap,
 
     defs: Vec<ModuleDefId>,
 }
@@ -39,24 +44,23 @@ impl ItemScope {
         mod_def: ModuleDefId,
         trait_id: Option<TraitId>,
     ) -> Result<(), (Ident, Ident)> {
-        let add_item =
-            |map: &mut HashMap<Ident, HashMap<Option<TraitId>, (ModuleDefId, Visibility)>>| {
-                if let Entry::Occupied(mut o) = map.entry(name.clone()) {
-                    let trait_hashmap = o.get_mut();
-                    if let Entry::Occupied(_) = trait_hashmap.entry(trait_id) {
-                        let old_ident = o.key();
-                        Err((old_ident.clone(), name))
-                    } else {
-                        trait_hashmap.insert(trait_id, (mod_def, Visibility::Public));
-                        Ok(())
-                    }
+        let add_item = |map: &mut ItemMap| {
+            if let Entry::Occupied(mut o) = map.entry(name.clone()) {
+                let trait_hashmap = o.get_mut();
+                if let Entry::Occupied(_) = trait_hashmap.entry(trait_id) {
+                    let old_ident = o.key();
+                    Err((old_ident.clone(), name))
                 } else {
-                    let mut trait_hashmap = HashMap::new();
                     trait_hashmap.insert(trait_id, (mod_def, Visibility::Public));
-                    map.insert(name, trait_hashmap);
                     Ok(())
                 }
-            };
+            } else {
+                let mut trait_hashmap = IndexMap::new();
+                trait_hashmap.insert(trait_id, (mod_def, Visibility::Public));
+                map.insert(name, trait_hashmap);
+                Ok(())
+            }
+        };
 
         match mod_def {
             ModuleDefId::ModuleId(_) => add_item(&mut self.types),
@@ -115,20 +119,19 @@ impl ItemScope {
     pub fn find_name(&self, name: &Ident) -> PerNs {
         // Names, not associated with traits are searched first. If not found, we search for name, coming from a trait.
         // If we find only one name from trait, we return it. If there are multiple traits, providing the same name, we return None.
-        let find_name_in =
-            |a: &HashMap<Ident, HashMap<Option<TraitId>, (ModuleDefId, Visibility)>>| {
-                if let Some(t) = a.get(name) {
-                    if let Some(tt) = t.get(&None) {
-                        Some(*tt)
-                    } else if t.len() == 1 {
-                        t.values().last().cloned()
-                    } else {
-                        None
-                    }
+        let find_name_in = |a: &ItemMap| {
+            if let Some(t) = a.get(name) {
+                if let Some(tt) = t.get(&None) {
+                    Some(*tt)
+                } else if t.len() == 1 {
+                    t.values().last().cloned()
                 } else {
                     None
                 }
-            };
+            } else {
+                None
+            }
+        };
 
         PerNs { types: find_name_in(&self.types), values: find_name_in(&self.values) }
     }
@@ -148,16 +151,16 @@ impl ItemScope {
         self.defs.clone()
     }
 
-    pub fn types(&self) -> &HashMap<Ident, HashMap<Option<TraitId>, (ModuleDefId, Visibility)>> {
+    pub fn types(&self) -> &ItemMap {
         &self.types
     }
 
-    pub fn values(&self) -> &HashMap<Ident, HashMap<Option<TraitId>, (ModuleDefId, Visibility)>> {
+    pub fn values(&self) -> &ItemMap {
         &self.values
     }
 
     pub fn remove_definition(&mut self, name: &Ident) {
-        self.types.remove(name);
-        self.values.remove(name);
+        self.types.shift_remove(name);
+        self.values.shift_remove(name);
     }
 }
@@ -62,10 +62,16 @@ pub struct CrateDefMap {
     pub(crate) krate: CrateId,
 
     pub(crate) extern_prelude: BTreeMap<String, ModuleId>,
+
+    /// The module whose items are in scope in every module of the crate without being imported:
+    /// `std::prelude` unless the crate root says otherwise with `#![no_std_prelude]` or
+    /// `#![prelude(path)]`.
+    pub(crate) prelude: Option<ModuleId>,
 }
 
 impl CrateDefMap {
     /// Collect all definitions in the crate
+    #[tracing::instrument(skip(context))]
     pub fn collect_defs(
         crate_id: CrateId,
         context: &mut Context,
@@ -80,9 +86,11 @@ impl CrateDefMap {
             return errors;
         }
 
+        context.def_interner.cancellation_token().check();
+
         // First parse the root file.
         let root_file_id = context.crate_graph[crate_id].root_file_id;
-        let (ast, parsing_errors) = parse_file(&context.file_manager, root_file_id);
+        let (ast, parsing_errors) = context.parse_file(root_file_id);
         let ast = ast.into_sorted();
 
         #[cfg(feature = "aztec")]
@@ -104,6 +112,7 @@ impl CrateDefMap {
             modules,
             krate: crate_id,
             extern_prelude: BTreeMap::new(),
+            prelude: None,
         };
 
         // Now we want to populate the CrateDefMap using the DefCollector
@@ -162,6 +171,23 @@ impl CrateDefMap {
         })
     }
 
+    /// Go through all modules in this crate, and find all functions in
+    /// each module with the #[oracle] attribute alongside the name of their oracle
+    pub fn get_all_oracle_functions<'a>(
+        &'a self,
+        interner: &'a NodeInterner,
+    ) -> impl Iterator<Item = (FuncId, String)> + 'a {
+        self.modules.iter().flat_map(|(_, module)| {
+            module.value_definitions().filter_map(|id| {
+                let func_id = id.as_function()?;
+                match &interner.function_attributes(&func_id).function {
+                    Some(FunctionAttribute::Oracle(name)) => Some((func_id, name.clone())),
+                    _ => None,
+                }
+            })
+        })
+    }
+
     /// Go through all modules in this crate, find all `contract ... { ... }` declarations,
     /// and collect them all into a Vec.
     pub fn get_all_contracts(&self, interner: &NodeInterner) -> Vec<Contract> {
@@ -258,6 +284,7 @@ pub struct Contract {
 }
 
 /// Given a FileId, fetch the File, from the FileManager and parse it's content
+#[tracing::instrument(level = "debug", skip_all, fields(file = %fm.path(file_id).display()))]
 pub fn parse_file(fm: &FileManager, file_id: FileId) -> (ParsedModule, Vec<ParserError>) {
     let file = fm.fetch_file(file_id);
     parse_program(file.source())
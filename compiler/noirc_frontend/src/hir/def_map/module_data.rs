@@ -112,10 +112,23 @@ impl ModuleData {
         self.scope.find_name(name)
     }
 
+    /// Return an iterator over the names of all values visible in this module, including imports.
+    pub fn value_names(&self) -> impl Iterator<Item = &Ident> {
+        self.scope.values().keys()
+    }
+
     pub fn type_definitions(&self) -> impl Iterator<Item = ModuleDefId> + '_ {
         self.definitions.types().values().flat_map(|a| a.values().map(|(id, _)| *id))
     }
 
+    /// Return an iterator over the names and ids of all definitions defined within this module,
+    /// types first followed by values.
+    pub fn named_definitions(&self) -> impl Iterator<Item = (&Ident, ModuleDefId)> + '_ {
+        let types = self.definitions.types().iter();
+        let values = self.definitions.values().iter();
+        types.chain(values).flat_map(|(name, a)| a.values().map(move |(id, _)| (name, *id)))
+    }
+
     /// Return an iterator over all definitions defined within this module,
     /// excluding any type definitions.
     pub fn value_definitions(&self) -> impl Iterator<Item = ModuleDefId> + '_ {
@@ -0,0 +1,143 @@
+//! Tracks where the levels of lints are changed by `#[allow(..)]`, `#[warn(..)]` and
+//! `#[deny(..)]` attributes so that the warnings of each lint can be reported at the level
+//! set where they occur.
+//!
+//! An attribute on a function applies to the whole function. An inner attribute such as
+//! `#![allow(dead_code)]` applies to the module it is written in along with its submodules,
+//! so one in a crate's root file applies to the whole crate. The innermost level set for a
+//! lint takes precedence, then any level set on the command line, then the lint's default level.
+use fm::FileId;
+use noirc_errors::{FileDiagnostic, Lint, LintLevel, Location, Span};
+
+use crate::token::SecondaryAttribute;
+
+#[derive(Debug, Default)]
+pub struct LintLevels {
+    scopes: Vec<LintScope>,
+
+    /// The levels set with nargo's `--allow`, `--warn` and `--deny` options.
+    command_line: Vec<(Lint, LintLevel)>,
+}
+
+#[derive(Debug)]
+struct LintScope {
+    file: FileId,
+    /// The part of the file the levels apply to, or `None` if they apply to the whole file.
+    span: Option<Span>,
+    /// Later levels take precedence over earlier ones for the same lint.
+    levels: Vec<(Lint, LintLevel)>,
+}
+
+impl LintScope {
+    fn contains(&self, file: FileId, span: Span) -> bool {
+        self.file == file
+            && self
+                .span
+                .map_or(true, |scope| scope.start() <= span.start() && span.end() <= scope.end())
+    }
+
+    fn level(&self, lint: Lint) -> Option<LintLevel> {
+        self.levels
+            .iter()
+            .rev()
+            .find(|(scope_lint, _)| *scope_lint == lint)
+            .map(|(_, level)| *level)
+    }
+
+    /// Scopes over part of a file are nested within any over the whole file, and
+    /// otherwise a scope is nested within another if it is smaller.
+    fn size(&self) -> u32 {
+        self.span.map_or(u32::MAX, |span| span.end() - span.start())
+    }
+}
+
+impl LintLevels {
+    /// Sets the level of `lint` wherever it isn't set by an attribute.
+    pub fn set_command_line_level(&mut self, lint: Lint, level: LintLevel) {
+        self.command_line.push((lint, level));
+    }
+
+    /// Records the levels set by the attributes of an item covering `span` in `file`.
+    pub(crate) fn push_item(
+        &mut self,
+        file: FileId,
+        span: Span,
+        attributes: &[SecondaryAttribute],
+    ) {
+        let levels = lint_levels(attributes);
+        if !levels.is_empty() {
+            self.scopes.push(LintScope { file, span: Some(span), levels });
+        }
+    }
+
+    /// Records the levels set by the inner attributes of the module in `file`. Modules declared
+    /// with `mod foo;` also inherit the levels where they are declared, given by `declaration`.
+    pub(crate) fn push_file(
+        &mut self,
+        file: FileId,
+        declaration: Option<Location>,
+        attributes: &[SecondaryAttribute],
+    ) {
+        let mut levels = Vec::new();
+        if let Some(declaration) = declaration {
+            for lint in Lint::all() {
+                if let Some(level) = self.attribute_level(lint, declaration.file, declaration.span)
+                {
+                    levels.push((lint, level));
+                }
+            }
+        }
+        levels.extend(lint_levels(attributes));
+
+        if !levels.is_empty() {
+            self.scopes.push(LintScope { file, span: None, levels });
+        }
+    }
+
+    /// The level of `lint` at `span` in `file`.
+    pub fn level(&self, lint: Lint, file: FileId, span: Span) -> LintLevel {
+        self.attribute_level(lint, file, span)
+            .or_else(|| {
+                let command_line = self.command_line.iter().rev();
+                command_line
+                    .find(|(command_line_lint, _)| *command_line_lint == lint)
+                    .map(|(_, level)| *level)
+            })
+            .unwrap_or(lint.default_level())
+    }
+
+    fn attribute_level(&self, lint: Lint, file: FileId, span: Span) -> Option<LintLevel> {
+        self.scopes
+            .iter()
+            .filter(|scope| scope.contains(file, span))
+            .filter_map(|scope| Some((scope.size(), scope.level(lint)?)))
+            .min_by_key(|(size, _)| *size)
+            .map(|(_, level)| level)
+    }
+
+    /// Reports `diagnostic` at the level of its lint where it occurs, returning `None` if the
+    /// lint is allowed there. Diagnostics which don't belong to a lint are returned unchanged.
+    pub fn apply(&self, diagnostic: FileDiagnostic) -> Option<FileDiagnostic> {
+        let FileDiagnostic { file_id, diagnostic, call_stack } = diagnostic;
+
+        let diagnostic = match (diagnostic.lint, diagnostic.secondaries.first()) {
+            (Some(lint), Some(label)) => {
+                let level = self.level(lint, file_id, label.span);
+                diagnostic.at_lint_level(level)?
+            }
+            _ => diagnostic,
+        };
+        Some(FileDiagnostic { file_id, diagnostic, call_stack })
+    }
+}
+
+fn lint_levels(attributes: &[SecondaryAttribute]) -> Vec<(Lint, LintLevel)> {
+    let mut levels = Vec::new();
+    for attribute in attributes {
+        if let SecondaryAttribute::Lint(level, names) = attribute {
+            // Lint names are checked when the attribute is lexed
+            levels.extend(names.iter().filter_map(|name| Some((Lint::lookup(name)?, *level))));
+        }
+    }
+    levels
+}
@@ -1,5 +1,8 @@
+pub mod cancellation;
+pub mod comptime;
 pub mod def_collector;
 pub mod def_map;
+pub mod lints;
 pub mod resolution;
 pub mod scope;
 pub mod type_check;
@@ -10,8 +13,11 @@ pub(crate) mod aztec_library;
 use crate::graph::{CrateGraph, CrateId, Dependency};
 use crate::hir_def::function::FuncMeta;
 use crate::node_interner::{FuncId, NodeInterner, StructId};
-use def_map::{Contract, CrateDefMap};
-use fm::FileManager;
+use crate::parser::{preparsed::PreparsedFiles, ParserError};
+use crate::ParsedModule;
+use def_map::{parse_file, Contract, CrateDefMap};
+use fm::{FileId, FileManager};
+use lints::LintLevels;
 use noirc_errors::Location;
 use std::collections::BTreeMap;
 
@@ -33,6 +39,16 @@ pub struct Context {
     /// Maps a given (contract) module id to the next available storage slot
     /// for that contract.
     pub storage_slots: BTreeMap<def_map::ModuleId, StorageSlot>,
+
+    /// Files which were parsed ahead of time and so don't need to be parsed again, e.g. the stdlib.
+    pub preparsed_files: PreparsedFiles,
+
+    /// The levels set for each lint, both by attributes in the source and on the command line.
+    pub lint_levels: LintLevels,
+
+    /// The crate whose tests are being compiled, if any. Modules marked with `#[test]` are only
+    /// compiled in this crate, and skipped everywhere else.
+    pub tested_crate: Option<CrateId>,
 }
 
 #[derive(Debug, Copy, Clone)]
@@ -53,6 +69,17 @@ impl Context {
             crate_graph,
             file_manager,
             storage_slots: BTreeMap::new(),
+            preparsed_files: PreparsedFiles::default(),
+            lint_levels: LintLevels::default(),
+            tested_crate: None,
+        }
+    }
+
+    /// Parses the given file, reusing its pre-parsed module if it has one.
+    pub fn parse_file(&self, file_id: FileId) -> (ParsedModule, Vec<ParserError>) {
+        match self.preparsed_files.get(self.file_manager.path(file_id)) {
+            Some(module) => (module, Vec::new()),
+            None => parse_file(&self.file_manager, file_id),
         }
     }
 
@@ -1,5 +1,6 @@
+use noirc_errors::codes::*;
 pub use noirc_errors::Span;
-use noirc_errors::{CustomDiagnostic as Diagnostic, FileDiagnostic};
+use noirc_errors::{lints, CustomDiagnostic as Diagnostic, ErrorCode, FileDiagnostic};
 use thiserror::Error;
 
 use crate::{parser::ParserError, Ident, Type};
@@ -20,8 +21,10 @@ pub enum ResolverError {
     DuplicateDefinition { name: String, first_span: Span, second_span: Span },
     #[error("Unused variable")]
     UnusedVariable { ident: Ident },
+    #[error("Variable shadows an earlier binding")]
+    ShadowedVariable { name: String, first_span: Span, second_span: Span },
     #[error("Could not find variable in this scope")]
-    VariableNotDeclared { name: String, span: Span },
+    VariableNotDeclared { name: String, span: Span, suggestion: Option<String> },
     #[error("path is not an identifier")]
     PathIsNotIdent { span: Span },
     #[error("could not resolve path")]
@@ -31,7 +34,7 @@ pub enum ResolverError {
     #[error("Duplicate field in constructor")]
     DuplicateField { field: Ident },
     #[error("No such field in struct")]
-    NoSuchField { field: Ident, struct_definition: Ident },
+    NoSuchField { field: Ident, struct_definition: Ident, suggestion: Option<String> },
     #[error("Missing fields from struct")]
     MissingFields { span: Span, missing_fields: Vec<String>, struct_definition: Ident },
     #[error("Unneeded 'mut', pattern is already marked as mutable")]
@@ -80,12 +83,79 @@ pub enum ResolverError {
     PrivateFunctionCalled { name: String, span: Span },
     #[error("Only sized types may be used in the entry point to a program")]
     InvalidTypeForEntryPoint { span: Span },
+    #[error("{name} is not a const fn and cannot be evaluated at compile time")]
+    CallToNonConstFunction { name: String, span: Span },
+    #[error("Evaluating {name} at compile time exceeded the recursion limit")]
+    ConstEvaluationTooDeep { name: String, span: Span },
+    #[error("{value} does not fit into `{typ}`")]
+    ConstValueOutOfRange { value: u128, typ: Type, span: Span },
+    #[error("use of deprecated {kind} {name}")]
+    UseOfDeprecated { kind: &'static str, name: String, note: Option<String>, span: Span },
+    #[error("`loop` is only allowed in unconstrained functions")]
+    LoopInConstrainedFunction { span: Span },
+    #[error("`break` is only allowed within a `loop`")]
+    BreakOutsideOfLoop { span: Span },
+    #[error("Mutable global {name} can only be used in unconstrained functions")]
+    MutableGlobalInConstrainedCode { name: String, span: Span },
+    #[error("Environment variable {name} is not set")]
+    EnvironmentVariableNotSet { name: String, span: Span },
+    #[error("Could not read included file {path}")]
+    IncludedFileNotFound { path: String, span: Span },
+    #[error("Included file {path} is not valid UTF-8")]
+    IncludedFileNotUtf8 { path: String, span: Span },
 }
 
 impl ResolverError {
     pub fn into_file_diagnostic(self, file: fm::FileId) -> FileDiagnostic {
         Diagnostic::from(self).in_file(file)
     }
+
+    pub fn code(&self) -> ErrorCode {
+        match self {
+            ResolverError::DuplicateDefinition { .. } => E0200,
+            ResolverError::UnusedVariable { .. } => E0201,
+            ResolverError::VariableNotDeclared { .. } => E0202,
+            ResolverError::PathResolutionError(error) => error.code(),
+            ResolverError::ParserError(error) => error.code(),
+            ResolverError::ContractFunctionTypeInNormalFunction { .. }
+            | ResolverError::ContractFunctionInternalInNormalFunction { .. } => E0222,
+            ResolverError::PathIsNotIdent { .. } | ResolverError::Expected { .. } => E0204,
+            ResolverError::MissingFields { .. } => E0205,
+            ResolverError::NoSuchField { .. } => E0223,
+            ResolverError::DuplicateField { .. } => E0224,
+            ResolverError::NonStructUsedInConstructor { .. } => E0225,
+            ResolverError::MutableReferenceToImmutableVariable { .. }
+            | ResolverError::MutableReferenceToArrayElement { .. } => E0206,
+            ResolverError::UnnecessaryMut { .. } => E0226,
+            ResolverError::CapturedMutableVariable { .. } => E0227,
+            ResolverError::UnnecessaryPub { .. } => E0207,
+            ResolverError::NecessaryPub { .. } => E0228,
+            ResolverError::DistinctNotAllowed { .. } => E0229,
+            ResolverError::InvalidTypeForEntryPoint { .. } => E0230,
+            ResolverError::MissingRhsExpr { .. } => E0208,
+            ResolverError::InvalidArrayLengthExpr { .. } => E0209,
+            ResolverError::IntegerTooLarge { .. } => E0231,
+            ResolverError::NoSuchNumericTypeVariable { .. } => E0232,
+            ResolverError::CallToNonConstFunction { .. } => E0233,
+            ResolverError::ConstEvaluationTooDeep { .. } => E0234,
+            ResolverError::ConstValueOutOfRange { .. } => E0235,
+            ResolverError::TestFunctionHasParameters { .. } => E0210,
+            ResolverError::NonStructWithGenerics { .. }
+            | ResolverError::IncorrectGenericCount { .. } => E0211,
+            ResolverError::GenericsOnSelfType { .. } => E0236,
+            ResolverError::NumericConstantInFormatString { .. } => E0212,
+            ResolverError::InvalidClosureEnvironment { .. } => E0213,
+            ResolverError::PrivateFunctionCalled { .. } => E0214,
+            ResolverError::UseOfDeprecated { .. } => E0215,
+            ResolverError::LoopInConstrainedFunction { .. } => E0216,
+            ResolverError::BreakOutsideOfLoop { .. } => E0237,
+            ResolverError::MutableGlobalInConstrainedCode { .. } => E0217,
+            ResolverError::EnvironmentVariableNotSet { .. } => E0219,
+            ResolverError::IncludedFileNotFound { .. } => E0220,
+            ResolverError::IncludedFileNotUtf8 { .. } => E0238,
+            ResolverError::ShadowedVariable { .. } => E0239,
+        }
+    }
 }
 
 impl From<ResolverError> for Diagnostic {
@@ -93,7 +163,8 @@ impl From<ResolverError> for Diagnostic {
     /// ICEs will make the compiler panic, as they could affect the
     /// soundness of the generated program
     fn from(error: ResolverError) -> Diagnostic {
-        match error {
+        let code = error.code();
+        let diagnostic = match error {
             ResolverError::DuplicateDefinition { name, first_span, second_span } => {
                 let mut diag = Diagnostic::simple_error(
                     format!("duplicate definitions of {name} found"),
@@ -111,12 +182,28 @@ impl From<ResolverError> for Diagnostic {
                     "unused variable ".to_string(),
                     ident.span(),
                 )
+                .with_lint(lints::UNUSED_VARIABLES)
+            }
+            ResolverError::ShadowedVariable { name, first_span, second_span } => {
+                let mut diag = Diagnostic::simple_warning(
+                    format!("`{name}` shadows an earlier binding"),
+                    format!("this `let` shadows `{name}`"),
+                    second_span,
+                );
+                diag.add_secondary(format!("`{name}` was first bound here"), first_span);
+                diag.with_lint(lints::SHADOWED_VARIABLES)
+            }
+            ResolverError::VariableNotDeclared { name, span, suggestion } => {
+                let mut diag = Diagnostic::simple_error(
+                    format!("cannot find `{name}` in this scope "),
+                    "not found in this scope".to_string(),
+                    span,
+                );
+                if let Some(suggestion) = suggestion {
+                    diag.add_note(format!("help: did you mean `{suggestion}`?"));
+                }
+                diag
             }
-            ResolverError::VariableNotDeclared { name, span } => Diagnostic::simple_error(
-                format!("cannot find `{name}` in this scope "),
-                "not found in this scope".to_string(),
-                span,
-            ),
             ResolverError::PathIsNotIdent { span } => Diagnostic::simple_error(
                 "cannot use path as an identifier".to_string(),
                 String::new(),
@@ -133,7 +220,7 @@ impl From<ResolverError> for Diagnostic {
                 String::new(),
                 field.span(),
             ),
-            ResolverError::NoSuchField { field, struct_definition } => {
+            ResolverError::NoSuchField { field, struct_definition, suggestion } => {
                 let mut error = Diagnostic::simple_error(
                     format!("no such field {field} defined in struct {struct_definition}"),
                     String::new(),
@@ -144,6 +231,9 @@ impl From<ResolverError> for Diagnostic {
                     format!("{struct_definition} defined here with no {field} field"),
                     struct_definition.span(),
                 );
+                if let Some(suggestion) = suggestion {
+                    error.add_note(format!("help: did you mean `{suggestion}`?"));
+                }
                 error
             }
             ResolverError::MissingFields { span, missing_fields, struct_definition } => {
@@ -184,7 +274,7 @@ impl From<ResolverError> for Diagnostic {
                 );
 
                 diag.add_note("The `pub` keyword only has effects on arguments to the entry-point function of a program. Thus, adding it to other function parameters can be deceiving and should be removed".to_owned());
-                diag
+                diag.with_lint(lints::UNNECESSARY_PUB)
             }
             ResolverError::NecessaryPub { ident } => {
                 let name = &ident.0.contents;
@@ -299,6 +389,61 @@ impl From<ResolverError> for Diagnostic {
             ResolverError::InvalidTypeForEntryPoint { span } => Diagnostic::simple_error(
                 "Only sized types may be used in the entry point to a program".to_string(),
                 "Slices, references, or any type containing them may not be used in main or a contract function".to_string(), span),
-        }
+            ResolverError::CallToNonConstFunction { name, span } => Diagnostic::simple_error(
+                format!("{name} is not a const fn and cannot be evaluated at compile time"),
+                format!("Mark {name} as `const fn` to use it in an array length"),
+                span,
+            ),
+            ResolverError::ConstEvaluationTooDeep { name, span } => Diagnostic::simple_error(
+                format!("Evaluating {name} at compile time exceeded the recursion limit"),
+                "const fn calls may only be nested 128 deep".to_string(),
+                span,
+            ),
+            ResolverError::ConstValueOutOfRange { value, typ, span } => Diagnostic::simple_error(
+                format!("{value} does not fit into `{typ}`"),
+                format!("This is evaluated at compile time and must fit into `{typ}`"),
+                span,
+            ),
+            ResolverError::UseOfDeprecated { kind, name, note, span } => Diagnostic::simple_warning(
+                format!("use of deprecated {kind} {name}"),
+                note.unwrap_or_default(),
+                span,
+            )
+            .with_lint(lints::DEPRECATED),
+            ResolverError::LoopInConstrainedFunction { span } => Diagnostic::simple_error(
+                "`loop` is only allowed in unconstrained functions".into(),
+                "Constrained code must know how many times each loop runs, try a `for` loop".into(),
+                span,
+            ),
+            ResolverError::BreakOutsideOfLoop { span } => Diagnostic::simple_error(
+                "`break` is only allowed within a `loop`".into(),
+                "`for` loops always run to the end of their range".into(),
+                span,
+            ),
+            ResolverError::MutableGlobalInConstrainedCode { name, span } => {
+                let primary =
+                    format!("Mutable global `{name}` can only be used in unconstrained functions");
+                let secondary =
+                    "Constrained code, lambdas and compile-time code can't use mutable globals";
+                Diagnostic::simple_error(primary, secondary.into(), span)
+            }
+            ResolverError::EnvironmentVariableNotSet { name, span } => Diagnostic::simple_error(
+                format!("Environment variable `{name}` is not set"),
+                "`std::env!` reads environment variables while the program is compiled".into(),
+                span,
+            ),
+            ResolverError::IncludedFileNotFound { path, span } => Diagnostic::simple_error(
+                format!("Could not read included file `{path}`"),
+                "Included files are relative to the directory of the current file".into(),
+                span,
+            ),
+            ResolverError::IncludedFileNotUtf8 { path, span } => Diagnostic::simple_error(
+                format!("Included file `{path}` is not valid UTF-8"),
+                "`std::include_str!` can only include text, use `std::include_bytes!` instead"
+                    .into(),
+                span,
+            ),
+        };
+        diagnostic.with_code(code)
     }
 }
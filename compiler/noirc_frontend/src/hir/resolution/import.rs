@@ -1,5 +1,6 @@
 use iter_extended::partition_results;
-use noirc_errors::CustomDiagnostic;
+use noirc_errors::codes::*;
+use noirc_errors::{CustomDiagnostic, ErrorCode, Span};
 
 use crate::graph::CrateId;
 use std::collections::BTreeMap;
@@ -12,6 +13,7 @@ pub struct ImportDirective {
     pub module_id: LocalModuleId,
     pub path: Path,
     pub alias: Option<Ident>,
+    pub statement_span: Option<Span>,
 }
 
 pub type PathResolution = Result<PerNs, PathResolutionError>;
@@ -22,6 +24,15 @@ pub enum PathResolutionError {
     ExternalContractUsed(Ident),
 }
 
+impl PathResolutionError {
+    pub fn code(&self) -> ErrorCode {
+        match self {
+            PathResolutionError::Unresolved(_) => E0202,
+            PathResolutionError::ExternalContractUsed(_) => E0203,
+        }
+    }
+}
+
 #[derive(Debug)]
 pub struct ResolvedImport {
     // name of the namespace, either last path segment or an alias
@@ -30,11 +41,14 @@ pub struct ResolvedImport {
     pub resolved_namespace: PerNs,
     // The module which we must add the resolved namespace to
     pub module_scope: LocalModuleId,
+    // The span of the `use` statement, if this is its only import
+    pub statement_span: Option<Span>,
 }
 
 impl From<PathResolutionError> for CustomDiagnostic {
     fn from(error: PathResolutionError) -> Self {
-        match error {
+        let code = error.code();
+        let diagnostic = match error {
             PathResolutionError::Unresolved(ident) => CustomDiagnostic::simple_error(
                 format!("Could not resolve '{ident}' in path"),
                 String::new(),
@@ -45,7 +59,8 @@ impl From<PathResolutionError> for CustomDiagnostic {
                 "Contracts may only be referenced from within a contract".to_string(),
                 ident.span(),
             ),
-        }
+        };
+        diagnostic.with_code(code)
     }
 }
 
@@ -66,7 +81,8 @@ pub fn resolve_imports(
                 .map_err(|error| (error, module_scope))?;
 
         let name = resolve_path_name(&import_directive);
-        Ok(ResolvedImport { name, resolved_namespace, module_scope })
+        let statement_span = import_directive.statement_span;
+        Ok(ResolvedImport { name, resolved_namespace, module_scope, statement_span })
     })
 }
 
@@ -97,17 +113,27 @@ pub fn resolve_path_to_ns(
         crate::ast::PathKind::Plain => {
             // Plain paths are only used to import children modules. It's possible to allow import of external deps, but maybe this distinction is better?
             // In Rust they can also point to external Dependencies, if no children can be found with the specified name
-            resolve_name_in_module(
-                def_map,
-                import_path,
-                import_directive.module_id,
-                def_maps,
-                allow_contracts,
-            )
+            let scope = plain_path_scope(def_map, import_directive.module_id, import_path);
+            let def_map =
+                if scope.krate == def_map.krate { def_map } else { &def_maps[&scope.krate] };
+            resolve_name_in_module(def_map, import_path, scope.local_id, def_maps, allow_contracts)
         }
     }
 }
 
+/// The module a plain path is resolved from when written in `module`: that module, unless it
+/// has no item named by the path's first segment, in which case it's the crate's prelude.
+fn plain_path_scope(def_map: &CrateDefMap, module: LocalModuleId, path: &[Ident]) -> ModuleId {
+    match (def_map.prelude, path.first()) {
+        (Some(prelude), Some(first_segment))
+            if def_map.modules[module.0].find_name(first_segment).is_none() =>
+        {
+            prelude
+        }
+        _ => ModuleId { krate: def_map.krate, local_id: module },
+    }
+}
+
 fn resolve_path_from_crate_root(
     def_map: &CrateDefMap,
     import_path: &[Ident],
@@ -175,6 +201,45 @@ fn resolve_name_in_module(
     Ok(current_ns)
 }
 
+/// Returns each segment of `path` along with the module it is looked up in when resolving the
+/// path from `module_id`. This tells which names, including imported ones, the path makes use of.
+pub fn path_segment_scopes(
+    def_maps: &BTreeMap<CrateId, CrateDefMap>,
+    module_id: ModuleId,
+    path: &Path,
+) -> Vec<(ModuleId, Ident)> {
+    let def_map = &def_maps[&module_id.krate];
+    let (mut current_mod, segments) = match path.kind {
+        PathKind::Crate => {
+            (ModuleId { krate: module_id.krate, local_id: def_map.root }, &path.segments[..])
+        }
+        PathKind::Dep => match path
+            .segments
+            .first()
+            .and_then(|name| def_map.extern_prelude.get(&name.0.contents))
+        {
+            Some(dep_module) => (*dep_module, &path.segments[1..]),
+            None => return Vec::new(),
+        },
+        PathKind::Plain => {
+            (plain_path_scope(def_map, module_id.local_id, &path.segments), &path.segments[..])
+        }
+    };
+
+    let mut scopes = Vec::new();
+    for segment in segments {
+        scopes.push((current_mod, segment.clone()));
+
+        current_mod = match current_mod.module(def_maps).find_name(segment).take_types() {
+            Some(ModuleDefId::ModuleId(id)) => id,
+            Some(ModuleDefId::TypeId(id)) => id.module_id(),
+            Some(ModuleDefId::TraitId(id)) => id.0,
+            _ => break,
+        };
+    }
+    scopes
+}
+
 fn resolve_path_name(import_directive: &ImportDirective) -> Ident {
     match &import_directive.alias {
         None => import_directive.path.segments.last().unwrap().clone(),
@@ -202,11 +267,13 @@ fn resolve_external_dep(
     // Create an import directive for the dependency crate
     let path_without_crate_name = &path[1..]; // XXX: This will panic if the path is of the form `use dep::std` Ideal algorithm will not distinguish between crate and module
 
-    let path = Path { segments: path_without_crate_name.to_vec(), kind: PathKind::Plain };
-    let dep_directive =
-        ImportDirective { module_id: dep_module.local_id, path, alias: directive.alias.clone() };
-
+    // Paths into a dependency don't see the dependency's prelude
     let dep_def_map = def_maps.get(&dep_module.krate).unwrap();
-
-    resolve_path_to_ns(&dep_directive, dep_def_map, def_maps, allow_contracts)
+    resolve_name_in_module(
+        dep_def_map,
+        path_without_crate_name,
+        dep_module.local_id,
+        def_maps,
+        allow_contracts,
+    )
 }
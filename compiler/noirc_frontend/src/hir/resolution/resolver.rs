@@ -19,9 +19,10 @@ use crate::hir_def::expr::{
 };
 
 use crate::hir_def::traits::{Trait, TraitConstraint};
-use crate::token::FunctionAttribute;
+use crate::lexer::Lexer;
+use crate::token::{FunctionAttribute, SecondaryAttribute, SpannedToken, Token};
 use regex::Regex;
-use std::collections::{BTreeMap, HashSet};
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::rc::Rc;
 
 use crate::graph::CrateId;
@@ -32,16 +33,18 @@ use crate::node_interner::{
 };
 use crate::{
     hir::{def_map::CrateDefMap, resolution::path_resolver::PathResolver},
-    BlockExpression, Expression, ExpressionKind, FunctionKind, Ident, Literal, NoirFunction,
-    StatementKind,
+    AssertMessage, BlockExpression, ConstrainKind, ConstrainStatement, ConstructorExpression,
+    Expression, ExpressionKind, FunctionKind, Ident, InfixExpression, Literal, NoirFunction,
+    RangeExpression, Statement, StatementKind, StaticAssert,
 };
 use crate::{
-    ArrayLiteral, ContractFunctionType, Distinctness, Generics, LValue, NoirStruct, NoirTypeAlias,
-    Path, PathKind, Pattern, Shared, StructType, Type, TypeAliasType, TypeBinding, TypeVariable,
-    UnaryOp, UnresolvedGenerics, UnresolvedTraitConstraint, UnresolvedType, UnresolvedTypeData,
+    ArrayLiteral, BinaryOpKind, BinaryTypeOperator, ContractFunctionType, Distinctness, Generics,
+    LValue, NoirStruct, NoirTypeAlias, Path, PathKind, Pattern, Shared, Signedness, StructType,
+    Type, TypeAliasType, TypeBinding, TypeVariable, UnaryOp, UnresolvedGenerics,
+    UnresolvedNumericConstraint, UnresolvedTraitConstraint, UnresolvedType, UnresolvedTypeData,
     UnresolvedTypeExpression, Visibility, ERROR_IDENT,
 };
-use fm::FileId;
+use fm::{FileId, FileManager};
 use iter_extended::vecmap;
 use noirc_errors::{Location, Span, Spanned};
 
@@ -49,14 +52,55 @@ use crate::hir::scope::{
     Scope as GenericScope, ScopeForest as GenericScopeForest, ScopeTree as GenericScopeTree,
 };
 use crate::hir_def::{
-    function::{FuncMeta, HirFunction},
-    stmt::{HirConstrainStatement, HirLetStatement, HirStatement},
+    function::{FuncMeta, HirFunction, NumericConstraint},
+    stmt::{HirAssertMessage, HirConstrainStatement, HirLetStatement, HirStatement},
 };
 
 use super::errors::{PubPosition, ResolverError};
+use super::import::path_segment_scopes;
+use super::suggestions::find_similar_name;
 
 const SELF_TYPE_NAME: &str = "Self";
 
+/// How many nested `const fn` calls may be evaluated when computing an array length
+const MAX_CONST_CALL_DEPTH: usize = 128;
+
+/// An integer evaluated at compile time, along with the type it was declared with. Literals and
+/// arithmetic on them have no type until they are used where a type is declared.
+#[derive(Debug, Clone)]
+struct ConstValue {
+    value: u128,
+    typ: Option<Type>,
+}
+
+impl ConstValue {
+    fn bool(value: bool) -> ConstValue {
+        ConstValue { value: value as u128, typ: Some(Type::Bool) }
+    }
+
+    /// Gives the value the type `typ`, failing if it is out of the range of that type
+    fn with_type(self, typ: Type, span: Span) -> Result<ConstValue, Option<ResolverError>> {
+        let in_range = match &typ {
+            Type::FieldElement => true,
+            Type::Integer(Signedness::Unsigned, bits) => {
+                *bits >= u128::BITS || self.value < 1 << bits
+            }
+            Type::Integer(Signedness::Signed, bits) => {
+                *bits > u128::BITS || self.value < 1 << (bits - 1)
+            }
+            Type::Bool => self.value <= 1,
+            // Other types can't be checked, such as generics which are only known once the
+            // function is type checked, or the types of globals declared without one
+            _ => return Ok(self),
+        };
+        if in_range {
+            Ok(ConstValue { value: self.value, typ: Some(typ) })
+        } else {
+            Err(Some(ResolverError::ConstValueOutOfRange { value: self.value, typ, span }))
+        }
+    }
+}
+
 type Scope = GenericScope<String, ResolverMeta>;
 type ScopeTree = GenericScopeTree<String, ResolverMeta>;
 type ScopeForest = GenericScopeForest<String, ResolverMeta>;
@@ -97,6 +141,25 @@ pub struct Resolver<'a> {
     /// that are captured. We do this in order to create the hidden environment
     /// parameter for the lambda function.
     lambda_stack: Vec<LambdaContext>,
+
+    /// The function being resolved, if any. Functions referenced while resolving
+    /// it are recorded as its dependencies in the call graph.
+    current_function: Option<FuncId>,
+
+    /// True while resolving code which is evaluated at compile time: the body of a
+    /// `comptime fn`, a `comptime` block, or the arguments of a call to a `comptime fn`.
+    in_comptime: bool,
+
+    /// True while resolving an unconstrained function, in which `loop` may be used
+    in_unconstrained_function: bool,
+
+    /// True while resolving the block of a `loop`, outside of any `for` loop or lambda
+    /// nested in it, which `break` exits
+    in_loop: bool,
+
+    /// Reads the files included by `std::include_bytes!` and `std::include_str!`. Only set when
+    /// resolving items which can contain expressions.
+    file_manager: Option<&'a FileManager>,
 }
 
 /// ResolverMetas are tagged onto each definition to track how many times they are used
@@ -130,10 +193,19 @@ impl<'a> Resolver<'a> {
             generics: Vec::new(),
             errors: Vec::new(),
             lambda_stack: Vec::new(),
+            current_function: None,
+            in_comptime: false,
+            in_unconstrained_function: false,
+            in_loop: false,
+            file_manager: None,
             file,
         }
     }
 
+    pub fn set_file_manager(&mut self, file_manager: &'a FileManager) {
+        self.file_manager = Some(file_manager);
+    }
+
     pub fn set_self_type(&mut self, self_type: Option<Type>) {
         self.self_type = self_type;
     }
@@ -160,7 +232,11 @@ impl<'a> Resolver<'a> {
         func: NoirFunction,
         func_id: FuncId,
     ) -> (HirFunction, FuncMeta, Vec<ResolverError>) {
+        self.interner.cancellation_token().check();
         self.scopes.start_function();
+        self.current_function = Some(func_id);
+        self.in_comptime = func.def.is_comptime;
+        self.in_unconstrained_function = func.def.is_unconstrained || func.def.is_open;
 
         // Check whether the function has globals in the local module and add them to the scope
         self.resolve_local_globals();
@@ -230,7 +306,7 @@ impl<'a> Resolver<'a> {
         definition: DefinitionKind,
     ) -> HirIdent {
         if definition.is_global() {
-            return self.add_global_variable_decl(name, definition);
+            return self.add_global_variable_decl(name, mutable, definition);
         }
 
         let id = self.interner.push_definition(name.0.contents.clone(), mutable, definition);
@@ -254,7 +330,12 @@ impl<'a> Resolver<'a> {
         ident
     }
 
-    fn add_global_variable_decl(&mut self, name: Ident, definition: DefinitionKind) -> HirIdent {
+    fn add_global_variable_decl(
+        &mut self,
+        name: Ident,
+        mutable: bool,
+        definition: DefinitionKind,
+    ) -> HirIdent {
         let scope = self.scopes.get_mut_scope();
         let ident;
         let resolver_meta;
@@ -277,7 +358,7 @@ impl<'a> Resolver<'a> {
             ident = hir_let_stmt.ident();
             resolver_meta = ResolverMeta { num_times_used: 0, ident, warn_if_unused: true };
         } else {
-            let id = self.interner.push_definition(name.0.contents.clone(), false, definition);
+            let id = self.interner.push_definition(name.0.contents.clone(), mutable, definition);
             let location = Location::new(name.span(), self.file);
             ident = HirIdent { location, id };
             resolver_meta = ResolverMeta { num_times_used: 0, ident, warn_if_unused: true };
@@ -303,6 +384,7 @@ impl<'a> Resolver<'a> {
     // is returned, for better error reporting UX
     fn find_variable_or_default(&mut self, name: &Ident) -> (HirIdent, usize) {
         self.find_variable(name).unwrap_or_else(|error| {
+            let error = self.suggest_similar_variable(error);
             self.push_err(error);
             let id = DefinitionId::dummy_id();
             let location = Location::new(name.span(), self.file);
@@ -324,10 +406,35 @@ impl<'a> Resolver<'a> {
             Err(ResolverError::VariableNotDeclared {
                 name: name.0.contents.clone(),
                 span: name.0.span(),
+                suggestion: None,
             })
         }
     }
 
+    /// Attaches a "did you mean" suggestion to a `VariableNotDeclared` error. This is done
+    /// separately from `find_variable` since most failed lookups are retried as globals.
+    fn suggest_similar_variable(&self, error: ResolverError) -> ResolverError {
+        match error {
+            ResolverError::VariableNotDeclared { name, span, suggestion: None } => {
+                let suggestion = self.find_similar_value_name(&name);
+                ResolverError::VariableNotDeclared { name, span, suggestion }
+            }
+            other => other,
+        }
+    }
+
+    /// Finds a local variable, function or global visible from the current scope with a
+    /// name similar to `name`.
+    fn find_similar_value_name(&self, name: &str) -> Option<String> {
+        let scopes = self.scopes.0.last().into_iter().flat_map(|tree| tree.0.iter());
+        let locals = scopes.flat_map(|scope| scope.0.keys());
+
+        let module = self.path_resolver.module_id().module(self.def_maps);
+        let values = module.value_names().map(|ident| &ident.0.contents);
+
+        find_similar_name(name, locals.chain(values).map(String::as_str))
+    }
+
     fn intern_function(&mut self, func: NoirFunction, id: FuncId) -> (HirFunction, FuncMeta) {
         let func_meta = self.extract_meta(&func, id);
         let hir_func = match func.kind {
@@ -377,6 +484,7 @@ impl<'a> Resolver<'a> {
             Error => Type::Error,
             Named(path, args) => self.resolve_named_type(path, args, new_variables),
             TraitAsType(path, args) => self.resolve_trait_as_type(path, args, new_variables),
+            TraitObject(path) => self.resolve_trait_object(path),
 
             Tuple(fields) => {
                 Type::Tuple(vecmap(fields, |field| self.resolve_type_inner(field, new_variables)))
@@ -494,6 +602,14 @@ impl<'a> Resolver<'a> {
         }
     }
 
+    fn resolve_trait_object(&mut self, path: Path) -> Type {
+        if let Some(t) = self.lookup_trait_or_error(path) {
+            Type::TraitObject(t)
+        } else {
+            Type::Error
+        }
+    }
+
     fn verify_generics_count(
         &mut self,
         expected_count: usize,
@@ -523,6 +639,7 @@ impl<'a> Resolver<'a> {
         }
 
         // If we cannot find a local generic of the same name, try to look up a global
+        self.record_path_use(path);
         match self.path_resolver.resolve(self.def_maps, path.clone()) {
             Ok(ModuleDefId::GlobalId(id)) => {
                 Some(Type::Constant(self.eval_global_as_array_length(id)))
@@ -561,7 +678,6 @@ impl<'a> Resolver<'a> {
             }
             UnresolvedTypeExpression::Constant(int, _) => Type::Constant(int),
             UnresolvedTypeExpression::BinaryOperation(lhs, op, rhs, _) => {
-                let (lhs_span, rhs_span) = (lhs.span(), rhs.span());
                 let lhs = self.convert_expression_type(*lhs);
                 let rhs = self.convert_expression_type(*rhs);
 
@@ -569,17 +685,41 @@ impl<'a> Resolver<'a> {
                     (Type::Constant(lhs), Type::Constant(rhs)) => {
                         Type::Constant(op.function()(lhs, rhs))
                     }
-                    (lhs, _) => {
-                        let span =
-                            if !matches!(lhs, Type::Constant(_)) { lhs_span } else { rhs_span };
-                        self.push_err(ResolverError::InvalidArrayLengthExpr { span });
-                        Type::Constant(0)
-                    }
+                    // Expressions over numeric generics are evaluated once the generics are known
+                    (lhs, rhs) => Type::InfixExpr(Box::new(lhs), op, Box::new(rhs)),
                 }
             }
         }
     }
 
+    /// The length of a comprehension is part of its type, so its range must be made of the
+    /// same constants, globals and generics as an array-length type.
+    fn resolve_comprehension_length(&mut self, start: &Expression, end: &Expression) -> Type {
+        let start = self.resolve_comprehension_bound(start);
+        let end = self.resolve_comprehension_bound(end);
+
+        match (start, end) {
+            (Type::Constant(start), Type::Constant(end)) => {
+                Type::Constant(end.saturating_sub(start))
+            }
+            // `[f(i) for i in 0..N]` has the type `[T; N]` rather than `[T; N - 0]`
+            (Type::Constant(0), end) => end,
+            (start, end) => {
+                Type::InfixExpr(Box::new(end), BinaryTypeOperator::Subtraction, Box::new(start))
+            }
+        }
+    }
+
+    fn resolve_comprehension_bound(&mut self, bound: &Expression) -> Type {
+        let bound = UnresolvedTypeExpression::from_expr(bound.clone(), bound.span).unwrap_or_else(
+            |error| {
+                self.errors.push(ResolverError::ParserError(Box::new(error)));
+                UnresolvedTypeExpression::Constant(0, bound.span)
+            },
+        );
+        self.convert_expression_type(bound)
+    }
+
     fn get_ident_from_path(&mut self, path: Path) -> (HirIdent, usize) {
         let location = Location::new(path.span(), self.file);
 
@@ -588,7 +728,7 @@ impl<'a> Resolver<'a> {
             // Try to look it up as a global, but still issue the first error if we fail
             Some(Err(error)) => match self.lookup_global(path) {
                 Ok(id) => return (HirIdent { location, id }, 0),
-                Err(_) => error,
+                Err(_) => self.suggest_similar_variable(error),
             },
             None => match self.lookup_global(path) {
                 Ok(id) => return (HirIdent { location, id }, 0),
@@ -689,7 +829,8 @@ impl<'a> Resolver<'a> {
             if global_info.local_id == self.path_resolver.local_module_id() {
                 let global_stmt = self.interner.let_statement(&stmt_id);
                 let definition = DefinitionKind::Global(global_stmt.expression);
-                self.add_global_variable_decl(global_info.ident, definition);
+                let mutable = global_stmt.is_mutable();
+                self.add_global_variable_decl(global_info.ident, mutable, definition);
             }
         }
     }
@@ -700,9 +841,19 @@ impl<'a> Resolver<'a> {
         &mut self,
         where_clause: &Vec<UnresolvedTraitConstraint>,
     ) -> Vec<TraitConstraint> {
-        vecmap(where_clause, |constraint| TraitConstraint {
-            typ: self.resolve_type(constraint.typ.clone()),
-            trait_id: constraint.trait_bound.trait_id.unwrap_or_else(TraitId::dummy_id),
+        vecmap(where_clause, |constraint| {
+            self.record_path_use(&constraint.trait_bound.trait_path);
+            if let Some(trait_id) = constraint.trait_bound.trait_id {
+                self.check_trait_deprecation(trait_id, constraint.trait_bound.trait_path.span());
+            }
+            let trait_generics = vecmap(&constraint.trait_bound.trait_generics, |typ| {
+                self.resolve_type(typ.clone())
+            });
+            TraitConstraint {
+                typ: self.resolve_type(constraint.typ.clone()),
+                trait_id: constraint.trait_bound.trait_id.unwrap_or_else(TraitId::dummy_id),
+                trait_generics,
+            }
         })
     }
 
@@ -802,9 +953,22 @@ impl<'a> Resolver<'a> {
             return_distinctness: func.def.return_distinctness,
             has_body: !func.def.body.is_empty(),
             trait_constraints: self.resolve_trait_constraints(&func.def.where_clause),
+            numeric_constraints: self.resolve_numeric_constraints(&func.def.numeric_constraints),
         }
     }
 
+    fn resolve_numeric_constraints(
+        &mut self,
+        constraints: &[UnresolvedNumericConstraint],
+    ) -> Vec<NumericConstraint> {
+        vecmap(constraints, |constraint| NumericConstraint {
+            lhs: self.convert_expression_type(constraint.lhs.clone()),
+            comparison: constraint.comparison,
+            rhs: self.convert_expression_type(constraint.rhs.clone()),
+            span: constraint.span,
+        })
+    }
+
     /// True if the 'pub' keyword is allowed on parameters in this function
     fn pub_allowed(&self, func: &NoirFunction) -> bool {
         if self.in_contract() {
@@ -899,15 +1063,15 @@ impl<'a> Resolver<'a> {
             | Type::Error
             | Type::TypeVariable(_, _)
             | Type::Constant(_)
+            | Type::InfixExpr(..)
             | Type::NamedGeneric(_, _)
             | Type::NotConstant
             | Type::TraitAsType(_)
+            | Type::TraitObject(_)
             | Type::Forall(_, _) => (),
 
             Type::Array(length, element_type) => {
-                if let Type::NamedGeneric(type_variable, name) = length.as_ref() {
-                    found.insert(name.to_string(), type_variable.clone());
-                }
+                Self::find_numeric_generics_in_length(length, found);
                 Self::find_numeric_generics_in_type(element_type, found);
             }
 
@@ -936,35 +1100,180 @@ impl<'a> Resolver<'a> {
                 }
             }
             Type::MutableReference(element) => Self::find_numeric_generics_in_type(element, found),
-            Type::String(length) => {
-                if let Type::NamedGeneric(type_variable, name) = length.as_ref() {
-                    found.insert(name.to_string(), type_variable.clone());
-                }
-            }
+            Type::String(length) => Self::find_numeric_generics_in_length(length, found),
             Type::FmtString(length, fields) => {
-                if let Type::NamedGeneric(type_variable, name) = length.as_ref() {
-                    found.insert(name.to_string(), type_variable.clone());
-                }
+                Self::find_numeric_generics_in_length(length, found);
                 Self::find_numeric_generics_in_type(fields, found);
             }
         }
     }
 
+    /// Finds the numeric generics used by an array or string length, which may
+    /// be a type-level expression such as `N + 1`
+    fn find_numeric_generics_in_length(
+        length: &Type,
+        found: &mut BTreeMap<String, Shared<TypeBinding>>,
+    ) {
+        match length {
+            Type::NamedGeneric(type_variable, name) => {
+                found.insert(name.to_string(), type_variable.clone());
+            }
+            Type::InfixExpr(lhs, _, rhs) => {
+                Self::find_numeric_generics_in_length(lhs, found);
+                Self::find_numeric_generics_in_length(rhs, found);
+            }
+            _ => (),
+        }
+    }
+
+    /// Resolves a global. Its initializer is only evaluated at compile time if compile-time code
+    /// uses the global, or if it contains compile-time code itself. Otherwise it is compiled
+    /// wherever the global is used, like any other expression.
     pub fn resolve_global_let(&mut self, let_stmt: crate::LetStatement) -> HirStatement {
         let expression = self.resolve_expression(let_stmt.expression);
         let definition = DefinitionKind::Global(expression);
 
-        HirStatement::Let(HirLetStatement {
-            pattern: self.resolve_pattern(let_stmt.pattern, definition),
-            r#type: self.resolve_type(let_stmt.r#type),
-            expression,
-        })
+        let pattern = self.resolve_pattern(let_stmt.pattern, definition);
+        let r#type = self.resolve_type(let_stmt.r#type);
+
+        // Record the declared type so that the global is checked against it when it is
+        // evaluated in an array length, before the global is type checked
+        if let (HirPattern::Identifier(ident), false) = (&pattern, r#type == Type::Error) {
+            self.interner.push_definition_type(ident.id, r#type.clone());
+        }
+
+        HirStatement::Let(HirLetStatement { pattern, r#type, expression })
+    }
+
+    /// Resolves a `static_assert(condition, message)` item as the compile-time assertion
+    /// `comptime { assert(condition, message) }`, so it can only refer to globals.
+    pub fn resolve_static_assert(&mut self, assertion: StaticAssert) -> ExprId {
+        let message = Some(AssertMessage::Static(assertion.message));
+        let constrain = ConstrainStatement(assertion.condition, message, ConstrainKind::Assert);
+        let statement =
+            Statement { kind: StatementKind::Constrain(constrain), span: assertion.span };
+        let block = BlockExpression(vec![statement]);
+        self.resolve_expression(Expression::new(ExpressionKind::Comptime(block), assertion.span))
+    }
+
+    /// Resolves a call passing the format string `message` of an assertion to
+    /// `std::internals::resolve_assert_message`, which reports its values if `condition` is false.
+    /// The condition is the one already resolved for the assertion, so it is only resolved once.
+    fn resolve_assert_message(&mut self, message: Expression, condition: ExprId) -> ExprId {
+        let span = message.span;
+        let path = self.stdlib_path(&["internals", "resolve_assert_message"]);
+        let func =
+            self.resolve_expression(Expression::new(ExpressionKind::Variable(path, None), span));
+        let message = self.resolve_expression(message);
+        let location = Location::new(span, self.file);
+        let call = HirCallExpression { func, arguments: vec![message, condition], location };
+        let expr_id = self.interner.push_expr(HirExpression::Call(call));
+        self.interner.push_expr_location(expr_id, span, self.file);
+        expr_id
+    }
+
+    /// The path to an item of the standard library, which is `crate::` within the standard
+    /// library itself and `dep::std::` elsewhere.
+    fn stdlib_path(&self, segments: &[&str]) -> Path {
+        let (kind, krate) = if self.path_resolver.module_id().krate.is_stdlib() {
+            (PathKind::Crate, None)
+        } else {
+            (PathKind::Dep, Some("std"))
+        };
+        let segments = krate.into_iter().chain(segments.iter().copied()).map(Ident::from).collect();
+        Path { segments, kind }
     }
 
-    pub fn resolve_stmt(&mut self, stmt: StatementKind) -> HirStatement {
+    /// Resolves a range `start..end` into the construction of a `std::ops::Range`
+    fn resolve_range(&mut self, range: RangeExpression, span: Span) -> ExprId {
+        let type_name = self.stdlib_path(&["ops", "Range"]);
+        let fields = vec![
+            (Ident::new("start".to_string(), range.start.span), range.start),
+            (Ident::new("end".to_string(), range.end.span), range.end),
+        ];
+        let constructor = Box::new(ConstructorExpression { type_name, fields });
+        self.resolve_expression(Expression::new(ExpressionKind::Constructor(constructor), span))
+    }
+
+    /// Resolves an `assert_eq` or `assert_ne` without a message into an assertion reporting the
+    /// values it compares. They're bound to variables first so that each is only evaluated once:
+    ///
+    /// `{ let (lhs, rhs) = (a, b); assert(lhs == rhs, f"left: {lhs}, right: {rhs}"); }`
+    fn resolve_comparison_assertion(
+        &mut self,
+        condition: Expression,
+        kind: ConstrainKind,
+    ) -> HirStatement {
+        let span = condition.span;
+        let ExpressionKind::Infix(infix) = condition.kind else {
+            unreachable!("The condition of an `assert_eq` or `assert_ne` is a comparison")
+        };
+        let InfixExpression { lhs, rhs, operator } = *infix;
+        let (lhs_span, rhs_span) = (lhs.span, rhs.span);
+
+        let pattern = Pattern::Tuple(
+            vec![
+                Pattern::Identifier(Ident::new("lhs".to_string(), lhs_span)),
+                Pattern::Identifier(Ident::new("rhs".to_string(), rhs_span)),
+            ],
+            span,
+        );
+        let values = Expression::new(ExpressionKind::Tuple(vec![lhs, rhs]), span);
+        let bindings = StatementKind::new_let(((pattern, UnresolvedType::unspecified()), values));
+
+        let variable = |name: &str, span| {
+            let path = Path::from_single(name.to_string(), span);
+            Expression::new(ExpressionKind::Variable(path, None), span)
+        };
+        let comparison = Expression::new(
+            ExpressionKind::Infix(Box::new(InfixExpression {
+                lhs: variable("lhs", lhs_span),
+                rhs: variable("rhs", rhs_span),
+                operator,
+            })),
+            span,
+        );
+        let message = ExpressionKind::format_string("left: {lhs}, right: {rhs}".to_string());
+        let message = AssertMessage::Formatted(Expression::new(message, span));
+        let assertion =
+            StatementKind::Constrain(ConstrainStatement(comparison, Some(message), kind));
+
+        let block = BlockExpression(vec![
+            Statement { kind: bindings, span },
+            Statement { kind: assertion, span },
+        ]);
+        HirStatement::Semi(
+            self.resolve_expression(Expression::new(ExpressionKind::Block(block), span)),
+        )
+    }
+
+    /// Resolves a call to `std::assert_ne` into an assertion of kind `ConstrainKind::AssertNe`,
+    /// so it's checked like `assert_eq`: `{ assert(lhs != rhs, message); }`
+    fn resolve_assert_ne(&mut self, arguments: Vec<Expression>, span: Span) -> ExprId {
+        let mut arguments = arguments.into_iter();
+        let lhs = arguments.next().expect("`assert_ne` compares two values");
+        let rhs = arguments.next().expect("`assert_ne` compares two values");
+        let message = arguments.next().map(|message| match message.kind {
+            ExpressionKind::Literal(Literal::Str(string)) => AssertMessage::Static(string),
+            _ => AssertMessage::Formatted(message),
+        });
+
+        let operator = Spanned::from(span, BinaryOpKind::NotEqual);
+        let condition = Expression::new(
+            ExpressionKind::Infix(Box::new(InfixExpression { lhs, rhs, operator })),
+            span,
+        );
+        let assertion = ConstrainStatement(condition, message, ConstrainKind::AssertNe);
+        let block =
+            BlockExpression(vec![Statement { kind: StatementKind::Constrain(assertion), span }]);
+        self.resolve_expression(Expression::new(ExpressionKind::Block(block), span))
+    }
+
+    pub fn resolve_stmt(&mut self, stmt: StatementKind, span: Span) -> HirStatement {
         match stmt {
             StatementKind::Let(let_stmt) => {
                 let expression = self.resolve_expression(let_stmt.expression);
+                self.check_for_shadowing(&let_stmt.pattern);
                 let definition = DefinitionKind::Local(Some(expression));
                 HirStatement::Let(HirLetStatement {
                     pattern: self.resolve_pattern(let_stmt.pattern, definition),
@@ -972,9 +1281,21 @@ impl<'a> Resolver<'a> {
                     expression,
                 })
             }
-            StatementKind::Constrain(constrain_stmt) => {
-                let expr_id = self.resolve_expression(constrain_stmt.0);
-                let assert_message = constrain_stmt.1;
+            StatementKind::Constrain(ConstrainStatement(
+                condition,
+                None,
+                kind @ (ConstrainKind::AssertEq | ConstrainKind::AssertNe),
+            )) if self.interner.report_assert_values() => {
+                self.resolve_comparison_assertion(condition, kind)
+            }
+            StatementKind::Constrain(ConstrainStatement(condition, message, _)) => {
+                let expr_id = self.resolve_expression(condition);
+                let assert_message = message.map(|message| match message {
+                    AssertMessage::Static(message) => HirAssertMessage::Static(message),
+                    AssertMessage::Formatted(message) => {
+                        HirAssertMessage::Dynamic(self.resolve_assert_message(message, expr_id))
+                    }
+                });
                 HirStatement::Constrain(HirConstrainStatement(expr_id, self.file, assert_message))
             }
             StatementKind::Expression(expr) => {
@@ -1001,17 +1322,51 @@ impl<'a> Resolver<'a> {
                         true,
                         DefinitionKind::Local(None),
                     );
-                    (decl, this.resolve_expression(block))
+                    let was_in_loop = std::mem::replace(&mut this.in_loop, false);
+                    let block = this.resolve_expression(block);
+                    this.in_loop = was_in_loop;
+                    (decl, block)
                 });
 
                 HirStatement::For(HirForStatement { start_range, end_range, block, identifier })
             }
+            StatementKind::Loop(block) => {
+                if !self.in_unconstrained_code() {
+                    self.push_err(ResolverError::LoopInConstrainedFunction { span });
+                }
+                let was_in_loop = std::mem::replace(&mut self.in_loop, true);
+                let block = self.resolve_expression(block);
+                self.in_loop = was_in_loop;
+                HirStatement::Loop(block)
+            }
+            StatementKind::Break => {
+                if !self.in_loop {
+                    self.push_err(ResolverError::BreakOutsideOfLoop { span });
+                }
+                HirStatement::Break
+            }
             StatementKind::Error => HirStatement::Error,
         }
     }
 
-    pub fn intern_stmt(&mut self, stmt: StatementKind) -> StmtId {
-        let hir_stmt = self.resolve_stmt(stmt);
+    /// Whether the code being resolved is compiled to unconstrained code, which can use `loop`
+    /// and mutable globals. Lambdas and compile-time code never are.
+    fn in_unconstrained_code(&self) -> bool {
+        self.in_unconstrained_function && self.lambda_stack.is_empty() && !self.in_comptime
+    }
+
+    /// Reports a use of a mutable global outside of unconstrained code
+    fn check_mutable_global_use(&mut self, ident: HirIdent) {
+        let definition = self.interner.definition(ident.id);
+        if definition.is_global() && definition.mutable && !self.in_unconstrained_code() {
+            let name = definition.name.clone();
+            let span = ident.location.span;
+            self.push_err(ResolverError::MutableGlobalInConstrainedCode { name, span });
+        }
+    }
+
+    pub fn intern_stmt(&mut self, stmt: Statement) -> StmtId {
+        let hir_stmt = self.resolve_stmt(stmt.kind, stmt.span);
         self.interner.push_stmt(hir_stmt)
     }
 
@@ -1020,6 +1375,9 @@ impl<'a> Resolver<'a> {
             LValue::Ident(ident) => {
                 let ident = self.find_variable_or_default(&ident);
                 self.resolve_local_variable(ident.0, ident.1);
+                if ident.0.id != DefinitionId::dummy_id() {
+                    self.check_mutable_global_use(ident.0);
+                }
 
                 HirLValue::Ident(ident.0, Type::Error)
             }
@@ -1107,7 +1465,19 @@ impl<'a> Resolver<'a> {
     }
 
     pub fn resolve_expression(&mut self, expr: Expression) -> ExprId {
+        // Whether this is the outermost expression to be evaluated at compile time
+        let mut is_comptime_root = false;
+
         let hir_expr = match expr.kind {
+            // Character literals are sugar for their ASCII code as a `u8`
+            ExpressionKind::Literal(Literal::Char(byte)) => {
+                let code =
+                    Expression::new(ExpressionKind::integer((byte as u128).into()), expr.span);
+                HirExpression::Cast(HirCastExpression {
+                    lhs: self.resolve_expression(code),
+                    r#type: Type::Integer(Signedness::Unsigned, 8),
+                })
+            }
             ExpressionKind::Literal(literal) => HirExpression::Literal(match literal {
                 Literal::Bool(b) => HirLiteral::Bool(b),
                 Literal::Array(ArrayLiteral::Standard(elements)) => {
@@ -1128,12 +1498,41 @@ impl<'a> Resolver<'a> {
 
                     HirLiteral::Array(HirArrayLiteral::Repeated { repeated_element, length })
                 }
-                Literal::Integer(integer) => HirLiteral::Integer(integer),
+                Literal::Array(ArrayLiteral::Comprehension {
+                    element,
+                    identifier,
+                    start_range,
+                    end_range,
+                }) => {
+                    let length = self.resolve_comprehension_length(&start_range, &end_range);
+                    let start = self.resolve_expression(*start_range);
+                    let end = self.resolve_expression(*end_range);
+
+                    let (identifier, element) = self.in_new_scope(|this| {
+                        let decl = this.add_variable_decl(
+                            identifier,
+                            false,
+                            true,
+                            DefinitionKind::Local(None),
+                        );
+                        (decl, this.resolve_expression(*element))
+                    });
+
+                    HirLiteral::Array(HirArrayLiteral::Comprehension {
+                        element,
+                        identifier,
+                        start,
+                        end,
+                        length,
+                    })
+                }
+                Literal::Integer(integer, negative) => HirLiteral::Integer(integer, negative),
                 Literal::Str(str) => HirLiteral::Str(str),
                 Literal::FmtStr(str) => self.resolve_fmt_str_literal(str, expr.span),
                 Literal::Unit => HirLiteral::Unit,
+                Literal::Char(_) => unreachable!("Character literals are resolved above"),
             }),
-            ExpressionKind::Variable(path) => {
+            ExpressionKind::Variable(path, generics) => {
                 if let Some(expr) = self.resolve_trait_generic_path(&path) {
                     expr
                 } else {
@@ -1146,12 +1545,13 @@ impl<'a> Resolver<'a> {
                     if hir_ident.id != DefinitionId::dummy_id() {
                         match self.interner.definition(hir_ident.id).kind {
                             DefinitionKind::Function(id) => {
+                                self.interner.add_function_reference(self.current_function, id);
                                 if self.interner.function_visibility(id) == Visibility::Private {
                                     let span = hir_ident.location.span;
                                     self.check_can_reference_private_function(id, span);
                                 }
                             }
-                            DefinitionKind::Global(_) => {}
+                            DefinitionKind::Global(_) => self.check_mutable_global_use(hir_ident),
                             DefinitionKind::GenericType(_) => {
                                 // Initialize numeric generics to a polymorphic integer type in case
                                 // they're used in expressions. We must do this here since the type
@@ -1169,7 +1569,9 @@ impl<'a> Resolver<'a> {
                         }
                     }
 
-                    HirExpression::Ident(hir_ident)
+                    let generics =
+                        generics.map(|generics| vecmap(generics, |typ| self.resolve_type(typ)));
+                    HirExpression::Ident(hir_ident, generics)
                 }
             }
             ExpressionKind::Prefix(prefix) => {
@@ -1198,7 +1600,20 @@ impl<'a> Resolver<'a> {
                 // Get the span and name of path for error reporting
                 let func = self.resolve_expression(*call_expr.func);
 
+                if self.is_stdlib_function(func, "assert_ne")
+                    && is_assert_ne_call(&call_expr.arguments)
+                {
+                    return self.resolve_assert_ne(call_expr.arguments, expr.span);
+                }
+
+                // Calls to a `comptime fn` are evaluated at compile time along with their arguments
+                is_comptime_root = !self.in_comptime && self.is_comptime_function(func);
+                let was_comptime = self.in_comptime;
+                self.in_comptime |= is_comptime_root;
+
                 let arguments = vecmap(call_expr.arguments, |arg| self.resolve_expression(arg));
+                self.in_comptime = was_comptime;
+
                 let location = Location::new(expr.span, self.file);
                 HirExpression::Call(HirCallExpression { func, arguments, location })
             }
@@ -1277,7 +1692,9 @@ impl<'a> Resolver<'a> {
                 });
 
                 let return_type = this.resolve_inferred_type(lambda.return_type);
+                let was_in_loop = std::mem::replace(&mut this.in_loop, false);
                 let body = this.resolve_expression(lambda.body);
+                this.in_loop = was_in_loop;
 
                 let lambda_context = this.lambda_stack.pop().unwrap();
 
@@ -1289,13 +1706,142 @@ impl<'a> Resolver<'a> {
                 })
             }),
             ExpressionKind::Parenthesized(sub_expr) => return self.resolve_expression(*sub_expr),
+            ExpressionKind::Range(range) => return self.resolve_range(*range, expr.span),
+            ExpressionKind::Comptime(block_expr) => {
+                is_comptime_root = !self.in_comptime;
+                let was_comptime = std::mem::replace(&mut self.in_comptime, true);
+                let block = self.resolve_block(block_expr);
+                self.in_comptime = was_comptime;
+                block
+            }
+            ExpressionKind::Env(name) => match self.interner.read_environment_variable(&name) {
+                Some(value) => HirExpression::Literal(environment_literal(value)),
+                None => {
+                    let span = expr.span;
+                    self.push_err(ResolverError::EnvironmentVariableNotSet { name, span });
+                    HirExpression::Error
+                }
+            },
+            ExpressionKind::IncludeBytes(path) => match self.read_included_file(&path, expr.span) {
+                Some(contents) => return self.resolve_included_bytes(contents, expr.span),
+                None => HirExpression::Error,
+            },
+            ExpressionKind::IncludeStr(path) => match self.read_included_file(&path, expr.span) {
+                Some(contents) => match String::from_utf8(contents) {
+                    Ok(contents) => HirExpression::Literal(HirLiteral::Str(contents)),
+                    Err(_) => {
+                        let span = expr.span;
+                        self.push_err(ResolverError::IncludedFileNotUtf8 { path, span });
+                        HirExpression::Error
+                    }
+                },
+                None => HirExpression::Error,
+            },
         };
 
         let expr_id = self.interner.push_expr(hir_expr);
         self.interner.push_expr_location(expr_id, expr.span, self.file);
+        if is_comptime_root {
+            self.interner.push_comptime_expression(expr_id);
+        }
         expr_id
     }
 
+    /// Reads the file at `path`, relative to the current file, for `std::include_bytes!` or
+    /// `std::include_str!`, recording that it was included.
+    fn read_included_file(&mut self, path: &str, span: Span) -> Option<Vec<u8>> {
+        let file = self.file;
+        match self.file_manager.and_then(|fm| fm.read_included_file(file, path).ok()) {
+            Some((resolved_path, contents)) => {
+                self.interner.record_included_file(resolved_path);
+                Some(contents)
+            }
+            None => {
+                let path = path.to_owned();
+                self.push_err(ResolverError::IncludedFileNotFound { path, span });
+                None
+            }
+        }
+    }
+
+    /// `std::include_bytes!` is resolved as an array literal of each byte cast to a `u8`.
+    fn resolve_included_bytes(&mut self, contents: Vec<u8>, span: Span) -> ExprId {
+        let u8_type = UnresolvedTypeData::Integer(Signedness::Unsigned, 8);
+        let bytes = vecmap(contents, |byte| {
+            let byte = Expression::new(ExpressionKind::integer(u128::from(byte).into()), span);
+            Expression::cast(byte, UnresolvedType::without_span(u8_type.clone()), span)
+        });
+        self.resolve_expression(Expression::new(ExpressionKind::array(bytes), span))
+    }
+
+    /// True if `func` refers directly to the function `name` in the root module of the standard
+    /// library
+    fn is_stdlib_function(&self, func: ExprId, name: &str) -> bool {
+        let ident = match self.interner.expression(&func) {
+            HirExpression::Ident(ident, _) => ident,
+            _ => return false,
+        };
+        match self.interner.try_definition(ident.id).map(|definition| &definition.kind) {
+            Some(DefinitionKind::Function(func_id)) => {
+                let module = self.interner.function_module(*func_id);
+                module.krate.is_stdlib()
+                    && module.local_id == self.def_maps[&module.krate].root()
+                    && self.interner.function_name(func_id) == name
+            }
+            _ => false,
+        }
+    }
+
+    /// True if `func` refers directly to a `comptime fn`
+    fn is_comptime_function(&self, func: ExprId) -> bool {
+        let ident = match self.interner.expression(&func) {
+            HirExpression::Ident(ident, _) => ident,
+            _ => return false,
+        };
+        match self.interner.try_definition(ident.id).map(|definition| &definition.kind) {
+            Some(DefinitionKind::Function(func_id)) => {
+                self.interner.function_modifiers(func_id).is_comptime
+            }
+            _ => false,
+        }
+    }
+
+    /// Warns about each variable bound by the `let` pattern `pattern` which shadows an earlier
+    /// binding of the current function. Globals and variables prefixed with `_` are skipped.
+    fn check_for_shadowing(&mut self, pattern: &Pattern) {
+        match pattern {
+            Pattern::Identifier(name) => {
+                let name_str = &name.0.contents;
+                if name_str.starts_with('_') || name_str == ERROR_IDENT {
+                    return;
+                }
+                let Some((earlier, _)) = self.scopes.current_scope_tree().find(name_str.as_str())
+                else {
+                    return;
+                };
+                let earlier = earlier.ident;
+                if !self.interner.definition(earlier.id).is_global() {
+                    self.push_err(ResolverError::ShadowedVariable {
+                        name: name_str.clone(),
+                        first_span: earlier.location.span,
+                        second_span: name.span(),
+                    });
+                }
+            }
+            Pattern::Mutable(pattern, _) => self.check_for_shadowing(pattern),
+            Pattern::Tuple(fields, _) => {
+                for field in fields {
+                    self.check_for_shadowing(field);
+                }
+            }
+            Pattern::Struct(_, fields, _) => {
+                for (_, field) in fields {
+                    self.check_for_shadowing(field);
+                }
+            }
+        }
+    }
+
     fn resolve_pattern(&mut self, pattern: Pattern, definition: DefinitionKind) -> HirPattern {
         self.resolve_pattern_mutable(pattern, None, definition)
     }
@@ -1391,9 +1937,14 @@ impl<'a> Resolver<'a> {
                 self.push_err(ResolverError::DuplicateField { field: field.clone() });
             } else {
                 // field not required by struct
+                let suggestion = find_similar_name(
+                    &field.0.contents,
+                    unseen_fields.iter().map(|field| field.0.contents.as_str()),
+                );
                 self.push_err(ResolverError::NoSuchField {
                     field: field.clone(),
                     struct_definition: struct_type.borrow().name.clone(),
+                    suggestion,
                 });
             }
 
@@ -1449,8 +2000,12 @@ impl<'a> Resolver<'a> {
 
     /// Lookup a given struct type by name.
     fn lookup_struct_or_error(&mut self, path: Path) -> Option<Shared<StructType>> {
+        let span = path.span();
         match self.lookup(path) {
-            Ok(struct_id) => Some(self.get_struct(struct_id)),
+            Ok(struct_id) => {
+                self.check_struct_deprecation(struct_id, span);
+                Some(self.get_struct(struct_id))
+            }
             Err(error) => {
                 self.push_err(error);
                 None
@@ -1460,8 +2015,12 @@ impl<'a> Resolver<'a> {
 
     /// Lookup a given trait by name/path.
     fn lookup_trait_or_error(&mut self, path: Path) -> Option<Trait> {
+        let span = path.span();
         match self.lookup(path) {
-            Ok(trait_id) => Some(self.get_trait(trait_id)),
+            Ok(trait_id) => {
+                self.check_trait_deprecation(trait_id, span);
+                Some(self.get_trait(trait_id))
+            }
             Err(error) => {
                 self.push_err(error);
                 None
@@ -1479,8 +2038,10 @@ impl<'a> Resolver<'a> {
             }
         }
 
+        let span = path.span();
         match self.lookup(path) {
             Ok(struct_id) => {
+                self.check_struct_deprecation(struct_id, span);
                 let struct_type = self.get_struct(struct_id);
                 let generics = struct_type.borrow().instantiate(self.interner);
                 Some(Type::Struct(struct_type, generics))
@@ -1492,6 +2053,24 @@ impl<'a> Resolver<'a> {
         }
     }
 
+    /// Issues a warning if the struct used at `span` is marked `#[deprecated]`
+    fn check_struct_deprecation(&mut self, struct_id: StructId, span: Span) {
+        let attributes = self.interner.struct_attributes(&struct_id);
+        if let Some(note) = SecondaryAttribute::get_deprecated_note(attributes) {
+            let name = self.get_struct(struct_id).borrow().name.to_string();
+            self.push_err(ResolverError::UseOfDeprecated { kind: "struct", name, note, span });
+        }
+    }
+
+    /// Issues a warning if the trait used at `span` is marked `#[deprecated]`
+    fn check_trait_deprecation(&mut self, trait_id: TraitId, span: Span) {
+        let attributes = self.interner.trait_attributes(&trait_id);
+        if let Some(note) = SecondaryAttribute::get_deprecated_note(attributes) {
+            let name = self.get_trait(trait_id).name.to_string();
+            self.push_err(ResolverError::UseOfDeprecated { kind: "trait", name, note, span });
+        }
+    }
+
     fn lookup_type_alias(&mut self, path: Path) -> Option<&TypeAliasType> {
         self.lookup(path).ok().map(|id| self.interner.get_type_alias(id))
     }
@@ -1555,13 +2134,23 @@ impl<'a> Resolver<'a> {
             .or_else(|| self.resolve_trait_method_by_named_generic(path))
     }
 
+    /// Records the names `path` refers to in each module it passes through,
+    /// so that imports which are never used can be reported
+    fn record_path_use(&mut self, path: &Path) {
+        let module_id = self.path_resolver.module_id();
+        for (module, segment) in path_segment_scopes(self.def_maps, module_id, path) {
+            self.interner.mark_name_used(module, &segment.0.contents);
+        }
+    }
+
     fn resolve_path(&mut self, path: Path) -> Result<ModuleDefId, ResolverError> {
+        self.record_path_use(&path);
         self.path_resolver.resolve(self.def_maps, path).map_err(ResolverError::PathResolutionError)
     }
 
     fn resolve_block(&mut self, block_expr: BlockExpression) -> HirExpression {
         let statements =
-            self.in_new_scope(|this| vecmap(block_expr.0, |stmt| this.intern_stmt(stmt.kind)));
+            self.in_new_scope(|this| vecmap(block_expr.0, |stmt| this.intern_stmt(stmt)));
         HirExpression::Block(HirBlockExpression(statements))
     }
 
@@ -1581,9 +2170,16 @@ impl<'a> Resolver<'a> {
 
         let length = stmt.expression;
         let span = self.interner.expr_span(&length);
-        let result = self.try_eval_array_length_id(length, span);
+        // The value of a mutable global isn't known until it's used
+        if stmt.is_mutable() {
+            self.push_err(ResolverError::InvalidArrayLengthExpr { span });
+            return 0;
+        }
+        let result = self
+            .try_eval_const_expression(length, &HashMap::new(), MAX_CONST_CALL_DEPTH, span)
+            .and_then(|value| value.with_type(stmt.r#type, span));
 
-        match result.map(|length| length.try_into()) {
+        match result.map(|length| length.value.try_into()) {
             Ok(Ok(length_value)) => return length_value,
             Ok(Err(_cast_err)) => self.push_err(ResolverError::IntegerTooLarge { span }),
             Err(Some(error)) => self.push_err(error),
@@ -1592,16 +2188,129 @@ impl<'a> Resolver<'a> {
         0
     }
 
-    fn try_eval_array_length_id(
+    /// Evaluates an integer expression at compile time. Besides integer literals this accepts
+    /// globals, arithmetic and comparisons, `if` expressions, and calls to `const fn`s, where
+    /// `arguments` holds the values of the parameters of the `const fn` being evaluated.
+    ///
+    /// Values are checked against the types declared for the parameters and return values of
+    /// `const fn`s and for globals, and arithmetic on a value of a declared type must stay
+    /// within that type.
+    fn try_eval_const_expression(
         &self,
-        rhs: ExprId,
+        expr: ExprId,
+        arguments: &HashMap<DefinitionId, ConstValue>,
+        depth: usize,
         span: Span,
-    ) -> Result<u128, Option<ResolverError>> {
-        match self.interner.expression(&rhs) {
-            HirExpression::Literal(HirLiteral::Integer(int)) => {
-                int.try_into_u128().ok_or(Some(ResolverError::IntegerTooLarge { span }))
+    ) -> Result<ConstValue, Option<ResolverError>> {
+        let eval = |expr| self.try_eval_const_expression(expr, arguments, depth, span);
+        let too_large = || Some(ResolverError::IntegerTooLarge { span });
+        let invalid = || Some(ResolverError::InvalidArrayLengthExpr { span });
+
+        match self.interner.expression(&expr) {
+            HirExpression::Literal(HirLiteral::Integer(int, false)) => {
+                let value = int.try_into_u128().ok_or_else(too_large)?;
+                Ok(ConstValue { value, typ: None })
+            }
+            HirExpression::Literal(HirLiteral::Bool(boolean)) => {
+                Ok(ConstValue { value: boolean as u128, typ: Some(Type::Bool) })
+            }
+            HirExpression::Ident(ident, _) => {
+                if let Some(value) = arguments.get(&ident.id) {
+                    return Ok(value.clone());
+                }
+                // The value of a mutable global isn't known until it's used
+                let definition = self.interner.try_definition(ident.id).filter(|def| !def.mutable);
+                match definition.map(|def| &def.kind) {
+                    Some(DefinitionKind::Global(global)) => {
+                        let value =
+                            self.try_eval_const_expression(*global, &HashMap::new(), depth, span)?;
+                        value.with_type(self.interner.id_type(ident.id), span)
+                    }
+                    _ => Err(invalid()),
+                }
+            }
+            HirExpression::Infix(infix) => {
+                let lhs = eval(infix.lhs)?;
+                let rhs = eval(infix.rhs)?;
+                let typ = lhs.typ.or(rhs.typ);
+                let (lhs, rhs) = (lhs.value, rhs.value);
+                let value = match infix.operator.kind {
+                    BinaryOpKind::Add => lhs.checked_add(rhs).ok_or_else(too_large)?,
+                    BinaryOpKind::Subtract => lhs.checked_sub(rhs).ok_or_else(invalid)?,
+                    BinaryOpKind::Multiply => lhs.checked_mul(rhs).ok_or_else(too_large)?,
+                    BinaryOpKind::Divide => lhs.checked_div(rhs).ok_or_else(invalid)?,
+                    BinaryOpKind::Modulo => lhs.checked_rem(rhs).ok_or_else(invalid)?,
+                    BinaryOpKind::ShiftLeft => u32::try_from(rhs)
+                        .ok()
+                        .and_then(|rhs| lhs.checked_shl(rhs))
+                        .ok_or_else(too_large)?,
+                    BinaryOpKind::ShiftRight => u32::try_from(rhs)
+                        .ok()
+                        .and_then(|rhs| lhs.checked_shr(rhs))
+                        .ok_or_else(invalid)?,
+                    BinaryOpKind::Equal => return Ok(ConstValue::bool(lhs == rhs)),
+                    BinaryOpKind::NotEqual => return Ok(ConstValue::bool(lhs != rhs)),
+                    BinaryOpKind::Less => return Ok(ConstValue::bool(lhs < rhs)),
+                    BinaryOpKind::LessEqual => return Ok(ConstValue::bool(lhs <= rhs)),
+                    BinaryOpKind::Greater => return Ok(ConstValue::bool(lhs > rhs)),
+                    BinaryOpKind::GreaterEqual => return Ok(ConstValue::bool(lhs >= rhs)),
+                    _ => return Err(invalid()),
+                };
+                match typ {
+                    Some(typ) => ConstValue { value, typ: None }.with_type(typ, span),
+                    None => Ok(ConstValue { value, typ: None }),
+                }
+            }
+            HirExpression::If(if_expr) => {
+                match (eval(if_expr.condition)?.value, if_expr.alternative) {
+                    (0, Some(alternative)) => eval(alternative),
+                    (0, None) => Err(invalid()),
+                    (_, _) => eval(if_expr.consequence),
+                }
             }
-            _other => Err(Some(ResolverError::InvalidArrayLengthExpr { span })),
+            HirExpression::Block(block) => match block.statements() {
+                [statement] => match self.interner.statement(statement) {
+                    HirStatement::Expression(expr) => eval(expr),
+                    _ => Err(invalid()),
+                },
+                _ => Err(invalid()),
+            },
+            HirExpression::Call(call) => {
+                let func_id = match self.interner.expression(&call.func) {
+                    HirExpression::Ident(ident, _) => {
+                        match self.interner.try_definition(ident.id).map(|def| &def.kind) {
+                            Some(DefinitionKind::Function(func_id)) => *func_id,
+                            _ => return Err(invalid()),
+                        }
+                    }
+                    _ => return Err(invalid()),
+                };
+
+                let name = self.interner.function_name(&func_id).to_owned();
+                let meta = match self.interner.try_function_meta(&func_id) {
+                    Some(meta) if self.interner.function_modifiers(&func_id).is_const => meta,
+                    _ => return Err(Some(ResolverError::CallToNonConstFunction { name, span })),
+                };
+                if depth == 0 {
+                    return Err(Some(ResolverError::ConstEvaluationTooDeep { name, span }));
+                }
+
+                let mut parameters = HashMap::new();
+                for ((pattern, typ, _), argument) in meta.parameters.0.iter().zip(call.arguments) {
+                    match pattern {
+                        HirPattern::Identifier(ident) => {
+                            let value = eval(argument)?.with_type(typ.clone(), span)?;
+                            parameters.insert(ident.id, value);
+                        }
+                        _ => return Err(invalid()),
+                    }
+                }
+
+                let body = *self.interner.function(&func_id).as_expr();
+                let value = self.try_eval_const_expression(body, &parameters, depth - 1, span)?;
+                value.with_type(meta.return_type().clone(), span)
+            }
+            _other => Err(invalid()),
         }
     }
 
@@ -1622,7 +2331,7 @@ impl<'a> Resolver<'a> {
             let variable = scope_tree.find(ident_name);
             if let Some((old_value, _)) = variable {
                 old_value.num_times_used += 1;
-                let expr_id = self.interner.push_expr(HirExpression::Ident(old_value.ident));
+                let expr_id = self.interner.push_expr(HirExpression::Ident(old_value.ident, None));
                 self.interner.push_expr_location(expr_id, call_expr_span, self.file);
                 fmt_str_idents.push(expr_id);
             } else if ident_name.parse::<usize>().is_ok() {
@@ -1631,9 +2340,11 @@ impl<'a> Resolver<'a> {
                     span: call_expr_span,
                 });
             } else {
+                let suggestion = self.find_similar_value_name(ident_name);
                 self.errors.push(ResolverError::VariableNotDeclared {
                     name: ident_name.to_owned(),
                     span: call_expr_span,
+                    suggestion,
                 });
             }
         }
@@ -1655,6 +2366,7 @@ impl<'a> Resolver<'a> {
             | UnresolvedTypeData::Function(_, _, _)
             | UnresolvedTypeData::FormatString(_, _)
             | UnresolvedTypeData::TraitAsType(..)
+            | UnresolvedTypeData::TraitObject(..)
             | UnresolvedTypeData::Unspecified => {
                 let span = typ.span.expect("Function parameters should always have spans");
                 self.push_err(ResolverError::InvalidTypeForEntryPoint { span });
@@ -1723,6 +2435,31 @@ impl<'a> Resolver<'a> {
     }
 }
 
+/// The literal which `std::env!` is replaced with, given the value of its environment variable:
+/// an integer literal if the value is one, such as `32` or `0x2a`, and a string literal otherwise.
+fn environment_literal(value: String) -> HirLiteral {
+    let (tokens, errors) = Lexer::lex(&value);
+    let mut tokens = tokens.0.into_iter().map(SpannedToken::into_token);
+    match (tokens.next(), tokens.next()) {
+        (Some(Token::Int(int)), Some(Token::EOF)) if errors.is_empty() => {
+            HirLiteral::Integer(int, false)
+        }
+        _ => HirLiteral::Str(value),
+    }
+}
+
+/// True if a call to `std::assert_ne` with these arguments can be checked like `assert_eq`: it
+/// compares two values, optionally followed by a string literal or format string message.
+fn is_assert_ne_call(arguments: &[Expression]) -> bool {
+    match arguments {
+        [_, _] => true,
+        [_, _, message] => {
+            matches!(message.kind, ExpressionKind::Literal(Literal::Str(_) | Literal::FmtStr(_)))
+        }
+        _ => false,
+    }
+}
+
 /// Gives an error if a user tries to create a mutable reference
 /// to an immutable variable.
 pub fn verify_mutable_reference(interner: &NodeInterner, rhs: ExprId) -> Result<(), ResolverError> {
@@ -1734,7 +2471,7 @@ pub fn verify_mutable_reference(interner: &NodeInterner, rhs: ExprId) -> Result<
             let span = interner.expr_span(&rhs);
             Err(ResolverError::MutableReferenceToArrayElement { span })
         }
-        HirExpression::Ident(ident) => {
+        HirExpression::Ident(ident, _) => {
             if let Some(definition) = interner.try_definition(ident.id) {
                 if !definition.mutable {
                     return Err(ResolverError::MutableReferenceToImmutableVariable {
@@ -0,0 +1,70 @@
+//! Finds likely intended names when a name fails to resolve, so that errors can carry a
+//! "did you mean ...?" hint for simple typos.
+
+/// Returns the candidate closest to `name` by edit distance, provided it is close enough
+/// to plausibly be a typo of `name`. Names shorter than three characters never get a
+/// suggestion since almost any other short name would be within reach of them.
+/// Ties are broken alphabetically so that the suggestion does not depend on the iteration
+/// order of `candidates`.
+pub fn find_similar_name<'a>(
+    name: &str,
+    candidates: impl IntoIterator<Item = &'a str>,
+) -> Option<String> {
+    let max_distance = name.chars().count() / 3;
+    if max_distance == 0 {
+        return None;
+    }
+
+    candidates
+        .into_iter()
+        .filter(|candidate| *candidate != name)
+        .map(|candidate| (edit_distance(name, candidate), candidate))
+        .filter(|(distance, _)| *distance <= max_distance)
+        .min()
+        .map(|(_, candidate)| candidate.to_owned())
+}
+
+/// The Levenshtein distance between two strings: the number of single character
+/// insertions, deletions or substitutions needed to turn one into the other.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous_row: Vec<usize> = (0..=b.len()).collect();
+
+    for (i, a_char) in a.chars().enumerate() {
+        let mut current_row = Vec::with_capacity(b.len() + 1);
+        current_row.push(i + 1);
+
+        for (j, b_char) in b.iter().enumerate() {
+            let substitution = previous_row[j] + usize::from(a_char != *b_char);
+            let insertion = current_row[j] + 1;
+            let deletion = previous_row[j + 1] + 1;
+            current_row.push(substitution.min(insertion).min(deletion));
+        }
+        previous_row = current_row;
+    }
+
+    previous_row[b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{edit_distance, find_similar_name};
+
+    #[test]
+    fn edit_distances() {
+        assert_eq!(edit_distance("", ""), 0);
+        assert_eq!(edit_distance("foo", ""), 3);
+        assert_eq!(edit_distance("foo", "foo"), 0);
+        assert_eq!(edit_distance("foo", "fop"), 1);
+        assert_eq!(edit_distance("kitten", "sitting"), 3);
+    }
+
+    #[test]
+    fn suggests_only_close_names() {
+        let candidates = ["value", "other", "values"];
+        assert_eq!(find_similar_name("vlue", candidates), Some("value".to_owned()));
+        assert_eq!(find_similar_name("xyz", candidates), None);
+        assert_eq!(find_similar_name("x", ["y"]), None);
+        assert_eq!(find_similar_name("value", candidates), Some("values".to_owned()));
+    }
+}
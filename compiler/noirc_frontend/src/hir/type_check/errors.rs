@@ -1,11 +1,11 @@
-use acvm::FieldElement;
+use noirc_errors::codes::*;
 use noirc_errors::CustomDiagnostic as Diagnostic;
-use noirc_errors::Span;
+use noirc_errors::{lints, ErrorCode, Fix, Span};
 use thiserror::Error;
 
 use crate::hir::resolution::errors::ResolverError;
 use crate::hir_def::expr::HirBinaryOp;
-use crate::hir_def::types::Type;
+use crate::hir_def::types::{Type, TypeDisplayOptions};
 use crate::BinaryOpKind;
 use crate::FunctionReturnType;
 use crate::Signedness;
@@ -34,9 +34,9 @@ pub enum Source {
 pub enum TypeCheckError {
     #[error("Operator {op:?} cannot be used in a {place:?}")]
     OpCannotBeUsed { op: HirBinaryOp, place: &'static str, span: Span },
-    #[error("The literal `{expr:?}` cannot fit into `{ty}` which has range `{range}`")]
-    OverflowingAssignment { expr: FieldElement, ty: Type, range: String, span: Span },
-    #[error("Type {typ:?} cannot be used in a {place:?}")]
+    #[error("The literal `{literal}` cannot fit into `{ty}` which has range `{range}`")]
+    OverflowingLiteral { literal: String, ty: Type, range: String, span: Span },
+    #[error("Type {typ} cannot be used in a {place}")]
     TypeCannotBeUsed { typ: Type, place: &'static str, span: Span },
     #[error("Expected type {expected_typ:?} is not the same as {expr_typ:?}")]
     TypeMismatch { expected_typ: String, expr_typ: String, expr_span: Span },
@@ -51,9 +51,16 @@ pub enum TypeCheckError {
     #[error("Expected a function, but found a(n) {found}")]
     ExpectedFunction { found: Type, span: Span },
     #[error("Type {lhs_type} has no member named {field_name}")]
-    AccessUnknownMember { lhs_type: Type, field_name: String, span: Span },
+    AccessUnknownMember {
+        lhs_type: Type,
+        field_name: String,
+        span: Span,
+        suggestion: Option<String>,
+    },
     #[error("Function expects {expected} parameters but {found} given")]
     ParameterCountMismatch { expected: usize, found: usize, span: Span },
+    #[error("Expected {expected} generics but {actual} were given")]
+    IncorrectExplicitGenericCount { expected: usize, actual: usize, span: Span },
     #[error("Only integer and Field types may be casted to")]
     UnsupportedCast { span: Span },
     #[error("Index {index} is out of bounds for this tuple {lhs_type} of length {length}")]
@@ -61,7 +68,12 @@ pub enum TypeCheckError {
     #[error("Variable {name} must be mutable to be assigned to")]
     VariableMustBeMutable { name: String, span: Span },
     #[error("No method named '{method_name}' found for type '{object_type}'")]
-    UnresolvedMethodCall { method_name: String, object_type: Type, span: Span },
+    UnresolvedMethodCall {
+        method_name: String,
+        object_type: Type,
+        span: Span,
+        suggestion: Option<String>,
+    },
     #[error("Comparisons are invalid on Field types. Try casting the operands to a sized integer type first")]
     InvalidComparisonOnField { span: Span },
     #[error("Integers must have the same signedness LHS is {sign_x:?}, RHS is {sign_y:?}")]
@@ -86,6 +98,8 @@ pub enum TypeCheckError {
     AmbiguousBitWidth { span: Span },
     #[error("Error with additional context")]
     Context { err: Box<TypeCheckError>, ctx: &'static str },
+    #[error("{err}")]
+    WithFix { err: Box<TypeCheckError>, fix: Fix },
     #[error("Array is not homogeneous")]
     NonHomogeneousArray {
         first_span: Span,
@@ -111,17 +125,71 @@ pub enum TypeCheckError {
         parameter_span: Span,
         parameter_index: usize,
     },
+    #[error("No matching impl found for `{typ}: {trait_name}`")]
+    NoMatchingImplFound { typ: Type, trait_name: String, span: Span },
+    #[error("`{trait_name}` cannot be used as a trait object")]
+    TraitNotObjectSafe { trait_name: String, reason: String, span: Span },
+    #[error("Only values of concrete types can be converted into `dyn {trait_name}`, but `{typ}` is not concrete")]
+    TraitObjectOfNonConcreteType { trait_name: String, typ: Type, span: Span },
 }
 
 impl TypeCheckError {
     pub fn add_context(self, ctx: &'static str) -> Self {
         TypeCheckError::Context { err: Box::new(self), ctx }
     }
+
+    pub fn add_fix(self, fix: Fix) -> Self {
+        TypeCheckError::WithFix { err: Box::new(self), fix }
+    }
+
+    pub fn code(&self) -> ErrorCode {
+        match self {
+            TypeCheckError::TypeMismatch { .. } | TypeCheckError::TypeMismatchWithSource { .. } => {
+                E0300
+            }
+            TypeCheckError::NonHomogeneousArray { .. } => E0314,
+            TypeCheckError::TraitMethodParameterTypeMismatch { .. } => E0315,
+            TypeCheckError::OpCannotBeUsed { .. }
+            | TypeCheckError::TypeCannotBeUsed { .. }
+            | TypeCheckError::InvalidInfixOp { .. }
+            | TypeCheckError::InvalidUnaryOp { .. } => E0301,
+            TypeCheckError::InvalidBitwiseOperationOnField { .. }
+            | TypeCheckError::FieldModulo { .. }
+            | TypeCheckError::FieldComparison { .. }
+            | TypeCheckError::InvalidComparisonOnField { .. } => E0316,
+            TypeCheckError::OverflowingLiteral { .. } => E0302,
+            TypeCheckError::ArityMisMatch { .. }
+            | TypeCheckError::ParameterCountMismatch { .. } => E0303,
+            TypeCheckError::ExpectedFunction { .. } => E0317,
+            TypeCheckError::PublicReturnType { .. } => E0304,
+            TypeCheckError::InvalidCast { .. } => E0305,
+            TypeCheckError::UnsupportedCast { .. } => E0318,
+            TypeCheckError::AccessUnknownMember { .. } => E0306,
+            TypeCheckError::TupleIndexOutOfBounds { .. } => E0319,
+            TypeCheckError::VariableMustBeMutable { .. } => E0307,
+            TypeCheckError::UnresolvedMethodCall { .. } => E0308,
+            TypeCheckError::IntegerSignedness { .. }
+            | TypeCheckError::IntegerBitWidth { .. }
+            | TypeCheckError::IntegerTypeMismatch { .. } => E0309,
+            TypeCheckError::IntegerAndFieldBinaryOperation { .. } => E0320,
+            TypeCheckError::AmbiguousBitWidth { .. } => E0321,
+            TypeCheckError::TypeAnnotationsNeeded { .. } => E0310,
+            TypeCheckError::UnusedResultError { .. } => E0311,
+            TypeCheckError::NoMatchingImplFound { .. } => E0312,
+            TypeCheckError::TraitNotObjectSafe { .. } => E0313,
+            TypeCheckError::TraitObjectOfNonConcreteType { .. } => E0322,
+            TypeCheckError::IncorrectExplicitGenericCount { .. } => E0211,
+            TypeCheckError::CallDeprecated { .. } => E0215,
+            TypeCheckError::Context { err, .. } | TypeCheckError::WithFix { err, .. } => err.code(),
+            TypeCheckError::ResolverError(error) => error.code(),
+        }
+    }
 }
 
 impl From<TypeCheckError> for Diagnostic {
     fn from(error: TypeCheckError) -> Diagnostic {
-        match error {
+        let code = error.code();
+        let diagnostic = match error {
             TypeCheckError::TypeCannotBeUsed { typ, place, span } => Diagnostic::simple_error(
                 format!("The type {} cannot be used in a {}", &typ, place),
                 String::new(),
@@ -132,6 +200,11 @@ impl From<TypeCheckError> for Diagnostic {
                 diag.add_note(ctx.to_owned());
                 diag
             }
+            TypeCheckError::WithFix { err, fix } => {
+                let mut diag = Diagnostic::from(*err);
+                diag.add_fix(fix);
+                diag
+            }
             TypeCheckError::OpCannotBeUsed { op, place, span } => Diagnostic::simple_error(
                 format!("The operator {op:?} cannot be used in a {place}"),
                 String::new(),
@@ -180,13 +253,20 @@ impl From<TypeCheckError> for Diagnostic {
                 let msg = format!("Function expects {expected} parameter{empty_or_s} but {found} {was_or_were} given");
                 Diagnostic::simple_error(msg, String::new(), span)
             }
+            TypeCheckError::IncorrectExplicitGenericCount { expected, actual, span } => {
+                let expected_plural = if expected == 1 { "" } else { "s" };
+                let actual_plural = if actual == 1 { "is" } else { "are" };
+                Diagnostic::simple_error(
+                    format!("This function has {expected} generic{expected_plural} but {actual} {actual_plural} given here"),
+                    "Incorrect number of generic arguments".into(),
+                    span,
+                )
+            }
             TypeCheckError::InvalidCast { span, .. }
             | TypeCheckError::ExpectedFunction { span, .. }
-            | TypeCheckError::AccessUnknownMember { span, .. }
             | TypeCheckError::UnsupportedCast { span }
             | TypeCheckError::TupleIndexOutOfBounds { span, .. }
             | TypeCheckError::VariableMustBeMutable { span, .. }
-            | TypeCheckError::UnresolvedMethodCall { span, .. }
             | TypeCheckError::InvalidComparisonOnField { span }
             | TypeCheckError::IntegerSignedness { span, .. }
             | TypeCheckError::IntegerBitWidth { span, .. }
@@ -197,13 +277,21 @@ impl From<TypeCheckError> for Diagnostic {
             | TypeCheckError::FieldComparison { span, .. }
             | TypeCheckError::AmbiguousBitWidth { span, .. }
             | TypeCheckError::IntegerAndFieldBinaryOperation { span }
-            | TypeCheckError::OverflowingAssignment { span, .. }
+            | TypeCheckError::OverflowingLiteral { span, .. }
             | TypeCheckError::FieldModulo { span } => {
                 Diagnostic::simple_error(error.to_string(), String::new(), span)
             }
+            TypeCheckError::AccessUnknownMember { span, ref suggestion, .. }
+            | TypeCheckError::UnresolvedMethodCall { span, ref suggestion, .. } => {
+                let mut diagnostic = Diagnostic::simple_error(error.to_string(), String::new(), span);
+                if let Some(suggestion) = suggestion {
+                    diagnostic.add_note(format!("help: did you mean `{suggestion}`?"));
+                }
+                diagnostic
+            }
             TypeCheckError::PublicReturnType { typ, span } => Diagnostic::simple_error(
                 "Functions cannot declare a public return type".to_string(),
-                format!("return type is {typ}"),
+                format!("return type is {}", typ.display(TypeDisplayOptions::ABBREVIATED)),
                 span,
             ),
             TypeCheckError::TypeAnnotationsNeeded { span } => Diagnostic::simple_error(
@@ -213,6 +301,7 @@ impl From<TypeCheckError> for Diagnostic {
             ),
             TypeCheckError::ResolverError(error) => error.into(),
             TypeCheckError::TypeMismatchWithSource { expected, actual, span, source } => {
+                let (expected, actual) = display_differing_types(&expected, &actual);
                 let message = match source {
                     Source::Binary => format!("Types in a binary operation should match, but found {expected} and {actual}"),
                     Source::Assignment => {
@@ -237,7 +326,7 @@ impl From<TypeCheckError> for Diagnostic {
 
                         diagnostic.add_secondary(format!("{actual} returned here"), expr_span);
 
-                        return diagnostic
+                        return diagnostic.with_code(code)
                     },
                 };
 
@@ -248,14 +337,41 @@ impl From<TypeCheckError> for Diagnostic {
                 let secondary_message = note.clone().unwrap_or_default();
 
                 Diagnostic::simple_warning(primary_message, secondary_message, span)
+                    .with_lint(lints::DEPRECATED)
+            }
+            TypeCheckError::NoMatchingImplFound { ref typ, ref trait_name, span } => {
+                let secondary = format!("No impl for `{typ}: {trait_name}`");
+                Diagnostic::simple_error(error.to_string(), secondary, span)
+            }
+            TypeCheckError::TraitNotObjectSafe { ref reason, span, .. } => {
+                Diagnostic::simple_error(error.to_string(), reason.clone(), span)
+            }
+            TypeCheckError::TraitObjectOfNonConcreteType { span, .. } => {
+                let secondary = "The types of trait objects' values must be known without generics".to_string();
+                Diagnostic::simple_error(error.to_string(), secondary, span)
             }
             TypeCheckError::UnusedResultError { expr_type, expr_span } => {
+                let expr_type = expr_type.display(TypeDisplayOptions::ABBREVIATED);
                 Diagnostic::simple_warning(
                     format!("Unused expression result of type {expr_type}"),
                     String::new(),
                     expr_span,
                 )
+                .with_lint(lints::UNUSED_RESULTS)
             }
-        }
+        };
+        diagnostic.with_code(code)
+    }
+}
+
+/// Displays two types which were expected to be the same, abbreviating them unless that would
+/// hide the difference between them.
+fn display_differing_types(expected: &Type, actual: &Type) -> (String, String) {
+    let options = TypeDisplayOptions::ABBREVIATED;
+    let abbreviated = (expected.display(options).to_string(), actual.display(options).to_string());
+    if abbreviated.0 == abbreviated.1 {
+        (expected.to_string(), actual.to_string())
+    } else {
+        abbreviated
     }
 }
@@ -1,24 +1,64 @@
 use iter_extended::vecmap;
-use noirc_errors::Span;
+use noirc_errors::{Location, Span};
 
 use crate::{
-    hir::{resolution::resolver::verify_mutable_reference, type_check::errors::Source},
+    hir::{
+        resolution::{resolver::verify_mutable_reference, suggestions::find_similar_name},
+        type_check::errors::Source,
+    },
     hir_def::{
         expr::{
-            self, HirArrayLiteral, HirBinaryOp, HirExpression, HirLiteral, HirMethodCallExpression,
-            HirMethodReference, HirPrefixExpression,
+            self, HirArrayLiteral, HirBinaryOp, HirBlockExpression, HirCallExpression,
+            HirConstructorExpression, HirExpression, HirInfixExpression, HirLiteral,
+            HirMethodCallExpression, HirMethodReference, HirPrefixExpression,
         },
+        stmt::{HirLValue, HirStatement},
+        traits::TraitConstraint,
         types::Type,
     },
     node_interner::{DefinitionKind, ExprId, FuncId, TraitMethodId},
-    BinaryOpKind, Signedness, TypeBinding, TypeVariableKind, UnaryOp,
+    BinaryOpKind, Ident, Signedness, TypeBinding, TypeBindings, TypeVariableKind, UnaryOp,
 };
 
 use super::{errors::TypeCheckError, TypeChecker};
 
 impl<'interner> TypeChecker<'interner> {
+    /// Binds the generics given explicitly to a function, as in `foo::<Field>()`, to the type
+    /// variables the function's type was instantiated with. They're given in the order they're
+    /// declared in, after those of the `impl` the function is in, if any.
+    fn bind_explicit_generics(
+        &mut self,
+        typ: &Type,
+        bindings: &TypeBindings,
+        generics: Vec<Type>,
+        span: Span,
+    ) {
+        let type_variables = match typ {
+            Type::Forall(type_variables, _) => type_variables.as_slice(),
+            _ => &[],
+        };
+        if type_variables.len() != generics.len() {
+            let (expected, actual) = (type_variables.len(), generics.len());
+            self.errors.push(TypeCheckError::IncorrectExplicitGenericCount {
+                expected,
+                actual,
+                span,
+            });
+            return;
+        }
+
+        for ((id, _), generic) in type_variables.iter().zip(generics) {
+            let (_, instantiated) = &bindings[id];
+            self.unify(instantiated, &generic, || TypeCheckError::TypeMismatch {
+                expected_typ: instantiated.to_string(),
+                expr_typ: generic.to_string(),
+                expr_span: span,
+            });
+        }
+    }
+
     fn check_if_deprecated(&mut self, expr: &ExprId) {
-        if let HirExpression::Ident(expr::HirIdent { location, id }) =
+        if let HirExpression::Ident(expr::HirIdent { location, id }, _) =
             self.interner.expression(expr)
         {
             if let Some(DefinitionKind::Function(func_id)) =
@@ -36,6 +76,21 @@ impl<'interner> TypeChecker<'interner> {
         }
     }
 
+    /// Remember the trait constraints of the given function, instantiated with `bindings`,
+    /// so that they can be checked once the types they refer to are known.
+    fn push_trait_constraints(&mut self, func_id: FuncId, bindings: &TypeBindings, span: Span) {
+        if let Some(func_meta) = self.interner.try_function_meta(&func_id) {
+            for constraint in func_meta.trait_constraints {
+                let typ = constraint.typ.substitute(bindings);
+                let trait_generics =
+                    vecmap(&constraint.trait_generics, |typ| typ.substitute(bindings));
+                let constraint =
+                    TraitConstraint { typ, trait_id: constraint.trait_id, trait_generics };
+                self.trait_constraints.push((constraint, span));
+            }
+        }
+    }
+
     /// Infers a type for a given expression, and return this type.
     /// As a side-effect, this function will also remember this type in the NodeInterner
     /// for the given expr_id key.
@@ -46,13 +101,24 @@ impl<'interner> TypeChecker<'interner> {
     /// function `foo` to refer to.
     pub(crate) fn check_expression(&mut self, expr_id: &ExprId) -> Type {
         let typ = match self.interner.expression(expr_id) {
-            HirExpression::Ident(ident) => {
+            HirExpression::Ident(ident, generics) => {
                 // An identifiers type may be forall-quantified in the case of generic functions.
                 // E.g. `fn foo<T>(t: T, field: Field) -> T` has type `forall T. fn(T, Field) -> T`.
                 // We must instantiate identifiers at every call site to replace this T with a new type
                 // variable to handle generic functions.
                 let t = self.interner.id_type_substitute_trait_as_type(ident.id);
                 let (typ, bindings) = t.instantiate(self.interner);
+
+                if let Some(generics) = generics {
+                    self.bind_explicit_generics(&t, &bindings, generics, ident.location.span);
+                }
+
+                if let Some(DefinitionKind::Function(func_id)) =
+                    self.interner.try_definition(ident.id).map(|def| def.kind.clone())
+                {
+                    self.push_trait_constraints(func_id, &bindings, ident.location.span);
+                }
+
                 self.interner.store_instantiation_bindings(*expr_id, bindings);
                 typ
             }
@@ -73,6 +139,9 @@ impl<'interner> TypeChecker<'interner> {
 
                         // Check if the array is homogeneous
                         for (index, elem_type) in elem_types.iter().enumerate().skip(1) {
+                            if self.have_equal_types(arr[index], arr[0]) {
+                                continue;
+                            }
                             let location = self.interner.expr_location(&arr[index]);
 
                             elem_type.unify(&first_elem_type, &mut self.errors, || {
@@ -100,8 +169,33 @@ impl<'interner> TypeChecker<'interner> {
                         };
                         Type::Array(Box::new(length), Box::new(elem_type))
                     }
+                    HirLiteral::Array(HirArrayLiteral::Comprehension {
+                        element,
+                        identifier,
+                        start,
+                        end,
+                        length,
+                    }) => {
+                        let context =
+                            "The range of an array comprehension must be known at compile-time";
+                        let range_type =
+                            self.check_range(&start, &end, "array comprehension", context);
+                        self.interner.push_definition_type(identifier.id, range_type);
+
+                        let elem_type = self.check_expression(&element);
+                        let length = match length {
+                            Type::Constant(length) => {
+                                Type::constant_variable(length, self.interner)
+                            }
+                            other => other,
+                        };
+                        Type::Array(Box::new(length), Box::new(elem_type))
+                    }
                     HirLiteral::Bool(_) => Type::Bool,
-                    HirLiteral::Integer(_) => Type::polymorphic_integer(self.interner),
+                    HirLiteral::Integer(..) => {
+                        self.integer_literals.push(*expr_id);
+                        Type::polymorphic_integer(self.interner)
+                    }
                     HirLiteral::Str(string) => {
                         let len = Type::Constant(string.len() as u64);
                         Type::String(Box::new(len))
@@ -123,11 +217,17 @@ impl<'interner> TypeChecker<'interner> {
                 let rhs_span = self.interner.expr_span(&infix_expr.rhs);
                 let span = lhs_span.merge(rhs_span);
 
-                self.infix_operand_type_rules(&lhs_type, &infix_expr.operator, &rhs_type, span)
-                    .unwrap_or_else(|error| {
-                        self.errors.push(error);
-                        Type::Error
-                    })
+                if let Some(typ) =
+                    self.check_operator_overload(expr_id, &infix_expr, &lhs_type, &rhs_type, span)
+                {
+                    typ
+                } else {
+                    self.infix_operand_type_rules(&lhs_type, &infix_expr.operator, &rhs_type, span)
+                        .unwrap_or_else(|error| {
+                            self.errors.push(error);
+                            Type::Error
+                        })
+                }
             }
             HirExpression::Index(index_expr) => self.check_index_expression(expr_id, index_expr),
             HirExpression::Call(call_expr) => {
@@ -138,12 +238,22 @@ impl<'interner> TypeChecker<'interner> {
                     (typ, *arg, self.interner.expr_span(arg))
                 });
                 let span = self.interner.expr_span(expr_id);
-                self.bind_function_type(function, args, span)
+                let return_type = self.bind_function_type(function, args, span);
+                self.wrap_unchecked_result(expr_id, call_expr, return_type)
             }
             HirExpression::MethodCall(mut method_call) => {
                 let object_type = self.check_expression(&method_call.object).follow_bindings();
                 let method_name = method_call.method.0.contents.as_str();
-                match self.lookup_method(&object_type, method_name, expr_id) {
+
+                let original_object = method_call.object;
+                let (object, object_type) =
+                    self.auto_deref(original_object, object_type, |this, typ| {
+                        this.find_method(typ, method_name).is_some()
+                    });
+                let dereferenced = object != original_object;
+                method_call.object = object;
+
+                match self.find_method(&object_type, method_name) {
                     Some(method_ref) => {
                         let mut args = vec![(
                             object_type,
@@ -164,13 +274,34 @@ impl<'interner> TypeChecker<'interner> {
                             // Automatically add `&mut` if the method expects a mutable reference and
                             // the object is not already one.
                             if func_id != FuncId::dummy_id() {
+                                self.interner
+                                    .add_function_reference(self.current_function, func_id);
                                 let func_meta = self.interner.function_meta(&func_id);
+                                if dereferenced && takes_mutable_reference(&func_meta.typ) {
+                                    self.errors.push(TypeCheckError::TypeCannotBeUsed {
+                                        typ: self.interner.id_type(original_object),
+                                        place: "`&mut self` method call through `Deref`",
+                                        span: self.interner.expr_span(expr_id),
+                                    });
+                                }
                                 self.try_add_mutable_reference_to_object(
                                     &mut method_call,
                                     &func_meta.typ,
                                     &mut args,
                                 );
                             }
+                        } else if let HirMethodReference::TraitMethodId(self_type, method) =
+                            &method_ref
+                        {
+                            // Trait methods are dereferenced or borrowed in the same way, as in
+                            // `x.serialize()` where `x: &mut T` and `T: Serialize`
+                            let the_trait = self.interner.get_trait(method.trait_id);
+                            let method_type = the_trait.method_type(method.method_index, self_type);
+                            self.try_add_mutable_reference_to_object(
+                                &mut method_call,
+                                &method_type,
+                                &mut args,
+                            );
                         }
 
                         let (function_id, function_call) = method_call.into_function_call(
@@ -182,10 +313,28 @@ impl<'interner> TypeChecker<'interner> {
                         let span = self.interner.expr_span(expr_id);
                         let ret = self.check_method_call(&function_id, method_ref, args, span);
 
-                        self.interner.replace_expr(expr_id, function_call);
-                        ret
+                        self.interner.replace_expr(expr_id, function_call.clone());
+                        match function_call {
+                            HirExpression::Call(call) => {
+                                self.wrap_unchecked_result(expr_id, call, ret)
+                            }
+                            _ => ret,
+                        }
+                    }
+                    // `x.into()` converts `x` with a `From` impl if its type has no `into` method
+                    None if method_name == "into"
+                        && method_call.arguments.is_empty()
+                        && self.interner.get_from_trait_method().is_some() =>
+                    {
+                        self.check_into_conversion(expr_id, method_call.object, object_type)
+                    }
+                    // If we fail to resolve the object to a struct type, we have no way of type
+                    // checking its arguments as we can't even resolve the name of the function
+                    None if object_type == Type::Error => Type::Error,
+                    None => {
+                        self.push_unresolved_method_call(&object_type, method_name, expr_id);
+                        Type::Error
                     }
-                    None => Type::Error,
                 }
             }
             HirExpression::Cast(cast_expr) => {
@@ -257,15 +406,9 @@ impl<'interner> TypeChecker<'interner> {
 
                 Type::Function(params, Box::new(lambda.return_type), Box::new(env_type))
             }
-            HirExpression::TraitMethodReference(_, method) => {
+            HirExpression::TraitMethodReference(self_type, method) => {
                 let the_trait = self.interner.get_trait(method.trait_id);
-                let method = &the_trait.methods[method.method_index];
-
-                let typ = Type::Function(
-                    method.arguments.clone(),
-                    Box::new(method.return_type.clone()),
-                    Box::new(Type::Unit),
-                );
+                let typ = the_trait.method_type(method.method_index, &self_type);
                 let (typ, bindings) = typ.instantiate(self.interner);
                 self.interner.store_instantiation_bindings(*expr_id, bindings);
                 typ
@@ -404,6 +547,18 @@ impl<'interner> TypeChecker<'interner> {
         let index_type = self.check_expression(&index_expr.index);
         let span = self.interner.expr_span(&index_expr.index);
 
+        // When writing `a[i]`, if `a : &mut ...` then automatically dereference `a` as many
+        // times as needed to get the underlying array.
+        let lhs_type = self.check_expression(&index_expr.collection);
+        let (new_lhs, lhs_type) = self.insert_auto_dereferences(index_expr.collection, lhs_type);
+        index_expr.collection = new_lhs;
+
+        if let Some(typ) = self.check_index_overload(id, &index_expr, &lhs_type, &index_type) {
+            return typ;
+        }
+
+        self.interner.replace_expr(id, HirExpression::Index(index_expr));
+
         index_type.unify(&Type::polymorphic_integer(self.interner), &mut self.errors, || {
             TypeCheckError::TypeMismatch {
                 expected_typ: "an integer".to_owned(),
@@ -412,17 +567,12 @@ impl<'interner> TypeChecker<'interner> {
             }
         });
 
-        // When writing `a[i]`, if `a : &mut ...` then automatically dereference `a` as many
-        // times as needed to get the underlying array.
-        let lhs_type = self.check_expression(&index_expr.collection);
-        let (new_lhs, lhs_type) = self.insert_auto_dereferences(index_expr.collection, lhs_type);
-        index_expr.collection = new_lhs;
-        self.interner.replace_expr(id, HirExpression::Index(index_expr));
-
         match lhs_type.follow_bindings() {
             // XXX: We can check the array bounds here also, but it may be better to constant fold first
             // and have ConstId instead of ExprId for constants
             Type::Array(_, base_type) => *base_type,
+            // Strings are indexed by byte
+            Type::String(_) => Type::Integer(Signedness::Unsigned, 8),
             Type::Error => Type::Error,
             typ => {
                 let span = self.interner.expr_span(&new_lhs);
@@ -436,6 +586,251 @@ impl<'interner> TypeChecker<'interner> {
         }
     }
 
+    /// Desugars `collection[index]` where `collection` is a struct into a call to the
+    /// `std::ops::Index` impl of that struct: `Index::index(collection, index)`.
+    /// Returns None if the collection is not a struct or the `Index` trait is unavailable.
+    fn check_index_overload(
+        &mut self,
+        expr_id: &ExprId,
+        index_expr: &expr::HirIndexExpression,
+        collection_type: &Type,
+        index_type: &Type,
+    ) -> Option<Type> {
+        let object_type = collection_type.follow_bindings();
+        if !matches!(object_type, Type::Struct(..)) {
+            return None;
+        }
+
+        let method = self.interner.get_index_trait_method()?;
+        let the_trait = self.interner.get_trait(method.trait_id);
+        let span = self.interner.expr_span(expr_id);
+
+        let Some((trait_impl, _)) =
+            self.interner.lookup_trait_implementation(&object_type, method.trait_id, &[])
+        else {
+            let trait_name = the_trait.name.to_string();
+            self.errors.push(TypeCheckError::NoMatchingImplFound {
+                typ: object_type,
+                trait_name,
+                span,
+            });
+            return Some(Type::Error);
+        };
+
+        let func_id = trait_impl.borrow().methods[method.method_index];
+        let method_ref = HirMethodReference::FuncId(func_id);
+
+        let method_call = HirMethodCallExpression {
+            method: the_trait.methods[method.method_index].name.clone(),
+            object: index_expr.collection,
+            arguments: vec![index_expr.index],
+            location: self.interner.expr_location(expr_id),
+        };
+
+        let args = vec![
+            (object_type, index_expr.collection, self.interner.expr_span(&index_expr.collection)),
+            (index_type.clone(), index_expr.index, self.interner.expr_span(&index_expr.index)),
+        ];
+
+        let location = method_call.location;
+        let (function_id, function_call) =
+            method_call.into_function_call(method_ref.clone(), location, self.interner);
+
+        let typ = self.check_method_call(&function_id, method_ref, args, span);
+        self.interner.replace_expr(expr_id, function_call);
+        Some(typ)
+    }
+
+    /// Desugars the assignment `collection[index] = value` where `collection` is a struct into a
+    /// call to its `std::ops::IndexMut` impl: `IndexMut::index_mut(&mut collection, index, value)`.
+    /// Returns the call, or None if an error was reported.
+    pub(crate) fn check_index_assign_overload(
+        &mut self,
+        collection: HirLValue,
+        collection_type: Type,
+        index: ExprId,
+        (value_type, value): (Type, ExprId),
+        span: Span,
+    ) -> Option<ExprId> {
+        let index_type = self.check_expression(&index);
+        let object_type = collection_type.follow_bindings();
+
+        // Only a reference to a variable or its fields can be passed to `index_mut`, taking
+        // one to an array element would mutate a copy of it.
+        if lvalue_contains_index(&collection) {
+            self.errors.push(TypeCheckError::TypeCannotBeUsed {
+                typ: object_type,
+                place: "nested index assignment",
+                span,
+            });
+            return None;
+        }
+
+        let method = self.interner.get_index_mut_trait_method()?;
+        let the_trait = self.interner.get_trait(method.trait_id);
+
+        let Some((trait_impl, _)) =
+            self.interner.lookup_trait_implementation(&object_type, method.trait_id, &[])
+        else {
+            let trait_name = the_trait.name.to_string();
+            self.errors.push(TypeCheckError::NoMatchingImplFound {
+                typ: object_type,
+                trait_name,
+                span,
+            });
+            return None;
+        };
+
+        let func_id = trait_impl.borrow().methods[method.method_index];
+        let method_ref = HirMethodReference::FuncId(func_id);
+
+        let object = self.lvalue_expression(collection);
+        let object_span = self.interner.expr_span(&object);
+        let reference_type = Type::MutableReference(Box::new(object_type));
+
+        // Each dereference of the lvalue is marked as implicitly added, so removing them passes
+        // on the reference being dereferenced instead of a reference to a copy of its value.
+        let object = if self.try_remove_implicit_dereference(object).is_some() {
+            object
+        } else {
+            let location = self.interner.id_location(object);
+            let reference = self.interner.push_expr(HirExpression::Prefix(HirPrefixExpression {
+                operator: UnaryOp::MutableReference,
+                rhs: object,
+            }));
+            self.interner.push_expr_type(&reference, reference_type.clone());
+            self.interner.push_expr_location(reference, location.span, location.file);
+            reference
+        };
+
+        let location = Location::new(span, self.interner.id_location(value).file);
+        let method_call = HirMethodCallExpression {
+            method: the_trait.methods[method.method_index].name.clone(),
+            object,
+            arguments: vec![index, value],
+            location,
+        };
+
+        let args = vec![
+            (reference_type, object, object_span),
+            (index_type, index, self.interner.expr_span(&index)),
+            (value_type, value, self.interner.expr_span(&value)),
+        ];
+
+        let (function_id, function_call) =
+            method_call.into_function_call(method_ref.clone(), location, self.interner);
+
+        let typ = self.check_method_call(&function_id, method_ref, args, span);
+        let call = self.interner.push_expr(function_call);
+        self.interner.push_expr_type(&call, typ);
+        self.interner.push_expr_location(call, span, location.file);
+        Some(call)
+    }
+
+    /// Converts a type checked lvalue into the expression reading from the same place.
+    /// Every dereference in an lvalue is of the reference itself rather than of a copy of its
+    /// value, so they're marked as implicitly added.
+    fn lvalue_expression(&mut self, lvalue: HirLValue) -> ExprId {
+        let (expression, typ, location) = match lvalue {
+            HirLValue::Ident(ident, typ) => {
+                let location = ident.location;
+                (HirExpression::Ident(ident, None), typ, location)
+            }
+            HirLValue::MemberAccess { object, field_name, field_index, typ } => {
+                let lhs = self.lvalue_expression(*object);
+                let location =
+                    Location::new(field_name.span(), self.interner.id_location(lhs).file);
+                let access =
+                    HirExpression::MemberAccess(expr::HirMemberAccess { lhs, rhs: field_name });
+                let expr_id = self.interner.push_expr(access);
+                self.interner.set_field_index(expr_id, field_index.unwrap_or_default());
+                self.interner.push_expr_type(&expr_id, typ);
+                self.interner.push_expr_location(expr_id, location.span, location.file);
+                return expr_id;
+            }
+            HirLValue::Index { array, index, typ } => {
+                let collection = self.lvalue_expression(*array);
+                let location = self.interner.id_location(collection);
+                (
+                    HirExpression::Index(expr::HirIndexExpression { collection, index }),
+                    typ,
+                    location,
+                )
+            }
+            HirLValue::Dereference { lvalue, element_type } => {
+                let rhs = self.lvalue_expression(*lvalue);
+                let location = self.interner.id_location(rhs);
+                let operator = UnaryOp::Dereference { implicitly_added: true };
+                let prefix = HirExpression::Prefix(HirPrefixExpression { operator, rhs });
+                (prefix, element_type, location)
+            }
+        };
+
+        let expr_id = self.interner.push_expr(expression);
+        self.interner.push_expr_type(&expr_id, typ);
+        self.interner.push_expr_location(expr_id, location.span, location.file);
+        expr_id
+    }
+
+    /// Dereferences `object` with the `std::ops::Deref` impl of its type, and of the types it
+    /// dereferences to, until `found` holds for the resulting type. This lets wrapper types
+    /// expose the fields and methods of the type they wrap. If `found` never holds, `object`
+    /// and `object_type` are returned unchanged.
+    fn auto_deref(
+        &mut self,
+        object: ExprId,
+        object_type: Type,
+        found: impl Fn(&Self, &Type) -> bool,
+    ) -> (ExprId, Type) {
+        let mut current = (object, object_type.clone());
+        let mut seen = Vec::new();
+
+        while !found(self, &current.1) {
+            if seen.contains(&current.1) {
+                return (object, object_type);
+            }
+            let Some(target) = self.check_deref(current.0, &current.1) else {
+                return (object, object_type);
+            };
+            seen.push(std::mem::replace(&mut current, target).1);
+        }
+        current
+    }
+
+    /// Creates the call `Deref::deref(object)` if the struct type of `object` implements
+    /// `std::ops::Deref`, returning it along with its type.
+    fn check_deref(&mut self, object: ExprId, object_type: &Type) -> Option<(ExprId, Type)> {
+        if !matches!(object_type, Type::Struct(..)) {
+            return None;
+        }
+
+        let method = self.interner.get_deref_trait_method()?;
+        let (trait_impl, _) =
+            self.interner.lookup_trait_implementation(object_type, method.trait_id, &[])?;
+
+        let func_id = trait_impl.borrow().methods[method.method_index];
+        let method_ref = HirMethodReference::FuncId(func_id);
+        let the_trait = self.interner.get_trait(method.trait_id);
+
+        let location = self.interner.expr_location(&object);
+        let method_call = HirMethodCallExpression {
+            method: the_trait.methods[method.method_index].name.clone(),
+            object,
+            arguments: Vec::new(),
+            location,
+        };
+        let args = vec![(object_type.clone(), object, location.span)];
+
+        let (function_id, function_call) =
+            method_call.into_function_call(method_ref.clone(), location, self.interner);
+
+        let typ = self.check_method_call(&function_id, method_ref, args, location.span);
+        let call = self.interner.push_expr(function_call);
+        self.interner.push_expr_type(&call, typ.clone());
+        self.interner.push_expr_location(call, location.span, location.file);
+        Some((call, typ.follow_bindings()))
+    }
+
     fn check_cast(&mut self, from: Type, to: Type, span: Span) -> Type {
         match from.follow_bindings() {
             Type::Integer(..)
@@ -486,11 +881,11 @@ impl<'interner> TypeChecker<'interner> {
 
                 (func_meta.typ, param_len)
             }
-            HirMethodReference::TraitMethodId(_, method) => {
+            HirMethodReference::TraitMethodId(ref object_type, method) => {
                 let the_trait = self.interner.get_trait(method.trait_id);
-                let method = &the_trait.methods[method.method_index];
+                let typ = the_trait.method_type(method.method_index, object_type);
 
-                (method.get_type(), method.arguments.len())
+                (typ, the_trait.methods[method.method_index].arguments.len())
             }
         };
 
@@ -506,6 +901,10 @@ impl<'interner> TypeChecker<'interner> {
 
         let (function_type, instantiation_bindings) = fn_typ.instantiate(self.interner);
 
+        if let HirMethodReference::FuncId(func_id) = method_ref {
+            self.push_trait_constraints(func_id, &instantiation_bindings, span);
+        }
+
         self.interner.store_instantiation_bindings(*function_ident_id, instantiation_bindings);
         self.interner.push_expr_type(function_ident_id, function_type.clone());
 
@@ -528,6 +927,9 @@ impl<'interner> TypeChecker<'interner> {
             None => Type::Unit,
             Some(alternative) => {
                 let else_type = self.check_expression(&alternative);
+                if self.have_equal_types(if_expr.consequence, alternative) {
+                    return then_type;
+                }
 
                 let expr_span = self.interner.expr_span(expr_id);
                 self.unify(&then_type, &else_type, || {
@@ -594,6 +996,12 @@ impl<'interner> TypeChecker<'interner> {
 
     fn check_member_access(&mut self, mut access: expr::HirMemberAccess, expr_id: ExprId) -> Type {
         let lhs_type = self.check_expression(&access.lhs).follow_bindings();
+        let field_name = access.rhs.0.contents.as_str();
+        let (lhs, lhs_type) = self.auto_deref(access.lhs, lhs_type, |_, typ| match typ {
+            Type::Struct(s, args) => s.borrow().get_field(field_name, args).is_some(),
+            _ => true,
+        });
+        access.lhs = lhs;
         let span = self.interner.expr_span(&expr_id);
         let access_lhs = &mut access.lhs;
 
@@ -680,10 +1088,21 @@ impl<'interner> TypeChecker<'interner> {
         if let Type::TypeVariable(..) = &lhs_type {
             self.errors.push(TypeCheckError::TypeAnnotationsNeeded { span });
         } else if lhs_type != Type::Error {
+            let suggestion = match &lhs_type {
+                Type::Struct(s, _) => {
+                    let fields = s.borrow().field_names();
+                    find_similar_name(
+                        field_name,
+                        fields.iter().map(|field| field.0.contents.as_str()),
+                    )
+                }
+                _ => None,
+            };
             self.errors.push(TypeCheckError::AccessUnknownMember {
                 lhs_type,
                 field_name: field_name.to_string(),
                 span,
+                suggestion,
             });
         }
 
@@ -818,92 +1237,146 @@ impl<'interner> TypeChecker<'interner> {
         }
     }
 
-    fn lookup_method(
-        &mut self,
-        object_type: &Type,
-        method_name: &str,
-        expr_id: &ExprId,
-    ) -> Option<HirMethodReference> {
+    /// Finds the method named `method_name` callable on `object_type`, if any.
+    fn find_method(&self, object_type: &Type, method_name: &str) -> Option<HirMethodReference> {
         match object_type {
             Type::Struct(typ, _args) => {
                 let id = typ.borrow().id;
-                match self.interner.lookup_method(object_type, id, method_name, false) {
-                    Some(method_id) => Some(HirMethodReference::FuncId(method_id)),
-                    None => {
-                        self.errors.push(TypeCheckError::UnresolvedMethodCall {
-                            method_name: method_name.to_string(),
-                            object_type: object_type.clone(),
-                            span: self.interner.expr_span(expr_id),
-                        });
-                        None
-                    }
-                }
+                self.interner
+                    .lookup_method(object_type, id, method_name, false)
+                    .map(HirMethodReference::FuncId)
+                    .or_else(|| self.find_constrained_method(object_type, method_name))
             }
-            Type::TraitAsType(_trait) => {
-                self.errors.push(TypeCheckError::UnresolvedMethodCall {
-                    method_name: method_name.to_string(),
-                    object_type: object_type.clone(),
-                    span: self.interner.expr_span(expr_id),
-                });
-                None
-            }
-            Type::NamedGeneric(_, _) => {
-                let func_meta = self.interner.function_meta(
-                    &self.current_function.expect("unexpected method outside a function"),
-                );
-
-                for constraint in func_meta.trait_constraints {
-                    if *object_type == constraint.typ {
-                        let the_trait = self.interner.get_trait(constraint.trait_id);
-
-                        for (method_index, method) in the_trait.methods.iter().enumerate() {
-                            if method.name.0.contents == method_name {
-                                let trait_method =
-                                    TraitMethodId { trait_id: constraint.trait_id, method_index };
-                                return Some(HirMethodReference::TraitMethodId(
-                                    object_type.clone(),
-                                    trait_method,
-                                ));
-                            }
-                        }
-                    }
-                }
-
-                self.errors.push(TypeCheckError::UnresolvedMethodCall {
-                    method_name: method_name.to_string(),
-                    object_type: object_type.clone(),
-                    span: self.interner.expr_span(expr_id),
-                });
-                None
+            // Methods called on a trait object are dispatched at runtime
+            Type::TraitObject(the_trait) => {
+                let the_trait = self.interner.get_trait(the_trait.id);
+                let method_index = the_trait
+                    .methods
+                    .iter()
+                    .position(|method| method.name.0.contents == method_name)?;
+
+                let trait_method = TraitMethodId { trait_id: the_trait.id, method_index };
+                Some(HirMethodReference::TraitMethodId(object_type.clone(), trait_method))
             }
+            Type::TraitAsType(_trait) => None,
+            Type::NamedGeneric(_, _) => self.find_constrained_method(object_type, method_name),
             // Mutable references to another type should resolve to methods of their element type.
             // This may be a struct or a primitive type.
             Type::MutableReference(element) => self
                 .interner
                 .lookup_mut_primitive_trait_method(element.as_ref(), method_name)
                 .map(HirMethodReference::FuncId)
-                .or_else(|| self.lookup_method(element, method_name, expr_id)),
-            // If we fail to resolve the object to a struct type, we have no way of type
-            // checking its arguments as we can't even resolve the name of the function
+                .or_else(|| self.find_method(element, method_name)),
             Type::Error => None,
 
             // In the future we could support methods for non-struct types if we have a context
             // (in the interner?) essentially resembling HashMap<Type, Methods>
-            other => match self
+            other => self
                 .interner
                 .lookup_primitive_method(other, method_name)
                 .or_else(|| self.interner.lookup_primitive_trait_method(other, method_name))
-            {
-                Some(method_id) => Some(HirMethodReference::FuncId(method_id)),
-                None => {
-                    self.errors.push(TypeCheckError::UnresolvedMethodCall {
-                        method_name: method_name.to_string(),
-                        object_type: object_type.clone(),
-                        span: self.interner.expr_span(expr_id),
-                    });
-                    None
-                }
-            },
+                .map(HirMethodReference::FuncId)
+                .or_else(|| self.find_constrained_method(other, method_name)),
+        }
+    }
+
+    /// Finds the method named `method_name` of a trait which the current function's where clause
+    /// requires `object_type` to implement, as in `x.serialize()` where `T: Serialize`.
+    /// The implementation called is only known once the function is monomorphized.
+    fn find_constrained_method(
+        &self,
+        object_type: &Type,
+        method_name: &str,
+    ) -> Option<HirMethodReference> {
+        let func_meta = self.interner.function_meta(&self.current_function?);
+
+        func_meta.trait_constraints.iter().find_map(|constraint| {
+            if *object_type != constraint.typ {
+                return None;
+            }
+            let the_trait = self.interner.get_trait(constraint.trait_id);
+            let method_index = the_trait
+                .methods
+                .iter()
+                .position(|method| method.name.0.contents == method_name)?;
+
+            let trait_method = TraitMethodId { trait_id: constraint.trait_id, method_index };
+            Some(HirMethodReference::TraitMethodId(object_type.clone(), trait_method))
+        })
+    }
+
+    fn push_unresolved_method_call(
+        &mut self,
+        object_type: &Type,
+        method_name: &str,
+        expr_id: &ExprId,
+    ) {
+        let suggestion = self.find_similar_method_name(object_type, method_name);
+        self.errors.push(TypeCheckError::UnresolvedMethodCall {
+            method_name: method_name.to_string(),
+            object_type: object_type.clone(),
+            span: self.interner.expr_span(expr_id),
+            suggestion,
+        });
+    }
+
+    /// With `--unchecked-hints`, replaces a call from a constrained function in the root crate to
+    /// an unconstrained one by its result wrapped in `std::unchecked::Unchecked`, which has to be
+    /// checked before its value is used. Calls returning nothing are left unchanged, as are calls
+    /// whose callee isn't known while type checking, such as trait methods or function parameters.
+    fn wrap_unchecked_result(
+        &mut self,
+        expr_id: &ExprId,
+        call: HirCallExpression,
+        return_type: Type,
+    ) -> Type {
+        let unchecked = match self.interner.get_unchecked_struct() {
+            Some(unchecked) if self.unchecked_hints_enabled() => unchecked,
+            _ => return return_type,
+        };
+        let caller_is_constrained = self
+            .current_function
+            .map_or(false, |caller| !self.interner.function_modifiers(&caller).is_unconstrained);
+        let callee_is_unconstrained = self
+            .called_function(call.func)
+            .map_or(false, |callee| self.interner.function_modifiers(&callee).is_unconstrained);
+        if !caller_is_constrained
+            || !callee_is_unconstrained
+            || return_type.follow_bindings() == Type::Unit
+        {
+            return return_type;
+        }
+
+        let location = self.interner.expr_location(expr_id);
+        let call_id = self.interner.push_expr(HirExpression::Call(call));
+        self.interner.push_expr_location(call_id, location.span, location.file);
+        self.interner.push_expr_type(&call_id, return_type.clone());
+
+        let unchecked = self.interner.get_struct(unchecked);
+        let value = Ident::new("value".to_string(), location.span);
+        let constructor = HirConstructorExpression {
+            r#type: unchecked.clone(),
+            struct_generics: vec![return_type.clone()],
+            fields: vec![(value, call_id)],
+        };
+        self.interner.replace_expr(expr_id, HirExpression::Constructor(constructor));
+        Type::Struct(unchecked, vec![return_type])
+    }
+
+    /// Finds a method callable on `object_type` with a name similar to `method_name`.
+    fn find_similar_method_name(&self, object_type: &Type, method_name: &str) -> Option<String> {
+        match object_type {
+            Type::Struct(typ, _) => {
+                let id = typ.borrow().id;
+                find_similar_name(method_name, self.interner.struct_method_names(id))
+            }
+            Type::TraitObject(the_trait) => {
+                let the_trait = self.interner.get_trait(the_trait.id);
+                let methods =
+                    the_trait.methods.iter().map(|method| method.name.0.contents.as_str());
+                find_similar_name(method_name, methods)
+            }
+            other => find_similar_name(method_name, self.interner.primitive_method_names(other)),
         }
     }
 
@@ -923,7 +1396,13 @@ impl<'interner> TypeChecker<'interner> {
             return Type::Error;
         }
 
-        for (param, (arg, _, arg_span)) in fn_params.iter().zip(callsite_args) {
+        for (param, (arg, arg_id, arg_span)) in fn_params.iter().zip(callsite_args) {
+            if self.implicit_conversions_enabled()
+                && self.try_implicit_conversion(arg, param, *arg_id)
+            {
+                continue;
+            }
+
             self.unify(arg, param, || TypeCheckError::TypeMismatch {
                 expected_typ: param.to_string(),
                 expr_typ: arg.to_string(),
@@ -934,6 +1413,86 @@ impl<'interner> TypeChecker<'interner> {
         fn_ret.clone()
     }
 
+    /// Unchecked hints only apply to the root crate, like implicit conversions, so that
+    /// dependencies are checked the same way whichever package they're compiled for.
+    fn unchecked_hints_enabled(&self) -> bool {
+        self.interner.unchecked_hints()
+            && self
+                .current_function
+                .map_or(false, |func| self.interner.function_module(func).krate.is_root())
+    }
+
+    /// The function called through `func`, when it names one directly or through an immutable
+    /// local variable bound to one.
+    fn called_function(&self, func: ExprId) -> Option<FuncId> {
+        let HirExpression::Ident(ident, _) = self.interner.expression(&func) else {
+            return None;
+        };
+        let definition = self.interner.definition(ident.id);
+        match definition.kind {
+            DefinitionKind::Function(func_id) => Some(func_id),
+            DefinitionKind::Local(Some(value)) if !definition.mutable => {
+                self.called_function(value)
+            }
+            _ => None,
+        }
+    }
+
+    /// Implicit conversions only apply to the root crate, so that dependencies are checked the
+    /// same way whichever package they're compiled for.
+    fn implicit_conversions_enabled(&self) -> bool {
+        self.interner.implicit_conversions()
+            && self
+                .current_function
+                .map_or(false, |func| self.interner.function_module(func).krate.is_root())
+    }
+
+    /// Converts an argument whose type differs from its parameter's with the `From` impl
+    /// between them, if there is exactly one. Returns false if the argument isn't converted.
+    fn try_implicit_conversion(&mut self, arg: &Type, param: &Type, arg_id: ExprId) -> bool {
+        let source = arg.follow_bindings();
+        let target = param.follow_bindings();
+
+        let is_known = |typ: &Type| !matches!(typ, Type::TypeVariable(..) | Type::Error);
+        if !is_known(&source) || !is_known(&target) {
+            return false;
+        }
+
+        // A failed try_unify may still bind some of their variables, so they're unbound again
+        let mut snapshot = source.snapshot_bindings();
+        snapshot.extend(target.snapshot_bindings());
+        if source.try_unify(&target).is_ok() {
+            return false;
+        }
+        snapshot.restore();
+
+        match self.interner.lookup_conversion(&source, &target) {
+            Some(conversion) => {
+                self.interner.push_conversion(arg_id, conversion);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Type checks `object.into()`, where the object's type has no `into` method, as the
+    /// conversion of the object into the type the call is expected to have with a `From` impl.
+    /// The method call is replaced by the object, whose value is then converted.
+    fn check_into_conversion(
+        &mut self,
+        expr_id: &ExprId,
+        object: ExprId,
+        object_type: Type,
+    ) -> Type {
+        let statement = self.interner.push_stmt(HirStatement::Expression(object));
+        let block = HirExpression::Block(HirBlockExpression(vec![statement]));
+        self.interner.replace_expr(expr_id, block);
+
+        let target = self.interner.next_type_variable();
+        self.interner.push_unchecked_conversion(object, object_type, target.clone());
+        target
+    }
+
     fn bind_function_type(
         &mut self,
         function: Type,
@@ -970,6 +1529,50 @@ impl<'interner> TypeChecker<'interner> {
         }
     }
 
+    /// Type checks an infix expression whose left operand is a struct as a call to the trait
+    /// method overloading its operator, e.g. `a + b` as `std::ops::Add::add(a, b)`.
+    /// Returns None if the operator cannot be overloaded for these operands.
+    fn check_operator_overload(
+        &mut self,
+        expr_id: &ExprId,
+        infix_expr: &HirInfixExpression,
+        lhs_type: &Type,
+        rhs_type: &Type,
+        span: Span,
+    ) -> Option<Type> {
+        let object_type = lhs_type.follow_bindings();
+        if !matches!(object_type, Type::Struct(..)) {
+            return None;
+        }
+
+        let method = self.interner.get_operator_trait_method(infix_expr.operator.kind)?;
+        let the_trait = self.interner.get_trait(method.trait_id);
+        let function_type = the_trait.method_type(method.method_index, &object_type);
+        let (function_type, bindings) = function_type.instantiate(self.interner);
+        self.interner.store_instantiation_bindings(*expr_id, bindings);
+        self.interner.set_operator_overload(*expr_id, method, function_type.clone());
+
+        let constraint = TraitConstraint {
+            typ: object_type,
+            trait_id: method.trait_id,
+            trait_generics: Vec::new(),
+        };
+        self.trait_constraints.push((constraint, span));
+
+        let args = vec![
+            (lhs_type.clone(), infix_expr.lhs, self.interner.expr_span(&infix_expr.lhs)),
+            (rhs_type.clone(), infix_expr.rhs, self.interner.expr_span(&infix_expr.rhs)),
+        ];
+        let return_type = self.bind_function_type(function_type, args, span);
+
+        // Comparison operators always produce a bool, even though `Ord::cmp` returns an `Ordering`
+        if infix_expr.operator.kind.is_comparator() {
+            Some(Type::Bool)
+        } else {
+            Some(return_type)
+        }
+    }
+
     // Given a binary operator and another type. This method will produce the output type
     fn infix_operand_type_rules(
         &mut self,
@@ -1140,6 +1743,29 @@ impl<'interner> TypeChecker<'interner> {
     }
 }
 
+/// Whether the function type takes its first parameter, `self` for methods, by `&mut`
+fn takes_mutable_reference(function_type: &Type) -> bool {
+    let parameters = match function_type {
+        Type::Function(parameters, _, _) => parameters,
+        Type::Forall(_, typ) => match typ.as_ref() {
+            Type::Function(parameters, _, _) => parameters,
+            _ => return false,
+        },
+        _ => return false,
+    };
+    matches!(parameters.first().map(Type::follow_bindings), Some(Type::MutableReference(_)))
+}
+
+fn lvalue_contains_index(lvalue: &HirLValue) -> bool {
+    match lvalue {
+        HirLValue::Ident(..) => false,
+        HirLValue::Index { .. } => true,
+        HirLValue::MemberAccess { object: lvalue, .. } | HirLValue::Dereference { lvalue, .. } => {
+            lvalue_contains_index(lvalue)
+        }
+    }
+}
+
 /// Taken from: https://stackoverflow.com/a/47127500
 fn sort_by_key_ref<T, F, K>(xs: &mut [T], key: F)
 where
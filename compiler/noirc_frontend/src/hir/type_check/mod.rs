@@ -13,10 +13,20 @@ mod stmt;
 
 pub use errors::TypeCheckError;
 
+use std::collections::HashMap;
+
+use iter_extended::vecmap;
+use noirc_errors::Span;
+use num_bigint::BigUint;
+
 use crate::{
-    hir_def::{expr::HirExpression, stmt::HirStatement},
-    node_interner::{ExprId, FuncId, NodeInterner, StmtId, TraitImplKey},
-    Type,
+    hir_def::{
+        expr::{HirExpression, HirLiteral},
+        stmt::HirStatement,
+        traits::TraitConstraint,
+    },
+    node_interner::{DefinitionId, ExprId, FuncId, NodeInterner, StmtId},
+    Signedness, Type,
 };
 
 use self::errors::Source;
@@ -28,11 +38,25 @@ pub struct TypeChecker<'interner> {
     interner: &'interner mut NodeInterner,
     errors: Vec<TypeCheckError>,
     current_function: Option<FuncId>,
+
+    /// Trait constraints of each generic function referenced in the current function,
+    /// instantiated at the point of reference. These are verified once the whole body
+    /// has been type checked and the instantiated types are known.
+    trait_constraints: Vec<(TraitConstraint, Span)>,
+
+    /// Each integer literal in the current function. Their values are checked against
+    /// their types once the whole body has been type checked.
+    integer_literals: Vec<ExprId>,
+
+    /// Where each local variable of the current function was declared, so that assigning to
+    /// an immutable one can suggest where to add `mut`.
+    local_declarations: HashMap<DefinitionId, Span>,
 }
 
 /// Type checks a function and assigns the
 /// appropriate types to expressions in a side table
 pub fn type_check_func(interner: &mut NodeInterner, func_id: FuncId) -> Vec<TypeCheckError> {
+    interner.cancellation_token().check();
     let meta = interner.function_meta(&func_id);
     let declared_return_type = meta.return_type().clone();
     let can_ignore_ret = meta.can_ignore_return_type();
@@ -65,8 +89,8 @@ pub fn type_check_func(interner: &mut NodeInterner, func_id: FuncId) -> Vec<Type
         let (expr_span, empty_function) = function_info(interner, function_body_id);
         let func_span = interner.expr_span(function_body_id); // XXX: We could be more specific and return the span of the last stmt, however stmts do not have spans yet
         if let Type::TraitAsType(t) = &declared_return_type {
-            let key = TraitImplKey { typ: function_last_type.follow_bindings(), trait_id: t.id };
-            if interner.get_trait_implementation(&key).is_none() {
+            let typ = function_last_type.follow_bindings();
+            if interner.lookup_trait_implementation(&typ, t.id, &[]).is_none() {
                 let error = TypeCheckError::TypeMismatchWithSource {
                     expected: declared_return_type.clone(),
                     actual: function_last_type.clone(),
@@ -100,9 +124,75 @@ pub fn type_check_func(interner: &mut NodeInterner, func_id: FuncId) -> Vec<Type
         }
     }
 
+    check_trait_object_coercions(interner, &mut errors);
+    check_conversions(interner, &mut errors);
     errors
 }
 
+/// Finds the `From` impl called by each `x.into()` within the function being checked, now that
+/// the types converted into are known. Converting a value into its own type leaves it unchanged.
+fn check_conversions(interner: &mut NodeInterner, errors: &mut Vec<TypeCheckError>) {
+    for (expr_id, source, target) in interner.take_unchecked_conversions() {
+        let source = source.follow_bindings();
+        let target = target.follow_bindings();
+        let span = interner.expr_span(&expr_id);
+
+        match (&source, &target) {
+            (Type::Error, _) | (_, Type::Error) => continue,
+            (Type::TypeVariable(..), _) | (_, Type::TypeVariable(..)) => {
+                errors.push(TypeCheckError::TypeAnnotationsNeeded { span });
+                continue;
+            }
+            _ => (),
+        }
+
+        // A failed try_unify may still bind some of their variables, so they're unbound again
+        let mut snapshot = source.snapshot_bindings();
+        snapshot.extend(target.snapshot_bindings());
+        if source.try_unify(&target).is_ok() {
+            continue;
+        }
+        snapshot.restore();
+
+        match interner.lookup_conversion(&source, &target) {
+            Some(conversion) => interner.push_conversion(expr_id, conversion),
+            None => {
+                let trait_name = format!("From<{source}>");
+                errors.push(TypeCheckError::NoMatchingImplFound { typ: target, trait_name, span });
+            }
+        }
+    }
+}
+
+/// Checks that each trait object created within the function being checked is of a trait which
+/// can be used as a trait object, and that the type of the value converted into it is concrete.
+/// Each such type is recorded as one of the types trait objects of that trait may hold.
+fn check_trait_object_coercions(interner: &mut NodeInterner, errors: &mut Vec<TypeCheckError>) {
+    for expr_id in interner.take_unchecked_trait_object_coercions() {
+        let (trait_id, typ) = interner
+            .get_trait_object_coercion(expr_id)
+            .cloned()
+            .expect("ICE: Unchecked trait object coercions should always be recorded");
+
+        let span = interner.expr_span(&expr_id);
+        let the_trait = interner.get_trait(trait_id);
+        let trait_name = the_trait.name.to_string();
+
+        if let Some(reason) = the_trait.object_safety_violation() {
+            errors.push(TypeCheckError::TraitNotObjectSafe { trait_name, reason, span });
+            continue;
+        }
+
+        match typ.concrete() {
+            Some(typ) => interner.add_trait_object_type(trait_id, typ),
+            None => {
+                let typ = typ.follow_bindings();
+                errors.push(TypeCheckError::TraitObjectOfNonConcreteType { trait_name, typ, span });
+            }
+        }
+    }
+}
+
 fn function_info(interner: &NodeInterner, function_body_id: &ExprId) -> (noirc_errors::Span, bool) {
     let (expr_span, empty_function) =
         if let HirExpression::Block(block) = interner.expression(function_body_id) {
@@ -124,7 +214,15 @@ fn function_info(interner: &NodeInterner, function_body_id: &ExprId) -> (noirc_e
 
 impl<'interner> TypeChecker<'interner> {
     fn new(interner: &'interner mut NodeInterner) -> Self {
-        Self { delayed_type_checks: Vec::new(), interner, errors: vec![], current_function: None }
+        Self {
+            delayed_type_checks: Vec::new(),
+            interner,
+            errors: vec![],
+            current_function: None,
+            trait_constraints: Vec::new(),
+            integer_literals: Vec::new(),
+            local_declarations: HashMap::new(),
+        }
     }
 
     pub fn push_delayed_type_check(&mut self, f: TypeCheckFn) {
@@ -136,20 +234,135 @@ impl<'interner> TypeChecker<'interner> {
         body: &ExprId,
     ) -> (Type, Vec<TypeCheckFn>, Vec<TypeCheckError>) {
         let body_type = self.check_expression(body);
+        self.verify_trait_constraints();
+        self.verify_integer_literals();
         (body_type, self.delayed_type_checks, self.errors)
     }
 
+    /// Check that each trait constraint required by a generic function referenced in this
+    /// function is satisfied by the types it was instantiated with.
+    fn verify_trait_constraints(&mut self) {
+        for (constraint, span) in std::mem::take(&mut self.trait_constraints) {
+            let typ = constraint.typ.follow_bindings();
+            let generics = vecmap(&constraint.trait_generics, Type::follow_bindings);
+
+            let satisfied = match &typ {
+                // The type isn't known yet, so there's nothing to check it against.
+                Type::TypeVariable(..) | Type::Error => true,
+                // A generic only satisfies a constraint if the current function requires it.
+                Type::NamedGeneric(..) => match self.current_function {
+                    Some(function) => {
+                        let meta = self.interner.function_meta(&function);
+                        meta.trait_constraints.iter().any(|current| {
+                            current.trait_id == constraint.trait_id
+                                && current.typ == typ
+                                && (generics.is_empty() || current.trait_generics == generics)
+                        })
+                    }
+                    None => false,
+                },
+                _ => self.interner.implements_trait(&typ, constraint.trait_id, &generics),
+            };
+
+            if !satisfied {
+                let mut trait_name = self.interner.get_trait(constraint.trait_id).name.to_string();
+                if !generics.is_empty() {
+                    let generics = vecmap(&generics, ToString::to_string);
+                    trait_name = format!("{trait_name}<{}>", generics.join(", "));
+                }
+                self.errors.push(TypeCheckError::NoMatchingImplFound { typ, trait_name, span });
+            }
+        }
+    }
+
+    /// Check that each integer literal in this function fits into the integer type it was
+    /// inferred to have. Literals of type `Field` are already checked by the lexer.
+    fn verify_integer_literals(&mut self) {
+        for expr_id in std::mem::take(&mut self.integer_literals) {
+            let HirExpression::Literal(HirLiteral::Integer(value, negative)) =
+                self.interner.expression(&expr_id)
+            else {
+                continue;
+            };
+            let typ = self.interner.id_type(expr_id).follow_bindings();
+            let Type::Integer(sign, bit_size) = typ else {
+                continue;
+            };
+
+            let value = BigUint::from_bytes_be(&value.to_be_bytes());
+            let (min, max) = match sign {
+                Signedness::Unsigned => {
+                    (BigUint::from(0u32), (BigUint::from(1u32) << bit_size) - 1u32)
+                }
+                Signedness::Signed if bit_size == 0 => (BigUint::from(0u32), BigUint::from(0u32)),
+                Signedness::Signed => {
+                    let min = BigUint::from(1u32) << (bit_size - 1);
+                    let max = &min - 1u32;
+                    (min, max)
+                }
+            };
+
+            let (literal, limit) =
+                if negative { (format!("-{value}"), &min) } else { (value.to_string(), &max) };
+
+            // Zero is the only negative literal which fits into an unsigned type
+            if value > *limit {
+                let range = match sign {
+                    Signedness::Unsigned => format!("0..={max}"),
+                    Signedness::Signed => format!("-{min}..={max}"),
+                };
+                let span = self.interner.expr_span(&expr_id);
+                let error = TypeCheckError::OverflowingLiteral { literal, ty: typ, range, span };
+                self.errors.push(error);
+            }
+        }
+    }
+
     pub fn check_global(id: &StmtId, interner: &'interner mut NodeInterner) -> Vec<TypeCheckError> {
         let mut this = Self {
             delayed_type_checks: Vec::new(),
             interner,
             errors: vec![],
             current_function: None,
+            trait_constraints: Vec::new(),
+            integer_literals: Vec::new(),
+            local_declarations: HashMap::new(),
         };
         this.check_statement(id);
+        this.verify_integer_literals();
+        check_conversions(this.interner, &mut this.errors);
+        this.errors
+    }
+
+    /// Type checks the compile-time assertion a `static_assert` item was resolved to.
+    pub fn check_static_assert(
+        id: &ExprId,
+        interner: &'interner mut NodeInterner,
+    ) -> Vec<TypeCheckError> {
+        let mut this = Self {
+            delayed_type_checks: Vec::new(),
+            interner,
+            errors: vec![],
+            current_function: None,
+            trait_constraints: Vec::new(),
+            integer_literals: Vec::new(),
+            local_declarations: HashMap::new(),
+        };
+        this.check_expression(id);
+        this.verify_integer_literals();
+        check_conversions(this.interner, &mut this.errors);
         this.errors
     }
 
+    /// True if the types of both expressions are fully known and equal. Comparing interned
+    /// types is constant time, so this is checked before walking both types to unify them.
+    fn have_equal_types(&mut self, lhs: ExprId, rhs: ExprId) -> bool {
+        match (self.interner.id_interned_type(lhs), self.interner.id_interned_type(rhs)) {
+            (Some(lhs), Some(rhs)) => lhs == rhs,
+            _ => false,
+        }
+    }
+
     /// Wrapper of Type::unify using self.errors
     fn unify(
         &mut self,
@@ -183,6 +396,7 @@ impl<'interner> TypeChecker<'interner> {
 #[cfg(test)]
 mod test {
     use std::collections::{BTreeMap, HashMap};
+    use std::rc::Rc;
     use std::vec;
 
     use fm::FileId;
@@ -195,7 +409,11 @@ mod test {
     use crate::hir_def::expr::HirIdent;
     use crate::hir_def::stmt::HirLetStatement;
     use crate::hir_def::stmt::HirPattern::Identifier;
-    use crate::hir_def::types::Type;
+    use crate::hir_def::type_interner::TypeInterner;
+    use crate::hir_def::types::{
+        BinaryTypeOperator, Shared, Type, TypeBinding, TypeDisplayOptions, TypeVariableId,
+        TypeVariableKind,
+    };
     use crate::hir_def::{
         expr::{HirBinaryOp, HirBlockExpression, HirExpression, HirInfixExpression},
         function::{FuncMeta, HirFunction},
@@ -236,8 +454,8 @@ mod test {
         let z = HirIdent { id: z_id, location };
 
         // Push x and y as expressions
-        let x_expr_id = interner.push_expr(HirExpression::Ident(x));
-        let y_expr_id = interner.push_expr(HirExpression::Ident(y));
+        let x_expr_id = interner.push_expr(HirExpression::Ident(x, None));
+        let y_expr_id = interner.push_expr(HirExpression::Ident(y, None));
 
         // Create Infix
         let operator = HirBinaryOp { location, kind: BinaryOpKind::Add };
@@ -287,6 +505,7 @@ mod test {
             has_body: true,
             return_type: FunctionReturnType::Default(Span::default()),
             trait_constraints: Vec::new(),
+            numeric_constraints: Vec::new(),
         };
         interner.push_fn_meta(func_meta, func_id);
 
@@ -375,6 +594,78 @@ mod test {
 
         type_check_src_code(src, vec![String::from("main")]);
     }
+
+    #[test]
+    fn shared_bindings_are_only_traversed_once() {
+        // Each level refers to the binding of the level below it twice, so walking the
+        // type naively would visit 2^64 leaves.
+        let mut typ = Type::FieldElement;
+        for _ in 0..64 {
+            let binding = Shared::new(TypeBinding::Bound(typ));
+            let variable = Type::TypeVariable(binding, TypeVariableKind::Normal);
+            typ = Type::Tuple(vec![variable.clone(), variable]);
+        }
+
+        assert!(!typ.occurs(TypeVariableId(0)));
+        assert!(typ.try_unify(&typ.clone()).is_ok());
+        assert_eq!(typ, typ.clone());
+    }
+
+    #[test]
+    fn only_fully_known_types_are_interned() {
+        let array = |len, elem| Type::Array(Box::new(len), Box::new(elem));
+        let nested =
+            |depth| (0..depth).fold(Type::FieldElement, |typ, _| array(Type::Constant(2), typ));
+        let variable = |binding, kind| Type::TypeVariable(Shared::new(binding), kind);
+
+        let mut types = TypeInterner::default();
+        let bound = variable(TypeBinding::Bound(nested(32)), TypeVariableKind::Normal);
+        assert!(types.intern(&nested(32)).is_some());
+        assert_eq!(types.intern(&nested(32)), types.intern(&bound));
+        assert_ne!(types.intern(&nested(32)), types.intern(&nested(31)));
+
+        // Unbound type variables may still be bound to any type, and variables for array
+        // lengths only default to their length
+        let unbound = variable(TypeBinding::Unbound(TypeVariableId(0)), TypeVariableKind::Normal);
+        let length =
+            variable(TypeBinding::Unbound(TypeVariableId(1)), TypeVariableKind::Constant(2));
+        assert_eq!(types.intern(&array(Type::Constant(2), unbound)), None);
+        assert_eq!(types.intern(&array(length, Type::FieldElement)), None);
+    }
+
+    #[test]
+    fn types_are_displayed_with_evaluated_numbers_and_abbreviated() {
+        let length = Type::InfixExpr(
+            Box::new(Type::Constant(2)),
+            BinaryTypeOperator::Multiplication,
+            Box::new(Type::Constant(3)),
+        );
+        let array = Type::Array(Box::new(length), Box::new(Type::FieldElement));
+        assert_eq!(array.to_string(), "[Field; 6]");
+
+        let generic = Type::NamedGeneric(
+            Shared::new(TypeBinding::Unbound(TypeVariableId(0))),
+            Rc::new("N".to_string()),
+        );
+        let unevaluated = Type::InfixExpr(
+            Box::new(generic),
+            BinaryTypeOperator::Addition,
+            Box::new(Type::Constant(1)),
+        );
+        assert_eq!(unevaluated.to_string(), "N + 1");
+
+        let tuple = Type::Tuple(vec![Type::Bool; 8]);
+        let nested = Type::Tuple(vec![Type::Tuple(vec![Type::Tuple(vec![Type::Tuple(vec![
+            tuple.clone(),
+        ])])])]);
+        assert_eq!(tuple.to_string(), "(bool, bool, bool, bool, bool, bool, bool, bool)");
+        assert_eq!(
+            tuple.display(TypeDisplayOptions::ABBREVIATED).to_string(),
+            "(bool, bool, bool, bool, bool, ..)"
+        );
+        assert_eq!(nested.display(TypeDisplayOptions::ABBREVIATED).to_string(), "((((..))))");
+    }
+
     // This is the same Stub that is in the resolver, maybe we can pull this out into a test module and re-use?
     struct TestPathResolver(HashMap<String, ModuleDefId>);
 
@@ -454,6 +745,7 @@ mod test {
                 modules,
                 krate: CrateId::dummy_id(),
                 extern_prelude: BTreeMap::new(),
+                prelude: None,
             },
         );
 
@@ -1,10 +1,10 @@
 use iter_extended::vecmap;
-use noirc_errors::{Location, Span};
+use noirc_errors::{Edit, Fix, Location, Span};
 
-use crate::hir_def::expr::{HirExpression, HirIdent, HirLiteral};
+use crate::hir_def::expr::{HirExpression, HirIdent};
 use crate::hir_def::stmt::{
-    HirAssignStatement, HirConstrainStatement, HirForStatement, HirLValue, HirLetStatement,
-    HirPattern, HirStatement,
+    HirAssertMessage, HirAssignStatement, HirConstrainStatement, HirForStatement, HirLValue,
+    HirLetStatement, HirPattern, HirStatement,
 };
 use crate::hir_def::types::Type;
 use crate::node_interner::{DefinitionId, ExprId, StmtId};
@@ -48,20 +48,48 @@ impl<'interner> TypeChecker<'interner> {
                 self.check_expression(&expr_id);
             }
             HirStatement::Let(let_stmt) => self.check_let_stmt(let_stmt),
-            HirStatement::Constrain(constrain_stmt) => self.check_constrain_stmt(constrain_stmt),
+            HirStatement::Constrain(constrain_stmt) => {
+                let always_fails = constrain_stmt.always_fails(self.interner);
+                self.check_constrain_stmt(constrain_stmt);
+                // The never type is represented by a fresh type variable, which unifies with
+                // the type of whatever value the statement is used as, such as an `if` branch
+                if always_fails {
+                    return self.interner.next_type_variable();
+                }
+            }
             HirStatement::Assign(assign_stmt) => self.check_assign_stmt(assign_stmt, stmt_id),
             HirStatement::For(for_loop) => self.check_for_loop(for_loop),
-            HirStatement::Error => (),
+            HirStatement::Loop(block) => {
+                self.check_expression(&block);
+            }
+            HirStatement::Break | HirStatement::Error => (),
         }
         Type::Unit
     }
 
     fn check_for_loop(&mut self, for_loop: HirForStatement) {
-        let start_range_type = self.check_expression(&for_loop.start_range);
-        let end_range_type = self.check_expression(&for_loop.end_range);
+        let context = "The range of a loop must be known at compile-time";
+        let range_type =
+            self.check_range(&for_loop.start_range, &for_loop.end_range, "for loop", context);
+
+        self.interner.push_definition_type(for_loop.identifier.id, range_type);
+
+        self.check_expression(&for_loop.block);
+    }
 
-        let start_span = self.interner.expr_span(&for_loop.start_range);
-        let end_span = self.interner.expr_span(&for_loop.end_range);
+    /// Checks the bounds of a `start..end` range, returning the integer type they share.
+    pub(crate) fn check_range(
+        &mut self,
+        start_range: &ExprId,
+        end_range: &ExprId,
+        place: &'static str,
+        context: &'static str,
+    ) -> Type {
+        let start_range_type = self.check_expression(start_range);
+        let end_range_type = self.check_expression(end_range);
+
+        let start_span = self.interner.expr_span(start_range);
+        let end_span = self.interner.expr_span(end_range);
 
         // Check that start range and end range have the same types
         let range_span = start_span.merge(end_span);
@@ -78,22 +106,23 @@ impl<'interner> TypeChecker<'interner> {
         self.unify(&start_range_type, &expected_type, || {
             TypeCheckError::TypeCannotBeUsed {
                 typ: start_range_type.clone(),
-                place: "for loop",
+                place,
                 span: range_span,
             }
-            .add_context("The range of a loop must be known at compile-time")
+            .add_context(context)
         });
 
-        self.interner.push_definition_type(for_loop.identifier.id, start_range_type);
-
-        self.check_expression(&for_loop.block);
+        start_range_type
     }
 
     /// Associate a given HirPattern with the given Type, and remember
     /// this association in the NodeInterner.
     pub(crate) fn bind_pattern(&mut self, pattern: &HirPattern, typ: Type) {
         match pattern {
-            HirPattern::Identifier(ident) => self.interner.push_definition_type(ident.id, typ),
+            HirPattern::Identifier(ident) => {
+                self.local_declarations.insert(ident.id, ident.location.span);
+                self.interner.push_definition_type(ident.id, typ);
+            }
             HirPattern::Mutable(pattern, _) => self.bind_pattern(pattern, typ),
             HirPattern::Tuple(fields, span) => match typ {
                 Type::Tuple(field_types) if field_types.len() == fields.len() => {
@@ -140,12 +169,37 @@ impl<'interner> TypeChecker<'interner> {
     fn check_assign_stmt(&mut self, assign_stmt: HirAssignStatement, stmt_id: &StmtId) {
         let expr_type = self.check_expression(&assign_stmt.expression);
         let span = self.interner.expr_span(&assign_stmt.expression);
-        let (lvalue_type, new_lvalue, mutable) = self.check_lvalue(&assign_stmt.lvalue, span);
 
-        if !mutable {
-            let (name, span) = self.get_lvalue_name_and_span(&assign_stmt.lvalue);
-            self.errors.push(TypeCheckError::VariableMustBeMutable { name, span });
-        }
+        let (lvalue_type, new_lvalue, mutable) = match &assign_stmt.lvalue {
+            HirLValue::Index { array, index, .. } => {
+                let collection = self.check_lvalue(array, span);
+                let (collection_type, collection, mutable) = dereference_lvalue(collection);
+
+                // Assigning to an index of a struct calls its `IndexMut` impl instead
+                if matches!(collection_type.follow_bindings(), Type::Struct(..))
+                    && self.interner.get_index_mut_trait_method().is_some()
+                {
+                    self.check_lvalue_mutable(&assign_stmt.lvalue, mutable);
+                    let (_, name_span) = self.get_lvalue_name_and_span(&assign_stmt.lvalue);
+                    let value = (expr_type, assign_stmt.expression);
+                    let call = self.check_index_assign_overload(
+                        collection,
+                        collection_type,
+                        *index,
+                        value,
+                        name_span.merge(span),
+                    );
+                    if let Some(call) = call {
+                        self.interner
+                            .update_statement(stmt_id, |stmt| *stmt = HirStatement::Semi(call));
+                    }
+                    return;
+                }
+                self.check_index_lvalue((collection_type, collection, mutable), *index, span)
+            }
+            lvalue => self.check_lvalue(lvalue, span),
+        };
+        self.check_lvalue_mutable(&assign_stmt.lvalue, mutable);
 
         // Must push new lvalue to the interner, we've resolved any field indices
         self.interner.update_statement(stmt_id, |stmt| match stmt {
@@ -154,16 +208,77 @@ impl<'interner> TypeChecker<'interner> {
         });
 
         let span = self.interner.expr_span(&assign_stmt.expression);
+        let cast_fix = self.cast_fix(assign_stmt.expression, &expr_type, &lvalue_type);
         self.unify_with_coercions(&expr_type, &lvalue_type, assign_stmt.expression, || {
-            TypeCheckError::TypeMismatchWithSource {
+            let error = TypeCheckError::TypeMismatchWithSource {
                 actual: expr_type.clone(),
                 expected: lvalue_type.clone(),
                 span,
                 source: Source::Assignment,
+            };
+            match cast_fix {
+                Some(fix) => error.add_fix(fix),
+                None => error,
             }
         });
     }
 
+    /// Suggests casting `expr` to the `expected` type if the cast can't change its value, e.g.
+    /// when assigning a `u8` to a `u32` variable. Narrowing casts truncate so they are never
+    /// suggested.
+    fn cast_fix(&self, expr: ExprId, actual: &Type, expected: &Type) -> Option<Fix> {
+        use crate::Signedness::{Signed, Unsigned};
+        let is_widening = match (actual.follow_bindings(), expected.follow_bindings()) {
+            (Type::Integer(Unsigned, _), Type::FieldElement) => true,
+            (Type::Integer(from_sign, from_bits), Type::Integer(to_sign, to_bits)) => {
+                match (from_sign, to_sign) {
+                    (Unsigned, Signed) => from_bits < to_bits,
+                    (Signed, Unsigned) => false,
+                    _ => from_bits <= to_bits,
+                }
+            }
+            _ => false,
+        };
+        if !is_widening {
+            return None;
+        }
+
+        let span = self.interner.expr_span(&expr);
+        let message = format!("cast to `{expected}`");
+        let cast = format!(" as {expected}");
+
+        // `as` binds tighter than the other operators so compound expressions are
+        // parenthesized first.
+        let fix = match self.interner.expression(&expr) {
+            HirExpression::Ident(..)
+            | HirExpression::Literal(_)
+            | HirExpression::Index(_)
+            | HirExpression::MemberAccess(_)
+            | HirExpression::Call(_)
+            | HirExpression::MethodCall(_) => Fix::insert(message, span.end(), cast),
+            _ => {
+                let open = Edit::insert(span.start(), "(".into());
+                let close = Edit::insert(span.end(), format!("){cast}"));
+                Fix::new(message, vec![open, close])
+            }
+        };
+        Some(fix)
+    }
+
+    fn check_lvalue_mutable(&mut self, lvalue: &HirLValue, mutable: bool) {
+        if !mutable {
+            let (name, span) = self.get_lvalue_name_and_span(lvalue);
+            let declaration = self.local_declarations.get(&lvalue_ident(lvalue).id);
+
+            let mut error = TypeCheckError::VariableMustBeMutable { name: name.clone(), span };
+            if let Some(declaration) = declaration {
+                let message = format!("make `{name}` mutable");
+                error = error.add_fix(Fix::insert(message, declaration.start(), "mut ".into()));
+            }
+            self.errors.push(error);
+        }
+    }
+
     fn get_lvalue_name_and_span(&self, lvalue: &HirLValue) -> (String, Span) {
         match lvalue {
             HirLValue::Ident(name, _) => {
@@ -235,48 +350,9 @@ impl<'interner> TypeChecker<'interner> {
                 (object_type, lvalue, mutable)
             }
             HirLValue::Index { array, index, .. } => {
-                let index_type = self.check_expression(index);
-                let expr_span = self.interner.expr_span(index);
-
-                index_type.unify(
-                    &Type::polymorphic_integer(self.interner),
-                    &mut self.errors,
-                    || TypeCheckError::TypeMismatch {
-                        expected_typ: "an integer".to_owned(),
-                        expr_typ: index_type.to_string(),
-                        expr_span,
-                    },
-                );
-
-                let (mut lvalue_type, mut lvalue, mut mutable) =
-                    self.check_lvalue(array, assign_span);
-
-                // Before we check that the lvalue is an array, try to dereference it as many times
-                // as needed to unwrap any &mut wrappers.
-                while let Type::MutableReference(element) = lvalue_type.follow_bindings() {
-                    let element_type = element.as_ref().clone();
-                    lvalue = HirLValue::Dereference { lvalue: Box::new(lvalue), element_type };
-                    lvalue_type = *element;
-                    // We know this value to be mutable now since we found an `&mut`
-                    mutable = true;
-                }
-
-                let typ = match lvalue_type.follow_bindings() {
-                    Type::Array(_, elem_type) => *elem_type,
-                    Type::Error => Type::Error,
-                    other => {
-                        // TODO: Need a better span here
-                        self.errors.push(TypeCheckError::TypeMismatch {
-                            expected_typ: "array".to_string(),
-                            expr_typ: other.to_string(),
-                            expr_span: assign_span,
-                        });
-                        Type::Error
-                    }
-                };
-
-                let array = Box::new(lvalue);
-                (typ.clone(), HirLValue::Index { array, index: *index, typ }, mutable)
+                let collection = self.check_lvalue(array, assign_span);
+                let collection = dereference_lvalue(collection);
+                self.check_index_lvalue(collection, *index, assign_span)
             }
             HirLValue::Dereference { lvalue, element_type: _ } => {
                 let (reference_type, lvalue, _) = self.check_lvalue(lvalue, assign_span);
@@ -297,6 +373,42 @@ impl<'interner> TypeChecker<'interner> {
         }
     }
 
+    /// Type check the lvalue `array[index]`, given the already checked `array`.
+    fn check_index_lvalue(
+        &mut self,
+        (lvalue_type, lvalue, mutable): (Type, HirLValue, bool),
+        index: ExprId,
+        assign_span: Span,
+    ) -> (Type, HirLValue, bool) {
+        let index_type = self.check_expression(&index);
+        let expr_span = self.interner.expr_span(&index);
+
+        index_type.unify(&Type::polymorphic_integer(self.interner), &mut self.errors, || {
+            TypeCheckError::TypeMismatch {
+                expected_typ: "an integer".to_owned(),
+                expr_typ: index_type.to_string(),
+                expr_span,
+            }
+        });
+
+        let typ = match lvalue_type.follow_bindings() {
+            Type::Array(_, elem_type) => *elem_type,
+            Type::Error => Type::Error,
+            other => {
+                // TODO: Need a better span here
+                self.errors.push(TypeCheckError::TypeMismatch {
+                    expected_typ: "array".to_string(),
+                    expr_typ: other.to_string(),
+                    expr_span: assign_span,
+                });
+                Type::Error
+            }
+        };
+
+        let array = Box::new(lvalue);
+        (typ.clone(), HirLValue::Index { array, index, typ }, mutable)
+    }
+
     fn check_let_stmt(&mut self, let_stmt: HirLetStatement) {
         let resolved_type = self.check_declaration(let_stmt.expression, let_stmt.r#type);
 
@@ -305,7 +417,15 @@ impl<'interner> TypeChecker<'interner> {
     }
 
     fn check_constrain_stmt(&mut self, stmt: HirConstrainStatement) {
-        let expr_type = self.check_expression(&stmt.0);
+        // A formatted message is resolved by a call which is also passed the condition, so the
+        // condition is checked as part of that call
+        let expr_type = match stmt.2 {
+            Some(HirAssertMessage::Dynamic(message)) => {
+                self.check_expression(&message);
+                self.interner.id_type(stmt.0)
+            }
+            _ => self.check_expression(&stmt.0),
+        };
         let expr_span = self.interner.expr_span(&stmt.0);
 
         self.unify(&expr_type, &Type::Bool, || TypeCheckError::TypeMismatch {
@@ -328,15 +448,20 @@ impl<'interner> TypeChecker<'interner> {
             // Now check if LHS is the same type as the RHS
             // Importantly, we do not coerce any types implicitly
             let expr_span = self.interner.expr_span(&rhs_expr);
+            let cast_fix = self.cast_fix(rhs_expr, &expr_type, &annotated_type);
             self.unify_with_coercions(&expr_type, &annotated_type, rhs_expr, || {
-                TypeCheckError::TypeMismatch {
+                let error = TypeCheckError::TypeMismatch {
                     expected_typ: annotated_type.to_string(),
                     expr_typ: expr_type.to_string(),
                     expr_span,
+                };
+                match cast_fix {
+                    Some(fix) => error.add_fix(fix),
+                    None => error,
                 }
             });
             if annotated_type.is_unsigned() {
-                self.lint_overflowing_uint(&rhs_expr, &annotated_type);
+                self.lint_unary_ops_on_uint(&rhs_expr, &annotated_type);
             }
             annotated_type
         } else {
@@ -344,34 +469,45 @@ impl<'interner> TypeChecker<'interner> {
         }
     }
 
-    /// Check if an assignment is overflowing with respect to `annotated_type`
-    /// in a declaration statement where `annotated_type` is an unsigned integer
-    fn lint_overflowing_uint(&mut self, rhs_expr: &ExprId, annotated_type: &Type) {
+    /// Check that no unary operators are used on the RHS of a declaration statement
+    /// where `annotated_type` is an unsigned integer
+    fn lint_unary_ops_on_uint(&mut self, rhs_expr: &ExprId, annotated_type: &Type) {
         let expr = self.interner.expression(rhs_expr);
         let span = self.interner.expr_span(rhs_expr);
         match expr {
-            HirExpression::Literal(HirLiteral::Integer(value)) => {
-                let v = value.to_u128();
-                if let Type::Integer(_, bit_count) = annotated_type {
-                    let max = 1 << bit_count;
-                    if v >= max {
-                        self.errors.push(TypeCheckError::OverflowingAssignment {
-                            expr: value,
-                            ty: annotated_type.clone(),
-                            range: format!("0..={}", max - 1),
-                            span,
-                        });
-                    };
-                };
-            }
             HirExpression::Prefix(_) => self
                 .errors
                 .push(TypeCheckError::InvalidUnaryOp { kind: annotated_type.to_string(), span }),
             HirExpression::Infix(expr) => {
-                self.lint_overflowing_uint(&expr.lhs, annotated_type);
-                self.lint_overflowing_uint(&expr.rhs, annotated_type);
+                self.lint_unary_ops_on_uint(&expr.lhs, annotated_type);
+                self.lint_unary_ops_on_uint(&expr.rhs, annotated_type);
             }
             _ => {}
         }
     }
 }
+
+/// The variable at the root of an lvalue, e.g. `a` in `a.b[c]`.
+/// Before we check that an indexed lvalue is an array, dereference it as many times as needed
+/// to unwrap any &mut wrappers.
+fn dereference_lvalue(
+    (mut lvalue_type, mut lvalue, mut mutable): (Type, HirLValue, bool),
+) -> (Type, HirLValue, bool) {
+    while let Type::MutableReference(element) = lvalue_type.follow_bindings() {
+        let element_type = element.as_ref().clone();
+        lvalue = HirLValue::Dereference { lvalue: Box::new(lvalue), element_type };
+        lvalue_type = *element;
+        // We know this value to be mutable now since we found an `&mut`
+        mutable = true;
+    }
+    (lvalue_type, lvalue, mutable)
+}
+
+fn lvalue_ident(lvalue: &HirLValue) -> &HirIdent {
+    match lvalue {
+        HirLValue::Ident(ident, _) => ident,
+        HirLValue::MemberAccess { object, .. } => lvalue_ident(object),
+        HirLValue::Index { array, .. } => lvalue_ident(array),
+        HirLValue::Dereference { lvalue, .. } => lvalue_ident(lvalue),
+    }
+}
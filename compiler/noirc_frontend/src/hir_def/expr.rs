@@ -15,7 +15,8 @@ use super::types::{StructType, Type};
 /// from the definition that refers to them so there is no ambiguity with names.
 #[derive(Debug, Clone)]
 pub enum HirExpression {
-    Ident(HirIdent),
+    /// An identifier, along with any generics given explicitly with `::<..>`
+    Ident(HirIdent, Option<Vec<Type>>),
     Literal(HirLiteral),
     Block(HirBlockExpression),
     Prefix(HirPrefixExpression),
@@ -78,7 +79,8 @@ impl HirBinaryOp {
 pub enum HirLiteral {
     Array(HirArrayLiteral),
     Bool(bool),
-    Integer(FieldElement),
+    /// The magnitude of an integer literal, and whether it is negated
+    Integer(FieldElement, bool),
     Str(String),
     FmtStr(String, Vec<ExprId>),
     Unit,
@@ -87,7 +89,18 @@ pub enum HirLiteral {
 #[derive(Debug, Clone)]
 pub enum HirArrayLiteral {
     Standard(Vec<ExprId>),
-    Repeated { repeated_element: ExprId, length: Type },
+    Repeated {
+        repeated_element: ExprId,
+        length: Type,
+    },
+    /// `[element for identifier in start..end]`, whose length is `end - start`.
+    Comprehension {
+        element: ExprId,
+        identifier: HirIdent,
+        start: ExprId,
+        end: ExprId,
+        length: Type,
+    },
 }
 
 #[derive(Debug, Clone)]
@@ -172,7 +185,7 @@ impl HirMethodCallExpression {
         let expr = match method {
             HirMethodReference::FuncId(func_id) => {
                 let id = interner.function_definition_id(func_id);
-                HirExpression::Ident(HirIdent { location, id })
+                HirExpression::Ident(HirIdent { location, id }, None)
             }
             HirMethodReference::TraitMethodId(typ, method_id) => {
                 HirExpression::TraitMethodReference(typ, method_id)
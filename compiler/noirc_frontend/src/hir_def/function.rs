@@ -6,7 +6,7 @@ use super::stmt::HirPattern;
 use super::traits::TraitConstraint;
 use crate::node_interner::{ExprId, NodeInterner};
 use crate::FunctionKind;
-use crate::{Distinctness, FunctionReturnType, Type, Visibility};
+use crate::{BinaryOpKind, Distinctness, FunctionReturnType, Signedness, Type, Visibility};
 
 /// A Hir function is a block expression
 /// with a list of statements
@@ -114,6 +114,40 @@ pub struct FuncMeta {
     pub has_body: bool,
 
     pub trait_constraints: Vec<TraitConstraint>,
+
+    /// Bounds on the function's numeric generics from its where clause, such as `N <= 32`
+    pub numeric_constraints: Vec<NumericConstraint>,
+}
+
+/// A comparison between type-level expressions over a function's numeric generics.
+/// These can only be checked once the function is instantiated, during monomorphization.
+#[derive(Debug, Clone)]
+pub struct NumericConstraint {
+    pub lhs: Type,
+    pub comparison: BinaryOpKind,
+    pub rhs: Type,
+    pub span: Span,
+}
+
+impl NumericConstraint {
+    /// Returns whether the constraint holds for the given operands
+    pub fn holds(&self, lhs: u64, rhs: u64) -> bool {
+        match self.comparison {
+            BinaryOpKind::Equal => lhs == rhs,
+            BinaryOpKind::NotEqual => lhs != rhs,
+            BinaryOpKind::Less => lhs < rhs,
+            BinaryOpKind::LessEqual => lhs <= rhs,
+            BinaryOpKind::Greater => lhs > rhs,
+            BinaryOpKind::GreaterEqual => lhs >= rhs,
+            other => unreachable!("Expected a comparison in a numeric constraint, found {other}"),
+        }
+    }
+}
+
+impl std::fmt::Display for NumericConstraint {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} {} {}", self.lhs, self.comparison, self.rhs)
+    }
 }
 
 impl FuncMeta {
@@ -146,6 +180,21 @@ impl FuncMeta {
         (self.parameters.0, return_type)
     }
 
+    /// The signature identifying this function within a contract, such as `transfer(Field,u64)`,
+    /// made of its name and the ABI types of its parameters.
+    pub fn signature(&self, name: &str) -> String {
+        let parameters = vecmap(&self.parameters.0, |(_, typ, _)| signature_type_name(typ));
+        format!("{name}({})", parameters.join(","))
+    }
+
+    /// The selector used to call this function from other contracts: the first four bytes of
+    /// the keccak256 hash of its [signature][FuncMeta::signature].
+    pub fn selector(&self, name: &str) -> u32 {
+        let hash = acvm::blackbox_solver::keccak256(self.signature(name).as_bytes())
+            .expect("Rust solvable black box function should not fail");
+        u32::from_be_bytes([hash[0], hash[1], hash[2], hash[3]])
+    }
+
     /// Gives the (uninstantiated) return type of this function.
     pub fn return_type(&self) -> &Type {
         match &self.typ {
@@ -158,3 +207,24 @@ impl FuncMeta {
         }
     }
 }
+
+/// The name of a parameter's type within a function signature. Structs are written as the tuple
+/// of their fields' types in the order of their layout, so renaming a struct doesn't change the
+/// selectors of functions using it.
+fn signature_type_name(typ: &Type) -> String {
+    let tuple = |fields: Vec<Type>| format!("({})", vecmap(&fields, signature_type_name).join(","));
+    match typ.follow_bindings() {
+        Type::FieldElement => "Field".to_string(),
+        Type::Integer(Signedness::Unsigned, bit_size) => format!("u{bit_size}"),
+        Type::Integer(Signedness::Signed, bit_size) => format!("i{bit_size}"),
+        Type::Bool => "bool".to_string(),
+        Type::String(length) => format!("str<{length}>"),
+        Type::Array(length, element) => format!("[{};{length}]", signature_type_name(&element)),
+        Type::Tuple(fields) => tuple(fields),
+        Type::Struct(definition, generics) => {
+            let fields = definition.borrow().get_fields_in_layout_order(&generics);
+            tuple(vecmap(fields, |(_, typ)| typ))
+        }
+        other => other.to_string(),
+    }
+}
@@ -19,4 +19,5 @@ pub mod expr;
 pub mod function;
 pub mod stmt;
 pub mod traits;
+pub mod type_interner;
 pub mod types;
@@ -1,6 +1,6 @@
-use super::expr::HirIdent;
-use crate::node_interner::ExprId;
-use crate::{Ident, Type};
+use super::expr::{HirExpression, HirIdent, HirLiteral};
+use crate::node_interner::{DefinitionKind, ExprId, NodeInterner};
+use crate::{BinaryOpKind, Ident, Type, UnaryOp};
 use fm::FileId;
 use noirc_errors::Span;
 
@@ -14,6 +14,9 @@ pub enum HirStatement {
     Constrain(HirConstrainStatement),
     Assign(HirAssignStatement),
     For(HirForStatement),
+    /// A `loop` and its block, which may only be used in unconstrained functions
+    Loop(ExprId),
+    Break,
     Expression(ExprId),
     Semi(ExprId),
     Error,
@@ -28,11 +31,20 @@ pub struct HirLetStatement {
 
 impl HirLetStatement {
     pub fn ident(&self) -> HirIdent {
-        match self.pattern {
-            HirPattern::Identifier(ident) => ident,
+        let pattern = match &self.pattern {
+            HirPattern::Mutable(pattern, _) => pattern.as_ref(),
+            pattern => pattern,
+        };
+        match pattern {
+            HirPattern::Identifier(ident) => *ident,
             _ => panic!("can only fetch hir ident from HirPattern::Identifier"),
         }
     }
+
+    /// Whether the variable bound is mutable, as in `let mut x` or `global mut X`
+    pub fn is_mutable(&self) -> bool {
+        matches!(self.pattern, HirPattern::Mutable(..))
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -55,7 +67,101 @@ pub struct HirAssignStatement {
 /// originates from. This is used later in the SSA pass to issue
 /// an error if a constrain is found to be always false.
 #[derive(Debug, Clone)]
-pub struct HirConstrainStatement(pub ExprId, pub FileId, pub Option<String>);
+pub struct HirConstrainStatement(pub ExprId, pub FileId, pub Option<HirAssertMessage>);
+
+impl HirConstrainStatement {
+    /// Whether the condition of this assertion is false before the program is executed, such as
+    /// `assert(false)` or `assert(LIMIT > 10)` for a `global LIMIT = 8;`, so it never completes.
+    /// A block ending with it has the never type, so it can be used as a value of any type.
+    pub fn always_fails(&self, interner: &NodeInterner) -> bool {
+        constant_value(interner, self.0, &mut Vec::new()) == Some(ConstantValue::Bool(false))
+    }
+}
+
+/// A value which an expression is known to evaluate to without executing the program
+#[derive(Debug, PartialEq)]
+enum ConstantValue {
+    Bool(bool),
+    Integer(i128),
+}
+
+/// Evaluates `expr` if it's built from literals, non-mutable globals and the builtin logical and
+/// comparison operators. This only uses the resolved program, so it gives the same answer
+/// whether or not the expression has been type checked yet. `globals` holds the globals being
+/// evaluated, so that a global defined in terms of itself isn't followed forever.
+fn constant_value(
+    interner: &NodeInterner,
+    expr: ExprId,
+    globals: &mut Vec<ExprId>,
+) -> Option<ConstantValue> {
+    match interner.expression(&expr) {
+        HirExpression::Literal(HirLiteral::Bool(value)) => Some(ConstantValue::Bool(value)),
+        HirExpression::Literal(HirLiteral::Integer(value, negative)) => {
+            let value = i128::try_from(value.try_into_u128()?).ok()?;
+            Some(ConstantValue::Integer(if negative { -value } else { value }))
+        }
+        HirExpression::Ident(ident, _) => {
+            let definition = interner.try_definition(ident.id)?;
+            match definition.kind {
+                DefinitionKind::Global(global) if !definition.mutable => {
+                    if globals.contains(&global) {
+                        return None;
+                    }
+                    globals.push(global);
+                    let value = constant_value(interner, global, globals);
+                    globals.pop();
+                    value
+                }
+                _ => None,
+            }
+        }
+        HirExpression::Prefix(prefix) => {
+            match (prefix.operator, constant_value(interner, prefix.rhs, globals)?) {
+                (UnaryOp::Not, ConstantValue::Bool(value)) => Some(ConstantValue::Bool(!value)),
+                (UnaryOp::Minus, ConstantValue::Integer(value)) => {
+                    Some(ConstantValue::Integer(-value))
+                }
+                _ => None,
+            }
+        }
+        HirExpression::Infix(infix) => {
+            let lhs = constant_value(interner, infix.lhs, globals)?;
+            let rhs = constant_value(interner, infix.rhs, globals)?;
+            let value = match (infix.operator.kind, lhs, rhs) {
+                (BinaryOpKind::Equal, lhs, rhs) => lhs == rhs,
+                (BinaryOpKind::NotEqual, lhs, rhs) => lhs != rhs,
+                (BinaryOpKind::And, ConstantValue::Bool(lhs), ConstantValue::Bool(rhs)) => {
+                    lhs & rhs
+                }
+                (BinaryOpKind::Or, ConstantValue::Bool(lhs), ConstantValue::Bool(rhs)) => lhs | rhs,
+                (BinaryOpKind::Xor, ConstantValue::Bool(lhs), ConstantValue::Bool(rhs)) => {
+                    lhs ^ rhs
+                }
+                (operator, ConstantValue::Integer(lhs), ConstantValue::Integer(rhs)) => {
+                    match operator {
+                        BinaryOpKind::Less => lhs < rhs,
+                        BinaryOpKind::LessEqual => lhs <= rhs,
+                        BinaryOpKind::Greater => lhs > rhs,
+                        BinaryOpKind::GreaterEqual => lhs >= rhs,
+                        _ => return None,
+                    }
+                }
+                _ => return None,
+            };
+            Some(ConstantValue::Bool(value))
+        }
+        _ => None,
+    }
+}
+
+/// The message reported when an assertion fails
+#[derive(Debug, Clone)]
+pub enum HirAssertMessage {
+    Static(String),
+    /// A call to `std::internals::resolve_assert_message`, made before the assertion is checked,
+    /// which resolves the format string used as the message if the assertion is about to fail
+    Dynamic(ExprId),
+}
 
 #[derive(Debug, Clone, Hash)]
 pub enum HirPattern {
@@ -1,7 +1,7 @@
 use crate::{
     graph::CrateId,
     node_interner::{FuncId, TraitId, TraitMethodId},
-    Generics, Ident, NoirFunction, Type, TypeVariable, TypeVariableId,
+    Generics, Ident, NoirFunction, Type, TypeBinding, TypeBindings, TypeVariable, TypeVariableId,
 };
 use noirc_errors::Span;
 
@@ -63,13 +63,29 @@ pub struct TraitImpl {
     pub typ: Type,
     pub trait_id: TraitId,
     pub methods: Vec<FuncId>, // methods[i] is the implementation of trait.methods[i] for Type typ
+
+    /// The generics of the trait given by the impl, e.g. `u8` in `impl From<u8> for u64`.
+    /// Empty if the impl leaves them out.
+    pub trait_generics: Vec<Type>,
+
+    /// Generics declared on the impl, e.g. `T` in `impl<T> Foo for Bar<T>`.
+    /// Each lookup of this impl instantiates these to match the requested type.
+    pub generics: Generics,
+
+    /// Constraints on the impl generics, taken from the impl's where clause.
+    pub where_clause: Vec<TraitConstraint>,
+
+    /// The span of the impl header from the trait name to the implementing type
+    pub span: Span,
 }
 
 #[derive(Debug, Clone)]
 pub struct TraitConstraint {
     pub typ: Type,
     pub trait_id: TraitId,
-    // pub trait_generics: Generics, TODO
+    /// The generics given to the trait, e.g. `u8` in `T: From<u8>`. Empty if the constraint
+    /// leaves them out, in which case they aren't checked.
+    pub trait_generics: Vec<Type>,
 }
 
 impl std::hash::Hash for Trait {
@@ -112,6 +128,70 @@ impl Trait {
         self.methods = methods;
     }
 
+    /// Returns the type of the method at `method_index`, with `Self` replaced by `self_type`.
+    ///
+    /// The trait's `Self` type variable is shared by every use of the trait, so it must be
+    /// substituted rather than unified to avoid binding it to a single implementing type.
+    /// For the same reason, the method's type is quantified over the trait's generics so that
+    /// each use of it is instantiated with fresh type variables.
+    pub fn method_type(&self, method_index: usize, self_type: &Type) -> Type {
+        let mut bindings = TypeBindings::new();
+        let self_binding = (self.self_type_typevar.clone(), self_type.clone());
+        bindings.insert(self.self_type_typevar_id, self_binding);
+        let typ = self.methods[method_index].get_type().substitute(&bindings);
+
+        if self.generics.is_empty() {
+            typ
+        } else {
+            Type::Forall(self.generics.clone(), Box::new(typ))
+        }
+    }
+
+    /// Returns the reason this trait cannot be used as a trait object, if there is one.
+    ///
+    /// The concrete type of a trait object is unknown when one of its methods is called, so each
+    /// method must take `self` by value as its first parameter and not refer to `Self` otherwise.
+    pub fn object_safety_violation(&self) -> Option<String> {
+        if !self.generics.is_empty() {
+            return Some("Traits with generics cannot be used as trait objects".to_string());
+        }
+
+        let is_self_type = |typ: &Type| match typ {
+            Type::TypeVariable(binding, _) => {
+                matches!(&*binding.borrow(), TypeBinding::Unbound(id) if *id == self.self_type_typevar_id)
+            }
+            _ => false,
+        };
+
+        for method in &self.methods {
+            let name = &method.name.0.contents;
+            if !method.generics.is_empty() {
+                return Some(format!("Method `{name}` has generics"));
+            }
+
+            match method.arguments.split_first() {
+                Some((first, rest)) if is_self_type(first) => {
+                    let refers_to_self = rest
+                        .iter()
+                        .chain(std::iter::once(&method.return_type))
+                        .any(|typ| typ.occurs(self.self_type_typevar_id));
+
+                    if refers_to_self {
+                        return Some(format!(
+                            "Method `{name}` refers to `Self` outside of its `self` parameter"
+                        ));
+                    }
+                }
+                _ => {
+                    return Some(format!(
+                        "Method `{name}` does not take `self` as its first parameter"
+                    ))
+                }
+            }
+        }
+        None
+    }
+
     pub fn find_method(&self, name: Ident) -> Option<TraitMethodId> {
         for (idx, method) in self.methods.iter().enumerate() {
             if method.name == name {
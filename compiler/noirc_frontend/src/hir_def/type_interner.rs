@@ -0,0 +1,91 @@
+//! Hash-consing of fully known types. Once interned, checking whether two types are equal is
+//! a comparison of their ids rather than a walk over both types.
+
+use std::collections::HashMap;
+
+use crate::hir_def::types::Type;
+use crate::node_interner::StructId;
+use crate::{Signedness, TypeBinding};
+
+/// The id of a fully known type within a [TypeInterner]. Two types interned in the same
+/// interner are equal exactly when their ids are equal.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct InternedType(usize);
+
+/// A type whose components have already been interned. Since each component is only stored
+/// once, interning a type only needs to hash the outermost layer of each of its nodes.
+#[derive(PartialEq, Eq, Hash)]
+enum TypeNode {
+    FieldElement,
+    Integer(Signedness, u32),
+    Bool,
+    Unit,
+    Constant(u64),
+    NotConstant,
+    Array(InternedType, InternedType),
+    String(InternedType),
+    FmtString(InternedType, InternedType),
+    Struct(StructId, Vec<InternedType>),
+    Tuple(Vec<InternedType>),
+    Function(Vec<InternedType>, InternedType, InternedType),
+    MutableReference(InternedType),
+}
+
+#[derive(Default)]
+pub struct TypeInterner {
+    ids: HashMap<TypeNode, InternedType>,
+}
+
+impl TypeInterner {
+    /// Interns `typ`, following any type variables it contains which are already bound.
+    ///
+    /// Returns `None` if `typ` is not fully known yet: binding its type variables later
+    /// could change which types it is equal to. Generic types and type errors are never
+    /// interned either.
+    pub fn intern(&mut self, typ: &Type) -> Option<InternedType> {
+        let node = match typ {
+            Type::FieldElement => TypeNode::FieldElement,
+            Type::Integer(sign, bits) => TypeNode::Integer(*sign, *bits),
+            Type::Bool => TypeNode::Bool,
+            Type::Unit => TypeNode::Unit,
+            Type::Constant(value) => TypeNode::Constant(*value),
+            Type::NotConstant => TypeNode::NotConstant,
+            Type::Array(len, elem) => TypeNode::Array(self.intern(len)?, self.intern(elem)?),
+            Type::String(len) => TypeNode::String(self.intern(len)?),
+            Type::FmtString(len, fields) => {
+                TypeNode::FmtString(self.intern(len)?, self.intern(fields)?)
+            }
+            Type::Struct(def, generics) => {
+                let id = def.borrow().id;
+                TypeNode::Struct(id, self.intern_all(generics)?)
+            }
+            Type::Tuple(fields) => TypeNode::Tuple(self.intern_all(fields)?),
+            Type::Function(args, ret, env) => {
+                TypeNode::Function(self.intern_all(args)?, self.intern(ret)?, self.intern(env)?)
+            }
+            Type::MutableReference(element) => TypeNode::MutableReference(self.intern(element)?),
+            Type::InfixExpr(lhs, _, rhs) => {
+                // Both operands must be fully known, not just have a default value
+                self.intern(lhs)?;
+                self.intern(rhs)?;
+                TypeNode::Constant(typ.evaluate_to_u64()?)
+            }
+            Type::TypeVariable(binding, _) | Type::NamedGeneric(binding, _) => {
+                return match &*binding.borrow() {
+                    TypeBinding::Bound(typ) => self.intern(typ),
+                    TypeBinding::Unbound(_) => None,
+                };
+            }
+            Type::TraitAsType(_) | Type::TraitObject(_) | Type::Forall(..) | Type::Error => {
+                return None
+            }
+        };
+
+        let next_id = InternedType(self.ids.len());
+        Some(*self.ids.entry(node).or_insert(next_id))
+    }
+
+    fn intern_all(&mut self, types: &[Type]) -> Option<Vec<InternedType>> {
+        types.iter().map(|typ| self.intern(typ)).collect()
+    }
+}
@@ -1,6 +1,6 @@
 use std::{
     cell::RefCell,
-    collections::{BTreeSet, HashMap},
+    collections::{BTreeSet, HashMap, HashSet},
     rc::Rc,
 };
 
@@ -11,8 +11,9 @@ use crate::{
 use iter_extended::vecmap;
 use noirc_errors::Span;
 use noirc_printable_type::PrintableType;
+use serde::{Deserialize, Serialize};
 
-use crate::{node_interner::StructId, Ident, Signedness};
+use crate::{node_interner::StructId, token::StructLayout, Ident, Signedness};
 
 use super::{
     expr::{HirCallExpression, HirExpression, HirIdent},
@@ -67,6 +68,10 @@ pub enum Type {
 
     TraitAsType(Trait),
 
+    /// A trait object, `dyn Trait`. Values of any type implementing the trait may be converted
+    /// into a trait object, after which calls to the trait's methods are dispatched at runtime.
+    TraitObject(Trait),
+
     /// NamedGenerics are the 'T' or 'U' in a user-defined generic function
     /// like `fn foo<T, U>(...) {}`. Unlike TypeVariables, they cannot be bound over.
     NamedGeneric(TypeVariable, Rc<String>),
@@ -91,6 +96,11 @@ pub enum Type {
     /// bind to an integer without special checks to bind it to a non-type.
     Constant(u64),
 
+    /// A type-level arithmetic expression over array lengths, such as the `N + 1` in
+    /// `[T; N + 1]`. It is evaluated to a Constant once the numeric generics it refers
+    /// to are known, which for generic functions is only during monomorphization.
+    InfixExpr(Box<Type>, BinaryTypeOperator, Box<Type>),
+
     /// The type of a slice is an array of size NotConstant.
     /// The size of an array literal is resolved to this if it ever uses operations
     /// involving slices.
@@ -124,6 +134,10 @@ pub struct StructType {
     /// since these will handle applying generic arguments to fields as well.
     fields: Vec<(Ident, Type)>,
 
+    /// How the fields are laid out once the struct is compiled, set by the `#[repr(...)]`
+    /// attribute of the struct. `fields` is always kept in the order the fields are declared.
+    pub layout: StructLayout,
+
     pub generics: Generics,
     pub span: Span,
 }
@@ -154,16 +168,17 @@ impl StructType {
         fields: Vec<(Ident, Type)>,
         generics: Generics,
     ) -> StructType {
-        StructType { id, fields, name, span, generics }
+        StructType { id, fields, name, span, generics, layout: StructLayout::Declared }
     }
 
     /// To account for cyclic references between structs, a struct's
     /// fields are resolved strictly after the struct itself is initially
     /// created. Therefore, this method is used to set the fields once they
     /// become known.
-    pub fn set_fields(&mut self, fields: Vec<(Ident, Type)>) {
+    pub fn set_fields(&mut self, fields: Vec<(Ident, Type)>, layout: StructLayout) {
         assert!(self.fields.is_empty());
         self.fields = fields;
+        self.layout = layout;
     }
 
     pub fn num_fields(&self) -> usize {
@@ -205,6 +220,23 @@ impl StructType {
         })
     }
 
+    /// Returns all the fields of this type in the order given by its layout, which is the order
+    /// they are compiled and flattened in, after being applied to the given generic arguments.
+    pub fn get_fields_in_layout_order(&self, generic_args: &[Type]) -> Vec<(String, Type)> {
+        let mut fields = self.get_fields(generic_args);
+        self.layout.arrange(&mut fields, |(name, _)| name);
+        fields
+    }
+
+    /// Returns the position in the struct's layout of the field with the given index, as
+    /// returned by `get_field`.
+    pub fn layout_position(&self, field_index: usize) -> usize {
+        let mut names: Vec<_> =
+            self.fields.iter().map(|(name, _)| name.0.contents.as_str()).enumerate().collect();
+        self.layout.arrange(&mut names, |(_, name)| name);
+        names.iter().position(|(index, _)| *index == field_index).expect("Expected a field index")
+    }
+
     pub fn field_names(&self) -> BTreeSet<Ident> {
         self.fields.iter().map(|(name, _)| name.clone()).collect()
     }
@@ -309,6 +341,9 @@ impl<T: std::hash::Hash> std::hash::Hash for Shared<T> {
 
 impl<T: PartialEq> PartialEq for Shared<T> {
     fn eq(&self, other: &Self) -> bool {
+        if self.ptr_eq(other) {
+            return true;
+        }
         let ref1 = self.0.borrow();
         let ref2 = other.0.borrow();
         *ref1 == *ref2
@@ -339,11 +374,21 @@ impl<T> Shared<T> {
     pub fn borrow_mut(&self) -> std::cell::RefMut<T> {
         self.0.borrow_mut()
     }
+
+    /// True if both values refer to the same shared instance, regardless of its contents
+    pub fn ptr_eq(&self, other: &Self) -> bool {
+        Rc::ptr_eq(&self.0, &other.0)
+    }
+
+    /// The address of the shared instance, which identifies it for as long as it is alive
+    fn address(&self) -> usize {
+        Rc::as_ptr(&self.0) as usize
+    }
 }
 
 /// A restricted subset of binary operators useable on
 /// type level integers for use in the array length positions of types.
-#[derive(Debug, Copy, Clone, Hash, PartialEq, Eq)]
+#[derive(Debug, Copy, Clone, Hash, PartialEq, Eq, Serialize, Deserialize)]
 pub enum BinaryTypeOperator {
     Addition,
     Subtraction,
@@ -464,19 +509,8 @@ impl Type {
     }
 
     fn contains_numeric_typevar(&self, target_id: TypeVariableId) -> bool {
-        // True if the given type is a NamedGeneric with the target_id
-        let named_generic_id_matches_target = |typ: &Type| {
-            if let Type::NamedGeneric(type_variable, _) = typ {
-                match &*type_variable.borrow() {
-                    TypeBinding::Bound(_) => {
-                        unreachable!("Named generics should not be bound until monomorphization")
-                    }
-                    TypeBinding::Unbound(id) => target_id == *id,
-                }
-            } else {
-                false
-            }
-        };
+        // True if the given type is a NamedGeneric with the target_id, or an expression using it
+        let named_generic_id_matches_target = |typ: &Type| typ.refers_to_named_generic(target_id);
 
         match self {
             Type::FieldElement
@@ -489,7 +523,8 @@ impl Type {
             | Type::NamedGeneric(_, _)
             | Type::NotConstant
             | Type::Forall(_, _)
-            | Type::TraitAsType(_) => false,
+            | Type::TraitAsType(_)
+            | Type::TraitObject(_) => false,
 
             Type::Array(length, elem) => {
                 elem.contains_numeric_typevar(target_id) || named_generic_id_matches_target(length)
@@ -513,6 +548,7 @@ impl Type {
                 })
             }
             Type::MutableReference(element) => element.contains_numeric_typevar(target_id),
+            Type::InfixExpr(..) => named_generic_id_matches_target(self),
             Type::String(length) => named_generic_id_matches_target(length),
             Type::FmtString(length, elements) => {
                 elements.contains_numeric_typevar(target_id)
@@ -521,6 +557,23 @@ impl Type {
         }
     }
 
+    /// True if this type is the NamedGeneric with the given id, or a type-level
+    /// expression such as `N + 1` which uses it.
+    fn refers_to_named_generic(&self, target_id: TypeVariableId) -> bool {
+        match self {
+            Type::NamedGeneric(type_variable, _) => match &*type_variable.borrow() {
+                TypeBinding::Bound(_) => {
+                    unreachable!("Named generics should not be bound until monomorphization")
+                }
+                TypeBinding::Unbound(id) => target_id == *id,
+            },
+            Type::InfixExpr(lhs, _, rhs) => {
+                lhs.refers_to_named_generic(target_id) || rhs.refers_to_named_generic(target_id)
+            }
+            _ => false,
+        }
+    }
+
     /// True if this type can be used as a parameter to `main` or a contract function.
     /// This is only false for unsized types like slices or slices that do not make sense
     /// as a program input such as named generics or mutable references.
@@ -546,12 +599,14 @@ impl Type {
             | Type::MutableReference(_)
             | Type::Forall(_, _)
             | Type::TraitAsType(..)
+            | Type::TraitObject(..)
             | Type::NotConstant => false,
 
             Type::Array(length, element) => {
                 length.is_valid_for_program_input() && element.is_valid_for_program_input()
             }
             Type::String(length) => length.is_valid_for_program_input(),
+            Type::InfixExpr(..) => self.evaluate_to_u64().is_some(),
             Type::Tuple(elements) => elements.iter().all(|elem| elem.is_valid_for_program_input()),
             Type::Struct(definition, generics) => definition
                 .borrow()
@@ -562,92 +617,184 @@ impl Type {
     }
 }
 
-impl std::fmt::Display for Type {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+/// Controls how much of a [`Type`] is shown by [`Type::display`].
+///
+/// By default every part of a type is shown.
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq)]
+pub struct TypeDisplayOptions {
+    /// Types nested more deeply than this within the displayed type are shown as `..`.
+    pub max_depth: Option<usize>,
+
+    /// Tuples with more elements than this show only that many of them, followed by `..`.
+    pub max_tuple_elements: Option<usize>,
+}
+
+impl TypeDisplayOptions {
+    /// Shortens large types so that they don't overwhelm the message they are shown in.
+    pub const ABBREVIATED: TypeDisplayOptions =
+        TypeDisplayOptions { max_depth: Some(3), max_tuple_elements: Some(5) };
+}
+
+/// Displays a [`Type`] according to some [`TypeDisplayOptions`], see [`Type::display`].
+pub struct TypeDisplay<'a> {
+    typ: &'a Type,
+    options: TypeDisplayOptions,
+    depth: usize,
+}
+
+impl Type {
+    /// Displays this type, abbreviating it according to `options`.
+    ///
+    /// Whatever the options, numeric generics are shown as the number they evaluate to where
+    /// possible, and generic arguments are left out when none of them are known yet.
+    pub fn display(&self, options: TypeDisplayOptions) -> TypeDisplay<'_> {
+        TypeDisplay { typ: self, options, depth: 0 }
+    }
+
+    /// True if this is a type variable which hasn't been bound to any type yet, and so is
+    /// shown as `_`.
+    fn is_unknown(&self) -> bool {
         match self {
+            Type::TypeVariable(binding, TypeVariableKind::Normal) => match &*binding.borrow() {
+                TypeBinding::Bound(binding) => binding.is_unknown(),
+                TypeBinding::Unbound(_) => true,
+            },
+            Type::NamedGeneric(binding, name) => match &*binding.borrow() {
+                TypeBinding::Bound(binding) => binding.is_unknown(),
+                TypeBinding::Unbound(_) => name.is_empty(),
+            },
+            _ => false,
+        }
+    }
+}
+
+impl<'a> TypeDisplay<'a> {
+    /// Displays a type nested within this one.
+    fn nested<'b>(&self, typ: &'b Type) -> TypeDisplay<'b> {
+        TypeDisplay { typ, options: self.options, depth: self.depth + 1 }
+    }
+
+    /// Displays the type this one is bound to, which takes its place.
+    fn bound<'b>(&self, typ: &'b Type) -> TypeDisplay<'b> {
+        TypeDisplay { typ, options: self.options, depth: self.depth }
+    }
+
+    fn nested_list(&self, types: &[Type]) -> String {
+        vecmap(types, |typ| self.nested(typ).to_string()).join(", ")
+    }
+}
+
+impl std::fmt::Display for TypeDisplay<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.options.max_depth.map_or(false, |max_depth| self.depth > max_depth) {
+            return write!(f, "..");
+        }
+
+        match self.typ {
             Type::FieldElement => {
                 write!(f, "Field")
             }
             Type::Array(len, typ) => {
                 if matches!(len.follow_bindings(), Type::NotConstant) {
-                    write!(f, "[{typ}]")
+                    write!(f, "[{}]", self.nested(typ))
                 } else {
-                    write!(f, "[{typ}; {len}]")
+                    write!(f, "[{}; {}]", self.nested(typ), self.nested(len))
                 }
             }
             Type::Integer(sign, num_bits) => match sign {
                 Signedness::Signed => write!(f, "i{num_bits}"),
                 Signedness::Unsigned => write!(f, "u{num_bits}"),
             },
-            Type::TypeVariable(id, TypeVariableKind::Normal) => write!(f, "{}", id.borrow()),
+            Type::TypeVariable(binding, TypeVariableKind::Normal) => match &*binding.borrow() {
+                TypeBinding::Bound(binding) => self.bound(binding).fmt(f),
+                TypeBinding::Unbound(id) => id.fmt(f),
+            },
             Type::TypeVariable(binding, TypeVariableKind::IntegerOrField) => {
-                if let TypeBinding::Unbound(_) = &*binding.borrow() {
+                match &*binding.borrow() {
+                    TypeBinding::Bound(binding) => self.bound(binding).fmt(f),
                     // Show a Field by default if this TypeVariableKind::IntegerOrField is unbound, since that is
                     // what they bind to by default anyway. It is less confusing than displaying it
                     // as a generic.
-                    write!(f, "Field")
-                } else {
-                    write!(f, "{}", binding.borrow())
+                    TypeBinding::Unbound(_) => write!(f, "Field"),
                 }
             }
             Type::TypeVariable(binding, TypeVariableKind::Constant(n)) => {
-                if let TypeBinding::Unbound(_) = &*binding.borrow() {
+                match &*binding.borrow() {
+                    TypeBinding::Bound(binding) => self.bound(binding).fmt(f),
                     // TypeVariableKind::Constant(n) binds to Type::Constant(n) by default, so just show that.
-                    write!(f, "{n}")
-                } else {
-                    write!(f, "{}", binding.borrow())
+                    TypeBinding::Unbound(_) => write!(f, "{n}"),
                 }
             }
             Type::Struct(s, args) => {
-                let args = vecmap(args, |arg| arg.to_string());
-                if args.is_empty() {
+                // `Foo` is clearer than `Foo<_, _>` while the arguments are still being inferred
+                if args.iter().all(Type::is_unknown) {
                     write!(f, "{}", s.borrow())
                 } else {
-                    write!(f, "{}<{}>", s.borrow(), args.join(", "))
+                    write!(f, "{}<{}>", s.borrow(), self.nested_list(args))
                 }
             }
             Type::TraitAsType(tr) => {
                 write!(f, "impl {}", tr.name)
             }
-            Type::Tuple(elements) => {
-                let elements = vecmap(elements, ToString::to_string);
-                write!(f, "({})", elements.join(", "))
-            }
+            Type::TraitObject(tr) => write!(f, "dyn {}", tr.name),
+            Type::Tuple(elements) => match self.options.max_tuple_elements {
+                Some(max) if elements.len() > max => {
+                    let mut shown = vecmap(&elements[..max], |typ| self.nested(typ).to_string());
+                    shown.push("..".to_string());
+                    write!(f, "({})", shown.join(", "))
+                }
+                _ => write!(f, "({})", self.nested_list(elements)),
+            },
             Type::Bool => write!(f, "bool"),
-            Type::String(len) => write!(f, "str<{len}>"),
+            Type::String(len) => write!(f, "str<{}>", self.nested(len)),
             Type::FmtString(len, elements) => {
-                write!(f, "fmtstr<{len}, {elements}>")
+                write!(f, "fmtstr<{}, {}>", self.nested(len), self.nested(elements))
             }
             Type::Unit => write!(f, "()"),
             Type::Error => write!(f, "error"),
             Type::NamedGeneric(binding, name) => match &*binding.borrow() {
-                TypeBinding::Bound(binding) => binding.fmt(f),
+                TypeBinding::Bound(binding) => self.bound(binding).fmt(f),
                 TypeBinding::Unbound(_) if name.is_empty() => write!(f, "_"),
                 TypeBinding::Unbound(_) => write!(f, "{name}"),
             },
             Type::Constant(x) => x.fmt(f),
+            Type::InfixExpr(lhs, op, rhs) => {
+                if let Some(value) = self.typ.evaluate_to_u64() {
+                    return write!(f, "{value}");
+                }
+                let operand = |typ: &Type| match typ {
+                    Type::InfixExpr(..) => format!("({})", self.bound(typ)),
+                    _ => self.bound(typ).to_string(),
+                };
+                write!(f, "{} {op} {}", operand(lhs), operand(rhs))
+            }
             Type::Forall(typevars, typ) => {
                 let typevars = vecmap(typevars, |(var, _)| var.to_string());
-                write!(f, "forall {}. {}", typevars.join(" "), typ)
+                write!(f, "forall {}. {}", typevars.join(" "), self.bound(typ))
             }
             Type::Function(args, ret, env) => {
                 let closure_env_text = match **env {
                     Type::Unit => "".to_string(),
-                    _ => format!(" with closure environment {env}"),
+                    _ => format!(" with closure environment {}", self.nested(env)),
                 };
 
-                let args = vecmap(args.iter(), ToString::to_string);
-
-                write!(f, "fn({}) -> {ret}{closure_env_text}", args.join(", "))
+                let args = self.nested_list(args);
+                write!(f, "fn({args}) -> {}{closure_env_text}", self.nested(ret))
             }
             Type::MutableReference(element) => {
-                write!(f, "&mut {element}")
+                write!(f, "&mut {}", self.nested(element))
             }
             Type::NotConstant => write!(f, "_"),
         }
     }
 }
 
+impl std::fmt::Display for Type {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.display(TypeDisplayOptions::default()).fmt(f)
+    }
+}
+
 impl std::fmt::Display for BinaryTypeOperator {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
@@ -677,6 +824,24 @@ impl std::fmt::Display for TypeBinding {
 
 pub struct UnificationError;
 
+/// The type variables of some types which were unbound when the snapshot was taken. Restoring
+/// the snapshot unbinds them again, undoing every binding made to them since, including those
+/// made by a failed `try_unify`.
+#[derive(Default)]
+pub struct BindingsSnapshot(Vec<(TypeVariable, TypeVariableId)>);
+
+impl BindingsSnapshot {
+    pub fn extend(&mut self, other: BindingsSnapshot) {
+        self.0.extend(other.0);
+    }
+
+    pub fn restore(self) {
+        for (binding, id) in self.0 {
+            binding.borrow_mut().unbind(id);
+        }
+    }
+}
+
 impl Type {
     /// Try to bind a MaybeConstant variable to self, succeeding if self is a Constant,
     /// MaybeConstant, or type variable.
@@ -699,6 +864,12 @@ impl Type {
                 *var.borrow_mut() = TypeBinding::Bound(Type::NotConstant);
                 Ok(())
             }
+            Type::InfixExpr(..) => {
+                // The expression must be able to evaluate to the target length
+                self.try_unify(&Type::Constant(target_length))?;
+                *var.borrow_mut() = TypeBinding::Bound(Type::Constant(target_length));
+                Ok(())
+            }
             Type::TypeVariable(binding, kind) => {
                 let borrow = binding.borrow();
                 match &*borrow {
@@ -842,9 +1013,21 @@ impl Type {
         use Type::*;
         use TypeVariableKind as Kind;
 
+        // Inferred types share their structure through type variable bindings, so a type is
+        // often unified with itself. Comparing pointers avoids walking the whole type again.
+        if std::ptr::eq(self, other) {
+            return Ok(());
+        }
+
         match (self, other) {
             (Error, _) | (_, Error) => Ok(()),
 
+            (TypeVariable(binding_a, _), TypeVariable(binding_b, _))
+                if binding_a.ptr_eq(binding_b) =>
+            {
+                Ok(())
+            }
+
             (TypeVariable(binding, Kind::IntegerOrField), other)
             | (other, TypeVariable(binding, Kind::IntegerOrField)) => {
                 // If it is already bound, unify against what it is bound to
@@ -874,6 +1057,10 @@ impl Type {
                 other.try_bind_to_maybe_constant(binding, *length)
             }
 
+            (InfixExpr(lhs, op, rhs), other) | (other, InfixExpr(lhs, op, rhs)) => {
+                Self::try_unify_infix_expr(lhs, *op, rhs, other)
+            }
+
             (Array(len_a, elem_a), Array(len_b, elem_b)) => {
                 len_a.try_unify(len_b)?;
                 elem_a.try_unify(elem_b)
@@ -948,11 +1135,55 @@ impl Type {
         }
     }
 
+    /// Unify the type-level expression `lhs op rhs` with another type. If the expression cannot
+    /// be evaluated yet but the other type is a known length, this solves for the expression's
+    /// unknown operand where the operator allows it, e.g. unifying `N + 1` with `4` binds `N` to `3`.
+    fn try_unify_infix_expr(
+        lhs: &Type,
+        op: BinaryTypeOperator,
+        rhs: &Type,
+        other: &Type,
+    ) -> Result<(), UnificationError> {
+        use BinaryTypeOperator::*;
+
+        let (lhs_value, rhs_value) = (lhs.evaluate_to_u64(), rhs.evaluate_to_u64());
+        if let (Some(lhs_value), Some(rhs_value)) = (lhs_value, rhs_value) {
+            let value = op.checked_function()(lhs_value, rhs_value).ok_or(UnificationError)?;
+            return Type::Constant(value).try_unify(other);
+        }
+
+        if let Some(target) = other.evaluate_to_u64() {
+            let solution = match (lhs_value, op, rhs_value) {
+                (Some(known), Addition, None) | (None, Addition, Some(known)) => {
+                    target.checked_sub(known)
+                }
+                (Some(known), Subtraction, None) => known.checked_sub(target),
+                (None, Subtraction, Some(known)) => target.checked_add(known),
+                (Some(known), Multiplication, None) | (None, Multiplication, Some(known)) => {
+                    (known != 0 && target % known == 0).then(|| target / known)
+                }
+                // The operands of a division or modulo cannot be recovered from its result
+                _ => None,
+            };
+            let unknown = if lhs_value.is_none() { lhs } else { rhs };
+            return unknown.try_unify(&Type::Constant(solution.ok_or(UnificationError)?));
+        }
+
+        match other {
+            Type::InfixExpr(other_lhs, other_op, other_rhs) if op == *other_op => {
+                lhs.try_unify(other_lhs)?;
+                rhs.try_unify(other_rhs)
+            }
+            _ => Err(UnificationError),
+        }
+    }
+
     /// Similar to `unify` but if the check fails this will attempt to coerce the
     /// argument to the target type. When this happens, the given expression is wrapped in
     /// a new expression to convert its type. E.g. `array` -> `array.as_slice()`
     ///
-    /// Currently the only type coercion in Noir is `[T; N]` into `[T]` via `.as_slice()`.
+    /// The type coercions in Noir are `[T; N]` into `[T]` via `.as_slice()`, and any type
+    /// implementing a trait into a trait object of that trait, `dyn Trait`.
     pub fn unify_with_coercions(
         &self,
         expected: &Type,
@@ -962,7 +1193,9 @@ impl Type {
         make_error: impl FnOnce() -> TypeCheckError,
     ) {
         if let Err(UnificationError) = self.try_unify(expected) {
-            if !self.try_array_to_slice_coercion(expected, expression, interner) {
+            if !self.try_array_to_slice_coercion(expected, expression, interner)
+                && !self.try_trait_object_coercion(expected, expression, interner)
+            {
                 errors.push(make_error());
             }
         }
@@ -996,6 +1229,88 @@ impl Type {
         false
     }
 
+    /// Try to convert a value of this type into a trait object of the target type.
+    /// This is possible if this type is known and implements the trait of the trait object.
+    /// The conversion is only recorded here, whether this type is concrete is checked once the
+    /// enclosing function has been checked and its type variables won't be bound any further.
+    fn try_trait_object_coercion(
+        &self,
+        target: &Type,
+        expression: ExprId,
+        interner: &mut NodeInterner,
+    ) -> bool {
+        let Type::TraitObject(the_trait) = target.follow_bindings() else {
+            return false;
+        };
+
+        let this = self.follow_bindings();
+        match this {
+            Type::TraitObject(_)
+            | Type::TypeVariable(..)
+            | Type::NamedGeneric(..)
+            | Type::Forall(..)
+            | Type::Error => false,
+            _ => {
+                if interner.lookup_trait_implementation(&this, the_trait.id, &[]).is_none() {
+                    return false;
+                }
+                interner.push_trait_object_coercion(expression, the_trait.id, self.clone());
+                true
+            }
+        }
+    }
+
+    /// Returns this type with its bindings followed, or None if it is not yet fully known or
+    /// refers to generics. Type variables which are still unbound but have a default type,
+    /// such as those of integer literals, are replaced with that default.
+    ///
+    /// Only concrete types may be converted into trait objects since every trait object of a
+    /// trait has room for a value of each type converted into it.
+    pub fn concrete(&self) -> Option<Type> {
+        let concrete_all = |types: &[Type]| types.iter().map(Type::concrete).collect();
+        Some(match self {
+            Type::Array(len, elem) => {
+                Type::Array(Box::new(len.concrete()?), Box::new(elem.concrete()?))
+            }
+            Type::String(len) => Type::String(Box::new(len.concrete()?)),
+            Type::FmtString(len, fields) => {
+                Type::FmtString(Box::new(len.concrete()?), Box::new(fields.concrete()?))
+            }
+            Type::Struct(def, args) => Type::Struct(def.clone(), concrete_all(args)?),
+            Type::Tuple(fields) => Type::Tuple(concrete_all(fields)?),
+            Type::TypeVariable(binding, kind) => match &*binding.borrow() {
+                TypeBinding::Bound(binding) => binding.concrete()?,
+                TypeBinding::Unbound(_) if *kind == TypeVariableKind::Normal => return None,
+                TypeBinding::Unbound(_) => kind.default_type(),
+            },
+            Type::NamedGeneric(binding, _) => match &*binding.borrow() {
+                TypeBinding::Bound(binding) => binding.concrete()?,
+                TypeBinding::Unbound(_) => return None,
+            },
+            Type::Function(args, ret, env) => Type::Function(
+                concrete_all(args)?,
+                Box::new(ret.concrete()?),
+                Box::new(env.concrete()?),
+            ),
+            Type::MutableReference(element) => {
+                Type::MutableReference(Box::new(element.concrete()?))
+            }
+            Type::InfixExpr(..) => match self.follow_bindings() {
+                Type::Constant(value) => Type::Constant(value),
+                _ => return None,
+            },
+            Type::Forall(..) | Type::TraitAsType(_) | Type::NotConstant | Type::Error => {
+                return None
+            }
+            Type::FieldElement
+            | Type::Integer(..)
+            | Type::Bool
+            | Type::Unit
+            | Type::Constant(_)
+            | Type::TraitObject(_) => self.clone(),
+        })
+    }
+
     /// If this type is a Type::Constant (used in array lengths), or is bound
     /// to a Type::Constant, return the constant as a u64.
     pub fn evaluate_to_u64(&self) -> Option<u64> {
@@ -1009,10 +1324,76 @@ impl Type {
             Type::TypeVariable(_, TypeVariableKind::Constant(size)) => Some(*size),
             Type::Array(len, _elem) => len.evaluate_to_u64(),
             Type::Constant(x) => Some(*x),
+            Type::InfixExpr(lhs, op, rhs) => {
+                op.checked_function()(lhs.evaluate_to_u64()?, rhs.evaluate_to_u64()?)
+            }
             _ => None,
         }
     }
 
+    /// The number of field elements a value of this type is serialized into, if it's fixed
+    pub(crate) fn field_count(&self) -> Option<u64> {
+        match self.follow_bindings() {
+            Type::FieldElement | Type::Integer(..) | Type::Bool => Some(1),
+            Type::TypeVariable(_, TypeVariableKind::IntegerOrField) => Some(1),
+            Type::String(length) => length.evaluate_to_u64(),
+            Type::Array(length, element) => {
+                length.evaluate_to_u64()?.checked_mul(element.field_count()?)
+            }
+            Type::Tuple(fields) => fields.iter().map(Type::field_count).sum(),
+            Type::Struct(definition, generics) => {
+                let fields = definition.borrow().get_fields(&generics);
+                fields.iter().map(|(_, typ)| typ.field_count()).sum()
+            }
+            _ => None,
+        }
+    }
+
+    /// If this is a struct with a packed layout, returns the number of field elements it is
+    /// packed into when passed to or returned from a foreign call. Structs holding strings, slices
+    /// or other values without a fixed number of numeric elements aren't packed.
+    pub fn packed_length(&self) -> Option<u64> {
+        fn element_count(typ: &Type) -> Option<u64> {
+            match typ.follow_bindings() {
+                Type::FieldElement | Type::Integer(..) | Type::Bool => Some(1),
+                Type::TypeVariable(_, TypeVariableKind::IntegerOrField) => Some(1),
+                Type::Array(length, element) => {
+                    length.evaluate_to_u64()?.checked_mul(element_count(&element)?)
+                }
+                Type::Tuple(fields) => fields.iter().map(element_count).sum(),
+                Type::Struct(definition, generics) => {
+                    let fields = definition.borrow().get_fields(&generics);
+                    fields.iter().map(|(_, typ)| element_count(typ)).sum()
+                }
+                _ => None,
+            }
+        }
+
+        match self.follow_bindings() {
+            Type::Struct(definition, _) if definition.borrow().layout == StructLayout::Packed => {
+                element_count(self)
+            }
+            _ => None,
+        }
+    }
+
+    /// Whether values of this type can be converted into a [PrintableType] to be printed
+    pub(crate) fn is_printable(&self) -> bool {
+        match self.follow_bindings() {
+            Type::FieldElement | Type::Integer(..) | Type::Bool => true,
+            Type::TypeVariable(_, TypeVariableKind::IntegerOrField) => true,
+            Type::String(length) => length.evaluate_to_u64().is_some(),
+            Type::Array(length, element) => {
+                length.evaluate_to_u64().is_some() && element.is_printable()
+            }
+            Type::Struct(definition, generics) => {
+                let fields = definition.borrow().get_fields(&generics);
+                fields.iter().all(|(_, typ)| typ.is_printable())
+            }
+            _ => false,
+        }
+    }
+
     /// Iterate over the fields of this type.
     /// Panics if the type is not a struct or tuple.
     pub fn iter_fields(&self) -> impl Iterator<Item = (String, Type)> {
@@ -1073,31 +1454,51 @@ impl Type {
     /// given bindings if found. If a type variable is not found within
     /// the given TypeBindings, it is unchanged.
     pub fn substitute(&self, type_bindings: &TypeBindings) -> Type {
+        self.substitute_helper(type_bindings, false)
+    }
+
+    /// Like `substitute`, but type variables in the given bindings are replaced even if
+    /// they are currently bound. This is needed when instantiating a generic definition
+    /// whose generics may be bound from an enclosing instantiation of the same definition.
+    pub fn force_substitute(&self, type_bindings: &TypeBindings) -> Type {
+        self.substitute_helper(type_bindings, true)
+    }
+
+    fn substitute_helper(&self, type_bindings: &TypeBindings, force: bool) -> Type {
         if type_bindings.is_empty() {
             return self.clone();
         }
 
-        let substitute_binding = |binding: &TypeVariable| match &*binding.borrow() {
-            TypeBinding::Bound(binding) => binding.substitute(type_bindings),
-            TypeBinding::Unbound(id) => match type_bindings.get(id) {
-                Some((_, binding)) => binding.clone(),
-                None => self.clone(),
-            },
+        let substitute_binding = |binding: &TypeVariable| {
+            if force {
+                let replacement = type_bindings.values().find(|(var, _)| var.ptr_eq(binding));
+                if let Some((_, replacement)) = replacement {
+                    return replacement.clone();
+                }
+            }
+
+            match &*binding.borrow() {
+                TypeBinding::Bound(binding) => binding.substitute_helper(type_bindings, force),
+                TypeBinding::Unbound(id) => match type_bindings.get(id) {
+                    Some((_, binding)) => binding.clone(),
+                    None => self.clone(),
+                },
+            }
         };
 
         match self {
             Type::Array(size, element) => {
-                let size = Box::new(size.substitute(type_bindings));
-                let element = Box::new(element.substitute(type_bindings));
+                let size = Box::new(size.substitute_helper(type_bindings, force));
+                let element = Box::new(element.substitute_helper(type_bindings, force));
                 Type::Array(size, element)
             }
             Type::String(size) => {
-                let size = Box::new(size.substitute(type_bindings));
+                let size = Box::new(size.substitute_helper(type_bindings, force));
                 Type::String(size)
             }
             Type::FmtString(size, fields) => {
-                let size = Box::new(size.substitute(type_bindings));
-                let fields = Box::new(fields.substitute(type_bindings));
+                let size = Box::new(size.substitute_helper(type_bindings, force));
+                let fields = Box::new(fields.substitute_helper(type_bindings, force));
                 Type::FmtString(size, fields)
             }
             Type::NamedGeneric(binding, _) | Type::TypeVariable(binding, _) => {
@@ -1106,31 +1507,37 @@ impl Type {
             // Do not substitute fields, it can lead to infinite recursion
             // and we should not match fields when type checking anyway.
             Type::Struct(fields, args) => {
-                let args = vecmap(args, |arg| arg.substitute(type_bindings));
+                let args = vecmap(args, |arg| arg.substitute_helper(type_bindings, force));
                 Type::Struct(fields.clone(), args)
             }
             Type::Tuple(fields) => {
-                let fields = vecmap(fields, |field| field.substitute(type_bindings));
+                let fields = vecmap(fields, |field| field.substitute_helper(type_bindings, force));
                 Type::Tuple(fields)
             }
             Type::TraitAsType(_) => todo!(),
+            Type::TraitObject(_) => self.clone(),
             Type::Forall(typevars, typ) => {
                 // Trying to substitute a variable defined within a nested Forall
                 // is usually impossible and indicative of an error in the type checker somewhere.
                 for (var, _) in typevars {
                     assert!(!type_bindings.contains_key(var));
                 }
-                let typ = Box::new(typ.substitute(type_bindings));
+                let typ = Box::new(typ.substitute_helper(type_bindings, force));
                 Type::Forall(typevars.clone(), typ)
             }
             Type::Function(args, ret, env) => {
-                let args = vecmap(args, |arg| arg.substitute(type_bindings));
-                let ret = Box::new(ret.substitute(type_bindings));
-                let env = Box::new(env.substitute(type_bindings));
+                let args = vecmap(args, |arg| arg.substitute_helper(type_bindings, force));
+                let ret = Box::new(ret.substitute_helper(type_bindings, force));
+                let env = Box::new(env.substitute_helper(type_bindings, force));
                 Type::Function(args, ret, env)
             }
             Type::MutableReference(element) => {
-                Type::MutableReference(Box::new(element.substitute(type_bindings)))
+                Type::MutableReference(Box::new(element.substitute_helper(type_bindings, force)))
+            }
+            Type::InfixExpr(lhs, op, rhs) => {
+                let lhs = Box::new(lhs.substitute_helper(type_bindings, force));
+                let rhs = Box::new(rhs.substitute_helper(type_bindings, force));
+                Type::InfixExpr(lhs, *op, rhs)
             }
 
             Type::FieldElement
@@ -1144,33 +1551,48 @@ impl Type {
     }
 
     /// True if the given TypeVariableId is free anywhere within self
-    fn occurs(&self, target_id: TypeVariableId) -> bool {
+    pub fn occurs(&self, target_id: TypeVariableId) -> bool {
+        self.occurs_helper(target_id, &mut HashSet::new())
+    }
+
+    /// A bound type variable is shared by every type it was unified into, so the same binding
+    /// may be reachable through many parts of `self`. `searched` caches the addresses of the
+    /// bindings already searched, which did not contain the target or the search would have
+    /// stopped, so that each is only searched once.
+    fn occurs_helper(&self, target_id: TypeVariableId, searched: &mut HashSet<usize>) -> bool {
+        let occurs =
+            |typ: &Type, searched: &mut HashSet<usize>| typ.occurs_helper(target_id, searched);
+
         match self {
-            Type::Array(len, elem) => len.occurs(target_id) || elem.occurs(target_id),
-            Type::String(len) => len.occurs(target_id),
+            Type::Array(len, elem) => occurs(len, searched) || occurs(elem, searched),
+            Type::String(len) => occurs(len, searched),
             Type::FmtString(len, fields) => {
-                let len_occurs = len.occurs(target_id);
-                let field_occurs = fields.occurs(target_id);
+                let len_occurs = occurs(len, searched);
+                let field_occurs = occurs(fields, searched);
                 len_occurs || field_occurs
             }
             Type::TraitAsType(_) => todo!(),
-            Type::Struct(_, generic_args) => generic_args.iter().any(|arg| arg.occurs(target_id)),
-            Type::Tuple(fields) => fields.iter().any(|field| field.occurs(target_id)),
+            Type::TraitObject(_) => false,
+            Type::Struct(_, generic_args) => generic_args.iter().any(|arg| occurs(arg, searched)),
+            Type::Tuple(fields) => fields.iter().any(|field| occurs(field, searched)),
             Type::NamedGeneric(binding, _) | Type::TypeVariable(binding, _) => {
                 match &*binding.borrow() {
-                    TypeBinding::Bound(binding) => binding.occurs(target_id),
+                    TypeBinding::Bound(typ) => {
+                        searched.insert(binding.address()) && occurs(typ, searched)
+                    }
                     TypeBinding::Unbound(id) => *id == target_id,
                 }
             }
             Type::Forall(typevars, typ) => {
-                !typevars.iter().any(|(id, _)| *id == target_id) && typ.occurs(target_id)
+                !typevars.iter().any(|(id, _)| *id == target_id) && occurs(typ, searched)
             }
             Type::Function(args, ret, env) => {
-                args.iter().any(|arg| arg.occurs(target_id))
-                    || ret.occurs(target_id)
-                    || env.occurs(target_id)
+                args.iter().any(|arg| occurs(arg, searched))
+                    || occurs(ret, searched)
+                    || occurs(env, searched)
             }
-            Type::MutableReference(element) => element.occurs(target_id),
+            Type::MutableReference(element) => occurs(element, searched),
+            Type::InfixExpr(lhs, _, rhs) => occurs(lhs, searched) || occurs(rhs, searched),
 
             Type::FieldElement
             | Type::Integer(_, _)
@@ -1182,6 +1604,61 @@ impl Type {
         }
     }
 
+    /// Records the type variables of this type which are currently unbound, so that any bindings
+    /// made to them by a unification which shouldn't be committed can be undone.
+    pub fn snapshot_bindings(&self) -> BindingsSnapshot {
+        let mut snapshot = BindingsSnapshot::default();
+        self.snapshot_helper(&mut snapshot, &mut HashSet::new());
+        snapshot
+    }
+
+    fn snapshot_helper(&self, snapshot: &mut BindingsSnapshot, searched: &mut HashSet<usize>) {
+        match self {
+            Type::Array(len, elem) => {
+                len.snapshot_helper(snapshot, searched);
+                elem.snapshot_helper(snapshot, searched);
+            }
+            Type::String(len) => len.snapshot_helper(snapshot, searched),
+            Type::FmtString(len, fields) => {
+                len.snapshot_helper(snapshot, searched);
+                fields.snapshot_helper(snapshot, searched);
+            }
+            Type::Struct(_, args) | Type::Tuple(args) => {
+                args.iter().for_each(|arg| arg.snapshot_helper(snapshot, searched));
+            }
+            Type::NamedGeneric(binding, _) | Type::TypeVariable(binding, _) => {
+                if !searched.insert(binding.address()) {
+                    return;
+                }
+                match &*binding.borrow() {
+                    TypeBinding::Bound(typ) => typ.snapshot_helper(snapshot, searched),
+                    TypeBinding::Unbound(id) => snapshot.0.push((binding.clone(), *id)),
+                }
+            }
+            Type::Forall(_, typ) | Type::MutableReference(typ) => {
+                typ.snapshot_helper(snapshot, searched);
+            }
+            Type::Function(args, ret, env) => {
+                args.iter().for_each(|arg| arg.snapshot_helper(snapshot, searched));
+                ret.snapshot_helper(snapshot, searched);
+                env.snapshot_helper(snapshot, searched);
+            }
+            Type::InfixExpr(lhs, _, rhs) => {
+                lhs.snapshot_helper(snapshot, searched);
+                rhs.snapshot_helper(snapshot, searched);
+            }
+            Type::TraitAsType(_)
+            | Type::TraitObject(_)
+            | Type::FieldElement
+            | Type::Integer(_, _)
+            | Type::Bool
+            | Type::Constant(_)
+            | Type::Error
+            | Type::NotConstant
+            | Type::Unit => (),
+        }
+    }
+
     /// Follow any TypeVariable bindings within this type. Doing so ensures
     /// that if the bindings are rebound or unbound from under the type then the
     /// returned type will not change (because it will no longer contain the
@@ -1221,9 +1698,23 @@ impl Type {
 
             MutableReference(element) => MutableReference(Box::new(element.follow_bindings())),
 
+            InfixExpr(lhs, op, rhs) => {
+                let (lhs, rhs) = (lhs.follow_bindings(), rhs.follow_bindings());
+
+                // Fold the expression once both operands are known so that it
+                // compares equal to the same length written as a constant
+                if let (Constant(lhs), Constant(rhs)) = (&lhs, &rhs) {
+                    if let Some(value) = op.checked_function()(*lhs, *rhs) {
+                        return Constant(value);
+                    }
+                }
+                InfixExpr(Box::new(lhs), *op, Box::new(rhs))
+            }
+
             // Expect that this function should only be called on instantiated types
             Forall(..) => unreachable!(),
             TraitAsType(_)
+            | TraitObject(_)
             | FieldElement
             | Integer(_, _)
             | Bool
@@ -1248,7 +1739,7 @@ fn convert_array_expression_to_slice(
 
     let as_slice_id = interner.function_definition_id(as_slice_method);
     let location = interner.expr_location(&expression);
-    let as_slice = HirExpression::Ident(HirIdent { location, id: as_slice_id });
+    let as_slice = HirExpression::Ident(HirIdent { location, id: as_slice_id }, None);
     let func = interner.push_expr(as_slice);
 
     let arguments = vec![expression];
@@ -1276,6 +1767,18 @@ impl BinaryTypeOperator {
             BinaryTypeOperator::Modulo => |a, b| a.wrapping_rem(b), // % b,
         }
     }
+
+    /// Like `function`, but returns None instead of wrapping on overflow or underflow,
+    /// and on division by zero
+    pub fn checked_function(self) -> fn(u64, u64) -> Option<u64> {
+        match self {
+            BinaryTypeOperator::Addition => |a, b| a.checked_add(b),
+            BinaryTypeOperator::Subtraction => |a, b| a.checked_sub(b),
+            BinaryTypeOperator::Multiplication => |a, b| a.checked_mul(b),
+            BinaryTypeOperator::Division => |a, b| a.checked_div(b),
+            BinaryTypeOperator::Modulo => |a, b| a.checked_rem(b),
+        }
+    }
 }
 
 impl TypeVariableKind {
@@ -1325,13 +1828,15 @@ impl From<&Type> for PrintableType {
             Type::Error => unreachable!(),
             Type::Unit => unreachable!(),
             Type::Constant(_) => unreachable!(),
+            Type::InfixExpr(..) => unreachable!(),
             Type::Struct(def, ref args) => {
                 let struct_type = def.borrow();
-                let fields = struct_type.get_fields(args);
+                let fields = struct_type.get_fields_in_layout_order(args);
                 let fields = vecmap(fields, |(name, typ)| (name, typ.into()));
                 PrintableType::Struct { fields, name: struct_type.name.to_string() }
             }
             Type::TraitAsType(_) => unreachable!(),
+            Type::TraitObject(_) => unreachable!(),
             Type::Tuple(_) => todo!("printing tuple types is not yet implemented"),
             Type::TypeVariable(_, _) => unreachable!(),
             Type::NamedGeneric(..) => unreachable!(),
@@ -3,8 +3,9 @@ use crate::parser::ParserErrorReason;
 use crate::token::SpannedToken;
 
 use super::token::Token;
+use noirc_errors::codes::*;
 use noirc_errors::CustomDiagnostic as Diagnostic;
-use noirc_errors::Span;
+use noirc_errors::{ErrorCode, Span};
 use thiserror::Error;
 
 #[derive(Error, Clone, Debug, PartialEq, Eq)]
@@ -15,6 +16,8 @@ pub enum LexerErrorKind {
     NotADoubleChar { span: Span, found: Token },
     #[error("InvalidIntegerLiteral : {:?} is not a integer", found)]
     InvalidIntegerLiteral { span: Span, found: String },
+    #[error("Integer literal is too large to fit into a field element")]
+    IntegerLiteralTooLarge { span: Span, limit: String },
     #[error("MalformedFuncAttribute : {:?} is not a valid attribute", found)]
     MalformedFuncAttribute { span: Span, found: String },
     #[error("TooManyBits")]
@@ -29,6 +32,10 @@ pub enum LexerErrorKind {
         "'\\{escaped}' is not a valid escape sequence. Use '\\' for a literal backslash character."
     )]
     InvalidEscape { escaped: char, span: Span },
+    #[error("Invalid unicode escape sequence")]
+    InvalidUnicodeEscape { span: Span },
+    #[error("Invalid character literal")]
+    InvalidCharLiteral { span: Span },
 }
 
 impl From<LexerErrorKind> for ParserError {
@@ -44,12 +51,34 @@ impl LexerErrorKind {
             LexerErrorKind::UnexpectedCharacter { span, .. } => *span,
             LexerErrorKind::NotADoubleChar { span, .. } => *span,
             LexerErrorKind::InvalidIntegerLiteral { span, .. } => *span,
+            LexerErrorKind::IntegerLiteralTooLarge { span, .. } => *span,
             LexerErrorKind::MalformedFuncAttribute { span, .. } => *span,
             LexerErrorKind::TooManyBits { span, .. } => *span,
             LexerErrorKind::LogicalAnd { span } => *span,
             LexerErrorKind::UnterminatedBlockComment { span } => *span,
             LexerErrorKind::UnterminatedStringLiteral { span } => *span,
             LexerErrorKind::InvalidEscape { span, .. } => *span,
+            LexerErrorKind::InvalidUnicodeEscape { span } => *span,
+            LexerErrorKind::InvalidCharLiteral { span } => *span,
+        }
+    }
+
+    pub fn code(&self) -> ErrorCode {
+        match self {
+            LexerErrorKind::UnexpectedCharacter { .. } | LexerErrorKind::NotADoubleChar { .. } => {
+                E0001
+            }
+            LexerErrorKind::InvalidIntegerLiteral { .. } => E0002,
+            LexerErrorKind::IntegerLiteralTooLarge { .. } => E0022,
+            LexerErrorKind::TooManyBits { .. } => E0023,
+            LexerErrorKind::MalformedFuncAttribute { .. } => E0003,
+            LexerErrorKind::LogicalAnd { .. } => E0004,
+            LexerErrorKind::UnterminatedStringLiteral { .. } => E0005,
+            LexerErrorKind::UnterminatedBlockComment { .. } => E0024,
+            LexerErrorKind::InvalidEscape { .. } | LexerErrorKind::InvalidUnicodeEscape { .. } => {
+                E0006
+            }
+            LexerErrorKind::InvalidCharLiteral { .. } => E0025,
         }
     }
 
@@ -80,6 +109,11 @@ impl LexerErrorKind {
                 format!(" {found} is not an integer"),
                 *span,
             ),
+            LexerErrorKind::IntegerLiteralTooLarge { span, limit } => (
+                "Integer literal too large".to_string(),
+                format!("The maximum value of a field element is {limit}"),
+                *span,
+            ),
             LexerErrorKind::MalformedFuncAttribute { span, found } => (
                 "Malformed function attribute".to_string(),
                 format!(" {found} is not a valid attribute"),
@@ -102,6 +136,16 @@ impl LexerErrorKind {
                 ("Unterminated string literal".to_string(), "Unterminated string literal".to_string(), *span),
             LexerErrorKind::InvalidEscape { escaped, span } =>
                 (format!("'\\{escaped}' is not a valid escape sequence. Use '\\' for a literal backslash character."), "Invalid escape sequence".to_string(), *span),
+            LexerErrorKind::InvalidUnicodeEscape { span } => (
+                "Invalid unicode escape sequence".to_string(),
+                "Unicode escapes are written `\\u{XXXX}`, with 1 to 6 hex digits naming a valid character".to_string(),
+                *span,
+            ),
+            LexerErrorKind::InvalidCharLiteral { span } => (
+                "Invalid character literal".to_string(),
+                "Character literals must contain exactly one ASCII character, such as 'a' or '\\n'".to_string(),
+                *span,
+            ),
         }
     }
 }
@@ -109,7 +153,7 @@ impl LexerErrorKind {
 impl From<LexerErrorKind> for Diagnostic {
     fn from(error: LexerErrorKind) -> Diagnostic {
         let (primary, secondary, span) = error.parts();
-        Diagnostic::simple_error(primary, secondary, span)
+        Diagnostic::simple_error(primary, secondary, span).with_code(error.code())
     }
 }
 
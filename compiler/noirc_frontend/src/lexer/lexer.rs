@@ -6,6 +6,7 @@ use super::{
 };
 use acvm::FieldElement;
 use noirc_errors::{Position, Span};
+use num_bigint::BigUint;
 use std::str::Chars;
 use std::{
     iter::{Peekable, Zip},
@@ -119,6 +120,7 @@ impl<'a> Lexer<'a> {
             Some('[') => self.single_char_token(Token::LeftBracket),
             Some(']') => self.single_char_token(Token::RightBracket),
             Some('"') => self.eat_string_literal(),
+            Some('\'') => self.eat_char_literal(),
             Some('f') => self.eat_format_string_or_alpha_numeric(),
             Some('#') => self.eat_attribute(),
             Some(ch) if ch.is_ascii_alphanumeric() || ch == '_' => self.eat_alpha_numeric(ch),
@@ -255,6 +257,11 @@ impl<'a> Lexer<'a> {
     fn eat_attribute(&mut self) -> SpannedTokenResult {
         let start = self.position;
 
+        let is_inner = self.peek_char_is('!');
+        if is_inner {
+            self.next_char();
+        }
+
         if !self.peek_char_is('[') {
             return Err(LexerErrorKind::UnexpectedCharacter {
                 span: Span::single_char(self.position),
@@ -277,7 +284,17 @@ impl<'a> Lexer<'a> {
 
         let end = self.position;
 
-        let attribute = Attribute::lookup_attribute(&word, Span::inclusive(start, end))?;
+        let span = Span::inclusive(start, end);
+        let attribute = match Attribute::lookup_attribute(&word, span)? {
+            // Function attributes only apply to the function they're written above
+            Token::Attribute(Attribute::Secondary(attribute)) if is_inner => {
+                Token::InnerAttribute(attribute)
+            }
+            _ if is_inner => {
+                return Err(LexerErrorKind::MalformedFuncAttribute { span, found: word });
+            }
+            attribute => attribute,
+        };
 
         Ok(attribute.into_span(start, end))
     }
@@ -321,6 +338,19 @@ impl<'a> Lexer<'a> {
 
         let end = self.position;
 
+        // Literals which don't fit into a field element would otherwise be silently
+        // reduced modulo the field's order.
+        let value = match integer_str.strip_prefix("0x") {
+            Some(hex) => BigUint::parse_bytes(hex.as_bytes(), 16),
+            None => BigUint::parse_bytes(integer_str.as_bytes(), 10),
+        };
+        if matches!(value, Some(value) if value >= FieldElement::modulus()) {
+            return Err(LexerErrorKind::IntegerLiteralTooLarge {
+                span: Span::inclusive(start, end),
+                limit: (FieldElement::modulus() - 1u32).to_string(),
+            });
+        }
+
         let integer = match FieldElement::try_from_str(&integer_str) {
             None => {
                 return Err(LexerErrorKind::InvalidIntegerLiteral {
@@ -342,22 +372,7 @@ impl<'a> Lexer<'a> {
         while let Some(next) = self.next_char() {
             let char = match next {
                 '"' => break,
-                '\\' => match self.next_char() {
-                    Some('r') => '\r',
-                    Some('n') => '\n',
-                    Some('t') => '\t',
-                    Some('0') => '\0',
-                    Some('"') => '"',
-                    Some('\\') => '\\',
-                    Some(escaped) => {
-                        let span = Span::inclusive(start, self.position);
-                        return Err(LexerErrorKind::InvalidEscape { escaped, span });
-                    }
-                    None => {
-                        let span = Span::inclusive(start, self.position);
-                        return Err(LexerErrorKind::UnterminatedStringLiteral { span });
-                    }
-                },
+                '\\' => self.eat_escape(start)?,
                 other => other,
             };
 
@@ -370,6 +385,76 @@ impl<'a> Lexer<'a> {
         Ok(str_literal_token.into_span(start, end))
     }
 
+    /// Eats a character literal such as `'a'` or `'\n'`, which must contain a single ASCII character
+    fn eat_char_literal(&mut self) -> SpannedTokenResult {
+        let start = self.position;
+        let invalid = |lexer: &Self| LexerErrorKind::InvalidCharLiteral {
+            span: Span::inclusive(start, lexer.position),
+        };
+
+        let char = match self.next_char() {
+            Some('\\') => self.eat_escape(start)?,
+            Some('\'') | None => return Err(invalid(self)),
+            Some(other) => other,
+        };
+
+        if self.next_char() != Some('\'') || !char.is_ascii() {
+            return Err(invalid(self));
+        }
+
+        Ok(Token::Char(char as u8).into_span(start, self.position))
+    }
+
+    /// Eats the escape sequence following a `\` in a string or character literal starting at
+    /// `literal_start`, returning the character it stands for
+    fn eat_escape(&mut self, literal_start: u32) -> Result<char, LexerErrorKind> {
+        match self.next_char() {
+            Some('r') => Ok('\r'),
+            Some('n') => Ok('\n'),
+            Some('t') => Ok('\t'),
+            Some('0') => Ok('\0'),
+            Some('"') => Ok('"'),
+            Some('\'') => Ok('\''),
+            Some('\\') => Ok('\\'),
+            Some('u') => self.eat_unicode_escape(literal_start),
+            Some(escaped) => {
+                let span = Span::inclusive(literal_start, self.position);
+                Err(LexerErrorKind::InvalidEscape { escaped, span })
+            }
+            None => {
+                let span = Span::inclusive(literal_start, self.position);
+                Err(LexerErrorKind::UnterminatedStringLiteral { span })
+            }
+        }
+    }
+
+    /// Eats the `{XXXX}` following a `\u` in a string literal, returning the character it names
+    fn eat_unicode_escape(&mut self, string_start: u32) -> Result<char, LexerErrorKind> {
+        let escape_start = self.position - 1;
+        let invalid = |lexer: &Self| LexerErrorKind::InvalidUnicodeEscape {
+            span: Span::inclusive(escape_start, lexer.position),
+        };
+
+        if self.next_char() != Some('{') {
+            return Err(invalid(self));
+        }
+
+        let mut digits = String::new();
+        loop {
+            match self.next_char() {
+                Some('}') => break,
+                Some(digit) if digit.is_ascii_hexdigit() && digits.len() < 6 => digits.push(digit),
+                Some(_) => return Err(invalid(self)),
+                None => {
+                    let span = Span::inclusive(string_start, self.position);
+                    return Err(LexerErrorKind::UnterminatedStringLiteral { span });
+                }
+            }
+        }
+
+        u32::from_str_radix(&digits, 16).ok().and_then(char::from_u32).ok_or_else(|| invalid(self))
+    }
+
     // This differs from `eat_string_literal` in that we want the leading `f` to be captured in the Span
     fn eat_fmt_string(&mut self) -> SpannedTokenResult {
         let start = self.position;
@@ -482,7 +567,10 @@ impl<'a> Iterator for Lexer<'a> {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::token::{FunctionAttribute, SecondaryAttribute, TestScope};
+    use crate::token::{
+        CustomAttribute, FunctionAttribute, InlineType, SecondaryAttribute, StructLayout, TestScope,
+    };
+    use noirc_errors::LintLevel;
     #[test]
     fn test_single_double_char() {
         let input = "! != + ( ) { } [ ] | , ; : :: < <= > >= & - -> . .. % / * = == << >>";
@@ -539,6 +627,22 @@ mod tests {
         assert!(token.is_err());
     }
 
+    #[test]
+    fn integer_literal_exceeding_field_modulus() {
+        let modulus = FieldElement::modulus();
+        let largest = (modulus.clone() - 1u32).to_string();
+        let token = Lexer::new(&largest).next_token().unwrap();
+        assert_eq!(token.token(), &Token::Int(-FieldElement::one()));
+
+        for input in [modulus.to_string(), format!("0x{}", modulus.to_str_radix(16))] {
+            let error = Lexer::new(&input).next_token().unwrap_err();
+            assert!(matches!(
+                error,
+                LexerErrorKind::IntegerLiteralTooLarge { ref limit, .. } if *limit == largest
+            ));
+        }
+    }
+
     #[test]
     fn deprecated_attribute() {
         let input = r#"#[deprecated]"#;
@@ -592,12 +696,33 @@ mod tests {
         let token = lexer.next_token().unwrap();
         assert_eq!(
             token.token(),
-            &Token::Attribute(Attribute::Secondary(SecondaryAttribute::Custom(
-                "custom(hello)".to_string()
-            )))
+            &Token::Attribute(Attribute::Secondary(SecondaryAttribute::Custom(CustomAttribute {
+                name: "custom".to_string(),
+                arguments: vec!["hello".to_string()],
+            })))
         );
     }
 
+    #[test]
+    fn custom_attribute_with_path_and_arguments() {
+        let input = r#"#[my_framework::tag("x, y", inner(1, 2), 3)]"#;
+        let mut lexer = Lexer::new(input);
+
+        let token = lexer.next_token().unwrap();
+        let expected = CustomAttribute {
+            name: "my_framework::tag".to_string(),
+            arguments: vec![r#""x, y""#.to_string(), "inner(1, 2)".to_string(), "3".to_string()],
+        };
+        assert_eq!(
+            token.token(),
+            &Token::Attribute(Attribute::Secondary(SecondaryAttribute::Custom(expected)))
+        );
+
+        for malformed in ["#[my_framework::(x)]", "#[tag(\"x)]", "#[tag(x,)]", "#[tag(x]"] {
+            assert!(Lexer::new(malformed).next_token().is_err(), "{malformed} should not lex");
+        }
+    }
+
     #[test]
     fn test_attribute() {
         let input = r#"#[test]"#;
@@ -622,6 +747,100 @@ mod tests {
         );
     }
 
+    #[test]
+    fn derive_attribute() {
+        let input = r#"#[derive(Eq, Default)]"#;
+        let mut lexer = Lexer::new(input);
+
+        let token = lexer.next_token().unwrap();
+        assert_eq!(
+            token.token(),
+            &Token::Attribute(Attribute::Secondary(SecondaryAttribute::Derive(vec![
+                "Eq".to_string(),
+                "Default".to_string()
+            ])))
+        );
+
+        let mut lexer = Lexer::new("#[derive(Eq,)]");
+        assert!(lexer.next_token().is_err());
+    }
+
+    #[test]
+    fn repr_attribute() {
+        let mut lexer = Lexer::new("#[repr(sorted)]");
+        assert_eq!(
+            lexer.next_token().unwrap().token(),
+            &Token::Attribute(Attribute::Secondary(SecondaryAttribute::Repr(StructLayout::Sorted)))
+        );
+
+        let mut lexer = Lexer::new("#[repr(packed)]");
+        assert_eq!(
+            lexer.next_token().unwrap().token(),
+            &Token::Attribute(Attribute::Secondary(SecondaryAttribute::Repr(StructLayout::Packed)))
+        );
+
+        let mut lexer = Lexer::new("#[repr(C)]");
+        assert!(lexer.next_token().is_err());
+    }
+
+    #[test]
+    fn inline_attribute() {
+        let mut lexer = Lexer::new("#[inline(never)]");
+        assert_eq!(
+            lexer.next_token().unwrap().token(),
+            &Token::Attribute(Attribute::Secondary(SecondaryAttribute::Inline(InlineType::Never)))
+        );
+
+        let mut lexer = Lexer::new("#[inline(sometimes)]");
+        assert!(lexer.next_token().is_err());
+    }
+
+    #[test]
+    fn lint_attributes() {
+        let mut lexer = Lexer::new("#[allow(unused_variables, dead_code)]");
+        assert_eq!(
+            lexer.next_token().unwrap().token(),
+            &Token::Attribute(Attribute::Secondary(SecondaryAttribute::Lint(
+                LintLevel::Allow,
+                vec!["unused_variables".to_string(), "dead_code".to_string()]
+            )))
+        );
+
+        let mut lexer = Lexer::new("#![deny(unused_imports)]");
+        assert_eq!(
+            lexer.next_token().unwrap().token(),
+            &Token::InnerAttribute(SecondaryAttribute::Lint(
+                LintLevel::Deny,
+                vec!["unused_imports".to_string()]
+            ))
+        );
+
+        let mut lexer = Lexer::new("#[warn(not_a_lint)]");
+        assert!(lexer.next_token().is_err());
+
+        let mut lexer = Lexer::new("#![test]");
+        assert!(lexer.next_token().is_err());
+    }
+
+    #[test]
+    fn prelude_attributes() {
+        let mut lexer = Lexer::new("#![no_std_prelude]");
+        assert_eq!(
+            lexer.next_token().unwrap().token(),
+            &Token::InnerAttribute(SecondaryAttribute::NoStdPrelude)
+        );
+
+        let mut lexer = Lexer::new("#![prelude(dep::dsl::prelude)]");
+        let token = lexer.next_token().unwrap();
+        let Token::InnerAttribute(SecondaryAttribute::Prelude(path, _)) = token.token() else {
+            panic!("Expected a prelude attribute, got: {token:?}");
+        };
+        assert_eq!(path, "dep::dsl::prelude");
+
+        let mut lexer = Lexer::new("#![prelude(dsl::)]");
+        assert!(lexer.next_token().is_err());
+    }
+
     #[test]
     fn test_attribute_with_valid_scope() {
         let input = r#"#[test(should_fail)]"#;
@@ -831,6 +1050,46 @@ mod tests {
         }
     }
 
+    #[test]
+    fn string_literal_escapes() {
+        let input = r#""a\tb\n\"c\" \\ \u{48}\u{1F600} é""#;
+        let token = Lexer::new(input).next_token().unwrap();
+        assert_eq!(token.token(), &Token::Str("a\tb\n\"c\" \\ H\u{1F600} é".to_string()));
+
+        // Literals keep their decoded contents, so their length is the number of UTF-8 bytes
+        if let Token::Str(string) = token.token() {
+            assert_eq!(string.len(), 18);
+        }
+
+        for invalid in
+            [r#""\u48""#, r#""\u{}""#, r#""\u{1234567}""#, r#""\u{D800}""#, r#""\u{zz}""#]
+        {
+            let error = Lexer::new(invalid).next_token().unwrap_err();
+            assert!(
+                matches!(error, LexerErrorKind::InvalidUnicodeEscape { .. }),
+                "expected {invalid} to be an invalid unicode escape, got {error:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn char_literals() {
+        let input = r#"'a' '\n' '\'' '"' '\u{41}'"#;
+        let expected = [b'a', b'\n', b'\'', b'"', b'A'];
+        let mut lexer = Lexer::new(input);
+        for byte in expected {
+            assert_eq!(lexer.next_token().unwrap().token(), &Token::Char(byte));
+        }
+
+        for invalid in ["''", "'ab'", "'é'", "'a", r#"'\u{e9}'"#] {
+            let error = Lexer::new(invalid).next_token().unwrap_err();
+            assert!(
+                matches!(error, LexerErrorKind::InvalidCharLiteral { .. }),
+                "expected {invalid} to be an invalid character literal, got {error:?}"
+            );
+        }
+    }
+
     #[test]
     fn test_eat_hex_int() {
         let input = "0x05";
@@ -1,5 +1,7 @@
 use acvm::FieldElement;
-use noirc_errors::{Position, Span, Spanned};
+use noirc_errors::{Lint, LintLevel, Position, Span, Spanned};
+use noirc_printable_type::FieldDisplay;
+use serde::{Deserialize, Serialize};
 use std::{fmt, iter::Map, vec::IntoIter};
 
 use crate::lexer::errors::LexerErrorKind;
@@ -16,9 +18,13 @@ pub enum Token {
     Bool(bool),
     Str(String),
     FmtStr(String),
+    /// A character literal such as `'a'`, holding its ASCII code
+    Char(u8),
     Keyword(Keyword),
     IntType(IntType),
     Attribute(Attribute),
+    /// An attribute applying to the module it is written in, e.g. `#![allow(dead_code)]`
+    InnerAttribute(SecondaryAttribute),
     LineComment(String, Option<DocStyle>),
     BlockComment(String, Option<DocStyle>),
     /// <
@@ -151,12 +157,16 @@ impl fmt::Display for Token {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match *self {
             Token::Ident(ref s) => write!(f, "{s}"),
-            Token::Int(n) => write!(f, "{}", n.to_u128()),
+            Token::Int(n) => write!(f, "{}", FieldDisplay::Decimal.format(n)),
             Token::Bool(b) => write!(f, "{b}"),
             Token::Str(ref b) => write!(f, "{b}"),
             Token::FmtStr(ref b) => write!(f, "f{b}"),
+            Token::Char(c) => write!(f, "'{}'", (c as char).escape_default()),
             Token::Keyword(k) => write!(f, "{k}"),
             Token::Attribute(ref a) => write!(f, "{a}"),
+            Token::InnerAttribute(ref a) => {
+                write!(f, "#!{}", a.to_string().trim_start_matches('#'))
+            }
             Token::LineComment(ref s, _style) => write!(f, "//{s}"),
             Token::BlockComment(ref s, _style) => write!(f, "/*{s}*/"),
             Token::IntType(ref i) => write!(f, "{i}"),
@@ -206,6 +216,7 @@ pub enum TokenKind {
     Literal,
     Keyword,
     Attribute,
+    InnerAttribute,
 }
 
 impl fmt::Display for TokenKind {
@@ -216,6 +227,7 @@ impl fmt::Display for TokenKind {
             TokenKind::Literal => write!(f, "literal"),
             TokenKind::Keyword => write!(f, "keyword"),
             TokenKind::Attribute => write!(f, "attribute"),
+            TokenKind::InnerAttribute => write!(f, "inner attribute"),
         }
     }
 }
@@ -224,9 +236,12 @@ impl Token {
     pub fn kind(&self) -> TokenKind {
         match *self {
             Token::Ident(_) => TokenKind::Ident,
-            Token::Int(_) | Token::Bool(_) | Token::Str(_) | Token::FmtStr(_) => TokenKind::Literal,
+            Token::Int(_) | Token::Bool(_) | Token::Str(_) | Token::FmtStr(_) | Token::Char(_) => {
+                TokenKind::Literal
+            }
             Token::Keyword(_) => TokenKind::Keyword,
             Token::Attribute(_) => TokenKind::Attribute,
+            Token::InnerAttribute(_) => TokenKind::InnerAttribute,
             ref tok => TokenKind::Token(tok.clone()),
         }
     }
@@ -327,7 +342,7 @@ impl IntType {
 }
 
 /// TestScope is used to specify additional annotations for test functions
-#[derive(PartialEq, Eq, Hash, Debug, Clone, PartialOrd, Ord)]
+#[derive(PartialEq, Eq, Hash, Debug, Clone, PartialOrd, Ord, Serialize, Deserialize)]
 pub enum TestScope {
     /// If a test has a scope of ShouldFailWith, then it can only pass
     /// if it fails with the specified reason. If the reason is None, then
@@ -368,7 +383,89 @@ impl fmt::Display for TestScope {
     }
 }
 
-#[derive(PartialEq, Eq, Hash, Debug, Clone, PartialOrd, Ord)]
+/// How the fields of a struct are flattened into field elements, such as in the public inputs of a
+/// program or the arguments of a foreign call.
+#[derive(
+    PartialEq, Eq, Hash, Debug, Clone, Copy, Default, PartialOrd, Ord, Serialize, Deserialize,
+)]
+pub enum StructLayout {
+    /// The fields are in the order they are declared, which is the default
+    #[default]
+    Declared,
+    /// The fields are in the alphabetical order of their names
+    Sorted,
+    /// The fields are in the order they are declared, and a struct passed to or returned from
+    /// a foreign call is passed as a single array of all of its flattened field elements
+    Packed,
+}
+
+impl StructLayout {
+    fn lookup_str(string: &str) -> Option<StructLayout> {
+        match string.trim() {
+            "declared" => Some(StructLayout::Declared),
+            "sorted" => Some(StructLayout::Sorted),
+            "packed" => Some(StructLayout::Packed),
+            _ => None,
+        }
+    }
+
+    /// Reorders `fields`, given in the order they are declared, into the order of this layout
+    pub fn arrange<T>(self, fields: &mut [T], name: impl Fn(&T) -> &str) {
+        if self == StructLayout::Sorted {
+            fields.sort_by(|lhs, rhs| name(lhs).cmp(name(rhs)));
+        }
+    }
+}
+
+impl fmt::Display for StructLayout {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            StructLayout::Declared => write!(f, "declared"),
+            StructLayout::Sorted => write!(f, "sorted"),
+            StructLayout::Packed => write!(f, "packed"),
+        }
+    }
+}
+
+/// Whether calls to a function should be inlined into their callers, as set by
+/// `#[inline(always)]` or `#[inline(never)]`.
+#[derive(
+    PartialEq, Eq, Hash, Debug, Clone, Copy, Default, PartialOrd, Ord, Serialize, Deserialize,
+)]
+pub enum InlineType {
+    /// The cost model decides whether calls are inlined, although calls to unconstrained
+    /// functions from constrained code are always kept
+    #[default]
+    Default,
+    /// Calls must be inlined, including calls to unconstrained functions from unconstrained code,
+    /// and constrained functions marked with this are never fold boundaries
+    Always,
+    /// Calls are kept. Constrained functions marked with this are fold boundaries, which are
+    /// compiled on their own before being inlined into the circuit
+    Never,
+}
+
+impl InlineType {
+    fn lookup_str(string: &str) -> Option<InlineType> {
+        match string.trim() {
+            "always" => Some(InlineType::Always),
+            "never" => Some(InlineType::Never),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for InlineType {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            InlineType::Default => write!(f, "default"),
+            InlineType::Always => write!(f, "always"),
+            InlineType::Never => write!(f, "never"),
+        }
+    }
+}
+
+#[derive(PartialEq, Eq, Hash, Debug, Clone, PartialOrd, Ord, Serialize, Deserialize)]
 // Attributes are special language markers in the target language
 // An example of one is `#[SHA256]` . Currently only Foreign attributes are supported
 // Calls to functions which have the foreign attribute are executed in the host language
@@ -407,10 +504,16 @@ impl Attributes {
 
     /// Returns note if a deprecated secondary attribute is found
     pub fn get_deprecated_note(&self) -> Option<Option<String>> {
-        self.secondary.iter().find_map(|attr| match attr {
-            SecondaryAttribute::Deprecated(note) => Some(note.clone()),
+        SecondaryAttribute::get_deprecated_note(&self.secondary)
+    }
+
+    /// Returns the inlining hint set by the last `#[inline(...)]` attribute, if there is one
+    pub fn inline_type(&self) -> InlineType {
+        let inline_types = self.secondary.iter().filter_map(|attr| match attr {
+            SecondaryAttribute::Inline(inline_type) => Some(*inline_type),
             _ => None,
-        })
+        });
+        inline_types.last().unwrap_or_default()
     }
 
     pub fn get_field_attribute(&self) -> Option<String> {
@@ -421,6 +524,18 @@ impl Attributes {
         }
         None
     }
+
+    /// Returns the attributes not recognized by the compiler, which are passed through
+    /// to compiled artifacts
+    pub fn custom_attributes(&self) -> Vec<CustomAttribute> {
+        self.secondary
+            .iter()
+            .filter_map(|attr| match attr {
+                SecondaryAttribute::Custom(attribute) => Some(attribute.clone()),
+                _ => None,
+            })
+            .collect()
+    }
 }
 
 /// An Attribute can be either a Primary Attribute or a Secondary Attribute
@@ -502,6 +617,66 @@ impl Attribute {
                 Attribute::Secondary(SecondaryAttribute::ContractLibraryMethod)
             }
             ["event"] => Attribute::Secondary(SecondaryAttribute::Event),
+            ["derive", names] => {
+                let names: Vec<String> =
+                    names.split(',').map(|name| name.trim().to_string()).collect();
+                let is_valid = names.iter().all(|name| {
+                    !name.is_empty()
+                        && name.chars().all(|ch| ch.is_ascii_alphanumeric() || ch == '_')
+                });
+                if !is_valid {
+                    return Err(LexerErrorKind::MalformedFuncAttribute {
+                        span,
+                        found: word.to_owned(),
+                    });
+                }
+                Attribute::Secondary(SecondaryAttribute::Derive(names))
+            }
+            [level, names] if LintLevel::lookup(level).is_some() => {
+                let names: Vec<String> =
+                    names.split(',').map(|name| name.trim().to_string()).collect();
+                if !names.iter().all(|name| Lint::lookup(name).is_some()) {
+                    return Err(LexerErrorKind::MalformedFuncAttribute {
+                        span,
+                        found: word.to_owned(),
+                    });
+                }
+                let level = LintLevel::lookup(level).expect("level was checked above");
+                Attribute::Secondary(SecondaryAttribute::Lint(level, names))
+            }
+            ["no_std_prelude"] => Attribute::Secondary(SecondaryAttribute::NoStdPrelude),
+            ["prelude", path] => {
+                let path = path.trim();
+                let is_path = path.split("::").all(|segment| {
+                    !segment.is_empty()
+                        && segment.chars().all(|ch| ch.is_ascii_alphanumeric() || ch == '_')
+                });
+                if !is_path {
+                    return Err(LexerErrorKind::MalformedFuncAttribute {
+                        span,
+                        found: word.to_owned(),
+                    });
+                }
+                Attribute::Secondary(SecondaryAttribute::Prelude(path.to_string(), span))
+            }
+            ["repr", layout] => match StructLayout::lookup_str(layout) {
+                Some(layout) => Attribute::Secondary(SecondaryAttribute::Repr(layout)),
+                None => {
+                    return Err(LexerErrorKind::MalformedFuncAttribute {
+                        span,
+                        found: word.to_owned(),
+                    })
+                }
+            },
+            ["inline", inline_type] => match InlineType::lookup_str(inline_type) {
+                Some(inline_type) => Attribute::Secondary(SecondaryAttribute::Inline(inline_type)),
+                None => {
+                    return Err(LexerErrorKind::MalformedFuncAttribute {
+                        span,
+                        found: word.to_owned(),
+                    })
+                }
+            },
             ["deprecated", name] => {
                 if !name.starts_with('"') && !name.ends_with('"') {
                     return Err(LexerErrorKind::MalformedFuncAttribute {
@@ -514,10 +689,9 @@ impl Attribute {
                     name.trim_matches('"').to_string().into(),
                 ))
             }
-            tokens => {
-                tokens.iter().try_for_each(|token| validate(token))?;
-                Attribute::Secondary(SecondaryAttribute::Custom(word.to_owned()))
-            }
+            _ => Attribute::Secondary(SecondaryAttribute::Custom(CustomAttribute::parse(
+                word, span,
+            )?)),
         };
 
         Ok(Token::Attribute(attribute))
@@ -526,7 +700,7 @@ impl Attribute {
 
 /// Primary Attributes are those which a function can only have one of.
 /// They change the FunctionKind and thus have direct impact on the IR output
-#[derive(PartialEq, Eq, Hash, Debug, Clone, PartialOrd, Ord)]
+#[derive(PartialEq, Eq, Hash, Debug, Clone, PartialOrd, Ord, Serialize, Deserialize)]
 pub enum FunctionAttribute {
     Foreign(String),
     Builtin(String),
@@ -572,7 +746,7 @@ impl fmt::Display for FunctionAttribute {
 /// Secondary attributes are those which a function can have many of.
 /// They are not able to change the `FunctionKind` and thus do not have direct impact on the IR output
 /// They are often consumed by libraries or used as notices for the developer
-#[derive(PartialEq, Eq, Hash, Debug, Clone, PartialOrd, Ord)]
+#[derive(PartialEq, Eq, Hash, Debug, Clone, PartialOrd, Ord, Serialize, Deserialize)]
 pub enum SecondaryAttribute {
     Deprecated(Option<String>),
     // This is an attribute to specify that a function
@@ -581,7 +755,30 @@ pub enum SecondaryAttribute {
     ContractLibraryMethod,
     Event,
     Field(String),
-    Custom(String),
+    // Traits whose impls are generated from the shape of a struct, e.g. `#[derive(Eq, Default)]`
+    Derive(Vec<String>),
+    // Sets the level of the named lints within the item, e.g. `#[allow(unused_variables)]`
+    Lint(LintLevel, Vec<String>),
+    // Written in the crate root as `#![no_std_prelude]` so that `std::prelude` isn't in scope
+    NoStdPrelude,
+    // Written in the crate root as `#![prelude(path)]` to bring the items of the module at
+    // `path` into scope instead of `std::prelude`, along with the span of the attribute
+    Prelude(String, Span),
+    // Sets how the fields of a struct are flattened, e.g. `#[repr(sorted)]`
+    Repr(StructLayout),
+    // Sets whether calls to a function are inlined, e.g. `#[inline(never)]`
+    Inline(InlineType),
+    Custom(CustomAttribute),
+}
+
+impl SecondaryAttribute {
+    /// Returns note if a deprecated attribute is found among `attributes`
+    pub fn get_deprecated_note(attributes: &[SecondaryAttribute]) -> Option<Option<String>> {
+        attributes.iter().find_map(|attr| match attr {
+            SecondaryAttribute::Deprecated(note) => Some(note.clone()),
+            _ => None,
+        })
+    }
 }
 
 impl fmt::Display for SecondaryAttribute {
@@ -591,11 +788,100 @@ impl fmt::Display for SecondaryAttribute {
             SecondaryAttribute::Deprecated(Some(ref note)) => {
                 write!(f, r#"#[deprecated("{note}")]"#)
             }
-            SecondaryAttribute::Custom(ref k) => write!(f, "#[{k}]"),
+            SecondaryAttribute::Custom(ref attribute) => write!(f, "#[{attribute}]"),
             SecondaryAttribute::ContractLibraryMethod => write!(f, "#[contract_library_method]"),
             SecondaryAttribute::Event => write!(f, "#[event]"),
             SecondaryAttribute::Field(ref k) => write!(f, "#[field({k})]"),
+            SecondaryAttribute::Derive(ref names) => write!(f, "#[derive({})]", names.join(", ")),
+            SecondaryAttribute::Lint(level, ref names) => {
+                write!(f, "#[{level}({})]", names.join(", "))
+            }
+            SecondaryAttribute::NoStdPrelude => write!(f, "#[no_std_prelude]"),
+            SecondaryAttribute::Prelude(ref path, _) => write!(f, "#[prelude({path})]"),
+            SecondaryAttribute::Repr(layout) => write!(f, "#[repr({layout})]"),
+            SecondaryAttribute::Inline(inline_type) => write!(f, "#[inline({inline_type})]"),
+        }
+    }
+}
+
+/// An attribute which is not recognized by the compiler, such as `#[aztec(private)]` or
+/// `#[my_framework::tag("x")]`. These have no effect on compilation and are instead passed
+/// through to the compiled artifact for downstream tooling to consume.
+#[derive(PartialEq, Eq, Hash, Debug, Clone, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct CustomAttribute {
+    /// The path naming the attribute, e.g. `my_framework::tag`
+    pub name: String,
+    /// The comma separated arguments of the attribute, if any.
+    /// String arguments keep their quotes, e.g. `"x"`.
+    pub arguments: Vec<String>,
+}
+
+impl CustomAttribute {
+    fn parse(word: &str, span: Span) -> Result<CustomAttribute, LexerErrorKind> {
+        let malformed = || LexerErrorKind::MalformedFuncAttribute { span, found: word.to_owned() };
+
+        let (name, arguments) = match word.split_once('(') {
+            Some((name, rest)) => {
+                let arguments = rest.strip_suffix(')').ok_or_else(malformed)?;
+                (name.trim(), split_attribute_arguments(arguments).ok_or_else(malformed)?)
+            }
+            None => (word.trim(), Vec::new()),
+        };
+
+        let is_path = name.split("::").all(|segment| {
+            !segment.is_empty() && segment.chars().all(|ch| ch.is_ascii_alphanumeric() || ch == '_')
+        });
+        if !is_path {
+            return Err(malformed());
+        }
+
+        Ok(CustomAttribute { name: name.to_owned(), arguments })
+    }
+}
+
+/// Splits the arguments of an attribute on each comma which is not within a string or
+/// parentheses. Returns `None` if a string or parenthesis is left unclosed or if an
+/// argument is empty.
+fn split_attribute_arguments(arguments: &str) -> Option<Vec<String>> {
+    let mut result = Vec::new();
+    let mut current = String::new();
+    let mut depth = 0usize;
+    let mut in_string = false;
+
+    for ch in arguments.chars() {
+        match ch {
+            '"' => in_string = !in_string,
+            '(' if !in_string => depth += 1,
+            ')' if !in_string => depth = depth.checked_sub(1)?,
+            ',' if !in_string && depth == 0 => {
+                result.push(current.trim().to_owned());
+                current.clear();
+                continue;
+            }
+            _ => (),
+        }
+        current.push(ch);
+    }
+
+    if in_string || depth != 0 {
+        return None;
+    }
+    if !current.trim().is_empty() || !result.is_empty() {
+        result.push(current.trim().to_owned());
+    }
+    if result.iter().any(|argument| argument.is_empty()) {
+        return None;
+    }
+    Some(result)
+}
+
+impl fmt::Display for CustomAttribute {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name)?;
+        if !self.arguments.is_empty() {
+            write!(f, "({})", self.arguments.join(", "))?;
         }
+        Ok(())
     }
 }
 
@@ -615,9 +901,16 @@ impl AsRef<str> for SecondaryAttribute {
         match self {
             SecondaryAttribute::Deprecated(Some(string)) => string,
             SecondaryAttribute::Deprecated(None) => "",
-            SecondaryAttribute::Custom(string) | SecondaryAttribute::Field(string) => string,
+            SecondaryAttribute::Custom(attribute) => &attribute.name,
+            SecondaryAttribute::Field(string) => string,
             SecondaryAttribute::ContractLibraryMethod => "",
             SecondaryAttribute::Event => "",
+            SecondaryAttribute::Derive(_) => "",
+            SecondaryAttribute::Lint(..) => "",
+            SecondaryAttribute::NoStdPrelude => "",
+            SecondaryAttribute::Prelude(path, _) => path,
+            SecondaryAttribute::Repr(_) => "",
+            SecondaryAttribute::Inline(_) => "",
         }
     }
 }
@@ -631,13 +924,16 @@ pub enum Keyword {
     Assert,
     AssertEq,
     Bool,
+    Break,
     Char,
     CompTime,
+    Const,
     Constrain,
     Contract,
     Crate,
     Dep,
     Distinct,
+    Dyn,
     Else,
     Field,
     Fn,
@@ -649,6 +945,7 @@ pub enum Keyword {
     In,
     Internal,
     Let,
+    Loop,
     Mod,
     Mut,
     Open,
@@ -671,13 +968,16 @@ impl fmt::Display for Keyword {
             Keyword::Assert => write!(f, "assert"),
             Keyword::AssertEq => write!(f, "assert_eq"),
             Keyword::Bool => write!(f, "bool"),
+            Keyword::Break => write!(f, "break"),
             Keyword::Char => write!(f, "char"),
             Keyword::CompTime => write!(f, "comptime"),
+            Keyword::Const => write!(f, "const"),
             Keyword::Constrain => write!(f, "constrain"),
             Keyword::Contract => write!(f, "contract"),
             Keyword::Crate => write!(f, "crate"),
             Keyword::Dep => write!(f, "dep"),
             Keyword::Distinct => write!(f, "distinct"),
+            Keyword::Dyn => write!(f, "dyn"),
             Keyword::Else => write!(f, "else"),
             Keyword::Field => write!(f, "Field"),
             Keyword::Fn => write!(f, "fn"),
@@ -689,6 +989,7 @@ impl fmt::Display for Keyword {
             Keyword::In => write!(f, "in"),
             Keyword::Internal => write!(f, "internal"),
             Keyword::Let => write!(f, "let"),
+            Keyword::Loop => write!(f, "loop"),
             Keyword::Mod => write!(f, "mod"),
             Keyword::Mut => write!(f, "mut"),
             Keyword::Open => write!(f, "open"),
@@ -714,13 +1015,16 @@ impl Keyword {
             "assert" => Keyword::Assert,
             "assert_eq" => Keyword::AssertEq,
             "bool" => Keyword::Bool,
+            "break" => Keyword::Break,
             "char" => Keyword::Char,
             "comptime" => Keyword::CompTime,
+            "const" => Keyword::Const,
             "constrain" => Keyword::Constrain,
             "contract" => Keyword::Contract,
             "crate" => Keyword::Crate,
             "dep" => Keyword::Dep,
             "distinct" => Keyword::Distinct,
+            "dyn" => Keyword::Dyn,
             "else" => Keyword::Else,
             "Field" => Keyword::Field,
             "fn" => Keyword::Fn,
@@ -732,6 +1036,7 @@ impl Keyword {
             "in" => Keyword::In,
             "internal" => Keyword::Internal,
             "let" => Keyword::Let,
+            "loop" => Keyword::Loop,
             "mod" => Keyword::Mod,
             "mut" => Keyword::Mut,
             "open" => Keyword::Open,
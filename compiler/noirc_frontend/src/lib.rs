@@ -10,6 +10,7 @@
 #![warn(unreachable_pub)]
 #![warn(clippy::semicolon_if_nothing_returned)]
 
+pub mod api;
 pub mod ast;
 pub mod graph;
 pub mod lexer;
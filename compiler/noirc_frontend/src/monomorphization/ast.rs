@@ -2,7 +2,9 @@ use acvm::FieldElement;
 use iter_extended::vecmap;
 use noirc_errors::Location;
 
-use crate::{hir_def::function::FunctionSignature, BinaryOpKind, Distinctness, Signedness};
+use crate::{
+    hir_def::function::FunctionSignature, token::InlineType, BinaryOpKind, Distinctness, Signedness,
+};
 
 /// The monomorphized AST is expression-based, all statements are also
 /// folded into this expression enum. Compared to the HIR, the monomorphized
@@ -24,6 +26,9 @@ pub enum Expression {
     Index(Index),
     Cast(Cast),
     For(For),
+    /// A `loop`, only used in unconstrained functions
+    Loop(Box<Expression>),
+    Break,
     If(If),
     Tuple(Vec<Expression>),
     ExtractTupleField(Box<Expression>, usize),
@@ -44,6 +49,8 @@ pub enum Definition {
     LowLevel(String),
     // used as a foreign/externally defined unconstrained function
     Oracle(String),
+    /// A `global mut`, given by its index in `Program::mutable_globals`
+    MutableGlobal(usize),
 }
 
 /// ID of a local definition, e.g. from a let binding or
@@ -146,6 +153,8 @@ pub struct Index {
     pub index: Box<Expression>,
     pub element_type: Type,
     pub location: Location,
+    /// False for `get_unchecked`, which skips checking that a slice index is within its length
+    pub checked: bool,
 }
 
 /// Rather than a Pattern containing possibly several variables, Let now
@@ -202,6 +211,17 @@ pub struct Function {
 
     pub return_type: Type,
     pub unconstrained: bool,
+    /// Whether calls to this function should be inlined, as set by its `#[inline(...)]` attribute
+    pub inline_type: InlineType,
+}
+
+/// A `global mut` used by the program. Each time constrained code calls unconstrained code,
+/// the global starts out with its initial value.
+#[derive(Debug, Clone, Hash)]
+pub struct MutableGlobal {
+    pub name: String,
+    pub typ: Type,
+    pub initial_value: Expression,
 }
 
 /// Compared to hir_def::types::Type, this monomorphized Type has:
@@ -243,6 +263,7 @@ pub struct Program {
     /// forwarding to the next phase.
     pub return_distinctness: Distinctness,
     pub return_location: Option<Location>,
+    pub mutable_globals: Vec<MutableGlobal>,
 }
 
 impl Program {
@@ -251,8 +272,15 @@ impl Program {
         main_function_signature: FunctionSignature,
         return_distinctness: Distinctness,
         return_location: Option<Location>,
+        mutable_globals: Vec<MutableGlobal>,
     ) -> Program {
-        Program { functions, main_function_signature, return_distinctness, return_location }
+        Program {
+            functions,
+            main_function_signature,
+            return_distinctness,
+            return_location,
+            mutable_globals,
+        }
     }
 
     pub fn main(&self) -> &Function {
@@ -296,6 +324,10 @@ impl std::ops::IndexMut<FuncId> for Program {
 
 impl std::fmt::Display for Program {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (index, global) in self.mutable_globals.iter().enumerate() {
+            let MutableGlobal { name, typ, initial_value } = global;
+            writeln!(f, "global mut {name}$g{index}: {typ} = {initial_value};")?;
+        }
         for function in &self.functions {
             super::printer::AstPrinter::default().print_function(function, f)?;
         }
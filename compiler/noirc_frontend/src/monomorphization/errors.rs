@@ -0,0 +1,137 @@
+use iter_extended::vecmap;
+use noirc_errors::codes::{E0400, E0401, E0402, E0403};
+use noirc_errors::{CustomDiagnostic as Diagnostic, ErrorCode, FileDiagnostic, Location};
+use thiserror::Error;
+
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum MonomorphizationError {
+    #[error("Type-level expression cannot be evaluated")]
+    InvalidTypeExpression {
+        expression: String,
+        reason: &'static str,
+        /// The generic function whose instantiation at `location` led to this expression
+        function: Option<String>,
+        location: Location,
+    },
+    #[error("Numeric constraint is not satisfied")]
+    UnsatisfiedNumericConstraint {
+        constraint: String,
+        /// The constraint with each numeric generic replaced by its value in this instantiation
+        instantiated: String,
+        function: String,
+        location: Location,
+    },
+    #[error("Constrained functions cannot be recursive")]
+    RecursiveConstrainedFunction {
+        /// Each call in the cycle, starting with the first one reached from `main`
+        cycle: Vec<RecursiveCall>,
+    },
+    #[error("The argument of `selector_of` must be the name of a function")]
+    SelectorOfNonFunction { location: Location },
+}
+
+/// A call from one constrained function to another, as part of a cycle of calls.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RecursiveCall {
+    pub caller: String,
+    pub callee: String,
+    pub location: Location,
+}
+
+impl MonomorphizationError {
+    fn location(&self) -> Location {
+        match self {
+            MonomorphizationError::InvalidTypeExpression { location, .. }
+            | MonomorphizationError::UnsatisfiedNumericConstraint { location, .. }
+            | MonomorphizationError::SelectorOfNonFunction { location } => *location,
+            MonomorphizationError::RecursiveConstrainedFunction { cycle } => cycle[0].location,
+        }
+    }
+
+    pub fn code(&self) -> ErrorCode {
+        match self {
+            MonomorphizationError::InvalidTypeExpression { .. } => E0400,
+            MonomorphizationError::UnsatisfiedNumericConstraint { .. } => E0403,
+            MonomorphizationError::RecursiveConstrainedFunction { .. } => E0401,
+            MonomorphizationError::SelectorOfNonFunction { .. } => E0402,
+        }
+    }
+}
+
+impl From<MonomorphizationError> for FileDiagnostic {
+    fn from(error: MonomorphizationError) -> FileDiagnostic {
+        let file = error.location().file;
+        Diagnostic::from(error).in_file(file)
+    }
+}
+
+impl From<MonomorphizationError> for Diagnostic {
+    fn from(error: MonomorphizationError) -> Diagnostic {
+        let code = error.code();
+        let diagnostic = match error {
+            MonomorphizationError::InvalidTypeExpression {
+                expression,
+                reason,
+                function,
+                location,
+            } => {
+                let message = match function {
+                    Some(function) => {
+                        format!("Cannot evaluate `{expression}` when instantiating `{function}`")
+                    }
+                    None => format!("Cannot evaluate array length `{expression}`"),
+                };
+                Diagnostic::simple_error(message, reason.to_string(), location.span)
+            }
+            MonomorphizationError::UnsatisfiedNumericConstraint {
+                constraint,
+                instantiated,
+                function,
+                location,
+            } => Diagnostic::simple_error(
+                format!("`{function}` requires `{constraint}`, which is not satisfied here"),
+                format!("`{instantiated}` is false for this instantiation of `{function}`"),
+                location.span,
+            ),
+            MonomorphizationError::RecursiveConstrainedFunction { cycle } => {
+                let first = &cycle[0];
+                let message = if cycle.len() == 1 {
+                    format!("Constrained function `{}` calls itself", first.caller)
+                } else {
+                    format!(
+                        "Constrained function `{}` calls itself through other functions",
+                        first.caller
+                    )
+                };
+                let mut diagnostic = Diagnostic::simple_error(
+                    message,
+                    format!("`{}` calls `{}` here", first.caller, first.callee),
+                    first.location.span,
+                );
+
+                // Labels can only point into the file of the diagnostic, so calls in other
+                // files are only listed in the note below
+                for call in
+                    cycle.iter().skip(1).filter(|call| call.location.file == first.location.file)
+                {
+                    diagnostic.add_secondary(
+                        format!("`{}` calls `{}` here", call.caller, call.callee),
+                        call.location.span,
+                    );
+                }
+
+                let mut path = vecmap(&cycle, |call| format!("`{}`", call.caller));
+                path.push(format!("`{}`", first.caller));
+                diagnostic.add_note(format!("call cycle: {}", path.join(" -> ")));
+                diagnostic.add_note("help: constrained functions are inlined and so cannot be recursive, use a loop or make one of these functions `unconstrained`".to_string());
+                diagnostic
+            }
+            MonomorphizationError::SelectorOfNonFunction { location } => Diagnostic::simple_error(
+                "The argument of `selector_of` must be the name of a function".to_string(),
+                "Selectors are computed from the function's signature at compile-time".to_string(),
+                location.span,
+            ),
+        };
+        diagnostic.with_code(code)
+    }
+}
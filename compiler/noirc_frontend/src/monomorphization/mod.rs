@@ -21,19 +21,27 @@ use crate::{
     hir_def::{
         expr::*,
         function::{FuncMeta, FunctionSignature, Parameters},
-        stmt::{HirAssignStatement, HirLValue, HirLetStatement, HirPattern, HirStatement},
-        types,
+        stmt::{
+            HirAssertMessage, HirAssignStatement, HirLValue, HirLetStatement, HirPattern,
+            HirStatement,
+        },
+        types::{self, BinaryTypeOperator},
+    },
+    node_interner::{
+        self, Conversion, DefinitionKind, ExprId, NodeInterner, StmtId, TraitId, TraitMethodId,
     },
-    node_interner::{self, DefinitionKind, NodeInterner, StmtId, TraitImplKey, TraitMethodId},
-    token::FunctionAttribute,
-    ContractFunctionType, FunctionKind, Type, TypeBinding, TypeBindings, TypeVariableKind,
-    Visibility,
+    token::{FunctionAttribute, InlineType},
+    BinaryOpKind, ContractFunctionType, FunctionKind, Type, TypeBinding, TypeBindings,
+    TypeVariableKind, UnaryOp, Visibility,
 };
 
 use self::ast::{Definition, FuncId, Function, LocalId, Program};
+use self::errors::MonomorphizationError;
 
 pub mod ast;
+pub mod errors;
 pub mod printer;
+mod recursion;
 
 struct LambdaContext {
     env_ident: ast::Ident,
@@ -49,8 +57,10 @@ struct Monomorphizer<'interner> {
     /// a new version of the global for each type. Note that 'global' here means 'globally
     /// visible' and thus includes both functions and global variables.
     ///
-    /// Using nested HashMaps here lets us avoid cloning HirTypes when calling .get()
-    globals: HashMap<node_interner::FuncId, HashMap<HirType, FuncId>>,
+    /// The types the function's generics are instantiated with are part of the key as well,
+    /// since a generic which isn't used in the function's signature, as in `zeroed::<N>`,
+    /// doesn't change its type.
+    globals: HashMap<node_interner::FuncId, HashMap<InstantiationKey, FuncId>>,
 
     /// Unlike globals, locals are only keyed by their unique ID because they are never
     /// duplicated during monomorphization. Doing so would allow them to be used polymorphically
@@ -75,11 +85,39 @@ struct Monomorphizer<'interner> {
 
     is_range_loop: bool,
 
+    /// Whether the function being monomorphized is unconstrained, in which case `Drop` impls
+    /// are called on its variables when they go out of scope
+    in_unconstrained_function: bool,
+
+    /// The variables bound so far by each block being monomorphized in unconstrained code,
+    /// innermost last, which are dropped when the block ends or a `break` exits it
+    drop_scopes: Vec<Vec<(HirIdent, ExprId)>>,
+
+    /// For each `loop` being monomorphized, the number of drop scopes outside of it
+    loop_drop_depths: Vec<usize>,
+
     return_location: Option<Location>,
+
+    /// The `global mut`s used by the program, in the order they're first used
+    mutable_globals: Vec<ast::MutableGlobal>,
+
+    /// The index of each `global mut` within `mutable_globals`
+    mutable_global_indices: HashMap<node_interner::DefinitionId, usize>,
+
+    /// The functions through which trait objects call their methods, keyed by the trait, the
+    /// index of the type of the value the trait object holds and the index of the method
+    trait_object_methods: HashMap<(TraitId, usize, usize), FuncId>,
+
+    /// The first error found while monomorphizing. Monomorphization continues past it
+    /// but its result is discarded.
+    error: Option<MonomorphizationError>,
 }
 
 type HirType = crate::Type;
 
+/// A function's instantiated type along with the types its generics are instantiated with
+type InstantiationKey = (HirType, Vec<HirType>);
+
 /// Starting from the given `main` function, monomorphize the entire program,
 /// replacing all references to type variables and NamedGenerics with concrete
 /// types, duplicating definitions as necessary to do so.
@@ -91,11 +129,16 @@ type HirType = crate::Type;
 /// Note that there is no requirement on the `main` function that can be passed into
 /// this function. Typically, this is the function named "main" in the source project,
 /// but it can also be, for example, an arbitrary test function for running `nargo test`.
-pub fn monomorphize(main: node_interner::FuncId, interner: &NodeInterner) -> Program {
+#[tracing::instrument(skip_all)]
+pub fn monomorphize(
+    main: node_interner::FuncId,
+    interner: &NodeInterner,
+) -> Result<Program, MonomorphizationError> {
     let mut monomorphizer = Monomorphizer::new(interner);
     let function_sig = monomorphizer.compile_main(main);
 
     while !monomorphizer.queue.is_empty() {
+        interner.cancellation_token().check();
         let (next_fn_id, new_id, bindings) = monomorphizer.queue.pop_front().unwrap();
         monomorphizer.locals.clear();
 
@@ -104,9 +147,21 @@ pub fn monomorphize(main: node_interner::FuncId, interner: &NodeInterner) -> Pro
         undo_instantiation_bindings(bindings);
     }
 
+    if let Some(error) = monomorphizer.error {
+        return Err(error);
+    }
+
+    recursion::check_for_recursion(&monomorphizer.finished_functions, Program::main_id())?;
+
     let functions = vecmap(monomorphizer.finished_functions, |(_, f)| f);
     let FuncMeta { return_distinctness, .. } = interner.function_meta(&main);
-    Program::new(functions, function_sig, return_distinctness, monomorphizer.return_location)
+    Ok(Program::new(
+        functions,
+        function_sig,
+        return_distinctness,
+        monomorphizer.return_location,
+        monomorphizer.mutable_globals,
+    ))
 }
 
 impl<'interner> Monomorphizer<'interner> {
@@ -121,7 +176,14 @@ impl<'interner> Monomorphizer<'interner> {
             interner,
             lambda_envs_stack: Vec::new(),
             is_range_loop: false,
+            in_unconstrained_function: false,
+            drop_scopes: Vec::new(),
+            loop_drop_depths: Vec::new(),
             return_location: None,
+            mutable_globals: Vec::new(),
+            mutable_global_indices: HashMap::new(),
+            trait_object_methods: HashMap::new(),
+            error: None,
         }
     }
 
@@ -146,9 +208,26 @@ impl<'interner> Monomorphizer<'interner> {
         id: node_interner::FuncId,
         expr_id: node_interner::ExprId,
         typ: &HirType,
+        impl_bindings: &TypeBindings,
     ) -> Definition {
-        let typ = typ.follow_bindings();
-        match self.globals.get(&id).and_then(|inner_map| inner_map.get(&typ)) {
+        let mut bindings = self.follow_bindings(self.interner.get_instantiation_bindings(expr_id));
+
+        // Methods of generic trait impls also need the impl's generics bound
+        bindings.extend(self.follow_bindings(impl_bindings));
+        self.lookup_function_instance(id, expr_id, typ, bindings)
+    }
+
+    /// Like `lookup_function`, but with the generics of the function already bound by
+    /// `bindings` rather than by those `expr_id` was instantiated with.
+    fn lookup_function_instance(
+        &mut self,
+        id: node_interner::FuncId,
+        expr_id: node_interner::ExprId,
+        typ: &HirType,
+        bindings: TypeBindings,
+    ) -> Definition {
+        let key = (typ.follow_bindings(), instantiated_generics(&bindings));
+        match self.globals.get(&id).and_then(|inner_map| inner_map.get(&key)) {
             Some(id) => Definition::Function(*id),
             None => {
                 // Function has not been monomorphized yet
@@ -169,7 +248,7 @@ impl<'interner> Monomorphizer<'interner> {
                         Definition::Builtin(opcode)
                     }
                     FunctionKind::Normal => {
-                        let id = self.queue_function(id, expr_id, typ);
+                        let id = self.queue_function(id, expr_id, key, bindings);
                         Definition::Function(id)
                     }
                     FunctionKind::Oracle => {
@@ -192,9 +271,9 @@ impl<'interner> Monomorphizer<'interner> {
         self.locals.insert(id, new_id);
     }
 
-    /// Prerequisite: typ = typ.follow_bindings()
-    fn define_global(&mut self, id: node_interner::FuncId, typ: HirType, new_id: FuncId) {
-        self.globals.entry(id).or_default().insert(typ, new_id);
+    /// Prerequisite: the types of `key` have had their bindings followed
+    fn define_global(&mut self, id: node_interner::FuncId, key: InstantiationKey, new_id: FuncId) {
+        self.globals.entry(id).or_default().insert(key, new_id);
     }
 
     fn compile_main(&mut self, main_id: node_interner::FuncId) -> FunctionSignature {
@@ -229,12 +308,16 @@ impl<'interner> Monomorphizer<'interner> {
             _ => meta.return_type(),
         });
 
-        let parameters = self.parameters(meta.parameters);
-        let body = self.expr(body_expr_id);
         let unconstrained = modifiers.is_unconstrained
             || matches!(modifiers.contract_function_type, Some(ContractFunctionType::Open));
+        self.in_unconstrained_function = unconstrained;
+        let inline_type = modifiers.attributes.inline_type();
+
+        let parameters = self.parameters(meta.parameters);
+        let body = self.expr(body_expr_id);
 
-        let function = ast::Function { id, name, parameters, body, return_type, unconstrained };
+        let function =
+            ast::Function { id, name, parameters, body, return_type, unconstrained, inline_type };
         self.push_function(id, function);
     }
 
@@ -282,7 +365,7 @@ impl<'interner> Monomorphizer<'interner> {
                 let mut fields = btree_map(fields, |(name, field)| (name.0.contents, field));
 
                 // Iterate over `struct_field_types` since `unwrap_struct_type` will always
-                // return the fields in the order defined by the struct type.
+                // return the fields in the order given by the layout of the struct type.
                 for (field_name, field_type) in struct_field_types {
                     let field = fields.remove(&field_name).unwrap_or_else(|| {
                         unreachable!("Expected a field named '{field_name}' in the struct pattern")
@@ -295,11 +378,24 @@ impl<'interner> Monomorphizer<'interner> {
     }
 
     fn expr(&mut self, expr: node_interner::ExprId) -> ast::Expression {
+        if let Some(conversion) = self.interner.get_conversion(expr) {
+            return self.conversion(expr, conversion.clone());
+        }
+
+        match self.interner.get_trait_object_coercion(expr) {
+            Some((trait_id, typ)) => self.trait_object(expr, *trait_id, typ),
+            None => self.uncoerced_expr(expr),
+        }
+    }
+
+    /// Monomorphizes the given expression without applying any implicit conversion
+    /// of its value into a trait object.
+    fn uncoerced_expr(&mut self, expr: node_interner::ExprId) -> ast::Expression {
         use ast::Expression::Literal;
         use ast::Literal::*;
 
         match self.interner.expression(&expr) {
-            HirExpression::Ident(ident) => self.ident(ident, expr),
+            HirExpression::Ident(ident, _) => self.ident(ident, expr),
             HirExpression::Literal(HirLiteral::Str(contents)) => Literal(Str(contents)),
             HirExpression::Literal(HirLiteral::FmtStr(contents, idents)) => {
                 let fields = vecmap(idents, |ident| self.expr(ident));
@@ -310,8 +406,9 @@ impl<'interner> Monomorphizer<'interner> {
                 ))
             }
             HirExpression::Literal(HirLiteral::Bool(value)) => Literal(Bool(value)),
-            HirExpression::Literal(HirLiteral::Integer(value)) => {
+            HirExpression::Literal(HirLiteral::Integer(value, negative)) => {
                 let typ = self.convert_type(&self.interner.id_type(expr));
+                let value = if negative { negate_integer_literal(value, &typ) } else { value };
                 Literal(Integer(value, typ))
             }
             HirExpression::Literal(HirLiteral::Array(array)) => match array {
@@ -319,9 +416,12 @@ impl<'interner> Monomorphizer<'interner> {
                 HirArrayLiteral::Repeated { repeated_element, length } => {
                     self.repeated_array(expr, repeated_element, length)
                 }
+                HirArrayLiteral::Comprehension { element, identifier, start, end, length: _ } => {
+                    self.comprehension(expr, element, identifier, start, end)
+                }
             },
             HirExpression::Literal(HirLiteral::Unit) => ast::Expression::Block(vec![]),
-            HirExpression::Block(block) => self.block(block.0),
+            HirExpression::Block(block) => self.block(expr, block.0),
 
             HirExpression::Prefix(prefix) => {
                 let location = self.interner.expr_location(&expr);
@@ -334,17 +434,24 @@ impl<'interner> Monomorphizer<'interner> {
             }
 
             HirExpression::Infix(infix) => {
-                let lhs = Box::new(self.expr(infix.lhs));
-                let rhs = Box::new(self.expr(infix.rhs));
-                let operator = infix.operator.kind;
-                let location = self.interner.expr_location(&expr);
-                ast::Expression::Binary(ast::Binary { lhs, rhs, operator, location })
+                if let Some((method, function_type)) =
+                    self.interner.get_operator_overload(expr).cloned()
+                {
+                    self.operator_impl_call(expr, infix, method, function_type)
+                } else {
+                    let lhs = Box::new(self.expr(infix.lhs));
+                    let rhs = Box::new(self.expr(infix.rhs));
+                    let operator = infix.operator.kind;
+                    let location = self.interner.expr_location(&expr);
+                    ast::Expression::Binary(ast::Binary { lhs, rhs, operator, location })
+                }
             }
 
             HirExpression::Index(index) => self.index(expr, index),
 
             HirExpression::MemberAccess(access) => {
                 let field_index = self.interner.get_field_index(expr);
+                let field_index = layout_position(&self.interner.id_type(access.lhs), field_index);
                 let expr = Box::new(self.expr(access.lhs));
                 ast::Expression::ExtractTupleField(expr, field_index)
             }
@@ -378,8 +485,9 @@ impl<'interner> Monomorphizer<'interner> {
             HirExpression::Lambda(lambda) => self.lambda(lambda, expr),
 
             HirExpression::TraitMethodReference(typ, method) => {
-                if let Type::Function(_, _, _) = self.interner.id_type(expr) {
-                    self.resolve_trait_method_reference(typ, expr, method)
+                let function_type = self.interner.id_type(expr);
+                if let Type::Function(_, _, _) = function_type {
+                    self.resolve_trait_method_reference(typ, expr, function_type, method)
                 } else {
                     unreachable!(
                         "Calling a non-function, this should've been caught in typechecking"
@@ -412,21 +520,120 @@ impl<'interner> Monomorphizer<'interner> {
     ) -> ast::Expression {
         let typ = self.convert_type(&self.interner.id_type(array));
 
-        let length = length
-            .evaluate_to_u64()
-            .expect("Length of array is unknown when evaluating numeric generic");
+        let length = length.evaluate_to_u64().unwrap_or_else(|| {
+            let expression = find_invalid_type_expression(&length)
+                .expect("Length of array is unknown when evaluating numeric generic");
+
+            if self.error.is_none() {
+                let location = self.interner.expr_location(&array);
+                self.error = Some(invalid_type_expression_error(expression, None, location));
+            }
+            0
+        });
 
         let contents = vecmap(0..length, |_| self.expr(repeated_element));
         ast::Expression::Literal(ast::Literal::Array(ast::ArrayLiteral { contents, typ }))
     }
 
+    /// Lowers `[element for i in start..end]` into a zeroed array which is filled in by a loop:
+    ///
+    /// ```text
+    /// {
+    ///     let mut array = [zeroed; end - start];
+    ///     for i in start..end {
+    ///         array[i - start] = element;
+    ///     }
+    ///     array
+    /// }
+    /// ```
+    fn comprehension(
+        &mut self,
+        array: node_interner::ExprId,
+        element: node_interner::ExprId,
+        identifier: HirIdent,
+        start_range: node_interner::ExprId,
+        end_range: node_interner::ExprId,
+    ) -> ast::Expression {
+        let location = self.interner.expr_location(&array);
+        let typ = self.convert_type(&self.interner.id_type(array));
+        let element_type = match &typ {
+            ast::Type::Array(_, element_type) => element_type.as_ref().clone(),
+            other => unreachable!("Expected an array comprehension to be an array, found {other}"),
+        };
+
+        let zeroed = self.zeroed_value_of_type(&typ, location);
+        let array_id = self.next_local_id();
+        let name = "comprehension".to_string();
+        let definition = Definition::Local(array_id);
+        let array_ident =
+            ast::Ident { location: Some(location), definition, mutable: true, name, typ };
+
+        self.is_range_loop = true;
+        let start = self.expr(start_range);
+        let end = self.expr(end_range);
+        self.is_range_loop = false;
+        let index_variable = self.next_local_id();
+        self.define_local(identifier.id, index_variable);
+
+        let index_name = self.interner.definition_name(identifier.id).to_owned();
+        let index_type = self.convert_type(&self.interner.id_type(start_range));
+        let index_ident = ast::Ident {
+            location: Some(location),
+            definition: Definition::Local(index_variable),
+            mutable: false,
+            name: index_name.clone(),
+            typ: index_type.clone(),
+        };
+        let index = ast::Expression::Binary(ast::Binary {
+            lhs: Box::new(ast::Expression::Ident(index_ident)),
+            operator: BinaryOpKind::Subtract,
+            rhs: Box::new(start.clone()),
+            location,
+        });
+
+        let element = self.expr(element);
+        let lvalue = ast::LValue::Index {
+            array: Box::new(ast::LValue::Ident(array_ident.clone())),
+            index: Box::new(index),
+            element_type,
+            location,
+        };
+        let block = ast::Expression::Assign(ast::Assign { lvalue, expression: Box::new(element) });
+
+        ast::Expression::Block(vec![
+            ast::Expression::Let(ast::Let {
+                id: array_id,
+                mutable: true,
+                name: array_ident.name.clone(),
+                expression: Box::new(zeroed),
+            }),
+            ast::Expression::For(ast::For {
+                index_variable,
+                index_name,
+                index_type,
+                start_range: Box::new(start),
+                end_range: Box::new(end),
+                start_range_location: self.interner.expr_location(&start_range),
+                end_range_location: self.interner.expr_location(&end_range),
+                block: Box::new(block),
+            }),
+            ast::Expression::Ident(array_ident),
+        ])
+    }
+
     fn index(&mut self, id: node_interner::ExprId, index: HirIndexExpression) -> ast::Expression {
         let element_type = self.convert_type(&self.interner.id_type(id));
 
         let collection = Box::new(self.expr(index.collection));
         let index = Box::new(self.expr(index.index));
         let location = self.interner.expr_location(&id);
-        ast::Expression::Index(ast::Index { collection, index, element_type, location })
+        ast::Expression::Index(ast::Index {
+            collection,
+            index,
+            element_type,
+            location,
+            checked: true,
+        })
     }
 
     fn statement(&mut self, id: StmtId) -> ast::Expression {
@@ -435,7 +642,20 @@ impl<'interner> Monomorphizer<'interner> {
             HirStatement::Constrain(constrain) => {
                 let expr = self.expr(constrain.0);
                 let location = self.interner.expr_location(&constrain.0);
-                ast::Expression::Constrain(Box::new(expr), location, constrain.2)
+                match constrain.2 {
+                    Some(HirAssertMessage::Dynamic(message)) if self.is_printable(message) => {
+                        // The message is resolved before the assertion which may fail is checked
+                        let message = ast::Expression::Semi(Box::new(self.expr(message)));
+                        let constrain = ast::Expression::Constrain(Box::new(expr), location, None);
+                        ast::Expression::Block(vec![message, constrain])
+                    }
+                    Some(HirAssertMessage::Static(message)) => {
+                        ast::Expression::Constrain(Box::new(expr), location, Some(message))
+                    }
+                    Some(HirAssertMessage::Dynamic(_)) | None => {
+                        ast::Expression::Constrain(Box::new(expr), location, None)
+                    }
+                }
             }
             HirStatement::Assign(assign) => self.assign(assign),
             HirStatement::For(for_loop) => {
@@ -459,6 +679,20 @@ impl<'interner> Monomorphizer<'interner> {
                     block,
                 })
             }
+            HirStatement::Loop(block) => {
+                self.loop_drop_depths.push(self.drop_scopes.len());
+                let block = self.expr(block);
+                self.loop_drop_depths.pop();
+                ast::Expression::Loop(Box::new(block))
+            }
+            HirStatement::Break => {
+                // The variables of every block the `break` exits are dropped first
+                let depth = self.loop_drop_depths.last().copied().unwrap_or_default();
+                let variables: Vec<_> = self.drop_scopes[depth..].concat();
+                let mut statements = self.drop_calls(&variables);
+                statements.push(ast::Expression::Break);
+                ast::Expression::Block(statements)
+            }
             HirStatement::Expression(expr) => self.expr(expr),
             HirStatement::Semi(expr) => ast::Expression::Semi(Box::new(self.expr(expr))),
             HirStatement::Error => unreachable!(),
@@ -503,7 +737,7 @@ impl<'interner> Monomorphizer<'interner> {
         }
 
         // We must ensure the tuple created from the variables here matches the order
-        // of the fields as defined in the type. To do this, we iterate over field_types,
+        // of the fields in the layout of the type. To do this, we iterate over field_types,
         // rather than field_type_map which is a sorted BTreeMap.
         let field_idents = vecmap(field_types, |(name, _)| {
             let (id, typ) = field_vars.remove(&name).unwrap_or_else(|| {
@@ -520,8 +754,125 @@ impl<'interner> Monomorphizer<'interner> {
         ast::Expression::Block(new_exprs)
     }
 
-    fn block(&mut self, statement_ids: Vec<StmtId>) -> ast::Expression {
-        ast::Expression::Block(vecmap(statement_ids, |id| self.statement(id)))
+    fn block(&mut self, id: node_interner::ExprId, statement_ids: Vec<StmtId>) -> ast::Expression {
+        let diverges = statement_ids.last().map_or(false, |statement| {
+            matches!(
+                self.interner.statement(statement),
+                HirStatement::Constrain(constrain) if constrain.always_fails(self.interner)
+            )
+        });
+        let drops_variables = self.in_unconstrained_function && !diverges;
+        if drops_variables {
+            self.drop_scopes.push(Vec::new());
+        }
+        let mut statements = vecmap(statement_ids, |id| {
+            let statement = self.statement(id);
+            if drops_variables {
+                let variables = self.let_bound_variables(id);
+                self.drop_scopes.last_mut().expect("Pushed above").extend(variables);
+            }
+            statement
+        });
+        let variables = if drops_variables {
+            self.drop_scopes.pop().expect("Pushed above")
+        } else {
+            Vec::new()
+        };
+
+        // Variables are dropped once the block's value has been evaluated
+        let drops = self.drop_calls(&variables);
+        if !drops.is_empty() {
+            let typ = self.convert_type(&self.interner.id_type(id));
+            let value = (typ != ast::Type::Unit).then(|| {
+                let value = statements.pop().expect("a block with a value has a statement");
+                let local_id = self.next_local_id();
+                statements.push(ast::Expression::Let(ast::Let {
+                    id: local_id,
+                    mutable: false,
+                    name: "tmp".to_string(),
+                    expression: Box::new(value),
+                }));
+                ast::Expression::Ident(ast::Ident {
+                    location: None,
+                    definition: Definition::Local(local_id),
+                    mutable: false,
+                    name: "tmp".to_string(),
+                    typ,
+                })
+            });
+            statements.extend(drops);
+            statements.extend(value);
+        }
+
+        // A block ending with `assert(false)` may be used as a value of any type. Since both
+        // branches of an `if` are evaluated once control flow is flattened, it still needs a
+        // value of that type, which is never used as the assertion fails whenever it's reached.
+        if diverges {
+            let typ = self.convert_type(&self.interner.id_type(id));
+            if typ != ast::Type::Unit {
+                let location = self.interner.expr_location(&id);
+                statements.push(self.zeroed_value_of_type(&typ, location));
+            }
+        }
+        ast::Expression::Block(statements)
+    }
+
+    /// The variables bound by a statement if it is a `let` statement, along with the expression
+    /// each is bound to
+    fn let_bound_variables(&self, statement: StmtId) -> Vec<(HirIdent, ExprId)> {
+        fn collect(pattern: &HirPattern, value: ExprId, variables: &mut Vec<(HirIdent, ExprId)>) {
+            match pattern {
+                HirPattern::Identifier(ident) => variables.push((ident.clone(), value)),
+                HirPattern::Mutable(pattern, _) => collect(pattern, value, variables),
+                HirPattern::Tuple(patterns, _) => {
+                    patterns.iter().for_each(|pattern| collect(pattern, value, variables));
+                }
+                HirPattern::Struct(_, fields, _) => {
+                    fields.iter().for_each(|(_, pattern)| collect(pattern, value, variables));
+                }
+            }
+        }
+
+        let mut variables = Vec::new();
+        if let HirStatement::Let(let_statement) = self.interner.statement(&statement) {
+            collect(&let_statement.pattern, let_statement.expression, &mut variables);
+        }
+        variables
+    }
+
+    /// Drops each variable in the reverse order they're declared in
+    fn drop_calls(&mut self, variables: &[(HirIdent, ExprId)]) -> Vec<ast::Expression> {
+        variables.iter().rev().filter_map(|variable| self.drop_call(variable)).collect()
+    }
+
+    /// Calls the `Drop` impl of the variable's type on it, if its type has one
+    fn drop_call(&mut self, (variable, value): &(HirIdent, ExprId)) -> Option<ast::Expression> {
+        let method = self.interner.get_drop_trait_method()?;
+        let typ = self.interner.id_type(variable.id).follow_bindings();
+        if !matches!(typ, HirType::Struct(..)) {
+            return None;
+        }
+
+        let (trait_impl, impl_bindings) =
+            self.interner.lookup_trait_implementation(&typ, method.trait_id, &[])?;
+        let func_id = trait_impl.borrow().methods[method.method_index];
+        let bindings = self.follow_bindings(&impl_bindings);
+
+        let unit = Box::new(HirType::Unit);
+        let function_type = HirType::Function(vec![typ], unit.clone(), unit);
+        let definition = self.lookup_function_instance(func_id, *value, &function_type, bindings);
+
+        let func = Box::new(ast::Expression::Ident(ast::Ident {
+            location: None,
+            definition,
+            mutable: false,
+            name: "drop".to_string(),
+            typ: self.convert_type(&function_type),
+        }));
+        let arguments = vec![ast::Expression::Ident(self.local_ident(variable)?)];
+        let location = variable.location;
+        let call = ast::Call { func, arguments, return_type: ast::Type::Unit, location };
+        Some(ast::Expression::Semi(Box::new(ast::Expression::Call(call))))
     }
 
     fn unpack_pattern(
@@ -555,7 +906,7 @@ impl<'interner> Monomorphizer<'interner> {
                 let mut patterns =
                     btree_map(patterns, |(name, pattern)| (name.0.contents, pattern));
 
-                // We iterate through the type's fields to match the order defined in the struct type
+                // We iterate through the type's fields to match the layout of the struct type
                 let patterns_iter = fields.into_iter().map(|(field_name, field_type)| {
                     let pattern = patterns.remove(&field_name).unwrap();
                     (pattern, field_type)
@@ -632,6 +983,34 @@ impl<'interner> Monomorphizer<'interner> {
         Some(ast::Ident { location: Some(ident.location), mutable, definition, name, typ })
     }
 
+    /// A `global mut` only, which is added to the program's mutable globals when first used
+    fn mutable_global(&mut self, ident: &HirIdent) -> Option<ast::Ident> {
+        let definition = self.interner.definition(ident.id);
+        let DefinitionKind::Global(initial_value) = &definition.kind else {
+            return None;
+        };
+        if !definition.mutable {
+            return None;
+        }
+
+        let name = definition.name.clone();
+        let typ = self.convert_type(&self.interner.id_type(ident.id));
+        let index = match self.mutable_global_indices.get(&ident.id) {
+            Some(index) => *index,
+            None => {
+                let initial_value = self.expr(*initial_value);
+                let global =
+                    ast::MutableGlobal { name: name.clone(), typ: typ.clone(), initial_value };
+                self.mutable_globals.push(global);
+                self.mutable_global_indices.insert(ident.id, self.mutable_globals.len() - 1);
+                self.mutable_globals.len() - 1
+            }
+        };
+
+        let definition = Definition::MutableGlobal(index);
+        Some(ast::Ident { location: Some(ident.location), mutable: true, definition, name, typ })
+    }
+
     fn ident(&mut self, ident: HirIdent, expr_id: node_interner::ExprId) -> ast::Expression {
         let definition = self.interner.definition(ident.id);
         match &definition.kind {
@@ -640,7 +1019,8 @@ impl<'interner> Monomorphizer<'interner> {
                 let location = Some(ident.location);
                 let name = definition.name.clone();
                 let typ = self.interner.id_type(expr_id);
-                let definition = self.lookup_function(*func_id, expr_id, &typ);
+                let definition =
+                    self.lookup_function(*func_id, expr_id, &typ, &TypeBindings::new());
                 let typ = self.convert_type(&typ);
                 let ident = ast::Ident { location, mutable, definition, name, typ: typ.clone() };
                 let ident_expression = ast::Expression::Ident(ident);
@@ -656,6 +1036,9 @@ impl<'interner> Monomorphizer<'interner> {
                     ident_expression
                 }
             }
+            DefinitionKind::Global(_) if definition.mutable => {
+                ast::Expression::Ident(self.mutable_global(&ident).unwrap())
+            }
             DefinitionKind::Global(expr_id) => self.expr(*expr_id),
             DefinitionKind::Local(_) => self.lookup_captured_expr(ident.id).unwrap_or_else(|| {
                 let ident = self.local_ident(&ident).unwrap();
@@ -702,6 +1085,7 @@ impl<'interner> Monomorphizer<'interner> {
             HirType::TraitAsType(_) => {
                 unreachable!("All TraitAsType should be replaced before calling convert_type");
             }
+            HirType::TraitObject(the_trait) => self.convert_trait_object_type(the_trait.id),
             HirType::NamedGeneric(binding, _) => {
                 if let TypeBinding::Bound(binding) = &*binding.borrow() {
                     return self.convert_type(binding);
@@ -743,7 +1127,7 @@ impl<'interner> Monomorphizer<'interner> {
             }
 
             HirType::Struct(def, args) => {
-                let fields = def.borrow().get_fields(args);
+                let fields = def.borrow().get_fields_in_layout_order(args);
                 let fields = vecmap(fields, |(_, field)| self.convert_type(&field));
                 ast::Type::Tuple(fields)
             }
@@ -778,6 +1162,7 @@ impl<'interner> Monomorphizer<'interner> {
 
             HirType::Forall(_, _)
             | HirType::Constant(_)
+            | HirType::InfixExpr(..)
             | HirType::NotConstant
             | HirType::Error => {
                 unreachable!("Unexpected type {} found", typ)
@@ -785,6 +1170,28 @@ impl<'interner> Monomorphizer<'interner> {
         }
     }
 
+    /// A trait object is represented as a tuple of its data followed by each method of its
+    /// trait. The data is a tuple with a field for each type converted into a trait object of
+    /// the trait, only one of which holds a value, and replaces the `self` parameter of each
+    /// method. The methods are function values, so calls to them are dispatched to the impl of
+    /// the type the trait object was created from by defunctionalization.
+    fn convert_trait_object_type(&self, trait_id: TraitId) -> ast::Type {
+        let types = self.interner.get_trait_object_types(trait_id);
+        let data = ast::Type::Tuple(vecmap(types, |typ| self.convert_type(typ)));
+
+        let the_trait = self.interner.get_trait(trait_id);
+        let methods = the_trait.methods.iter().map(|method| {
+            let mut parameters = vec![data.clone()];
+            parameters.extend(method.arguments.iter().skip(1).map(|arg| self.convert_type(arg)));
+            let return_type = Box::new(self.convert_type(&method.return_type));
+            ast::Type::Function(parameters, return_type, Box::new(ast::Type::Unit))
+        });
+
+        let mut fields = vec![data.clone()];
+        fields.extend(methods);
+        ast::Type::Tuple(fields)
+    }
+
     fn is_function_closure(&self, t: ast::Type) -> bool {
         if self.is_function_closure_type(&t) {
             true
@@ -812,21 +1219,24 @@ impl<'interner> Monomorphizer<'interner> {
         &mut self,
         self_type: HirType,
         expr_id: node_interner::ExprId,
+        function_type: HirType,
         method: TraitMethodId,
     ) -> ast::Expression {
-        let function_type = self.interner.id_type(expr_id);
-
-        let trait_impl = self
-            .interner
-            .get_trait_implementation(&TraitImplKey {
-                typ: self_type.follow_bindings(),
-                trait_id: method.trait_id,
-            })
-            .expect("ICE: missing trait impl - should be caught during type checking");
-
-        let hir_func_id = trait_impl.borrow().methods[method.method_index];
+        let (hir_func_id, impl_bindings) =
+            match self.interner.lookup_from_method(method, &function_type) {
+                Some(conversion) => (conversion.function, conversion.bindings),
+                None => {
+                    let self_type = self_type.follow_bindings();
+                    let (trait_impl, impl_bindings) = self
+                        .interner
+                        .lookup_trait_implementation(&self_type, method.trait_id, &[])
+                        .expect("ICE: missing trait impl - should be caught during type checking");
+                    let hir_func_id = trait_impl.borrow().methods[method.method_index];
+                    (hir_func_id, impl_bindings)
+                }
+            };
 
-        let func_def = self.lookup_function(hir_func_id, expr_id, &function_type);
+        let func_def = self.lookup_function(hir_func_id, expr_id, &function_type, &impl_bindings);
         let func_id = match func_def {
             Definition::Function(func_id) => func_id,
             _ => unreachable!(),
@@ -843,26 +1253,281 @@ impl<'interner> Monomorphizer<'interner> {
         })
     }
 
+    /// Replaces an overloaded operator with a call to the trait impl method implementing it.
+    /// `!=` negates the result of `Eq::eq`, while `<`, `<=`, `>` and `>=` check the
+    /// `Ordering` returned by `Ord::cmp`.
+    fn operator_impl_call(
+        &mut self,
+        expr_id: node_interner::ExprId,
+        infix: HirInfixExpression,
+        method: TraitMethodId,
+        function_type: HirType,
+    ) -> ast::Expression {
+        let return_type = match &function_type {
+            HirType::Function(_, return_type, _) => self.convert_type(return_type),
+            _ => unreachable!("Operator trait methods should always have a function type"),
+        };
+
+        let self_type = self.interner.id_type(infix.lhs);
+        let func = self.resolve_trait_method_reference(self_type, expr_id, function_type, method);
+        let func = Box::new(func);
+
+        let arguments = vec![self.expr(infix.lhs), self.expr(infix.rhs)];
+        let location = self.interner.expr_location(&expr_id);
+        let call = ast::Expression::Call(ast::Call { func, arguments, return_type, location });
+
+        use BinaryOpKind::*;
+        let operator = infix.operator.kind;
+        match operator {
+            NotEqual => ast::Expression::Unary(ast::Unary {
+                operator: UnaryOp::Not,
+                rhs: Box::new(call),
+                result_type: ast::Type::Bool,
+                location,
+            }),
+            Less | LessEqual | Greater | GreaterEqual => {
+                // `Ordering::less()`, `Ordering::equal()` and `Ordering::greater()`
+                // hold 0, 1 and 2 respectively in their only field.
+                let (operator, ordering) = match operator {
+                    Less => (Equal, 0u128),
+                    LessEqual => (NotEqual, 2),
+                    Greater => (Equal, 2),
+                    _ => (NotEqual, 0),
+                };
+                let lhs = Box::new(ast::Expression::ExtractTupleField(Box::new(call), 0));
+                let ordering = ast::Literal::Integer(ordering.into(), ast::Type::Field);
+                let rhs = Box::new(ast::Expression::Literal(ordering));
+                ast::Expression::Binary(ast::Binary { lhs, rhs, operator, location })
+            }
+            _ => call,
+        }
+    }
+
+    /// Converts the value of `expr` into another type by calling the `from` method of a
+    /// `From` impl on it.
+    fn conversion(
+        &mut self,
+        expr: node_interner::ExprId,
+        conversion: Conversion,
+    ) -> ast::Expression {
+        let value = self.uncoerced_expr(expr);
+        let Conversion { function, function_type, bindings, target } = conversion;
+
+        let definition = self.lookup_function(function, expr, &function_type, &bindings);
+        let func = Box::new(ast::Expression::Ident(ast::Ident {
+            location: None,
+            definition,
+            mutable: false,
+            name: "from".to_string(),
+            typ: self.convert_type(&function_type),
+        }));
+
+        let return_type = self.convert_type(&target);
+        let location = self.interner.expr_location(&expr);
+        ast::Expression::Call(ast::Call { func, arguments: vec![value], return_type, location })
+    }
+
+    /// Converts the value of `expr`, of type `typ`, into a trait object of the given trait
+    /// by placing it in its field of the trait object's data, along with the methods of the
+    /// trait implemented for `typ`.
+    fn trait_object(
+        &mut self,
+        expr: node_interner::ExprId,
+        trait_id: TraitId,
+        typ: &HirType,
+    ) -> ast::Expression {
+        let value = self.uncoerced_expr(expr);
+        let location = self.interner.expr_location(&expr);
+
+        let typ = typ.concrete().expect("ICE: trait object types should be concrete");
+        let types = self.interner.get_trait_object_types(trait_id);
+        let variant = types
+            .iter()
+            .position(|other| *other == typ)
+            .expect("ICE: types converted into trait objects should be recorded");
+
+        let (data_type, method_types) = match self.convert_trait_object_type(trait_id) {
+            ast::Type::Tuple(mut fields) => (fields.remove(0), fields),
+            _ => unreachable!("Trait objects are represented as tuples"),
+        };
+        let ast::Type::Tuple(data_types) = data_type else {
+            unreachable!("The data of trait objects is represented as a tuple")
+        };
+        let mut data = vecmap(&data_types, |typ| self.zeroed_value_of_type(typ, location));
+        data[variant] = value;
+        let mut fields = vec![ast::Expression::Tuple(data)];
+
+        let the_trait = self.interner.get_trait(trait_id);
+        for (method_index, method_type) in method_types.into_iter().enumerate() {
+            let definition = self.trait_object_method(expr, trait_id, variant, method_index);
+            fields.push(ast::Expression::Ident(ast::Ident {
+                definition,
+                mutable: false,
+                location: None,
+                name: the_trait.methods[method_index].name.0.contents.clone(),
+                typ: method_type,
+            }));
+        }
+
+        ast::Expression::Tuple(fields)
+    }
+
+    /// Returns the function through which trait objects holding a value of the `variant`-th
+    /// type converted into trait objects of this trait call the given method. It takes the
+    /// data of the trait object in place of `self` and calls the method of that type's impl
+    /// with the value in the data, creating the function if it doesn't exist yet.
+    fn trait_object_method(
+        &mut self,
+        expr: node_interner::ExprId,
+        trait_id: TraitId,
+        variant: usize,
+        method_index: usize,
+    ) -> Definition {
+        let key = (trait_id, variant, method_index);
+        if let Some(id) = self.trait_object_methods.get(&key) {
+            return Definition::Function(*id);
+        }
+
+        let typ = self.interner.get_trait_object_types(trait_id)[variant].clone();
+        let (trait_impl, impl_bindings) = self
+            .interner
+            .lookup_trait_implementation(&typ, trait_id, &[])
+            .expect("ICE: missing trait impl - should be caught during type checking");
+
+        let the_trait = self.interner.get_trait(trait_id);
+        let method = &the_trait.methods[method_index];
+        let function_type = the_trait.method_type(method_index, &typ);
+        let hir_func_id = trait_impl.borrow().methods[method_index];
+        let impl_method = self.lookup_function(hir_func_id, expr, &function_type, &impl_bindings);
+
+        let id = self.next_function_id();
+        self.trait_object_methods.insert(key, id);
+
+        let data_type = match self.convert_trait_object_type(trait_id) {
+            ast::Type::Tuple(mut fields) => fields.swap_remove(0),
+            _ => unreachable!("Trait objects are represented as tuples"),
+        };
+        let mut parameters = vec![(self.next_local_id(), false, "data".to_string(), data_type)];
+        for argument in method.arguments.iter().skip(1) {
+            let argument_type = self.convert_type(argument);
+            parameters.push((self.next_local_id(), false, "arg".to_string(), argument_type));
+        }
+
+        let mut arguments = vecmap(&parameters, |(id, _, name, typ)| {
+            ast::Expression::Ident(ast::Ident {
+                location: None,
+                definition: Definition::Local(*id),
+                mutable: false,
+                name: name.clone(),
+                typ: typ.clone(),
+            })
+        });
+        arguments[0] = ast::Expression::ExtractTupleField(Box::new(arguments[0].clone()), variant);
+
+        let func = Box::new(ast::Expression::Ident(ast::Ident {
+            definition: impl_method,
+            mutable: false,
+            location: None,
+            name: method.name.0.contents.clone(),
+            typ: self.convert_type(&function_type),
+        }));
+        let return_type = self.convert_type(&method.return_type);
+        let location = self.interner.expr_location(&expr);
+        let body = ast::Expression::Call(ast::Call {
+            func,
+            arguments,
+            return_type: return_type.clone(),
+            location,
+        });
+
+        let name = format!("{}_dyn", method.name.0.contents);
+        let unconstrained = false;
+        let inline_type = InlineType::Default;
+        let function =
+            ast::Function { id, name, parameters, body, return_type, unconstrained, inline_type };
+        self.push_function(id, function);
+        Definition::Function(id)
+    }
+
+    /// Calls a method of a trait object: `object.method(args)` becomes `object.i(object.0, args)`
+    /// where `i` is the position of the method within the trait object.
+    fn trait_object_method_call(
+        &mut self,
+        call: HirCallExpression,
+        id: node_interner::ExprId,
+        method: TraitMethodId,
+    ) -> ast::Expression {
+        let object_type = self.convert_type(&self.interner.id_type(call.arguments[0]));
+        let mut arguments = vecmap(&call.arguments, |id| self.expr(*id));
+        let object = arguments.remove(0);
+
+        let local_id = self.next_local_id();
+        let let_stmt = ast::Expression::Let(ast::Let {
+            id: local_id,
+            mutable: false,
+            name: "tmp".to_string(),
+            expression: Box::new(object),
+        });
+
+        let object = Box::new(ast::Expression::Ident(ast::Ident {
+            location: None,
+            definition: Definition::Local(local_id),
+            mutable: false,
+            name: "tmp".to_string(),
+            typ: object_type,
+        }));
+
+        let func =
+            Box::new(ast::Expression::ExtractTupleField(object.clone(), method.method_index + 1));
+        arguments.insert(0, ast::Expression::ExtractTupleField(object, 0));
+
+        let return_type = self.convert_type(&self.interner.id_type(id));
+        let location = call.location;
+        let call = ast::Expression::Call(ast::Call { func, arguments, return_type, location });
+        ast::Expression::Block(vec![let_stmt, call])
+    }
+
     fn function_call(
         &mut self,
         call: HirCallExpression,
         id: node_interner::ExprId,
     ) -> ast::Expression {
+        if let HirExpression::TraitMethodReference(self_type, method) =
+            self.interner.expression(&call.func)
+        {
+            if let HirType::TraitObject(_) = self_type.follow_bindings() {
+                return self.trait_object_method_call(call, id, method);
+            }
+        }
+
         let original_func = Box::new(self.expr(call.func));
         let mut arguments = vecmap(&call.arguments, |id| self.expr(*id));
         let hir_arguments = vecmap(&call.arguments, |id| self.interner.expression(id));
         let func: Box<ast::Expression>;
-        let return_type = self.interner.id_type(id);
-        let return_type = self.convert_type(&return_type);
+        let hir_return_type = self.interner.id_type(id);
+        let mut return_type = self.convert_type(&hir_return_type);
+        let mut packed_return = None;
 
         let location = call.location;
 
         if let ast::Expression::Ident(ident) = original_func.as_ref() {
             if let Definition::Oracle(name) = &ident.definition {
-                if name.as_str() == "println" {
+                if name.as_str() == "println" || name.as_str() == "assert_message" {
                     // Oracle calls are required to be wrapped in an unconstrained function
-                    // Thus, the only argument to the `println` oracle is expected to always be an ident
+                    // Thus, the only argument to these oracles is expected to always be an ident
                     self.append_printable_type_info(&hir_arguments[0], &mut arguments);
+                } else {
+                    for (argument, id) in arguments.iter_mut().zip(&call.arguments) {
+                        let typ = self.interner.id_type(*id);
+                        if let Some(length) = typ.packed_length() {
+                            let value = std::mem::replace(argument, ast::Expression::Block(vec![]));
+                            *argument = self.pack_struct(value, &typ, length, location);
+                        }
+                    }
+                    if let Some(length) = hir_return_type.packed_length() {
+                        return_type = packed_array_type(length);
+                        packed_return = Some(length);
+                    }
                 }
             }
         }
@@ -904,8 +1569,12 @@ impl<'interner> Monomorphizer<'interner> {
         };
 
         let call = self
-            .try_evaluate_call(&func, &id, &return_type)
+            .try_evaluate_call(&func, &id, &return_type, &mut arguments)
             .unwrap_or(ast::Expression::Call(ast::Call { func, arguments, return_type, location }));
+        let call = match packed_return {
+            Some(length) => self.unpack_struct(call, &hir_return_type, length, location),
+            None => call,
+        };
 
         if !block_expressions.is_empty() {
             block_expressions.push(call);
@@ -915,6 +1584,123 @@ impl<'interner> Monomorphizer<'interner> {
         }
     }
 
+    /// Packs a value of a struct with a packed layout, passed to a foreign call, into a single
+    /// array of its flattened field elements.
+    fn pack_struct(
+        &mut self,
+        value: ast::Expression,
+        typ: &HirType,
+        length: u64,
+        location: Location,
+    ) -> ast::Expression {
+        let (definition, variable) = self.packing_variable(value, self.convert_type(typ));
+        let mut contents = Vec::new();
+        self.flatten_elements(variable, typ, location, &mut contents);
+        let typ = packed_array_type(length);
+        let array =
+            ast::Expression::Literal(ast::Literal::Array(ast::ArrayLiteral { contents, typ }));
+        ast::Expression::Block(vec![definition, array])
+    }
+
+    /// Appends each field element of `value` to `elements`, cast to a `Field`
+    fn flatten_elements(
+        &self,
+        value: ast::Expression,
+        typ: &HirType,
+        location: Location,
+        elements: &mut Vec<ast::Expression>,
+    ) {
+        match typ.follow_bindings() {
+            HirType::Array(length, element) => {
+                let length = length.evaluate_to_u64().expect("Packed arrays have a known length");
+                let element_type = self.convert_type(&element);
+                for index in 0..length {
+                    let value =
+                        index_constant(value.clone(), index, element_type.clone(), location);
+                    self.flatten_elements(value, &element, location, elements);
+                }
+            }
+            HirType::Struct(..) | HirType::Tuple(_) => {
+                let fields = unwrap_fields(typ);
+                for (position, field) in fields.iter().enumerate() {
+                    let value =
+                        ast::Expression::ExtractTupleField(Box::new(value.clone()), position);
+                    self.flatten_elements(value, field, location, elements);
+                }
+            }
+            _ => {
+                let lhs = Box::new(value);
+                let r#type = ast::Type::Field;
+                elements.push(ast::Expression::Cast(ast::Cast { lhs, r#type, location }));
+            }
+        }
+    }
+
+    /// Rebuilds a value of a struct with a packed layout from the array of its flattened field
+    /// elements returned by a foreign call.
+    fn unpack_struct(
+        &mut self,
+        call: ast::Expression,
+        typ: &HirType,
+        length: u64,
+        location: Location,
+    ) -> ast::Expression {
+        let (definition, array) = self.packing_variable(call, packed_array_type(length));
+        let mut elements = (0..length)
+            .map(|index| index_constant(array.clone(), index, ast::Type::Field, location));
+        let value = self.unflatten_elements(&mut elements, typ, location);
+        ast::Expression::Block(vec![definition, value])
+    }
+
+    /// Builds a value of type `typ` from the next field elements of `elements`
+    fn unflatten_elements(
+        &self,
+        elements: &mut impl Iterator<Item = ast::Expression>,
+        typ: &HirType,
+        location: Location,
+    ) -> ast::Expression {
+        match typ.follow_bindings() {
+            HirType::Array(length, element) => {
+                let length = length.evaluate_to_u64().expect("Packed arrays have a known length");
+                let contents =
+                    vecmap(0..length, |_| self.unflatten_elements(elements, &element, location));
+                let typ = self.convert_type(typ);
+                ast::Expression::Literal(ast::Literal::Array(ast::ArrayLiteral { contents, typ }))
+            }
+            HirType::Struct(..) | HirType::Tuple(_) => {
+                let fields = unwrap_fields(typ);
+                ast::Expression::Tuple(vecmap(fields, |field| {
+                    self.unflatten_elements(elements, &field, location)
+                }))
+            }
+            other => {
+                let lhs = Box::new(elements.next().expect("Packed structs have a known length"));
+                let r#type = self.convert_type(&other);
+                ast::Expression::Cast(ast::Cast { lhs, r#type, location })
+            }
+        }
+    }
+
+    /// Binds `value` to a new variable named `packed`, returning the definition and the variable
+    fn packing_variable(
+        &mut self,
+        value: ast::Expression,
+        typ: ast::Type,
+    ) -> (ast::Expression, ast::Expression) {
+        let id = self.next_local_id();
+        let name = "packed".to_string();
+        let definition = ast::Expression::Let(ast::Let {
+            id,
+            mutable: false,
+            name: name.clone(),
+            expression: Box::new(value),
+        });
+        let definition_id = Definition::Local(id);
+        let variable =
+            ast::Ident { location: None, definition: definition_id, mutable: false, name, typ };
+        (definition, ast::Expression::Ident(variable))
+    }
+
     /// Adds a function argument that contains type metadata that is required to tell
     /// `println` how to convert values passed to an foreign call  back to a human-readable string.
     /// The values passed to an foreign call will be a simple list of field elements,
@@ -931,7 +1717,7 @@ impl<'interner> Monomorphizer<'interner> {
         arguments: &mut Vec<ast::Expression>,
     ) {
         match hir_argument {
-            HirExpression::Ident(ident) => {
+            HirExpression::Ident(ident, _) => {
                 let typ = self.interner.id_type(ident.id);
                 let typ: Type = typ.follow_bindings();
                 let is_fmt_str = match typ {
@@ -962,6 +1748,22 @@ impl<'interner> Monomorphizer<'interner> {
         }
     }
 
+    /// Whether every value interpolated into the format string of a dynamic assert message can
+    /// be printed. The messages reporting the values compared by `assert_eq` and `assert_ne` are
+    /// generated for any values, so are dropped if they can't be reported.
+    fn is_printable(&self, message: node_interner::ExprId) -> bool {
+        let HirExpression::Call(call) = self.interner.expression(&message) else {
+            unreachable!("Dynamic assert messages are calls to `resolve_assert_message`")
+        };
+        match self.interner.id_type(call.arguments[0]).follow_bindings() {
+            Type::FmtString(_, elements) => match *elements {
+                Type::Tuple(element_types) => element_types.iter().all(Type::is_printable),
+                _ => false,
+            },
+            _ => false,
+        }
+    }
+
     fn append_printable_type_info_inner(typ: &Type, arguments: &mut Vec<ast::Expression>) {
         if let HirType::Array(size, _) = typ {
             if let HirType::NotConstant = **size {
@@ -975,8 +1777,9 @@ impl<'interner> Monomorphizer<'interner> {
         arguments.push(ast::Expression::Literal(ast::Literal::Str(abi_as_string)));
     }
 
-    /// Try to evaluate certain builtin functions (currently only 'array_len' and field modulus methods)
-    /// at their call site.
+    /// Try to evaluate certain builtin functions (such as 'zeroed', 'array_get_unchecked' and the
+    /// field modulus methods) at their call site. Builtins which consume their arguments take
+    /// them from `arguments`.
     /// NOTE: Evaluating at the call site means we cannot track aliased functions.
     ///       E.g. `let f = std::array::len; f(arr)` will fail to evaluate.
     ///       To fix this we need to evaluate on the identifier instead, which
@@ -986,6 +1789,7 @@ impl<'interner> Monomorphizer<'interner> {
         func: &ast::Expression,
         expr_id: &node_interner::ExprId,
         result_type: &ast::Type,
+        arguments: &mut Vec<ast::Expression>,
     ) -> Option<ast::Expression> {
         if let ast::Expression::Ident(ident) = func {
             if let Definition::Builtin(opcode) = &ident.definition {
@@ -999,6 +1803,19 @@ impl<'interner> Monomorphizer<'interner> {
                         let location = self.interner.expr_location(expr_id);
                         Some(self.zeroed_value_of_type(result_type, location))
                     }
+                    "selector_of" => Some(self.selector_of(expr_id)),
+                    "array_get_unchecked" => {
+                        let [collection, index]: [_; 2] = std::mem::take(arguments)
+                            .try_into()
+                            .expect("array_get_unchecked takes a collection and an index");
+                        Some(ast::Expression::Index(ast::Index {
+                            collection: Box::new(collection),
+                            index: Box::new(index),
+                            element_type: result_type.clone(),
+                            location: self.interner.expr_location(expr_id),
+                            checked: false,
+                        }))
+                    }
                     "modulus_le_bits" => {
                         let bits = FieldElement::modulus().to_radix_le(2);
                         Some(self.modulus_array_literal(bits, 1))
@@ -1040,18 +1857,105 @@ impl<'interner> Monomorphizer<'interner> {
         &mut self,
         id: node_interner::FuncId,
         expr_id: node_interner::ExprId,
-        function_type: HirType,
+        key: InstantiationKey,
+        bindings: TypeBindings,
     ) -> FuncId {
         let new_id = self.next_function_id();
-        self.define_global(id, function_type, new_id);
-
-        let bindings = self.interner.get_instantiation_bindings(expr_id);
-        let bindings = self.follow_bindings(bindings);
+        self.check_type_expressions(id, expr_id, &key.0);
+        self.define_global(id, key, new_id);
+        self.check_numeric_constraints(id, expr_id, &bindings);
 
         self.queue.push_back((id, new_id, bindings));
         new_id
     }
 
+    /// Type-level expressions such as `N - 1` in a generic function's signature can only be
+    /// evaluated once the function is instantiated. Report any which are invalid for this
+    /// instantiation, e.g. because they would make an array length negative.
+    fn check_type_expressions(
+        &mut self,
+        id: node_interner::FuncId,
+        expr_id: node_interner::ExprId,
+        function_type: &HirType,
+    ) {
+        if self.error.is_some() {
+            return;
+        }
+
+        if let Some(expression) = find_invalid_type_expression(function_type) {
+            let function = Some(self.interner.function_name(&id).to_owned());
+            let location = self.interner.expr_location(&expr_id);
+            self.error = Some(invalid_type_expression_error(expression, function, location));
+        }
+    }
+
+    /// Evaluates a call to `std::selector_of` to the selector of the function it is passed,
+    /// which must be named directly rather than through a variable.
+    fn selector_of(&mut self, expr_id: &node_interner::ExprId) -> ast::Expression {
+        let function = match self.interner.expression(expr_id) {
+            HirExpression::Call(call) => self.interner.expression(&call.arguments[0]),
+            other => unreachable!("Expected a call to `selector_of`, got {other:?}"),
+        };
+        let selector = match function {
+            HirExpression::Ident(ident, _) => match self.interner.definition(ident.id).kind {
+                DefinitionKind::Function(func_id) => {
+                    let name = self.interner.function_name(&func_id);
+                    Some(self.interner.function_meta(&func_id).selector(name))
+                }
+                _ => None,
+            },
+            _ => None,
+        };
+        let selector = selector.unwrap_or_else(|| {
+            if self.error.is_none() {
+                let location = self.interner.expr_location(expr_id);
+                self.error = Some(MonomorphizationError::SelectorOfNonFunction { location });
+            }
+            0
+        });
+        let typ = ast::Type::Integer(crate::Signedness::Unsigned, 32);
+        ast::Expression::Literal(ast::Literal::Integer((selector as u128).into(), typ))
+    }
+
+    /// Check that the numeric generics a function is instantiated with at `expr_id` satisfy
+    /// the bounds in its where clause, such as `N <= 32`.
+    fn check_numeric_constraints(
+        &mut self,
+        id: node_interner::FuncId,
+        expr_id: node_interner::ExprId,
+        bindings: &TypeBindings,
+    ) {
+        if self.error.is_some() {
+            return;
+        }
+
+        let Some(meta) = self.interner.try_function_meta(&id) else {
+            return;
+        };
+
+        for constraint in &meta.numeric_constraints {
+            let lhs = constraint.lhs.substitute(bindings);
+            let rhs = constraint.rhs.substitute(bindings);
+
+            // Expressions which can't be evaluated are reported by `check_type_expressions`
+            let (Some(lhs_value), Some(rhs_value)) = (lhs.evaluate_to_u64(), rhs.evaluate_to_u64())
+            else {
+                continue;
+            };
+
+            if !constraint.holds(lhs_value, rhs_value) {
+                let comparison = constraint.comparison;
+                self.error = Some(MonomorphizationError::UnsatisfiedNumericConstraint {
+                    constraint: constraint.to_string(),
+                    instantiated: format!("{lhs_value} {comparison} {rhs_value}"),
+                    function: self.interner.function_name(&id).to_owned(),
+                    location: self.interner.expr_location(&expr_id),
+                });
+                return;
+            }
+        }
+    }
+
     /// Follow any type variable links within the given TypeBindings to produce
     /// a new TypeBindings that won't be changed when bindings are pushed or popped
     /// during {perform,undo}_monomorphization_bindings.
@@ -1077,11 +1981,15 @@ impl<'interner> Monomorphizer<'interner> {
 
     fn lvalue(&mut self, lvalue: HirLValue) -> ast::LValue {
         match lvalue {
-            HirLValue::Ident(ident, _) => self
-                .lookup_captured_lvalue(ident.id)
-                .unwrap_or_else(|| ast::LValue::Ident(self.local_ident(&ident).unwrap())),
+            HirLValue::Ident(ident, _) => {
+                if let Some(lvalue) = self.lookup_captured_lvalue(ident.id) {
+                    return lvalue;
+                }
+                let ident = self.mutable_global(&ident).or_else(|| self.local_ident(&ident));
+                ast::LValue::Ident(ident.unwrap())
+            }
             HirLValue::MemberAccess { object, field_index, .. } => {
-                let field_index = field_index.unwrap();
+                let field_index = layout_position(&lvalue_type(&object), field_index.unwrap());
                 let object = Box::new(self.lvalue(*object));
                 ast::LValue::MemberAccess { object, field_index }
             }
@@ -1125,8 +2033,10 @@ impl<'interner> Monomorphizer<'interner> {
         let return_type = ret_type.clone();
         let name = lambda_name.to_owned();
         let unconstrained = false;
+        let inline_type = InlineType::Default;
 
-        let function = ast::Function { id, name, parameters, body, return_type, unconstrained };
+        let function =
+            ast::Function { id, name, parameters, body, return_type, unconstrained, inline_type };
         self.push_function(id, function);
 
         let typ =
@@ -1240,7 +2150,9 @@ impl<'interner> Monomorphizer<'interner> {
         parameters.append(&mut converted_parameters);
 
         let unconstrained = false;
-        let function = ast::Function { id, name, parameters, body, return_type, unconstrained };
+        let inline_type = InlineType::Default;
+        let function =
+            ast::Function { id, name, parameters, body, return_type, unconstrained, inline_type };
         self.push_function(id, function);
 
         let lambda_value =
@@ -1357,7 +2269,9 @@ impl<'interner> Monomorphizer<'interner> {
         let name = lambda_name.to_owned();
 
         let unconstrained = false;
-        let function = ast::Function { id, name, parameters, body, return_type, unconstrained };
+        let inline_type = InlineType::Default;
+        let function =
+            ast::Function { id, name, parameters, body, return_type, unconstrained, inline_type };
         self.push_function(id, function);
 
         ast::Expression::Ident(ast::Ident {
@@ -1374,6 +2288,69 @@ impl<'interner> Monomorphizer<'interner> {
     }
 }
 
+fn invalid_type_expression_error(
+    (lhs, op, rhs): (u64, BinaryTypeOperator, u64),
+    function: Option<String>,
+    location: Location,
+) -> MonomorphizationError {
+    let reason = match op {
+        BinaryTypeOperator::Subtraction => "the result would be negative",
+        BinaryTypeOperator::Division | BinaryTypeOperator::Modulo if rhs == 0 => "division by zero",
+        _ => "the result is too large",
+    };
+    let expression = format!("{lhs} {op} {rhs}");
+    MonomorphizationError::InvalidTypeExpression { expression, reason, function, location }
+}
+
+/// Finds a type-level expression in the given type whose operands are known
+/// but which cannot be evaluated, returning its operator and operands.
+fn find_invalid_type_expression(typ: &HirType) -> Option<(u64, BinaryTypeOperator, u64)> {
+    match typ {
+        HirType::InfixExpr(lhs, op, rhs) => match (lhs.evaluate_to_u64(), rhs.evaluate_to_u64()) {
+            (Some(lhs), Some(rhs)) if op.checked_function()(lhs, rhs).is_none() => {
+                Some((lhs, *op, rhs))
+            }
+            _ => find_invalid_type_expression(lhs).or_else(|| find_invalid_type_expression(rhs)),
+        },
+        HirType::TypeVariable(binding, _) | HirType::NamedGeneric(binding, _) => {
+            match &*binding.borrow() {
+                TypeBinding::Bound(binding) => find_invalid_type_expression(binding),
+                TypeBinding::Unbound(_) => None,
+            }
+        }
+        HirType::Array(length, element) => {
+            find_invalid_type_expression(length).or_else(|| find_invalid_type_expression(element))
+        }
+        HirType::String(length) => find_invalid_type_expression(length),
+        HirType::FmtString(length, fields) => {
+            find_invalid_type_expression(length).or_else(|| find_invalid_type_expression(fields))
+        }
+        HirType::Struct(def, args) => {
+            let fields = def.borrow().get_fields(args);
+            fields.iter().find_map(|(_, field)| find_invalid_type_expression(field))
+        }
+        HirType::Tuple(fields) => fields.iter().find_map(find_invalid_type_expression),
+        HirType::Function(args, ret, env) => {
+            args.iter().chain([ret.as_ref(), env.as_ref()]).find_map(find_invalid_type_expression)
+        }
+        HirType::MutableReference(element) => find_invalid_type_expression(element),
+        _ => None,
+    }
+}
+
+/// Returns the representation of `-value` in the given type: the two's complement of `value`
+/// for integers, or its additive inverse for fields. The type checker has already checked
+/// that `value` fits into the type.
+fn negate_integer_literal(value: FieldElement, typ: &ast::Type) -> FieldElement {
+    match typ {
+        ast::Type::Integer(_, bit_size) => {
+            let modulus = 1u128 << bit_size;
+            FieldElement::from((modulus - value.to_u128()) % modulus)
+        }
+        _ => -value,
+    }
+}
+
 fn unwrap_tuple_type(typ: &HirType) -> Vec<HirType> {
     match typ {
         HirType::Tuple(fields) => fields.clone(),
@@ -1385,9 +2362,61 @@ fn unwrap_tuple_type(typ: &HirType) -> Vec<HirType> {
     }
 }
 
+/// Returns the position of the field with the given index in the tuple a value of type `typ`
+/// is compiled into, which differs from the index for structs whose layout reorders their fields.
+fn layout_position(typ: &HirType, field_index: usize) -> usize {
+    match typ.follow_bindings() {
+        HirType::Struct(def, _) => def.borrow().layout_position(field_index),
+        HirType::MutableReference(element) => layout_position(&element, field_index),
+        _ => field_index,
+    }
+}
+
+/// Returns the type of the value a type checked lvalue refers to
+fn lvalue_type(lvalue: &HirLValue) -> HirType {
+    match lvalue {
+        HirLValue::Ident(_, typ)
+        | HirLValue::MemberAccess { typ, .. }
+        | HirLValue::Index { typ, .. }
+        | HirLValue::Dereference { element_type: typ, .. } => typ.clone(),
+    }
+}
+
+/// Returns the types of the fields of a struct or tuple type, in the order they're compiled in
+fn unwrap_fields(typ: &HirType) -> Vec<HirType> {
+    match typ.follow_bindings() {
+        HirType::Tuple(fields) => fields,
+        other => vecmap(unwrap_struct_type(&other), |(_, field)| field),
+    }
+}
+
+/// The type of the array a struct with a packed layout is passed to foreign calls as
+fn packed_array_type(length: u64) -> ast::Type {
+    ast::Type::Array(length, Box::new(ast::Type::Field))
+}
+
+/// Indexes into `array` at a constant index known to be within its bounds
+fn index_constant(
+    array: ast::Expression,
+    index: u64,
+    element_type: ast::Type,
+    location: Location,
+) -> ast::Expression {
+    let index_type = ast::Type::Integer(crate::Signedness::Unsigned, 64);
+    let index = ast::Literal::Integer(FieldElement::from(index as u128), index_type);
+    ast::Expression::Index(ast::Index {
+        collection: Box::new(array),
+        index: Box::new(ast::Expression::Literal(index)),
+        element_type,
+        location,
+        checked: true,
+    })
+}
+
+/// Returns the fields of a struct type in the order given by its layout
 fn unwrap_struct_type(typ: &HirType) -> Vec<(String, HirType)> {
     match typ {
-        HirType::Struct(def, args) => def.borrow().get_fields(args),
+        HirType::Struct(def, args) => def.borrow().get_fields_in_layout_order(args),
         HirType::TypeVariable(binding, TypeVariableKind::Normal) => match &*binding.borrow() {
             TypeBinding::Bound(binding) => unwrap_struct_type(binding),
             TypeBinding::Unbound(_) => unreachable!(),
@@ -1396,6 +2425,13 @@ fn unwrap_struct_type(typ: &HirType) -> Vec<(String, HirType)> {
     }
 }
 
+/// The types bound to each generic of an instantiation, in a deterministic order
+fn instantiated_generics(bindings: &TypeBindings) -> Vec<HirType> {
+    let mut generics: Vec<_> = bindings.iter().collect();
+    generics.sort_by_key(|(id, _)| id.0);
+    vecmap(generics, |(_, (_, typ))| typ.clone())
+}
+
 fn perform_instantiation_bindings(bindings: &TypeBindings) {
     for (var, binding) in bindings.values() {
         *var.borrow_mut() = TypeBinding::Bound(binding.clone());
@@ -2,11 +2,14 @@
 
 use super::ast::{Definition, Expression, Function, LValue};
 use iter_extended::vecmap;
+use noirc_printable_type::FieldDisplay;
 use std::fmt::{Display, Formatter};
 
 #[derive(Default)]
 pub struct AstPrinter {
     indent_level: u32,
+    /// How to show integer literals, or with their usual display if `None`
+    pub field_display: Option<FieldDisplay>,
 }
 
 impl AstPrinter {
@@ -47,6 +50,15 @@ impl AstPrinter {
                 write!(f, " as {})", cast.r#type)
             }
             Expression::For(for_expr) => self.print_for(for_expr, f),
+            Expression::Loop(block) => {
+                write!(f, "loop {{")?;
+                self.indent_level += 1;
+                self.print_expr_expect_block(block, f)?;
+                self.indent_level -= 1;
+                self.next_line(f)?;
+                write!(f, "}}")
+            }
+            Expression::Break => write!(f, "break"),
             Expression::If(if_expr) => self.print_if(if_expr, f),
             Expression::Tuple(tuple) => self.print_tuple(tuple, f),
             Expression::ExtractTupleField(expr, index) => {
@@ -93,9 +105,12 @@ impl AstPrinter {
                 self.print_comma_separated(&array.contents, f)?;
                 write!(f, "]")
             }
-            super::ast::Literal::Integer(x, _) => x.fmt(f),
+            super::ast::Literal::Integer(x, _) => match self.field_display {
+                Some(display) => write!(f, "{}", display.format(*x)),
+                None => x.fmt(f),
+            },
             super::ast::Literal::Bool(x) => x.fmt(f),
-            super::ast::Literal::Str(s) => s.fmt(f),
+            super::ast::Literal::Str(s) => write!(f, "\"{}\"", crate::escape_string(s)),
             super::ast::Literal::FmtStr(s, _, _) => {
                 write!(f, "f\"")?;
                 s.fmt(f)?;
@@ -283,6 +298,7 @@ impl Display for Definition {
             Definition::Builtin(name) => write!(f, "{name}"),
             Definition::LowLevel(name) => write!(f, "{name}"),
             Definition::Oracle(name) => write!(f, "{name}"),
+            Definition::MutableGlobal(index) => write!(f, "g{index}"),
         }
     }
 }
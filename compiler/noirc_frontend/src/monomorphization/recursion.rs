@@ -0,0 +1,159 @@
+//! Constrained functions are all inlined into `main` when compiling to ACIR, so they cannot be
+//! recursive. This module looks for cycles of calls between constrained functions in the
+//! monomorphized program so that they can be reported along with each call in the cycle,
+//! rather than failing part way through inlining.
+//!
+//! Calls made from unconstrained functions are not considered since everything they call is
+//! compiled to Brillig, where recursion is allowed.
+use std::collections::{BTreeMap, HashSet};
+
+use noirc_errors::Location;
+
+use super::ast::{Definition, Expression, FuncId, Function, LValue, Literal};
+use super::errors::{MonomorphizationError, RecursiveCall};
+
+/// Returns an error describing the first cycle of calls between constrained functions which
+/// is reachable from `main`, if there is one.
+pub(super) fn check_for_recursion(
+    functions: &BTreeMap<FuncId, Function>,
+    main: FuncId,
+) -> Result<(), MonomorphizationError> {
+    let mut checker = RecursionChecker { functions, path: Vec::new(), finished: HashSet::new() };
+    match checker.find_cycle(main) {
+        Some(cycle) => Err(MonomorphizationError::RecursiveConstrainedFunction { cycle }),
+        None => Ok(()),
+    }
+}
+
+struct RecursionChecker<'a> {
+    functions: &'a BTreeMap<FuncId, Function>,
+
+    /// The functions currently being searched, each with the call to the next one in the path.
+    path: Vec<(FuncId, Location)>,
+
+    /// Functions which have been searched and are not part of any cycle.
+    finished: HashSet<FuncId>,
+}
+
+impl<'a> RecursionChecker<'a> {
+    fn find_cycle(&mut self, function: FuncId) -> Option<Vec<RecursiveCall>> {
+        if self.finished.contains(&function) || self.functions[&function].unconstrained {
+            return None;
+        }
+
+        for (callee, location) in self.calls_to_constrained_functions(function) {
+            self.path.push((function, location));
+            if let Some(start) = self.path.iter().position(|(caller, _)| *caller == callee) {
+                return Some(self.cycle(start));
+            }
+
+            let cycle = self.find_cycle(callee);
+            self.path.pop();
+
+            if cycle.is_some() {
+                return cycle;
+            }
+        }
+
+        self.finished.insert(function);
+        None
+    }
+
+    /// Returns the calls in the path from `start` back around to the function at `start`.
+    fn cycle(&self, start: usize) -> Vec<RecursiveCall> {
+        let calls = &self.path[start..];
+        let callees = calls.iter().skip(1).chain(calls.first()).map(|(callee, _)| *callee);
+
+        calls
+            .iter()
+            .zip(callees)
+            .map(|((caller, location), callee)| RecursiveCall {
+                caller: self.functions[caller].name.clone(),
+                callee: self.functions[&callee].name.clone(),
+                location: *location,
+            })
+            .collect()
+    }
+
+    /// The constrained functions called directly by `function`, each with the location of the
+    /// first call to it.
+    fn calls_to_constrained_functions(&self, function: FuncId) -> Vec<(FuncId, Location)> {
+        let mut calls = Vec::new();
+        collect_calls(&self.functions[&function].body, &mut calls);
+
+        let mut seen = HashSet::new();
+        calls.retain(|(callee, _)| !self.functions[callee].unconstrained && seen.insert(*callee));
+        calls
+    }
+}
+
+/// Collects every direct call to a function within `expression`.
+fn collect_calls(expression: &Expression, calls: &mut Vec<(FuncId, Location)>) {
+    match expression {
+        Expression::Ident(_) => (),
+        Expression::Literal(literal) => match literal {
+            Literal::Array(array) => {
+                array.contents.iter().for_each(|element| collect_calls(element, calls));
+            }
+            Literal::FmtStr(_, _, captures) => collect_calls(captures, calls),
+            Literal::Integer(..) | Literal::Bool(_) | Literal::Str(_) => (),
+        },
+        Expression::Block(expressions) | Expression::Tuple(expressions) => {
+            expressions.iter().for_each(|expression| collect_calls(expression, calls));
+        }
+        Expression::Unary(unary) => collect_calls(&unary.rhs, calls),
+        Expression::Binary(binary) => {
+            collect_calls(&binary.lhs, calls);
+            collect_calls(&binary.rhs, calls);
+        }
+        Expression::Index(index) => {
+            collect_calls(&index.collection, calls);
+            collect_calls(&index.index, calls);
+        }
+        Expression::Cast(cast) => collect_calls(&cast.lhs, calls),
+        Expression::For(for_loop) => {
+            collect_calls(&for_loop.start_range, calls);
+            collect_calls(&for_loop.end_range, calls);
+            collect_calls(&for_loop.block, calls);
+        }
+        Expression::Loop(block) => collect_calls(block, calls),
+        Expression::Break => (),
+        Expression::If(if_expr) => {
+            collect_calls(&if_expr.condition, calls);
+            collect_calls(&if_expr.consequence, calls);
+            if let Some(alternative) = &if_expr.alternative {
+                collect_calls(alternative, calls);
+            }
+        }
+        Expression::ExtractTupleField(tuple, _) => collect_calls(tuple, calls),
+        Expression::Call(call) => {
+            if let Expression::Ident(ident) = call.func.as_ref() {
+                if let Definition::Function(callee) = ident.definition {
+                    calls.push((callee, call.location));
+                }
+            } else {
+                collect_calls(&call.func, calls);
+            }
+            call.arguments.iter().for_each(|argument| collect_calls(argument, calls));
+        }
+        Expression::Let(let_statement) => collect_calls(&let_statement.expression, calls),
+        Expression::Constrain(condition, _, _) => collect_calls(condition, calls),
+        Expression::Assign(assign) => {
+            collect_lvalue_calls(&assign.lvalue, calls);
+            collect_calls(&assign.expression, calls);
+        }
+        Expression::Semi(expression) => collect_calls(expression, calls),
+    }
+}
+
+fn collect_lvalue_calls(lvalue: &LValue, calls: &mut Vec<(FuncId, Location)>) {
+    match lvalue {
+        LValue::Ident(_) => (),
+        LValue::Index { array, index, .. } => {
+            collect_lvalue_calls(array, calls);
+            collect_calls(index, calls);
+        }
+        LValue::MemberAccess { object, .. } => collect_lvalue_calls(object, calls),
+        LValue::Dereference { reference, .. } => collect_lvalue_calls(reference, calls),
+    }
+}
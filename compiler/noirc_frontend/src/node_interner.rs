@@ -1,18 +1,22 @@
-use std::collections::HashMap;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::path::PathBuf;
 
 use arena::{Arena, Index};
 use fm::FileId;
+use indexmap::IndexMap;
 use iter_extended::vecmap;
 use noirc_errors::{Location, Span, Spanned};
 
 use crate::ast::Ident;
 use crate::graph::CrateId;
+use crate::hir::cancellation::CancellationToken;
 use crate::hir::def_collector::dc_crate::{UnresolvedStruct, UnresolvedTrait, UnresolvedTypeAlias};
 use crate::hir::def_map::{LocalModuleId, ModuleId};
 use crate::hir::StorageSlot;
 use crate::hir_def::stmt::HirLetStatement;
 use crate::hir_def::traits::Trait;
 use crate::hir_def::traits::TraitImpl;
+use crate::hir_def::type_interner::{InternedType, TypeInterner};
 use crate::hir_def::types::{StructType, Type};
 use crate::hir_def::{
     expr::HirExpression,
@@ -21,18 +25,33 @@ use crate::hir_def::{
 };
 use crate::token::{Attributes, SecondaryAttribute};
 use crate::{
-    ContractFunctionType, FunctionDefinition, Generics, Shared, TypeAliasType, TypeBinding,
-    TypeBindings, TypeVariable, TypeVariableId, TypeVariableKind, Visibility,
+    BinaryOpKind, ContractFunctionType, FunctionDefinition, Generics, Shared, TypeAliasType,
+    TypeBinding, TypeBindings, TypeVariable, TypeVariableId, TypeVariableKind, Visibility,
 };
 
 #[derive(Eq, PartialEq, Hash, Clone)]
 pub struct TraitImplKey {
     pub typ: Type,
     pub trait_id: TraitId,
-    // pub generics: Generics - TODO
+    /// The generics of the trait given by the impl, e.g. `u8` in `impl From<u8> for u64`.
+    /// Empty if the impl leaves them out.
+    pub generics: Vec<Type>,
+}
+
+/// A call to `From::from` inserted around an expression to convert its value into another type.
+#[derive(Debug, Clone)]
+pub struct Conversion {
+    /// The `from` method of the `From` impl which is called
+    pub function: FuncId,
+    /// The instantiated type of `function`
+    pub function_type: Type,
+    pub bindings: TypeBindings,
+    /// The type the value is converted into
+    pub target: Type,
 }
 
 type StructAttributes = Vec<SecondaryAttribute>;
+type TraitAttributes = Vec<SecondaryAttribute>;
 
 /// The node interner is the central storage location of all nodes in Noir's Hir (the
 /// various node types can be found in hir_def). The interner is also used to collect
@@ -68,6 +87,12 @@ pub struct NodeInterner {
     // Because we use one Arena to store all Definitions/Nodes
     id_to_type: HashMap<Index, Type>,
 
+    // Fully known types are interned so that checking whether two of them are equal is cheap.
+    // `id_to_interned_type` caches the interned type of each entry of `id_to_type` which was
+    // fully known when it was looked up.
+    types: TypeInterner,
+    id_to_interned_type: HashMap<Index, InternedType>,
+
     // Struct map.
     //
     // Each struct definition is possibly shared across multiple type nodes.
@@ -89,10 +114,21 @@ pub struct NodeInterner {
     // methods from impls to the type.
     traits: HashMap<TraitId, Trait>,
 
+    trait_attributes: HashMap<TraitId, TraitAttributes>,
+
+    /// The call graph: the functions referenced in the body of each function, or
+    /// outside of any function (e.g. in a global) when the key is `None`.
+    function_references: HashMap<Option<FuncId>, HashSet<FuncId>>,
+
+    /// The names referred to by any path in each module. Imports whose name
+    /// never appears here are reported as unused.
+    used_names: HashSet<(ModuleId, String)>,
+
     // Trait implementation map
     // For each type that implements a given Trait ( corresponding TraitId), there should be an entry here
     // The purpose for this hashmap is to detect duplication of trait implementations ( if any )
-    trait_implementations: HashMap<TraitImplKey, Shared<TraitImpl>>,
+    // It is kept in insertion order since generic impls are searched by iterating over it.
+    trait_implementations: IndexMap<TraitImplKey, Shared<TraitImpl>>,
 
     /// Map from ExprId (referring to a Function/Method call) to its corresponding TypeBindings,
     /// filled out during type checking from instantiated variables. Used during monomorphization
@@ -103,7 +139,7 @@ pub struct NodeInterner {
     /// checking.
     field_indices: HashMap<ExprId, usize>,
 
-    globals: HashMap<StmtId, GlobalInfo>, // NOTE: currently only used for checking repeat globals and restricting their scope to a module
+    globals: IndexMap<StmtId, GlobalInfo>, // NOTE: currently only used for checking repeat globals and restricting their scope to a module
 
     next_type_variable_id: std::cell::Cell<usize>,
 
@@ -123,6 +159,90 @@ pub struct NodeInterner {
 
     /// Trait implementations on primitive types
     primitive_trait_impls: HashMap<(Type, String), FuncId>,
+
+    /// Operators which may be overloaded on user types, mapped to the stdlib
+    /// trait method they are rewritten into. E.g. `+` maps to `std::ops::Add::add`.
+    operator_traits: HashMap<BinaryOpKind, TraitMethodId>,
+
+    /// The `std::ops::Index::index` method used to overload indexing on user types.
+    index_trait_method: Option<TraitMethodId>,
+
+    /// The `std::ops::IndexMut::index_mut` method used to overload assigning to an index on
+    /// user types.
+    index_mut_trait_method: Option<TraitMethodId>,
+
+    /// The `std::ops::Deref::deref` method used to access the fields and methods of the type
+    /// a wrapper type dereferences to.
+    deref_trait_method: Option<TraitMethodId>,
+
+    /// The `std::ops::Drop::drop` method called on variables of unconstrained functions
+    /// when they go out of scope.
+    drop_trait_method: Option<TraitMethodId>,
+
+    /// The `std::convert::From::from` method, called by `x.into()` and implicit conversions.
+    from_trait_method: Option<TraitMethodId>,
+
+    /// The `std::convert::Into::into` method. `Into` has no impls of its own: `T: Into<U>` holds
+    /// when there is an `impl From<T> for U`, whose `from` method is called by `into`.
+    into_trait_method: Option<TraitMethodId>,
+
+    /// Whether arguments in the root crate are implicitly converted into the type of their
+    /// parameter when the types differ but there is a `From` impl between them.
+    implicit_conversions: bool,
+
+    /// Expressions whose value is converted into another type with a `From` impl.
+    conversions: HashMap<ExprId, Conversion>,
+
+    /// The `std::unchecked::Unchecked` struct, which wraps the results of unconstrained calls
+    /// from constrained code when `unchecked_hints` is set.
+    unchecked_struct: Option<StructId>,
+
+    /// Whether the results of calls from constrained functions to unconstrained ones are
+    /// wrapped in `std::unchecked::Unchecked`.
+    unchecked_hints: bool,
+
+    /// Whether `assert_eq` and `assert_ne` without a message report the values they compare
+    /// when they fail, which adds an unconstrained call to the program for each of them.
+    report_assert_values: bool,
+
+    /// Values converted with `x.into()` whose `From` impl has not been found yet, along with
+    /// the types they are converted from and into. These are checked once the enclosing
+    /// function is type checked, since the type converted into is usually only known then.
+    unchecked_conversions: Vec<(ExprId, Type, Type)>,
+
+    /// Infix expressions which were type checked as a call to an operator trait method,
+    /// along with the instantiated type of that method. Used during monomorphization
+    /// to replace the operator with a call to the trait impl.
+    operator_overloads: HashMap<ExprId, (TraitMethodId, Type)>,
+
+    /// Expressions which are implicitly converted into a trait object, mapped to the trait
+    /// and the type of the expression before the conversion.
+    trait_object_coercions: HashMap<ExprId, (TraitId, Type)>,
+
+    /// Conversions into trait objects whose types have not yet been checked against the
+    /// layout of the trait object. These are checked once the enclosing function is type checked.
+    unchecked_trait_object_coercions: Vec<ExprId>,
+
+    /// The concrete types of the values converted into trait objects of each trait, in the order
+    /// they were first converted. Each trait object has room for a value of every one of them.
+    trait_object_types: HashMap<TraitId, Vec<Type>>,
+
+    /// The outermost `comptime` blocks and calls to `comptime fn`s which have not been evaluated
+    /// yet. Each is replaced by its value once the crate they are in is type checked.
+    unevaluated_comptime_expressions: Vec<ExprId>,
+
+    /// The environment variables which `std::env!` can read, as set by the driver.
+    environment: HashMap<String, String>,
+
+    /// The environment variables read by `std::env!` along with their values, which are
+    /// recorded in the compiled artifact.
+    environment_reads: BTreeMap<String, String>,
+
+    /// The files read by `std::include_bytes!` and `std::include_str!`.
+    included_files: BTreeSet<PathBuf>,
+
+    /// Checked periodically by each pass over the program so that it can be aborted early.
+    cancellation: CancellationToken,
 }
 
 /// All the information from a function that is filled out during definition collection rather than
@@ -138,6 +258,12 @@ pub struct FunctionModifiers {
 
     pub is_unconstrained: bool,
 
+    /// Whether the function is a `const fn`, which may be evaluated at compile time.
+    pub is_const: bool,
+
+    /// Whether the function is a `comptime fn`, whose calls are evaluated at compile time.
+    pub is_comptime: bool,
+
     /// This function's type in its contract.
     /// If this function is not in a contract, this is always 'Secret'.
     pub contract_function_type: Option<ContractFunctionType>,
@@ -158,6 +284,8 @@ impl FunctionModifiers {
             visibility: Visibility::Public,
             attributes: Attributes::empty(),
             is_unconstrained: false,
+            is_const: false,
+            is_comptime: false,
             is_internal: None,
             contract_function_type: None,
         }
@@ -376,18 +504,45 @@ impl Default for NodeInterner {
             id_to_location: HashMap::new(),
             definitions: vec![],
             id_to_type: HashMap::new(),
+            types: TypeInterner::default(),
+            id_to_interned_type: HashMap::new(),
             structs: HashMap::new(),
             struct_attributes: HashMap::new(),
             type_aliases: Vec::new(),
             traits: HashMap::new(),
-            trait_implementations: HashMap::new(),
+            trait_attributes: HashMap::new(),
+            function_references: HashMap::new(),
+            used_names: HashSet::new(),
+            trait_implementations: IndexMap::new(),
             instantiation_bindings: HashMap::new(),
             field_indices: HashMap::new(),
             next_type_variable_id: std::cell::Cell::new(0),
-            globals: HashMap::new(),
+            globals: IndexMap::new(),
             struct_methods: HashMap::new(),
             primitive_methods: HashMap::new(),
             primitive_trait_impls: HashMap::new(),
+            operator_traits: HashMap::new(),
+            index_trait_method: None,
+            index_mut_trait_method: None,
+            deref_trait_method: None,
+            drop_trait_method: None,
+            from_trait_method: None,
+            into_trait_method: None,
+            implicit_conversions: false,
+            conversions: HashMap::new(),
+            unchecked_struct: None,
+            unchecked_hints: false,
+            report_assert_values: false,
+            unchecked_conversions: Vec::new(),
+            operator_overloads: HashMap::new(),
+            trait_object_coercions: HashMap::new(),
+            unchecked_trait_object_coercions: Vec::new(),
+            trait_object_types: HashMap::new(),
+            unevaluated_comptime_expressions: Vec::new(),
+            environment: HashMap::new(),
+            environment_reads: BTreeMap::new(),
+            included_files: BTreeSet::new(),
+            cancellation: CancellationToken::default(),
         };
 
         // An empty block expression is used often, we add this into the `node` on startup
@@ -400,6 +555,74 @@ impl Default for NodeInterner {
 // XXX: Add check that insertions are not overwrites for maps
 // XXX: Maybe change push to intern, and remove comments
 impl NodeInterner {
+    pub fn cancellation_token(&self) -> &CancellationToken {
+        &self.cancellation
+    }
+
+    /// Sets the token which the resolver, type checker, monomorphizer and SSA passes
+    /// check to see whether this compilation should be aborted.
+    pub fn set_cancellation_token(&mut self, token: CancellationToken) {
+        self.cancellation = token;
+    }
+
+    pub fn implicit_conversions(&self) -> bool {
+        self.implicit_conversions
+    }
+
+    /// Sets whether function arguments in the root crate are implicitly converted with `From`
+    /// impls
+    pub fn set_implicit_conversions(&mut self, enabled: bool) {
+        self.implicit_conversions = enabled;
+    }
+
+    pub fn unchecked_hints(&self) -> bool {
+        self.unchecked_hints
+    }
+
+    /// Sets whether the results of unconstrained calls from constrained functions are wrapped
+    /// in `std::unchecked::Unchecked`
+    pub fn set_unchecked_hints(&mut self, enabled: bool) {
+        self.unchecked_hints = enabled;
+    }
+
+    pub fn report_assert_values(&self) -> bool {
+        self.report_assert_values
+    }
+
+    /// Sets whether failing `assert_eq` and `assert_ne` statements without a message report
+    /// the values they compare
+    pub fn set_report_assert_values(&mut self, enabled: bool) {
+        self.report_assert_values = enabled;
+    }
+
+    /// Sets the environment variables which `std::env!` can read.
+    pub fn set_environment(&mut self, environment: impl IntoIterator<Item = (String, String)>) {
+        self.environment = environment.into_iter().collect();
+    }
+
+    /// Returns the value of the environment variable `name` for `std::env!`, if it is set,
+    /// recording that it was read.
+    pub fn read_environment_variable(&mut self, name: &str) -> Option<String> {
+        let value = self.environment.get(name)?.clone();
+        self.environment_reads.insert(name.to_owned(), value.clone());
+        Some(value)
+    }
+
+    /// The environment variables read by `std::env!` so far, along with their values.
+    pub fn environment_reads(&self) -> &BTreeMap<String, String> {
+        &self.environment_reads
+    }
+
+    /// Records that the file at `path` was read by `std::include_bytes!` or `std::include_str!`.
+    pub fn record_included_file(&mut self, path: PathBuf) {
+        self.included_files.insert(path);
+    }
+
+    /// The files read by `std::include_bytes!` and `std::include_str!` so far.
+    pub fn included_files(&self) -> &BTreeSet<PathBuf> {
+        &self.included_files
+    }
+
     /// Interns a HIR statement.
     pub fn push_stmt(&mut self, stmt: HirStatement) -> StmtId {
         StmtId(self.nodes.insert(Node::Statement(stmt)))
@@ -421,6 +644,7 @@ impl NodeInterner {
 
     /// Store the type for an interned expression
     pub fn push_expr_type(&mut self, expr_id: &ExprId, typ: Type) {
+        self.id_to_interned_type.remove(&expr_id.into());
         self.id_to_type.insert(expr_id.into(), typ);
     }
 
@@ -447,6 +671,7 @@ impl NodeInterner {
                 self_type_typevar,
             ),
         );
+        self.trait_attributes.insert(type_id, typ.trait_def.attributes.clone());
     }
 
     pub fn new_struct(
@@ -533,6 +758,7 @@ impl NodeInterner {
 
     /// Store the type for an interned Identifier
     pub fn push_definition_type(&mut self, definition_id: DefinitionId, typ: Type) {
+        self.id_to_interned_type.remove(&definition_id.into());
         self.id_to_type.insert(definition_id.into(), typ);
     }
 
@@ -640,6 +866,8 @@ impl NodeInterner {
             visibility: if function.is_public { Visibility::Public } else { Visibility::Private },
             attributes: function.attributes.clone(),
             is_unconstrained: function.is_unconstrained,
+            is_const: function.is_const,
+            is_comptime: function.is_comptime,
             contract_function_type: Some(if function.is_open { Open } else { Secret }),
             is_internal: Some(function.is_internal),
         };
@@ -726,6 +954,31 @@ impl NodeInterner {
         &self.struct_attributes[struct_id]
     }
 
+    pub fn trait_attributes(&self, trait_id: &TraitId) -> &TraitAttributes {
+        &self.trait_attributes[trait_id]
+    }
+
+    pub fn add_function_reference(&mut self, referrer: Option<FuncId>, func: FuncId) {
+        self.function_references.entry(referrer).or_default().insert(func);
+    }
+
+    /// Returns the functions referenced in the body of `referrer`, or outside
+    /// of any function if `referrer` is `None`.
+    pub fn function_references(
+        &self,
+        referrer: Option<FuncId>,
+    ) -> impl Iterator<Item = FuncId> + '_ {
+        self.function_references.get(&referrer).into_iter().flatten().copied()
+    }
+
+    pub fn mark_name_used(&mut self, module: ModuleId, name: &str) {
+        self.used_names.insert((module, name.to_owned()));
+    }
+
+    pub fn is_name_used(&self, module: ModuleId, name: &str) -> bool {
+        self.used_names.contains(&(module, name.to_owned()))
+    }
+
     /// Returns the interned statement corresponding to `stmt_id`
     pub fn statement(&self, stmt_id: &StmtId) -> HirStatement {
         let def =
@@ -811,7 +1064,7 @@ impl NodeInterner {
         self.globals.get(stmt_id).cloned()
     }
 
-    pub fn get_all_globals(&self) -> HashMap<StmtId, GlobalInfo> {
+    pub fn get_all_globals(&self) -> IndexMap<StmtId, GlobalInfo> {
         self.globals.clone()
     }
 
@@ -820,6 +1073,19 @@ impl NodeInterner {
         self.id_to_type.get(&index.into()).cloned().unwrap_or(Type::Error)
     }
 
+    /// Returns the interned type of the given expression or definition, or `None` if its type
+    /// is not fully known yet. A fully known type can't change through type variable bindings,
+    /// so it is only interned once.
+    pub fn id_interned_type(&mut self, index: impl Into<Index>) -> Option<InternedType> {
+        let index = index.into();
+        if let Some(interned) = self.id_to_interned_type.get(&index) {
+            return Some(*interned);
+        }
+        let interned = self.types.intern(self.id_to_type.get(&index)?)?;
+        self.id_to_interned_type.insert(index, interned);
+        Some(interned)
+    }
+
     pub fn id_type_substitute_trait_as_type(&self, def_id: DefinitionId) -> Type {
         let typ = self.id_type(def_id);
         if let Type::Function(args, ret, env) = &typ {
@@ -878,6 +1144,180 @@ impl NodeInterner {
         self.field_indices.insert(expr_id, index);
     }
 
+    /// Registers the given stdlib trait as the trait used to overload its operators,
+    /// if it is one of the operator traits in `std::ops` or `std::cmp`. `std::convert::From`
+    /// is registered in the same way, as the trait used for conversions.
+    ///
+    /// `module_path` is the path of the module defining the trait within the stdlib, so that
+    /// other stdlib traits which happen to share a name aren't mistaken for these.
+    pub fn try_add_operator_trait(&mut self, trait_id: TraitId, module_path: &str) {
+        let the_trait = &self.traits[&trait_id];
+        let find_method = |name: &str| {
+            let method_index =
+                the_trait.methods.iter().position(|method| method.name.0.contents == name)?;
+            Some(TraitMethodId { trait_id, method_index })
+        };
+
+        let (operators, method): (&[BinaryOpKind], _) =
+            match (module_path, the_trait.name.0.contents.as_str()) {
+                ("ops", "Add") => (&[BinaryOpKind::Add], find_method("add")),
+                ("ops", "Sub") => (&[BinaryOpKind::Subtract], find_method("sub")),
+                ("ops", "Mul") => (&[BinaryOpKind::Multiply], find_method("mul")),
+                ("ops", "Div") => (&[BinaryOpKind::Divide], find_method("div")),
+                ("cmp", "Eq") => {
+                    (&[BinaryOpKind::Equal, BinaryOpKind::NotEqual], find_method("eq"))
+                }
+                ("cmp", "Ord") => (
+                    &[
+                        BinaryOpKind::Less,
+                        BinaryOpKind::LessEqual,
+                        BinaryOpKind::Greater,
+                        BinaryOpKind::GreaterEqual,
+                    ],
+                    find_method("cmp"),
+                ),
+                ("ops", "Index") => {
+                    self.index_trait_method = find_method("index");
+                    return;
+                }
+                ("ops", "IndexMut") => {
+                    self.index_mut_trait_method = find_method("index_mut");
+                    return;
+                }
+                ("ops", "Deref") => {
+                    self.deref_trait_method = find_method("deref");
+                    return;
+                }
+                ("ops", "Drop") => {
+                    self.drop_trait_method = find_method("drop");
+                    return;
+                }
+                ("convert", "From") => {
+                    self.from_trait_method = find_method("from");
+                    return;
+                }
+                ("convert", "Into") => {
+                    self.into_trait_method = find_method("into");
+                    return;
+                }
+                _ => return,
+            };
+
+        if let Some(method) = method {
+            for operator in operators {
+                self.operator_traits.insert(*operator, method);
+            }
+        }
+    }
+
+    pub fn get_operator_trait_method(&self, operator: BinaryOpKind) -> Option<TraitMethodId> {
+        self.operator_traits.get(&operator).copied()
+    }
+
+    pub fn get_index_trait_method(&self) -> Option<TraitMethodId> {
+        self.index_trait_method
+    }
+
+    pub fn get_index_mut_trait_method(&self) -> Option<TraitMethodId> {
+        self.index_mut_trait_method
+    }
+
+    pub fn get_deref_trait_method(&self) -> Option<TraitMethodId> {
+        self.deref_trait_method
+    }
+
+    pub fn get_drop_trait_method(&self) -> Option<TraitMethodId> {
+        self.drop_trait_method
+    }
+
+    pub fn get_from_trait_method(&self) -> Option<TraitMethodId> {
+        self.from_trait_method
+    }
+
+    /// Registers the given stdlib struct as `std::unchecked::Unchecked` if it is that struct,
+    /// where `module_path` is the path of the module defining it within the stdlib.
+    pub fn try_add_unchecked_struct(&mut self, struct_id: StructId, module_path: &str) {
+        if module_path == "unchecked"
+            && self.structs[&struct_id].borrow().name.0.contents == "Unchecked"
+        {
+            self.unchecked_struct = Some(struct_id);
+        }
+    }
+
+    pub fn get_unchecked_struct(&self) -> Option<StructId> {
+        self.unchecked_struct
+    }
+
+    pub fn set_operator_overload(&mut self, expr_id: ExprId, method: TraitMethodId, typ: Type) {
+        self.operator_overloads.insert(expr_id, (method, typ));
+    }
+
+    pub fn get_operator_overload(&self, expr_id: ExprId) -> Option<&(TraitMethodId, Type)> {
+        self.operator_overloads.get(&expr_id)
+    }
+
+    /// Records that the value of `expr_id`, of type `typ`, is converted into a trait object.
+    pub fn push_trait_object_coercion(&mut self, expr_id: ExprId, trait_id: TraitId, typ: Type) {
+        self.trait_object_coercions.insert(expr_id, (trait_id, typ));
+        self.unchecked_trait_object_coercions.push(expr_id);
+
+        // The methods of the trait impl are referenced from this expression during
+        // monomorphization, which expects every such expression to have instantiation bindings.
+        self.instantiation_bindings.entry(expr_id).or_default();
+    }
+
+    pub fn get_trait_object_coercion(&self, expr_id: ExprId) -> Option<&(TraitId, Type)> {
+        self.trait_object_coercions.get(&expr_id)
+    }
+
+    /// Records that the value of `expr_id` is converted by calling a `From` impl.
+    pub fn push_conversion(&mut self, expr_id: ExprId, conversion: Conversion) {
+        self.conversions.insert(expr_id, conversion);
+
+        // As with trait object coercions, the impl is referenced from this expression
+        // during monomorphization, which expects it to have instantiation bindings.
+        self.instantiation_bindings.entry(expr_id).or_default();
+    }
+
+    pub fn get_conversion(&self, expr_id: ExprId) -> Option<&Conversion> {
+        self.conversions.get(&expr_id)
+    }
+
+    /// Records that the value of `expr_id` is converted from `source` into `target`, once
+    /// the `From` impl converting between them is known.
+    pub fn push_unchecked_conversion(&mut self, expr_id: ExprId, source: Type, target: Type) {
+        self.unchecked_conversions.push((expr_id, source, target));
+    }
+
+    pub fn take_unchecked_conversions(&mut self) -> Vec<(ExprId, Type, Type)> {
+        std::mem::take(&mut self.unchecked_conversions)
+    }
+
+    pub fn take_unchecked_trait_object_coercions(&mut self) -> Vec<ExprId> {
+        std::mem::take(&mut self.unchecked_trait_object_coercions)
+    }
+
+    pub fn get_trait_object_types(&self, trait_id: TraitId) -> &[Type] {
+        self.trait_object_types.get(&trait_id).map_or(&[], Vec::as_slice)
+    }
+
+    /// Records that a value of the given concrete type is converted into a trait object
+    pub fn add_trait_object_type(&mut self, trait_id: TraitId, typ: Type) {
+        let types = self.trait_object_types.entry(trait_id).or_default();
+        if !types.contains(&typ) {
+            types.push(typ);
+        }
+    }
+
+    /// Records that `expr_id` should be evaluated at compile time and replaced by its value.
+    pub fn push_comptime_expression(&mut self, expr_id: ExprId) {
+        self.unevaluated_comptime_expressions.push(expr_id);
+    }
+
+    pub fn take_unevaluated_comptime_expressions(&mut self) -> Vec<ExprId> {
+        std::mem::take(&mut self.unevaluated_comptime_expressions)
+    }
+
     pub fn function_definition_id(&self, function: FuncId) -> DefinitionId {
         self.function_definition_ids[&function]
     }
@@ -919,6 +1359,216 @@ impl NodeInterner {
         self.trait_implementations.get(key).cloned()
     }
 
+    /// Find the implementation of the given trait for `object_type`, returning it along with
+    /// the bindings of the impl's generics needed to match `object_type`.
+    ///
+    /// Generic impls such as `impl<T> Foo for Bar<T>` are instantiated and unified against
+    /// `object_type`, and are only selected if their where clause is also satisfied. Unless
+    /// `trait_generics` is empty, the impl must also give the trait these generics, so that
+    /// `impl From<u16> for u64` doesn't satisfy `u64: From<u8>`.
+    pub fn lookup_trait_implementation(
+        &self,
+        object_type: &Type,
+        trait_id: TraitId,
+        trait_generics: &[Type],
+    ) -> Option<(Shared<TraitImpl>, TypeBindings)> {
+        let key =
+            TraitImplKey { typ: object_type.clone(), trait_id, generics: trait_generics.to_vec() };
+        if let Some(trait_impl) = self.get_trait_implementation(&key) {
+            if trait_impl.borrow().generics.is_empty() {
+                return Some((trait_impl, TypeBindings::new()));
+            }
+        }
+
+        for (key, trait_impl) in &self.trait_implementations {
+            if key.trait_id != trait_id {
+                continue;
+            }
+
+            // The impl's generics may currently be bound if we're within one of its own
+            // methods, so they must be forcibly replaced rather than instantiated normally.
+            let trait_impl_ref = trait_impl.borrow();
+            let bindings: TypeBindings = trait_impl_ref
+                .generics
+                .iter()
+                .map(|(id, var)| (*id, (var.clone(), self.next_type_variable())))
+                .collect();
+            let impl_type = trait_impl_ref.typ.force_substitute(&bindings);
+            let impl_generics = Type::Tuple(vecmap(&trait_impl_ref.trait_generics, |typ| {
+                typ.force_substitute(&bindings)
+            }));
+            let generics = Type::Tuple(trait_generics.to_vec());
+
+            // try_unify may bind some of the object type's variables even when it fails, and a
+            // lookup must leave them as it found them, so they're unbound again afterward.
+            let mut snapshot = object_type.snapshot_bindings();
+            snapshot.extend(generics.snapshot_bindings());
+            let generics_match = trait_generics.is_empty()
+                || (trait_impl_ref.trait_generics.len() == trait_generics.len()
+                    && impl_generics.try_unify(&generics).is_ok());
+            let where_clause_satisfied = generics_match
+                && impl_type.try_unify(object_type).is_ok()
+                && trait_impl_ref.where_clause.iter().all(|constraint| {
+                    let typ = constraint.typ.force_substitute(&bindings).follow_bindings();
+                    let generics =
+                        vecmap(&constraint.trait_generics, |typ| typ.force_substitute(&bindings));
+                    // Constraints on generic types can only be checked once they're instantiated
+                    matches!(typ, Type::NamedGeneric(..) | Type::TypeVariable(..))
+                        || self.implements_trait(&typ, constraint.trait_id, &generics)
+                });
+            snapshot.restore();
+
+            if where_clause_satisfied {
+                return Some((trait_impl.clone(), bindings));
+            }
+        }
+
+        None
+    }
+
+    /// Whether `typ` satisfies the constraint `typ: Trait<trait_generics>`. Besides the impls of
+    /// the trait, `T: Into<U>` is satisfied by an `impl From<T> for U`.
+    pub fn implements_trait(&self, typ: &Type, trait_id: TraitId, trait_generics: &[Type]) -> bool {
+        if self.lookup_trait_implementation(typ, trait_id, trait_generics).is_some() {
+            return true;
+        }
+        match (self.into_trait_method, trait_generics) {
+            (Some(into), [target]) if into.trait_id == trait_id => {
+                // Checking the constraint mustn't bind either type, unlike a conversion
+                let mut snapshot = typ.snapshot_bindings();
+                snapshot.extend(target.snapshot_bindings());
+                let converts = self.lookup_conversion(typ, target).is_some();
+                snapshot.restore();
+                converts
+            }
+            _ => false,
+        }
+    }
+
+    /// Find an existing implementation of the same trait which overlaps with `trait_impl`,
+    /// i.e. one whose type unifies with `trait_impl`'s type for some choice of both impls'
+    /// generics. Where clauses are not taken into account, so `impl<T> Foo for T where T: Bar`
+    /// still overlaps with `impl Foo for u32` even if `u32` does not implement `Bar`.
+    ///
+    /// Impls which both give the trait's generics only overlap if those unify as well, so
+    /// `impl From<u8> for u64` doesn't overlap with `impl From<u16> for u64`.
+    pub fn find_overlapping_trait_impl(&self, trait_impl: &TraitImpl) -> Option<Shared<TraitImpl>> {
+        let instantiate = |trait_impl: &TraitImpl| {
+            let bindings: TypeBindings = trait_impl
+                .generics
+                .iter()
+                .map(|(id, var)| (*id, (var.clone(), self.next_type_variable())))
+                .collect();
+            let generics =
+                vecmap(&trait_impl.trait_generics, |typ| typ.force_substitute(&bindings));
+            (Type::Tuple(generics), trait_impl.typ.force_substitute(&bindings))
+        };
+
+        self.trait_implementations
+            .values()
+            .find(|existing| {
+                let existing = existing.borrow();
+                if existing.trait_id != trait_impl.trait_id {
+                    return false;
+                }
+
+                // Both types are instantiated anew for each check, and any other type variables
+                // they contain are unbound again afterward since try_unify may bind them.
+                let (existing_generics, existing_type) = instantiate(&existing);
+                let (generics, typ) = instantiate(trait_impl);
+                let mut snapshot = existing_type.snapshot_bindings();
+                snapshot.extend(typ.snapshot_bindings());
+                snapshot.extend(existing_generics.snapshot_bindings());
+                snapshot.extend(generics.snapshot_bindings());
+                let generics_overlap = existing.trait_generics.len()
+                    != trait_impl.trait_generics.len()
+                    || trait_impl.trait_generics.is_empty()
+                    || existing_generics.try_unify(&generics).is_ok();
+                let overlaps = generics_overlap && existing_type.try_unify(&typ).is_ok();
+                snapshot.restore();
+                overlaps
+            })
+            .cloned()
+    }
+
+    /// Find the `From` impl implementing `method` if it is `From::from` or `Into::into`, whose
+    /// instantiated type is `function_type`. Unlike other traits, a type may implement `From`
+    /// several times, once for each type it can be converted from.
+    pub fn lookup_from_method(
+        &self,
+        method: TraitMethodId,
+        function_type: &Type,
+    ) -> Option<Conversion> {
+        if self.from_trait_method != Some(method) && self.into_trait_method != Some(method) {
+            return None;
+        }
+        match function_type.follow_bindings() {
+            Type::Function(parameters, return_type, _) if parameters.len() == 1 => {
+                self.lookup_conversion(&parameters[0].follow_bindings(), &return_type)
+            }
+            _ => None,
+        }
+    }
+
+    /// Find the `From` impl converting a value of type `source` into `target`, returning its
+    /// `from` method instantiated for these types. Both types should already be known, and
+    /// None is returned unless exactly one impl can convert between them.
+    pub fn lookup_conversion(&self, source: &Type, target: &Type) -> Option<Conversion> {
+        let method = self.from_trait_method?;
+
+        let mut conversions = self.trait_implementations.values().filter_map(|trait_impl| {
+            let trait_impl = trait_impl.borrow();
+            if trait_impl.trait_id != method.trait_id {
+                return None;
+            }
+
+            let function = *trait_impl.methods.get(method.method_index)?;
+            let (function_type, bindings) = self.function_meta(&function).typ.instantiate(self);
+            let Type::Function(parameters, return_type, _) = &function_type else {
+                return None;
+            };
+            let [parameter] = parameters.as_slice() else {
+                return None;
+            };
+
+            // Until an impl is chosen, any bindings made to the variables of `source` or
+            // `target` while checking it are undone, whether or not it matches.
+            let mut snapshot = source.snapshot_bindings();
+            snapshot.extend(target.snapshot_bindings());
+            let converts = parameter.try_unify(source).is_ok()
+                && return_type.try_unify(target).is_ok()
+                && trait_impl.where_clause.iter().all(|constraint| {
+                    let typ = constraint.typ.substitute(&bindings).follow_bindings();
+                    let generics =
+                        vecmap(&constraint.trait_generics, |typ| typ.substitute(&bindings));
+                    matches!(typ, Type::NamedGeneric(..) | Type::TypeVariable(..))
+                        || self.implements_trait(&typ, constraint.trait_id, &generics)
+                });
+            snapshot.restore();
+
+            converts.then(|| Conversion {
+                function,
+                function_type,
+                bindings,
+                target: target.clone(),
+            })
+        });
+
+        let conversion = conversions.next()?;
+        if conversions.next().is_some() {
+            return None;
+        }
+
+        // The chosen impl's method is unified with the converted types once more, keeping the
+        // bindings this time
+        let Type::Function(parameters, return_type, _) = &conversion.function_type else {
+            unreachable!("`from` is a function")
+        };
+        parameters[0].try_unify(source).ok()?;
+        return_type.try_unify(target).ok()?;
+        Some(conversion)
+    }
+
     pub fn add_trait_implementation(
         &mut self,
         key: &TraitImplKey,
@@ -960,7 +1610,9 @@ impl NodeInterner {
             | Type::Forall(..)
             | Type::NotConstant
             | Type::Constant(..)
+            | Type::InfixExpr(..)
             | Type::TraitAsType(..)
+            | Type::TraitObject(..)
             | Type::Error => false,
         }
     }
@@ -1011,6 +1663,23 @@ impl NodeInterner {
         None
     }
 
+    /// Returns the names of every method defined on the given struct.
+    pub fn struct_method_names(&self, id: StructId) -> impl Iterator<Item = &str> {
+        self.struct_methods
+            .keys()
+            .filter(move |(struct_id, _)| *struct_id == id)
+            .map(|(_, name)| name.as_str())
+    }
+
+    /// Returns the names of every method defined on the given primitive type.
+    pub fn primitive_method_names(&self, typ: &Type) -> impl Iterator<Item = &str> {
+        let key = get_type_method_key(typ);
+        self.primitive_methods
+            .keys()
+            .filter(move |(method_key, _)| Some(*method_key) == key)
+            .map(|(_, name)| name.as_str())
+    }
+
     /// Looks up a given method name on the given primitive type.
     pub fn lookup_primitive_method(&self, typ: &Type, method_name: &str) -> Option<FuncId> {
         get_type_method_key(typ)
@@ -1066,10 +1735,12 @@ fn get_type_method_key(typ: &Type) -> Option<TypeMethodKey> {
         | Type::NamedGeneric(_, _)
         | Type::Forall(_, _)
         | Type::Constant(_)
+        | Type::InfixExpr(..)
         | Type::Error
         | Type::NotConstant
         | Type::Struct(_, _)
         | Type::TraitAsType(_)
+        | Type::TraitObject(_)
         | Type::FmtString(_, _) => None,
     }
 }
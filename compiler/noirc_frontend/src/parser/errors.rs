@@ -1,12 +1,13 @@
 use crate::lexer::errors::LexerErrorKind;
 use crate::lexer::token::Token;
-use crate::Expression;
+use crate::{Expression, Path};
 use small_ord_set::SmallOrdSet;
 use thiserror::Error;
 
 use iter_extended::vecmap;
+use noirc_errors::codes::*;
 use noirc_errors::CustomDiagnostic as Diagnostic;
-use noirc_errors::Span;
+use noirc_errors::{lints, ErrorCode, Span};
 
 use super::labels::ParsingRuleLabel;
 
@@ -26,20 +27,40 @@ pub enum ParserErrorReason {
     EarlyReturn,
     #[error("Patterns aren't allowed in a trait's function declarations")]
     PatternInTraitFunctionParameter,
+    #[error("Only a variable can be bound by a loop over a range")]
+    PatternInRangeLoop,
     #[error("comptime keyword is deprecated")]
     ComptimeDeprecated,
     #[error("{0} are experimental and aren't fully supported yet")]
     ExperimentalFeature(&'static str),
     #[error("Where clauses are allowed only on functions with generic parameters")]
     WhereClauseOnNonGenericFunction,
+    #[error("Where clauses are allowed only on impls with generic parameters")]
+    WhereClauseOnNonGenericImpl,
     #[error(
         "Multiple primary attributes found. Only one function attribute is allowed per function"
     )]
     MultipleFunctionAttributesFound,
     #[error("A function attribute cannot be placed on a struct")]
     NoFunctionAttributesAllowedOnStruct,
-    #[error("Assert statements can only accept string literals")]
+    #[error("A function attribute cannot be placed on a trait")]
+    NoFunctionAttributesAllowedOnTrait,
+    #[error("`derive` can only be placed on a struct")]
+    DeriveOnlyAllowedOnStruct,
+    #[error("`repr` can only be placed on a struct")]
+    ReprOnlyAllowedOnStruct,
+    #[error("`inline` can only be placed on a function")]
+    InlineOnlyAllowedOnFunction,
+    #[error("Only `#[test]` can be placed on a module")]
+    OnlyTestAttributeAllowedOnModule,
+    #[error("Assert statements can only accept string literals or format strings")]
     AssertMessageNotString,
+    #[error("The message of a static assertion must be a string literal")]
+    StaticAssertMessageNotString,
+    #[error("`{0}!` is not a macro, the macros are `std::env!`, `std::include_bytes!` and `std::include_str!`")]
+    UnknownMacro(Path),
+    #[error("An array comprehension can only iterate over a range, such as `0..N`")]
+    ComprehensionOverNonRange,
     #[error("{0}")]
     Lexer(LexerErrorKind),
 }
@@ -99,6 +120,40 @@ impl ParserError {
     pub fn reason(&self) -> Option<&ParserErrorReason> {
         self.reason.as_ref()
     }
+
+    pub fn code(&self) -> ErrorCode {
+        self.reason.as_ref().map_or(E0010, ParserErrorReason::code)
+    }
+}
+
+impl ParserErrorReason {
+    pub fn code(&self) -> ErrorCode {
+        match self {
+            ParserErrorReason::ExpectedFieldName(_)
+            | ParserErrorReason::ExpectedPatternButFoundType(_) => E0011,
+            ParserErrorReason::PatternInTraitFunctionParameter
+            | ParserErrorReason::PatternInRangeLoop => E0026,
+            ParserErrorReason::MissingSeparatingSemi => E0012,
+            ParserErrorReason::ConstrainDeprecated | ParserErrorReason::ComptimeDeprecated => E0013,
+            ParserErrorReason::InvalidArrayLengthExpression(_) => E0014,
+            ParserErrorReason::EarlyReturn => E0015,
+            ParserErrorReason::ExperimentalFeature(_) => E0016,
+            ParserErrorReason::WhereClauseOnNonGenericFunction
+            | ParserErrorReason::WhereClauseOnNonGenericImpl => E0017,
+            ParserErrorReason::MultipleFunctionAttributesFound => E0027,
+            ParserErrorReason::NoFunctionAttributesAllowedOnStruct
+            | ParserErrorReason::NoFunctionAttributesAllowedOnTrait
+            | ParserErrorReason::DeriveOnlyAllowedOnStruct
+            | ParserErrorReason::ReprOnlyAllowedOnStruct
+            | ParserErrorReason::InlineOnlyAllowedOnFunction
+            | ParserErrorReason::OnlyTestAttributeAllowedOnModule => E0018,
+            ParserErrorReason::AssertMessageNotString
+            | ParserErrorReason::StaticAssertMessageNotString => E0019,
+            ParserErrorReason::UnknownMacro(_) => E0020,
+            ParserErrorReason::ComprehensionOverNonRange => E0021,
+            ParserErrorReason::Lexer(error) => error.code(),
+        }
+    }
 }
 
 impl std::fmt::Display for ParserError {
@@ -128,7 +183,7 @@ impl std::fmt::Display for ParserError {
 
 impl From<ParserError> for Diagnostic {
     fn from(error: ParserError) -> Diagnostic {
-        match &error.reason {
+        let diagnostic = match &error.reason {
             Some(reason) => {
                 match reason {
                     ParserErrorReason::ConstrainDeprecated => Diagnostic::simple_error(
@@ -140,7 +195,8 @@ impl From<ParserError> for Diagnostic {
                         "Use of deprecated keyword 'comptime'".into(),
                         "The 'comptime' keyword has been deprecated. It can be removed without affecting your program".into(),
                         error.span,
-                    ),
+                    )
+                    .with_lint(lints::DEPRECATED),
                     ParserErrorReason::ExperimentalFeature(_) => Diagnostic::simple_warning(
                         reason.to_string(),
                         "".into(),
@@ -159,7 +215,8 @@ impl From<ParserError> for Diagnostic {
                 let primary = error.to_string();
                 Diagnostic::simple_error(primary, String::new(), error.span)
             }
-        }
+        };
+        diagnostic.with_code(error.code())
     }
 }
 
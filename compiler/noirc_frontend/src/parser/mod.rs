@@ -10,15 +10,17 @@ mod errors;
 mod labels;
 #[allow(clippy::module_inception)]
 mod parser;
+pub mod preparsed;
 
 use std::sync::atomic::{AtomicU32, Ordering};
 
-use crate::token::{Keyword, Token};
+use crate::token::{Keyword, SecondaryAttribute, Token};
 use crate::{ast::ImportStatement, Expression, NoirStruct};
 use crate::{
     BlockExpression, ExpressionKind, ForLoopStatement, Ident, IndexExpression, LetStatement,
     MethodCallExpression, NoirFunction, NoirTrait, NoirTraitImpl, NoirTypeAlias, Path, PathKind,
-    Pattern, Recoverable, Statement, StatementKind, TypeImpl, UnresolvedType, UseTree,
+    Pattern, Recoverable, Statement, StatementKind, StaticAssert, TypeImpl, UnresolvedType,
+    UseTree,
 };
 
 use acvm::FieldElement;
@@ -28,6 +30,7 @@ pub use errors::ParserError;
 pub use errors::ParserErrorReason;
 use noirc_errors::Span;
 pub use parser::parse_program;
+use serde::{Deserialize, Serialize};
 
 /// Counter used to generate unique names when desugaring
 /// code in the parser requires the creation of fresh variables.
@@ -37,7 +40,7 @@ static UNIQUE_NAME_COUNTER: AtomicU32 = AtomicU32::new(0);
 #[derive(Debug, Clone)]
 pub(crate) enum TopLevelStatement {
     Function(NoirFunction),
-    Module(Ident),
+    Module(ModuleDeclaration),
     Import(UseTree),
     Struct(NoirStruct),
     Trait(NoirTrait),
@@ -46,6 +49,8 @@ pub(crate) enum TopLevelStatement {
     TypeAlias(NoirTypeAlias),
     SubModule(ParsedSubModule),
     Global(LetStatement),
+    StaticAssert(StaticAssert),
+    InnerAttribute(SecondaryAttribute),
     Error,
 }
 
@@ -227,12 +232,16 @@ pub struct SortedModule {
     pub impls: Vec<TypeImpl>,
     pub type_aliases: Vec<NoirTypeAlias>,
     pub globals: Vec<LetStatement>,
+    pub static_asserts: Vec<StaticAssert>,
 
     /// Module declarations like `mod foo;`
-    pub module_decls: Vec<Ident>,
+    pub module_decls: Vec<ModuleDeclaration>,
 
     /// Full submodules as in `mod foo { ... definitions ... }`
     pub submodules: Vec<SortedSubModule>,
+
+    /// Attributes applying to the whole module, written as `#![...]`
+    pub inner_attributes: Vec<SecondaryAttribute>,
 }
 
 impl std::fmt::Display for SortedModule {
@@ -249,6 +258,10 @@ impl std::fmt::Display for SortedModule {
             write!(f, "{global_const}")?;
         }
 
+        for static_assert in &self.static_asserts {
+            writeln!(f, "{static_assert};")?;
+        }
+
         for type_ in &self.types {
             write!(f, "{type_}")?;
         }
@@ -274,7 +287,7 @@ impl std::fmt::Display for SortedModule {
 }
 
 /// A ParsedModule contains an entire Ast for one file.
-#[derive(Clone, Debug, Default)]
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
 pub struct ParsedModule {
     pub items: Vec<Item>,
 }
@@ -285,7 +298,7 @@ impl ParsedModule {
 
         for item in self.items {
             match item.kind {
-                ItemKind::Import(import) => module.push_import(import),
+                ItemKind::Import(import) => module.push_import(import, item.span),
                 ItemKind::Function(func) => module.push_function(func),
                 ItemKind::Struct(typ) => module.push_type(typ),
                 ItemKind::Trait(noir_trait) => module.push_trait(noir_trait),
@@ -293,8 +306,10 @@ impl ParsedModule {
                 ItemKind::Impl(r#impl) => module.push_impl(r#impl),
                 ItemKind::TypeAlias(type_alias) => module.push_type_alias(type_alias),
                 ItemKind::Global(global) => module.push_global(global),
+                ItemKind::StaticAssert(assertion) => module.push_static_assert(assertion),
                 ItemKind::ModuleDecl(mod_name) => module.push_module_decl(mod_name),
                 ItemKind::Submodules(submodule) => module.push_submodule(submodule.into_sorted()),
+                ItemKind::InnerAttribute(attribute) => module.inner_attributes.push(attribute),
             }
         }
 
@@ -302,13 +317,13 @@ impl ParsedModule {
     }
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct Item {
     pub kind: ItemKind,
     pub span: Span,
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub enum ItemKind {
     Import(UseTree),
     Function(NoirFunction),
@@ -318,17 +333,40 @@ pub enum ItemKind {
     Impl(TypeImpl),
     TypeAlias(NoirTypeAlias),
     Global(LetStatement),
-    ModuleDecl(Ident),
+    StaticAssert(StaticAssert),
+    ModuleDecl(ModuleDeclaration),
     Submodules(ParsedSubModule),
+    InnerAttribute(SecondaryAttribute),
+}
+
+/// A module declared via `mod name;`, whose contents are in another file
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ModuleDeclaration {
+    pub name: Ident,
+    /// Whether the module is marked with `#[test]`, so is only compiled when running tests
+    pub is_test_only: bool,
+}
+
+impl std::fmt::Display for ModuleDeclaration {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.is_test_only {
+            write!(f, "#[test] ")?;
+        }
+        write!(f, "mod {}", self.name)
+    }
 }
 
 /// A submodule defined via `mod name { contents }` in some larger file.
 /// These submodules always share the same file as some larger ParsedModule
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct ParsedSubModule {
     pub name: Ident,
     pub contents: ParsedModule,
     pub is_contract: bool,
+    /// Whether the module is marked with `#[test]`, so is only compiled when running tests
+    pub is_test_only: bool,
+    /// The span of the whole `mod name { contents }` item
+    pub span: Span,
 }
 
 impl ParsedSubModule {
@@ -337,12 +375,17 @@ impl ParsedSubModule {
             name: self.name,
             contents: self.contents.into_sorted(),
             is_contract: self.is_contract,
+            is_test_only: self.is_test_only,
+            span: self.span,
         }
     }
 }
 
 impl std::fmt::Display for SortedSubModule {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.is_test_only {
+            write!(f, "#[test] ")?;
+        }
         write!(f, "mod {} {{", self.name)?;
 
         for line in self.contents.to_string().lines() {
@@ -357,6 +400,8 @@ pub struct SortedSubModule {
     pub name: Ident,
     pub contents: SortedModule,
     pub is_contract: bool,
+    pub is_test_only: bool,
+    pub span: Span,
 }
 
 impl SortedModule {
@@ -384,12 +429,16 @@ impl SortedModule {
         self.type_aliases.push(type_alias);
     }
 
-    fn push_import(&mut self, import_stmt: UseTree) {
-        self.imports.extend(import_stmt.desugar(None));
+    fn push_import(&mut self, import_stmt: UseTree, span: Span) {
+        let mut imports = import_stmt.desugar(None);
+        if let [import] = imports.as_mut_slice() {
+            import.statement_span = Some(span);
+        }
+        self.imports.extend(imports);
     }
 
-    fn push_module_decl(&mut self, mod_name: Ident) {
-        self.module_decls.push(mod_name);
+    fn push_module_decl(&mut self, module_decl: ModuleDeclaration) {
+        self.module_decls.push(module_decl);
     }
 
     fn push_submodule(&mut self, submodule: SortedSubModule) {
@@ -399,6 +448,10 @@ impl SortedModule {
     fn push_global(&mut self, global: LetStatement) {
         self.globals.push(global);
     }
+
+    fn push_static_assert(&mut self, assertion: StaticAssert) {
+        self.static_asserts.push(assertion);
+    }
 }
 
 #[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd)]
@@ -490,13 +543,27 @@ impl ForRange {
     /// {
     ///     let fresh1 = array;
     ///     for fresh2 in 0 .. std::array::len(fresh1) {
-    ///         let elem = fresh1[fresh2];
+    ///         let e = fresh1[fresh2];
     ///         ...
     ///     }
     /// }
-    fn into_for(self, identifier: Ident, block: Expression, for_loop_span: Span) -> StatementKind {
+    ///
+    /// Loops over arrays may bind any pattern, while loops over a range may only bind a
+    /// variable.
+    fn into_for(
+        self,
+        pattern: Pattern,
+        block: Expression,
+        for_loop_span: Span,
+        emit: &mut dyn FnMut(ParserError),
+    ) -> StatementKind {
         match self {
             ForRange::Range(start_range, end_range) => {
+                let Pattern::Identifier(identifier) = pattern else {
+                    let reason = ParserErrorReason::PatternInRangeLoop;
+                    emit(ParserError::with_reason(reason, pattern.span()));
+                    return StatementKind::Error;
+                };
                 StatementKind::For(ForLoopStatement { identifier, start_range, end_range, block })
             }
             ForRange::Array(array) => {
@@ -522,7 +589,7 @@ impl ForRange {
                 // array.len()
                 let segments = vec![array_ident];
                 let array_ident =
-                    ExpressionKind::Variable(Path { segments, kind: PathKind::Plain });
+                    ExpressionKind::Variable(Path { segments, kind: PathKind::Plain }, None);
 
                 let end_range = ExpressionKind::MethodCall(Box::new(MethodCallExpression {
                     object: Expression::new(array_ident.clone(), array_span),
@@ -538,7 +605,7 @@ impl ForRange {
                 // array[i]
                 let segments = vec![Ident::new(index_name, array_span)];
                 let index_ident =
-                    ExpressionKind::Variable(Path { segments, kind: PathKind::Plain });
+                    ExpressionKind::Variable(Path { segments, kind: PathKind::Plain }, None);
 
                 let loop_element = ExpressionKind::Index(Box::new(IndexExpression {
                     collection: Expression::new(array_ident, array_span),
@@ -548,7 +615,7 @@ impl ForRange {
                 // let elem = array[i];
                 let let_elem = Statement {
                     kind: StatementKind::Let(LetStatement {
-                        pattern: Pattern::Identifier(identifier),
+                        pattern,
                         r#type: UnresolvedType::unspecified(),
                         expression: Expression::new(loop_element, array_span),
                     }),
@@ -591,6 +658,10 @@ impl std::fmt::Display for TopLevelStatement {
             TopLevelStatement::TypeAlias(t) => t.fmt(f),
             TopLevelStatement::SubModule(s) => s.fmt(f),
             TopLevelStatement::Global(c) => c.fmt(f),
+            TopLevelStatement::StaticAssert(a) => a.fmt(f),
+            TopLevelStatement::InnerAttribute(a) => {
+                write!(f, "{}", Token::InnerAttribute(a.clone()))
+            }
             TopLevelStatement::Error => write!(f, "error"),
         }
     }
@@ -26,8 +26,8 @@
 use super::{
     foldl_with_span, labels::ParsingRuleLabel, parameter_name_recovery, parameter_recovery,
     parenthesized, then_commit, then_commit_ignore, top_level_statement_recovery, ExprParser,
-    ForRange, NoirParser, ParsedModule, ParsedSubModule, ParserError, ParserErrorReason,
-    Precedence, TopLevelStatement,
+    ForRange, ModuleDeclaration, NoirParser, ParsedModule, ParsedSubModule, ParserError,
+    ParserErrorReason, Precedence, TopLevelStatement,
 };
 use super::{spanned, Item, ItemKind};
 use crate::ast::{
@@ -35,12 +35,16 @@ use crate::ast::{
 };
 use crate::lexer::Lexer;
 use crate::parser::{force, ignore_then_commit, statement_recovery};
-use crate::token::{Attribute, Attributes, Keyword, SecondaryAttribute, Token, TokenKind};
+use crate::token::{
+    Attribute, Attributes, FunctionAttribute, Keyword, SecondaryAttribute, TestScope, Token,
+    TokenKind,
+};
 use crate::{
-    BinaryOp, BinaryOpKind, BlockExpression, ConstrainStatement, Distinctness, FunctionDefinition,
-    FunctionReturnType, Ident, IfExpression, InfixExpression, LValue, Lambda, Literal,
-    NoirFunction, NoirStruct, NoirTrait, NoirTraitImpl, NoirTypeAlias, Path, PathKind, Pattern,
-    Recoverable, Statement, TraitBound, TraitImplItem, TraitItem, TypeImpl, UnaryOp,
+    ArrayLiteral, AssertMessage, BinaryOp, BinaryOpKind, BlockExpression, ConstrainKind,
+    ConstrainStatement, Distinctness, FunctionDefinition, FunctionReturnType, Ident, IfExpression,
+    InfixExpression, LValue, Lambda, Literal, NoirFunction, NoirStruct, NoirTrait, NoirTraitImpl,
+    NoirTypeAlias, Path, PathKind, Pattern, RangeExpression, Recoverable, Statement, StaticAssert,
+    TraitBound, TraitImplItem, TraitItem, TypeImpl, UnaryOp, UnresolvedNumericConstraint,
     UnresolvedTraitConstraint, UnresolvedTypeExpression, UseTree, UseTreeKind, Visibility,
 };
 
@@ -89,6 +93,8 @@ fn module() -> impl NoirParser<ParsedModule> {
                     TopLevelStatement::TypeAlias(t) => push_item(ItemKind::TypeAlias(t)),
                     TopLevelStatement::SubModule(s) => push_item(ItemKind::Submodules(s)),
                     TopLevelStatement::Global(c) => push_item(ItemKind::Global(c)),
+                    TopLevelStatement::StaticAssert(a) => push_item(ItemKind::StaticAssert(a)),
+                    TopLevelStatement::InnerAttribute(a) => push_item(ItemKind::InnerAttribute(a)),
                     TopLevelStatement::Error => (),
                 }
                 program
@@ -104,6 +110,8 @@ fn module() -> impl NoirParser<ParsedModule> {
 ///                    | module_declaration
 ///                    | use_statement
 ///                    | global_declaration
+///                    | static_assertion
+///                    | inner_attribute
 fn top_level_statement(
     module_parser: impl NoirParser<ParsedModule>,
 ) -> impl NoirParser<TopLevelStatement> {
@@ -119,31 +127,74 @@ fn top_level_statement(
         module_declaration().then_ignore(force(just(Token::Semicolon))),
         use_statement().then_ignore(force(just(Token::Semicolon))),
         global_declaration().then_ignore(force(just(Token::Semicolon))),
+        static_assertion().then_ignore(force(just(Token::Semicolon))),
+        inner_attribute().map(TopLevelStatement::InnerAttribute),
     ))
     .recover_via(top_level_statement_recovery())
 }
 
-/// global_declaration: 'global' ident global_type_annotation '=' literal
+/// global_declaration: 'global' 'mut'? ident global_type_annotation '=' expression
 fn global_declaration() -> impl NoirParser<TopLevelStatement> {
+    let mutable_global = keyword(Keyword::Mut)
+        .ignore_then(ident())
+        .map_with_span(|name, span| Pattern::Mutable(Box::new(Pattern::Identifier(name)), span));
+
     let p = ignore_then_commit(
         keyword(Keyword::Global).labelled(ParsingRuleLabel::Global),
-        ident().map(Pattern::Identifier),
+        mutable_global.or(ident().map(Pattern::Identifier)),
     );
     let p = then_commit(p, optional_type_annotation());
     let p = then_commit_ignore(p, just(Token::Assign));
-    let p = then_commit(p, literal_or_collection(expression()).map_with_span(Expression::new));
+    let p = then_commit(p, expression());
     p.map(LetStatement::new_let).map(TopLevelStatement::Global)
 }
 
-/// submodule: 'mod' ident '{' module '}'
+/// static_assertion: 'static_assert' '(' expression ',' string ')'
+///
+/// `static_assert` isn't a keyword, so that `std::static_assert` can still be called within
+/// functions, but an item starting with it can only be a static assertion.
+fn static_assertion() -> impl NoirParser<TopLevelStatement> {
+    let name = filter_map(|span, found: Token| match found {
+        Token::Ident(ref word) if word == "static_assert" => Ok(()),
+        _ => Err(ParserError::empty(found, span)),
+    });
+    let argument_parser =
+        expression().separated_by(just(Token::Comma)).allow_trailing().at_least(2).at_most(2);
+
+    ignore_then_commit(name, parenthesized(argument_parser)).validate(
+        |arguments: Vec<Expression>, span, emit| {
+            let condition = arguments.get(0).cloned().unwrap_or_else(|| Expression::error(span));
+            let message = match arguments.get(1).map(|message| &message.kind) {
+                Some(ExpressionKind::Literal(Literal::Str(message))) => message.clone(),
+                _ => {
+                    let reason = ParserErrorReason::StaticAssertMessageNotString;
+                    emit(ParserError::with_reason(reason, span));
+                    String::new()
+                }
+            };
+            TopLevelStatement::StaticAssert(StaticAssert { condition, message, span })
+        },
+    )
+}
+
+/// submodule: attributes 'mod' ident '{' module '}'
 fn submodule(module_parser: impl NoirParser<ParsedModule>) -> impl NoirParser<TopLevelStatement> {
-    keyword(Keyword::Mod)
-        .ignore_then(ident())
+    attributes()
+        .or_not()
+        .then_ignore(keyword(Keyword::Mod))
+        .then(ident())
         .then_ignore(just(Token::LeftBrace))
         .then(module_parser)
         .then_ignore(just(Token::RightBrace))
-        .map(|(name, contents)| {
-            TopLevelStatement::SubModule(ParsedSubModule { name, contents, is_contract: false })
+        .validate(|((attributes, name), contents), span, emit| {
+            let is_test_only = validate_module_attributes(attributes, span, emit);
+            TopLevelStatement::SubModule(ParsedSubModule {
+                name,
+                contents,
+                is_contract: false,
+                is_test_only,
+                span,
+            })
         })
 }
 
@@ -154,8 +205,14 @@ fn contract(module_parser: impl NoirParser<ParsedModule>) -> impl NoirParser<Top
         .then_ignore(just(Token::LeftBrace))
         .then(module_parser)
         .then_ignore(just(Token::RightBrace))
-        .map(|(name, contents)| {
-            TopLevelStatement::SubModule(ParsedSubModule { name, contents, is_contract: true })
+        .map_with_span(|(name, contents), span| {
+            TopLevelStatement::SubModule(ParsedSubModule {
+                name,
+                contents,
+                is_contract: true,
+                is_test_only: false,
+                span,
+            })
         })
 }
 
@@ -170,14 +227,21 @@ fn function_definition(allow_self: bool) -> impl NoirParser<NoirFunction> {
         .then(generics())
         .then(parenthesized(function_parameters(allow_self)))
         .then(function_return_type())
-        .then(where_clause())
+        .then(function_where_clause())
         .then(spanned(block(fresh_statement())))
         .validate(|(((args, ret), where_clause), (body, body_span)), span, emit| {
             let ((((attributes, modifiers), name), generics), parameters) = args;
+            let (where_clause, numeric_constraints) = where_clause;
 
             // Validate collected attributes, filtering them into function and secondary variants
             let attrs = validate_attributes(attributes, span, emit);
             validate_where_clause(&generics, &where_clause, span, emit);
+            if !numeric_constraints.is_empty() && generics.is_empty() {
+                emit(ParserError::with_reason(
+                    ParserErrorReason::WhereClauseOnNonGenericFunction,
+                    span,
+                ));
+            }
             FunctionDefinition {
                 span: body_span,
                 name,
@@ -186,10 +250,13 @@ fn function_definition(allow_self: bool) -> impl NoirParser<NoirFunction> {
                 is_open: modifiers.1,
                 is_internal: modifiers.2,
                 is_public: modifiers.3,
+                is_const: modifiers.4,
+                is_comptime: modifiers.5,
                 generics,
                 parameters,
                 body,
                 where_clause,
+                numeric_constraints,
                 return_type: ret.1,
                 return_visibility: ret.0 .1,
                 return_distinctness: ret.0 .0,
@@ -198,17 +265,26 @@ fn function_definition(allow_self: bool) -> impl NoirParser<NoirFunction> {
         })
 }
 
-/// function_modifiers: 'unconstrained'? 'open'? 'internal'?
+/// function_modifiers: 'unconstrained'? 'pub'? 'const'? 'comptime'? 'open'? 'internal'?
 ///
-/// returns (is_unconstrained, is_open, is_internal) for whether each keyword was present
-fn function_modifiers() -> impl NoirParser<(bool, bool, bool, bool)> {
+/// returns (is_unconstrained, is_open, is_internal, is_public, is_const, is_comptime) for whether each keyword was present
+fn function_modifiers() -> impl NoirParser<(bool, bool, bool, bool, bool, bool)> {
     keyword(Keyword::Unconstrained)
         .or_not()
         .then(keyword(Keyword::Pub).or_not())
+        .then(keyword(Keyword::Const).or_not())
+        .then(keyword(Keyword::CompTime).or_not())
         .then(keyword(Keyword::Open).or_not())
         .then(keyword(Keyword::Internal).or_not())
-        .map(|(((unconstrained, public), open), internal)| {
-            (unconstrained.is_some(), open.is_some(), internal.is_some(), public.is_some())
+        .map(|(((((unconstrained, public), is_const), comptime), open), internal)| {
+            (
+                unconstrained.is_some(),
+                open.is_some(),
+                internal.is_some(),
+                public.is_some(),
+                is_const.is_some(),
+                comptime.is_some(),
+            )
         })
 }
 
@@ -299,6 +375,13 @@ fn attributes() -> impl NoirParser<Vec<Attribute>> {
     attribute().repeated()
 }
 
+fn inner_attribute() -> impl NoirParser<SecondaryAttribute> {
+    token_kind(TokenKind::InnerAttribute).map(|token| match token {
+        Token::InnerAttribute(attribute) => attribute,
+        _ => unreachable!(),
+    })
+}
+
 fn struct_fields() -> impl NoirParser<Vec<(Ident, UnresolvedType)>> {
     ident()
         .then_ignore(just(Token::Colon))
@@ -382,17 +465,27 @@ fn self_parameter() -> impl NoirParser<(Pattern, UnresolvedType, Visibility)> {
 }
 
 fn trait_definition() -> impl NoirParser<TopLevelStatement> {
-    keyword(Keyword::Trait)
-        .ignore_then(ident())
+    attributes()
+        .or_not()
+        .then_ignore(keyword(Keyword::Trait))
+        .then(ident())
         .then(generics())
         .then(where_clause())
         .then_ignore(just(Token::LeftBrace))
         .then(trait_body())
         .then_ignore(just(Token::RightBrace))
-        .validate(|(((name, generics), where_clause), items), span, emit| {
+        .validate(|((((raw_attributes, name), generics), where_clause), items), span, emit| {
+            let attributes = validate_trait_attributes(raw_attributes, span, emit);
             validate_where_clause(&generics, &where_clause, span, emit);
             emit(ParserError::with_reason(ParserErrorReason::ExperimentalFeature("Traits"), span));
-            TopLevelStatement::Trait(NoirTrait { name, generics, where_clause, span, items })
+            TopLevelStatement::Trait(NoirTrait {
+                name,
+                attributes,
+                generics,
+                where_clause,
+                span,
+                items,
+            })
         })
 }
 
@@ -462,6 +555,12 @@ fn validate_attributes(
                 }
                 primary = Some(attr);
             }
+            Attribute::Secondary(SecondaryAttribute::Derive(_)) => {
+                emit(ParserError::with_reason(ParserErrorReason::DeriveOnlyAllowedOnStruct, span));
+            }
+            Attribute::Secondary(SecondaryAttribute::Repr(_)) => {
+                emit(ParserError::with_reason(ParserErrorReason::ReprOnlyAllowedOnStruct, span));
+            }
             Attribute::Secondary(attr) => secondary.push(attr),
         }
     }
@@ -485,6 +584,12 @@ fn validate_struct_attributes(
                     span,
                 ));
             }
+            Attribute::Secondary(SecondaryAttribute::Inline(_)) => {
+                emit(ParserError::with_reason(
+                    ParserErrorReason::InlineOnlyAllowedOnFunction,
+                    span,
+                ));
+            }
             Attribute::Secondary(attr) => struct_attributes.push(attr),
         }
     }
@@ -492,6 +597,60 @@ fn validate_struct_attributes(
     struct_attributes
 }
 
+/// Returns whether a module is marked with `#[test]`, which is the only attribute allowed on modules
+fn validate_module_attributes(
+    attributes: Option<Vec<Attribute>>,
+    span: Span,
+    emit: &mut dyn FnMut(ParserError),
+) -> bool {
+    let mut is_test_only = false;
+    for attribute in attributes.unwrap_or_default() {
+        match attribute {
+            Attribute::Function(FunctionAttribute::Test(TestScope::None)) => is_test_only = true,
+            _ => emit(ParserError::with_reason(
+                ParserErrorReason::OnlyTestAttributeAllowedOnModule,
+                span,
+            )),
+        }
+    }
+    is_test_only
+}
+
+fn validate_trait_attributes(
+    attributes: Option<Vec<Attribute>>,
+    span: Span,
+    emit: &mut dyn FnMut(ParserError),
+) -> Vec<SecondaryAttribute> {
+    let attrs = attributes.unwrap_or_default();
+    let mut trait_attributes = vec![];
+
+    for attribute in attrs {
+        match attribute {
+            Attribute::Function(..) => {
+                emit(ParserError::with_reason(
+                    ParserErrorReason::NoFunctionAttributesAllowedOnTrait,
+                    span,
+                ));
+            }
+            Attribute::Secondary(SecondaryAttribute::Derive(_)) => {
+                emit(ParserError::with_reason(ParserErrorReason::DeriveOnlyAllowedOnStruct, span));
+            }
+            Attribute::Secondary(SecondaryAttribute::Repr(_)) => {
+                emit(ParserError::with_reason(ParserErrorReason::ReprOnlyAllowedOnStruct, span));
+            }
+            Attribute::Secondary(SecondaryAttribute::Inline(_)) => {
+                emit(ParserError::with_reason(
+                    ParserErrorReason::InlineOnlyAllowedOnFunction,
+                    span,
+                ));
+            }
+            Attribute::Secondary(attr) => trait_attributes.push(attr),
+        }
+    }
+
+    trait_attributes
+}
+
 fn validate_where_clause(
     generics: &Vec<Ident>,
     where_clause: &Vec<UnresolvedTraitConstraint>,
@@ -503,6 +662,17 @@ fn validate_where_clause(
     }
 }
 
+fn validate_impl_where_clause(
+    generics: &[Ident],
+    where_clause: &[UnresolvedTraitConstraint],
+    span: Span,
+    emit: &mut dyn FnMut(ParserError),
+) {
+    if !where_clause.is_empty() && generics.is_empty() {
+        emit(ParserError::with_reason(ParserErrorReason::WhereClauseOnNonGenericImpl, span));
+    }
+}
+
 /// Function declaration parameters differ from other parameters in that parameter
 /// patterns are not allowed in declarations. All parameters must be identifiers.
 fn function_declaration_parameters() -> impl NoirParser<Vec<(Ident, UnresolvedType)>> {
@@ -543,16 +713,24 @@ fn trait_type_declaration() -> impl NoirParser<TraitItem> {
 
 /// Parses a non-trait implementation, adding a set of methods to a type.
 ///
-/// implementation: 'impl' generics type '{' function_definition ... '}'
+/// implementation: 'impl' generics type where_clause '{' function_definition ... '}'
 fn implementation() -> impl NoirParser<TopLevelStatement> {
     keyword(Keyword::Impl)
         .ignore_then(generics())
         .then(parse_type().map_with_span(|typ, span| (typ, span)))
+        .then(where_clause())
         .then_ignore(just(Token::LeftBrace))
         .then(function_definition(true).repeated())
         .then_ignore(just(Token::RightBrace))
-        .map(|((generics, (object_type, type_span)), methods)| {
-            TopLevelStatement::Impl(TypeImpl { generics, object_type, type_span, methods })
+        .validate(|(((generics, (object_type, type_span)), where_clause), methods), span, emit| {
+            validate_impl_where_clause(&generics, &where_clause, span, emit);
+            TopLevelStatement::Impl(TypeImpl {
+                generics,
+                object_type,
+                type_span,
+                where_clause,
+                methods,
+            })
         })
 }
 
@@ -560,7 +738,7 @@ fn implementation() -> impl NoirParser<TopLevelStatement> {
 /// This has a similar syntax to `implementation`, but the `for type` clause is required,
 /// and an optional `where` clause is also useable.
 ///
-/// trait_implementation: 'impl' generics ident generic_args for type '{' trait_implementation_body '}'
+/// trait_implementation: 'impl' generics ident generic_args for type where_clause '{' trait_implementation_body '}'
 fn trait_implementation() -> impl NoirParser<TopLevelStatement> {
     keyword(Keyword::Impl)
         .ignore_then(generics())
@@ -577,6 +755,7 @@ fn trait_implementation() -> impl NoirParser<TopLevelStatement> {
             let (((impl_generics, trait_name), trait_generics), object_type) = other_args;
 
             emit(ParserError::with_reason(ParserErrorReason::ExperimentalFeature("Traits"), span));
+            validate_impl_where_clause(&impl_generics, &where_clause, span, emit);
             TopLevelStatement::TraitImpl(NoirTraitImpl {
                 impl_generics,
                 trait_name,
@@ -601,37 +780,93 @@ fn trait_implementation_body() -> impl NoirParser<Vec<TraitImplItem>> {
     function.or(alias).repeated()
 }
 
-fn where_clause() -> impl NoirParser<Vec<UnresolvedTraitConstraint>> {
-    struct MultiTraitConstraint {
-        typ: UnresolvedType,
-        trait_bounds: Vec<TraitBound>,
+/// A type along with one or more trait bounds on it, such as `T: Eq + Default`
+struct MultiTraitConstraint {
+    typ: UnresolvedType,
+    trait_bounds: Vec<TraitBound>,
+}
+
+impl MultiTraitConstraint {
+    fn flatten(constraints: Vec<MultiTraitConstraint>) -> Vec<UnresolvedTraitConstraint> {
+        let mut result: Vec<UnresolvedTraitConstraint> = Vec::new();
+        for constraint in constraints {
+            for bound in constraint.trait_bounds {
+                result.push(UnresolvedTraitConstraint {
+                    typ: constraint.typ.clone(),
+                    trait_bound: bound,
+                });
+            }
+        }
+        result
     }
+}
 
-    let constraints = parse_type().then_ignore(just(Token::Colon)).then(trait_bounds()).validate(
+fn multi_trait_constraint() -> impl NoirParser<MultiTraitConstraint> {
+    parse_type().then_ignore(just(Token::Colon)).then(trait_bounds()).validate(
         |(typ, trait_bounds), span, emit| {
             emit(ParserError::with_reason(ParserErrorReason::ExperimentalFeature("Traits"), span));
             MultiTraitConstraint { typ, trait_bounds }
         },
-    );
+    )
+}
 
+fn where_clause() -> impl NoirParser<Vec<UnresolvedTraitConstraint>> {
     keyword(Keyword::Where)
-        .ignore_then(constraints.separated_by(just(Token::Comma)))
+        .ignore_then(multi_trait_constraint().separated_by(just(Token::Comma)))
         .or_not()
         .map(|option| option.unwrap_or_default())
-        .map(|x: Vec<MultiTraitConstraint>| {
-            let mut result: Vec<UnresolvedTraitConstraint> = Vec::new();
-            for constraint in x {
-                for bound in constraint.trait_bounds {
-                    result.push(UnresolvedTraitConstraint {
-                        typ: constraint.typ.clone(),
-                        trait_bound: bound,
-                    });
+        .map(MultiTraitConstraint::flatten)
+}
+
+/// function_where_clause: 'where' (trait_constraint | numeric_constraint) (',' ...)*
+///
+/// Unlike other where clauses, a function's where clause may also bound its numeric generics,
+/// e.g. `where N <= 32`.
+fn function_where_clause(
+) -> impl NoirParser<(Vec<UnresolvedTraitConstraint>, Vec<UnresolvedNumericConstraint>)> {
+    enum Constraint {
+        Trait(MultiTraitConstraint),
+        Numeric(UnresolvedNumericConstraint),
+    }
+
+    let constraint = multi_trait_constraint()
+        .map(Constraint::Trait)
+        .or(numeric_constraint().map(Constraint::Numeric));
+
+    keyword(Keyword::Where)
+        .ignore_then(constraint.separated_by(just(Token::Comma)))
+        .or_not()
+        .map(|option| option.unwrap_or_default())
+        .map(|constraints| {
+            let mut trait_constraints = Vec::new();
+            let mut numeric_constraints = Vec::new();
+            for constraint in constraints {
+                match constraint {
+                    Constraint::Trait(constraint) => trait_constraints.push(constraint),
+                    Constraint::Numeric(constraint) => numeric_constraints.push(constraint),
                 }
             }
-            result
+            (MultiTraitConstraint::flatten(trait_constraints), numeric_constraints)
         })
 }
 
+/// numeric_constraint: type_expression comparison_operator type_expression
+fn numeric_constraint() -> impl NoirParser<UnresolvedNumericConstraint> {
+    let comparison = one_of([
+        Token::Less,
+        Token::LessEqual,
+        Token::Greater,
+        Token::GreaterEqual,
+        Token::Equal,
+        Token::NotEqual,
+    ])
+    .map(|token| token.try_into_binary_op(Span::default()).unwrap().contents);
+
+    type_expression().then(comparison).then(type_expression()).map_with_span(
+        |((lhs, comparison), rhs), span| UnresolvedNumericConstraint { lhs, comparison, rhs, span },
+    )
+}
+
 fn trait_bounds() -> impl NoirParser<Vec<TraitBound>> {
     trait_bound().separated_by(just(Token::Plus)).at_least(1).allow_trailing()
 }
@@ -689,8 +924,14 @@ fn optional_type_annotation<'a>() -> impl NoirParser<UnresolvedType> + 'a {
         .map(|r#type| r#type.unwrap_or_else(UnresolvedType::unspecified))
 }
 
+/// module_declaration: attributes 'mod' ident
 fn module_declaration() -> impl NoirParser<TopLevelStatement> {
-    keyword(Keyword::Mod).ignore_then(ident()).map(TopLevelStatement::Module)
+    attributes().or_not().then_ignore(keyword(Keyword::Mod)).then(ident()).validate(
+        |(attributes, name), span, emit| {
+            let is_test_only = validate_module_attributes(attributes, span, emit);
+            TopLevelStatement::Module(ModuleDeclaration { name, is_test_only })
+        },
+    )
 }
 
 fn use_statement() -> impl NoirParser<TopLevelStatement> {
@@ -781,7 +1022,9 @@ where
             assertion_eq(expr_parser.clone()),
             declaration(expr_parser.clone()),
             assignment(expr_parser.clone()),
-            for_loop(expr_no_constructors, statement),
+            for_loop(expr_no_constructors, statement.clone()),
+            loop_statement(statement),
+            break_statement(),
             return_statement(expr_parser.clone()),
             expr_parser.map(StatementKind::Expression),
         ))
@@ -800,7 +1043,7 @@ where
         keyword(Keyword::Constrain).labelled(ParsingRuleLabel::Statement),
         expr_parser,
     )
-    .map(|expr| StatementKind::Constrain(ConstrainStatement(expr, None)))
+    .map(|expr| StatementKind::Constrain(ConstrainStatement(expr, None, ConstrainKind::Constrain)))
     .validate(|expr, span, emit| {
         emit(ParserError::with_reason(ParserErrorReason::ConstrainDeprecated, span));
         expr
@@ -818,17 +1061,8 @@ where
         .labelled(ParsingRuleLabel::Statement)
         .validate(|expressions, span, emit| {
             let condition = expressions.get(0).unwrap_or(&Expression::error(span)).clone();
-            let mut message_str = None;
-
-            if let Some(message) = expressions.get(1) {
-                if let ExpressionKind::Literal(Literal::Str(message)) = &message.kind {
-                    message_str = Some(message.clone());
-                } else {
-                    emit(ParserError::with_reason(ParserErrorReason::AssertMessageNotString, span));
-                }
-            }
-
-            StatementKind::Constrain(ConstrainStatement(condition, message_str))
+            let message = assert_message(expressions.get(1), span, emit);
+            StatementKind::Constrain(ConstrainStatement(condition, message, ConstrainKind::Assert))
         })
 }
 
@@ -850,19 +1084,36 @@ where
                 })),
                 span,
             );
-            let mut message_str = None;
-
-            if let Some(message) = exprs.get(2) {
-                if let ExpressionKind::Literal(Literal::Str(message)) = &message.kind {
-                    message_str = Some(message.clone());
-                } else {
-                    emit(ParserError::with_reason(ParserErrorReason::AssertMessageNotString, span));
-                }
-            }
-            StatementKind::Constrain(ConstrainStatement(predicate, message_str))
+            let message = assert_message(exprs.get(2), span, emit);
+            StatementKind::Constrain(ConstrainStatement(
+                predicate,
+                message,
+                ConstrainKind::AssertEq,
+            ))
         })
 }
 
+/// The message of an `assert` or `assert_eq` must be a string literal or a format string
+fn assert_message(
+    message: Option<&Expression>,
+    span: Span,
+    emit: &mut dyn FnMut(ParserError),
+) -> Option<AssertMessage> {
+    let message = message?;
+    match &message.kind {
+        ExpressionKind::Literal(Literal::Str(string)) => {
+            Some(AssertMessage::Static(string.clone()))
+        }
+        ExpressionKind::Literal(Literal::FmtStr(_)) => {
+            Some(AssertMessage::Formatted(message.clone()))
+        }
+        _ => {
+            emit(ParserError::with_reason(ParserErrorReason::AssertMessageNotString, span));
+            None
+        }
+    }
+}
+
 fn declaration<'a, P>(expr_parser: P) -> impl NoirParser<StatementKind> + 'a
 where
     P: ExprParser + 'a,
@@ -997,6 +1248,7 @@ fn parse_type_inner(
         format_string_type(recursive_type_parser.clone()),
         named_type(recursive_type_parser.clone()),
         named_trait(recursive_type_parser.clone()),
+        trait_object_type(),
         array_type(recursive_type_parser.clone()),
         recursive_type_parser.clone().delimited_by(just(Token::LeftParen), just(Token::RightParen)),
         tuple_type(recursive_type_parser.clone()),
@@ -1092,6 +1344,12 @@ fn named_trait(type_parser: impl NoirParser<UnresolvedType>) -> impl NoirParser<
     )
 }
 
+fn trait_object_type() -> impl NoirParser<UnresolvedType> {
+    keyword(Keyword::Dyn)
+        .ignore_then(path())
+        .map_with_span(|path, span| UnresolvedTypeData::TraitObject(path).with_span(span))
+}
+
 fn generic_type_args(
     type_parser: impl NoirParser<UnresolvedType>,
 ) -> impl NoirParser<Vec<UnresolvedType>> {
@@ -1186,14 +1444,14 @@ where
 
 fn expression() -> impl ExprParser {
     recursive(|expr| {
-        expression_with_precedence(
+        range_expression(expression_with_precedence(
             Precedence::Lowest,
             expr.clone(),
             expression_no_constructors(expr.clone()),
             statement(expr.clone(), expression_no_constructors(expr)),
             false,
             true,
-        )
+        ))
     })
     .labelled(ParsingRuleLabel::Expression)
 }
@@ -1203,18 +1461,37 @@ where
     P: ExprParser + 'a,
 {
     recursive(|expr_no_constructors| {
-        expression_with_precedence(
+        range_expression(expression_with_precedence(
             Precedence::Lowest,
             expr_parser.clone(),
             expr_no_constructors.clone(),
             statement(expr_parser, expr_no_constructors),
             false,
             false,
-        )
+        ))
     })
     .labelled(ParsingRuleLabel::Expression)
 }
 
+/// range_expression: operand ('..' operand)?
+///
+/// Both operands are parsed with the lowest precedence, so `0..n + 1` is a range ending at
+/// `n + 1`, but they may not be ranges themselves.
+fn range_expression<P>(operand: P) -> impl NoirParser<Expression>
+where
+    P: ExprParser,
+{
+    operand.clone().then(just(Token::DoubleDot).ignore_then(operand).or_not()).map_with_span(
+        |(start, end), span| match end {
+            Some(end) => {
+                let range = Box::new(RangeExpression { start, end });
+                Expression::new(ExpressionKind::Range(range), span)
+            }
+            None => start,
+        },
+    )
+}
+
 fn return_statement<'a, P>(expr_parser: P) -> impl NoirParser<StatementKind> + 'a
 where
     P: ExprParser + 'a,
@@ -1448,11 +1725,25 @@ where
     S: NoirParser<StatementKind> + 'a,
 {
     keyword(Keyword::For)
-        .ignore_then(ident())
+        .ignore_then(pattern())
         .then_ignore(keyword(Keyword::In))
         .then(for_range(expr_no_constructors))
         .then(block_expr(statement))
-        .map_with_span(|((identifier, range), block), span| range.into_for(identifier, block, span))
+        .validate(|((pattern, range), block), span, emit| {
+            range.into_for(pattern, block, span, emit)
+        })
+}
+
+/// loop_statement: 'loop' block
+fn loop_statement<'a, S>(statement: S) -> impl NoirParser<StatementKind> + 'a
+where
+    S: NoirParser<StatementKind> + 'a,
+{
+    keyword(Keyword::Loop).ignore_then(block_expr(statement)).map(StatementKind::Loop)
+}
+
+fn break_statement() -> impl NoirParser<StatementKind> {
+    keyword(Keyword::Break).to(StatementKind::Break)
 }
 
 /// The 'range' of a for loop. Either an actual range `start .. end` or an array expression.
@@ -1460,19 +1751,73 @@ fn for_range<P>(expr_no_constructors: P) -> impl NoirParser<ForRange>
 where
     P: ExprParser,
 {
-    expr_no_constructors
-        .clone()
-        .then_ignore(just(Token::DoubleDot))
-        .then(expr_no_constructors.clone())
-        .map(|(start, end)| ForRange::Range(start, end))
-        .or(expr_no_constructors.map(ForRange::Array))
+    expr_no_constructors.map(|expr| match expr.kind {
+        ExpressionKind::Range(range) => ForRange::Range(range.start, range.end),
+        kind => ForRange::Array(Expression::new(kind, expr.span)),
+    })
+}
+
+/// comptime_expr: 'comptime' block
+///               | 'comptime' array_comprehension
+///
+/// `comptime [f(i) for i in 0..N]` is short for `comptime { [f(i) for i in 0..N] }`.
+fn comptime_expr<'a, P, S>(expr_parser: P, statement: S) -> impl NoirParser<ExpressionKind> + 'a
+where
+    P: ExprParser + 'a,
+    S: NoirParser<StatementKind> + 'a,
+{
+    let comprehension = array_comprehension(expr_parser).map_with_span(|comprehension, span| {
+        let comprehension = Expression::new(comprehension, span);
+        BlockExpression(vec![Statement { kind: StatementKind::Expression(comprehension), span }])
+    });
+
+    keyword(Keyword::CompTime)
+        .ignore_then(block(statement).or(comprehension))
+        .map(ExpressionKind::Comptime)
+}
+
+/// macro_expr: path '!' '(' string ')'
+///
+/// `std::env!`, `std::include_bytes!` and `std::include_str!` are the only macros, so any other
+/// path followed by `!` is an error.
+fn macro_expr() -> impl NoirParser<ExpressionKind> {
+    let argument = filter_map(|span, token: Token| match token {
+        Token::Str(argument) => Ok(argument),
+        unexpected => Err(ParserError::expected_label(
+            ParsingRuleLabel::TokenKind(TokenKind::Literal),
+            unexpected,
+            span,
+        )),
+    });
+
+    path()
+        .then_ignore(just(Token::Bang))
+        .then(argument.delimited_by(just(Token::LeftParen), just(Token::RightParen)))
+        .validate(|(path, argument), span, emit| {
+            let segments = vecmap(&path.segments, |segment| segment.0.contents.as_str());
+            match segments.as_slice() {
+                ["std", "include_bytes"] if path.kind != PathKind::Crate => {
+                    ExpressionKind::IncludeBytes(argument)
+                }
+                ["std", "include_str"] if path.kind != PathKind::Crate => {
+                    ExpressionKind::IncludeStr(argument)
+                }
+                ["std", "env"] if path.kind != PathKind::Crate => ExpressionKind::Env(argument),
+                _ => {
+                    emit(ParserError::with_reason(ParserErrorReason::UnknownMacro(path), span));
+                    ExpressionKind::Error
+                }
+            }
+        })
 }
 
 fn array_expr<P>(expr_parser: P) -> impl NoirParser<ExpressionKind>
 where
     P: ExprParser,
 {
-    standard_array(expr_parser.clone()).or(array_sugar(expr_parser))
+    standard_array(expr_parser.clone())
+        .or(array_sugar(expr_parser.clone()))
+        .or(array_comprehension(expr_parser))
 }
 
 /// [a, b, c, ...]
@@ -1497,6 +1842,35 @@ where
         .map(|(lhs, count)| ExpressionKind::repeated_array(lhs, count))
 }
 
+/// array_comprehension: '[' expression 'for' ident 'in' expression '..' expression ']'
+fn array_comprehension<P>(expr_parser: P) -> impl NoirParser<ExpressionKind>
+where
+    P: ExprParser,
+{
+    expr_parser
+        .clone()
+        .then_ignore(keyword(Keyword::For))
+        .then(ident())
+        .then_ignore(keyword(Keyword::In))
+        .then(expr_parser)
+        .delimited_by(just(Token::LeftBracket), just(Token::RightBracket))
+        .validate(|((element, identifier), range), _span, emit| match range.kind {
+            ExpressionKind::Range(range) => {
+                ExpressionKind::Literal(Literal::Array(ArrayLiteral::Comprehension {
+                    element: Box::new(element),
+                    identifier,
+                    start_range: Box::new(range.start),
+                    end_range: Box::new(range.end),
+                }))
+            }
+            _ => {
+                let reason = ParserErrorReason::ComprehensionOverNonRange;
+                emit(ParserError::with_reason(reason, range.span));
+                ExpressionKind::Error
+            }
+        })
+}
+
 fn expression_list<P>(expr_parser: P) -> impl NoirParser<Vec<Expression>>
 where
     P: ExprParser,
@@ -1515,9 +1889,14 @@ fn negation<P>(term_parser: P) -> impl NoirParser<ExpressionKind>
 where
     P: ExprParser,
 {
-    just(Token::Minus)
-        .ignore_then(term_parser)
-        .map(|rhs| ExpressionKind::prefix(UnaryOp::Minus, rhs))
+    just(Token::Minus).ignore_then(term_parser).map(|rhs| match rhs.kind {
+        // Negated integer literals are literals themselves so that they can be typed
+        // and folded as negative numbers rather than negations of a positive one
+        ExpressionKind::Literal(Literal::Integer(integer, false)) => {
+            ExpressionKind::Literal(Literal::Integer(integer, true))
+        }
+        _ => ExpressionKind::prefix(UnaryOp::Minus, rhs),
+    })
 }
 
 fn mutable_reference<P>(term_parser: P) -> impl NoirParser<ExpressionKind>
@@ -1562,7 +1941,9 @@ where
             nothing().boxed()
         },
         lambda(expr_parser.clone()),
+        comptime_expr(expr_parser.clone(), statement.clone()),
         block(statement).map(ExpressionKind::Block),
+        macro_expr(),
         variable(),
         literal(),
     ))
@@ -1580,7 +1961,7 @@ fn type_expression_atom<'a, P>(expr_parser: P) -> impl NoirParser<Expression> +
 where
     P: ExprParser + 'a,
 {
-    variable()
+    variable_no_turbofish()
         .or(literal())
         .map_with_span(Expression::new)
         .or(parenthesized(expr_parser))
@@ -1629,8 +2010,16 @@ where
     long_form.or(short_form)
 }
 
+/// variable: path ( '::' generic_type_args )?
 fn variable() -> impl NoirParser<ExpressionKind> {
-    path().map(ExpressionKind::Variable)
+    let turbofish = just(Token::DoubleColon)
+        .ignore_then(just(Token::Less).rewind())
+        .ignore_then(generic_type_args(parse_type()));
+    path().then(turbofish.or_not()).map(|(path, generics)| ExpressionKind::Variable(path, generics))
+}
+
+fn variable_no_turbofish() -> impl NoirParser<ExpressionKind> {
+    path().map(|path| ExpressionKind::Variable(path, None))
 }
 
 fn literal() -> impl NoirParser<ExpressionKind> {
@@ -1639,16 +2028,11 @@ fn literal() -> impl NoirParser<ExpressionKind> {
         Token::Bool(b) => ExpressionKind::boolean(b),
         Token::Str(s) => ExpressionKind::string(s),
         Token::FmtStr(s) => ExpressionKind::format_string(s),
+        Token::Char(c) => ExpressionKind::Literal(Literal::Char(c)),
         unexpected => unreachable!("Non-literal {} parsed as a literal", unexpected),
     })
 }
 
-fn literal_or_collection<'a>(
-    expr_parser: impl ExprParser + 'a,
-) -> impl NoirParser<ExpressionKind> + 'a {
-    choice((literal(), constructor(expr_parser.clone()), array_expr(expr_parser)))
-}
-
 #[cfg(test)]
 mod test {
     use noirc_errors::CustomDiagnostic;
@@ -1770,6 +2154,18 @@ mod test {
         parse_all(expression(), valid);
     }
 
+    #[test]
+    fn parse_turbofish() {
+        let call = parse_with(expression(), "std::meta::fields_of::<Foo<Field>, 3>()").unwrap();
+        match call.kind {
+            ExpressionKind::Call(call) => {
+                assert_eq!(call.func.to_string(), "std::meta::fields_of::<Foo<Field>, 3>");
+            }
+            other => panic!("Expected a call, got {other:?}"),
+        }
+        parse_all_failing(expression(), vec!["foo::<>()", "foo::<Field()"]);
+    }
+
     #[test]
     fn parse_cast() {
         parse_all(
@@ -1838,6 +2234,9 @@ mod test {
                 ArrayLiteral::Repeated { length, .. } => {
                     assert_eq!(length.kind, ExpressionKind::integer(5i128.into()));
                 }
+                ArrayLiteral::Comprehension { .. } => {
+                    panic!("Expected a standard or repeated array")
+                }
             }
         }
 
@@ -1993,11 +2392,23 @@ mod test {
 
         match parse_with(assertion(expression()), "assert(x == y, \"assertion message\")").unwrap()
         {
-            StatementKind::Constrain(ConstrainStatement(_, message)) => {
-                assert_eq!(message, Some("assertion message".to_owned()));
+            StatementKind::Constrain(ConstrainStatement(_, message, _)) => {
+                assert_eq!(message, Some(AssertMessage::Static("assertion message".to_owned())));
+            }
+            _ => unreachable!(),
+        }
+
+        match parse_with(assertion(expression()), "assert(x == y, f\"{x} is not {y}\")").unwrap() {
+            StatementKind::Constrain(ConstrainStatement(
+                _,
+                Some(AssertMessage::Formatted(msg)),
+                _,
+            )) => {
+                assert_eq!(msg.kind, ExpressionKind::format_string("{x} is not {y}".to_owned()));
             }
             _ => unreachable!(),
         }
+        parse_all_failing(assertion(expression()), vec!["assert(x == y, x)"]);
     }
 
     /// This is the standard way to assert that two expressions are equivalent
@@ -2017,8 +2428,8 @@ mod test {
         match parse_with(assertion_eq(expression()), "assert_eq(x, y, \"assertion message\")")
             .unwrap()
         {
-            StatementKind::Constrain(ConstrainStatement(_, message)) => {
-                assert_eq!(message, Some("assertion message".to_owned()));
+            StatementKind::Constrain(ConstrainStatement(_, message, _)) => {
+                assert_eq!(message, Some(AssertMessage::Static("assertion message".to_owned())));
             }
             _ => unreachable!(),
         }
@@ -2044,19 +2455,58 @@ mod test {
     fn parse_for_loop() {
         parse_all(
             for_loop(expression_no_constructors(expression()), fresh_statement()),
-            vec!["for i in x+y..z {}", "for i in 0..100 { foo; bar }"],
+            vec![
+                "for i in x+y..z {}",
+                "for i in 0..100 { foo; bar }",
+                "for x in array { foo }",
+                "for (i, x) in array.enumerate() { foo }",
+                "for Point { x, y } in points {}",
+            ],
         );
 
         parse_all_failing(
             for_loop(expression_no_constructors(expression()), fresh_statement()),
             vec![
-                "for 1 in x+y..z {}",  // Cannot have a literal as the loop identifier
+                "for 1 in x+y..z {}",      // Cannot have a literal as the loop identifier
                 "for i in 0...100 {}", // Only '..' is supported, there are no inclusive ranges yet
                 "for i in 0..=100 {}", // Only '..' is supported, there are no inclusive ranges yet
+                "for (i, j) in 0..100 {}", // Loops over a range can only bind a variable
             ],
         );
     }
 
+    #[test]
+    fn parse_loop() {
+        parse_all(
+            fresh_statement(),
+            vec!["loop {}", "loop { if x == 3 { break } x += 1; }", "loop { loop { break; } }"],
+        );
+        parse_all_failing(fresh_statement(), vec!["loop", "loop x", "break x"]);
+    }
+
+    #[test]
+    fn parse_range() {
+        parse_all(
+            expression(),
+            vec!["0..10", "x + 1..y * 2", "foo(0..n)", "(a..b).len()", "Foo { range: 1..2 }"],
+        );
+        parse_all_failing(expression(), vec!["0.. 1..2", "..10", "0..=10"]);
+    }
+
+    #[test]
+    fn parse_global() {
+        parse_all(
+            global_declaration(),
+            vec![
+                "global X = 1",
+                "global X: u8 = 1",
+                "global mut X = 1",
+                "global mut X: [u8; 2] = [1, 2]",
+            ],
+        );
+        parse_all_failing(global_declaration(), vec!["global mut = 1", "global mut mut X = 1"]);
+    }
+
     #[test]
     fn parse_function() {
         parse_all(
@@ -2088,6 +2538,10 @@ mod test {
                 "fn func_name<T>(f: Field, y : T) where T: SomeTrait + {}",
                 // The following should produce compile error on later stage. From the parser's perspective it's fine
                 "fn func_name<A>(f: Field, y : Field, z : Field) where T: SomeTrait {}",
+                "fn func_name<N>(f: [Field; N]) where N <= 32 {}",
+                "fn func_name<T, N>(f: [T; N]) where T: SomeTrait, N * 2 > 1, N != 3 {}",
+                "comptime fn table() -> [Field; 4] { [1, 2, 3, 4] }",
+                "pub comptime fn square(x: Field) -> Field { x * x }",
             ],
         );
 
@@ -2104,6 +2558,8 @@ mod test {
                 "fn func_name(f: Field, y : pub Field, z : pub [u8;5],) where T: SomeTrait {}",
                 // A leading plus is not allowed.
                 "fn func_name<T>(f: Field, y : T) where T: + SomeTrait {}",
+                "fn func_name(f: [Field; 2]) where 2 <= 32 {}",
+                "fn func_name<N>(f: [Field; N]) where N + 1 {}",
                 "fn func_name<T>(f: Field, y : T) where T: TraitX + <Y> {}",
             ],
         );
@@ -2170,6 +2626,41 @@ mod test {
         );
     }
 
+    #[test]
+    fn parse_comptime_expr() {
+        parse_all(
+            comptime_expr(expression(), fresh_statement()),
+            vec![
+                "comptime {}",
+                "comptime { 1 + 2 }",
+                "comptime { let x = 3; x * x }",
+                "comptime [i * i for i in 0..4]",
+            ],
+        );
+
+        parse_all_failing(
+            comptime_expr(expression(), fresh_statement()),
+            vec!["comptime 1 + 2", "comptime", "comptime [1, 2]"],
+        );
+    }
+
+    #[test]
+    fn parse_array_comprehension() {
+        let valid = vec![
+            "[i * i for i in 0..4]",
+            "[f(i) for i in 1..N + 1]",
+            "[[j for j in 0..i] for i in 0..2]",
+        ];
+        for expr in parse_all(array_expr(expression()), valid) {
+            assert!(matches!(expr_to_array(expr), ArrayLiteral::Comprehension { .. }));
+        }
+
+        parse_all_failing(
+            array_expr(expression()),
+            vec!["[i for i in array]", "[i for 0..4]", "[i for (a, b) in 0..4]", "[for i in 0..4]"],
+        );
+    }
+
     fn expr_to_lit(expr: ExpressionKind) -> Literal {
         match expr {
             ExpressionKind::Literal(literal) => literal,
@@ -2183,7 +2674,7 @@ mod test {
         let hex = parse_with(literal(), "0x05").unwrap();
 
         match (expr_to_lit(int), expr_to_lit(hex)) {
-            (Literal::Integer(int), Literal::Integer(hex)) => assert_eq!(int, hex),
+            (Literal::Integer(int, false), Literal::Integer(hex, false)) => assert_eq!(int, hex),
             _ => unreachable!(),
         }
     }
@@ -2197,6 +2688,16 @@ mod test {
         };
     }
 
+    #[test]
+    fn string_literal_display_round_trips() {
+        let src = r#""tab\tquote\"backslash\\bell\u{7} é""#;
+        let parsed = expr_to_lit(parse_with(literal(), src).unwrap());
+        assert_eq!(parsed.to_string(), r#""tab\tquote\"backslash\\bell\u{7} é""#);
+
+        let reparsed = expr_to_lit(parse_with(literal(), &parsed.to_string()).unwrap());
+        assert_eq!(reparsed, parsed);
+    }
+
     #[test]
     fn parse_bool() {
         let expr_true = parse_with(literal(), "true").unwrap();
@@ -2217,6 +2718,18 @@ mod test {
         parse_with(module_declaration(), "mod 1").unwrap_err();
     }
 
+    #[test]
+    fn parse_test_modules() {
+        let declaration = parse_with(module_declaration(), "#[test] mod tests").unwrap();
+        assert!(matches!(declaration, TopLevelStatement::Module(decl) if decl.is_test_only));
+
+        let parsed = parse_with(module(), "#[test] mod tests { fn foo() {} }").unwrap();
+        assert!(matches!(&parsed.items[0].kind, ItemKind::Submodules(s) if s.is_test_only));
+
+        let failing = vec!["#[oracle(foo)] mod tests", "#[test(should_fail)] mod tests"];
+        parse_all_failing(module_declaration(), failing);
+    }
+
     #[test]
     fn parse_path() {
         let cases = vec![
@@ -2268,6 +2781,22 @@ mod test {
         );
     }
 
+    #[test]
+    fn parse_negative_integer_literal() {
+        let parser = || {
+            term(expression(), expression_no_constructors(expression()), fresh_statement(), true)
+        };
+
+        let negative = parse_with(parser(), "-5").unwrap();
+        assert_eq!(negative.kind, ExpressionKind::Literal(Literal::Integer(5_i128.into(), true)));
+
+        // Only the literal itself is negated, not a parenthesized or already negative literal
+        for src in ["-(5)", "--5"] {
+            let expression = parse_with(parser(), src).unwrap();
+            assert!(matches!(expression.kind, ExpressionKind::Prefix(_)), "{src} is a negation");
+        }
+    }
+
     #[test]
     fn parse_use() {
         parse_all(
@@ -2330,10 +2859,31 @@ mod test {
 
     #[test]
     fn parse_member_access() {
-        let cases = vec!["a.b", "a + b.c", "foo.bar as u32"];
+        let cases = vec!["a.b", "a + b.c", "foo.bar as u32", "a.0", "a.0.1", "foo().1.b[2].0"];
         parse_all(expression(), cases);
     }
 
+    #[test]
+    fn parse_tuple_field_call() {
+        let expr = parse_with(expression(), "a.1(2, 3)").unwrap();
+        let call = match expr.kind {
+            ExpressionKind::Call(call) => call,
+            other => panic!("Expected a call, got {other:?}"),
+        };
+        assert_eq!(call.arguments.len(), 2);
+        assert!(matches!(&call.func.kind, ExpressionKind::MemberAccess(access)
+            if access.rhs.0.contents == "1"));
+
+        let expr = parse_with(expression(), "a.b(2, 3)").unwrap();
+        assert!(matches!(expr.kind, ExpressionKind::MethodCall(_)));
+    }
+
+    #[test]
+    fn parse_tuple_field_assignment() {
+        let cases = vec!["a.0 = 1", "a.1.2 = x", "a.0[1].1 += 2", "(*a).1 = 3"];
+        parse_all(assignment(expression()), cases);
+    }
+
     #[test]
     fn parse_constructor() {
         let cases = vec![
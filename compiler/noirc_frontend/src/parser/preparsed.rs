@@ -0,0 +1,67 @@
+//! Modules which were parsed ahead of time, such as the stdlib which `noirc_driver` parses
+//! while it is being built rather than on every compilation.
+//!
+//! The modules are stored together in a single blob, each serialized separately, so that
+//! loading the blob only reads its index. A module is only deserialized once its file is
+//! actually collected.
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use super::ParsedModule;
+
+#[derive(Debug, Clone, Default)]
+pub struct PreparsedFiles {
+    modules: BTreeMap<&'static str, &'static [u8]>,
+}
+
+impl PreparsedFiles {
+    /// Serializes the given modules, keyed by the path of the file each was parsed from.
+    /// Paths should be `/` separated, e.g. `std/hash.nr`.
+    pub fn serialize(modules: impl IntoIterator<Item = (String, ParsedModule)>) -> Vec<u8> {
+        let modules: BTreeMap<String, Vec<u8>> = modules
+            .into_iter()
+            .map(|(path, module)| {
+                let module = bincode::serialize(&module).expect("ASTs are always serializable");
+                (path, module)
+            })
+            .collect();
+        bincode::serialize(&modules).expect("ASTs are always serializable")
+    }
+
+    /// Reads the index of a blob written by [`PreparsedFiles::serialize`] without
+    /// deserializing any of its modules.
+    pub fn from_bytes(bytes: &'static [u8]) -> PreparsedFiles {
+        let modules = bincode::deserialize(bytes).expect("ICE: pre-parsed files are corrupt");
+        PreparsedFiles { modules }
+    }
+
+    /// Returns the module parsed from the file at `path`, if there is one.
+    pub fn get(&self, path: &Path) -> Option<ParsedModule> {
+        let path: Vec<_> =
+            path.iter().map(|component| component.to_str()).collect::<Option<_>>()?;
+        let module = self.modules.get(path.join("/").as_str())?;
+        Some(bincode::deserialize(module).expect("ICE: pre-parsed files are corrupt"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use super::PreparsedFiles;
+    use crate::parse_program;
+
+    #[test]
+    fn round_trips_modules() {
+        let source = "fn foo(x: Field) -> pub Field { x + 1 }";
+        let (module, errors) = parse_program(source);
+        assert!(errors.is_empty());
+
+        let bytes = PreparsedFiles::serialize([("std/foo.nr".to_string(), module.clone())]);
+        let files = PreparsedFiles::from_bytes(Box::leak(bytes.into_boxed_slice()));
+
+        let preparsed = files.get(Path::new("std").join("foo.nr").as_path()).unwrap();
+        assert_eq!(format!("{preparsed:?}"), format!("{module:?}"));
+        assert!(files.get(Path::new("std/bar.nr")).is_none());
+    }
+}
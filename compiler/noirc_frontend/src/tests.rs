@@ -9,23 +9,28 @@ mod test {
 
     use fm::FileId;
 
-    use noirc_errors::Location;
+    use noirc_errors::codes::{E0202, E0205, E0223, E0224, E0300, E0309, E0316, E0320};
+    use noirc_errors::{CustomDiagnostic, Location};
 
+    use crate::api::{self, ItemKind};
+    use crate::hir::comptime::ComptimeError;
     use crate::hir::def_collector::dc_crate::CompilationError;
     use crate::hir::def_collector::errors::{DefCollectorErrorKind, DuplicateType};
-    use crate::hir::def_map::ModuleData;
+    use crate::hir::def_map::{ModuleData, ModuleDefId};
     use crate::hir::resolution::errors::ResolverError;
     use crate::hir::resolution::import::PathResolutionError;
     use crate::hir::type_check::TypeCheckError;
     use crate::hir::Context;
-    use crate::node_interner::{NodeInterner, StmtId};
+    use crate::node_interner::{DefinitionKind, NodeInterner, StmtId};
 
     use crate::graph::CrateGraph;
     use crate::hir::def_collector::dc_crate::DefCollector;
-    use crate::hir_def::expr::HirExpression;
-    use crate::hir_def::stmt::HirStatement;
+    use crate::hir_def::expr::{HirArrayLiteral, HirExpression, HirLiteral};
+    use crate::hir_def::stmt::{HirAssertMessage, HirConstrainStatement, HirStatement};
+    use crate::monomorphization::errors::MonomorphizationError;
     use crate::monomorphization::monomorphize;
     use crate::parser::ParserErrorReason;
+    use crate::token::{InlineType, StructLayout};
     use crate::ParsedModule;
     use crate::{
         hir::def_map::{CrateDefMap, LocalModuleId},
@@ -33,6 +38,7 @@ mod test {
     };
     use arena::Arena;
     use fm::FileManager;
+    use iter_extended::vecmap;
 
     pub(crate) fn has_parser_error(errors: &[(CompilationError, FileId)]) -> bool {
         errors.iter().any(|(e, _f)| matches!(e, CompilationError::ParseError(_)))
@@ -56,12 +62,22 @@ mod test {
 
     pub(crate) fn get_program(
         src: &str,
+    ) -> (ParsedModule, Context, Vec<(CompilationError, FileId)>) {
+        get_program_with(src, |_| {})
+    }
+
+    /// Like [`get_program`], configuring the interner with `configure` before collecting the
+    /// program
+    pub(crate) fn get_program_with(
+        src: &str,
+        configure: impl FnOnce(&mut NodeInterner),
     ) -> (ParsedModule, Context, Vec<(CompilationError, FileId)>) {
         let root = std::path::Path::new("/");
         let fm = FileManager::new(root, Box::new(|path| std::fs::read_to_string(path)));
         //let fm = FileManager::new(root,  Box::new(get_non_stdlib_asset));
         let graph = CrateGraph::default();
         let mut context = Context::new(fm, graph);
+        configure(&mut context.def_interner);
         let root_file_id = FileId::dummy();
         let root_crate_id = context.crate_graph.add_crate_root(root_file_id);
         let (program, parser_errors) = parse_program(src);
@@ -78,6 +94,7 @@ mod test {
                 modules,
                 krate: root_crate_id,
                 extern_prelude: BTreeMap::new(),
+                prelude: None,
             };
             // Now we want to populate the CrateDefMap using the DefCollector
             errors.extend(DefCollector::collect(
@@ -314,6 +331,269 @@ mod test {
         }
     }
 
+    #[test]
+    fn check_impl_where_clause_not_satisfied() {
+        let src = "
+        trait Double {
+            fn double(self) -> Self;
+        }
+
+        impl Double for Field {
+            fn double(self) -> Self {
+                self * 2
+            }
+        }
+
+        struct Wrapper<T> {
+            inner: T,
+        }
+
+        impl<T> Wrapper<T> where T: Double {
+            fn doubled(self) -> T {
+                self.inner.double()
+            }
+        }
+
+        fn main() {
+            let _ = Wrapper { inner: 1 as Field }.doubled();
+            let _ = Wrapper { inner: true }.doubled();
+        }
+        ";
+        let errors = get_program_errors(src);
+        assert!(!has_parser_error(&errors));
+        assert!(errors.len() == 1, "Expected 1 error, got: {:?}", errors);
+
+        for (err, _file_id) in errors {
+            match &err {
+                CompilationError::TypeError(TypeCheckError::NoMatchingImplFound {
+                    typ,
+                    trait_name,
+                    ..
+                }) => {
+                    assert_eq!(typ.to_string(), "bool");
+                    assert_eq!(trait_name, "Double");
+                }
+                _ => {
+                    panic!("No other errors are expected! Found = {:?}", err);
+                }
+            };
+        }
+    }
+
+    #[test]
+    fn check_generic_trait_impl_where_clause_not_satisfied() {
+        let src = "
+        trait Summable {
+            fn sum(self) -> Field;
+        }
+
+        impl Summable for Field {
+            fn sum(self) -> Field {
+                self
+            }
+        }
+
+        struct Wrapper<T> {
+            inner: T,
+        }
+
+        impl<T> Summable for Wrapper<T> where T: Summable {
+            fn sum(self) -> Field {
+                self.inner.sum()
+            }
+        }
+
+        fn sum_of<T>(value: T) -> Field where T: Summable {
+            value.sum()
+        }
+
+        fn main() {
+            let _ = sum_of(Wrapper { inner: 1 as Field });
+            let _ = sum_of(Wrapper { inner: true });
+        }
+        ";
+        let errors = get_program_errors(src);
+        assert!(!has_parser_error(&errors));
+        assert!(errors.len() == 1, "Expected 1 error, got: {:?}", errors);
+
+        for (err, _file_id) in errors {
+            match &err {
+                CompilationError::TypeError(TypeCheckError::NoMatchingImplFound {
+                    typ,
+                    trait_name,
+                    ..
+                }) => {
+                    assert_eq!(typ.to_string(), "Wrapper<bool>");
+                    assert_eq!(trait_name, "Summable");
+                }
+                _ => {
+                    panic!("No other errors are expected! Found = {:?}", err);
+                }
+            };
+        }
+    }
+
+    #[test]
+    fn method_calls_resolve_through_where_clause_constraints() {
+        let src = "
+        trait Serialize {
+            fn serialize(self) -> Field;
+        }
+
+        impl Serialize for (Field, Field) {
+            fn serialize(self) -> Field {
+                self.0 * 2 + self.1
+            }
+        }
+
+        fn serialize_pair<T>(pair: (T, T)) -> Field where (T, T): Serialize {
+            pair.serialize()
+        }
+
+        fn serialize_array<T, N>(array: [T; N]) -> Field where [T; N]: Serialize {
+            array.serialize()
+        }
+
+        fn main() {
+            let _ = serialize_pair((1 as Field, 2 as Field));
+        }
+        ";
+        let (_program, context, errors) = get_program(src);
+        assert!(errors.is_empty(), "Expected no errors, got: {:?}", errors);
+
+        let main_func_id = context.def_interner.find_function("main").unwrap();
+        let program = monomorphize(main_func_id, &context.def_interner).unwrap();
+        assert!(program.to_string().contains("fn serialize$"));
+    }
+
+    #[test]
+    fn trait_method_calls_auto_dereference_mutable_references() {
+        let src = "
+        trait Counter {
+            fn count(self) -> Field;
+            fn increment(&mut self);
+        }
+
+        struct Clicks {
+            clicks: Field,
+        }
+
+        impl Counter for Clicks {
+            fn count(self) -> Field {
+                self.clicks
+            }
+
+            fn increment(&mut self) {
+                self.clicks += 1;
+            }
+        }
+
+        fn increment_twice<T>(counter: &mut T) -> Field where T: Counter {
+            counter.increment();
+            counter.increment();
+            counter.count()
+        }
+
+        fn increment_copy<T>(counter: T) -> Field where T: Counter {
+            let mut counter = counter;
+            counter.increment();
+            counter.count()
+        }
+
+        fn main() {
+            let mut clicks = Clicks { clicks: 0 };
+            assert(increment_twice(&mut clicks) == 2);
+            assert(increment_copy(clicks) == 3);
+        }
+        ";
+        let errors = get_program_errors(src);
+        assert!(errors.is_empty(), "Expected no errors, got: {:?}", errors);
+    }
+
+    #[test]
+    fn check_trait_generic_mismatch() {
+        let src = "
+        trait Getter<T> {
+            fn get(self) -> T;
+        }
+
+        struct Foo {
+            x: Field,
+        }
+
+        struct Bar {
+            x: Field,
+        }
+
+        impl Getter<Field> for Foo {
+            fn get(self) -> Field {
+                self.x
+            }
+        }
+
+        impl Getter<bool> for Bar {
+            fn get(self) -> Field {
+                self.x
+            }
+        }
+
+        fn main() {
+        }
+        ";
+        let errors = get_program_errors(src);
+        assert!(!has_parser_error(&errors));
+        assert!(errors.len() == 1, "Expected 1 error, got: {:?}", errors);
+
+        for (err, _file_id) in errors {
+            match &err {
+                CompilationError::TypeError(TypeCheckError::TypeMismatch {
+                    expected_typ,
+                    expr_typ,
+                    ..
+                }) => {
+                    assert_eq!(expected_typ, "bool");
+                    assert_eq!(expr_typ, "Field");
+                }
+                _ => {
+                    panic!("No other errors are expected! Found = {:?}", err);
+                }
+            };
+        }
+    }
+
+    #[test]
+    fn check_trait_generic_count_mismatch() {
+        let src = "
+        trait Convert<A, B> {
+            fn convert(value: A) -> B;
+        }
+
+        struct Foo {}
+
+        impl Convert<Field> for Foo {
+            fn convert(value: Field) -> Field {
+                value
+            }
+        }
+
+        fn main() {
+        }
+        ";
+        let errors = get_program_errors(src);
+        assert!(!has_parser_error(&errors));
+        assert!(errors.len() == 1, "Expected 1 error, got: {:?}", errors);
+
+        match &errors[0].0 {
+            CompilationError::DefinitionError(
+                DefCollectorErrorKind::IncorrectTraitGenericCount { expected, actual, .. },
+            ) => {
+                assert_eq!(*expected, 2);
+                assert_eq!(*actual, 1);
+            }
+            err => panic!("No other errors are expected! Found = {:?}", err),
+        }
+    }
+
     #[test]
     fn check_trait_wrong_method_name() {
         let src = "
@@ -718,6 +998,75 @@ mod test {
         }
     }
 
+    #[test]
+    fn check_trait_overlapping_generic_implementation() {
+        let src = "
+        trait Default {
+        }
+
+        struct Wrapper<T> {
+            inner: T,
+        }
+
+        impl<T> Default for Wrapper<T> {
+        }
+
+        impl Default for Wrapper<Field> {
+        }
+
+        fn main() {
+        }
+        ";
+        let errors = get_program_errors(src);
+        assert!(!has_parser_error(&errors));
+        assert!(errors.len() == 1, "Expected 1 error, got: {:?}", errors);
+        for (err, _file_id) in errors {
+            match &err {
+                CompilationError::DefinitionError(DefCollectorErrorKind::OverlappingImpl {
+                    trait_name,
+                    typ,
+                    ..
+                }) => {
+                    assert_eq!(trait_name, "Default");
+                    assert_eq!(typ, "Wrapper<Field>");
+                }
+                _ => {
+                    panic!("No other errors are expected! Found = {:?}", err);
+                }
+            };
+        }
+    }
+
+    #[test]
+    fn check_trait_disjoint_generic_implementations() {
+        let src = "
+        trait Default {
+        }
+
+        struct Pair<A, B> {
+            a: A,
+            b: B,
+        }
+
+        impl<T> Default for Pair<T, Field> {
+        }
+
+        impl<T> Default for Pair<T, u32> {
+        }
+
+        impl Default for [Field; 3] {
+        }
+
+        impl Default for [u32; 3] {
+        }
+
+        fn main() {
+        }
+        ";
+        let errors = get_program_errors(src);
+        assert!(errors.is_empty(), "Expected no errors, got: {:?}", errors);
+    }
+
     fn get_program_captures(src: &str) -> Vec<Vec<String>> {
         let (program, context, _errors) = get_program(src);
         let interner = context.def_interner;
@@ -749,6 +1098,8 @@ mod test {
                 HirStatement::Constrain(constr_stmt) => constr_stmt.0,
                 HirStatement::Semi(semi_expr) => semi_expr,
                 HirStatement::For(for_loop) => for_loop.block,
+                HirStatement::Loop(block) => block,
+                HirStatement::Break => continue,
                 HirStatement::Error => panic!("Invalid HirStatement!"),
             };
             let expr = interner.expression(&expr_id);
@@ -832,6 +1183,7 @@ mod test {
             CompilationError::ResolveError(ResolverError::VariableNotDeclared {
                 name,
                 span: _,
+                suggestion: _,
             }) => assert_eq!(name, "z"),
             _ => unimplemented!("we should only have an unresolved variable"),
         }
@@ -1096,28 +1448,2049 @@ mod test {
         }
     }
 
-    fn check_rewrite(src: &str, expected: &str) {
-        let (_program, context, _errors) = get_program(src);
-        let main_func_id = context.def_interner.find_function("main").unwrap();
-        let program = monomorphize(main_func_id, &context.def_interner);
-        assert!(format!("{}", program) == expected);
+    #[test]
+    fn contract_functions_are_in_declaration_order() {
+        let src = r#"
+        contract Foo {
+            fn zeta() {}
+            fn alpha() {}
+            fn mu() {}
+            fn beta() {}
+            fn omega() {}
+            fn gamma() {}
+            fn kappa() {}
+            fn delta() {}
+        }
+        "#;
+        let (_program, context, errors) = get_program(src);
+        assert!(errors.is_empty(), "Expected no errors, got: {:?}", errors);
+
+        let crate_id = *context.def_maps.keys().next().unwrap();
+        let contracts = context.get_all_contracts(&crate_id);
+        assert_eq!(contracts.len(), 1);
+
+        let names = vecmap(&contracts[0].functions, |function| {
+            context.def_interner.function_name(&function.function_id).to_owned()
+        });
+        assert_eq!(names, ["zeta", "alpha", "mu", "beta", "omega", "gamma", "kappa", "delta"]);
     }
 
     #[test]
-    fn simple_closure_with_no_captured_variables() {
+    fn trait_returning_self_is_not_object_safe() {
         let src = r#"
-        fn main() -> pub Field {
-            let x = 1;
-            let closure = || x;
-            closure()
+        trait Double {
+            fn double(self) -> Self;
+        }
+
+        struct Foo { x: Field }
+
+        impl Double for Foo {
+            fn double(self) -> Self {
+                Foo { x: self.x * 2 }
+            }
+        }
+
+        fn main(x: Field) {
+            let _foo: dyn Double = Foo { x };
         }
         "#;
+        let errors = get_program_errors(src);
+        assert!(errors.len() == 1, "Expected 1 error, got: {:?}", errors);
+        match &errors[0].0 {
+            CompilationError::TypeError(TypeCheckError::TraitNotObjectSafe {
+                trait_name, ..
+            }) => {
+                assert_eq!(trait_name, "Double");
+            }
+            _ => panic!("Expected TraitNotObjectSafe, got: {:?}", errors[0].0),
+        }
+    }
 
-        let expected_rewrite = r#"fn main$f0() -> Field {
-    let x$0 = 1;
-    let closure$3 = {
-        let closure_variable$2 = {
-            let env$1 = (x$l0);
+    #[test]
+    fn generic_values_cannot_be_converted_into_trait_objects() {
+        let src = r#"
+        trait Shape {
+            fn area(self) -> Field;
+        }
+
+        struct Wrapper<T> { inner: T }
+
+        impl<T> Shape for Wrapper<T> {
+            fn area(self) -> Field {
+                0
+            }
+        }
+
+        fn to_shape<T>(inner: T) -> dyn Shape {
+            let shape: dyn Shape = Wrapper { inner };
+            shape
+        }
+
+        fn main() {
+            let _ = to_shape(1);
+        }
+        "#;
+        let errors = get_program_errors(src);
+        assert!(errors.len() == 1, "Expected 1 error, got: {:?}", errors);
+        match &errors[0].0 {
+            CompilationError::TypeError(TypeCheckError::TraitObjectOfNonConcreteType {
+                typ,
+                ..
+            }) => {
+                assert_eq!(typ.to_string(), "Wrapper<T>");
+            }
+            _ => panic!("Expected TraitObjectOfNonConcreteType, got: {:?}", errors[0].0),
+        }
+    }
+
+    fn check_rewrite(src: &str, expected: &str) {
+        let (_program, context, _errors) = get_program(src);
+        let main_func_id = context.def_interner.find_function("main").unwrap();
+        let program = monomorphize(main_func_id, &context.def_interner).unwrap();
+        assert!(format!("{}", program) == expected);
+    }
+
+    #[test]
+    fn type_level_expressions_in_signatures() {
+        let src = r#"
+        fn push<N>(array: [Field; N], element: Field) -> [Field; N + 1] {
+            let mut result = [element; N + 1];
+            for i in 0..N {
+                result[i] = array[i];
+            }
+            result
+        }
+
+        fn double<N>(array: [Field; N]) -> [Field; N * 2] {
+            let mut result = [0; N * 2];
+            for i in 0..N {
+                result[2 * i] = array[i];
+                result[2 * i + 1] = array[i];
+            }
+            result
+        }
+
+        fn main() {
+            let pushed: [Field; 4] = push([1, 2, 3], 4);
+            let _doubled: [Field; 8] = double(pushed);
+        }
+        "#;
+        let errors = get_program_errors(src);
+        assert!(errors.is_empty(), "Expected no errors, got: {:?}", errors);
+    }
+
+    #[test]
+    fn type_level_expression_length_mismatch() {
+        let src = r#"
+        fn push<N>(array: [Field; N], element: Field) -> [Field; N + 1] {
+            let mut result = [element; N + 1];
+            for i in 0..N {
+                result[i] = array[i];
+            }
+            result
+        }
+
+        fn main() {
+            let _pushed: [Field; 3] = push([1, 2, 3], 4);
+        }
+        "#;
+        let errors = get_program_errors(src);
+        assert_eq!(errors.len(), 1, "Expected 1 error, got: {:?}", errors);
+        assert!(matches!(errors[0].0, CompilationError::TypeError(_)));
+    }
+
+    #[test]
+    fn invalid_type_level_expression_is_reported_at_instantiation() {
+        let src = r#"
+        fn pop<N>(array: [Field; N]) -> [Field; N - 1] {
+            let mut result = [0; N - 1];
+            for i in 0..N - 1 {
+                result[i] = array[i];
+            }
+            result
+        }
+
+        fn pop_all<M>(array: [Field; M]) {
+            let _ = pop(array);
+        }
+
+        fn main() {
+            pop_all([]);
+        }
+        "#;
+        let (_program, context, errors) = get_program(src);
+        assert!(errors.is_empty(), "Expected no errors, got: {:?}", errors);
+
+        let main_func_id = context.def_interner.find_function("main").unwrap();
+        match monomorphize(main_func_id, &context.def_interner) {
+            Err(MonomorphizationError::InvalidTypeExpression { expression, function, .. }) => {
+                assert_eq!(expression, "0 - 1");
+                assert_eq!(function.as_deref(), Some("pop"));
+            }
+            other => panic!("Expected an InvalidTypeExpression error, got: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn numeric_constraint_is_checked_at_instantiation() {
+        let src = r#"
+        fn first<N>(array: [Field; N]) -> Field where N >= 1, N <= 4 {
+            array[0]
+        }
+
+        fn first_of_pair<M>(array: [Field; M]) -> Field {
+            first(array)
+        }
+
+        fn main() {
+            let _ = first([1, 2, 3, 4]);
+            let _ = first_of_pair([1, 2, 3, 4, 5]);
+        }
+        "#;
+        let (_program, context, errors) = get_program(src);
+        assert!(errors.is_empty(), "Expected no errors, got: {:?}", errors);
+
+        let main_func_id = context.def_interner.find_function("main").unwrap();
+        match monomorphize(main_func_id, &context.def_interner) {
+            Err(MonomorphizationError::UnsatisfiedNumericConstraint {
+                constraint,
+                instantiated,
+                function,
+                ..
+            }) => {
+                assert_eq!(constraint, "N <= 4");
+                assert_eq!(instantiated, "5 <= 4");
+                assert_eq!(function, "first");
+            }
+            other => panic!("Expected an UnsatisfiedNumericConstraint error, got: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn recursion_between_constrained_functions_is_reported() {
+        let src = r#"
+        fn is_even(n: u32) -> bool {
+            if n == 0 { true } else { is_odd(n - 1) }
+        }
+
+        fn is_odd(n: u32) -> bool {
+            if n == 0 { false } else { is_even(n - 1) }
+        }
+
+        unconstrained fn fibonacci(n: u32) -> u32 {
+            if n <= 1 { n } else { fibonacci(n - 1) + fibonacci(n - 2) }
+        }
+
+        fn main(x: u32) {
+            assert(fibonacci(x) != 0);
+            assert(is_even(x));
+        }
+        "#;
+        let (_program, context, errors) = get_program(src);
+        assert!(errors.is_empty(), "Expected no errors, got: {:?}", errors);
+
+        let main_func_id = context.def_interner.find_function("main").unwrap();
+        match monomorphize(main_func_id, &context.def_interner) {
+            Err(MonomorphizationError::RecursiveConstrainedFunction { cycle }) => {
+                let calls = vecmap(cycle, |call| (call.caller, call.callee));
+                let expected = vec![
+                    ("is_even".to_string(), "is_odd".to_string()),
+                    ("is_odd".to_string(), "is_even".to_string()),
+                ];
+                assert_eq!(calls, expected);
+            }
+            other => panic!("Expected a RecursiveConstrainedFunction error, got: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn derive_rejects_unknown_traits_and_unsupported_fields() {
+        let src = r#"
+        #[derive(Eq, Hash)]
+        struct Foo {
+            x: Field,
+        }
+
+        #[derive(Default)]
+        struct Bar {
+            f: fn() -> Field,
+        }
+
+        fn main() {}
+        "#;
+        // The stdlib is not available here so only errors from generating the impls are checked
+        let errors = get_program_errors(src);
+        let derive_errors: Vec<_> = errors
+            .iter()
+            .filter_map(|(error, _)| match error {
+                CompilationError::DefinitionError(DefCollectorErrorKind::UnknownDeriveTrait {
+                    name,
+                    ..
+                }) => Some(name.clone()),
+                CompilationError::DefinitionError(DefCollectorErrorKind::CannotDerive {
+                    trait_name,
+                    field,
+                    ..
+                }) => Some(format!("{trait_name} {field}")),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(derive_errors, vec!["Hash".to_string(), "Default f".to_string()]);
+    }
+
+    #[test]
+    fn overflowing_literals_are_checked_against_inferred_types() {
+        let src = r#"
+        fn double(x: u8) -> u8 {
+            x * 2
+        }
+
+        fn main() {
+            let _ = double(256);
+            let _: i8 = -128;
+            let _: i8 = 128;
+            let _: u128 = 0xffffffffffffffffffffffffffffffff;
+        }
+        "#;
+        let errors = get_program_errors(src);
+        assert_eq!(errors.len(), 2, "Expected 2 errors, got: {:?}", errors);
+
+        let ranges = vecmap(&errors, |(error, _)| match error {
+            CompilationError::TypeError(TypeCheckError::OverflowingLiteral {
+                literal,
+                range,
+                ..
+            }) => (literal.clone(), range.clone()),
+            _ => panic!("Expected an OverflowingLiteral error, got: {:?}", error),
+        });
+        assert_eq!(ranges[0], ("256".to_string(), "0..=255".to_string()));
+        assert_eq!(ranges[1], ("128".to_string(), "-128..=127".to_string()));
+    }
+
+    #[test]
+    fn negative_literals_are_typed_as_negative_numbers() {
+        let src = r#"
+        fn main() {
+            let x: i8 = -5;
+            let _: i8 = x * -2 - 118;
+            let _: u8 = -1;
+        }
+        "#;
+        let errors = get_program_errors(src);
+        assert_eq!(errors.len(), 1, "Expected 1 error, got: {:?}", errors);
+        assert!(matches!(
+            &errors[0].0,
+            CompilationError::TypeError(TypeCheckError::OverflowingLiteral { literal, .. })
+                if literal == "-1"
+        ));
+
+        let (_program, context, _errors) = get_program("fn main() -> pub i8 { -5 }");
+        let main_func_id = context.def_interner.find_function("main").unwrap();
+        let program = monomorphize(main_func_id, &context.def_interner).unwrap();
+        assert!(program.to_string().contains("251"), "-5 should be 251 in two's complement");
+    }
+
+    #[test]
+    fn unused_imports_are_reported() {
+        let src = r#"
+        mod foo {
+            struct Bar { x: Field }
+            pub fn baz() -> Field { 1 }
+            pub fn unused() {}
+        }
+
+        mod reexport {
+            use crate::foo::Bar;
+        }
+
+        use foo::baz;
+        use foo::unused;
+        use foo::Bar as Alias;
+
+        fn main(x: Field) {
+            let _ = crate::reexport::Bar { x: x };
+            assert(baz() == x);
+        }
+        "#;
+        let errors = get_program_errors(src);
+        let unused = vecmap(&errors, |(error, _)| match error {
+            CompilationError::DefinitionError(DefCollectorErrorKind::UnusedImport {
+                ident,
+                ..
+            }) => ident.0.contents.clone(),
+            _ => panic!("Expected only unused import warnings, got: {:?}", error),
+        });
+        assert_eq!(unused, vec!["unused".to_string(), "Alias".to_string()]);
+    }
+
+    #[test]
+    fn imports_used_by_trait_paths_are_not_reported() {
+        let src = r#"
+        mod traits {
+            trait Double { fn double(self) -> Self; }
+            trait Halve { fn halve(self) -> Self; }
+            trait Negate { fn negate(self) -> Self; }
+        }
+
+        use traits::Double;
+        use traits::Halve;
+        use traits::Negate;
+
+        struct Foo { x: Field }
+
+        impl Double for Foo {
+            fn double(self) -> Self { Foo { x: self.x * 2 } }
+        }
+
+        impl Halve for Foo where Foo: Double {
+            fn halve(self) -> Self { Foo { x: self.x / 2 } }
+        }
+
+        fn quadruple<T>(value: T) -> T where T: Double {
+            value.double().double()
+        }
+
+        fn main(x: Field) {
+            assert(quadruple(Foo { x: x }).halve().x == x * 2);
+        }
+        "#;
+        let errors = get_program_errors(src);
+        let unused = vecmap(&errors, |(error, _)| match error {
+            CompilationError::DefinitionError(DefCollectorErrorKind::UnusedImport {
+                ident,
+                ..
+            }) => ident.0.contents.clone(),
+            _ => panic!("Expected only unused import warnings, got: {:?}", error),
+        });
+        assert_eq!(unused, vec!["Negate".to_string()]);
+    }
+
+    #[test]
+    fn custom_prelude_is_in_scope_in_every_module() {
+        let src = r#"
+        #![prelude(crate::dsl)]
+
+        mod dsl {
+            use crate::shapes::Point;
+
+            pub fn origin() -> Point {
+                Point { x: 0, y: 0 }
+            }
+        }
+
+        mod shapes {
+            struct Point { x: Field, y: Field }
+        }
+
+        mod geometry {
+            pub fn norm(point: Point) -> Field {
+                point.x * point.x + point.y * point.y
+            }
+        }
+
+        fn origin() -> Field {
+            1
+        }
+
+        fn main() {
+            assert(geometry::norm(dsl::origin()) == 0);
+            // Items of the crate shadow those of the prelude
+            assert(origin() == 1);
+        }
+        "#;
+        let errors = get_program_errors(src);
+        assert!(errors.is_empty(), "Expected no errors, got: {:?}", errors);
+    }
+
+    #[test]
+    fn imports_shadow_prelude_items() {
+        let src = r#"
+        #![prelude(crate::dsl)]
+
+        mod dsl {
+            struct Point { x: Field }
+        }
+
+        mod shapes {
+            struct Point { x: Field, y: Field }
+        }
+
+        mod geometry {
+            use crate::shapes::Point;
+
+            pub fn origin() -> Point {
+                Point { x: 0, y: 0 }
+            }
+        }
+
+        mod other {
+            // Importing an item of the prelude explicitly is not a duplicate definition
+            use crate::dsl::Point;
+
+            pub fn origin() -> Point {
+                Point { x: 0 }
+            }
+        }
+
+        fn main() {
+            assert(geometry::origin().y == other::origin().x);
+        }
+        "#;
+        let errors = get_program_errors(src);
+        assert!(errors.is_empty(), "Expected no errors, got: {:?}", errors);
+    }
+
+    #[test]
+    fn prelude_must_be_a_module() {
+        let src = r#"
+        #![prelude(crate::Point)]
+
+        struct Point { x: Field }
+
+        fn main() {}
+        "#;
+        let errors = get_program_errors(src);
+        assert_eq!(errors.len(), 1, "Expected 1 error, got: {:?}", errors);
+        assert!(matches!(
+            &errors[0].0,
+            CompilationError::DefinitionError(DefCollectorErrorKind::PreludeNotAModule { .. })
+        ));
+
+        let errors = get_program_errors("#![prelude(crate::missing)]\nfn main() {}");
+        assert_eq!(errors.len(), 1, "Expected 1 error, got: {:?}", errors);
+        assert!(matches!(
+            &errors[0].0,
+            CompilationError::DefinitionError(DefCollectorErrorKind::PathResolutionError(_))
+        ));
+    }
+
+    #[test]
+    fn misspelled_names_get_suggestions() {
+        let src = r#"
+        struct Foo { value: Field }
+
+        impl Foo {
+            pub fn double(self) -> Field { self.value * 2 }
+        }
+
+        pub fn helper(counter: Field) -> Field {
+            counter + countr
+        }
+
+        fn main(x: Field) {
+            let foo = Foo { valu: x, value: x };
+            let _ = foo.vale;
+            let _ = foo.doubel();
+            let _ = helpr(x);
+        }
+        "#;
+        let errors = get_program_errors(src);
+
+        let mut suggestions = vecmap(&errors, |(error, _)| match error {
+            CompilationError::ResolveError(ResolverError::VariableNotDeclared {
+                name,
+                suggestion,
+                ..
+            }) => (name.clone(), suggestion.clone()),
+            CompilationError::ResolveError(ResolverError::NoSuchField {
+                field,
+                suggestion,
+                ..
+            }) => (field.0.contents.clone(), suggestion.clone()),
+            CompilationError::TypeError(TypeCheckError::AccessUnknownMember {
+                field_name,
+                suggestion,
+                ..
+            }) => (field_name.clone(), suggestion.clone()),
+            CompilationError::TypeError(TypeCheckError::UnresolvedMethodCall {
+                method_name,
+                suggestion,
+                ..
+            }) => (method_name.clone(), suggestion.clone()),
+            _ => panic!("Unexpected error: {:?}", error),
+        });
+        suggestions.sort();
+
+        let expected = [
+            ("countr", "counter"),
+            ("doubel", "double"),
+            ("helpr", "helper"),
+            ("vale", "value"),
+            ("valu", "value"),
+        ];
+        let expected =
+            vecmap(expected, |(name, suggestion)| (name.to_string(), Some(suggestion.to_string())));
+        assert_eq!(suggestions, expected);
+    }
+
+    #[test]
+    fn diagnostics_have_error_codes() {
+        let src = r#"
+        fn main(a: u8, b: u16) {
+            let _ = x;
+            let _: Field = true;
+            let _ = a + b;
+        }
+        "#;
+        let errors = get_program_errors(src);
+
+        let mut codes = vecmap(errors, |(error, _)| CustomDiagnostic::from(error).code.unwrap());
+        codes.sort();
+        assert_eq!(codes, vec![E0202, E0300, E0309]);
+    }
+
+    #[test]
+    fn related_diagnostics_have_distinct_error_codes() {
+        let src = r#"
+        struct Foo { x: Field, y: Field }
+
+        fn main(a: Field, b: Field, c: u8) {
+            let _ = Foo { x: 1 };
+            let _ = Foo { x: 1, y: 2, z: 3 };
+            let _ = Foo { x: 1, x: 2, y: 3 };
+            assert(a < b);
+            let _ = c + a;
+        }
+        "#;
+        let errors = get_program_errors(src);
+
+        let mut codes = vecmap(errors, |(error, _)| CustomDiagnostic::from(error).code.unwrap());
+        codes.sort();
+        assert_eq!(codes, vec![E0205, E0223, E0224, E0316, E0320]);
+    }
+
+    #[test]
+    fn diagnostics_have_fixes() {
+        let src = r#"
+        mod foo {
+            pub fn bar() {}
+        }
+
+        use foo::bar;
+
+        fn main(a: u8) {
+            let x = 1;
+            x = 2;
+            let _: u32 = a;
+        }
+        "#;
+        let errors = get_program_errors(src);
+
+        let mut fixes = Vec::new();
+        for (error, _) in errors {
+            for fix in CustomDiagnostic::from(error).fixes {
+                let edits = vecmap(fix.edits, |edit| {
+                    (edit.span.start() as usize, edit.span.end() as usize, edit.replacement)
+                });
+                fixes.push((fix.message, edits));
+            }
+        }
+        fixes.sort();
+
+        let import = src.find("use foo::bar;").unwrap();
+        let declaration = src.find("x = 1").unwrap();
+        let cast = src.find("a;").unwrap() + 1;
+        let expected = vec![
+            ("cast to `u32`".to_string(), vec![(cast, cast, " as u32".to_string())]),
+            ("make `x` mutable".to_string(), vec![(declaration, declaration, "mut ".to_string())]),
+            (
+                "remove the unused import".to_string(),
+                vec![(import, import + "use foo::bar;".len(), String::new())],
+            ),
+        ];
+        assert_eq!(fixes, expected);
+    }
+
+    #[test]
+    fn only_widening_casts_are_suggested() {
+        let src = r#"
+        fn main(a: u8, b: u64, c: i8, d: Field) {
+            let _: u64 = a;
+            let _: i16 = a;
+            let _: Field = a;
+            let _: u8 = b;
+            let _: i8 = a;
+            let _: u16 = c;
+            let _: Field = c;
+            let _: u8 = d;
+        }
+        "#;
+        let errors = get_program_errors(src);
+        assert_eq!(errors.len(), 8, "{errors:?}");
+
+        let fixes: Vec<_> = errors
+            .into_iter()
+            .flat_map(|(error, _)| CustomDiagnostic::from(error).fixes)
+            .map(|fix| fix.message)
+            .collect();
+        assert_eq!(fixes, vec!["cast to `u64`", "cast to `i16`", "cast to `Field`"]);
+    }
+
+    #[test]
+    fn api_lists_items_with_their_types() {
+        let src = r#"
+        struct Foo { x: Field }
+        type Bar = Foo;
+        global G = 3;
+
+        mod inner {
+            pub fn helper(x: u8) -> u8 { x }
+        }
+
+        fn main(x: Field) -> pub Field {
+            let foo: Bar = Foo { x };
+            foo.x + G
+        }
+        "#;
+        let (_, context, errors) = get_program(src);
+        assert!(errors.is_empty(), "{:?}", errors);
+
+        let items = api::items(&context, *context.root_crate_id());
+        let mut names = vecmap(&items, |item| (item.name.as_str(), item.kind));
+        names.sort_by_key(|(name, _)| *name);
+        assert_eq!(
+            names,
+            vec![
+                ("Bar", ItemKind::TypeAlias),
+                ("Foo", ItemKind::Struct),
+                ("G", ItemKind::Global),
+                ("helper", ItemKind::Function),
+                ("inner", ItemKind::Module),
+                ("main", ItemKind::Function),
+            ]
+        );
+
+        let helper = items.iter().find(|item| item.name == "helper").unwrap();
+        assert_eq!(helper.typ.as_deref(), Some("fn(u8) -> u8"));
+    }
+
+    #[test]
+    fn unreachable_functions_are_reported() {
+        let src = r#"
+        struct Foo { x: Field }
+
+        impl Foo {
+            fn get(self) -> Field { helper(self.x) }
+            fn unused_method(self) -> Field { self.x }
+        }
+
+        fn helper(x: Field) -> Field { x }
+        fn dead() -> Field { also_dead() }
+        fn also_dead() -> Field { 1 }
+        fn only_in_global() -> Field { 2 }
+        fn only_in_test() {}
+
+        global G = only_in_global();
+
+        pub fn library_function() {}
+
+        fn main(x: Field) {
+            let foo = Foo { x };
+            assert(foo.get() == x + G);
+        }
+
+        #[test]
+        fn test_something() {
+            only_in_test();
+        }
+        "#;
+        let errors = get_program_errors(src);
+        let mut unreachable = vecmap(&errors, |(error, _)| match error {
+            CompilationError::DefinitionError(DefCollectorErrorKind::UnreachableFunction {
+                ident,
+            }) => ident.0.contents.clone(),
+            _ => panic!("Expected only unreachable function warnings, got: {:?}", error),
+        });
+        unreachable.sort();
+        assert_eq!(unreachable, vec!["also_dead", "dead", "unused_method"]);
+    }
+
+    #[test]
+    fn lint_levels_are_scoped_by_attributes() {
+        let src = r#"
+        #![deny(unused_variables)]
+
+        #[allow(dead_code)]
+        fn allowed_dead() {}
+
+        fn warned_dead() {}
+
+        #[warn(unused_variables)]
+        fn warned() { let x = 1; }
+
+        mod inner {
+            #![allow(unused_variables)]
+            pub fn allowed() { let y = 2; }
+        }
+
+        fn main() {
+            let z = 3;
+            warned();
+            inner::allowed();
+        }
+        "#;
+        let (_, context, errors) = get_program(src);
+        let diagnostics = errors.into_iter().filter_map(|(error, file_id)| {
+            context.lint_levels.apply(CustomDiagnostic::from(error).in_file(file_id))
+        });
+        let mut reported = vecmap(diagnostics, |diagnostic| {
+            (diagnostic.diagnostic.message, diagnostic.diagnostic.is_error())
+        });
+        reported.sort();
+
+        assert_eq!(
+            reported,
+            vec![
+                ("function warned_dead is never used".to_string(), false),
+                ("unused variable x".to_string(), false),
+                ("unused variable z".to_string(), true),
+            ]
+        );
+    }
+
+    #[test]
+    fn shadowed_variables_are_only_reported_when_enabled() {
+        let src = r#"
+        fn allowed(x: Field) -> Field {
+            let x = x + 1;
+            x
+        }
+
+        #[warn(shadowed_variables)]
+        fn warned(x: Field) -> Field {
+            let y = x;
+            let x = y + 1;
+            let (y, _z) = (x, 2);
+            if x == 0 {
+                let x = 3;
+                assert(x == 3);
+            }
+            y
+        }
+
+        fn main() {
+            let _ = allowed(1);
+            let _ = warned(2);
+        }
+        "#;
+        let (_, context, errors) = get_program(src);
+        let diagnostics = errors.into_iter().filter_map(|(error, file_id)| {
+            context.lint_levels.apply(CustomDiagnostic::from(error).in_file(file_id))
+        });
+        let reported = vecmap(diagnostics, |diagnostic| {
+            let diagnostic = diagnostic.diagnostic;
+            assert!(diagnostic.is_warning());
+            // The shadowing binding is labelled first, then the binding it shadows
+            let spans = vecmap(&diagnostic.secondaries, |label| {
+                let span = label.span;
+                (span.start() as usize, &src[span.start() as usize..span.end() as usize])
+            });
+            assert!(spans[0].0 > spans[1].0);
+            (diagnostic.message, spans[0].1, spans[1].1)
+        });
+
+        assert_eq!(
+            reported,
+            vec![
+                ("`x` shadows an earlier binding".to_string(), "x", "x"),
+                ("`y` shadows an earlier binding".to_string(), "y", "y"),
+                ("`x` shadows an earlier binding".to_string(), "x", "x"),
+            ]
+        );
+    }
+
+    #[test]
+    fn const_fns_and_globals_size_main_parameters() {
+        let src = r#"
+        const fn words_for(bits: u64) -> u64 {
+            (bits + 31) / 32
+        }
+
+        const fn double(x: u64) -> u64 {
+            if x == 0 { 1 } else { x * 2 }
+        }
+
+        global WORDS = double(words_for(100));
+
+        fn main(_x: [Field; WORDS]) {}
+        "#;
+        let (_program, context, errors) = get_program(src);
+        assert!(errors.is_empty(), "Expected no errors, got: {:?}", errors);
+
+        let main_id = context.def_interner.find_function("main").unwrap();
+        let (_, typ, _) = &context.def_interner.function_meta(&main_id).parameters.0[0];
+        match typ {
+            crate::Type::Array(length, _) => assert_eq!(length.evaluate_to_u64(), Some(8)),
+            other => panic!("Expected an array parameter, got {other}"),
+        }
+    }
+
+    #[test]
+    fn non_const_fns_cannot_size_arrays() {
+        let src = r#"
+        fn size() -> u64 { 3 }
+
+        const fn recurse(x: u64) -> u64 { recurse(x) }
+
+        global SIZE = size();
+        global FOREVER = recurse(1);
+
+        fn main(_x: [Field; SIZE], _y: [Field; FOREVER]) {}
+        "#;
+        let errors = get_program_errors(src);
+        assert_eq!(errors.len(), 2, "Expected 2 errors, got: {:?}", errors);
+        assert!(matches!(
+            &errors[0].0,
+            CompilationError::ResolveError(ResolverError::CallToNonConstFunction { name, .. })
+                if name == "size"
+        ));
+        assert!(matches!(
+            &errors[1].0,
+            CompilationError::ResolveError(ResolverError::ConstEvaluationTooDeep { name, .. })
+                if name == "recurse"
+        ));
+    }
+
+    #[test]
+    fn const_evaluation_respects_declared_types() {
+        let src = r#"
+        const fn double(x: u8) -> u8 {
+            x * 2
+        }
+
+        const fn identity(x: u8) -> u8 {
+            x
+        }
+
+        global SMALL: u8 = 200;
+
+        fn main(
+            _a: [Field; double(100)],
+            _b: [Field; double(SMALL)],
+            _c: [Field; identity(256)],
+        ) {}
+        "#;
+        let (_program, context, errors) = get_program(src);
+        let out_of_range = vecmap(&errors, |(error, _)| match error {
+            CompilationError::ResolveError(ResolverError::ConstValueOutOfRange {
+                value, ..
+            }) => *value,
+            other => panic!("Expected only out of range errors, got: {other:?}"),
+        });
+        assert_eq!(out_of_range, vec![400, 256]);
+
+        let main_id = context.def_interner.find_function("main").unwrap();
+        let (_, typ, _) = &context.def_interner.function_meta(&main_id).parameters.0[0];
+        match typ {
+            crate::Type::Array(length, _) => assert_eq!(length.evaluate_to_u64(), Some(200)),
+            other => panic!("Expected an array parameter, got {other}"),
+        }
+    }
+
+    #[test]
+    fn comptime_code_is_replaced_by_its_value() {
+        let src = r#"
+        comptime fn square(x: Field) -> Field {
+            x * x
+        }
+
+        global TABLE: [Field; 4] = comptime {
+            let mut table = [0; 4];
+            for i in 0..4 {
+                table[i] = square(i);
+            }
+            table
+        };
+
+        fn main() -> pub Field {
+            TABLE[3] + square(2)
+        }
+        "#;
+        let (_program, context, errors) = get_program(src);
+        assert!(errors.is_empty(), "Expected no errors, got: {:?}", errors);
+
+        let interner = &context.def_interner;
+        let integer = |expr| match interner.expression(&expr) {
+            HirExpression::Literal(HirLiteral::Integer(value, false)) => value.to_u128(),
+            other => panic!("Expected an integer literal, got {other:?}"),
+        };
+
+        let main_id = interner.find_function("main").unwrap();
+        let statements = interner.function(&main_id).block(interner).statements().to_vec();
+        let infix = match interner.statement(&statements[0]) {
+            HirStatement::Expression(expr) => match interner.expression(&expr) {
+                HirExpression::Infix(infix) => infix,
+                other => panic!("Expected an infix expression, got {other:?}"),
+            },
+            other => panic!("Expected an expression statement, got {other:?}"),
+        };
+        assert_eq!(integer(infix.rhs), 4);
+
+        let table = match interner.expression(&infix.lhs) {
+            HirExpression::Index(index) => match interner.expression(&index.collection) {
+                HirExpression::Ident(ident, _) => match interner.definition(ident.id).kind {
+                    DefinitionKind::Global(expr) => interner.expression(&expr),
+                    ref other => panic!("Expected a global, got {other:?}"),
+                },
+                other => panic!("Expected an identifier, got {other:?}"),
+            },
+            other => panic!("Expected an index, got {other:?}"),
+        };
+        match table {
+            HirExpression::Literal(HirLiteral::Array(HirArrayLiteral::Standard(elements))) => {
+                assert_eq!(vecmap(elements, integer), vec![0, 1, 4, 9]);
+            }
+            other => panic!("Expected an array literal, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn comptime_code_cannot_use_runtime_values() {
+        let src = r#"
+        fn main(x: Field) -> pub Field {
+            comptime { x + 1 }
+        }
+        "#;
+        let errors = get_program_errors(src);
+        assert_eq!(errors.len(), 1, "Expected 1 error, got: {:?}", errors);
+        assert!(matches!(
+            &errors[0].0,
+            CompilationError::ComptimeError(ComptimeError::NonComptimeValue { name, .. })
+                if name == "x"
+        ));
+    }
+
+    #[test]
+    fn comptime_errors_are_reported() {
+        let src = r#"
+        comptime fn checked(x: u8) -> u8 {
+            assert(x < 10, "too big");
+            x
+        }
+
+        comptime fn double(x: u8) -> u8 {
+            x * 2
+        }
+
+        fn main() -> pub u8 {
+            checked(12) + double(200)
+        }
+        "#;
+        let errors = get_program_errors(src);
+        assert_eq!(errors.len(), 2, "Expected 2 errors, got: {:?}", errors);
+        assert!(matches!(
+            &errors[0].0,
+            CompilationError::ComptimeError(ComptimeError::AssertionFailed {
+                message: Some(message),
+                ..
+            }) if message == "too big"
+        ));
+        assert!(matches!(
+            &errors[1].0,
+            CompilationError::ComptimeError(ComptimeError::EvaluationFailed { reason, .. })
+                if reason == "attempt to multiply with overflow"
+        ));
+    }
+
+    #[test]
+    fn comptime_array_comprehensions_are_evaluated() {
+        let src = r#"
+        comptime fn square(x: u8) -> u8 {
+            x * x
+        }
+
+        global N: u8 = 4;
+
+        fn main() -> pub u8 {
+            let squares: [u8; 4] = comptime [square(i) for i in 0..N];
+            let offsets = comptime [[i + j for j in 0..2] for i in 1..3];
+            squares[3] + offsets[1][1]
+        }
+        "#;
+        let (_program, context, errors) = get_program(src);
+        assert!(errors.is_empty(), "Expected no errors, got: {:?}", errors);
+
+        let interner = &context.def_interner;
+        let main_id = interner.find_function("main").unwrap();
+        let statements = interner.function(&main_id).block(interner).statements().to_vec();
+        let squares = match interner.statement(&statements[0]) {
+            HirStatement::Let(let_statement) => interner.expression(&let_statement.expression),
+            other => panic!("Expected a let statement, got {other:?}"),
+        };
+        match squares {
+            HirExpression::Literal(HirLiteral::Array(HirArrayLiteral::Standard(elements))) => {
+                let elements = vecmap(elements, |expr| match interner.expression(&expr) {
+                    HirExpression::Literal(HirLiteral::Integer(value, false)) => value.to_u128(),
+                    other => panic!("Expected an integer literal, got {other:?}"),
+                });
+                assert_eq!(elements, vec![0, 1, 4, 9]);
+            }
+            other => panic!("Expected an array literal, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn array_comprehension_bounds_must_be_known_at_compile_time() {
+        let src = r#"
+        fn main(x: Field, n: Field) -> pub Field {
+            let xs = [x * i for i in 0..n];
+            xs[0]
+        }
+        "#;
+        let errors = get_program_errors(src);
+        assert_eq!(errors.len(), 1, "Expected 1 error, got: {:?}", errors);
+        assert!(matches!(
+            &errors[0].0,
+            CompilationError::ResolveError(ResolverError::NoSuchNumericTypeVariable { path })
+                if path.to_string() == "n"
+        ));
+    }
+
+    #[test]
+    fn global_initializers_are_only_evaluated_when_compile_time_code_uses_them() {
+        let src = r#"
+        fn compute_table() -> [u8; 4] {
+            let mut table = [0; 4];
+            for i in 0..4 {
+                table[i] = (i * 3) as u8;
+            }
+            table
+        }
+
+        global TABLE: [u8; 4] = compute_table();
+        global LAST = comptime { TABLE[3] + 1 };
+
+        fn main() -> pub u8 {
+            LAST + TABLE[0]
+        }
+        "#;
+        let (_program, context, errors) = get_program(src);
+        assert!(errors.is_empty(), "Expected no errors, got: {:?}", errors);
+
+        let interner = &context.def_interner;
+        let globals = interner.get_all_globals();
+        let global_value = |name: &str| {
+            let (stmt_id, _) =
+                globals.iter().find(|(_, global)| global.ident.0.contents == name).unwrap();
+            match interner.statement(stmt_id) {
+                HirStatement::Let(let_stmt) => interner.expression(&let_stmt.expression),
+                other => panic!("Expected a let statement, got {other:?}"),
+            }
+        };
+
+        // `TABLE` is evaluated for `LAST`, but is otherwise compiled where it is used
+        match global_value("TABLE") {
+            HirExpression::Call(_) => (),
+            other => panic!("Expected a call, got {other:?}"),
+        }
+        match global_value("LAST") {
+            HirExpression::Literal(HirLiteral::Integer(value, false)) => {
+                assert_eq!(value.to_u128(), 10);
+            }
+            other => panic!("Expected an integer literal, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn globals_which_cannot_be_evaluated_at_compile_time_are_allowed() {
+        let src = r#"
+        #[foreign(sha256)]
+        fn sha256<N>(_input: [u8; N]) -> [u8; 32] {}
+
+        global BIG: u128 = 1 << 100;
+        global HASH = sha256([1, 2, 3]);
+
+        fn main() -> pub u8 {
+            assert(BIG > 0);
+            HASH[0]
+        }
+        "#;
+        let errors = get_program_errors(src);
+        assert!(errors.is_empty(), "Expected no errors, got: {:?}", errors);
+    }
+
+    #[test]
+    fn static_asserts_are_evaluated_at_compile_time() {
+        let src = r#"
+        global WIDTH = 16;
+        global HEIGHT = 12;
+
+        static_assert(WIDTH % 8 == 0, "WIDTH must be a whole number of bytes");
+        static_assert(HEIGHT % 8 == 0, "HEIGHT must be a whole number of bytes");
+
+        fn main() -> pub Field {
+            WIDTH * HEIGHT
+        }
+        "#;
+        let errors = get_program_errors(src);
+        assert_eq!(errors.len(), 1, "Expected 1 error, got: {:?}", errors);
+        assert!(matches!(
+            &errors[0].0,
+            CompilationError::ComptimeError(ComptimeError::AssertionFailed {
+                message: Some(message),
+                ..
+            }) if message == "HEIGHT must be a whole number of bytes"
+        ));
+    }
+
+    #[test]
+    fn static_assert_items_cannot_use_generics() {
+        // Numeric generics are only in scope within the function or type declaring them, so
+        // they have to be checked by calling `std::static_assert` within a generic function.
+        let src = r#"
+        struct Words<N> {
+            bytes: [u8; N],
+        }
+
+        static_assert(N % 8 == 0, "bytes must fill a whole number of words");
+        "#;
+        let errors = get_program_errors(src);
+        assert!(
+            errors.iter().any(|(error, _)| matches!(
+                error,
+                CompilationError::ResolverError(ResolverError::VariableNotDeclared { name, .. })
+                    if name == "N"
+            )),
+            "Expected N to be undeclared, got: {errors:?}"
+        );
+    }
+
+    #[test]
+    fn static_assert_messages_must_be_string_literals() {
+        let src = r#"
+        global WIDTH = 12;
+        static_assert(WIDTH % 8 == 0, f"{WIDTH} is not a whole number of bytes");
+        "#;
+        let errors = get_program_errors(src);
+        assert_eq!(errors.len(), 1, "Expected 1 error, got: {:?}", errors);
+        match &errors[0].0 {
+            CompilationError::ParseError(parser_error) => assert!(matches!(
+                parser_error.reason(),
+                Some(ParserErrorReason::StaticAssertMessageNotString)
+            )),
+            other => panic!("Expected a parser error, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn sorted_structs_are_compiled_with_their_fields_ordered_by_name() {
+        let src = r#"
+        #[repr(sorted)]
+        struct Point { y: Field, x: Field, label: u8 }
+
+        fn main(point: Point) -> pub Field {
+            let Point { x, y, label } = point;
+            let mut moved = point;
+            moved.x = 2;
+            x + y + label as Field + moved.y
+        }
+        "#;
+        let (_, context, errors) = get_program(src);
+        assert!(errors.is_empty(), "Expected no errors, got: {:?}", errors);
+
+        let def_map = context.def_map(context.root_crate_id()).unwrap();
+        let root = &def_map.modules()[def_map.root().0];
+        let struct_id = root
+            .type_definitions()
+            .find_map(|id| match id {
+                ModuleDefId::TypeId(struct_id) => Some(struct_id),
+                _ => None,
+            })
+            .unwrap();
+
+        // The struct keeps its fields in declaration order, and only its layout is sorted
+        let point = context.def_interner.get_struct(struct_id);
+        let point = point.borrow();
+        assert_eq!(point.layout, StructLayout::Sorted);
+        assert_eq!(vecmap(point.get_fields(&[]), |(name, _)| name), vec!["y", "x", "label"]);
+        let sorted = point.get_fields_in_layout_order(&[]);
+        assert_eq!(vecmap(sorted, |(name, _)| name), vec!["label", "x", "y"]);
+        assert_eq!(vecmap(0..3, |index| point.layout_position(index)), vec![2, 1, 0]);
+
+        let main_func_id = context.def_interner.find_function("main").unwrap();
+        let program = monomorphize(main_func_id, &context.def_interner).unwrap();
+        assert_eq!(program.main().parameters[0].3.to_string(), "(u8, Field, Field)");
+        let main = program.main().to_string();
+        assert!(main.contains(".1 = 2"), "Expected `x` to be assigned at 1 in:\n{main}");
+        assert!(main.contains(".2)"), "Expected `y` to be read from 2 in:\n{main}");
+    }
+
+    #[test]
+    fn packed_structs_are_passed_to_oracles_as_arrays() {
+        let src = r#"
+        #[repr(packed)]
+        struct Note { owner: Field, amount: u64, flags: [bool; 2] }
+
+        #[oracle(store)]
+        unconstrained fn store_oracle(_note: Note) -> Note {}
+
+        unconstrained fn store(note: Note) -> Note {
+            store_oracle(note)
+        }
+
+        fn main(note: Note) -> pub u64 {
+            store(note).amount
+        }
+        "#;
+        let (_, context, errors) = get_program(src);
+        assert!(errors.is_empty(), "Expected no errors, got: {:?}", errors);
+
+        let main_func_id = context.def_interner.find_function("main").unwrap();
+        let program = monomorphize(main_func_id, &context.def_interner).unwrap();
+        let store = program.functions.iter().find(|function| function.name == "store").unwrap();
+        let store = store.to_string();
+
+        // The argument is flattened into an array of fields...
+        for element in [".0 as Field)", ".1 as Field)", ".2[0] as Field)", ".2[1] as Field)"] {
+            assert!(store.contains(element), "Expected `{element}` in:\n{store}");
+        }
+        // ...and the returned array is cast back into the fields of the struct
+        for element in ["[0] as Field)", "[1] as u64)", "[2] as bool)", "[3] as bool)"] {
+            assert!(store.contains(element), "Expected `{element}` in:\n{store}");
+        }
+    }
+
+    #[test]
+    fn repr_is_only_allowed_on_structs() {
+        let src = r#"
+        #[repr(sorted)]
+        fn main() {}
+        "#;
+        let errors = get_program_errors(src);
+        assert_eq!(errors.len(), 1, "Expected 1 error, got: {:?}", errors);
+        match &errors[0].0 {
+            CompilationError::ParseError(parser_error) => assert!(matches!(
+                parser_error.reason(),
+                Some(ParserErrorReason::ReprOnlyAllowedOnStruct)
+            )),
+            other => panic!("Expected a parser error, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn inline_attributes_reach_monomorphized_functions() {
+        let src = r#"
+        #[inline(always)]
+        unconstrained fn always(x: Field) -> Field { x + 1 }
+
+        #[inline(never)]
+        unconstrained fn never(x: Field) -> Field { x + 2 }
+
+        fn main(x: Field) -> pub Field {
+            let f = |y: Field| y * 2;
+            f(always(x) + never(x))
+        }
+        "#;
+        let (_program, context, errors) = get_program(src);
+        assert!(errors.is_empty(), "Expected no errors, got: {:?}", errors);
+
+        let main_func_id = context.def_interner.find_function("main").unwrap();
+        let program = monomorphize(main_func_id, &context.def_interner).unwrap();
+        let inline_types: BTreeMap<_, _> = program
+            .functions
+            .iter()
+            .map(|function| (function.name.as_str(), function.inline_type))
+            .collect();
+        assert_eq!(inline_types["always"], InlineType::Always);
+        assert_eq!(inline_types["never"], InlineType::Never);
+        assert_eq!(inline_types["main"], InlineType::Default);
+        assert_eq!(inline_types["lambda"], InlineType::Default);
+    }
+
+    #[test]
+    fn inline_never_marks_constrained_functions_as_fold_boundaries() {
+        let src = r#"
+        #[inline(never)]
+        fn double(x: Field) -> Field { x * 2 }
+
+        fn main(x: Field) -> pub Field { double(x) }
+        "#;
+        let (_program, context, errors) = get_program(src);
+        assert!(errors.is_empty(), "Expected no errors, got: {:?}", errors);
+
+        let main_func_id = context.def_interner.find_function("main").unwrap();
+        let program = monomorphize(main_func_id, &context.def_interner).unwrap();
+        let double = program.functions.iter().find(|function| function.name == "double");
+        assert_eq!(double.unwrap().inline_type, InlineType::Never);
+    }
+
+    #[test]
+    fn inline_is_only_allowed_on_functions() {
+        let src = r#"
+        #[inline(always)]
+        struct Foo {}
+
+        fn main() {}
+        "#;
+        let errors = get_program_errors(src);
+        assert_eq!(errors.len(), 1, "Expected 1 error, got: {:?}", errors);
+        match &errors[0].0 {
+            CompilationError::ParseError(parser_error) => assert!(matches!(
+                parser_error.reason(),
+                Some(ParserErrorReason::InlineOnlyAllowedOnFunction)
+            )),
+            other => panic!("Expected a parser error, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn env_is_replaced_with_the_value_of_the_environment_variable() {
+        let src = r#"
+        global DEPTH = std::env!("TREE_DEPTH");
+        global BUILD = std::env!("BUILD_ID");
+
+        fn main(leaves: [Field; DEPTH]) -> pub Field {
+            let _build: str<6> = BUILD;
+            leaves[0]
+        }
+        "#;
+        let environment = [("TREE_DEPTH", "0x4"), ("BUILD_ID", "v1.2.3"), ("UNUSED", "1")];
+        let (_program, context, errors) = get_program_with(src, |interner| {
+            let environment =
+                environment.iter().map(|(name, value)| (name.to_string(), value.to_string()));
+            interner.set_environment(environment);
+        });
+        assert!(errors.is_empty(), "Expected no errors, got: {:?}", errors);
+
+        let main_func_id = context.def_interner.find_function("main").unwrap();
+        let parameter_types = context.def_interner.function_meta(&main_func_id).parameters.0;
+        assert_eq!(parameter_types[0].1.to_string(), "[Field; 4]");
+
+        let reads = context.def_interner.environment_reads();
+        let reads = vecmap(reads, |(name, value)| (name.as_str(), value.as_str()));
+        assert_eq!(reads, vec![("BUILD_ID", "v1.2.3"), ("TREE_DEPTH", "0x4")]);
+    }
+
+    #[test]
+    fn env_reports_unset_environment_variables() {
+        let src = r#"
+        fn main() -> pub Field {
+            std::env!("NETWORK_ID")
+        }
+        "#;
+        let errors = get_program_errors(src);
+        assert_eq!(errors.len(), 1, "Expected 1 error, got: {:?}", errors);
+        assert!(matches!(
+            &errors[0].0,
+            CompilationError::ResolveError(ResolverError::EnvironmentVariableNotSet { name, .. })
+                if name == "NETWORK_ID"
+        ));
+    }
+
+    #[test]
+    fn only_env_is_a_macro() {
+        let src = r#"
+        fn main() {
+            let _ = format!("x");
+        }
+        "#;
+        let errors = get_program_errors(src);
+        assert!(errors.iter().any(|(error, _)| matches!(
+            error,
+            CompilationError::ParseError(error)
+                if matches!(error.reason(), Some(ParserErrorReason::UnknownMacro(_)))
+        )));
+    }
+
+    #[test]
+    fn include_bytes_and_include_str_are_replaced_with_the_file_contents() {
+        let path = std::env::temp_dir().join(format!("noir_include_{}.txt", std::process::id()));
+        std::fs::write(&path, "hello").unwrap();
+
+        let src = format!(
+            r#"
+        global GREETING = std::include_str!("{path}");
+
+        fn main() -> pub [u8; 5] {{
+            let _greeting: str<5> = GREETING;
+            std::include_bytes!("{path}")
+        }}
+        "#,
+            path = path.display()
+        );
+        let errors = get_program_errors(&src);
+        std::fs::remove_file(&path).unwrap();
+        assert!(errors.is_empty(), "Expected no errors, got: {:?}", errors);
+    }
+
+    #[test]
+    fn include_bytes_reads_binary_files() {
+        let path = std::env::temp_dir().join(format!("noir_include_{}.bin", std::process::id()));
+        std::fs::write(&path, [0xff, 0x00, 0xfe]).unwrap();
+
+        let src = format!(
+            r#"
+        global KEY = std::include_bytes!("{path}");
+
+        fn main() -> pub [u8; 3] {{
+            let _text = text();
+            KEY
+        }}
+
+        fn text() -> str<3> {{
+            std::include_str!("{path}")
+        }}
+        "#,
+            path = path.display()
+        );
+        let (_program, context, errors) = get_program_with(&src, |_| ());
+        std::fs::remove_file(&path).unwrap();
+
+        // Only `std::include_str!` needs the file to be valid UTF-8
+        assert_eq!(errors.len(), 1, "Expected 1 error, got: {:?}", errors);
+        assert!(matches!(
+            &errors[0].0,
+            CompilationError::ResolveError(ResolverError::IncludedFileNotUtf8 { .. })
+        ));
+        let included = vecmap(context.def_interner.included_files(), Clone::clone);
+        assert_eq!(included, vec![path]);
+    }
+
+    #[test]
+    fn include_reports_missing_files() {
+        let src = r#"
+        fn main() -> pub [u8; 3] {
+            std::include_bytes!("does/not/exist.bin")
+        }
+        "#;
+        let errors = get_program_errors(src);
+        assert_eq!(errors.len(), 1, "Expected 1 error, got: {:?}", errors);
+        assert!(matches!(
+            &errors[0].0,
+            CompilationError::ResolveError(ResolverError::IncludedFileNotFound { path, .. })
+                if path == "does/not/exist.bin"
+        ));
+    }
+
+    #[test]
+    fn assert_false_can_be_used_as_any_type() {
+        let src = r#"
+        fn main(x: Field) -> pub Field {
+            let y = if x == 0 { 1 } else { assert(false); };
+            y + abort()
+        }
+
+        fn abort() -> Field {
+            assert(false, "unreachable");
+        }
+        "#;
+        let errors = get_program_errors(src);
+        assert!(errors.is_empty(), "Expected no errors, got: {:?}", errors);
+    }
+
+    #[test]
+    fn assertions_with_constant_false_conditions_can_be_used_as_any_type() {
+        let src = r#"
+        global LIMIT: u32 = 8;
+        global ENABLED = !true;
+
+        fn main(x: Field) -> pub Field {
+            let a = if x == 0 { 1 } else { assert(LIMIT > 10, "limit is too low"); };
+            let b = if x == 1 { 2 } else { assert(ENABLED | (-1 == 1)); };
+            a + b
+        }
+        "#;
+        let errors = get_program_errors(src);
+        assert!(errors.is_empty(), "Expected no errors, got: {:?}", errors);
+    }
+
+    #[test]
+    fn assertions_with_runtime_conditions_are_not_used_as_any_type() {
+        let src = r#"
+        global mut LIMIT: u32 = 8;
+
+        fn main(x: Field) -> pub Field {
+            let a = if x == 0 { 1 } else { assert(x == 2); };
+            a
+        }
+
+        unconstrained fn limited(x: u32) -> u32 {
+            if x < 10 { x } else { assert(LIMIT > 10); }
+        }
+        "#;
+        let errors = get_program_errors(src);
+        assert_eq!(errors.len(), 2, "Expected 2 errors, got: {:?}", errors);
+        for (error, _) in &errors {
+            assert!(matches!(
+                error,
+                CompilationError::TypeError(TypeCheckError::Context { err, .. })
+                    if matches!(**err, TypeCheckError::TypeMismatch { .. })
+            ));
+        }
+    }
+
+    #[test]
+    fn comparison_assertions_only_report_their_values_when_enabled() {
+        let src = r#"
+        fn main(lhs: Field, rhs: (Field, bool)) {
+            assert_eq(rhs.0, lhs);
+        }
+        "#;
+        let assertion = |context: &Context| {
+            let interner = &context.def_interner;
+            let main_id = interner.find_function("main").unwrap();
+            let statements = interner.function(&main_id).block(interner).statements().to_vec();
+            interner.statement(&statements[0])
+        };
+
+        let (_program, context, errors) = get_program(src);
+        assert!(errors.is_empty(), "Expected no errors, got: {:?}", errors);
+        assert!(matches!(
+            assertion(&context),
+            HirStatement::Constrain(HirConstrainStatement(_, _, None))
+        ));
+
+        // Without the standard library, the call resolving the message can't be resolved
+        let (_program, context, _errors) =
+            get_program_with(src, |interner| interner.set_report_assert_values(true));
+        let interner = &context.def_interner;
+        let HirStatement::Semi(block) = assertion(&context) else {
+            panic!("Expected the values to be bound in a block");
+        };
+        let HirExpression::Block(block) = interner.expression(&block) else {
+            panic!("Expected a block");
+        };
+        let Some(HirStatement::Constrain(HirConstrainStatement(_, _, Some(message)))) =
+            block.statements().last().map(|stmt| interner.statement(stmt))
+        else {
+            panic!("Expected the block to end with an assertion with a message");
+        };
+        let HirAssertMessage::Dynamic(call) = &message else {
+            panic!("Expected a formatted message, got {message:?}");
+        };
+        let HirExpression::Call(call) = interner.expression(call) else {
+            panic!("Expected a call resolving the message");
+        };
+        assert!(matches!(
+            interner.expression(&call.arguments[0]),
+            HirExpression::Literal(HirLiteral::FmtStr(message, _))
+                if message == "left: {lhs}, right: {rhs}"
+        ));
+    }
+
+    #[test]
+    fn user_defined_assert_ne_is_not_an_assertion() {
+        let src = r#"
+        fn assert_ne(x: Field, y: Field) -> bool {
+            x != y
+        }
+
+        fn main(x: Field) -> pub bool {
+            let not_equal = assert_ne(x, 1);
+            let assert_ne = |y: Field| y == x;
+            not_equal & assert_ne(2)
+        }
+        "#;
+        let errors = get_program_errors(src);
+        assert!(errors.is_empty(), "Expected no errors, got: {:?}", errors);
+    }
+
+    #[test]
+    fn explicit_generics_must_match_the_function() {
+        let src = r#"
+        fn first<T, N>(array: [T; N]) -> T {
+            array[0]
+        }
+
+        fn main() {
+            let x = first::<Field, 2>([1, 2]);
+            let y = first::<Field>([x]);
+            let _ = first::<bool, 1>([y]);
+        }
+        "#;
+        let errors = get_program_errors(src);
+        assert_eq!(errors.len(), 2, "Expected 2 errors, got: {:?}", errors);
+        assert!(matches!(
+            &errors[0].0,
+            CompilationError::TypeError(TypeCheckError::IncorrectExplicitGenericCount {
+                expected: 2,
+                actual: 1,
+                ..
+            })
+        ));
+        assert!(matches!(
+            &errors[1].0,
+            CompilationError::TypeError(TypeCheckError::TypeMismatch { .. })
+        ));
+    }
+
+    #[test]
+    fn fields_of_describes_struct_fields_at_compile_time() {
+        let src = r#"
+        struct StructField { name: [u8], typ: [u8], size: u32 }
+
+        #[builtin(fields_of)]
+        comptime fn fields_of<T>() -> [StructField] {}
+
+        struct Point { x: Field, y: [u8; 3] }
+
+        global X_NAME = fields_of::<Point>()[0].name[0];
+        global Y_SIZE = fields_of::<Point>()[1].size;
+
+        fn main() -> pub u32 {
+            Y_SIZE + X_NAME as u32
+        }
+        "#;
+        let (_program, context, errors) = get_program(src);
+        assert!(errors.is_empty(), "Expected no errors, got: {:?}", errors);
+
+        let interner = &context.def_interner;
+        let globals = interner.get_all_globals();
+        let global_value = |name: &str| {
+            let (stmt_id, _) =
+                globals.iter().find(|(_, global)| global.ident.0.contents == name).unwrap();
+            match interner.statement(stmt_id) {
+                HirStatement::Let(let_stmt) => interner.expression(&let_stmt.expression),
+                other => panic!("Expected a let statement, got {other:?}"),
+            }
+        };
+
+        for (name, expected) in [("X_NAME", b'x' as u128), ("Y_SIZE", 3)] {
+            match global_value(name) {
+                HirExpression::Literal(HirLiteral::Integer(value, false)) => {
+                    assert_eq!(value.to_u128(), expected);
+                }
+                other => panic!("Expected an integer literal, got {other:?}"),
+            }
+        }
+    }
+
+    #[test]
+    fn function_signatures_use_abi_types() {
+        let src = r#"
+        struct Note { owner: Field, amount: u64 }
+
+        fn transfer(_to: Field, _note: Note, _memo: str<4>, _ids: [i8; 2], _flag: bool) {}
+
+        fn main() {
+            transfer(0, Note { owner: 0, amount: 0 }, "memo", [0, 0], true);
+        }
+        "#;
+        let (_program, context, errors) = get_program(src);
+        assert!(errors.is_empty(), "Expected no errors, got: {:?}", errors);
+
+        let interner = &context.def_interner;
+        let transfer = interner.find_function("transfer").unwrap();
+        let meta = interner.function_meta(&transfer);
+        assert_eq!(meta.signature("transfer"), "transfer(Field,(Field,u64),str<4>,[i8;2],bool)");
+
+        let hash = acvm::blackbox_solver::keccak256(meta.signature("transfer").as_bytes()).unwrap();
+        assert_eq!(meta.selector("transfer").to_be_bytes(), hash[..4]);
+    }
+
+    #[test]
+    fn deprecated_structs_and_traits_warn_at_use_sites() {
+        let src = r#"
+        #[deprecated("use NewPoint instead")]
+        struct OldPoint { x: Field }
+
+        struct NewPoint { x: Field }
+
+        #[deprecated]
+        trait OldTrait {
+            fn old(self) -> Field;
+        }
+
+        pub fn get_old<T>(value: T) -> Field where T: OldTrait {
+            value.old()
+        }
+
+        fn main() {
+            let point: OldPoint = OldPoint { x: 1 };
+            let _ = NewPoint { x: point.x };
+        }
+        "#;
+        let errors = get_program_errors(src);
+        let deprecations = vecmap(&errors, |(error, _)| match error {
+            CompilationError::ResolveError(ResolverError::UseOfDeprecated {
+                kind,
+                name,
+                note,
+                ..
+            }) => (*kind, name.clone(), note.clone()),
+            _ => panic!("Expected only deprecation warnings, got: {:?}", error),
+        });
+
+        let struct_note = Some("use NewPoint instead".to_string());
+        assert_eq!(
+            deprecations,
+            vec![
+                ("trait", "OldTrait".to_string(), None),
+                ("struct", "OldPoint".to_string(), struct_note.clone()),
+                ("struct", "OldPoint".to_string(), struct_note),
+            ]
+        );
+    }
+
+    #[test]
+    fn char_literals_are_typed_as_u8() {
+        let src = r#"
+        fn main() {
+            let a: u8 = 'a';
+            let _: u8 = a + '\n';
+            let _: Field = 'b';
+        }
+        "#;
+        let errors = get_program_errors(src);
+        assert_eq!(errors.len(), 1, "Expected 1 error, got: {:?}", errors);
+        // The mismatch between `u8` and `Field` comes with a fix casting the byte
+        let CompilationError::TypeError(TypeCheckError::WithFix { err, .. }) = &errors[0].0 else {
+            panic!("Expected a type error with a fix, got: {:?}", errors[0].0);
+        };
+        assert!(matches!(**err, TypeCheckError::TypeMismatch { .. }));
+
+        let (_program, context, _errors) = get_program("fn main() -> pub u8 { 'A' }");
+        let main_func_id = context.def_interner.find_function("main").unwrap();
+        let program = monomorphize(main_func_id, &context.def_interner).unwrap();
+        assert!(program.to_string().contains("65"), "'A' should be lowered to its ASCII code");
+    }
+
+    #[test]
+    fn loop_is_only_allowed_in_unconstrained_functions() {
+        let src = r#"
+        unconstrained fn search(x: u32) -> u32 {
+            let mut i = 0;
+            loop {
+                if i == x {
+                    break;
+                }
+                for _ in 0..2 {
+                    break;
+                }
+                let _ = || { loop {} };
+                i += 1;
+            }
+            i
+        }
+
+        fn main(x: u32) {
+            loop {
+                break;
+            }
+            break;
+            assert(search(x) == x);
+        }
+        "#;
+        let errors = get_program_errors(src);
+        let mut errors: Vec<_> = errors
+            .into_iter()
+            .map(|(error, _)| match error {
+                CompilationError::ResolveError(ResolverError::LoopInConstrainedFunction {
+                    ..
+                }) => "loop",
+                CompilationError::ResolveError(ResolverError::BreakOutsideOfLoop { .. }) => "break",
+                other => panic!("Unexpected error: {other:?}"),
+            })
+            .collect();
+        // `break` within a `for` loop and outside of any loop, `loop` within a lambda and
+        // within a constrained function
+        errors.sort();
+        assert_eq!(errors, vec!["break", "break", "loop", "loop"]);
+    }
+
+    #[test]
+    fn indexing_a_string_returns_a_byte() {
+        let src = r#"
+        fn main(message: str<5>) {
+            let byte: u8 = message[0];
+            let field: Field = message[1];
+            assert(byte as Field == field);
+        }
+        "#;
+        let errors = get_program_errors(src);
+        assert_eq!(errors.len(), 1, "Expected 1 error, got: {errors:?}");
+        // The mismatch between `u8` and `Field` comes with a fix casting the byte
+        let CompilationError::TypeError(TypeCheckError::WithFix { err, .. }) = &errors[0].0 else {
+            panic!("Expected a type error with a fix, got: {:?}", errors[0].0);
+        };
+        assert!(matches!(**err, TypeCheckError::TypeMismatch { .. }));
+    }
+
+    #[test]
+    fn failed_trait_impl_lookups_leave_type_variables_unbound() {
+        // Unifying `(u8, u16)` with the argument's type binds the literal's type to `u8` before
+        // failing on `u16`, which would make `300` overflow if the binding were kept.
+        let src = r#"
+        trait Foo {}
+        impl Foo for (u8, u16) {}
+
+        fn needs_foo<T>(_x: T) where T: Foo {}
+
+        fn main() {
+            needs_foo((300, 2 as u32));
+        }
+        "#;
+        let errors = get_program_errors(src);
+        assert_eq!(errors.len(), 1, "Expected 1 error, got: {errors:?}");
+        assert!(matches!(
+            errors[0].0,
+            CompilationError::TypeError(TypeCheckError::NoMatchingImplFound { .. })
+        ));
+    }
+
+    #[test]
+    fn loops_over_method_calls_named_enumerate_call_the_method() {
+        let src = r#"
+        struct Items {
+            values: [Field; 2],
+        }
+
+        impl Items {
+            fn enumerate(self) -> [Field; 2] {
+                self.values
+            }
+        }
+
+        fn main() {
+            let items = Items { values: [1, 2] };
+            for value in items.enumerate() {
+                assert(value != 0);
+            }
+        }
+        "#;
+        let errors = get_program_errors(src);
+        assert!(errors.is_empty(), "Expected no errors, got: {errors:?}");
+    }
+
+    #[test]
+    fn trait_constraints_match_the_generics_of_the_trait() {
+        let src = r#"
+        trait From<T> {
+            fn from(input: T) -> Self;
+        }
+
+        struct Meters {
+            value: u16,
+        }
+
+        impl From<u16> for Meters {
+            fn from(value: u16) -> Self {
+                Meters { value }
+            }
+        }
+
+        struct Bytes {
+            value: u8,
+        }
+
+        impl From<u8> for Bytes {
+            fn from(value: u8) -> Self {
+                Bytes { value }
+            }
+        }
+
+        fn convert<T>(value: u8) -> T where T: From<u8> {
+            T::from(value)
+        }
+
+        fn main() {
+            let _bytes: Bytes = convert(1);
+            let _meters: Meters = convert(1);
+        }
+        "#;
+        let errors = get_program_errors(src);
+        assert_eq!(errors.len(), 1, "Expected 1 error, got: {errors:?}");
+        let CompilationError::TypeError(TypeCheckError::NoMatchingImplFound {
+            typ, trait_name, ..
+        }) = &errors[0].0
+        else {
+            panic!("Expected a missing impl, got: {:?}", errors[0].0);
+        };
+        assert_eq!(typ.to_string(), "Meters");
+        assert_eq!(trait_name, "From<u8>");
+    }
+
+    #[test]
+    fn mutable_globals_are_only_accessible_from_unconstrained_functions() {
+        let src = r#"
+        global mut COUNTER: u32 = 0;
+
+        unconstrained fn next() -> u32 {
+            COUNTER += 1;
+            let _ = || COUNTER;
+            COUNTER
+        }
+
+        fn main() {
+            let _: [Field; COUNTER] = [];
+            COUNTER = 2;
+            assert(next() == 1);
+        }
+        "#;
+        let errors = get_program_errors(src);
+        let mut errors: Vec<_> = errors
+            .into_iter()
+            .map(|(error, _)| match error {
+                CompilationError::ResolveError(ResolverError::MutableGlobalInConstrainedCode {
+                    ..
+                }) => "constrained",
+                CompilationError::ResolveError(ResolverError::InvalidArrayLengthExpr {
+                    ..
+                }) => "array length",
+                other => panic!("Unexpected error: {other:?}"),
+            })
+            .collect();
+        // Use within a lambda and assignment in a constrained function, and use as an array length
+        errors.sort();
+        assert_eq!(errors, vec!["array length", "constrained", "constrained"]);
+    }
+
+    #[test]
+    fn tuple_fields_can_be_assigned_and_called() {
+        let src = r#"
+        fn main(x: Field) -> pub Field {
+            let mut nested = ((0, [1, 2]), 3);
+            nested.0.0 = x;
+            nested.0.1[1] = x;
+            nested.0.0 += 2;
+
+            let mut pairs = [(1, 2), (3, 4)];
+            pairs[1].0 = x;
+
+            let mut pair = (0, 0);
+            let counter = &mut pair;
+            counter.1 = 1;
+
+            let functions = (|y: Field| y + 1, 5);
+            functions.0(nested.0.0) + pairs[1].0 + nested.0.1[1] + counter.1
+        }
+        "#;
+        let errors = get_program_errors(src);
+        assert!(errors.is_empty(), "Expected no errors, got: {:?}", errors);
+    }
+
+    #[test]
+    fn tuple_field_assignments_require_a_mutable_tuple() {
+        let src = r#"
+        fn main(x: Field) {
+            let nested = ((0, 1), 2);
+            nested.0.1 = x;
+        }
+        "#;
+        let errors = get_program_errors(src);
+        assert_eq!(errors.len(), 1, "Expected 1 error, got: {:?}", errors);
+        assert!(matches!(
+            &errors[0].0,
+            CompilationError::TypeError(TypeCheckError::VariableMustBeMutable { name, .. })
+                if name == "nested"
+        ));
+    }
+
+    #[test]
+    fn simple_closure_with_no_captured_variables() {
+        let src = r#"
+        fn main() -> pub Field {
+            let x = 1;
+            let closure = || x;
+            closure()
+        }
+        "#;
+
+        let expected_rewrite = r#"fn main$f0() -> Field {
+    let x$0 = 1;
+    let closure$3 = {
+        let closure_variable$2 = {
+            let env$1 = (x$l0);
             (env$l1, lambda$f1)
         };
         closure_variable$l2
@@ -1133,4 +3506,48 @@ fn lambda$f1(mut env$l1: (Field)) -> Field {
 "#;
         check_rewrite(src, expected_rewrite);
     }
+
+    #[test]
+    fn test_modules_are_skipped_unless_the_crate_is_tested() {
+        let src = r#"
+        fn main() {}
+
+        #[test]
+        mod tests {
+            fn helper() -> Field {
+                undefined_variable
+            }
+        }
+        "#;
+
+        let errors = get_program_errors(src);
+        assert!(errors.is_empty(), "Expected no errors, got: {:?}", errors);
+
+        // Without the attribute, the module is resolved as usual
+        let errors = get_program_errors(&src.replace("#[test]", ""));
+        assert!(errors.iter().any(|(error, _)| matches!(
+            error,
+            CompilationError::ResolverError(ResolverError::VariableNotDeclared { name, .. })
+                if name == "undefined_variable"
+        )));
+    }
+
+    #[test]
+    fn formatted_assert_messages_resolve_the_condition_once() {
+        let src = r#"
+        fn main(x: Field) {
+            assert(x == undefined_variable, f"x is {x}");
+        }
+        "#;
+
+        let errors = get_program_errors(src);
+        let undeclared = errors.iter().filter(|(error, _)| {
+            matches!(
+                error,
+                CompilationError::ResolverError(ResolverError::VariableNotDeclared { name, .. })
+                    if name == "undefined_variable"
+            )
+        });
+        assert_eq!(undeclared.count(), 1, "Expected 1 undeclared variable, got: {:?}", errors);
+    }
 }
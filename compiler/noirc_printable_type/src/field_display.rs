@@ -0,0 +1,88 @@
+use std::str::FromStr;
+
+use acvm::FieldElement;
+use num_bigint::BigUint;
+use serde::{Deserialize, Serialize};
+
+/// How field elements are displayed to the user.
+///
+/// Each printer has its own default display, which callers may override by passing a display
+/// explicitly, e.g. from the `--field-display` option of nargo.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FieldDisplay {
+    /// The element as a decimal integer between 0 and p - 1
+    Decimal,
+    /// The element as a hexadecimal integer of even length, e.g. `0x2a`
+    Hex,
+    /// The element as a decimal integer between -(p - 1) / 2 and (p - 1) / 2, so that
+    /// elements such as `p - 1` which represent small negative numbers are shown as `-1`
+    Signed,
+}
+
+impl FieldDisplay {
+    pub fn format(self, field: FieldElement) -> String {
+        match self {
+            FieldDisplay::Decimal => to_biguint(field).to_string(),
+            FieldDisplay::Hex => {
+                if field.is_zero() {
+                    return "0x00".to_owned();
+                }
+                // Trim any leading zeroes, keeping an even number of digits so that
+                // the string can be decoded into bytes.
+                let mut trimmed_field = field.to_hex().trim_start_matches('0').to_owned();
+                if trimmed_field.len() % 2 != 0 {
+                    trimmed_field = "0".to_owned() + &trimmed_field;
+                }
+                "0x".to_owned() + &trimmed_field
+            }
+            FieldDisplay::Signed => {
+                let positive = to_biguint(field);
+                let negative = to_biguint(-field);
+                if negative < positive {
+                    format!("-{negative}")
+                } else {
+                    positive.to_string()
+                }
+            }
+        }
+    }
+}
+
+impl FromStr for FieldDisplay {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "decimal" => Ok(FieldDisplay::Decimal),
+            "hex" => Ok(FieldDisplay::Hex),
+            "signed" => Ok(FieldDisplay::Signed),
+            other => {
+                Err(format!("unknown field display `{other}`, expected decimal, hex or signed"))
+            }
+        }
+    }
+}
+
+fn to_biguint(field: FieldElement) -> BigUint {
+    BigUint::from_bytes_be(&field.to_be_bytes())
+}
+
+#[cfg(test)]
+mod tests {
+    use acvm::FieldElement;
+
+    use super::FieldDisplay;
+
+    #[test]
+    fn formats_elements_beyond_u128() {
+        let large = FieldElement::from(u128::MAX) * FieldElement::from(4u128);
+        assert_eq!(FieldDisplay::Decimal.format(large), "1361129467683753853853498429727072845820");
+        assert_eq!(FieldDisplay::Hex.format(large), "0x03fffffffffffffffffffffffffffffffc");
+
+        let minus_one = -FieldElement::one();
+        assert_eq!(FieldDisplay::Signed.format(minus_one), "-1");
+        assert_eq!(FieldDisplay::Signed.format(large), FieldDisplay::Decimal.format(large));
+        assert_eq!(FieldDisplay::Hex.format(FieldElement::zero()), "0x00");
+    }
+}
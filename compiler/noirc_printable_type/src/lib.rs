@@ -6,6 +6,10 @@ use regex::{Captures, Regex};
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
+mod field_display;
+
+pub use field_display::FieldDisplay;
+
 #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(tag = "kind", rename_all = "lowercase")]
 pub enum PrintableType {
@@ -73,6 +77,9 @@ pub enum ForeignCallError {
 
     #[error("Could not parse PrintableType argument. {0}")]
     ParsingError(#[from] serde_json::Error),
+
+    #[error("Value to be printed does not match its PrintableType")]
+    MismatchedValue,
 }
 
 impl TryFrom<&[ForeignCallParam]> for PrintableValueDisplay {
@@ -165,7 +172,11 @@ fn fetch_printable_type(
     Ok(printable_type)
 }
 
-fn to_string(value: &PrintableValue, typ: &PrintableType) -> Option<String> {
+fn to_string(
+    value: &PrintableValue,
+    typ: &PrintableType,
+    field_display: FieldDisplay,
+) -> Option<String> {
     let mut output = String::new();
     match (value, typ) {
         (
@@ -175,7 +186,7 @@ fn to_string(value: &PrintableValue, typ: &PrintableType) -> Option<String> {
             | PrintableType::SignedInteger { .. }
             | PrintableType::UnsignedInteger { .. },
         ) => {
-            output.push_str(&format_field_string(*f));
+            output.push_str(&field_display.format(*f));
         }
         (PrintableValue::Field(f), PrintableType::Boolean) => {
             if f.is_one() {
@@ -188,7 +199,7 @@ fn to_string(value: &PrintableValue, typ: &PrintableType) -> Option<String> {
             output.push('[');
             let mut values = vector.iter().peekable();
             while let Some(value) = values.next()  {
-                output.push_str(&format!("{}", PrintableValueDisplay::Plain(value.clone(), *typ.clone())));
+                output.push_str(&to_string(value, typ, field_display)?);
                 if values.peek().is_some() {
                     output.push_str(", ");
                 }
@@ -206,7 +217,7 @@ fn to_string(value: &PrintableValue, typ: &PrintableType) -> Option<String> {
             let mut fields = fields.iter().peekable();
             while let Some((key, field_type)) = fields.next()  {
                 let value = &map[key];
-                output.push_str(&format!("{key}: {}", PrintableValueDisplay::Plain(value.clone(), field_type.clone())));
+                output.push_str(&format!("{key}: {}", to_string(value, field_type, field_display)?));
                 if fields.peek().is_some() {
                     output.push_str(", ");
                 }
@@ -239,41 +250,29 @@ fn replace_all<E>(
     Ok(new)
 }
 
-impl std::fmt::Display for PrintableValueDisplay {
-    fn fmt(&self, fmt: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+impl PrintableValueDisplay {
+    /// Formats the value with its field elements shown with `field_display`
+    pub fn format(&self, field_display: FieldDisplay) -> Result<String, std::fmt::Error> {
         match self {
-            Self::Plain(value, typ) => {
-                let output_string = to_string(value, typ).ok_or(std::fmt::Error)?;
-                write!(fmt, "{output_string}")
-            }
+            Self::Plain(value, typ) => to_string(value, typ, field_display).ok_or(std::fmt::Error),
             Self::FmtString(template, values) => {
                 let mut display_iter = values.iter();
                 let re = Regex::new(r"\{([a-zA-Z0-9_]+)\}").map_err(|_| std::fmt::Error)?;
 
-                let formatted_str = replace_all(&re, template, |_: &Captures| {
+                replace_all(&re, template, |_: &Captures| {
                     let (value, typ) = display_iter.next().ok_or(std::fmt::Error)?;
-                    to_string(value, typ).ok_or(std::fmt::Error)
-                })?;
-
-                write!(fmt, "{formatted_str}")
+                    to_string(value, typ, field_display).ok_or(std::fmt::Error)
+                })
             }
         }
     }
 }
 
-/// This trims any leading zeroes.
-/// A singular '0' will be prepended as well if the trimmed string has an odd length.
-/// A hex string's length needs to be even to decode into bytes, as two digits correspond to
-/// one byte.
-fn format_field_string(field: FieldElement) -> String {
-    if field.is_zero() {
-        return "0x00".to_owned();
-    }
-    let mut trimmed_field = field.to_hex().trim_start_matches('0').to_owned();
-    if trimmed_field.len() % 2 != 0 {
-        trimmed_field = "0".to_owned() + &trimmed_field;
+/// Displays field elements in hex
+impl std::fmt::Display for PrintableValueDisplay {
+    fn fmt(&self, fmt: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(fmt, "{}", self.format(FieldDisplay::Hex)?)
     }
-    "0x".to_owned() + &trimmed_field
 }
 
 /// Assumes that `field_iterator` contains enough [FieldElement] in order to decode the [PrintableType]
@@ -3,7 +3,9 @@ use gloo_utils::format::JsValueSerdeExt;
 use js_sys::Array;
 use nargo::artifacts::{
     contract::{PreprocessedContract, PreprocessedContractFunction},
+    hints::WitnessHints,
     program::PreprocessedProgram,
+    ARTIFACT_VERSION,
 };
 use noirc_driver::{
     add_dep, compile_contract, compile_main, prepare_crate, prepare_dependency, CompileOptions,
@@ -115,9 +117,13 @@ fn add_noir_lib(context: &mut Context, library_name: &str) {
 
 fn preprocess_program(program: CompiledProgram) -> PreprocessedProgram {
     PreprocessedProgram {
+        artifact_version: ARTIFACT_VERSION,
         hash: program.hash,
         backend: String::from(BACKEND_IDENTIFIER),
         abi: program.abi,
+        custom_attributes: program.custom_attributes,
+        environment: program.environment,
+        witness_hints: WitnessHints::from_circuit(&program.circuit),
         bytecode: program.circuit,
     }
 }
@@ -128,18 +134,23 @@ fn preprocess_contract(contract: CompiledContract) -> PreprocessedContract {
         .into_iter()
         .map(|func| PreprocessedContractFunction {
             name: func.name,
+            selector: func.selector,
             function_type: func.function_type,
             is_internal: func.is_internal,
+            custom_attributes: func.custom_attributes,
             abi: func.abi,
+            witness_hints: WitnessHints::from_circuit(&func.bytecode),
             bytecode: func.bytecode,
         })
         .collect();
 
     PreprocessedContract {
+        artifact_version: ARTIFACT_VERSION,
         name: contract.name,
         backend: String::from(BACKEND_IDENTIFIER),
         functions: preprocessed_functions,
         events: contract.events,
+        environment: contract.environment,
     }
 }
 
@@ -8,6 +8,7 @@ use nargo::errors::ExecutionError;
 use nargo::NargoError;
 
 use nargo::ops::ForeignCallExecutor;
+use noirc_printable_type::FieldDisplay;
 
 use easy_repl::{command, CommandStatus, Critical, Repl};
 use std::cell::{Cell, RefCell};
@@ -25,6 +26,7 @@ struct DebugContext<'backend, B: BlackBoxFunctionSolver> {
     foreign_call_executor: ForeignCallExecutor,
     circuit: &'backend Circuit,
     show_output: bool,
+    field_display: Option<FieldDisplay>,
 }
 
 impl<'backend, B: BlackBoxFunctionSolver> DebugContext<'backend, B> {
@@ -78,6 +80,14 @@ impl<'backend, B: BlackBoxFunctionSolver> DebugContext<'backend, B> {
         }
     }
 
+    fn show_witness_map(&self) {
+        let witness_map = self.acvm.witness_map().clone();
+        for (witness, value) in witness_map {
+            let value = self.field_display.unwrap_or(FieldDisplay::Decimal).format(value);
+            println!("_{} = {value}", witness.witness_index());
+        }
+    }
+
     fn show_source_code_location(location: &OpcodeLocation, debug_artifact: &DebugArtifact) {
         let locations = debug_artifact.debug_symbols[0].opcode_location(location);
         if let Some(locations) = locations {
@@ -125,16 +135,19 @@ pub fn debug_circuit<B: BlackBoxFunctionSolver>(
     debug_artifact: DebugArtifact,
     initial_witness: WitnessMap,
     show_output: bool,
+    field_display: Option<FieldDisplay>,
 ) -> Result<Option<WitnessMap>, NargoError> {
     let context = RefCell::new(DebugContext {
         acvm: ACVM::new(blackbox_solver, &circuit.opcodes, initial_witness),
-        foreign_call_executor: ForeignCallExecutor::default(),
+        foreign_call_executor: ForeignCallExecutor::default().with_field_display(field_display),
         circuit,
         debug_artifact,
         show_output,
+        field_display,
     });
     let ref_step = &context;
     let ref_cont = &context;
+    let ref_witness = &context;
 
     let solved = Cell::new(false);
 
@@ -168,6 +181,16 @@ pub fn debug_circuit<B: BlackBoxFunctionSolver>(
                 }
             },
         )
+        .add(
+            "w",
+            command! {
+                "show the values of the witnesses solved so far",
+                () => || {
+                    ref_witness.borrow().show_witness_map();
+                    Ok(CommandStatus::Done)
+                }
+            },
+        )
         .build()
         .expect("Failed to initialize debugger repl");
 
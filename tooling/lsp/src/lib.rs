@@ -23,13 +23,15 @@ use noirc_frontend::{
 };
 use notifications::{
     on_did_change_configuration, on_did_change_text_document, on_did_close_text_document,
-    on_did_open_text_document, on_did_save_text_document, on_exit, on_initialized,
+    on_did_open_text_document, on_did_save_text_document, on_exit, on_initialized, PendingCheck,
 };
 use requests::{
-    on_code_lens_request, on_initialize, on_shutdown, on_test_run_request, on_tests_request,
+    on_code_action_request, on_code_lens_request, on_initialize, on_shutdown, on_test_run_request,
+    on_tests_request,
 };
 use serde_json::Value as JsonValue;
 use tower::Service;
+use tracing::Instrument;
 
 mod notifications;
 mod requests;
@@ -44,11 +46,19 @@ pub struct LspState {
     root_path: Option<PathBuf>,
     client: ClientSocket,
     solver: WrapperSolver,
+
+    /// The check started by the last save, which may still be running
+    pending_check: Option<PendingCheck>,
 }
 
 impl LspState {
     fn new(client: &ClientSocket, solver: impl BlackBoxFunctionSolver + 'static) -> Self {
-        Self { client: client.clone(), root_path: None, solver: WrapperSolver(Box::new(solver)) }
+        Self {
+            client: client.clone(),
+            root_path: None,
+            solver: WrapperSolver(Box::new(solver)),
+            pending_check: None,
+        }
     }
 }
 
@@ -64,6 +74,7 @@ impl NargoLspService {
             .request::<request::Initialize, _>(on_initialize)
             .request::<request::Shutdown, _>(on_shutdown)
             .request::<request::CodeLens, _>(on_code_lens_request)
+            .request::<request::CodeAction, _>(on_code_action_request)
             .request::<request::NargoTests, _>(on_tests_request)
             .request::<request::NargoTestRun, _>(on_test_run_request)
             .notification::<notification::Initialized>(on_initialized)
@@ -89,7 +100,9 @@ impl Service<AnyRequest> for NargoLspService {
     }
 
     fn call(&mut self, req: AnyRequest) -> Self::Future {
-        self.router.call(req)
+        let span = tracing::info_span!("request", method = %req.method);
+        let response = span.in_scope(|| self.router.call(req));
+        Box::pin(response.instrument(span))
     }
 }
 
@@ -97,7 +110,8 @@ impl Service<AnyRequest> for NargoLspService {
 // our `NargoLspService` able to accept the `async-lsp` middleware.
 impl LspService for NargoLspService {
     fn notify(&mut self, notification: AnyNotification) -> ControlFlow<Result<(), Error>> {
-        self.router.notify(notification)
+        let span = tracing::info_span!("notification", method = %notification.method);
+        span.in_scope(|| self.router.notify(notification))
     }
 
     fn emit(&mut self, event: AnyEvent) -> ControlFlow<Result<(), Error>> {
@@ -1,16 +1,21 @@
 use std::ops::ControlFlow;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
 
-use async_lsp::{ErrorCode, LanguageClient, ResponseError};
-use nargo::prepare_package;
-use nargo_toml::{find_package_manifest, resolve_workspace_from_toml, PackageSelection};
-use noirc_driver::check_crate;
+use async_lsp::{ClientSocket, ErrorCode, LanguageClient, ResponseError};
+use nargo::{package::Package, prepare_package_for_tests, workspace::Workspace};
+use nargo_toml::{find_package_manifest, resolve_test_workspace_from_toml, PackageSelection};
+use noirc_driver::{
+    check_crate, configure_frontend, CancellationToken, Cancelled, CompileOptions, Interrupted,
+};
 use noirc_errors::{DiagnosticKind, FileDiagnostic};
 
 use crate::types::{
-    notification, Diagnostic, DiagnosticSeverity, DidChangeConfigurationParams,
+    notification, Diagnostic, DiagnosticFix, DiagnosticSeverity, DidChangeConfigurationParams,
     DidChangeTextDocumentParams, DidCloseTextDocumentParams, DidOpenTextDocumentParams,
     DidSaveTextDocumentParams, InitializedParams, LogMessageParams, MessageType, NargoPackageTests,
-    PublishDiagnosticsParams,
+    NumberOrString, PublishDiagnosticsParams, ShowMessageParams, TextEdit, Url,
 };
 
 use crate::{byte_span_to_range, get_non_stdlib_asset, get_package_tests_in_crate, LspState};
@@ -37,9 +42,20 @@ pub(super) fn on_did_open_text_document(
 }
 
 pub(super) fn on_did_change_text_document(
-    _state: &mut LspState,
+    state: &mut LspState,
     _params: DidChangeTextDocumentParams,
 ) -> ControlFlow<Result<(), async_lsp::Error>> {
+    // An edit cancels the check of the last save so that it doesn't hold up the next one. The
+    // check is started again rather than dropped, as its diagnostics would otherwise never be
+    // published if the file isn't saved again.
+    let unfinished_check = state
+        .pending_check
+        .as_ref()
+        .filter(|pending_check| !pending_check.finished.load(Ordering::Relaxed))
+        .map(|pending_check| pending_check.save.clone());
+    if let Some(save) = unfinished_check {
+        start_check(state, save);
+    }
     ControlFlow::Continue(())
 }
 
@@ -88,7 +104,7 @@ pub(super) fn on_did_save_text_document(
             return ControlFlow::Continue(());
         }
     };
-    let workspace = match resolve_workspace_from_toml(&toml_path, PackageSelection::All) {
+    let workspace = match resolve_test_workspace_from_toml(&toml_path, PackageSelection::All) {
         Ok(workspace) => workspace,
         Err(err) => {
             // If we found a manifest, but the workspace is invalid, we raise an error about it
@@ -100,69 +116,174 @@ pub(super) fn on_did_save_text_document(
         }
     };
 
-    let diagnostics: Vec<_> = workspace
-        .into_iter()
-        .flat_map(|package| -> Vec<Diagnostic> {
-            let (mut context, crate_id) = prepare_package(package, Box::new(get_non_stdlib_asset));
+    start_check(state, SaveCheck { workspace, file_path, uri: params.text_document.uri });
+    ControlFlow::Continue(())
+}
 
-            let file_diagnostics = match check_crate(&mut context, crate_id, false) {
-                Ok(((), warnings)) => warnings,
-                Err(errors_and_warnings) => errors_and_warnings,
-            };
+/// The workspace and file checked after a save.
+#[derive(Clone)]
+pub(crate) struct SaveCheck {
+    workspace: Workspace,
+    file_path: PathBuf,
+    uri: Url,
+}
+
+/// The check started by the last save, which can be cancelled by starting another.
+pub(crate) struct PendingCheck {
+    save: SaveCheck,
+    token: CancellationToken,
+    /// Set once the check has published its diagnostics, or failed
+    finished: Arc<AtomicBool>,
+}
+
+/// Starts checking `save`, cancelling the previous check if it may still be running.
+fn start_check(state: &mut LspState, save: SaveCheck) {
+    let token = CancellationToken::new();
+    let finished = Arc::new(AtomicBool::new(false));
+    let pending_check =
+        PendingCheck { save: save.clone(), token: token.clone(), finished: finished.clone() };
+    if let Some(previous_check) = state.pending_check.replace(pending_check) {
+        previous_check.token.cancel();
+    }
+
+    let client = state.client.clone();
+    let check = move || {
+        if check_workspace(client, save, token) != Err(Interrupted::Cancelled) {
+            finished.store(true, Ordering::Relaxed);
+        }
+    };
+
+    // Checks run in the background so that later edits can cancel them rather than queueing
+    // up behind them. Threads aren't available to the WASM build, which checks in place instead.
+    cfg_if::cfg_if! {
+        if #[cfg(all(target_arch = "wasm32", not(target_os = "wasi")))] {
+            check();
+        } else {
+            std::thread::spawn(check);
+        }
+    }
+}
+
+/// Checks each package in the workspace, publishing the diagnostics found in the saved file
+/// unless the check is cancelled by a later save or edit first.
+///
+/// If the compiler panics, the panic is reported to the client instead of the diagnostics.
+fn check_workspace(
+    mut client: ClientSocket,
+    save: SaveCheck,
+    token: CancellationToken,
+) -> Result<(), Interrupted> {
+    let SaveCheck { workspace, file_path, uri } = save;
+    let diagnostics = Cancelled::catch(|| {
+        workspace
+            .into_iter()
+            .flat_map(|package| check_package(&mut client, package, &file_path, &token))
+            .collect::<Vec<_>>()
+    });
+
+    let diagnostics = match diagnostics {
+        Ok(diagnostics) => diagnostics,
+        // Stale diagnostics are dropped, the check which cancelled this one will publish its own
+        Err(Interrupted::Cancelled) => return Err(Interrupted::Cancelled),
+        Err(Interrupted::Panicked(message)) => {
+            let _ = client.show_message(ShowMessageParams {
+                typ: MessageType::ERROR,
+                message: format!("The compiler panicked while checking {uri}: {message}"),
+            });
+            return Err(Interrupted::Panicked(message));
+        }
+    };
+
+    // We need to refresh lenses when we compile since that's the only time they can be accurately reflected
+    std::mem::drop(client.code_lens_refresh(()));
+
+    let _ =
+        client.publish_diagnostics(PublishDiagnosticsParams { uri, version: None, diagnostics });
+    Ok(())
+}
+
+fn check_package(
+    client: &mut ClientSocket,
+    package: &Package,
+    file_path: &Path,
+    token: &CancellationToken,
+) -> Vec<Diagnostic> {
+    let (mut context, crate_id) =
+        prepare_package_for_tests(package, Box::new(get_non_stdlib_asset));
+    context.def_interner.set_cancellation_token(token.clone());
+    configure_frontend(&mut context, &package.compile_options(&CompileOptions::default()));
+
+    let file_diagnostics = match check_crate(&mut context, crate_id, false) {
+        Ok(((), warnings)) => warnings,
+        Err(errors_and_warnings) => errors_and_warnings,
+    };
+
+    // We don't add test headings for a package if it contains no `#[test]` functions
+    if let Some(tests) = get_package_tests_in_crate(&context, &crate_id, &package.name) {
+        let _ = client.notify::<notification::NargoUpdateTests>(NargoPackageTests {
+            package: package.name.to_string(),
+            tests,
+        });
+    }
 
-            // We don't add test headings for a package if it contains no `#[test]` functions
-            if let Some(tests) = get_package_tests_in_crate(&context, &crate_id, &package.name) {
-                let _ = state.client.notify::<notification::NargoUpdateTests>(NargoPackageTests {
-                    package: package.name.to_string(),
-                    tests,
-                });
+    let fm = &context.file_manager;
+    let files = fm.as_file_map();
+
+    file_diagnostics
+        .into_iter()
+        .filter_map(|FileDiagnostic { file_id, diagnostic, call_stack: _ }| {
+            // Ignore diagnostics for any file that wasn't the file we saved
+            // TODO: In the future, we could create "related" diagnostics for these files
+            if fm.path(file_id) != file_path {
+                return None;
             }
 
-            let fm = &context.file_manager;
-            let files = fm.as_file_map();
+            // TODO: Should this be the first item in secondaries? Should we bail when we find a range?
+            let range = diagnostic
+                .secondaries
+                .into_iter()
+                .filter_map(|sec| byte_span_to_range(files, file_id, sec.span.into()))
+                .last()
+                .unwrap_or_default();
+
+            let severity = match diagnostic.kind {
+                DiagnosticKind::Error => DiagnosticSeverity::ERROR,
+                DiagnosticKind::Warning => DiagnosticSeverity::WARNING,
+            };
 
-            file_diagnostics
+            // A fix is dropped entirely if any of its edits can't be mapped to the file, as
+            // applying only some of them would leave the code broken.
+            let fixes: Vec<_> = diagnostic
+                .fixes
                 .into_iter()
-                .filter_map(|FileDiagnostic { file_id, diagnostic, call_stack: _ }| {
-                    // Ignore diagnostics for any file that wasn't the file we saved
-                    // TODO: In the future, we could create "related" diagnostics for these files
-                    if fm.path(file_id) != file_path {
-                        return None;
-                    }
-
-                    // TODO: Should this be the first item in secondaries? Should we bail when we find a range?
-                    let range = diagnostic
-                        .secondaries
+                .filter_map(|fix| {
+                    let edits = fix
+                        .edits
                         .into_iter()
-                        .filter_map(|sec| byte_span_to_range(files, file_id, sec.span.into()))
-                        .last()
-                        .unwrap_or_default();
-
-                    let severity = match diagnostic.kind {
-                        DiagnosticKind::Error => DiagnosticSeverity::ERROR,
-                        DiagnosticKind::Warning => DiagnosticSeverity::WARNING,
-                    };
-                    Some(Diagnostic {
-                        range,
-                        severity: Some(severity),
-                        message: diagnostic.message,
-                        ..Default::default()
-                    })
+                        .map(|edit| {
+                            let range = byte_span_to_range(files, file_id, edit.span.into())?;
+                            Some(TextEdit { range, new_text: edit.replacement })
+                        })
+                        .collect::<Option<Vec<_>>>()?;
+                    Some(DiagnosticFix { title: fix.message, edits })
                 })
-                .collect()
-        })
-        .collect();
-
-    // We need to refresh lenses when we compile since that's the only time they can be accurately reflected
-    std::mem::drop(state.client.code_lens_refresh(()));
-
-    let _ = state.client.publish_diagnostics(PublishDiagnosticsParams {
-        uri: params.text_document.uri,
-        version: None,
-        diagnostics,
-    });
+                .collect();
+            let data = if fixes.is_empty() {
+                None
+            } else {
+                Some(serde_json::to_value(fixes).expect("fixes are always serializable"))
+            };
 
-    ControlFlow::Continue(())
+            Some(Diagnostic {
+                range,
+                severity: Some(severity),
+                code: diagnostic.code.map(|code| NumberOrString::String(code.to_string())),
+                message: diagnostic.message,
+                data,
+                ..Default::default()
+            })
+        })
+        .collect()
 }
 
 pub(super) fn on_exit(
@@ -171,3 +292,76 @@ pub(super) fn on_exit(
 ) -> ControlFlow<Result<(), async_lsp::Error>> {
     ControlFlow::Continue(())
 }
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::sync::Arc;
+
+    use async_lsp::ClientSocket;
+    use lsp_types::VersionedTextDocumentIdentifier;
+    use nargo::workspace::Workspace;
+    use noirc_driver::CancellationToken;
+
+    use super::{on_did_change_text_document, PendingCheck, SaveCheck};
+    use crate::{
+        solver::MockBackend,
+        types::{DidChangeTextDocumentParams, Url},
+        LspState,
+    };
+
+    fn pending_check(finished: bool) -> PendingCheck {
+        let workspace = Workspace {
+            root_dir: PathBuf::new(),
+            members: Vec::new(),
+            selected_package_index: None,
+        };
+        let uri = Url::parse("file:///project/src/main.nr").unwrap();
+        let save = SaveCheck { workspace, file_path: PathBuf::from("/project/src/main.nr"), uri };
+        PendingCheck {
+            save,
+            token: CancellationToken::new(),
+            finished: Arc::new(AtomicBool::new(finished)),
+        }
+    }
+
+    fn edit(state: &mut LspState) {
+        let params = DidChangeTextDocumentParams {
+            text_document: VersionedTextDocumentIdentifier {
+                uri: Url::parse("file:///project/src/main.nr").unwrap(),
+                version: 1,
+            },
+            content_changes: Vec::new(),
+        };
+        assert!(on_did_change_text_document(state, params).is_continue());
+    }
+
+    #[test]
+    fn edits_restart_unfinished_checks() {
+        let mut state = LspState::new(&ClientSocket::new_closed(), MockBackend);
+        let check = pending_check(false);
+        let cancelled_token = check.token.clone();
+        state.pending_check = Some(check);
+
+        edit(&mut state);
+
+        assert!(cancelled_token.is_cancelled());
+        let restarted = state.pending_check.as_ref().expect("the check should be restarted");
+        assert!(!restarted.token.is_cancelled());
+        assert_eq!(restarted.save.uri.path(), "/project/src/main.nr");
+    }
+
+    #[test]
+    fn edits_leave_finished_checks_alone() {
+        let mut state = LspState::new(&ClientSocket::new_closed(), MockBackend);
+        let check = pending_check(true);
+        let token = check.token.clone();
+        state.pending_check = Some(check);
+
+        edit(&mut state);
+
+        assert!(!token.is_cancelled());
+        assert!(state.pending_check.unwrap().finished.load(Ordering::Relaxed));
+    }
+}
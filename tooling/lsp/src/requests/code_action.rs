@@ -0,0 +1,115 @@
+use std::collections::HashMap;
+use std::future::{self, Future};
+
+use async_lsp::ResponseError;
+
+use crate::{
+    types::{
+        CodeAction, CodeActionKind, CodeActionOrCommand, CodeActionParams, CodeActionResult,
+        DiagnosticFix, WorkspaceEdit,
+    },
+    LspState,
+};
+
+/// Offers the fixes attached to the requested diagnostics as quick fixes.
+///
+/// The fixes are sent to the client in the `data` of each diagnostic when it is published,
+/// so they are read back from there rather than checking the package again.
+pub(crate) fn on_code_action_request(
+    _state: &mut LspState,
+    params: CodeActionParams,
+) -> impl Future<Output = Result<CodeActionResult, ResponseError>> {
+    future::ready(Ok(code_actions(params)))
+}
+
+fn code_actions(params: CodeActionParams) -> CodeActionResult {
+    let uri = params.text_document.uri;
+
+    let actions: Vec<_> = params
+        .context
+        .diagnostics
+        .into_iter()
+        .flat_map(|diagnostic| {
+            let fixes: Vec<DiagnosticFix> = diagnostic
+                .data
+                .clone()
+                .and_then(|data| serde_json::from_value(data).ok())
+                .unwrap_or_default();
+
+            let uri = uri.clone();
+            fixes.into_iter().map(move |fix| {
+                let changes = HashMap::from([(uri.clone(), fix.edits)]);
+                CodeActionOrCommand::CodeAction(CodeAction {
+                    title: fix.title,
+                    kind: Some(CodeActionKind::QUICKFIX),
+                    diagnostics: Some(vec![diagnostic.clone()]),
+                    edit: Some(WorkspaceEdit { changes: Some(changes), ..Default::default() }),
+                    ..Default::default()
+                })
+            })
+        })
+        .collect();
+
+    if actions.is_empty() {
+        None
+    } else {
+        Some(actions)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use lsp_types::{
+        CodeActionContext, Diagnostic, PartialResultParams, Position, Range,
+        TextDocumentIdentifier, TextEdit, Url, WorkDoneProgressParams,
+    };
+
+    use super::code_actions;
+    use crate::types::{CodeActionOrCommand, CodeActionParams, DiagnosticFix};
+
+    fn params(diagnostics: Vec<Diagnostic>) -> CodeActionParams {
+        CodeActionParams {
+            text_document: TextDocumentIdentifier {
+                uri: Url::parse("file:///project/src/main.nr").unwrap(),
+            },
+            range: Range::default(),
+            context: CodeActionContext { diagnostics, only: None, trigger_kind: None },
+            work_done_progress_params: WorkDoneProgressParams::default(),
+            partial_result_params: PartialResultParams::default(),
+        }
+    }
+
+    #[test]
+    fn offers_fixes_as_quick_fixes() {
+        let position = Position { line: 1, character: 8 };
+        let fix = DiagnosticFix {
+            title: "make `x` mutable".to_string(),
+            edits: vec![TextEdit {
+                range: Range { start: position, end: position },
+                new_text: "mut ".to_string(),
+            }],
+        };
+        let diagnostic = Diagnostic {
+            message: "Variable `x` must be mutable to be assigned to".to_string(),
+            data: Some(serde_json::to_value(vec![fix]).unwrap()),
+            ..Default::default()
+        };
+
+        let actions = code_actions(params(vec![diagnostic])).unwrap();
+        assert_eq!(actions.len(), 1);
+        let CodeActionOrCommand::CodeAction(action) = &actions[0] else {
+            panic!("Expected a code action");
+        };
+        assert_eq!(action.title, "make `x` mutable");
+
+        let changes = action.edit.as_ref().unwrap().changes.as_ref().unwrap();
+        let edits = changes.values().next().unwrap();
+        assert_eq!(edits[0].new_text, "mut ");
+    }
+
+    #[test]
+    fn ignores_diagnostics_without_fixes() {
+        let diagnostic = Diagnostic { message: "an error".to_string(), ..Default::default() };
+        assert!(code_actions(params(vec![diagnostic])).is_none());
+    }
+}
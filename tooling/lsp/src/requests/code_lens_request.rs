@@ -2,8 +2,8 @@ use std::future::{self, Future};
 
 use async_lsp::{ErrorCode, LanguageClient, ResponseError};
 
-use nargo::{package::Package, prepare_package, workspace::Workspace};
-use nargo_toml::{find_package_manifest, resolve_workspace_from_toml, PackageSelection};
+use nargo::{package::Package, prepare_package_for_tests, workspace::Workspace};
+use nargo_toml::{find_package_manifest, resolve_test_workspace_from_toml, PackageSelection};
 use noirc_driver::check_crate;
 use noirc_frontend::hir::FunctionNameMatch;
 
@@ -68,7 +68,7 @@ fn on_code_lens_request_inner(
         }
     };
     let workspace =
-        resolve_workspace_from_toml(&toml_path, PackageSelection::All).map_err(|err| {
+        resolve_test_workspace_from_toml(&toml_path, PackageSelection::All).map_err(|err| {
             // If we found a manifest, but the workspace is invalid, we raise an error about it
             ResponseError::new(ErrorCode::REQUEST_FAILED, err)
         })?;
@@ -76,7 +76,8 @@ fn on_code_lens_request_inner(
     let mut lenses: Vec<CodeLens> = vec![];
 
     for package in &workspace {
-        let (mut context, crate_id) = prepare_package(package, Box::new(get_non_stdlib_asset));
+        let (mut context, crate_id) =
+            prepare_package_for_tests(package, Box::new(get_non_stdlib_asset));
         // We ignore the warnings and errors produced by compilation for producing code lenses
         // because we can still get the test functions even if compilation fails
         let _ = check_crate(&mut context, crate_id, false);
@@ -1,6 +1,9 @@
 use std::future::Future;
 
-use crate::types::{CodeLensOptions, InitializeParams, TextDocumentSyncOptions};
+use crate::types::{
+    CodeActionKind, CodeActionOptions, CodeActionProviderCapability, CodeLensOptions,
+    InitializeParams, TextDocumentSyncOptions,
+};
 use async_lsp::ResponseError;
 
 use crate::{
@@ -18,12 +21,14 @@ use crate::{
 // They are not attached to the `NargoLspService` struct so they can be unit tested with only `LspState`
 // and params passed in.
 
+mod code_action;
 mod code_lens_request;
 mod test_run;
 mod tests;
 
 pub(crate) use {
-    code_lens_request::on_code_lens_request, test_run::on_test_run_request, tests::on_tests_request,
+    code_action::on_code_action_request, code_lens_request::on_code_lens_request,
+    test_run::on_test_run_request, tests::on_tests_request,
 };
 
 pub(crate) fn on_initialize(
@@ -38,6 +43,11 @@ pub(crate) fn on_initialize(
 
         let code_lens = CodeLensOptions { resolve_provider: Some(false) };
 
+        let code_action = CodeActionOptions {
+            code_action_kinds: Some(vec![CodeActionKind::QUICKFIX]),
+            ..Default::default()
+        };
+
         let nargo = NargoCapability {
             tests: Some(NargoTestsOptions {
                 fetch: Some(true),
@@ -50,6 +60,7 @@ pub(crate) fn on_initialize(
             capabilities: ServerCapabilities {
                 text_document_sync: Some(text_document_sync.into()),
                 code_lens_provider: Some(code_lens),
+                code_action_provider: Some(CodeActionProviderCapability::Options(code_action)),
                 nargo: Some(nargo),
             },
             server_info: None,
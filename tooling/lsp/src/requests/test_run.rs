@@ -3,10 +3,10 @@ use std::future::{self, Future};
 use async_lsp::{ErrorCode, ResponseError};
 use nargo::{
     ops::{run_test, TestStatus},
-    prepare_package,
+    prepare_package_for_tests,
 };
-use nargo_toml::{find_package_manifest, resolve_workspace_from_toml, PackageSelection};
-use noirc_driver::{check_crate, CompileOptions};
+use nargo_toml::{find_package_manifest, resolve_test_workspace_from_toml, PackageSelection};
+use noirc_driver::{check_crate, configure_frontend, CompileOptions};
 use noirc_frontend::hir::FunctionNameMatch;
 
 use crate::{
@@ -38,17 +38,25 @@ fn on_test_run_request_inner(
     let crate_name = params.id.crate_name();
     let function_name = params.id.function_name();
 
-    let workspace =
-        resolve_workspace_from_toml(&toml_path, PackageSelection::Selected(crate_name.clone()))
-            .map_err(|err| {
-                // If we found a manifest, but the workspace is invalid, we raise an error about it
-                ResponseError::new(ErrorCode::REQUEST_FAILED, err)
-            })?;
+    let workspace = resolve_test_workspace_from_toml(
+        &toml_path,
+        PackageSelection::Selected(crate_name.clone()),
+    )
+    .map_err(|err| {
+        // If we found a manifest, but the workspace is invalid, we raise an error about it
+        ResponseError::new(ErrorCode::REQUEST_FAILED, err)
+    })?;
 
     // Since we filtered on crate name, this should be the only item in the iterator
     match workspace.into_iter().next() {
         Some(package) => {
-            let (mut context, crate_id) = prepare_package(package, Box::new(get_non_stdlib_asset));
+            let (mut context, crate_id) =
+                prepare_package_for_tests(package, Box::new(get_non_stdlib_asset));
+            let compile_options = CompileOptions {
+                report_assert_values: true,
+                ..package.compile_options(&CompileOptions::default())
+            };
+            configure_frontend(&mut context, &compile_options);
             if check_crate(&mut context, crate_id, false).is_err() {
                 let result = NargoTestRunResult {
                     id: params.id.clone(),
@@ -71,7 +79,7 @@ fn on_test_run_request_inner(
             })?;
 
             let test_result =
-                run_test(&state.solver, &context, test_function, false, &CompileOptions::default());
+                run_test(&state.solver, &context, test_function, false, &compile_options, None);
             let result = match test_result {
                 TestStatus::Pass => NargoTestRunResult {
                     id: params.id.clone(),
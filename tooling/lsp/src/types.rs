@@ -3,11 +3,13 @@ use serde::{Deserialize, Serialize};
 
 // Re-providing lsp_types that we don't need to override
 pub(crate) use lsp_types::{
-    CodeLens, CodeLensOptions, CodeLensParams, Command, Diagnostic, DiagnosticSeverity,
-    DidChangeConfigurationParams, DidChangeTextDocumentParams, DidCloseTextDocumentParams,
-    DidOpenTextDocumentParams, DidSaveTextDocumentParams, InitializeParams, InitializedParams,
-    LogMessageParams, MessageType, Position, PublishDiagnosticsParams, Range, ServerInfo,
-    TextDocumentSyncCapability, TextDocumentSyncOptions, Url,
+    CodeAction, CodeActionKind, CodeActionOptions, CodeActionOrCommand, CodeActionParams,
+    CodeActionProviderCapability, CodeLens, CodeLensOptions, CodeLensParams, Command, Diagnostic,
+    DiagnosticSeverity, DidChangeConfigurationParams, DidChangeTextDocumentParams,
+    DidCloseTextDocumentParams, DidOpenTextDocumentParams, DidSaveTextDocumentParams,
+    InitializeParams, InitializedParams, LogMessageParams, MessageType, NumberOrString, Position,
+    PublishDiagnosticsParams, Range, ServerInfo, ShowMessageParams, TextDocumentSyncCapability,
+    TextDocumentSyncOptions, TextEdit, Url, WorkspaceEdit,
 };
 
 pub(crate) mod request {
@@ -19,7 +21,9 @@ pub(crate) mod request {
     };
 
     // Re-providing lsp_types that we don't need to override
-    pub(crate) use lsp_types::request::{CodeLensRequest as CodeLens, Shutdown};
+    pub(crate) use lsp_types::request::{
+        CodeActionRequest as CodeAction, CodeLensRequest as CodeLens, Shutdown,
+    };
 
     #[derive(Debug)]
     pub(crate) struct Initialize;
@@ -99,6 +103,10 @@ pub(crate) struct ServerCapabilities {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub(crate) code_lens_provider: Option<CodeLensOptions>,
 
+    /// The server provides code actions.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) code_action_provider: Option<CodeActionProviderCapability>,
+
     /// The server handles and provides custom nargo messages.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub(crate) nargo: Option<NargoCapability>,
@@ -188,3 +196,13 @@ pub(crate) struct NargoTestRunResult {
 }
 
 pub(crate) type CodeLensResult = Option<Vec<CodeLens>>;
+
+/// A fix for a diagnostic, carried in the `data` of the published diagnostic so that
+/// it can be offered as a quick fix without checking the package again.
+#[derive(Debug, Serialize, Deserialize)]
+pub(crate) struct DiagnosticFix {
+    pub(crate) title: String,
+    pub(crate) edits: Vec<TextEdit>,
+}
+
+pub(crate) type CodeActionResult = Option<Vec<CodeActionOrCommand>>;
@@ -1,8 +1,13 @@
+use std::collections::BTreeMap;
+
 use acvm::acir::circuit::Circuit;
 use noirc_abi::{Abi, ContractEvent};
 use noirc_driver::ContractFunctionType;
+use noirc_frontend::token::CustomAttribute;
 use serde::{Deserialize, Serialize};
 
+use super::hints::WitnessHints;
+
 /// `PreprocessedContract` represents a Noir contract which has been preprocessed by a particular backend proving system.
 ///
 /// This differs from a generic Noir contract artifact in that:
@@ -10,6 +15,9 @@ use serde::{Deserialize, Serialize};
 /// - Proving and verification keys have been pregenerated based on this ACIR.
 #[derive(Serialize, Deserialize)]
 pub struct PreprocessedContract {
+    /// The [version][super::ARTIFACT_VERSION] of the artifact format of this contract.
+    #[serde(default)]
+    pub artifact_version: u32,
     /// The name of the contract.
     pub name: String,
     /// The identifier of the proving backend which this contract has been compiled for.
@@ -18,6 +26,10 @@ pub struct PreprocessedContract {
     pub functions: Vec<PreprocessedContractFunction>,
     /// All the events defined inside the contract scope.
     pub events: Vec<ContractEvent>,
+    /// The environment variables read by `std::env!` while compiling the contract, along with
+    /// the values they were compiled with.
+    #[serde(default)]
+    pub environment: BTreeMap<String, String>,
 }
 
 /// Each function in the contract will be compiled as a separate noir program.
@@ -28,12 +40,26 @@ pub struct PreprocessedContract {
 pub struct PreprocessedContractFunction {
     pub name: String,
 
+    /// The identifier used to call this function from other contracts
+    #[serde(default)]
+    pub selector: u32,
+
     pub function_type: ContractFunctionType,
 
     pub is_internal: bool,
 
+    /// Attributes on the function which are not recognized by the compiler,
+    /// passed through for use by downstream tooling.
+    #[serde(default)]
+    pub custom_attributes: Vec<CustomAttribute>,
+
     pub abi: Abi,
 
+    /// Structural properties of the witnesses of `bytecode`, for backends to use when generating
+    /// its proving key.
+    #[serde(default)]
+    pub witness_hints: WitnessHints,
+
     #[serde(
         serialize_with = "super::serialize_circuit",
         deserialize_with = "super::deserialize_circuit"
@@ -0,0 +1,171 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use acvm::{
+    acir::{
+        circuit::{opcodes::BlackBoxFuncCall, Circuit, Opcode},
+        native_types::{Expression, Witness},
+    },
+    FieldElement,
+};
+use serde::{Deserialize, Serialize};
+
+/// `WitnessHints` describes structural properties of a circuit's witnesses which a backend would
+/// otherwise have to recover by analyzing the circuit's opcodes when generating its proving key.
+///
+/// Every hint is implied by the opcodes of the circuit, so a backend may ignore them entirely.
+/// Hints are only present in artifacts with an `artifact_version` of at least
+/// [`WITNESS_HINTS_VERSION`][super::WITNESS_HINTS_VERSION].
+#[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq, Eq)]
+pub struct WitnessHints {
+    /// The smallest number of bits each range constrained witness is constrained to.
+    pub ranges: BTreeMap<Witness, u32>,
+
+    /// Witnesses which are constrained to be either 0 or 1.
+    pub booleans: BTreeSet<Witness>,
+
+    /// Pairs of witnesses which are constrained to be equal, such that a backend may place them
+    /// in the same permutation cycle rather than encoding a gate for their equality. The lower
+    /// witness of each pair comes first.
+    pub equalities: BTreeSet<(Witness, Witness)>,
+}
+
+impl WitnessHints {
+    /// Collects the hints implied by the opcodes of `circuit`.
+    pub fn from_circuit(circuit: &Circuit) -> WitnessHints {
+        let mut hints = WitnessHints::default();
+
+        for opcode in &circuit.opcodes {
+            match opcode {
+                Opcode::BlackBoxFuncCall(BlackBoxFuncCall::RANGE { input }) => {
+                    let num_bits = hints.ranges.entry(input.witness).or_insert(input.num_bits);
+                    *num_bits = (*num_bits).min(input.num_bits);
+                }
+                Opcode::Arithmetic(expression) => {
+                    if let Some(witness) = as_boolean_constraint(expression) {
+                        hints.booleans.insert(witness);
+                    } else if let Some(equality) = as_equality_constraint(expression) {
+                        hints.equalities.insert(equality);
+                    }
+                }
+                _ => (),
+            }
+        }
+
+        // A single bit range constraint is also a boolean constraint.
+        let single_bit_witnesses = hints.ranges.iter().filter(|(_, num_bits)| **num_bits == 1);
+        hints.booleans.extend(single_bit_witnesses.map(|(witness, _)| *witness));
+
+        hints
+    }
+}
+
+/// Returns `w` if `expression` is of the form `c * w * w - c * w = 0`, which constrains `w` to
+/// be either 0 or 1.
+fn as_boolean_constraint(expression: &Expression) -> Option<Witness> {
+    let [(mul_coefficient, lhs, rhs)] = expression.mul_terms.as_slice() else {
+        return None;
+    };
+    let [(linear_coefficient, witness)] = expression.linear_combinations.as_slice() else {
+        return None;
+    };
+    let is_boolean = lhs == rhs
+        && lhs == witness
+        && !mul_coefficient.is_zero()
+        && *mul_coefficient + *linear_coefficient == FieldElement::zero()
+        && expression.q_c.is_zero();
+    is_boolean.then_some(*witness)
+}
+
+/// Returns `(w1, w2)`, ordered, if `expression` is of the form `c * w1 - c * w2 = 0`.
+fn as_equality_constraint(expression: &Expression) -> Option<(Witness, Witness)> {
+    if !expression.mul_terms.is_empty() || !expression.q_c.is_zero() {
+        return None;
+    }
+    let [(lhs_coefficient, lhs), (rhs_coefficient, rhs)] =
+        expression.linear_combinations.as_slice()
+    else {
+        return None;
+    };
+    let is_equality = lhs != rhs
+        && !lhs_coefficient.is_zero()
+        && *lhs_coefficient + *rhs_coefficient == FieldElement::zero();
+    is_equality.then_some((*lhs.min(rhs), *lhs.max(rhs)))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::{BTreeMap, BTreeSet};
+
+    use acvm::{
+        acir::{
+            circuit::{
+                opcodes::{BlackBoxFuncCall, FunctionInput},
+                Circuit, Opcode,
+            },
+            native_types::{Expression, Witness},
+        },
+        FieldElement,
+    };
+
+    use super::WitnessHints;
+
+    fn circuit(opcodes: Vec<Opcode>) -> Circuit {
+        Circuit { opcodes, ..Circuit::default() }
+    }
+
+    fn range(witness: u32, num_bits: u32) -> Opcode {
+        let input = FunctionInput { witness: Witness(witness), num_bits };
+        Opcode::BlackBoxFuncCall(BlackBoxFuncCall::RANGE { input })
+    }
+
+    /// `c * w * w - c * w + q_c = 0`
+    fn boolean(witness: u32, c: u128, q_c: u128) -> Opcode {
+        let c = FieldElement::from(c);
+        Opcode::Arithmetic(Expression {
+            mul_terms: vec![(c, Witness(witness), Witness(witness))],
+            linear_combinations: vec![(-c, Witness(witness))],
+            q_c: FieldElement::from(q_c),
+        })
+    }
+
+    /// `c * lhs - c * rhs = 0`
+    fn equality(lhs: u32, rhs: u32, c: u128) -> Opcode {
+        let c = FieldElement::from(c);
+        Opcode::Arithmetic(Expression {
+            mul_terms: Vec::new(),
+            linear_combinations: vec![(c, Witness(lhs)), (-c, Witness(rhs))],
+            q_c: FieldElement::zero(),
+        })
+    }
+
+    #[test]
+    fn ranges_keep_the_smallest_bit_size() {
+        let hints =
+            WitnessHints::from_circuit(&circuit(vec![range(1, 32), range(1, 8), range(2, 64)]));
+        assert_eq!(hints.ranges, BTreeMap::from([(Witness(1), 8), (Witness(2), 64)]));
+        assert!(hints.booleans.is_empty());
+    }
+
+    #[test]
+    fn booleans_are_detected_from_arithmetic_and_single_bit_ranges() {
+        let opcodes = vec![boolean(1, 1, 0), boolean(2, 5, 0), range(3, 1), boolean(4, 1, 1)];
+        let hints = WitnessHints::from_circuit(&circuit(opcodes));
+        // `w * w - w + 1 = 0` doesn't constrain `w` to a bit
+        assert_eq!(hints.booleans, BTreeSet::from([Witness(1), Witness(2), Witness(3)]));
+    }
+
+    #[test]
+    fn equalities_are_ordered_and_deduplicated() {
+        let opcodes =
+            vec![equality(2, 1, 1), equality(1, 2, 3), equality(3, 3, 1), equality(4, 5, 0)];
+        let hints = WitnessHints::from_circuit(&circuit(opcodes));
+        assert_eq!(hints.equalities, BTreeSet::from([(Witness(1), Witness(2))]));
+    }
+
+    #[test]
+    fn other_arithmetic_opcodes_give_no_hints() {
+        let sum = &(Witness(1) + Witness(2)) - &Expression::from(Witness(3));
+        let hints = WitnessHints::from_circuit(&circuit(vec![Opcode::Arithmetic(sum)]));
+        assert_eq!(hints, WitnessHints::default());
+    }
+}
@@ -8,11 +8,38 @@ use base64::Engine;
 use serde::{
     de::Error as DeserializationError, ser::Error as SerializationError, Deserializer, Serializer,
 };
+use thiserror::Error;
 
 pub mod contract;
 pub mod debug;
+pub mod hints;
 pub mod program;
 
+/// The version of the artifact format written by this version of Nargo.
+///
+/// Backends compare this against the `artifact_version` of an artifact to know which of its
+/// optional sections they may rely on. Artifacts written before the format was versioned are
+/// read with a version of 0.
+pub const ARTIFACT_VERSION: u32 = WITNESS_HINTS_VERSION;
+
+/// The first artifact version containing the [`WitnessHints`][hints::WitnessHints] of each circuit.
+pub const WITNESS_HINTS_VERSION: u32 = 1;
+
+/// An artifact was written by a newer version of Nargo, in a format this version doesn't know.
+#[derive(Debug, Error, PartialEq, Eq)]
+#[error("artifact version {0} is newer than the latest version supported, {ARTIFACT_VERSION}")]
+pub struct UnsupportedArtifactVersion(pub u32);
+
+/// Checks that an artifact with the given `artifact_version` can be read by this version of
+/// Nargo. Every older version can be, as the sections added since then are optional.
+pub fn check_artifact_version(artifact_version: u32) -> Result<(), UnsupportedArtifactVersion> {
+    if artifact_version > ARTIFACT_VERSION {
+        Err(UnsupportedArtifactVersion(artifact_version))
+    } else {
+        Ok(())
+    }
+}
+
 // TODO: move these down into ACVM.
 fn serialize_circuit<S>(circuit: &Circuit, s: S) -> Result<S::Ok, S::Error>
 where
@@ -34,3 +61,18 @@ where
     let circuit = Circuit::read(&*circuit_bytes).map_err(D::Error::custom)?;
     Ok(circuit)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{check_artifact_version, UnsupportedArtifactVersion, ARTIFACT_VERSION};
+
+    #[test]
+    fn only_artifacts_from_newer_versions_are_rejected() {
+        assert_eq!(check_artifact_version(0), Ok(()));
+        assert_eq!(check_artifact_version(ARTIFACT_VERSION), Ok(()));
+        assert_eq!(
+            check_artifact_version(ARTIFACT_VERSION + 1),
+            Err(UnsupportedArtifactVersion(ARTIFACT_VERSION + 1))
+        );
+    }
+}
@@ -1,7 +1,12 @@
+use std::collections::BTreeMap;
+
 use acvm::acir::circuit::Circuit;
 use noirc_abi::Abi;
+use noirc_frontend::token::CustomAttribute;
 use serde::{Deserialize, Serialize};
 
+use super::hints::WitnessHints;
+
 /// `PreprocessedProgram` represents a Noir program which has been preprocessed by a particular backend proving system.
 ///
 /// This differs from a generic Noir program artifact in that:
@@ -9,6 +14,10 @@ use serde::{Deserialize, Serialize};
 /// - Proving and verification keys have been pregenerated based on this ACIR.
 #[derive(Serialize, Deserialize, Debug)]
 pub struct PreprocessedProgram {
+    /// The [version][super::ARTIFACT_VERSION] of the artifact format of this program.
+    #[serde(default)]
+    pub artifact_version: u32,
+
     /// Hash of the [`Program`][noirc_frontend::monomorphization::ast::Program] from which this [`PreprocessedProgram`]
     /// was compiled.
     ///
@@ -18,6 +27,21 @@ pub struct PreprocessedProgram {
     pub backend: String,
     pub abi: Abi,
 
+    /// Attributes on the `main` function which are not recognized by the compiler,
+    /// passed through for use by downstream tooling.
+    #[serde(default)]
+    pub custom_attributes: Vec<CustomAttribute>,
+
+    /// The environment variables read by `std::env!` while compiling the program, along with
+    /// the values they were compiled with.
+    #[serde(default)]
+    pub environment: BTreeMap<String, String>,
+
+    /// Structural properties of the witnesses of `bytecode`, for backends to use when generating
+    /// its proving key.
+    #[serde(default)]
+    pub witness_hints: WitnessHints,
+
     #[serde(
         serialize_with = "super::serialize_circuit",
         deserialize_with = "super::deserialize_circuit"
@@ -32,13 +32,10 @@ pub fn prepare_dependencies(
     dependencies: &BTreeMap<CrateName, Dependency>,
 ) {
     for (dep_name, dep) in dependencies.iter() {
-        match dep {
-            Dependency::Remote { package } | Dependency::Local { package } => {
-                let crate_id = prepare_dependency(context, &package.entry_path);
-                add_dep(context, parent_crate, crate_id, dep_name.clone());
-                prepare_dependencies(context, crate_id, &package.dependencies);
-            }
-        }
+        let package = dep.package();
+        let crate_id = prepare_dependency(context, &package.entry_path);
+        add_dep(context, parent_crate, crate_id, dep_name.clone());
+        prepare_dependencies(context, crate_id, &package.dependencies);
     }
 }
 
@@ -54,3 +51,17 @@ pub fn prepare_package(package: &Package, file_reader: Box<FileReader>) -> (Cont
 
     (context, crate_id)
 }
+
+/// Prepares the package in the same way as [prepare_package] for compiling its tests, so its
+/// modules marked with `#[test]` are compiled and its dev-dependencies are added to the crate
+/// graph.
+pub fn prepare_package_for_tests(
+    package: &Package,
+    file_reader: Box<FileReader>,
+) -> (Context, CrateId) {
+    let (mut context, crate_id) = prepare_package(package, file_reader);
+    prepare_dependencies(&mut context, crate_id, &package.dev_dependencies);
+    context.tested_crate = Some(crate_id);
+
+    (context, crate_id)
+}
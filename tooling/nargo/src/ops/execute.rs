@@ -13,9 +13,31 @@ pub fn execute_circuit<B: BlackBoxFunctionSolver>(
     initial_witness: WitnessMap,
     show_output: bool,
 ) -> Result<WitnessMap, NargoError> {
-    let mut acvm = ACVM::new(blackbox_solver, &circuit.opcodes, initial_witness);
+    execute_circuit_with_executor(
+        blackbox_solver,
+        circuit,
+        initial_witness,
+        show_output,
+        &mut ForeignCallExecutor::default(),
+    )
+}
 
-    let mut foreign_call_executor = ForeignCallExecutor::default();
+/// Executes the circuit, resolving its foreign calls with the given executor so that any state
+/// the executor accumulates, such as the block profile, can be inspected afterwards. Execution
+/// fails if unconstrained functions run for longer than the executor's step limit.
+#[tracing::instrument(skip_all, fields(opcodes = circuit.opcodes.len()))]
+pub fn execute_circuit_with_executor<B: BlackBoxFunctionSolver>(
+    blackbox_solver: &B,
+    circuit: &Circuit,
+    initial_witness: WitnessMap,
+    show_output: bool,
+    foreign_call_executor: &mut ForeignCallExecutor,
+) -> Result<WitnessMap, NargoError> {
+    let mut acvm = ACVM::new(blackbox_solver, &circuit.opcodes, initial_witness);
+    if let Some(step_limit) = foreign_call_executor.step_limit() {
+        let step_limit = usize::try_from(step_limit).unwrap_or(usize::MAX);
+        acvm = acvm.with_brillig_step_limit(step_limit);
+    }
 
     loop {
         let solver_status = acvm.solve();
@@ -42,6 +64,10 @@ pub fn execute_circuit<B: BlackBoxFunctionSolver>(
                             *call_stack.last().expect("Call stacks should not be empty"),
                         ) {
                             ExecutionError::AssertionFailed(assert_message.to_owned(), call_stack)
+                        } else if let Some(assert_message) =
+                            foreign_call_executor.take_assert_message()
+                        {
+                            ExecutionError::AssertionFailed(assert_message, call_stack)
                         } else {
                             ExecutionError::SolvingError(error)
                         }
@@ -50,6 +76,7 @@ pub fn execute_circuit<B: BlackBoxFunctionSolver>(
                 }));
             }
             ACVMStatus::RequiresForeignCall(foreign_call) => {
+                tracing::debug!(function = %foreign_call.function, "resolving foreign call");
                 let foreign_call_result =
                     foreign_call_executor.execute(&foreign_call, show_output)?;
                 acvm.resolve_pending_foreign_call(foreign_call_result);
@@ -3,14 +3,19 @@ use acvm::{
     pwg::ForeignCallWaitInfo,
 };
 use iter_extended::vecmap;
-use noirc_printable_type::{decode_string_value, ForeignCallError, PrintableValueDisplay};
+use noirc_printable_type::{
+    decode_string_value, FieldDisplay, ForeignCallError, PrintableValueDisplay,
+};
+
+use crate::{errors::ExecutionError, NargoError};
 
-use crate::NargoError;
+use super::BlockProfile;
 
 /// This enumeration represents the Brillig foreign calls that are natively supported by nargo.
 /// After resolution of a foreign call, nargo will restart execution of the ACVM
 pub(crate) enum ForeignCall {
     Println,
+    AssertMessage,
     Sequence,
     ReverseSequence,
     CreateMock,
@@ -18,6 +23,7 @@ pub(crate) enum ForeignCall {
     SetMockReturns,
     SetMockTimes,
     ClearMock,
+    BlockCounter,
 }
 
 impl std::fmt::Display for ForeignCall {
@@ -30,6 +36,7 @@ impl ForeignCall {
     pub(crate) fn name(&self) -> &'static str {
         match self {
             ForeignCall::Println => "println",
+            ForeignCall::AssertMessage => "assert_message",
             ForeignCall::Sequence => "get_number_sequence",
             ForeignCall::ReverseSequence => "get_reverse_number_sequence",
             ForeignCall::CreateMock => "create_mock",
@@ -37,12 +44,14 @@ impl ForeignCall {
             ForeignCall::SetMockReturns => "set_mock_returns",
             ForeignCall::SetMockTimes => "set_mock_times",
             ForeignCall::ClearMock => "clear_mock",
+            ForeignCall::BlockCounter => "__block_counter",
         }
     }
 
     pub(crate) fn lookup(op_name: &str) -> Option<ForeignCall> {
         match op_name {
             "println" => Some(ForeignCall::Println),
+            "assert_message" => Some(ForeignCall::AssertMessage),
             "get_number_sequence" => Some(ForeignCall::Sequence),
             "get_reverse_number_sequence" => Some(ForeignCall::ReverseSequence),
             "create_mock" => Some(ForeignCall::CreateMock),
@@ -50,11 +59,18 @@ impl ForeignCall {
             "set_mock_returns" => Some(ForeignCall::SetMockReturns),
             "set_mock_times" => Some(ForeignCall::SetMockTimes),
             "clear_mock" => Some(ForeignCall::ClearMock),
+            "__block_counter" => Some(ForeignCall::BlockCounter),
             _ => None,
         }
     }
 }
 
+/// Returns true if calls to the oracle `name` are resolved by nargo itself, rather than by mocks
+/// registered by the program.
+pub fn is_native_foreign_call(name: &str) -> bool {
+    ForeignCall::lookup(name).is_some()
+}
+
 /// This struct represents an oracle mock. It can be used for testing programs that use oracles.
 #[derive(Debug, PartialEq, Eq, Clone)]
 struct MockedCall {
@@ -88,15 +104,58 @@ impl MockedCall {
     }
 }
 
+/// Resolves the foreign calls which are neither handled by nargo nor mocked by the program,
+/// such as by forwarding them to an external service.
+pub trait ForeignCallResolver: std::fmt::Debug {
+    fn resolve(
+        &mut self,
+        foreign_call: &ForeignCallWaitInfo,
+    ) -> Result<ForeignCallResult, ExecutionError>;
+}
+
 #[derive(Debug, Default)]
 pub struct ForeignCallExecutor {
     /// Mocks have unique ids used to identify them in Noir, allowing to update or remove them.
     last_mock_id: usize,
     /// The registered mocks
     mocked_responses: Vec<MockedCall>,
+    /// Execution counts of the blocks of instrumented Brillig functions
+    block_profile: BlockProfile,
+    /// The maximum number of opcodes unconstrained functions may execute, if any. This is
+    /// enforced by the ACVM rather than through foreign calls.
+    step_limit: Option<u64>,
+    /// The message of an assertion with a format string which is about to fail
+    assert_message: Option<String>,
+    /// Resolves the foreign calls which no mock matches, if any
+    resolver: Option<Box<dyn ForeignCallResolver>>,
+    /// How to show the field elements of printed values, or in hex if `None`
+    field_display: Option<FieldDisplay>,
 }
 
 impl ForeignCallExecutor {
+    /// Creates an executor for runs which fail once unconstrained functions have executed
+    /// `step_limit` opcodes
+    pub fn with_step_limit(step_limit: Option<u64>) -> Self {
+        ForeignCallExecutor { step_limit, ..ForeignCallExecutor::default() }
+    }
+
+    /// The maximum number of opcodes unconstrained functions may execute, if any
+    pub fn step_limit(&self) -> Option<u64> {
+        self.step_limit
+    }
+
+    /// Resolves the foreign calls which no mock matches with `resolver`, rather than panicking
+    pub fn with_resolver(mut self, resolver: Box<dyn ForeignCallResolver>) -> Self {
+        self.resolver = Some(resolver);
+        self
+    }
+
+    /// Shows the field elements of printed values and assertion messages with `field_display`
+    pub fn with_field_display(mut self, field_display: Option<FieldDisplay>) -> Self {
+        self.field_display = field_display;
+        self
+    }
+
     pub fn execute(
         &mut self,
         foreign_call: &ForeignCallWaitInfo,
@@ -106,10 +165,16 @@ impl ForeignCallExecutor {
         match ForeignCall::lookup(foreign_call_name) {
             Some(ForeignCall::Println) => {
                 if show_output {
-                    Self::execute_println(&foreign_call.inputs)?;
+                    self.execute_println(&foreign_call.inputs)?;
                 }
                 Ok(ForeignCallResult { values: vec![] })
             }
+            Some(ForeignCall::AssertMessage) => {
+                let display_values: PrintableValueDisplay =
+                    foreign_call.inputs.as_slice().try_into()?;
+                self.assert_message = Some(self.format(&display_values)?);
+                Ok(ForeignCallResult { values: vec![] })
+            }
             Some(ForeignCall::Sequence) => {
                 let sequence_length: u128 =
                     foreign_call.inputs[0].unwrap_value().to_field().to_u128();
@@ -178,12 +243,21 @@ impl ForeignCallExecutor {
                 self.mocked_responses.retain(|response| response.id != id);
                 Ok(ForeignCallResult { values: vec![] })
             }
+            Some(ForeignCall::BlockCounter) => {
+                let label = Self::parse_string(&foreign_call.inputs[0]);
+                self.block_profile.record(label, 1);
+                Ok(ForeignCallResult { values: vec![] })
+            }
             None => {
                 let response_position = self
                     .mocked_responses
                     .iter()
-                    .position(|response| response.matches(foreign_call_name, &foreign_call.inputs))
-                    .unwrap_or_else(|| panic!("Unknown foreign call {}", foreign_call_name));
+                    .position(|response| response.matches(foreign_call_name, &foreign_call.inputs));
+                let response_position = match (response_position, &mut self.resolver) {
+                    (Some(position), _) => position,
+                    (None, Some(resolver)) => return Ok(resolver.resolve(foreign_call)?),
+                    (None, None) => panic!("Unknown foreign call {}", foreign_call_name),
+                };
 
                 let mock = self
                     .mocked_responses
@@ -203,6 +277,17 @@ impl ForeignCallExecutor {
         }
     }
 
+    /// Takes the message resolved for an assertion with a format string, which is only resolved
+    /// when the assertion is about to fail
+    pub fn take_assert_message(&mut self) -> Option<String> {
+        self.assert_message.take()
+    }
+
+    /// Returns how many times each block of the instrumented Brillig functions was executed
+    pub fn block_profile(&self) -> &BlockProfile {
+        &self.block_profile
+    }
+
     fn extract_mock_id(
         foreign_call_inputs: &[ForeignCallParam],
     ) -> Result<(usize, &[ForeignCallParam]), ForeignCallError> {
@@ -220,9 +305,14 @@ impl ForeignCallExecutor {
         decode_string_value(&fields)
     }
 
-    fn execute_println(foreign_call_inputs: &[ForeignCallParam]) -> Result<(), NargoError> {
+    fn execute_println(&self, foreign_call_inputs: &[ForeignCallParam]) -> Result<(), NargoError> {
         let display_values: PrintableValueDisplay = foreign_call_inputs.try_into()?;
-        println!("{display_values}");
+        println!("{}", self.format(&display_values)?);
         Ok(())
     }
+
+    fn format(&self, display_values: &PrintableValueDisplay) -> Result<String, NargoError> {
+        let field_display = self.field_display.unwrap_or(FieldDisplay::Hex);
+        display_values.format(field_display).map_err(|_| ForeignCallError::MismatchedValue.into())
+    }
 }
@@ -1,9 +1,13 @@
-pub use self::execute::execute_circuit;
-pub use self::foreign_calls::ForeignCallExecutor;
+pub use self::execute::{execute_circuit, execute_circuit_with_executor};
+pub use self::foreign_calls::{is_native_foreign_call, ForeignCallExecutor, ForeignCallResolver};
+pub use self::opcode_budget::OpcodeBreakdown;
 pub use self::optimize::{optimize_contract, optimize_program};
+pub use self::profile::BlockProfile;
 pub use self::test::{run_test, TestStatus};
 
 mod execute;
 mod foreign_calls;
+mod opcode_budget;
 mod optimize;
+mod profile;
 mod test;
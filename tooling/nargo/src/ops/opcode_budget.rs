@@ -0,0 +1,111 @@
+use std::collections::{BTreeMap, HashMap};
+
+use acvm::acir::circuit::{Circuit, OpcodeLocation};
+use noirc_errors::{debug_info::DebugInfo, Location};
+
+/// The opcodes of a circuit counted by kind and by the source location which generated them,
+/// used to explain which parts of a program a circuit exceeding its opcode budget comes from.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct OpcodeBreakdown {
+    pub total: usize,
+    /// The number of opcodes of each kind, most common first
+    pub by_kind: Vec<(String, usize)>,
+    /// The number of opcodes generated by each source location, most opcodes first.
+    /// Opcodes are attributed to the innermost location of their call stack, and opcodes without
+    /// debug information aren't counted.
+    pub by_location: Vec<(Location, usize)>,
+}
+
+impl OpcodeBreakdown {
+    pub fn new(circuit: &Circuit, debug: &DebugInfo) -> Self {
+        let mut by_kind: BTreeMap<&str, usize> = BTreeMap::new();
+        let mut by_location: HashMap<Location, usize> = HashMap::new();
+        for (index, opcode) in circuit.opcodes.iter().enumerate() {
+            *by_kind.entry(opcode.name()).or_default() += 1;
+
+            let call_stack = debug.locations.get(&OpcodeLocation::Acir(index));
+            if let Some(location) = call_stack.and_then(|call_stack| call_stack.last()) {
+                *by_location.entry(*location).or_default() += 1;
+            }
+        }
+
+        let mut by_kind: Vec<_> =
+            by_kind.into_iter().map(|(kind, count)| (kind.to_owned(), count)).collect();
+        by_kind.sort_by(|(_, a), (_, b)| b.cmp(a));
+
+        let mut by_location: Vec<_> = by_location.into_iter().collect();
+        by_location.sort_by_key(|(location, count)| {
+            (std::cmp::Reverse(*count), location.file.as_usize(), location.span.start())
+        });
+
+        OpcodeBreakdown { total: circuit.opcodes.len(), by_kind, by_location }
+    }
+
+    /// Returns whether the circuit has more than `max_opcodes` opcodes
+    pub fn exceeds(&self, max_opcodes: usize) -> bool {
+        self.total > max_opcodes
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use acvm::acir::{
+        circuit::{
+            opcodes::{BlackBoxFuncCall, FunctionInput},
+            Circuit, Opcode, OpcodeLocation,
+        },
+        native_types::{Expression, Witness},
+    };
+    use fm::FileId;
+    use noirc_errors::{debug_info::DebugInfo, Location, Span};
+
+    use super::OpcodeBreakdown;
+
+    fn range(witness: u32) -> Opcode {
+        let input = FunctionInput { witness: Witness(witness), num_bits: 8 };
+        Opcode::BlackBoxFuncCall(BlackBoxFuncCall::RANGE { input })
+    }
+
+    fn location(start: u32) -> Location {
+        Location::new(Span::single_char(start), FileId::dummy())
+    }
+
+    #[test]
+    fn counts_opcodes_by_kind_and_innermost_location() {
+        let opcodes = vec![
+            Opcode::Arithmetic(Expression::default()),
+            range(1),
+            range(2),
+            range(3),
+            Opcode::Arithmetic(Expression::default()),
+        ];
+        let circuit = Circuit { opcodes, ..Circuit::default() };
+
+        // The first opcode has no debug information, so it isn't attributed to any location
+        let (caller, callee) = (location(10), location(20));
+        let debug = DebugInfo::new(BTreeMap::from([
+            (OpcodeLocation::Acir(1), vec![caller, callee]),
+            (OpcodeLocation::Acir(2), vec![caller, callee]),
+            (OpcodeLocation::Acir(3), vec![caller]),
+            (OpcodeLocation::Acir(4), vec![caller]),
+        ]));
+
+        let breakdown = OpcodeBreakdown::new(&circuit, &debug);
+        assert_eq!(breakdown.total, 5);
+        assert_eq!(breakdown.by_kind, vec![("range".to_owned(), 3), ("arithmetic".to_owned(), 2)]);
+        // Locations generating as many opcodes are ordered by their position in the source
+        assert_eq!(breakdown.by_location, vec![(caller, 2), (callee, 2)]);
+    }
+
+    #[test]
+    fn only_circuits_over_budget_exceed_it() {
+        let circuit = Circuit { opcodes: vec![range(1), range(2)], ..Circuit::default() };
+        let breakdown = OpcodeBreakdown::new(&circuit, &DebugInfo::default());
+
+        assert!(breakdown.by_location.is_empty());
+        assert!(!breakdown.exceeds(2));
+        assert!(breakdown.exceeds(1));
+    }
+}
@@ -0,0 +1,40 @@
+use std::collections::BTreeMap;
+
+use acvm::acir::native_types::WitnessMap;
+use noirc_errors::debug_info::DebugInfo;
+
+/// How many times each block of a program compiled with block instrumentation was executed,
+/// keyed by the block's `function:block` label.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct BlockProfile {
+    counts: BTreeMap<String, u64>,
+}
+
+impl BlockProfile {
+    /// Adds `count` executions of the block with the given label
+    pub fn record(&mut self, label: String, count: u64) {
+        *self.counts.entry(label).or_default() += count;
+    }
+
+    /// Adds the tallies of instrumented ACIR blocks, each of which is a witness that is
+    /// 1 in the solved witness map if its block was executed.
+    pub fn add_witness_tallies(&mut self, debug: &DebugInfo, solved_witness: &WitnessMap) {
+        for (witness, label) in &debug.block_tallies {
+            let count = solved_witness.get(witness).map_or(0, |value| value.to_u128() as u64);
+            self.record(label.clone(), count);
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.counts.is_empty()
+    }
+
+    /// Returns each block label along with its execution count, most executed first.
+    /// Blocks with the same count are ordered by label.
+    pub fn hot_blocks(&self) -> Vec<(&str, u64)> {
+        let mut blocks: Vec<_> =
+            self.counts.iter().map(|(label, count)| (label.as_str(), *count)).collect();
+        blocks.sort_by(|(_, a), (_, b)| b.cmp(a));
+        blocks
+    }
+}
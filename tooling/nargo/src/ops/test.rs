@@ -1,12 +1,12 @@
 use acvm::{acir::native_types::WitnessMap, BlackBoxFunctionSolver};
-use noirc_driver::{compile_no_check, CompileOptions};
+use noirc_driver::{compile_no_check, CompileError, CompileOptions};
 use noirc_errors::{debug_info::DebugInfo, FileDiagnostic};
 use noirc_evaluator::errors::RuntimeError;
 use noirc_frontend::hir::{def_map::TestFunction, Context};
 
 use crate::{errors::try_to_diagnose_runtime_error, NargoError};
 
-use super::execute_circuit;
+use super::{execute_circuit_with_executor, ForeignCallExecutor, ForeignCallResolver};
 
 pub enum TestStatus {
     Pass,
@@ -14,20 +14,33 @@ pub enum TestStatus {
     CompileError(FileDiagnostic),
 }
 
+/// Runs `test_function`, resolving the foreign calls which it does not mock with `resolver`
 pub fn run_test<B: BlackBoxFunctionSolver>(
     blackbox_solver: &B,
     context: &Context,
     test_function: TestFunction,
     show_output: bool,
     config: &CompileOptions,
+    resolver: Option<Box<dyn ForeignCallResolver>>,
 ) -> TestStatus {
     let program = compile_no_check(context, config, test_function.get_id(), None, false);
     match program {
         Ok(program) => {
+            let mut foreign_call_executor =
+                ForeignCallExecutor::with_step_limit(config.profile.brillig_step_limit)
+                    .with_field_display(config.field_display);
+            if let Some(resolver) = resolver {
+                foreign_call_executor = foreign_call_executor.with_resolver(resolver);
+            }
             // Run the backend to ensure the PWG evaluates functions like std::hash::pedersen,
             // otherwise constraints involving these expressions will not error.
-            let circuit_execution =
-                execute_circuit(blackbox_solver, &program.circuit, WitnessMap::new(), show_output);
+            let circuit_execution = execute_circuit_with_executor(
+                blackbox_solver,
+                &program.circuit,
+                WitnessMap::new(),
+                show_output,
+                &mut foreign_call_executor,
+            );
             test_status_program_compile_pass(test_function, program.debug, circuit_execution)
         }
         Err(err) => test_status_program_compile_fail(err, test_function),
@@ -40,14 +53,18 @@ pub fn run_test<B: BlackBoxFunctionSolver>(
 /// that a constraint was never satisfiable.
 /// An example of this is the program `assert(false)`
 /// In that case, we check if the test function should fail, and if so, we return `TestStatus::Pass`.
-fn test_status_program_compile_fail(err: RuntimeError, test_function: TestFunction) -> TestStatus {
+fn test_status_program_compile_fail(err: CompileError, test_function: TestFunction) -> TestStatus {
     // The test has failed compilation, but it should never fail. Report error.
     if !test_function.should_fail() {
         return TestStatus::CompileError(err.into());
     }
 
     // The test has failed compilation, extract the assertion message if present and check if it's expected.
-    let assert_message = if let RuntimeError::FailedConstraint { assert_message, .. } = &err {
+    let assert_message = if let CompileError::RuntimeError(RuntimeError::FailedConstraint {
+        assert_message,
+        ..
+    }) = &err
+    {
         assert_message.clone()
     } else {
         None
@@ -1,5 +1,6 @@
-use std::{collections::BTreeMap, fmt::Display, path::PathBuf};
+use std::{collections::BTreeMap, fmt::Display, path::PathBuf, time::Duration};
 
+use noirc_driver::{BuildProfile, CompileOptions};
 use noirc_frontend::graph::CrateName;
 
 use crate::constants::{PROVER_INPUT_FILE, VERIFIER_INPUT_FILE};
@@ -21,22 +22,72 @@ impl Display for PackageType {
     }
 }
 
+/// How calls to an oracle are sent to an oracle resolver
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OraclePolicy {
+    /// How long to wait for the resolver to answer a single request
+    pub timeout: Duration,
+    /// How many times a request which failed to reach the resolver is sent again
+    pub retries: u32,
+    /// How long to wait before the first retry, doubling before each further retry
+    pub backoff: Duration,
+}
+
+impl Default for OraclePolicy {
+    fn default() -> Self {
+        OraclePolicy {
+            timeout: Duration::from_secs(30),
+            retries: 3,
+            backoff: Duration::from_millis(200),
+        }
+    }
+}
+
+/// The `[oracle-resolver]` section of a `Nargo.toml`
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct OracleResolverConfig {
+    /// The environment variable holding the bearer token sent to the resolver, if not the
+    /// default one
+    pub token_env: Option<String>,
+    /// The policy of the oracles which do not have one of their own
+    pub default_policy: OraclePolicy,
+    /// The policies of individual oracles, by the name of their foreign call
+    pub oracles: BTreeMap<String, OraclePolicy>,
+}
+
+impl OracleResolverConfig {
+    /// The environment variable the bearer token is read from unless `token-env` is set
+    pub const DEFAULT_TOKEN_ENV: &'static str = "NARGO_ORACLE_RESOLVER_TOKEN";
+
+    /// Returns the name of the environment variable holding the bearer token
+    pub fn token_env(&self) -> &str {
+        self.token_env.as_deref().unwrap_or(Self::DEFAULT_TOKEN_ENV)
+    }
+
+    /// Returns the policy used for calls to the oracle `name`
+    pub fn policy(&self, name: &str) -> &OraclePolicy {
+        self.oracles.get(name).unwrap_or(&self.default_policy)
+    }
+}
+
 #[derive(Clone)]
 pub enum Dependency {
     Local { package: Package },
-    Remote { package: Package },
+    Remote { package: Package, git: String, tag: String },
 }
 
 impl Dependency {
     pub fn is_binary(&self) -> bool {
-        match self {
-            Self::Local { package } | Self::Remote { package } => package.is_binary(),
-        }
+        self.package().is_binary()
     }
 
     pub fn package_name(&self) -> &CrateName {
+        &self.package().name
+    }
+
+    pub fn package(&self) -> &Package {
         match self {
-            Self::Local { package } | Self::Remote { package } => &package.name,
+            Self::Local { package } | Self::Remote { package, .. } => package,
         }
     }
 }
@@ -47,7 +98,23 @@ pub struct Package {
     pub package_type: PackageType,
     pub entry_path: PathBuf,
     pub name: CrateName,
+    pub version: Option<String>,
     pub dependencies: BTreeMap<CrateName, Dependency>,
+    /// The dependencies only used by the package's tests, which are only resolved when the
+    /// package is loaded for testing
+    pub dev_dependencies: BTreeMap<CrateName, Dependency>,
+    /// The profile used to build the package, unless `--release` is passed
+    pub dev_profile: BuildProfile,
+    /// The profile used to build the package when `--release` is passed
+    pub release_profile: BuildProfile,
+    /// How the package's oracles are resolved when an oracle resolver is given
+    pub oracle_resolver: OracleResolverConfig,
+    /// The largest number of opcodes each of the package's circuits may have, unless
+    /// `--max-opcodes` is passed
+    pub max_opcodes: Option<usize>,
+    /// Whether the package's arguments are converted with `From` impls, as with
+    /// `--implicit-conversions`
+    pub implicit_conversions: bool,
 }
 
 impl Package {
@@ -73,4 +140,25 @@ impl Package {
     pub fn is_library(&self) -> bool {
         self.package_type == PackageType::Library
     }
+
+    /// Returns the release profile if `release` is set, or the dev profile otherwise
+    pub fn profile(&self, release: bool) -> &BuildProfile {
+        if release {
+            &self.release_profile
+        } else {
+            &self.dev_profile
+        }
+    }
+
+    /// Returns the given options with this package's profile selected, according to whether
+    /// they ask for a release build, its opcode budget unless they set one, and implicit
+    /// conversions if the package enables them
+    pub fn compile_options(&self, options: &CompileOptions) -> CompileOptions {
+        CompileOptions {
+            profile: self.profile(options.release).clone(),
+            max_opcodes: options.max_opcodes.or(self.max_opcodes),
+            implicit_conversions: options.implicit_conversions || self.implicit_conversions,
+            ..options.clone()
+        }
+    }
 }
@@ -6,7 +6,7 @@ use iter_extended::btree_map;
 use nargo::{package::Package, prepare_package};
 use nargo_toml::{get_package_manifest, resolve_workspace_from_toml, PackageSelection};
 use noirc_abi::{AbiParameter, AbiType, MAIN_RETURN_NAME};
-use noirc_driver::{check_crate, compute_function_abi, CompileOptions};
+use noirc_driver::{check_crate, compute_function_abi, configure_frontend, CompileOptions};
 use noirc_frontend::{
     graph::{CrateId, CrateName},
     hir::Context,
@@ -51,12 +51,7 @@ pub(crate) fn run(
 fn check_package(package: &Package, compile_options: &CompileOptions) -> Result<(), CompileError> {
     let (mut context, crate_id) =
         prepare_package(package, Box::new(|path| std::fs::read_to_string(path)));
-    check_crate_and_report_errors(
-        &mut context,
-        crate_id,
-        compile_options.deny_warnings,
-        compile_options.silence_warnings,
-    )?;
+    check_crate_and_report_errors(&mut context, crate_id, compile_options)?;
 
     if package.is_library() || package.is_contract() {
         // Libraries do not have ABIs while contracts have many, so we cannot generate a `Prover.toml` file.
@@ -124,7 +119,7 @@ fn create_input_toml_template(
 
 #[cfg(test)]
 mod tests {
-    use noirc_abi::{AbiParameter, AbiType, AbiVisibility, Sign};
+    use noirc_abi::{AbiParameter, AbiStructLayout, AbiType, AbiVisibility, Sign};
 
     use super::create_input_toml_template;
 
@@ -150,6 +145,7 @@ mod tests {
                             AbiType::Array { length: 3, typ: Box::new(AbiType::Field) },
                         ),
                     ],
+                    layout: AbiStructLayout::Declared,
                 },
             ),
             typed_param("e", AbiType::Boolean),
@@ -175,14 +171,15 @@ d2 = ["", "", ""]
 pub(crate) fn check_crate_and_report_errors(
     context: &mut Context,
     crate_id: CrateId,
-    deny_warnings: bool,
-    silence_warnings: bool,
+    options: &CompileOptions,
 ) -> Result<(), CompileError> {
-    let result = check_crate(context, crate_id, deny_warnings);
+    configure_frontend(context, options);
+    let result = check_crate(context, crate_id, options.deny_warnings);
     super::compile_cmd::report_errors(
         result,
         &context.file_manager,
-        deny_warnings,
-        silence_warnings,
+        options.deny_warnings,
+        options.silence_warnings,
+        options.message_format,
     )
 }
@@ -1,21 +1,27 @@
-use std::collections::BTreeMap;
-use std::path::Path;
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::{Path, PathBuf};
 
-use acvm::acir::circuit::Opcode;
+use acvm::acir::circuit::{Circuit, Opcode};
 use acvm::Language;
 use backend_interface::BackendOpcodeSupport;
-use fm::FileManager;
+use fm::{FileId, FileManager};
 use iter_extended::vecmap;
 use nargo::artifacts::contract::PreprocessedContract;
 use nargo::artifacts::contract::PreprocessedContractFunction;
 use nargo::artifacts::debug::DebugArtifact;
+use nargo::artifacts::hints::WitnessHints;
 use nargo::artifacts::program::PreprocessedProgram;
+use nargo::artifacts::ARTIFACT_VERSION;
+use nargo::ops::OpcodeBreakdown;
 use nargo::package::Package;
 use nargo::prepare_package;
 use nargo::workspace::Workspace;
 use nargo_toml::{get_package_manifest, resolve_workspace_from_toml, PackageSelection};
-use noirc_driver::{CompilationResult, CompileOptions, CompiledContract, CompiledProgram};
+use noirc_driver::{
+    CompilationResult, CompileOptions, CompiledContract, CompiledProgram, DebugFile,
+};
 use noirc_errors::debug_info::DebugInfo;
+use noirc_errors::MessageFormat;
 use noirc_frontend::graph::CrateName;
 
 use clap::Args;
@@ -23,16 +29,20 @@ use clap::Args;
 use crate::backends::Backend;
 use crate::errors::{CliError, CompileError};
 
+use super::fs::fingerprint::{is_fresh, package_fingerprint, save_fingerprint};
 use super::fs::program::read_program_from_file;
 use super::fs::program::{
     save_contract_to_file, save_debug_artifact_to_file, save_program_to_file,
 };
-use super::NargoConfig;
+use super::{NargoConfig, VERSION_STRING};
 use rayon::prelude::*;
 
 // TODO(#1388): pull this from backend.
 const BACKEND_IDENTIFIER: &str = "acvm-backend-barretenberg";
 
+/// The number of source locations listed when a circuit exceeds its opcode budget
+const MAX_REPORTED_LOCATIONS: usize = 10;
+
 /// Compile the program and its secret execution trace into ACIR format
 #[derive(Debug, Clone, Args)]
 pub(crate) struct CompileCommand {
@@ -68,14 +78,27 @@ pub(crate) fn run(
     let workspace = resolve_workspace_from_toml(&toml_path, selection)?;
     let circuit_dir = workspace.target_directory_path();
 
-    let (binary_packages, contract_packages): (Vec<_>, Vec<_>) = workspace
-        .into_iter()
-        .filter(|package| !package.is_library())
-        .cloned()
+    // Packages whose inputs haven't changed since they were last compiled are skipped
+    let mut stale_packages = Vec::new();
+    for package in workspace.into_iter().filter(|package| !package.is_library()) {
+        let compile_options = package.compile_options(&args.compile_options);
+        let fingerprint =
+            package_fingerprint(package, &compile_options, args.output_debug, VERSION_STRING);
+        if is_fresh(&circuit_dir, package, fingerprint) {
+            println!("{:>12} {}", "Fresh", package.name);
+        } else {
+            println!("{:>12} {}", "Compiling", package.name);
+            stale_packages.push((package.clone(), fingerprint));
+        }
+    }
+
+    let (binary_packages, contract_packages): (Vec<_>, Vec<_>) = stale_packages
+        .iter()
+        .map(|(package, _)| package.clone())
         .partition(|package| package.is_binary());
 
     let (np_language, opcode_support) = backend.get_backend_info()?;
-    let (_, compiled_contracts) = compile_workspace(
+    let (compiled_programs, compiled_contracts) = compile_workspace(
         &workspace,
         &binary_packages,
         &contract_packages,
@@ -85,9 +108,38 @@ pub(crate) fn run(
         args.output_debug,
     )?;
 
+    // The environment variables and files each package read, which are part of its fingerprint
+    let mut compile_reads: BTreeMap<_, _> = binary_packages
+        .iter()
+        .zip(compiled_programs)
+        .map(|(package, program)| {
+            (package.name.clone(), (program.environment, program.included_files))
+        })
+        .collect();
+
     // Save build artifacts to disk.
+    let mut contract_artifacts = BTreeMap::new();
     for (package, contract) in contract_packages.into_iter().zip(compiled_contracts) {
-        save_contract(contract, &package, &circuit_dir, args.output_debug);
+        let reads = (contract.environment.clone(), contract.included_files.clone());
+        compile_reads.insert(package.name.clone(), reads);
+        let artifact = save_contract(contract, &package, &circuit_dir, args.output_debug);
+        contract_artifacts.insert(package.name, artifact);
+    }
+
+    // Fingerprints are only saved once every package has compiled successfully
+    for (package, fingerprint) in stale_packages {
+        let artifact = contract_artifacts
+            .remove(&package.name)
+            .unwrap_or_else(|| workspace.package_build_path(&package));
+        let (environment, included_files) = compile_reads.remove(&package.name).unwrap_or_default();
+        save_fingerprint(
+            &circuit_dir,
+            &package,
+            fingerprint,
+            &artifact,
+            &environment,
+            &included_files,
+        );
     }
 
     Ok(())
@@ -135,6 +187,7 @@ pub(super) fn compile_workspace(
                 &file_manager,
                 compile_options.deny_warnings,
                 compile_options.silence_warnings,
+                compile_options.message_format,
             )
         })
         .collect::<Result<_, _>>()?;
@@ -146,10 +199,39 @@ pub(super) fn compile_workspace(
                 &file_manager,
                 compile_options.deny_warnings,
                 compile_options.silence_warnings,
+                compile_options.message_format,
             )
         })
         .collect::<Result<_, _>>()?;
 
+    for (package, program) in binary_packages.iter().zip(&compiled_programs) {
+        check_opcode_budget(
+            &package.name.to_string(),
+            &program.circuit,
+            &program.debug,
+            &program.file_map,
+            package.compile_options(compile_options).max_opcodes,
+        )?;
+    }
+    for (package, contract) in contract_packages.iter().zip(&compiled_contracts) {
+        let max_opcodes = package.compile_options(compile_options).max_opcodes;
+        for function in &contract.functions {
+            check_opcode_budget(
+                &format!("{}::{}", package.name, function.name),
+                &function.bytecode,
+                &function.debug,
+                &contract.file_map,
+                max_opcodes,
+            )?;
+        }
+    }
+
+    // Programs are only saved once they are known to fit in their opcode budget
+    let circuit_dir = workspace.target_directory_path();
+    for (package, program) in binary_packages.iter().zip(&compiled_programs) {
+        save_program(program.clone(), package, &circuit_dir, output_debug);
+    }
+
     Ok((compiled_programs, compiled_contracts))
 }
 
@@ -179,11 +261,59 @@ pub(crate) fn compile_bin_package(
         &file_manager,
         compile_options.deny_warnings,
         compile_options.silence_warnings,
+        compile_options.message_format,
     )?;
 
+    check_opcode_budget(
+        &package.name.to_string(),
+        &program.circuit,
+        &program.debug,
+        &program.file_map,
+        package.compile_options(compile_options).max_opcodes,
+    )?;
+    save_program(program.clone(), package, &workspace.target_directory_path(), output_debug);
+
     Ok(program)
 }
 
+/// Fails with a breakdown of the opcodes of `circuit` if it has more than `max_opcodes` opcodes
+fn check_opcode_budget(
+    circuit_name: &str,
+    circuit: &Circuit,
+    debug: &DebugInfo,
+    file_map: &BTreeMap<FileId, DebugFile>,
+    max_opcodes: Option<usize>,
+) -> Result<(), CompileError> {
+    let Some(max_opcodes) = max_opcodes else {
+        return Ok(());
+    };
+    let breakdown = OpcodeBreakdown::new(circuit, debug);
+    if !breakdown.exceeds(max_opcodes) {
+        return Ok(());
+    }
+
+    let mut lines = vec!["Opcodes by kind:".to_owned()];
+    lines.extend(breakdown.by_kind.iter().map(|(kind, count)| format!("{count:>10}  {kind}")));
+    if !breakdown.by_location.is_empty() {
+        lines.push("Source locations generating the most opcodes:".to_owned());
+        lines.extend(breakdown.by_location.iter().take(MAX_REPORTED_LOCATIONS).map(
+            |(location, count)| {
+                let file = &file_map[&location.file];
+                let start = location.span.start() as usize;
+                let line = file.source[..start].matches('\n').count() + 1;
+                format!("{count:>10}  {}:{line}", file.path.display())
+            },
+        ));
+    }
+
+    Err(CompileError::OpcodeBudgetExceeded {
+        circuit: circuit_name.to_owned(),
+        opcodes: breakdown.total,
+        max_opcodes,
+        breakdown: lines.join("\n"),
+    })
+}
+
 fn compile_program(
     workspace: &Workspace,
     package: &Package,
@@ -192,6 +322,7 @@ fn compile_program(
     np_language: Language,
     is_opcode_supported: &impl Fn(&Opcode) -> bool,
 ) -> (FileManager, CompilationResult<CompiledProgram>) {
+    let compile_options = &package.compile_options(compile_options);
     let (mut context, crate_id) =
         prepare_package(package, Box::new(|path| std::fs::read_to_string(path)));
 
@@ -203,6 +334,11 @@ fn compile_program(
             hash: preprocessed_program.hash,
             circuit: preprocessed_program.bytecode,
             abi: preprocessed_program.abi,
+            custom_attributes: preprocessed_program.custom_attributes,
+            environment: preprocessed_program.environment,
+            // Included files are only needed for the fingerprint, and are read again when the
+            // cached program is reused
+            included_files: BTreeSet::new(),
             debug: DebugInfo::default(),
             file_map: BTreeMap::new(),
         })
@@ -231,13 +367,6 @@ fn compile_program(
         nargo::ops::optimize_program(program, np_language, &is_opcode_supported)
             .expect("Backend does not support an opcode that is in the IR");
 
-    save_program(
-        optimized_program.clone(),
-        package,
-        &workspace.target_directory_path(),
-        output_debug,
-    );
-
     (context.file_manager, Ok((optimized_program, warnings)))
 }
 
@@ -247,6 +376,7 @@ fn compile_contract(
     np_language: Language,
     is_opcode_supported: &impl Fn(&Opcode) -> bool,
 ) -> (FileManager, CompilationResult<CompiledContract>) {
+    let compile_options = &package.compile_options(compile_options);
     let (mut context, crate_id) =
         prepare_package(package, Box::new(|path| std::fs::read_to_string(path)));
     let (contract, warnings) =
@@ -271,9 +401,13 @@ fn save_program(
     output_debug: bool,
 ) {
     let preprocessed_program = PreprocessedProgram {
+        artifact_version: ARTIFACT_VERSION,
         hash: program.hash,
         backend: String::from(BACKEND_IDENTIFIER),
         abi: program.abi,
+        custom_attributes: program.custom_attributes,
+        environment: program.environment,
+        witness_hints: WitnessHints::from_circuit(&program.circuit),
         bytecode: program.circuit,
     };
 
@@ -287,12 +421,13 @@ fn save_program(
     }
 }
 
+/// Saves the artifact of a compiled contract, returning its path
 fn save_contract(
     contract: CompiledContract,
     package: &Package,
     circuit_dir: &Path,
     output_debug: bool,
-) {
+) -> PathBuf {
     // TODO(#1389): I wonder if it is incorrect for nargo-core to know anything about contracts.
     // As can be seen here, It seems like a leaky abstraction where ContractFunctions (essentially CompiledPrograms)
     // are compiled via nargo-core and then the PreprocessedContract is constructed here.
@@ -304,20 +439,25 @@ fn save_contract(
 
     let preprocessed_functions = vecmap(contract.functions, |func| PreprocessedContractFunction {
         name: func.name,
+        selector: func.selector,
         function_type: func.function_type,
         is_internal: func.is_internal,
+        custom_attributes: func.custom_attributes,
         abi: func.abi,
+        witness_hints: WitnessHints::from_circuit(&func.bytecode),
         bytecode: func.bytecode,
     });
 
     let preprocessed_contract = PreprocessedContract {
+        artifact_version: ARTIFACT_VERSION,
         name: contract.name,
         backend: String::from(BACKEND_IDENTIFIER),
         functions: preprocessed_functions,
         events: contract.events,
+        environment: contract.environment,
     };
 
-    save_contract_to_file(
+    let artifact_path = save_contract_to_file(
         &preprocessed_contract,
         &format!("{}-{}", package.name, preprocessed_contract.name),
         circuit_dir,
@@ -330,6 +470,8 @@ fn save_contract(
             circuit_dir,
         );
     }
+
+    artifact_path
 }
 
 /// Helper function for reporting any errors in a `CompilationResult<T>`
@@ -339,6 +481,7 @@ pub(crate) fn report_errors<T>(
     file_manager: &FileManager,
     deny_warnings: bool,
     silence_warnings: bool,
+    message_format: MessageFormat,
 ) -> Result<T, CompileError> {
     let (t, warnings) = result.map_err(|errors| {
         noirc_errors::reporter::report_all(
@@ -346,6 +489,7 @@ pub(crate) fn report_errors<T>(
             &errors,
             deny_warnings,
             silence_warnings,
+            message_format,
         )
     })?;
 
@@ -354,6 +498,7 @@ pub(crate) fn report_errors<T>(
         &warnings,
         deny_warnings,
         silence_warnings,
+        message_format,
     );
 
     Ok(t)
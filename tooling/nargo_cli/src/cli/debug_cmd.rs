@@ -9,6 +9,7 @@ use noirc_abi::input_parser::{Format, InputValue};
 use noirc_abi::InputMap;
 use noirc_driver::{CompileOptions, CompiledProgram};
 use noirc_frontend::graph::CrateName;
+use noirc_printable_type::FieldDisplay;
 
 use super::compile_cmd::compile_bin_package;
 use super::fs::{inputs::read_inputs_from_file, witness::save_witness_to_dir};
@@ -62,14 +63,16 @@ pub(crate) fn run(
     )?;
 
     println!("[{}] Starting debugger", package.name);
+    let field_display = args.compile_options.field_display;
     let (return_value, solved_witness) =
-        debug_program_and_decode(compiled_program, package, &args.prover_name)?;
+        debug_program_and_decode(compiled_program, package, &args.prover_name, field_display)?;
 
     if let Some(solved_witness) = solved_witness {
         println!("[{}] Circuit witness successfully solved", package.name);
 
         if let Some(return_value) = return_value {
-            println!("[{}] Circuit output: {return_value:?}", package.name);
+            let return_value = return_value.display(field_display.unwrap_or(FieldDisplay::Decimal));
+            println!("[{}] Circuit output: {return_value}", package.name);
         }
 
         if let Some(witness_name) = &args.witness_name {
@@ -88,11 +91,12 @@ fn debug_program_and_decode(
     program: CompiledProgram,
     package: &Package,
     prover_name: &str,
+    field_display: Option<FieldDisplay>,
 ) -> Result<(Option<InputValue>, Option<WitnessMap>), CliError> {
     // Parse the initial witness values from Prover.toml
     let (inputs_map, _) =
         read_inputs_from_file(&package.root_dir, prover_name, Format::Toml, &program.abi)?;
-    let solved_witness = debug_program(&program, &inputs_map)?;
+    let solved_witness = debug_program(&program, &inputs_map, field_display)?;
     let public_abi = program.abi.public_abi();
 
     match solved_witness {
@@ -107,6 +111,7 @@ fn debug_program_and_decode(
 pub(crate) fn debug_program(
     compiled_program: &CompiledProgram,
     inputs_map: &InputMap,
+    field_display: Option<FieldDisplay>,
 ) -> Result<Option<WitnessMap>, CliError> {
     #[allow(deprecated)]
     let blackbox_solver = barretenberg_blackbox_solver::BarretenbergSolver::new();
@@ -124,6 +129,7 @@ pub(crate) fn debug_program(
         debug_artifact,
         initial_witness,
         true,
+        field_display,
     )
     .map_err(CliError::from)
 }
@@ -4,15 +4,18 @@ use clap::Args;
 use nargo::artifacts::debug::DebugArtifact;
 use nargo::constants::PROVER_INPUT_FILE;
 use nargo::errors::try_to_diagnose_runtime_error;
+use nargo::ops::{BlockProfile, ForeignCallExecutor};
 use nargo::package::Package;
 use nargo_toml::{get_package_manifest, resolve_workspace_from_toml, PackageSelection};
 use noirc_abi::input_parser::{Format, InputValue};
 use noirc_abi::InputMap;
 use noirc_driver::{CompileOptions, CompiledProgram};
 use noirc_frontend::graph::CrateName;
+use noirc_printable_type::FieldDisplay;
 
 use super::compile_cmd::compile_bin_package;
 use super::fs::{inputs::read_inputs_from_file, witness::save_witness_to_dir};
+use super::oracle_resolver::HttpOracleResolver;
 use super::NargoConfig;
 use crate::backends::Backend;
 use crate::errors::CliError;
@@ -35,6 +38,10 @@ pub(crate) struct ExecuteCommand {
     #[clap(long, conflicts_with = "package")]
     workspace: bool,
 
+    /// JSON-RPC endpoint resolving the foreign calls which the program does not mock
+    #[clap(long, value_name = "URL")]
+    oracle_resolver: Option<String>,
+
     #[clap(flatten)]
     compile_options: CompileOptions,
 }
@@ -62,12 +69,26 @@ pub(crate) fn run(
             &|opcode| opcode_support.is_opcode_supported(opcode),
         )?;
 
-        let (return_value, solved_witness) =
-            execute_program_and_decode(compiled_program, package, &args.prover_name)?;
+        let foreign_call_executor =
+            foreign_call_executor(package, &args.compile_options, args.oracle_resolver.as_deref())?;
+        let field_display = args.compile_options.field_display;
+        let (return_value, solved_witness, block_profile) = execute_program_and_decode(
+            compiled_program,
+            package,
+            &args.prover_name,
+            foreign_call_executor,
+        )?;
 
         println!("[{}] Circuit witness successfully solved", package.name);
         if let Some(return_value) = return_value {
-            println!("[{}] Circuit output: {return_value:?}", package.name);
+            let return_value = return_value.display(field_display.unwrap_or(FieldDisplay::Decimal));
+            println!("[{}] Circuit output: {return_value}", package.name);
+        }
+        if args.compile_options.instrument_blocks {
+            println!("[{}] Block execution counts:", package.name);
+            for (label, count) in block_profile.hot_blocks() {
+                println!("{count:>10}  {label}");
+            }
         }
         if let Some(witness_name) = &args.witness_name {
             let witness_path = save_witness_to_dir(solved_witness, witness_name, target_dir)?;
@@ -78,38 +99,78 @@ pub(crate) fn run(
     Ok(())
 }
 
+/// Creates the executor of the foreign calls of `package`, resolving those which the program
+/// does not mock with the oracle resolver at `oracle_resolver`, if one is given
+pub(crate) fn foreign_call_executor(
+    package: &Package,
+    compile_options: &CompileOptions,
+    oracle_resolver: Option<&str>,
+) -> Result<ForeignCallExecutor, CliError> {
+    let step_limit = package.profile(compile_options.release).brillig_step_limit;
+    let foreign_call_executor = ForeignCallExecutor::with_step_limit(step_limit)
+        .with_field_display(compile_options.field_display);
+    Ok(match oracle_resolver {
+        Some(url) => {
+            let resolver = HttpOracleResolver::new(url, package.oracle_resolver.clone())?;
+            foreign_call_executor.with_resolver(Box::new(resolver))
+        }
+        None => foreign_call_executor,
+    })
+}
+
 fn execute_program_and_decode(
     program: CompiledProgram,
     package: &Package,
     prover_name: &str,
-) -> Result<(Option<InputValue>, WitnessMap), CliError> {
+    foreign_call_executor: ForeignCallExecutor,
+) -> Result<(Option<InputValue>, WitnessMap, BlockProfile), CliError> {
     // Parse the initial witness values from Prover.toml
     let (inputs_map, _) =
         read_inputs_from_file(&package.root_dir, prover_name, Format::Toml, &program.abi)?;
-    let solved_witness = execute_program(&program, &inputs_map)?;
+    let (solved_witness, block_profile) =
+        execute_program_with_profile(&program, &inputs_map, foreign_call_executor)?;
     let public_abi = program.abi.public_abi();
     let (_, return_value) = public_abi.decode(&solved_witness)?;
 
-    Ok((return_value, solved_witness))
+    Ok((return_value, solved_witness, block_profile))
 }
 
 pub(crate) fn execute_program(
     compiled_program: &CompiledProgram,
     inputs_map: &InputMap,
+    foreign_call_executor: ForeignCallExecutor,
 ) -> Result<WitnessMap, CliError> {
+    let (solved_witness, _) =
+        execute_program_with_profile(compiled_program, inputs_map, foreign_call_executor)?;
+    Ok(solved_witness)
+}
+
+/// Executes the program, also returning how many times each of its blocks was executed
+/// if it was compiled with block instrumentation. Foreign calls are handled by
+/// `foreign_call_executor`, which also sets the step limit of the unconstrained functions.
+fn execute_program_with_profile(
+    compiled_program: &CompiledProgram,
+    inputs_map: &InputMap,
+    mut foreign_call_executor: ForeignCallExecutor,
+) -> Result<(WitnessMap, BlockProfile), CliError> {
     #[allow(deprecated)]
     let blackbox_solver = barretenberg_blackbox_solver::BarretenbergSolver::new();
 
     let initial_witness = compiled_program.abi.encode(inputs_map, None)?;
 
-    let solved_witness_err = nargo::ops::execute_circuit(
+    let solved_witness_err = nargo::ops::execute_circuit_with_executor(
         &blackbox_solver,
         &compiled_program.circuit,
         initial_witness,
         true,
+        &mut foreign_call_executor,
     );
     match solved_witness_err {
-        Ok(solved_witness) => Ok(solved_witness),
+        Ok(solved_witness) => {
+            let mut block_profile = foreign_call_executor.block_profile().clone();
+            block_profile.add_witness_tallies(&compiled_program.debug, &solved_witness);
+            Ok((solved_witness, block_profile))
+        }
         Err(err) => {
             let debug_artifact = DebugArtifact {
                 debug_symbols: vec![compiled_program.debug.clone()],
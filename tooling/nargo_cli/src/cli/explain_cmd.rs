@@ -0,0 +1,19 @@
+use clap::Args;
+use noirc_errors::ErrorCode;
+
+use crate::errors::CliError;
+
+/// Prints a detailed explanation of an error code
+///
+/// Error codes are printed alongside each compiler diagnostic, e.g. `error[E0202]`.
+#[derive(Debug, Clone, Args)]
+pub(crate) struct ExplainCommand {
+    /// The error code to explain, e.g. `E0202`
+    code: String,
+}
+
+pub(crate) fn run(args: ExplainCommand) -> Result<(), CliError> {
+    let code = ErrorCode::lookup(&args.code).ok_or(CliError::UnknownErrorCode(args.code))?;
+    print!("{}", code.explanation());
+    Ok(())
+}
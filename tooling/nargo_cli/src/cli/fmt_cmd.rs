@@ -3,7 +3,7 @@ use std::{fs::DirEntry, path::Path};
 use clap::Args;
 use fm::FileManager;
 use nargo_toml::{get_package_manifest, resolve_workspace_from_toml, PackageSelection};
-use noirc_errors::CustomDiagnostic;
+use noirc_errors::{CustomDiagnostic, MessageFormat};
 use noirc_frontend::hir::def_map::parse_file;
 
 use crate::errors::CliError;
@@ -42,6 +42,7 @@ pub(crate) fn run(_args: FormatCommand, config: NargoConfig) -> Result<(), CliEr
                     &file_manager,
                     false,
                     false,
+                    MessageFormat::Human,
                 );
                 return Ok(());
             }
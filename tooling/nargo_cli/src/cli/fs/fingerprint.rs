@@ -0,0 +1,173 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::hash::Hasher;
+use std::path::{Path, PathBuf};
+
+use fxhash::FxHasher;
+use nargo::constants::{PKG_FILE, SRC_DIR};
+use nargo::package::Package;
+use noirc_driver::CompileOptions;
+
+use super::{create_named_dir, write_to_file};
+
+/// The directory within `target` storing the fingerprint of each compiled package
+const FINGERPRINT_DIR: &str = ".fingerprint";
+
+/// Computes a fingerprint of everything known before compiling `package` to affect its artifacts:
+/// the sources and manifests of the package and its dependencies, the options it is compiled
+/// with and the version of nargo compiling it, which determines the standard library. The
+/// environment variables read by `std::env!` and the files read by `std::include_bytes!` and
+/// `std::include_str!` are only known once it has been compiled, so they are added by
+/// [`save_fingerprint`].
+pub(crate) fn package_fingerprint(
+    package: &Package,
+    compile_options: &CompileOptions,
+    output_debug: bool,
+    nargo_version: &str,
+) -> u64 {
+    let mut hasher = FxHasher::default();
+    hasher.write(nargo_version.as_bytes());
+    hasher.write(&serde_json::to_vec(compile_options).expect("compile options are serializable"));
+    hasher.write_u8(output_debug as u8);
+
+    for path in source_files(package) {
+        hasher.write(path.to_string_lossy().as_bytes());
+        // A missing file is hashed as empty, so it changes the fingerprint if it is created
+        hasher.write(&std::fs::read(&path).unwrap_or_default());
+    }
+    hasher.finish()
+}
+
+/// The files which make up `package` and its dependencies, in a deterministic order
+fn source_files(package: &Package) -> BTreeSet<PathBuf> {
+    let mut files = BTreeSet::new();
+    let mut packages = vec![package];
+    let mut visited = BTreeSet::new();
+
+    while let Some(package) = packages.pop() {
+        if !visited.insert(package.root_dir.clone()) {
+            continue;
+        }
+        files.insert(package.root_dir.join(PKG_FILE));
+        collect_noir_files(&package.root_dir.join(SRC_DIR), &mut files);
+        packages.extend(package.dependencies.values().map(|dependency| dependency.package()));
+    }
+    files
+}
+
+fn collect_noir_files(dir: &Path, files: &mut BTreeSet<PathBuf>) {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return;
+    };
+    for path in entries.flatten().map(|entry| entry.path()) {
+        if path.is_dir() {
+            collect_noir_files(&path, files);
+        } else if path.extension().map_or(false, |extension| extension == "nr") {
+            files.insert(path);
+        }
+    }
+}
+
+/// Combines `fingerprint` with what was read while compiling: the names and values of the
+/// environment variables read by `std::env!`, where a variable which isn't set has no value, and
+/// the paths and current contents of the files read by `std::include_bytes!` and
+/// `std::include_str!`.
+fn with_compile_reads<'a>(
+    fingerprint: u64,
+    environment: impl IntoIterator<Item = (String, Option<String>)>,
+    included_files: impl IntoIterator<Item = &'a Path>,
+) -> u64 {
+    let mut hasher = FxHasher::default();
+    hasher.write_u64(fingerprint);
+    for (name, value) in environment {
+        hasher.write(name.as_bytes());
+        hasher.write_u8(0);
+        match value {
+            Some(value) => {
+                hasher.write_u8(1);
+                hasher.write(value.as_bytes());
+                hasher.write_u8(0);
+            }
+            None => hasher.write_u8(2),
+        }
+    }
+    for path in included_files {
+        hasher.write(path.to_string_lossy().as_bytes());
+        hasher.write_u8(0);
+        match std::fs::read(path) {
+            Ok(contents) => {
+                hasher.write_u8(1);
+                hasher.write_usize(contents.len());
+                hasher.write(&contents);
+            }
+            Err(_) => hasher.write_u8(2),
+        }
+    }
+    hasher.finish()
+}
+
+/// Whether `package` was last compiled with the same `fingerprint`, the same values of the
+/// environment variables it read then and the same contents of the files it included then, and
+/// the artifact compiled then still exists.
+pub(crate) fn is_fresh(target_dir: &Path, package: &Package, fingerprint: u64) -> bool {
+    let Ok(contents) = std::fs::read_to_string(fingerprint_path(target_dir, package)) else {
+        return false;
+    };
+    let mut lines = contents.lines();
+    let saved_fingerprint = lines.next().and_then(|line| u64::from_str_radix(line, 16).ok());
+    let Some(artifact) = lines.next() else {
+        return false;
+    };
+
+    let mut environment = Vec::new();
+    let mut included_files = Vec::new();
+    for line in lines {
+        match line.split_once(' ') {
+            Some(("env", name)) => environment.push((name.to_owned(), std::env::var(name).ok())),
+            Some(("file", path)) => included_files.push(PathBuf::from(path)),
+            _ => return false,
+        }
+    }
+    let included_files = included_files.iter().map(PathBuf::as_path);
+    saved_fingerprint == Some(with_compile_reads(fingerprint, environment, included_files))
+        && target_dir.join(artifact).exists()
+}
+
+/// Saves the `fingerprint` of `package`, combined with the `environment` variables read and the
+/// `included_files` read while compiling it, along with the name of the artifact compiled from
+/// it, which is a file in `target_dir`, and the names of those variables and files.
+pub(crate) fn save_fingerprint(
+    target_dir: &Path,
+    package: &Package,
+    fingerprint: u64,
+    artifact: &Path,
+    environment: &BTreeMap<String, String>,
+    included_files: &BTreeSet<PathBuf>,
+) {
+    let artifact = artifact.file_name().expect("artifacts are files").to_string_lossy();
+    // Paths are saved as text, so they are hashed as they will be read back
+    let included_files = included_files
+        .iter()
+        .map(|path| PathBuf::from(path.to_string_lossy().into_owned()))
+        .collect::<Vec<_>>();
+    let fingerprint = with_compile_reads(
+        fingerprint,
+        environment.iter().map(|(name, value)| (name.clone(), Some(value.clone()))),
+        included_files.iter().map(PathBuf::as_path),
+    );
+    let mut contents = format!("{fingerprint:016x}\n{artifact}");
+    for name in environment.keys() {
+        contents.push_str("\nenv ");
+        contents.push_str(name);
+    }
+    for path in &included_files {
+        contents.push_str("\nfile ");
+        contents.push_str(&path.to_string_lossy());
+    }
+    create_named_dir(&target_dir.join(FINGERPRINT_DIR), "fingerprint");
+    write_to_file(contents.as_bytes(), &fingerprint_path(target_dir, package));
+}
+
+fn fingerprint_path(target_dir: &Path, package: &Package) -> PathBuf {
+    let name: String = package.name.clone().into();
+    target_dir.join(FINGERPRINT_DIR).join(name)
+}
@@ -101,9 +101,10 @@ mod tests {
             return_type: Some(AbiType::Field),
 
             // Input serialization is only dependent on types, not position in witness map.
-            // Neither of these should be relevant so we leave them empty.
+            // None of these should be relevant so we leave them empty.
             param_witnesses: BTreeMap::new(),
             return_witnesses: Vec::new(),
+            return_constants: BTreeMap::new(),
         };
         let input_map = BTreeMap::from([
             ("foo".to_owned(), InputValue::Field(42u128.into())),
@@ -1,7 +1,8 @@
 use std::path::{Path, PathBuf};
 
 use nargo::artifacts::{
-    contract::PreprocessedContract, debug::DebugArtifact, program::PreprocessedProgram,
+    check_artifact_version, contract::PreprocessedContract, debug::DebugArtifact,
+    program::PreprocessedProgram,
 };
 use noirc_frontend::graph::CrateName;
 
@@ -55,7 +56,9 @@ pub(crate) fn read_program_from_file<P: AsRef<Path>>(
 
     let input_string =
         std::fs::read(&file_path).map_err(|_| FilesystemError::PathNotValid(file_path))?;
-    let program = serde_json::from_slice(&input_string)
+    let program: PreprocessedProgram = serde_json::from_slice(&input_string)
+        .map_err(|err| FilesystemError::ProgramSerializationError(err.to_string()))?;
+    check_artifact_version(program.artifact_version)
         .map_err(|err| FilesystemError::ProgramSerializationError(err.to_string()))?;
 
     Ok(program)
@@ -1,4 +1,4 @@
-use clap::{Args, Parser, Subcommand};
+use clap::{Args, Parser, Subcommand, ValueEnum};
 use const_format::formatcp;
 use nargo_toml::find_package_root;
 use std::path::PathBuf;
@@ -15,13 +15,17 @@ mod codegen_verifier_cmd;
 mod compile_cmd;
 mod debug_cmd;
 mod execute_cmd;
+mod explain_cmd;
 mod fmt_cmd;
 mod info_cmd;
 mod init_cmd;
 mod lsp_cmd;
 mod new_cmd;
+mod oracle_resolver;
+mod oracles_cmd;
 mod prove_cmd;
 mod test_cmd;
+mod tree_cmd;
 mod verify_cmd;
 
 const GIT_HASH: &str = env!("GIT_COMMIT");
@@ -39,6 +43,16 @@ struct NargoCli {
 
     #[clap(flatten)]
     config: NargoConfig,
+
+    /// Write structured logs of each compiler phase and its duration to stderr as `text` or `json`
+    #[arg(long, global = true, value_enum)]
+    trace_output: Option<TraceOutput>,
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug)]
+enum TraceOutput {
+    Text,
+    Json,
 }
 
 #[non_exhaustive]
@@ -62,17 +76,24 @@ enum NargoCommand {
     New(new_cmd::NewCommand),
     Init(init_cmd::InitCommand),
     Execute(execute_cmd::ExecuteCommand),
+    Explain(explain_cmd::ExplainCommand),
     #[command(hide = true)] // Hidden while the feature is being built out
     Debug(debug_cmd::DebugCommand),
     Prove(prove_cmd::ProveCommand),
     Verify(verify_cmd::VerifyCommand),
     Test(test_cmd::TestCommand),
     Info(info_cmd::InfoCommand),
+    Oracles(oracles_cmd::OraclesCommand),
+    Tree(tree_cmd::TreeCommand),
     Lsp(lsp_cmd::LspCommand),
 }
 
 pub(crate) fn start_cli() -> eyre::Result<()> {
-    let NargoCli { command, mut config } = NargoCli::parse();
+    let NargoCli { command, mut config, trace_output } = NargoCli::parse();
+
+    if let Some(trace_output) = trace_output {
+        init_tracing(trace_output);
+    }
 
     // If the provided `program_dir` is relative, make it absolute by joining it to the current directory.
     if !config.program_dir.is_absolute() {
@@ -86,6 +107,7 @@ pub(crate) fn start_cli() -> eyre::Result<()> {
             | NargoCommand::Init(_)
             | NargoCommand::Lsp(_)
             | NargoCommand::Backend(_)
+            | NargoCommand::Explain(_)
     ) {
         config.program_dir = find_package_root(&config.program_dir)?;
     }
@@ -100,10 +122,13 @@ pub(crate) fn start_cli() -> eyre::Result<()> {
         NargoCommand::Compile(args) => compile_cmd::run(&backend, args, config),
         NargoCommand::Debug(args) => debug_cmd::run(&backend, args, config),
         NargoCommand::Execute(args) => execute_cmd::run(&backend, args, config),
+        NargoCommand::Explain(args) => explain_cmd::run(args),
         NargoCommand::Prove(args) => prove_cmd::run(&backend, args, config),
         NargoCommand::Verify(args) => verify_cmd::run(&backend, args, config),
         NargoCommand::Test(args) => test_cmd::run(&backend, args, config),
         NargoCommand::Info(args) => info_cmd::run(&backend, args, config),
+        NargoCommand::Oracles(args) => oracles_cmd::run(args, config),
+        NargoCommand::Tree(args) => tree_cmd::run(args, config),
         NargoCommand::CodegenVerifier(args) => codegen_verifier_cmd::run(&backend, args, config),
         NargoCommand::Backend(args) => backend_cmd::run(args),
         NargoCommand::Lsp(args) => lsp_cmd::run(&backend, args, config),
@@ -112,3 +137,21 @@ pub(crate) fn start_cli() -> eyre::Result<()> {
 
     Ok(())
 }
+
+/// Installs a subscriber printing the spans emitted by the compiler, and how long each took, to
+/// stderr. The `NARGO_LOG` environment variable can be used to filter them, e.g.
+/// `NARGO_LOG=noirc_evaluator=debug`, and defaults to `info`.
+fn init_tracing(output: TraceOutput) {
+    use tracing_subscriber::{fmt::format::FmtSpan, EnvFilter};
+
+    let filter = EnvFilter::try_from_env("NARGO_LOG").unwrap_or_else(|_| EnvFilter::new("info"));
+    let subscriber = tracing_subscriber::fmt()
+        .with_env_filter(filter)
+        .with_span_events(FmtSpan::CLOSE)
+        .with_writer(std::io::stderr);
+
+    match output {
+        TraceOutput::Text => subscriber.init(),
+        TraceOutput::Json => subscriber.json().init(),
+    }
+}
@@ -0,0 +1,85 @@
+//! A mock oracle server for the `{{package}}` package, generated by `nargo oracles`.
+//!
+//! It resolves foreign calls sent as JSON-RPC 2.0 `resolve_foreign_call` requests over HTTP,
+//! returning zeroed values of the right shape for each oracle. Edit `resolve` to return the values
+//! your tests expect.
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{TcpListener, TcpStream};
+
+use serde_json::{json, Value};
+
+/// Returns the result of a call to the oracle `function` with the given inputs, or `None` if the
+/// oracle is unknown.
+///
+/// Each input is either `{"Single": {"inner": "<hex>"}}` or `{"Array": [{"inner": "<hex>"}, ...]}`
+/// and results take the form `{"values": [<value>, ...]}` using the same encoding.
+fn resolve(function: &str, _inputs: &[Value]) -> Option<Value> {
+    let result = match function {
+{{responses}}
+        _ => return None,
+    };
+    Some(serde_json::from_str(result).expect("oracle results should be valid JSON"))
+}
+
+fn main() {
+    let address = std::env::args().nth(1).unwrap_or_else(|| "127.0.0.1:5555".to_owned());
+    let listener = TcpListener::bind(&address).expect("could not bind the server address");
+    println!("Resolving oracles at http://{address}");
+
+    for stream in listener.incoming().flatten() {
+        if let Err(error) = handle_connection(stream) {
+            eprintln!("failed to handle a request: {error}");
+        }
+    }
+}
+
+fn handle_connection(mut stream: TcpStream) -> std::io::Result<()> {
+    let mut reader = BufReader::new(stream.try_clone()?);
+    let mut content_length = 0;
+    loop {
+        let mut line = String::new();
+        reader.read_line(&mut line)?;
+        let line = line.trim_end();
+        if line.is_empty() {
+            break;
+        }
+        if let Some((name, value)) = line.split_once(':') {
+            if name.eq_ignore_ascii_case("content-length") {
+                content_length = value.trim().parse().unwrap_or(0);
+            }
+        }
+    }
+    let mut body = vec![0; content_length];
+    reader.read_exact(&mut body)?;
+
+    let response = match serde_json::from_slice::<Value>(&body) {
+        Ok(request) => respond(&request),
+        Err(error) => error_response(Value::Null, -32700, &error.to_string()),
+    };
+    let response = response.to_string();
+    write!(
+        stream,
+        "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\n\
+         Connection: close\r\n\r\n{response}",
+        response.len()
+    )
+}
+
+fn respond(request: &Value) -> Value {
+    let id = request["id"].clone();
+    if request["method"] != "resolve_foreign_call" {
+        return error_response(id, -32601, "method not found");
+    }
+
+    let call = &request["params"][0];
+    let function = call["function"].as_str().unwrap_or_default();
+    let inputs = call["inputs"].as_array().map(Vec::as_slice).unwrap_or_default();
+    match resolve(function, inputs) {
+        Some(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+        None => error_response(id, -32602, &format!("unknown oracle `{function}`")),
+    }
+}
+
+fn error_response(id: Value, code: i64, message: &str) -> Value {
+    json!({ "jsonrpc": "2.0", "id": id, "error": { "code": code, "message": message } })
+}
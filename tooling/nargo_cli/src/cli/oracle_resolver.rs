@@ -0,0 +1,283 @@
+//! Resolves the foreign calls of a program which it does not mock by sending them to an oracle
+//! resolver, as JSON-RPC 2.0 `resolve_foreign_call` requests over HTTP or HTTPS.
+//!
+//! Oracles aren't expected to be idempotent, so only requests which failed to connect to the
+//! resolver, and so were never received by it, are retried. They're retried according to the
+//! oracle's policy in the `[oracle-resolver]` section of the package's `Nargo.toml`.
+use std::{net::IpAddr, time::Duration};
+
+use acvm::{acir::brillig::ForeignCallResult, pwg::ForeignCallWaitInfo};
+use nargo::{
+    errors::ExecutionError,
+    ops::ForeignCallResolver,
+    package::{OraclePolicy, OracleResolverConfig},
+};
+use reqwest::{blocking::Client, header::CONTENT_TYPE, Url};
+use serde_json::{json, Value};
+
+use crate::errors::CliError;
+
+#[derive(Debug, Clone)]
+pub(crate) struct HttpOracleResolver {
+    url: Url,
+    client: Client,
+    /// The bearer token sent with each request, if one is set in the environment
+    token: Option<String>,
+    config: OracleResolverConfig,
+    /// The id of the next JSON-RPC request
+    next_id: u64,
+}
+
+/// The reason a request to the resolver failed
+#[derive(Debug, PartialEq, Eq)]
+enum RequestError {
+    /// The request was never received by the resolver, so it may be sent again
+    NotSent(String),
+    Fatal(String),
+}
+
+impl HttpOracleResolver {
+    /// Creates a resolver sending requests to `url`, reading the bearer token from the
+    /// environment variable given by `config`. The token is only sent over HTTPS, or over HTTP
+    /// to a resolver on the same machine.
+    pub(crate) fn new(url: &str, config: OracleResolverConfig) -> Result<Self, CliError> {
+        let invalid_url = |reason: String| {
+            CliError::Generic(format!("Invalid oracle resolver `{url}`: {reason}"))
+        };
+        let url = Url::parse(url).map_err(|error| invalid_url(error.to_string()))?;
+        if !matches!(url.scheme(), "http" | "https") {
+            return Err(invalid_url("only HTTP and HTTPS URLs are supported".to_string()));
+        }
+
+        let token = std::env::var(config.token_env()).ok().filter(|token| !token.is_empty());
+        if token.is_some() && url.scheme() == "http" && !is_loopback(&url) {
+            return Err(invalid_url(format!(
+                "refusing to send the token set in `{}` over plain HTTP, use an HTTPS URL instead",
+                config.token_env()
+            )));
+        }
+        Ok(HttpOracleResolver { url, client: Client::new(), token, config, next_id: 0 })
+    }
+
+    /// Sends a single request, without retrying it
+    fn send(&self, body: &str, policy: &OraclePolicy) -> Result<ForeignCallResult, RequestError> {
+        let mut request = self
+            .client
+            .post(self.url.clone())
+            .timeout(policy.timeout)
+            .header(CONTENT_TYPE, "application/json")
+            .body(body.to_owned());
+        if let Some(token) = &self.token {
+            request = request.bearer_auth(token);
+        }
+
+        // Once a request may have reached the resolver, such as one which timed out, sending it
+        // again could make the oracle call twice
+        let response = request.send().map_err(|error| {
+            if error.is_connect() {
+                RequestError::NotSent(error.to_string())
+            } else {
+                RequestError::Fatal(error.to_string())
+            }
+        })?;
+
+        let status = response.status();
+        if !status.is_success() {
+            return Err(RequestError::Fatal(format!("the resolver responded with {status}")));
+        }
+
+        let body = response.text().map_err(|error| RequestError::Fatal(error.to_string()))?;
+        parse_response(&body)
+    }
+}
+
+impl ForeignCallResolver for HttpOracleResolver {
+    fn resolve(
+        &mut self,
+        foreign_call: &ForeignCallWaitInfo,
+    ) -> Result<ForeignCallResult, ExecutionError> {
+        let request = json!({
+            "jsonrpc": "2.0",
+            "id": self.next_id,
+            "method": "resolve_foreign_call",
+            "params": [{ "function": foreign_call.function, "inputs": foreign_call.inputs }],
+        });
+        self.next_id += 1;
+        let body = request.to_string();
+
+        let policy = self.config.policy(&foreign_call.function);
+        let mut delays = retry_delays(policy);
+        let reason = loop {
+            match self.send(&body, policy) {
+                Ok(result) => return Ok(result),
+                Err(RequestError::NotSent(reason)) => match delays.next() {
+                    Some(delay) => std::thread::sleep(delay),
+                    None => break reason,
+                },
+                Err(RequestError::Fatal(reason)) => break reason,
+            }
+        };
+        Err(ExecutionError::OracleResolutionFailed { name: foreign_call.function.clone(), reason })
+    }
+}
+
+/// Returns whether `url` points to the machine nargo runs on
+fn is_loopback(url: &Url) -> bool {
+    match url.host_str() {
+        Some("localhost") => true,
+        Some(host) => host
+            .trim_start_matches('[')
+            .trim_end_matches(']')
+            .parse::<IpAddr>()
+            .map_or(false, |ip| ip.is_loopback()),
+        None => false,
+    }
+}
+
+/// Returns how long to wait before each retry allowed by `policy`
+fn retry_delays(policy: &OraclePolicy) -> impl Iterator<Item = Duration> {
+    let backoff = policy.backoff;
+    (0..policy.retries).map(move |retry| backoff.saturating_mul(2u32.saturating_pow(retry)))
+}
+
+/// Extracts the result of a foreign call from the body of a JSON-RPC response
+fn parse_response(body: &str) -> Result<ForeignCallResult, RequestError> {
+    let invalid_response = |reason: String| {
+        RequestError::Fatal(format!("invalid response from the resolver: {reason}"))
+    };
+    let response: Value =
+        serde_json::from_str(body).map_err(|error| invalid_response(error.to_string()))?;
+
+    if let Some(error) = response.get("error") {
+        let message = error["message"].as_str().unwrap_or("unknown error");
+        return Err(RequestError::Fatal(format!("the resolver returned an error: {message}")));
+    }
+    match response.get("result") {
+        Some(result) => serde_json::from_value(result.clone())
+            .map_err(|error| invalid_response(error.to_string())),
+        None => Err(invalid_response("missing `result`".to_string())),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{Read, Write};
+    use std::net::TcpListener;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    use acvm::acir::brillig::{ForeignCallParam, ForeignCallResult, Value};
+    use acvm::pwg::ForeignCallWaitInfo;
+    use nargo::ops::ForeignCallResolver;
+    use nargo::package::{OraclePolicy, OracleResolverConfig};
+
+    use super::{parse_response, retry_delays, HttpOracleResolver, RequestError};
+
+    /// A configuration retrying each failed call 3 times, without waiting between retries
+    fn config() -> OracleResolverConfig {
+        let default_policy =
+            OraclePolicy { retries: 3, backoff: Duration::ZERO, ..Default::default() };
+        OracleResolverConfig { default_policy, ..Default::default() }
+    }
+
+    fn foreign_call() -> ForeignCallWaitInfo {
+        ForeignCallWaitInfo { function: "get_price".to_owned(), inputs: Vec::new() }
+    }
+
+    /// Starts a resolver answering each request with `503 Service Unavailable`, returning its
+    /// URL and the number of requests it has received
+    fn unavailable_resolver() -> (String, Arc<AtomicUsize>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let requests = Arc::new(AtomicUsize::new(0));
+        let counter = requests.clone();
+        std::thread::spawn(move || {
+            for mut stream in listener.incoming().flatten() {
+                let _ = stream.read(&mut [0; 4096]);
+                counter.fetch_add(1, Ordering::SeqCst);
+                let response = "HTTP/1.1 503 Service Unavailable\r\n\
+                                Content-Length: 0\r\nConnection: close\r\n\r\n";
+                let _ = stream.write_all(response.as_bytes());
+            }
+        });
+        (url, requests)
+    }
+
+    #[test]
+    fn retries_back_off_exponentially() {
+        let policy =
+            OraclePolicy { retries: 3, backoff: Duration::from_millis(100), ..Default::default() };
+        let delays: Vec<_> = retry_delays(&policy).map(|delay| delay.as_millis()).collect();
+        assert_eq!(delays, vec![100, 200, 400]);
+
+        let policy = OraclePolicy { retries: 0, ..policy };
+        assert_eq!(retry_delays(&policy).count(), 0);
+    }
+
+    #[test]
+    fn requests_which_fail_to_connect_may_be_sent_again() {
+        // Nothing listens on a port once the listener bound to it is dropped
+        let address = TcpListener::bind("127.0.0.1:0").unwrap().local_addr().unwrap();
+        let resolver = HttpOracleResolver::new(&format!("http://{address}"), config()).unwrap();
+        let policy = resolver.config.policy("get_price");
+        assert!(matches!(resolver.send("{}", policy), Err(RequestError::NotSent(_))));
+    }
+
+    #[test]
+    fn requests_received_by_the_resolver_are_not_retried() {
+        let (url, requests) = unavailable_resolver();
+        let mut resolver = HttpOracleResolver::new(&url, config()).unwrap();
+
+        let error = resolver.resolve(&foreign_call()).unwrap_err();
+        assert!(error.to_string().contains("503"), "Unexpected error: {error}");
+        assert_eq!(requests.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn parses_results_and_errors() {
+        let zero = serde_json::json!({ "inner": "00".repeat(32) });
+        let body = serde_json::json!({ "jsonrpc": "2.0", "id": 0, "result": {
+            "values": [{ "Single": zero }, { "Array": [zero] }]
+        }});
+        let zero = Value::from(0u128);
+        assert_eq!(
+            parse_response(&body.to_string()),
+            Ok(ForeignCallResult {
+                values: vec![ForeignCallParam::Single(zero), ForeignCallParam::Array(vec![zero])]
+            })
+        );
+
+        let body = r#"{"jsonrpc":"2.0","id":0,"error":{"code":-32602,"message":"unknown oracle"}}"#;
+        assert!(matches!(
+            parse_response(body),
+            Err(RequestError::Fatal(reason)) if reason.contains("unknown oracle")
+        ));
+        assert!(matches!(parse_response("not json"), Err(RequestError::Fatal(_))));
+    }
+
+    #[test]
+    fn rejects_unsupported_urls() {
+        let config = OracleResolverConfig::default();
+        assert!(HttpOracleResolver::new("https://oracles.example.com/rpc", config.clone()).is_ok());
+        assert!(HttpOracleResolver::new("ftp://oracles.example.com", config.clone()).is_err());
+        assert!(HttpOracleResolver::new("localhost:5555", config).is_err());
+    }
+
+    #[test]
+    fn tokens_are_only_sent_over_plain_http_to_the_local_machine() {
+        let token_env = "NARGO_TEST_PLAIN_HTTP_ORACLE_TOKEN";
+        std::env::set_var(token_env, "secret");
+        let config = OracleResolverConfig { token_env: Some(token_env.to_owned()), ..config() };
+
+        let error = HttpOracleResolver::new("http://oracles.example.com", config.clone());
+        assert!(error.unwrap_err().to_string().contains("over plain HTTP"));
+        assert!(HttpOracleResolver::new("https://oracles.example.com", config.clone()).is_ok());
+        for url in ["http://localhost:5555", "http://127.0.0.1:5555", "http://[::1]:5555"] {
+            assert!(HttpOracleResolver::new(url, config.clone()).is_ok(), "Expected {url} to work");
+        }
+
+        std::env::remove_var(token_env);
+        assert!(HttpOracleResolver::new("http://oracles.example.com", config).is_ok());
+    }
+}
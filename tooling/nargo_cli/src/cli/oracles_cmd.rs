@@ -0,0 +1,209 @@
+use acvm::acir::brillig::{ForeignCallParam, ForeignCallResult, Value};
+use acvm::FieldElement;
+use clap::Args;
+use iter_extended::vecmap;
+use nargo::{ops::is_native_foreign_call, package::Package, prepare_package};
+use nargo_toml::{get_package_manifest, resolve_workspace_from_toml, PackageSelection};
+use noirc_driver::{
+    compute_oracle_signatures, CompileOptions, ForeignCallValueKind, ForeignCallValueSchema,
+    OracleSignature,
+};
+use noirc_frontend::graph::CrateName;
+use serde::Serialize;
+
+use crate::errors::{CliError, CompileError};
+
+use super::check_cmd::check_crate_and_report_errors;
+use super::fs::{create_named_dir, write_to_file};
+use super::NargoConfig;
+
+const ORACLE_SERVER_TEMPLATE: &str = include_str!("./noir_template_files/oracle_server.rs");
+
+/// Generates a schema of the oracles called by the program, and optionally a mock oracle server
+#[derive(Debug, Clone, Args)]
+pub(crate) struct OraclesCommand {
+    /// Also generate the source of a mock server resolving each oracle
+    #[clap(long)]
+    mock_server: bool,
+
+    /// The name of the package to generate the schema of
+    #[clap(long, conflicts_with = "workspace")]
+    package: Option<CrateName>,
+
+    /// Generate the schemas of all packages in the workspace
+    #[clap(long, conflicts_with = "package")]
+    workspace: bool,
+
+    #[clap(flatten)]
+    compile_options: CompileOptions,
+}
+
+/// The oracles of a package which are resolved outside of nargo, written to
+/// `target/<package>_oracles.json`.
+#[derive(Debug, Serialize)]
+struct OracleSchema {
+    oracles: Vec<OracleSignature>,
+}
+
+pub(crate) fn run(args: OraclesCommand, config: NargoConfig) -> Result<(), CliError> {
+    let toml_path = get_package_manifest(&config.program_dir)?;
+    let default_selection =
+        if args.workspace { PackageSelection::All } else { PackageSelection::DefaultOrAll };
+    let selection = args.package.map_or(default_selection, PackageSelection::Selected);
+    let workspace = resolve_workspace_from_toml(&toml_path, selection)?;
+    let target_dir = workspace.target_directory_path();
+
+    for package in &workspace {
+        let schema = compute_oracle_schema(package, &args.compile_options)?;
+        let name: String = package.name.clone().into();
+
+        create_named_dir(&target_dir, "target");
+        let schema_json =
+            serde_json::to_string_pretty(&schema).expect("oracle schemas should serialize");
+        let path = write_to_file(
+            schema_json.as_bytes(),
+            &target_dir.join(format!("{name}_oracles")).with_extension("json"),
+        );
+        println!("[{}] Oracle schema written to {path}", package.name);
+
+        if args.mock_server {
+            let server_name = format!("{name}_oracle_server");
+            let server_dir = create_named_dir(&target_dir.join(&server_name).join("src"), "src");
+            write_to_file(
+                server_manifest(&server_name).as_bytes(),
+                &target_dir.join(&server_name).join("Cargo.toml"),
+            );
+            let path = write_to_file(
+                server_source(&name, &schema.oracles).as_bytes(),
+                &server_dir.join("main.rs"),
+            );
+            println!("[{}] Mock oracle server written to {path}", package.name);
+        }
+    }
+    Ok(())
+}
+
+fn compute_oracle_schema(
+    package: &Package,
+    compile_options: &CompileOptions,
+) -> Result<OracleSchema, CompileError> {
+    let (mut context, crate_id) =
+        prepare_package(package, Box::new(|path| std::fs::read_to_string(path)));
+    check_crate_and_report_errors(&mut context, crate_id, compile_options)?;
+
+    let mut oracles = compute_oracle_signatures(&context);
+    oracles.retain(|oracle| !is_native_foreign_call(&oracle.name));
+    Ok(OracleSchema { oracles })
+}
+
+fn server_manifest(server_name: &str) -> String {
+    format!(
+        r#"[package]
+name = "{server_name}"
+version = "0.1.0"
+edition = "2021"
+
+[dependencies]
+serde_json = "1.0"
+
+# Keeps the server out of any workspace containing the Noir project
+[workspace]
+"#
+    )
+}
+
+/// Returns the source of a mock server answering each call to an oracle with zeroed values.
+///
+/// Oracles declared by several functions are answered according to the first of them.
+fn server_source(package_name: &str, oracles: &[OracleSignature]) -> String {
+    let mut responses = String::new();
+    let mut resolved = Vec::new();
+    for oracle in oracles {
+        if resolved.contains(&&oracle.name) {
+            continue;
+        }
+        resolved.push(&oracle.name);
+
+        let result = serde_json::to_string(&default_result(&oracle.outputs))
+            .expect("foreign call results should serialize");
+        responses.push_str(&format!(
+            "        // {}({}) -> ({})\n        {:?} => r#\"{result}\"#,\n",
+            oracle.function,
+            describe_values(&oracle.inputs),
+            describe_values(&oracle.outputs),
+            oracle.name,
+        ));
+    }
+
+    ORACLE_SERVER_TEMPLATE
+        .replace("{{package}}", package_name)
+        .replace("{{responses}}\n", &responses)
+}
+
+fn describe_values(values: &[ForeignCallValueSchema]) -> String {
+    vecmap(values, |value| format!("{}: {}", value.path, value.typ)).join(", ")
+}
+
+/// Returns a result holding zeroes for each of the given outputs of a foreign call. Arrays of
+/// unknown length, such as slices, are returned empty.
+fn default_result(outputs: &[ForeignCallValueSchema]) -> ForeignCallResult {
+    let zero = Value::from(FieldElement::zero());
+    let values = vecmap(outputs, |output| match output.kind {
+        ForeignCallValueKind::Single | ForeignCallValueKind::Generic => {
+            ForeignCallParam::Single(zero)
+        }
+        ForeignCallValueKind::Array { length } => {
+            ForeignCallParam::Array(vec![zero; length.unwrap_or(0) as usize])
+        }
+    });
+    ForeignCallResult { values }
+}
+
+#[cfg(test)]
+mod tests {
+    use noirc_driver::{ForeignCallValueKind, ForeignCallValueSchema, OracleSignature};
+
+    use super::{default_result, server_source};
+
+    fn value(path: &str, typ: &str, kind: ForeignCallValueKind) -> ForeignCallValueSchema {
+        ForeignCallValueSchema { path: path.to_owned(), typ: typ.to_owned(), kind }
+    }
+
+    #[test]
+    fn default_results_match_the_shape_of_the_outputs() {
+        let outputs = vec![
+            value("return.0", "u64", ForeignCallValueKind::Single),
+            value("return.1", "[Field; 2]", ForeignCallValueKind::Array { length: Some(2) }),
+            value("return.2.len", "Field", ForeignCallValueKind::Single),
+            value("return.2", "[Field]", ForeignCallValueKind::Array { length: None }),
+        ];
+        let result = serde_json::to_value(default_result(&outputs)).unwrap();
+
+        let zero = serde_json::json!({ "inner": "00".repeat(32) });
+        let expected = serde_json::json!({
+            "values": [
+                { "Single": zero },
+                { "Array": [zero, zero] },
+                { "Single": zero },
+                { "Array": [] },
+            ]
+        });
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    fn mock_servers_resolve_each_oracle_once() {
+        let oracle = |function: &str| OracleSignature {
+            name: "get_price".to_owned(),
+            function: function.to_owned(),
+            inputs: vec![value("pair", "Field", ForeignCallValueKind::Single)],
+            outputs: vec![value("return", "u64", ForeignCallValueKind::Single)],
+        };
+        let source = server_source("prices", &[oracle("get_price"), oracle("get_price_u64")]);
+
+        assert!(source.contains("the `prices` package"));
+        assert!(source.contains("// get_price(pair: Field) -> (return: u64)"));
+        assert_eq!(source.matches("\"get_price\" =>").count(), 1);
+        assert!(!source.contains("{{responses}}"));
+    }
+}
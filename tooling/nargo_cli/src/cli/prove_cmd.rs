@@ -1,5 +1,6 @@
 use clap::Args;
 use nargo::constants::{PROVER_INPUT_FILE, VERIFIER_INPUT_FILE};
+use nargo::ops::ForeignCallExecutor;
 use nargo::package::Package;
 use nargo::workspace::Workspace;
 use nargo_toml::{get_package_manifest, resolve_workspace_from_toml, PackageSelection};
@@ -13,7 +14,11 @@ use super::fs::{
     proof::save_proof_to_dir,
 };
 use super::NargoConfig;
-use crate::{backends::Backend, cli::execute_cmd::execute_program, errors::CliError};
+use crate::{
+    backends::Backend,
+    cli::execute_cmd::{execute_program, foreign_call_executor},
+    errors::CliError,
+};
 
 /// Create proof for this program. The proof is returned as a hex encoded string.
 #[derive(Debug, Clone, Args)]
@@ -38,6 +43,10 @@ pub(crate) struct ProveCommand {
     #[clap(long, conflicts_with = "package")]
     workspace: bool,
 
+    /// JSON-RPC endpoint resolving the foreign calls which the program does not mock
+    #[clap(long, value_name = "URL")]
+    oracle_resolver: Option<String>,
+
     #[clap(flatten)]
     compile_options: CompileOptions,
 }
@@ -72,12 +81,14 @@ pub(crate) fn run(
             &args.prover_name,
             &args.verifier_name,
             args.verify,
+            foreign_call_executor(package, &args.compile_options, args.oracle_resolver.as_deref())?,
         )?;
     }
 
     Ok(())
 }
 
+#[allow(clippy::too_many_arguments)]
 pub(crate) fn prove_package(
     backend: &Backend,
     workspace: &Workspace,
@@ -86,12 +97,13 @@ pub(crate) fn prove_package(
     prover_name: &str,
     verifier_name: &str,
     check_proof: bool,
+    foreign_call_executor: ForeignCallExecutor,
 ) -> Result<(), CliError> {
     // Parse the initial witness values from Prover.toml
     let (inputs_map, _) =
         read_inputs_from_file(&package.root_dir, prover_name, Format::Toml, &compiled_program.abi)?;
 
-    let solved_witness = execute_program(&compiled_program, &inputs_map)?;
+    let solved_witness = execute_program(&compiled_program, &inputs_map, foreign_call_executor)?;
 
     // Write public inputs into Verifier.toml
     let public_abi = compiled_program.abi.public_abi();
@@ -3,18 +3,18 @@ use std::io::Write;
 use acvm::BlackBoxFunctionSolver;
 use clap::Args;
 use nargo::{
-    ops::{run_test, TestStatus},
+    ops::{run_test, ForeignCallResolver, TestStatus},
     package::Package,
-    prepare_package,
+    prepare_package_for_tests,
 };
-use nargo_toml::{get_package_manifest, resolve_workspace_from_toml, PackageSelection};
+use nargo_toml::{get_package_manifest, resolve_test_workspace_from_toml, PackageSelection};
 use noirc_driver::CompileOptions;
 use noirc_frontend::{graph::CrateName, hir::FunctionNameMatch};
 use termcolor::{Color, ColorChoice, ColorSpec, StandardStream, WriteColor};
 
 use crate::{backends::Backend, cli::check_cmd::check_crate_and_report_errors, errors::CliError};
 
-use super::NargoConfig;
+use super::{oracle_resolver::HttpOracleResolver, NargoConfig};
 
 /// Run the tests for this program
 #[derive(Debug, Clone, Args)]
@@ -38,6 +38,10 @@ pub(crate) struct TestCommand {
     #[clap(long, conflicts_with = "package")]
     workspace: bool,
 
+    /// JSON-RPC endpoint resolving the foreign calls which the tests do not mock
+    #[clap(long, value_name = "URL")]
+    oracle_resolver: Option<String>,
+
     #[clap(flatten)]
     compile_options: CompileOptions,
 }
@@ -51,7 +55,7 @@ pub(crate) fn run(
     let default_selection =
         if args.workspace { PackageSelection::All } else { PackageSelection::DefaultOrAll };
     let selection = args.package.map_or(default_selection, PackageSelection::Selected);
-    let workspace = resolve_workspace_from_toml(&toml_path, selection)?;
+    let workspace = resolve_test_workspace_from_toml(&toml_path, selection)?;
 
     let pattern = match &args.test_name {
         Some(name) => {
@@ -69,7 +73,19 @@ pub(crate) fn run(
     for package in &workspace {
         // By unwrapping here with `?`, we stop the test runner upon a package failing
         // TODO: We should run the whole suite even if there are failures in a package
-        run_tests(&blackbox_solver, package, pattern, args.show_output, &args.compile_options)?;
+        let resolver = args
+            .oracle_resolver
+            .as_deref()
+            .map(|url| HttpOracleResolver::new(url, package.oracle_resolver.clone()))
+            .transpose()?;
+        run_tests(
+            &blackbox_solver,
+            package,
+            pattern,
+            args.show_output,
+            &args.compile_options,
+            resolver.as_ref(),
+        )?;
     }
 
     Ok(())
@@ -81,15 +97,14 @@ fn run_tests<S: BlackBoxFunctionSolver>(
     test_name: FunctionNameMatch,
     show_output: bool,
     compile_options: &CompileOptions,
+    resolver: Option<&HttpOracleResolver>,
 ) -> Result<(), CliError> {
+    // Failing `assert_eq` and `assert_ne` report the values they compared while testing
+    let compile_options =
+        &CompileOptions { report_assert_values: true, ..package.compile_options(compile_options) };
     let (mut context, crate_id) =
-        prepare_package(package, Box::new(|path| std::fs::read_to_string(path)));
-    check_crate_and_report_errors(
-        &mut context,
-        crate_id,
-        compile_options.deny_warnings,
-        compile_options.silence_warnings,
-    )?;
+        prepare_package_for_tests(package, Box::new(|path| std::fs::read_to_string(path)));
+    check_crate_and_report_errors(&mut context, crate_id, compile_options)?;
 
     let test_functions = context.get_all_test_functions_in_crate_matching(&crate_id, test_name);
 
@@ -104,7 +119,17 @@ fn run_tests<S: BlackBoxFunctionSolver>(
             .expect("Failed to write to stdout");
         writer.flush().expect("Failed to flush writer");
 
-        match run_test(blackbox_solver, &context, test_function, show_output, compile_options) {
+        // Each test gets its own resolver, so that they all number their requests from 0
+        let resolver =
+            resolver.map(|resolver| Box::new(resolver.clone()) as Box<dyn ForeignCallResolver>);
+        match run_test(
+            blackbox_solver,
+            &context,
+            test_function,
+            show_output,
+            compile_options,
+            resolver,
+        ) {
             TestStatus::Pass { .. } => {
                 writer
                     .set_color(ColorSpec::new().set_fg(Some(Color::Green)))
@@ -125,6 +150,7 @@ fn run_tests<S: BlackBoxFunctionSolver>(
                         &[diag],
                         compile_options.deny_warnings,
                         compile_options.silence_warnings,
+                        compile_options.message_format,
                     );
                 }
                 failing += 1;
@@ -135,6 +161,7 @@ fn run_tests<S: BlackBoxFunctionSolver>(
                     &[err],
                     compile_options.deny_warnings,
                     compile_options.silence_warnings,
+                    compile_options.message_format,
                 );
                 failing += 1;
             }
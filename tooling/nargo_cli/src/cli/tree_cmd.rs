@@ -0,0 +1,227 @@
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+use std::io::{self, ErrorKind, Write};
+use std::path::Path;
+
+use clap::Args;
+use nargo::package::{Dependency, Package};
+use nargo_toml::{get_package_manifest, resolve_workspace_from_toml, PackageSelection};
+use noirc_frontend::graph::CrateName;
+use termcolor::{Color, ColorChoice, ColorSpec, StandardStream, WriteColor};
+
+use crate::errors::CliError;
+
+use super::NargoConfig;
+
+/// Display the dependency tree of a package
+///
+/// Packages which appear more than once with different sources are highlighted. The
+/// dependencies of a package are only shown the first time it appears, with later
+/// appearances marked with `(*)`.
+#[derive(Debug, Clone, Args)]
+pub(crate) struct TreeCommand {
+    /// The name of the package to display the tree of
+    #[clap(long, conflicts_with = "workspace")]
+    package: Option<CrateName>,
+
+    /// Display the trees of all packages in the workspace
+    #[clap(long, conflicts_with = "package")]
+    workspace: bool,
+
+    /// Display the packages which depend on the given package instead
+    #[clap(long, short, value_name = "PACKAGE")]
+    invert: Option<CrateName>,
+}
+
+pub(crate) fn run(args: TreeCommand, config: NargoConfig) -> Result<(), CliError> {
+    let toml_path = get_package_manifest(&config.program_dir)?;
+    let default_selection =
+        if args.workspace { PackageSelection::All } else { PackageSelection::DefaultOrAll };
+    let selection = args.package.map_or(default_selection, PackageSelection::Selected);
+    let workspace = resolve_workspace_from_toml(&toml_path, selection)?;
+
+    let graph = DependencyGraph::new(workspace.into_iter());
+    let mut printer = TreePrinter {
+        graph: &graph,
+        writer: StandardStream::stdout(ColorChoice::Auto),
+        prefix: String::new(),
+        printed: HashSet::new(),
+    };
+
+    let printed = match args.invert {
+        Some(name) => {
+            let roots: Vec<_> =
+                graph.nodes.values().filter(|node| node.package.name == name).collect();
+            if roots.is_empty() {
+                return Err(CliError::Generic(format!(
+                    "package `{name}` is not a dependency of the selected packages"
+                )));
+            }
+            printer.print_roots(roots, &graph.dependents)
+        }
+        None => {
+            let roots = graph.members.iter().map(|member| &graph.nodes[member]);
+            printer.print_roots(roots, &graph.dependencies)
+        }
+    };
+
+    match printed {
+        // The reader stopped early, as in `nargo tree | head`, so there's no one to report to.
+        Err(error) if error.kind() == ErrorKind::BrokenPipe => Ok(()),
+        Err(error) => Err(CliError::Generic(format!("failed to write to stdout: {error}"))),
+        Ok(()) => Ok(()),
+    }
+}
+
+/// Where a package in the dependency graph was loaded from.
+enum Source<'a> {
+    Path(&'a Path),
+    Git { url: &'a str, tag: &'a str },
+}
+
+struct Node<'a> {
+    package: &'a Package,
+    source: Source<'a>,
+}
+
+/// Every package reachable from the selected workspace members, identified by the directory
+/// they were loaded from since the same package may be depended upon by several others.
+struct DependencyGraph<'a> {
+    members: Vec<&'a Path>,
+    nodes: BTreeMap<&'a Path, Node<'a>>,
+    dependencies: Edges<'a>,
+    dependents: Edges<'a>,
+    /// The names of packages which were loaded from more than one directory.
+    duplicated: HashSet<&'a CrateName>,
+}
+
+impl<'a> DependencyGraph<'a> {
+    fn new(members: impl Iterator<Item = &'a Package>) -> Self {
+        let mut graph = DependencyGraph {
+            members: Vec::new(),
+            nodes: BTreeMap::new(),
+            dependencies: BTreeMap::new(),
+            dependents: BTreeMap::new(),
+            duplicated: HashSet::new(),
+        };
+        for member in members {
+            let root = member.root_dir.as_path();
+            graph.members.push(root);
+            graph.add(Node { package: member, source: Source::Path(root) });
+        }
+
+        let mut directories: BTreeMap<&CrateName, HashSet<&Path>> = BTreeMap::new();
+        for node in graph.nodes.values() {
+            directories
+                .entry(&node.package.name)
+                .or_default()
+                .insert(node.package.root_dir.as_path());
+        }
+        graph.duplicated = directories
+            .into_iter()
+            .filter(|(_, directories)| directories.len() > 1)
+            .map(|(name, _)| name)
+            .collect();
+
+        graph
+    }
+
+    fn add(&mut self, node: Node<'a>) {
+        let package = node.package;
+        let root = package.root_dir.as_path();
+        if self.nodes.contains_key(root) {
+            return;
+        }
+        self.nodes.insert(root, node);
+
+        for dependency in package.dependencies.values() {
+            let dependency_root = dependency.package().root_dir.as_path();
+            self.dependencies.entry(root).or_default().insert(dependency_root);
+            self.dependents.entry(dependency_root).or_default().insert(root);
+
+            let source = match dependency {
+                Dependency::Local { package } => Source::Path(package.root_dir.as_path()),
+                Dependency::Remote { git, tag, .. } => Source::Git { url: git, tag },
+            };
+            self.add(Node { package: dependency.package(), source });
+        }
+    }
+}
+
+struct TreePrinter<'a> {
+    graph: &'a DependencyGraph<'a>,
+    writer: StandardStream,
+    /// The lines drawn to the left of the package being printed.
+    prefix: String,
+    /// Packages whose children have already been printed.
+    printed: HashSet<&'a Path>,
+}
+
+type Edges<'a> = BTreeMap<&'a Path, BTreeSet<&'a Path>>;
+
+impl<'a> TreePrinter<'a> {
+    /// Prints the tree of packages reached by following `edges` from each of `roots`,
+    /// separated by blank lines.
+    fn print_roots(
+        &mut self,
+        roots: impl IntoIterator<Item = &'a Node<'a>>,
+        edges: &Edges<'a>,
+    ) -> io::Result<()> {
+        for (index, root) in roots.into_iter().enumerate() {
+            if index > 0 {
+                writeln!(self.writer)?;
+            }
+            self.print_package(root, edges)?;
+            self.print_children(root, edges)?;
+        }
+        Ok(())
+    }
+
+    fn print_children(&mut self, node: &Node<'a>, edges: &Edges<'a>) -> io::Result<()> {
+        let root = node.package.root_dir.as_path();
+        if !self.printed.insert(root) {
+            return Ok(());
+        }
+
+        let graph = self.graph;
+        let children: Vec<_> = edges.get(root).into_iter().flatten().collect();
+        for (index, child) in children.iter().enumerate() {
+            let is_last = index + 1 == children.len();
+            let child = &graph.nodes[*child];
+
+            let branch = if is_last { "└── " } else { "├── " };
+            write!(self.writer, "{}{branch}", self.prefix)?;
+            self.print_package(child, edges)?;
+
+            let prefix_len = self.prefix.len();
+            self.prefix.push_str(if is_last { "    " } else { "│   " });
+            let printed = self.print_children(child, edges);
+            self.prefix.truncate(prefix_len);
+            printed?;
+        }
+        Ok(())
+    }
+
+    /// Prints the name, version and source of a package on a line of its own.
+    fn print_package(&mut self, node: &Node<'a>, edges: &Edges<'a>) -> io::Result<()> {
+        let package = node.package;
+        if self.graph.duplicated.contains(&package.name) {
+            self.writer.set_color(ColorSpec::new().set_fg(Some(Color::Yellow)).set_bold(true))?;
+        }
+        write!(self.writer, "{}", package.name)?;
+        if let Some(version) = &package.version {
+            write!(self.writer, " v{version}")?;
+        }
+        self.writer.reset()?;
+
+        match node.source {
+            Source::Path(path) => write!(self.writer, " ({})", path.display())?,
+            Source::Git { url, tag } => write!(self.writer, " ({url}?tag={tag})")?,
+        }
+
+        let root = package.root_dir.as_path();
+        if edges.contains_key(root) && self.printed.contains(root) {
+            write!(self.writer, " (*)")?;
+        }
+        writeln!(self.writer)
+    }
+}
@@ -44,6 +44,9 @@ pub(crate) enum CliError {
     #[error("Invalid package name {0}. Did you mean to use `--name`?")]
     InvalidPackageName(String),
 
+    #[error("{0} is not a known error code")]
+    UnknownErrorCode(String),
+
     /// ABI encoding/decoding error
     #[error(transparent)]
     AbiError(#[from] AbiError),
@@ -97,6 +100,9 @@ pub(crate) enum CompileError {
     #[error("Package `{0}` is expected to have a `main` function but it does not")]
     MissingMainFunction(CrateName),
 
+    #[error("Circuit `{circuit}` has {opcodes} opcodes, exceeding its budget of {max_opcodes}\n{breakdown}")]
+    OpcodeBudgetExceeded { circuit: String, opcodes: usize, max_opcodes: usize, breakdown: String },
+
     /// Errors encountered while compiling the Noir program.
     /// These errors are already written to stderr.
     #[error("Aborting due to {} previous error{}", .0.error_count, if .0.error_count == 1 { "" } else { "s" })]
@@ -0,0 +1,59 @@
+//! Failing `assert_eq` and `assert_ne` without a message only report the values they compared
+//! when asked to, since doing so changes the compiled program.
+
+use assert_cmd::prelude::*;
+use assert_fs::prelude::{FileWriteStr, PathChild};
+use assert_fs::TempDir;
+use predicates::prelude::*;
+use std::process::Command;
+
+test_binary::build_test_binary_once!(mock_backend, "../backend_interface/test-binaries");
+
+/// Writes a binary package named `assertions` whose `main` is given `x = 27`
+fn write_package(main: &str) -> TempDir {
+    let dir = TempDir::new().unwrap();
+    let manifest =
+        "[package]\nname = \"assertions\"\ntype = \"bin\"\nauthors = [\"\"]\n\n[dependencies]";
+    dir.child("Nargo.toml").write_str(manifest).unwrap();
+    dir.child("Prover.toml").write_str("x = \"27\"").unwrap();
+    dir.child("src").child("main.nr").write_str(main).unwrap();
+    dir
+}
+
+fn nargo(dir: &TempDir, args: &[&str]) -> Command {
+    let mut cmd = Command::cargo_bin("nargo").unwrap();
+    cmd.env("NARGO_BACKEND_PATH", path_to_mock_backend());
+    cmd.arg("--program-dir").arg(dir.path());
+    cmd.args(args);
+    cmd
+}
+
+#[test]
+fn assert_eq_reports_its_values_when_enabled() {
+    let dir = write_package("fn main(x: Field) { assert_eq(x, 28); }");
+
+    nargo(&dir, &["execute"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Failed constraint"))
+        .stderr(predicate::str::contains("left:").not());
+    nargo(&dir, &["execute", "--report-assert-values"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Assertion failed: 'left: 0x1b, right: 0x1c'"));
+}
+
+#[test]
+fn assert_ne_reports_its_message_or_values() {
+    let dir = write_package("fn main(x: Field) { assert_ne(x, 27, \"x must not be 27\"); }");
+    nargo(&dir, &["execute"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Assertion failed: 'x must not be 27'"));
+
+    let dir = write_package("fn main(x: Field) { assert_ne(x, 27); }");
+    nargo(&dir, &["execute", "--report-assert-values"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Assertion failed: 'left: 0x1b, right: 0x1b'"));
+}
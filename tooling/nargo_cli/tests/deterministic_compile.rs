@@ -0,0 +1,38 @@
+//! Compiling a program twice must give the same SSA and artifact. The iteration order of hash
+//! maps is randomized for each process, so this checks that no function or value ids depend on it.
+
+use assert_cmd::prelude::*;
+use assert_fs::prelude::PathCopy;
+use std::path::Path;
+use std::process::Command;
+
+test_binary::build_test_binary_once!(mock_backend, "../backend_interface/test-binaries");
+
+/// Compiles a fresh copy of the given `execution_success` program, returning the SSA printed
+/// after each pass along with the compiled artifact.
+fn compile(program: &str) -> (Vec<u8>, Vec<u8>) {
+    let program_dir =
+        Path::new(env!("CARGO_MANIFEST_DIR")).join("tests").join("execution_success").join(program);
+    let test_dir = assert_fs::TempDir::new().unwrap();
+    test_dir.copy_from(program_dir, &["Nargo.toml", "src/*.nr"]).unwrap();
+
+    let mut cmd = Command::cargo_bin("nargo").unwrap();
+    cmd.env("NARGO_BACKEND_PATH", path_to_mock_backend());
+    cmd.arg("--program-dir").arg(test_dir.path());
+    cmd.arg("compile").arg("--show-ssa");
+    let ssa = cmd.assert().success().get_output().stdout.clone();
+
+    let artifact_path = test_dir.path().join("target").join(format!("{program}.json"));
+    let artifact = std::fs::read(artifact_path).unwrap();
+    (ssa, artifact)
+}
+
+#[test]
+fn compilation_is_deterministic() {
+    for program in ["higher_order_functions", "operator_overloading", "trait_object"] {
+        let (first_ssa, first_artifact) = compile(program);
+        let (second_ssa, second_artifact) = compile(program);
+        assert!(first_ssa == second_ssa, "Compiling {program} twice gave different SSA");
+        assert!(first_artifact == second_artifact, "Compiling {program} twice gave different ACIR");
+    }
+}
@@ -0,0 +1,92 @@
+//! `[dev-dependencies]` and modules marked with `#[test]` are only compiled by `nargo test`, and
+//! only for the packages being tested.
+
+use assert_cmd::prelude::*;
+use assert_fs::prelude::{FileWriteStr, PathChild};
+use assert_fs::TempDir;
+use predicates::prelude::*;
+use std::process::Command;
+
+test_binary::build_test_binary_once!(mock_backend, "../backend_interface/test-binaries");
+
+/// Writes a library package named `name` with the given manifest sections and `src/lib.nr`
+fn write_package(dir: &TempDir, name: &str, sections: &str, lib: &str) {
+    let manifest =
+        format!("[package]\nname = \"{name}\"\ntype = \"lib\"\nauthors = [\"\"]\n\n{sections}");
+    let package = dir.child(name);
+    package.child("Nargo.toml").write_str(&manifest).unwrap();
+    package.child("src").child("lib.nr").write_str(lib).unwrap();
+}
+
+/// Writes a `mocks` package, which `app` uses from its test module as a dev-dependency.
+/// `app` depends on `helper`, whose own dev-dependency and test module don't exist.
+fn write_packages() -> TempDir {
+    let dir = TempDir::new().unwrap();
+    write_package(&dir, "mocks", "", "pub fn value() -> Field { 1 }");
+    write_package(
+        &dir,
+        "helper",
+        "[dev-dependencies]\nmissing = { path = \"../missing\" }",
+        "pub fn value() -> Field { 1 }\n\n#[test]\nmod tests;",
+    );
+    write_package(
+        &dir,
+        "app",
+        "[dependencies]\nhelper = { path = \"../helper\" }\n\n\
+         [dev-dependencies]\nmocks = { path = \"../mocks\" }",
+        "use dep::helper;\n\npub fn value() -> Field { helper::value() }\n\n#[test]\nmod tests;",
+    );
+    let tests = "use dep::mocks;\n\n\
+                 #[test]\nfn matches_mock() { assert(crate::value() == mocks::value()); }";
+    dir.child("app").child("src").child("tests.nr").write_str(tests).unwrap();
+    dir
+}
+
+fn nargo(dir: &TempDir, package: &str, args: &[&str]) -> Command {
+    let mut cmd = Command::cargo_bin("nargo").unwrap();
+    cmd.env("NARGO_BACKEND_PATH", path_to_mock_backend());
+    cmd.arg("--program-dir").arg(dir.child(package).path());
+    cmd.args(args);
+    cmd
+}
+
+#[test]
+fn tests_can_use_dev_dependencies() {
+    let dir = write_packages();
+
+    nargo(&dir, "app", &["test"])
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("[app] Testing matches_mock... ok"));
+}
+
+#[test]
+fn dev_dependencies_and_test_modules_are_ignored_outside_of_tests() {
+    let dir = write_packages();
+    std::fs::remove_dir_all(dir.child("mocks").path()).unwrap();
+    std::fs::remove_file(dir.child("app").child("src").child("tests.nr").path()).unwrap();
+
+    nargo(&dir, "app", &["check"]).assert().success();
+    nargo(&dir, "app", &["test"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("mocks").and(predicate::str::contains("does it exist?")));
+}
+
+#[test]
+fn dev_dependencies_are_only_resolved_for_the_tested_workspace_members() {
+    let dir = write_packages();
+    dir.child("Nargo.toml").write_str("[workspace]\nmembers = [\"app\", \"helper\"]").unwrap();
+    let workspace = |args: &[&str]| {
+        let mut cmd = Command::cargo_bin("nargo").unwrap();
+        cmd.env("NARGO_BACKEND_PATH", path_to_mock_backend());
+        cmd.arg("--program-dir").arg(dir.path());
+        cmd.args(args);
+        cmd
+    };
+
+    workspace(&["test", "--package", "app"]).assert().success();
+    workspace(&["test", "--workspace"]).assert().failure().stderr(
+        predicate::str::contains("missing").and(predicate::str::contains("does it exist?")),
+    );
+}
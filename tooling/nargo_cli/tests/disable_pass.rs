@@ -0,0 +1,67 @@
+//! Programs using mutable variables still compile and execute when any SSA pass which can be
+//! disabled is, as the runs of `mem2reg` and `die` that code generation depends on can't be
+//! skipped.
+
+use assert_cmd::prelude::*;
+use assert_fs::prelude::{FileWriteStr, PathChild};
+use assert_fs::TempDir;
+use predicates::prelude::*;
+use std::process::Command;
+
+test_binary::build_test_binary_once!(mock_backend, "../backend_interface/test-binaries");
+
+const MAIN: &str = "fn main(x: u32, cond: bool) -> pub u32 {
+    let mut sum = 0;
+    for i in 0..4 {
+        sum += x * i;
+    }
+    if cond {
+        sum += 1;
+    }
+    let mut array = [0; 2];
+    array[1] = sum;
+    array[1]
+}";
+
+fn execute(args: &[&str]) {
+    let dir = TempDir::new().unwrap();
+    let manifest = "[package]\nname = \"package\"\ntype = \"bin\"\nauthors = [\"\"]\n\n\
+                    [dependencies]";
+    dir.child("Nargo.toml").write_str(manifest).unwrap();
+    dir.child("src").child("main.nr").write_str(MAIN).unwrap();
+    dir.child("Prover.toml").write_str("x = 2\ncond = true").unwrap();
+
+    let mut cmd = Command::cargo_bin("nargo").unwrap();
+    cmd.env("NARGO_BACKEND_PATH", path_to_mock_backend());
+    cmd.arg("--program-dir").arg(dir.path());
+    cmd.arg("execute").args(args);
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("[package] Circuit witness successfully solved"))
+        .stdout(predicate::str::contains("[package] Circuit output: 13"));
+}
+
+/// The SSA passes accepted by `--disable-pass`
+const OPTIONAL_PASSES: &[&str] = &[
+    "merge_functions",
+    "die",
+    "mem2reg",
+    "sccp",
+    "simplify_cfg",
+    "simplify_arithmetic",
+    "cse",
+    "bounds_checks",
+    "fold_constants",
+    "range_checks",
+    "reduce_strength",
+];
+
+#[test]
+fn mutable_variables_compile_with_any_optional_pass_disabled() {
+    execute(&[]);
+    for pass in OPTIONAL_PASSES {
+        execute(&["--disable-pass", pass]);
+        execute(&["--disable-pass", pass, "--opt-level", "0"]);
+    }
+    execute(&["--disable-pass", "mem2reg", "--disable-pass", "die"]);
+}
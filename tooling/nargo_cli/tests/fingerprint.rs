@@ -0,0 +1,101 @@
+//! `nargo compile` skips packages whose inputs are unchanged since they were last compiled, as
+//! long as their artifacts still exist.
+
+use assert_cmd::prelude::*;
+use assert_fs::prelude::{FileWriteBin, FileWriteStr, PathChild};
+use assert_fs::TempDir;
+use predicates::prelude::*;
+use std::process::Command;
+
+test_binary::build_test_binary_once!(mock_backend, "../backend_interface/test-binaries");
+
+/// Writes a package named `package` of the given type, whose only source file is `main.nr`
+fn write_package(package_type: &str, main: &str) -> TempDir {
+    let dir = TempDir::new().unwrap();
+    let manifest = format!(
+        "[package]\nname = \"package\"\ntype = \"{package_type}\"\nauthors = [\"\"]\n\n\
+         [dependencies]"
+    );
+    dir.child("Nargo.toml").write_str(&manifest).unwrap();
+    dir.child("src").child("main.nr").write_str(main).unwrap();
+    dir
+}
+
+/// Compiles the package, checking whether it is reported as `Fresh` or `Compiling`
+fn compile(dir: &TempDir, status: &str) {
+    compile_with_env(dir, &[], status);
+}
+
+/// Compiles the package with the given environment variables set
+fn compile_with_env(dir: &TempDir, env: &[(&str, &str)], status: &str) {
+    let mut cmd = Command::cargo_bin("nargo").unwrap();
+    cmd.env("NARGO_BACKEND_PATH", path_to_mock_backend());
+    cmd.envs(env.iter().copied());
+    cmd.arg("--program-dir").arg(dir.path());
+    cmd.arg("compile");
+    cmd.assert().success().stdout(predicate::str::contains(format!("{status} package")));
+}
+
+#[test]
+fn unchanged_binaries_are_fresh_until_their_inputs_change() {
+    let dir = write_package("bin", "fn main(x: Field) { assert(x == 1); }");
+
+    compile(&dir, "Compiling");
+    compile(&dir, "Fresh");
+
+    dir.child("src").child("main.nr").write_str("fn main(x: Field) { assert(x == 2); }").unwrap();
+    compile(&dir, "Compiling");
+    compile(&dir, "Fresh");
+
+    dir.child("src").child("helpers.nr").write_str("fn helper() {}").unwrap();
+    compile(&dir, "Compiling");
+}
+
+#[test]
+fn binaries_are_recompiled_when_their_artifact_is_deleted() {
+    let dir = write_package("bin", "fn main(x: Field) { assert(x == 1); }");
+
+    compile(&dir, "Compiling");
+    std::fs::remove_file(dir.child("target").child("package.json").path()).unwrap();
+    compile(&dir, "Compiling");
+    compile(&dir, "Fresh");
+}
+
+#[test]
+fn contracts_are_recompiled_when_their_artifact_is_deleted() {
+    let dir =
+        write_package("contract", "contract Foo { fn double(x: Field) -> pub Field { x * 2 } }");
+
+    compile(&dir, "Compiling");
+    compile(&dir, "Fresh");
+    std::fs::remove_file(dir.child("target").child("package-Foo.json").path()).unwrap();
+    compile(&dir, "Compiling");
+    assert!(dir.child("target").child("package-Foo.json").path().exists());
+}
+
+#[test]
+fn binaries_are_recompiled_when_the_environment_variables_they_read_change() {
+    let main = "global DEPTH = std::env!(\"FINGERPRINT_TEST_DEPTH\");\n\n\
+                fn main(x: [Field; DEPTH]) { assert(x[0] == 1); }";
+    let dir = write_package("bin", main);
+
+    compile_with_env(&dir, &[("FINGERPRINT_TEST_DEPTH", "2")], "Compiling");
+    compile_with_env(&dir, &[("FINGERPRINT_TEST_DEPTH", "2")], "Fresh");
+    compile_with_env(&dir, &[("FINGERPRINT_TEST_DEPTH", "3")], "Compiling");
+    compile_with_env(&dir, &[("FINGERPRINT_TEST_DEPTH", "3")], "Fresh");
+}
+
+#[test]
+fn binaries_are_recompiled_when_the_files_they_include_change() {
+    let main = "global KEY = std::include_bytes!(\"../keys/vk.bin\");\n\n\
+                fn main(x: u8) { assert(x == KEY[0]); }";
+    let dir = write_package("bin", main);
+    // Included files aren't necessarily valid UTF-8, nor inside the source directory
+    dir.child("keys").child("vk.bin").write_binary(&[0xff, 0x00]).unwrap();
+
+    compile(&dir, "Compiling");
+    compile(&dir, "Fresh");
+    dir.child("keys").child("vk.bin").write_binary(&[0xfe, 0x00]).unwrap();
+    compile(&dir, "Compiling");
+    compile(&dir, "Fresh");
+}
@@ -0,0 +1,56 @@
+//! Compilation fails without writing an artifact when a circuit exceeds its opcode budget.
+
+use assert_cmd::prelude::*;
+use assert_fs::prelude::{FileWriteStr, PathChild};
+use assert_fs::TempDir;
+use predicates::prelude::*;
+use std::process::Command;
+
+test_binary::build_test_binary_once!(mock_backend, "../backend_interface/test-binaries");
+
+const MAIN: &str = "fn main(x: Field, y: Field) {\n    assert(x * y == 6);\n    assert(x != y);\n}";
+
+/// Writes a binary package named `package` with `extra_metadata` in its `[package]` section
+fn write_package(extra_metadata: &str) -> TempDir {
+    let dir = TempDir::new().unwrap();
+    let manifest = format!(
+        "[package]\nname = \"package\"\ntype = \"bin\"\nauthors = [\"\"]\n{extra_metadata}\n\n\
+         [dependencies]"
+    );
+    dir.child("Nargo.toml").write_str(&manifest).unwrap();
+    dir.child("src").child("main.nr").write_str(MAIN).unwrap();
+    dir
+}
+
+fn compile(dir: &TempDir, args: &[&str]) -> assert_cmd::assert::Assert {
+    let mut cmd = Command::cargo_bin("nargo").unwrap();
+    cmd.env("NARGO_BACKEND_PATH", path_to_mock_backend());
+    cmd.arg("--program-dir").arg(dir.path());
+    cmd.arg("compile").args(args);
+    cmd.assert()
+}
+
+#[test]
+fn circuits_over_budget_fail_without_writing_an_artifact() {
+    let dir = write_package("");
+
+    compile(&dir, &["--max-opcodes", "1"])
+        .failure()
+        .stderr(predicate::str::contains("exceeding its budget of 1"))
+        .stderr(predicate::str::contains("Opcodes by kind:"))
+        .stderr(predicate::str::contains("main.nr:"));
+    assert!(!dir.child("target").child("package.json").path().exists());
+
+    compile(&dir, &["--max-opcodes", "1000"]).success();
+    assert!(dir.child("target").child("package.json").path().exists());
+}
+
+#[test]
+fn the_package_budget_is_overridden_by_the_command_line() {
+    let dir = write_package("max_opcodes = 1");
+
+    compile(&dir, &[]).failure().stderr(predicate::str::contains("exceeding its budget of 1"));
+    assert!(!dir.child("target").child("package.json").path().exists());
+
+    compile(&dir, &["--max-opcodes", "1000"]).success();
+}
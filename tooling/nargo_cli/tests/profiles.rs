@@ -0,0 +1,91 @@
+//! Build profiles selected from `Nargo.toml` change how packages are compiled and executed.
+
+use assert_cmd::prelude::*;
+use assert_fs::prelude::{FileWriteStr, PathChild};
+use assert_fs::TempDir;
+use predicates::prelude::*;
+use std::process::Command;
+
+test_binary::build_test_binary_once!(mock_backend, "../backend_interface/test-binaries");
+
+/// Writes a binary package named `profiles` with the given profile sections and inputs
+fn write_package(main: &str, profiles: &str, prover: &str) -> TempDir {
+    let dir = TempDir::new().unwrap();
+    let manifest = format!(
+        "[package]\nname = \"profiles\"\ntype = \"bin\"\nauthors = [\"\"]\n\n[dependencies]\n\n\
+         {profiles}"
+    );
+    dir.child("Nargo.toml").write_str(&manifest).unwrap();
+    dir.child("Prover.toml").write_str(prover).unwrap();
+    dir.child("src").child("main.nr").write_str(main).unwrap();
+    dir
+}
+
+fn nargo(dir: &TempDir, args: &[&str]) -> Command {
+    let mut cmd = Command::cargo_bin("nargo").unwrap();
+    cmd.env("NARGO_BACKEND_PATH", path_to_mock_backend());
+    cmd.arg("--program-dir").arg(dir.path());
+    cmd.args(args);
+    cmd
+}
+
+#[test]
+fn release_profile_is_selected_with_the_release_flag() {
+    let main = "fn main(x: u8) -> pub u8 { x + 255 }";
+    let dir = write_package(main, "[profile.release]\noverflow-checks = true", "x = \"1\"");
+
+    nargo(&dir, &["execute"]).assert().success();
+    nargo(&dir, &["execute", "--release"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("attempt to add with overflow"));
+}
+
+#[test]
+fn opt_level_zero_skips_the_simplifying_passes() {
+    let main = "fn main(x: Field) -> pub Field { x + 1 + 2 }";
+    let dir = write_package(main, "[profile.dev]\nopt-level = 0", "x = \"1\"");
+
+    nargo(&dir, &["compile", "--show-ssa"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("After Constant Folding:").not());
+    nargo(&dir, &["compile", "--show-ssa", "-O", "1"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("After Constant Folding:"));
+}
+
+#[test]
+fn brillig_step_limit_stops_unconstrained_code_without_changing_the_program() {
+    let main = "
+    unconstrained fn sum(n: u32) -> u32 {
+        let mut total = 0;
+        for i in 0..n {
+            total += i;
+        }
+        total
+    }
+
+    fn main(n: u32) -> pub u32 {
+        sum(n)
+    }";
+    let unlimited = write_package(main, "", "n = \"1000\"");
+    let limited = write_package(main, "[profile.dev]\nbrillig-step-limit = 100", "n = \"1000\"");
+
+    nargo(&unlimited, &["execute"]).assert().success();
+    nargo(&limited, &["execute"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("exceeded the step limit of 100 opcodes"));
+
+    // The limit is only applied by nargo's executor, so the program runs unchanged elsewhere
+    let bytecode = |dir: &TempDir| {
+        let artifact = std::fs::read(dir.child("target").child("profiles.json")).unwrap();
+        let artifact: serde_json::Value = serde_json::from_slice(&artifact).unwrap();
+        artifact["bytecode"].clone()
+    };
+    nargo(&unlimited, &["compile"]).assert().success();
+    nargo(&limited, &["compile"]).assert().success();
+    assert_eq!(bytecode(&unlimited), bytecode(&limited));
+}
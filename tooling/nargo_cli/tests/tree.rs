@@ -0,0 +1,78 @@
+//! `nargo tree` prints the dependency tree of a workspace of path dependencies:
+//!
+//! app -> lib_a -> lib_b -> lib_c
+//!  \---------------^
+
+use assert_cmd::prelude::*;
+use assert_fs::prelude::{FileWriteStr, PathChild};
+use assert_fs::TempDir;
+use predicates::prelude::*;
+use std::process::Command;
+
+fn write_package(dir: &TempDir, name: &str, package_type: &str, dependencies: &[&str]) {
+    let dependencies: String =
+        dependencies.iter().map(|dep| format!("{dep} = {{ path = \"../{dep}\" }}\n")).collect();
+    let manifest = format!(
+        "[package]\nname = \"{name}\"\ntype = \"{package_type}\"\nauthors = [\"\"]\n\n\
+         [dependencies]\n{dependencies}"
+    );
+    let package = dir.child(name);
+    package.child("Nargo.toml").write_str(&manifest).unwrap();
+    let entry = if package_type == "bin" { "main.nr" } else { "lib.nr" };
+    package.child("src").child(entry).write_str("").unwrap();
+}
+
+fn write_workspace() -> TempDir {
+    let dir = TempDir::new().unwrap();
+    write_package(&dir, "app", "bin", &["lib_a", "lib_b"]);
+    write_package(&dir, "lib_a", "lib", &["lib_b"]);
+    write_package(&dir, "lib_b", "lib", &["lib_c"]);
+    write_package(&dir, "lib_c", "lib", &[]);
+    dir
+}
+
+/// Runs `nargo tree` in the `app` package, returning its output with the path of the
+/// workspace replaced by `..`.
+fn tree(dir: &TempDir, args: &[&str]) -> String {
+    let mut cmd = Command::cargo_bin("nargo").unwrap();
+    cmd.env("NO_COLOR", "1");
+    cmd.arg("--program-dir").arg(dir.child("app").path());
+    cmd.arg("tree").args(args);
+    let stdout = cmd.assert().success().get_output().stdout.clone();
+    String::from_utf8(stdout).unwrap().replace(dir.path().to_str().unwrap(), "..")
+}
+
+#[test]
+fn prints_the_dependencies_of_each_package_once() {
+    let dir = write_workspace();
+    let expected = "\
+app (../app)
+├── lib_a (../lib_a)
+│   └── lib_b (../lib_b)
+│       └── lib_c (../lib_c)
+└── lib_b (../lib_b) (*)
+";
+    assert_eq!(tree(&dir, &[]), expected);
+}
+
+#[test]
+fn inverted_trees_print_the_dependents_of_a_package() {
+    let dir = write_workspace();
+    let expected = "\
+lib_c (../lib_c)
+└── lib_b (../lib_b)
+    ├── app (../app)
+    └── lib_a (../lib_a)
+        └── app (../app)
+";
+    assert_eq!(tree(&dir, &["--invert", "lib_c"]), expected);
+}
+
+#[test]
+fn inverting_on_an_unknown_package_fails() {
+    let dir = write_workspace();
+    let mut cmd = Command::cargo_bin("nargo").unwrap();
+    cmd.arg("--program-dir").arg(dir.child("app").path());
+    cmd.arg("tree").arg("--invert").arg("lib_d");
+    cmd.assert().failure().stderr(predicate::str::contains("package `lib_d` is not a dependency"));
+}
@@ -0,0 +1,75 @@
+//! `--unchecked-hints` wraps the results of unconstrained calls from the constrained functions of
+//! the root crate in `std::unchecked::Unchecked`, leaving its dependencies unchanged.
+
+use assert_cmd::prelude::*;
+use assert_fs::prelude::{FileWriteStr, PathChild};
+use assert_fs::TempDir;
+use predicates::prelude::*;
+use std::process::Command;
+
+test_binary::build_test_binary_once!(mock_backend, "../backend_interface/test-binaries");
+
+/// Writes a library package named `name` with the given manifest sections and `src/lib.nr`
+fn write_package(dir: &TempDir, name: &str, sections: &str, lib: &str) {
+    let manifest =
+        format!("[package]\nname = \"{name}\"\ntype = \"lib\"\nauthors = [\"\"]\n\n{sections}");
+    let package = dir.child(name);
+    package.child("Nargo.toml").write_str(&manifest).unwrap();
+    package.child("src").child("lib.nr").write_str(lib).unwrap();
+}
+
+/// Writes an `app` package whose test checks the hints it gets from a function call, a method
+/// call and a call through a local variable. Its dependency `helper` uses a hint unchecked.
+fn write_packages() -> TempDir {
+    let dir = TempDir::new().unwrap();
+    write_package(
+        &dir,
+        "helper",
+        "",
+        "unconstrained fn unchecked_half(x: u32) -> u32 { x / 2 }\n\n\
+         pub fn half(x: u32) -> u32 {\n    let h = unchecked_half(x);\n    \
+         assert(h * 2 <= x);\n    h\n}",
+    );
+    write_package(
+        &dir,
+        "app",
+        "[dependencies]\nhelper = { path = \"../helper\" }",
+        "use dep::helper;\n\n\
+         struct Counter { count: u32 }\n\n\
+         impl Counter {\n    unconstrained fn doubled(self) -> u32 { self.count * 2 }\n}\n\n\
+         unconstrained fn divide(x: u32, y: u32) -> u32 { x / y }\n\n\
+         #[test]\nfn hints_are_checked() {\n    \
+         let quotient = divide(7, 2).validate(|q| q * 2 <= 7);\n    \
+         let doubled = Counter { count: 3 }.doubled().assert_eq(6);\n    \
+         let divide_by = divide;\n    \
+         let third = divide_by(9, 3).assert_eq(3);\n    \
+         assert(quotient + doubled + third + helper::half(4) == 14);\n}",
+    );
+    dir
+}
+
+fn nargo_test(dir: &TempDir, args: &[&str]) -> Command {
+    let mut cmd = Command::cargo_bin("nargo").unwrap();
+    cmd.env("NARGO_BACKEND_PATH", path_to_mock_backend());
+    cmd.arg("--program-dir").arg(dir.child("app").path());
+    cmd.arg("test");
+    cmd.args(args);
+    cmd
+}
+
+#[test]
+fn unchecked_hints_wrap_unconstrained_results_in_the_root_crate() {
+    let dir = write_packages();
+
+    nargo_test(&dir, &["--unchecked-hints"])
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("[app] Testing hints_are_checked... ok"));
+}
+
+#[test]
+fn unconstrained_results_are_unwrapped_without_unchecked_hints() {
+    let dir = write_packages();
+
+    nargo_test(&dir, &[]).assert().failure().stderr(predicate::str::contains("validate"));
+}
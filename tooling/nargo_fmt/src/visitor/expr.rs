@@ -92,8 +92,14 @@ impl FmtVisitor<'_> {
                 format_parens(self.fork(), exprs.len() == 1, exprs, span)
             }
             ExpressionKind::Literal(literal) => match literal {
-                Literal::Integer(_) | Literal::Bool(_) | Literal::Str(_) | Literal::FmtStr(_) => {
-                    self.slice(span).to_string()
+                Literal::Integer(_, false)
+                | Literal::Bool(_)
+                | Literal::Str(_)
+                | Literal::FmtStr(_)
+                | Literal::Char(_) => self.slice(span).to_string(),
+                Literal::Integer(_, true) => {
+                    // Formatted like other negations, without space after the `-`
+                    format!("-{}", self.slice(span).trim_start_matches('-').trim_start())
                 }
                 Literal::Array(ArrayLiteral::Repeated { repeated_element, length }) => {
                     let repeated = self.format_expr(*repeated_element);
@@ -104,6 +110,18 @@ impl FmtVisitor<'_> {
                 Literal::Array(ArrayLiteral::Standard(exprs)) => {
                     format_brackets(self.fork(), false, exprs, span)
                 }
+                Literal::Array(ArrayLiteral::Comprehension {
+                    element,
+                    identifier,
+                    start_range,
+                    end_range,
+                }) => {
+                    let element = self.format_expr(*element);
+                    let start_range = self.format_expr(*start_range);
+                    let end_range = self.format_expr(*end_range);
+
+                    format!("[{element} for {identifier} in {start_range}..{end_range}]")
+                }
                 Literal::Unit => "()".to_string(),
             },
             ExpressionKind::Parenthesized(mut sub_expr) => {
@@ -44,6 +44,11 @@ pub enum ManifestError {
     #[error("{} found in {toml}", if name.is_empty() { "Empty dependency name".into() } else { format!("Invalid dependency name `{name}`") })]
     InvalidDependencyName { toml: PathBuf, name: String },
 
+    #[error(
+        "Dependency `{name}` is declared in both [dependencies] and [dev-dependencies] in {toml}"
+    )]
+    DuplicateDevDependency { toml: PathBuf, name: CrateName },
+
     #[error("Invalid directory path {directory} in {toml}: It must point to a subdirectory")]
     InvalidDirectory { toml: PathBuf, directory: PathBuf },
 
@@ -63,6 +68,12 @@ pub enum ManifestError {
     #[error("Missing `name` field in {toml}")]
     MissingNameField { toml: PathBuf },
 
+    #[error("Invalid value {value} for `{key}` in `[profile.{profile}]` of {toml}")]
+    InvalidProfileValue { toml: PathBuf, profile: String, key: String, value: String },
+
+    #[error("Invalid value {value} for `{key}` in `[{section}]` of {toml}")]
+    InvalidOracleResolverValue { toml: PathBuf, section: String, key: String, value: String },
+
     #[error("No common ancestor between {root} and {current}")]
     NoCommonAncestor { root: PathBuf, current: PathBuf },
 }
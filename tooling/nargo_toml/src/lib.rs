@@ -18,9 +18,13 @@ use serde::Deserialize;
 
 mod errors;
 mod git;
+mod oracle_resolver;
+mod profile;
 
 pub use errors::ManifestError;
 use git::clone_git_repo;
+use oracle_resolver::OracleResolverSection;
+use profile::ProfilesConfig;
 
 /// Returns the [PathBuf] of the directory containing the `Nargo.toml` by searching from `current_path` to the root of its [Path].
 ///
@@ -94,10 +98,22 @@ struct PackageConfig {
     package: PackageMetadata,
     #[serde(default)]
     dependencies: BTreeMap<String, DependencyConfig>,
+    #[serde(default, rename = "dev-dependencies")]
+    dev_dependencies: BTreeMap<String, DependencyConfig>,
+    #[serde(default)]
+    profile: ProfilesConfig,
+    #[serde(default, rename = "oracle-resolver")]
+    oracle_resolver: OracleResolverSection,
 }
 
 impl PackageConfig {
-    fn resolve_to_package(&self, root_dir: &Path) -> Result<Package, ManifestError> {
+    /// Resolves the package along with its dependencies. Its dev-dependencies are only resolved
+    /// if `with_dev_dependencies` is set, as they are only needed to test the package.
+    fn resolve_to_package(
+        &self,
+        root_dir: &Path,
+        with_dev_dependencies: bool,
+    ) -> Result<Package, ManifestError> {
         let name = if let Some(name) = &self.package.name {
             name.parse().map_err(|_| ManifestError::InvalidPackageName {
                 toml: root_dir.join("Nargo.toml"),
@@ -118,6 +134,26 @@ impl PackageConfig {
             dependencies.insert(name, resolved_dep);
         }
 
+        let mut dev_dependencies: BTreeMap<CrateName, Dependency> = BTreeMap::new();
+        if with_dev_dependencies {
+            for (name, dep_config) in self.dev_dependencies.iter() {
+                let name: CrateName =
+                    name.parse().map_err(|_| ManifestError::InvalidDependencyName {
+                        toml: root_dir.join("Nargo.toml"),
+                        name: name.into(),
+                    })?;
+                if dependencies.contains_key(&name) {
+                    return Err(ManifestError::DuplicateDevDependency {
+                        toml: root_dir.join("Nargo.toml"),
+                        name,
+                    });
+                }
+                let resolved_dep = dep_config.resolve_to_dependency(root_dir)?;
+
+                dev_dependencies.insert(name, resolved_dep);
+            }
+        }
+
         let package_type = match self.package.package_type.as_deref() {
             Some("lib") => PackageType::Library,
             Some("bin") => PackageType::Binary,
@@ -162,12 +198,22 @@ impl PackageConfig {
             }
         };
 
+        let (dev_profile, release_profile) = self.profile.resolve(&root_dir.join("Nargo.toml"))?;
+        let oracle_resolver = self.oracle_resolver.resolve(&root_dir.join("Nargo.toml"))?;
+
         Ok(Package {
             root_dir: root_dir.to_path_buf(),
             entry_path,
             package_type,
             name,
+            version: self.package.version.clone(),
             dependencies,
+            dev_dependencies,
+            dev_profile,
+            release_profile,
+            oracle_resolver,
+            max_opcodes: self.package.max_opcodes,
+            implicit_conversions: self.package.implicit_conversions.unwrap_or(false),
         })
     }
 }
@@ -226,6 +272,7 @@ struct PackageMetadata {
     #[serde(alias = "type")]
     package_type: Option<String>,
     entry: Option<PathBuf>,
+    version: Option<String>,
     description: Option<String>,
     authors: Option<Vec<String>>,
     // If not compiler version is supplied, the latest is used
@@ -236,6 +283,8 @@ struct PackageMetadata {
     compiler_version: Option<String>,
     backend: Option<String>,
     license: Option<String>,
+    max_opcodes: Option<usize>,
+    implicit_conversions: Option<bool>,
 }
 
 #[derive(Debug, Deserialize, Clone)]
@@ -265,13 +314,13 @@ impl DependencyConfig {
                     dir_path
                 };
                 let toml_path = project_path.join("Nargo.toml");
-                let package = resolve_package_from_toml(&toml_path)?;
-                Dependency::Remote { package }
+                let package = resolve_package_from_toml(&toml_path, false)?;
+                Dependency::Remote { package, git: git.clone(), tag: tag.clone() }
             }
             Self::Path { path } => {
                 let dir_path = pkg_root.join(path);
                 let toml_path = dir_path.join("Nargo.toml");
-                let package = resolve_package_from_toml(&toml_path)?;
+                let package = resolve_package_from_toml(&toml_path, false)?;
                 Dependency::Local { package }
             }
         };
@@ -289,10 +338,12 @@ impl DependencyConfig {
 fn toml_to_workspace(
     nargo_toml: NargoToml,
     package_selection: PackageSelection,
+    with_dev_dependencies: bool,
 ) -> Result<Workspace, ManifestError> {
     let workspace = match nargo_toml.config {
         Config::Package { package_config } => {
-            let member = package_config.resolve_to_package(&nargo_toml.root_dir)?;
+            let member =
+                package_config.resolve_to_package(&nargo_toml.root_dir, with_dev_dependencies)?;
             match &package_selection {
                 PackageSelection::Selected(selected_name) if selected_name != &member.name => {
                     return Err(ManifestError::MissingSelectedPackage(member.name))
@@ -306,11 +357,12 @@ fn toml_to_workspace(
         }
         Config::Workspace { workspace_config } => {
             let mut members = Vec::new();
+            let mut member_toml_paths = Vec::new();
             let mut selected_package_index = None;
             for (index, member_path) in workspace_config.members.into_iter().enumerate() {
                 let package_root_dir = nargo_toml.root_dir.join(&member_path);
                 let package_toml_path = package_root_dir.join("Nargo.toml");
-                let member = resolve_package_from_toml(&package_toml_path)?;
+                let member = resolve_package_from_toml(&package_toml_path, false)?;
 
                 match &package_selection {
                     PackageSelection::Selected(selected_name) => {
@@ -327,6 +379,7 @@ fn toml_to_workspace(
                 }
 
                 members.push(member);
+                member_toml_paths.push(package_toml_path);
             }
 
             // If the selected_package_index is still `None` but we have see a default_member or selected package,
@@ -348,6 +401,16 @@ fn toml_to_workspace(
                 PackageSelection::All => (),
             }
 
+            // Only the members which are going to be tested need their dev-dependencies, so a
+            // broken dev-dependency of one member doesn't stop the others from being tested.
+            if with_dev_dependencies {
+                for (index, toml_path) in member_toml_paths.iter().enumerate() {
+                    if selected_package_index.map_or(true, |selected| selected == index) {
+                        members[index] = resolve_package_from_toml(toml_path, true)?;
+                    }
+                }
+            }
+
             Workspace { root_dir: nargo_toml.root_dir, members, selected_package_index }
         }
     };
@@ -367,12 +430,15 @@ fn read_toml(toml_path: &Path) -> Result<NargoToml, ManifestError> {
 }
 
 /// Resolves a Nargo.toml file into a `Package` struct as defined by our `nargo` core.
-fn resolve_package_from_toml(toml_path: &Path) -> Result<Package, ManifestError> {
+fn resolve_package_from_toml(
+    toml_path: &Path,
+    with_dev_dependencies: bool,
+) -> Result<Package, ManifestError> {
     let nargo_toml = read_toml(toml_path)?;
 
     match nargo_toml.config {
         Config::Package { package_config } => {
-            package_config.resolve_to_package(&nargo_toml.root_dir)
+            package_config.resolve_to_package(&nargo_toml.root_dir, with_dev_dependencies)
         }
         Config::Workspace { .. } => {
             Err(ManifestError::UnexpectedWorkspace(toml_path.to_path_buf()))
@@ -394,7 +460,20 @@ pub fn resolve_workspace_from_toml(
 ) -> Result<Workspace, ManifestError> {
     let nargo_toml = read_toml(toml_path)?;
 
-    toml_to_workspace(nargo_toml, package_selection)
+    toml_to_workspace(nargo_toml, package_selection, false)
+}
+
+/// Resolves a Nargo.toml file into a `Workspace` struct in the same way as
+/// [resolve_workspace_from_toml], but also resolves the `[dev-dependencies]` of the selected
+/// workspace members so that their tests can be compiled. The dev-dependencies of their
+/// dependencies and of the other members are never resolved.
+pub fn resolve_test_workspace_from_toml(
+    toml_path: &Path,
+    package_selection: PackageSelection,
+) -> Result<Workspace, ManifestError> {
+    let nargo_toml = read_toml(toml_path)?;
+
+    toml_to_workspace(nargo_toml, package_selection, true)
 }
 
 #[test]
@@ -410,6 +489,9 @@ fn parse_standard_toml() {
         rand = { tag = "next", git = "https://github.com/rust-lang-nursery/rand"}
         cool = { tag = "next", git = "https://github.com/rust-lang-nursery/rand"}
         hello = {path = "./noir_driver"}
+
+        [dev-dependencies]
+        mocks = {path = "./mocks"}
     "#;
 
     assert!(Config::try_from(String::from(src)).is_ok());
@@ -429,6 +511,34 @@ fn parse_package_toml_no_deps() {
     assert!(Config::try_from(src).is_ok());
 }
 
+#[test]
+fn parse_package_max_opcodes() {
+    let src = r#"
+        [package]
+        name = "test"
+        max_opcodes = 500000
+    "#;
+
+    let Config::Package { package_config } = Config::try_from(src).unwrap() else {
+        panic!("Expected a package config");
+    };
+    assert_eq!(package_config.package.max_opcodes, Some(500000));
+}
+
+#[test]
+fn parse_package_implicit_conversions() {
+    let src = r#"
+        [package]
+        name = "test"
+        implicit_conversions = true
+    "#;
+
+    let Config::Package { package_config } = Config::try_from(src).unwrap() else {
+        panic!("Expected a package config");
+    };
+    assert_eq!(package_config.package.implicit_conversions, Some(true));
+}
+
 #[test]
 fn parse_workspace_toml() {
     let src = r#"
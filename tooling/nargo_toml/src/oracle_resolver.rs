@@ -0,0 +1,138 @@
+use std::{collections::BTreeMap, path::Path, time::Duration};
+
+use nargo::package::{OraclePolicy, OracleResolverConfig};
+use serde::Deserialize;
+
+use crate::ManifestError;
+
+/// The `[oracle-resolver]` section of a `Nargo.toml`
+#[derive(Default, Debug, Deserialize, Clone)]
+#[serde(rename_all = "kebab-case")]
+pub(crate) struct OracleResolverSection {
+    token_env: Option<String>,
+    #[serde(flatten)]
+    policy: PolicyConfig,
+    #[serde(default)]
+    oracles: BTreeMap<String, PolicyConfig>,
+}
+
+impl OracleResolverSection {
+    /// Resolves the default policy and those of individual oracles, which start from the
+    /// default policy
+    pub(crate) fn resolve(&self, toml: &Path) -> Result<OracleResolverConfig, ManifestError> {
+        if let Some(token_env) = &self.token_env {
+            if token_env.is_empty() {
+                return Err(ManifestError::InvalidOracleResolverValue {
+                    toml: toml.to_path_buf(),
+                    section: "oracle-resolver".to_string(),
+                    key: "token-env".to_string(),
+                    value: "\"\"".to_string(),
+                });
+            }
+        }
+
+        let default_policy =
+            self.policy.resolve(OraclePolicy::default(), "oracle-resolver", toml)?;
+        let mut oracles = BTreeMap::new();
+        for (name, config) in &self.oracles {
+            let section = format!("oracle-resolver.oracles.{name}");
+            oracles.insert(name.clone(), config.resolve(default_policy.clone(), &section, toml)?);
+        }
+
+        Ok(OracleResolverConfig { token_env: self.token_env.clone(), default_policy, oracles })
+    }
+}
+
+/// The settings of a policy, overriding those of the policy it is resolved from
+#[derive(Default, Debug, Deserialize, Clone)]
+#[serde(rename_all = "kebab-case")]
+struct PolicyConfig {
+    timeout_ms: Option<u64>,
+    retries: Option<u32>,
+    backoff_ms: Option<u64>,
+}
+
+impl PolicyConfig {
+    fn resolve(
+        &self,
+        default: OraclePolicy,
+        section: &str,
+        toml: &Path,
+    ) -> Result<OraclePolicy, ManifestError> {
+        let mut policy = default;
+        if let Some(timeout_ms) = self.timeout_ms {
+            if timeout_ms == 0 {
+                return Err(ManifestError::InvalidOracleResolverValue {
+                    toml: toml.to_path_buf(),
+                    section: section.to_string(),
+                    key: "timeout-ms".to_string(),
+                    value: timeout_ms.to_string(),
+                });
+            }
+            policy.timeout = Duration::from_millis(timeout_ms);
+        }
+        if let Some(retries) = self.retries {
+            policy.retries = retries;
+        }
+        if let Some(backoff_ms) = self.backoff_ms {
+            policy.backoff = Duration::from_millis(backoff_ms);
+        }
+        Ok(policy)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{path::Path, time::Duration};
+
+    use nargo::package::{OraclePolicy, OracleResolverConfig};
+
+    use super::OracleResolverSection;
+
+    fn resolve(src: &str) -> Result<OracleResolverConfig, crate::ManifestError> {
+        let config: OracleResolverSection = toml::from_str(src).unwrap();
+        config.resolve(Path::new("Nargo.toml"))
+    }
+
+    #[test]
+    fn missing_section_uses_defaults() {
+        let config = resolve("").unwrap();
+        assert_eq!(config, OracleResolverConfig::default());
+        assert_eq!(config.token_env(), OracleResolverConfig::DEFAULT_TOKEN_ENV);
+    }
+
+    #[test]
+    fn oracle_policies_override_the_default_policy() {
+        let config = resolve(
+            r#"
+            token-env = "PRICE_ORACLE_TOKEN"
+            timeout-ms = 5000
+            retries = 1
+
+            [oracles.get_price]
+            retries = 5
+            backoff-ms = 1000
+        "#,
+        )
+        .unwrap();
+
+        let default_policy = OraclePolicy {
+            timeout: Duration::from_millis(5000),
+            retries: 1,
+            ..OraclePolicy::default()
+        };
+        assert_eq!(config.token_env(), "PRICE_ORACLE_TOKEN");
+        assert_eq!(config.policy("get_time"), &default_policy);
+        assert_eq!(
+            config.policy("get_price"),
+            &OraclePolicy { retries: 5, backoff: Duration::from_millis(1000), ..default_policy }
+        );
+    }
+
+    #[test]
+    fn rejects_invalid_values() {
+        assert!(resolve("timeout-ms = 0").is_err());
+        assert!(resolve("[oracles.get_price]\ntimeout-ms = 0").is_err());
+        assert!(resolve("token-env = \"\"").is_err());
+    }
+}
@@ -0,0 +1,156 @@
+use std::path::Path;
+
+use noirc_driver::{BuildProfile, DebugLevel};
+use serde::Deserialize;
+
+use crate::ManifestError;
+
+/// The `[profile]` section of a `Nargo.toml`
+#[derive(Default, Debug, Deserialize, Clone)]
+pub(crate) struct ProfilesConfig {
+    dev: Option<ProfileConfig>,
+    release: Option<ProfileConfig>,
+}
+
+impl ProfilesConfig {
+    /// Resolves the dev and release profiles, starting from their defaults
+    pub(crate) fn resolve(
+        &self,
+        toml: &Path,
+    ) -> Result<(BuildProfile, BuildProfile), ManifestError> {
+        let dev = resolve_profile(self.dev.as_ref(), BuildProfile::dev(), "dev", toml)?;
+        let release =
+            resolve_profile(self.release.as_ref(), BuildProfile::release(), "release", toml)?;
+        Ok((dev, release))
+    }
+}
+
+fn resolve_profile(
+    config: Option<&ProfileConfig>,
+    default: BuildProfile,
+    name: &str,
+    toml: &Path,
+) -> Result<BuildProfile, ManifestError> {
+    match config {
+        Some(config) => config.resolve(default, name, toml),
+        None => Ok(default),
+    }
+}
+
+/// A `[profile.<name>]` section, overriding the settings of the profile's defaults
+#[derive(Default, Debug, Deserialize, Clone)]
+#[serde(rename_all = "kebab-case")]
+struct ProfileConfig {
+    opt_level: Option<u8>,
+    overflow_checks: Option<bool>,
+    debug: Option<DebugConfig>,
+    brillig_step_limit: Option<u64>,
+}
+
+/// The `debug` setting may be a boolean, a level from 0 to 2 or the name of a level,
+/// in the same way as in Cargo
+#[derive(Debug, Deserialize, Clone)]
+#[serde(untagged)]
+enum DebugConfig {
+    Enabled(bool),
+    Level(u8),
+    Named(String),
+}
+
+impl ProfileConfig {
+    fn resolve(
+        &self,
+        default: BuildProfile,
+        name: &str,
+        toml: &Path,
+    ) -> Result<BuildProfile, ManifestError> {
+        let invalid_value = |key: &str, value: String| ManifestError::InvalidProfileValue {
+            toml: toml.to_path_buf(),
+            profile: name.to_string(),
+            key: key.to_string(),
+            value,
+        };
+
+        let mut profile = default;
+        if let Some(opt_level) = self.opt_level {
+            if opt_level > BuildProfile::MAX_OPT_LEVEL {
+                return Err(invalid_value("opt-level", opt_level.to_string()));
+            }
+            profile.opt_level = opt_level;
+        }
+        if let Some(overflow_checks) = self.overflow_checks {
+            profile.overflow_checks = overflow_checks;
+        }
+        if let Some(debug) = &self.debug {
+            profile.debug = match debug {
+                DebugConfig::Enabled(false) | DebugConfig::Level(0) => DebugLevel::None,
+                DebugConfig::Level(1) => DebugLevel::LineTablesOnly,
+                DebugConfig::Enabled(true) | DebugConfig::Level(2) => DebugLevel::Full,
+                DebugConfig::Named(name) => match name.as_str() {
+                    "none" => DebugLevel::None,
+                    "line-tables-only" => DebugLevel::LineTablesOnly,
+                    "full" => DebugLevel::Full,
+                    _ => return Err(invalid_value("debug", format!("\"{name}\""))),
+                },
+                DebugConfig::Level(level) => return Err(invalid_value("debug", level.to_string())),
+            };
+        }
+        if let Some(brillig_step_limit) = self.brillig_step_limit {
+            profile.brillig_step_limit = Some(brillig_step_limit);
+        }
+        Ok(profile)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use noirc_driver::{BuildProfile, DebugLevel};
+
+    use super::ProfilesConfig;
+
+    fn resolve(src: &str) -> Result<(BuildProfile, BuildProfile), crate::ManifestError> {
+        let config: ProfilesConfig = toml::from_str(src).unwrap();
+        config.resolve(Path::new("Nargo.toml"))
+    }
+
+    #[test]
+    fn missing_profiles_use_defaults() {
+        let (dev, release) = resolve("").unwrap();
+        assert_eq!(dev, BuildProfile::dev());
+        assert_eq!(release, BuildProfile::release());
+    }
+
+    #[test]
+    fn profiles_override_defaults() {
+        let (dev, release) = resolve(
+            r#"
+            [dev]
+            opt-level = 0
+            overflow-checks = true
+            brillig-step-limit = 1000
+
+            [release]
+            debug = "line-tables-only"
+        "#,
+        )
+        .unwrap();
+
+        assert_eq!(dev.opt_level, 0);
+        assert!(dev.overflow_checks);
+        assert_eq!(dev.debug, DebugLevel::Full);
+        assert_eq!(dev.brillig_step_limit, Some(1000));
+        assert_eq!(
+            release,
+            BuildProfile { debug: DebugLevel::LineTablesOnly, ..BuildProfile::release() }
+        );
+    }
+
+    #[test]
+    fn rejects_invalid_values() {
+        assert!(resolve("[dev]\nopt-level = 3").is_err());
+        assert!(resolve("[release]\ndebug = 3").is_err());
+        assert!(resolve("[release]\ndebug = \"some\"").is_err());
+    }
+}
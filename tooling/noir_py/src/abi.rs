@@ -0,0 +1,115 @@
+use std::collections::BTreeMap;
+
+use acvm::{
+    acir::native_types::{Witness, WitnessMap},
+    FieldElement,
+};
+use iter_extended::try_btree_map;
+use noirc_abi::{input_parser::json::JsonTypes, Abi, MAIN_RETURN_NAME};
+use pyo3::{prelude::*, types::PyDict};
+
+use crate::errors::{to_py_err, AbiError};
+
+/// Encodes the `inputs` dictionary, and optionally the expected return value, into the
+/// initial witness of a program with the given ABI.
+#[pyfunction]
+#[pyo3(signature = (abi, inputs, return_value = None))]
+pub(crate) fn abi_encode<'py>(
+    py: Python<'py>,
+    abi: &PyAny,
+    inputs: &PyAny,
+    return_value: Option<&PyAny>,
+) -> PyResult<&'py PyDict> {
+    let abi = parse_abi(py, abi)?;
+    let witness_map = encode_inputs(py, &abi, inputs, return_value)?;
+    witness_map_to_dict(py, witness_map)
+}
+
+/// Decodes a witness back into an `(inputs, return_value)` pair using the given ABI.
+#[pyfunction]
+pub(crate) fn abi_decode(
+    py: Python<'_>,
+    abi: &PyAny,
+    witness: &PyDict,
+) -> PyResult<(PyObject, PyObject)> {
+    let abi = parse_abi(py, abi)?;
+    let witness_map = witness_map_from_dict(witness)?;
+
+    let (inputs, return_value) =
+        abi.decode(&witness_map).map_err(|err| AbiError::new_err(err.to_string()))?;
+
+    let abi_types = abi.to_btree_map();
+    let inputs: BTreeMap<String, JsonTypes> = try_btree_map(inputs, |(key, value)| {
+        JsonTypes::try_from_input_value(&value, &abi_types[&key]).map(|value| (key, value))
+    })
+    .map_err(|err| AbiError::new_err(err.to_string()))?;
+
+    let return_value = match (return_value, &abi.return_type) {
+        (Some(value), Some(return_type)) => Some(
+            JsonTypes::try_from_input_value(&value, return_type)
+                .map_err(|err| AbiError::new_err(err.to_string()))?,
+        ),
+        _ => None,
+    };
+
+    Ok((to_python(py, &inputs)?, to_python(py, &return_value)?))
+}
+
+fn parse_abi(py: Python<'_>, abi: &PyAny) -> PyResult<Abi> {
+    serde_json::from_str(&to_json(py, abi)?)
+        .map_err(|err| AbiError::new_err(format!("invalid ABI: {err}")))
+}
+
+/// Encodes a dictionary of inputs, along with an optional return value, against `abi`.
+///
+/// As in `nargo`'s JSON input files, the return value may also be given under the `return` key.
+pub(crate) fn encode_inputs(
+    py: Python<'_>,
+    abi: &Abi,
+    inputs: &PyAny,
+    return_value: Option<&PyAny>,
+) -> PyResult<WitnessMap> {
+    let inputs: &PyDict = inputs.downcast()?;
+    let inputs = inputs.copy()?;
+    match (return_value, &abi.return_type) {
+        (Some(value), Some(_)) => inputs.set_item(MAIN_RETURN_NAME, value)?,
+        (Some(_), None) => {
+            return Err(AbiError::new_err("a return value was given but the ABI has none"));
+        }
+        (None, _) => (),
+    }
+
+    noirc_c::encode_inputs(abi, to_json(py, inputs)?.as_bytes()).map_err(|err| to_py_err(py, err))
+}
+
+pub(crate) fn witness_map_to_dict(py: Python<'_>, witness_map: WitnessMap) -> PyResult<&PyDict> {
+    let dict = PyDict::new(py);
+    for (witness, value) in witness_map {
+        dict.set_item(witness.witness_index(), format!("0x{}", value.to_hex()))?;
+    }
+    Ok(dict)
+}
+
+fn witness_map_from_dict(witness: &PyDict) -> PyResult<WitnessMap> {
+    let mut witness_map = WitnessMap::new();
+    for (index, value) in witness {
+        let index: u32 = index.extract()?;
+        let value: &str = value.extract()?;
+        let value = FieldElement::from_hex(value).ok_or_else(|| {
+            AbiError::new_err(format!("witness {index} is not a hex encoded field element"))
+        })?;
+        witness_map.insert(Witness(index), value);
+    }
+    Ok(witness_map)
+}
+
+/// Converts a Python object to JSON with the standard library's `json` module so that it can
+/// be deserialized into the same types used for `nargo`'s JSON input files.
+fn to_json(py: Python<'_>, value: &PyAny) -> PyResult<String> {
+    py.import("json")?.call_method1("dumps", (value,))?.extract()
+}
+
+pub(crate) fn to_python(py: Python<'_>, value: &impl serde::Serialize) -> PyResult<PyObject> {
+    let json = serde_json::to_string(value).expect("JSON values are always serializable");
+    Ok(py.import("json")?.call_method1("loads", (json,))?.into())
+}
@@ -0,0 +1,58 @@
+use noirc_c::NoircError;
+use pyo3::{
+    create_exception,
+    exceptions::{PyException, PyValueError},
+    prelude::*,
+};
+
+use crate::abi::to_python;
+
+create_exception!(noir_py, NoirError, PyException, "Base class of every error raised by noir_py.");
+create_exception!(
+    noir_py,
+    CompileError,
+    NoirError,
+    "A program failed to compile. Its `diagnostics` attribute lists the errors and warnings found."
+);
+create_exception!(
+    noir_py,
+    AbiError,
+    NoirError,
+    "An ABI or set of inputs was malformed, or the inputs did not match the ABI."
+);
+create_exception!(
+    noir_py,
+    ExecutionError,
+    NoirError,
+    "A program failed while being executed, e.g. due to a failing constraint."
+);
+
+pub(crate) fn register(py: Python<'_>, m: &PyModule) -> PyResult<()> {
+    m.add("NoirError", py.get_type::<NoirError>())?;
+    m.add("CompileError", py.get_type::<CompileError>())?;
+    m.add("AbiError", py.get_type::<AbiError>())?;
+    m.add("ExecutionError", py.get_type::<ExecutionError>())?;
+    Ok(())
+}
+
+/// Converts an error from the shared bindings in `noirc_c` into the matching Python exception.
+///
+/// A `CompileError` carries each diagnostic as a dictionary in its `diagnostics` attribute,
+/// using the same layout as the diagnostics reported by the WASM compiler.
+pub(crate) fn to_py_err(py: Python<'_>, error: NoircError) -> PyErr {
+    match error {
+        NoircError::InvalidArgument(message) => PyValueError::new_err(message),
+        NoircError::Compile { message, diagnostics } => {
+            let error = CompileError::new_err(message);
+            let diagnostics = to_python(py, &diagnostics)
+                .and_then(|diagnostics| error.value(py).setattr("diagnostics", diagnostics));
+            match diagnostics {
+                Ok(()) => error,
+                Err(conversion_error) => conversion_error,
+            }
+        }
+        NoircError::Abi(message) => AbiError::new_err(message),
+        NoircError::Execution(message) => ExecutionError::new_err(message),
+        NoircError::Panic => NoirError::new_err("the compiler panicked"),
+    }
+}
@@ -0,0 +1,30 @@
+//! Python bindings for compiling and executing Noir programs.
+//!
+//! ABIs, input maps and return values are passed as plain Python dictionaries, lists and
+//! strings using the same JSON layout as `nargo`. Witnesses are dictionaries mapping each
+//! witness index to its value as a hex encoded field element.
+#![warn(unused_crate_dependencies, unused_extern_crates)]
+#![warn(unreachable_pub)]
+#![warn(clippy::semicolon_if_nothing_returned)]
+
+use pyo3::prelude::*;
+
+mod abi;
+mod errors;
+mod program;
+
+#[cfg(test)]
+mod tests;
+
+#[pymodule]
+fn noir_py(py: Python<'_>, m: &PyModule) -> PyResult<()> {
+    m.add("__version__", env!("CARGO_PKG_VERSION"))?;
+    errors::register(py, m)?;
+
+    m.add_class::<program::Program>()?;
+    m.add_function(wrap_pyfunction!(program::compile, m)?)?;
+    m.add_function(wrap_pyfunction!(program::execute, m)?)?;
+    m.add_function(wrap_pyfunction!(abi::abi_encode, m)?)?;
+    m.add_function(wrap_pyfunction!(abi::abi_decode, m)?)?;
+    Ok(())
+}
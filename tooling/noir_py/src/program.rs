@@ -0,0 +1,58 @@
+use std::path::Path;
+
+use nargo::artifacts::program::PreprocessedProgram;
+use pyo3::{exceptions::PyValueError, prelude::*, types::PyDict};
+
+use crate::abi::{encode_inputs, to_python, witness_map_to_dict};
+use crate::errors::to_py_err;
+
+/// A compiled Noir program, equivalent to the JSON artifact written by `nargo compile`.
+#[pyclass(module = "noir_py")]
+pub(crate) struct Program {
+    program: PreprocessedProgram,
+}
+
+#[pymethods]
+impl Program {
+    /// The program's ABI as a dictionary.
+    #[getter]
+    fn abi(&self, py: Python<'_>) -> PyResult<PyObject> {
+        to_python(py, &self.program.abi)
+    }
+
+    /// Serializes the program into the JSON artifact format used by `nargo`.
+    fn to_json(&self) -> String {
+        serde_json::to_string(&self.program).expect("program artifacts are serializable")
+    }
+
+    /// Loads a program from a JSON artifact, e.g. one found in a package's `target` directory.
+    #[staticmethod]
+    fn from_json(json: &str) -> PyResult<Program> {
+        let program = serde_json::from_str(json)
+            .map_err(|err| PyValueError::new_err(format!("invalid program artifact: {err}")))?;
+        Ok(Program { program })
+    }
+}
+
+/// Compiles the binary package in `program_dir`, which must contain a `Nargo.toml`.
+#[pyfunction]
+pub(crate) fn compile(py: Python<'_>, program_dir: &str) -> PyResult<Program> {
+    let program =
+        noirc_c::compile_program(Path::new(program_dir)).map_err(|err| to_py_err(py, err))?;
+    Ok(Program { program })
+}
+
+/// Executes `program` with the given `inputs` dictionary, returning the solved witness.
+#[pyfunction]
+#[pyo3(signature = (program, inputs, return_value = None))]
+pub(crate) fn execute<'py>(
+    py: Python<'py>,
+    program: &Program,
+    inputs: &PyAny,
+    return_value: Option<&PyAny>,
+) -> PyResult<&'py PyDict> {
+    let initial_witness = encode_inputs(py, &program.program.abi, inputs, return_value)?;
+    let solved_witness = noirc_c::execute_program(&program.program, initial_witness)
+        .map_err(|err| to_py_err(py, err))?;
+    witness_map_to_dict(py, solved_witness)
+}
@@ -0,0 +1,104 @@
+//! Runs Python snippets against the module so that the bindings are checked as Python sees them,
+//! including the conversion of errors into exceptions.
+
+use pyo3::{prelude::*, types::PyDict};
+
+const MAIN: &str = r#"
+fn main(x: Field, y: pub Field) -> pub Field {
+    assert(x != y);
+    x * y
+}
+"#;
+
+/// Writes a binary package with the given `main.nr` into a temporary directory
+fn write_package(main: &str) -> tempfile::TempDir {
+    let dir = tempfile::tempdir().unwrap();
+    let manifest =
+        "[package]\nname = \"noir_py\"\ntype = \"bin\"\nauthors = [\"\"]\n\n[dependencies]";
+    std::fs::write(dir.path().join("Nargo.toml"), manifest).unwrap();
+    std::fs::create_dir(dir.path().join("src")).unwrap();
+    std::fs::write(dir.path().join("src").join("main.nr"), main).unwrap();
+    dir
+}
+
+/// Runs `code` with the module bound to `noir_py` and the package directory to `program_dir`
+fn run_python(main: &str, code: &str) {
+    let package = write_package(main);
+
+    pyo3::prepare_freethreaded_python();
+    Python::with_gil(|py| {
+        let module = PyModule::new(py, "noir_py").unwrap();
+        crate::noir_py(py, module).unwrap();
+
+        let globals = PyDict::new(py);
+        globals.set_item("noir_py", module).unwrap();
+        globals.set_item("program_dir", package.path().to_str().unwrap()).unwrap();
+        if let Err(error) = py.run(code, Some(globals), None) {
+            error.print(py);
+            panic!("Python code failed: {error}");
+        }
+    });
+}
+
+#[test]
+fn compiles_executes_and_decodes_a_program() {
+    run_python(
+        MAIN,
+        r#"
+program = noir_py.compile(program_dir)
+assert [param["name"] for param in program.abi["parameters"]] == ["x", "y"]
+
+witness = noir_py.execute(program, {"x": 3, "y": 4})
+inputs, return_value = noir_py.abi_decode(program.abi, witness)
+assert inputs == {"x": "0x03", "y": "0x04"}, inputs
+assert return_value == "0x0c", return_value
+
+initial_witness = noir_py.abi_encode(program.abi, {"x": 3, "y": 4}, return_value=12)
+assert len(initial_witness) < len(witness)
+
+reloaded = noir_py.Program.from_json(program.to_json())
+assert noir_py.execute(reloaded, {"x": 3, "y": 4}) == witness
+"#,
+    );
+}
+
+#[test]
+fn raises_compile_errors_with_their_diagnostics() {
+    run_python(
+        "fn main(x: Field) { let y: u8 = x; }",
+        r#"
+try:
+    noir_py.compile(program_dir)
+    raise AssertionError("expected a CompileError")
+except noir_py.CompileError as error:
+    errors = [diagnostic for diagnostic in error.diagnostics if not diagnostic["is_warning"]]
+    assert len(errors) == 1, error.diagnostics
+    assert errors[0]["file_path"].endswith("main.nr")
+    assert isinstance(error, noir_py.NoirError)
+"#,
+    );
+}
+
+#[test]
+fn raises_abi_and_execution_errors() {
+    run_python(
+        MAIN,
+        r#"
+program = noir_py.compile(program_dir)
+
+def raises(exception, f):
+    try:
+        f()
+    except exception as error:
+        return str(error)
+    raise AssertionError(f"expected {exception.__name__}")
+
+raises(noir_py.AbiError, lambda: noir_py.execute(program, {"x": 3}))
+raises(noir_py.AbiError, lambda: noir_py.abi_encode("not an abi", {}))
+raises(noir_py.AbiError, lambda: noir_py.abi_decode(program.abi, {0: "not hex"}))
+raises(noir_py.ExecutionError, lambda: noir_py.execute(program, {"x": 3, "y": 3}))
+raises(ValueError, lambda: noir_py.compile("/does/not/exist"))
+raises(ValueError, lambda: noir_py.Program.from_json("{}"))
+"#,
+    );
+}
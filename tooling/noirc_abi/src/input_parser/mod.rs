@@ -3,6 +3,8 @@ use num_traits::{Num, Zero};
 use std::collections::BTreeMap;
 
 use acvm::FieldElement;
+use iter_extended::vecmap;
+use noirc_printable_type::FieldDisplay;
 use serde::Serialize;
 
 use crate::errors::InputParserError;
@@ -81,6 +83,46 @@ impl InputValue {
     }
 }
 
+impl InputValue {
+    /// Displays the value with its field elements shown with `field_display`
+    pub fn display(&self, field_display: FieldDisplay) -> InputValueDisplay<'_> {
+        InputValueDisplay { value: self, field_display }
+    }
+}
+
+/// The display of an [`InputValue`] returned by [`InputValue::display`]
+pub struct InputValueDisplay<'value> {
+    value: &'value InputValue,
+    field_display: FieldDisplay,
+}
+
+impl std::fmt::Display for InputValueDisplay<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.value {
+            InputValue::Field(field) => write!(f, "{}", self.field_display.format(*field)),
+            InputValue::String(string) => write!(f, "{string:?}"),
+            InputValue::Vec(elements) => {
+                let elements =
+                    vecmap(elements, |element| element.display(self.field_display).to_string());
+                write!(f, "[{}]", elements.join(", "))
+            }
+            InputValue::Struct(fields) => {
+                let fields = vecmap(fields, |(name, value)| {
+                    format!("{name}: {}", value.display(self.field_display))
+                });
+                write!(f, "{{ {} }}", fields.join(", "))
+            }
+        }
+    }
+}
+
+/// Displays field elements in decimal
+impl std::fmt::Display for InputValue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.display(FieldDisplay::Decimal).fmt(f)
+    }
+}
+
 /// The different formats that are supported when parsing
 /// the initial witness values
 #[cfg_attr(test, derive(strum_macros::EnumIter))]
@@ -130,7 +172,8 @@ mod serialization_tests {
     use strum::IntoEnumIterator;
 
     use crate::{
-        input_parser::InputValue, Abi, AbiParameter, AbiType, AbiVisibility, Sign, MAIN_RETURN_NAME,
+        input_parser::InputValue, Abi, AbiParameter, AbiStructLayout, AbiType, AbiVisibility, Sign,
+        MAIN_RETURN_NAME,
     };
 
     use super::Format;
@@ -155,14 +198,16 @@ mod serialization_tests {
                                 AbiType::Array { length: 2, typ: Box::new(AbiType::Boolean) },
                             ),
                         ],
+                        layout: AbiStructLayout::Declared,
                     },
                     visibility: AbiVisibility::Private,
                 },
             ],
             return_type: Some(AbiType::String { length: 5 }),
-            // These two fields are unused when serializing/deserializing to file.
+            // These fields are unused when serializing/deserializing to file.
             param_witnesses: BTreeMap::new(),
             return_witnesses: Vec::new(),
+            return_constants: BTreeMap::new(),
         };
 
         let input_map: BTreeMap<String, InputValue> = BTreeMap::from([
@@ -254,10 +299,13 @@ fn field_from_big_int(bigint: BigInt) -> FieldElement {
 
 #[cfg(test)]
 mod test {
+    use std::collections::BTreeMap;
+
     use acvm::FieldElement;
+    use noirc_printable_type::FieldDisplay;
     use num_bigint::BigUint;
 
-    use super::parse_str_to_field;
+    use super::{parse_str_to_field, InputValue};
 
     fn big_uint_from_field(field: FieldElement) -> BigUint {
         BigUint::from_bytes_be(&field.to_be_bytes())
@@ -296,4 +344,20 @@ mod test {
         let parsed_field = parse_str_to_field(&noncanonical_field);
         println!("{parsed_field:?}");
     }
+
+    #[test]
+    fn displays_fields_with_the_given_display() {
+        let minus_one = InputValue::Field(-FieldElement::one());
+        let value = InputValue::Struct(BTreeMap::from([
+            ("a".to_owned(), InputValue::Vec(vec![minus_one, InputValue::Field(42_u128.into())])),
+            ("b".to_owned(), InputValue::String("hi".to_owned())),
+        ]));
+
+        assert_eq!(value.display(FieldDisplay::Signed).to_string(), r#"{ a: [-1, 42], b: "hi" }"#);
+        assert_eq!(
+            value.display(FieldDisplay::Hex).to_string(),
+            format!(r#"{{ a: [0x{}, 0x2a], b: "hi" }}"#, (-FieldElement::one()).to_hex())
+        );
+        assert_eq!(value.to_string(), value.display(FieldDisplay::Decimal).to_string());
+    }
 }
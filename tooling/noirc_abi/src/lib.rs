@@ -13,7 +13,8 @@ use errors::AbiError;
 use input_parser::InputValue;
 use iter_extended::{try_btree_map, try_vecmap, vecmap};
 use noirc_frontend::{
-    hir::Context, Signedness, StructType, Type, TypeBinding, TypeVariableKind, Visibility,
+    hir::Context, token::StructLayout, Signedness, StructType, Type, TypeBinding, TypeVariableKind,
+    Visibility,
 };
 use serde::{Deserialize, Serialize};
 // This is the ABI used to bridge the different TOML formats for the initial
@@ -61,6 +62,10 @@ pub enum AbiType {
             deserialize_with = "serialization::deserialize_struct_fields"
         )]
         fields: Vec<(String, AbiType)>,
+        /// The layout of the struct, which gives the order of `fields` they are encoded in. ABIs
+        /// from before the layout was recorded always use the declared order.
+        #[serde(default)]
+        layout: AbiStructLayout,
     },
     Tuple {
         fields: Vec<AbiType>,
@@ -113,6 +118,30 @@ pub enum AbiDistinctness {
     DuplicationAllowed,
 }
 
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+/// How the fields of a struct are encoded, as set by its `#[repr(...)]` attribute.
+pub enum AbiStructLayout {
+    /// The fields are in the order they are declared
+    #[default]
+    Declared,
+    /// The fields are in the alphabetical order of their names
+    Sorted,
+    /// The fields are in the order they are declared, and the struct is passed to foreign calls
+    /// as a single array of its field elements
+    Packed,
+}
+
+impl From<StructLayout> for AbiStructLayout {
+    fn from(value: StructLayout) -> Self {
+        match value {
+            StructLayout::Declared => AbiStructLayout::Declared,
+            StructLayout::Sorted => AbiStructLayout::Sorted,
+            StructLayout::Packed => AbiStructLayout::Packed,
+        }
+    }
+}
+
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
 pub enum Sign {
@@ -159,14 +188,15 @@ impl AbiType {
             Type::Unit => unreachable!(),
             Type::Constant(_) => unreachable!(),
             Type::TraitAsType(_) => unreachable!(),
+            Type::TraitObject(_) => unreachable!("trait objects cannot be used in the abi"),
             Type::Struct(def, ref args) => {
                 let struct_type = def.borrow();
-                let fields = struct_type.get_fields(args);
+                let fields = struct_type.get_fields_in_layout_order(args);
                 let fields = vecmap(fields, |(name, typ)| (name, Self::from_type(context, &typ)));
                 // For the ABI, we always want to resolve the struct paths from the root crate
                 let path =
                     context.fully_qualified_struct_path(context.root_crate_id(), struct_type.id);
-                Self::Struct { fields, path }
+                Self::Struct { fields, path, layout: struct_type.layout.into() }
             }
             Type::Tuple(fields) => {
                 let fields = vecmap(fields, |typ| Self::from_type(context, typ));
@@ -178,6 +208,7 @@ impl AbiType {
             Type::Function(_, _, _) => unreachable!(),
             Type::MutableReference(_) => unreachable!("&mut cannot be used in the abi"),
             Type::NotConstant => unreachable!(),
+            Type::InfixExpr(..) => unreachable!(),
         }
     }
 
@@ -221,6 +252,13 @@ pub struct Abi {
     pub param_witnesses: BTreeMap<String, Vec<Witness>>,
     pub return_type: Option<AbiType>,
     pub return_witnesses: Vec<Witness>,
+    /// Elements of the encoded return value which are fixed at compile time, keyed by their
+    /// position within the encoding.
+    ///
+    /// These elements have no witness and are not public inputs, so `return_witnesses` only
+    /// covers the remaining positions of the return value.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub return_constants: BTreeMap<u32, FieldElement>,
 }
 
 impl Abi {
@@ -270,6 +308,7 @@ impl Abi {
             param_witnesses,
             return_type: self.return_type,
             return_witnesses: self.return_witnesses,
+            return_constants: self.return_constants,
         }
     }
 
@@ -333,12 +372,28 @@ impl Abi {
                         value: return_value,
                     });
                 }
-                let encoded_return_fields = Self::encode_value(return_value, return_type)?;
+                let encoded_return_fields = Self::encode_value(return_value.clone(), return_type)?;
+
+                // Elements of the return value which were fixed at compile time have no witness,
+                // but we must still check that the provided return value agrees with them.
+                let mut return_witness_fields = Vec::new();
+                for (position, field_element) in encoded_return_fields.into_iter().enumerate() {
+                    match self.return_constants.get(&(position as u32)) {
+                        Some(constant) if *constant != field_element => {
+                            return Err(AbiError::ReturnConstantMismatch {
+                                expected: *constant,
+                                value: return_value,
+                            });
+                        }
+                        Some(_) => (),
+                        None => return_witness_fields.push(field_element),
+                    }
+                }
 
                 // We need to be more careful when writing the return value's witness values.
                 // This is as it may share witness indices with other public inputs so we must check that when
                 // this occurs the witness values are consistent with each other.
-                self.return_witnesses.iter().zip(encoded_return_fields.iter()).try_for_each(
+                self.return_witnesses.iter().zip(return_witness_fields.iter()).try_for_each(
                     |(&witness, &field_element)| match witness_map.insert(witness, field_element) {
                         Some(existing_value) if existing_value != field_element => {
                             Err(AbiError::InconsistentWitnessAssignment(witness))
@@ -425,7 +480,8 @@ impl Abi {
                         .copied()
                 })
             {
-                Some(decode_value(&mut return_witness_values.into_iter(), return_type)?)
+                let return_values = self.interleave_return_constants(return_witness_values);
+                Some(decode_value(&mut return_values.into_iter(), return_type)?)
             } else {
                 // Unlike for the circuit inputs, we tolerate not being able to find the witness values for the return value.
                 // This is because the user may be decoding a partial witness map for which is hasn't been calculated yet.
@@ -438,6 +494,19 @@ impl Abi {
 
         Ok((public_inputs_map, return_value))
     }
+
+    /// Reinserts the compile-time constant elements of the return value between the values read
+    /// from `return_witnesses`, recovering the full encoding of the return value.
+    fn interleave_return_constants(&self, witness_values: Vec<FieldElement>) -> Vec<FieldElement> {
+        let total_length = witness_values.len() + self.return_constants.len();
+        let mut witness_values = witness_values.into_iter();
+        (0..total_length as u32)
+            .filter_map(|position| match self.return_constants.get(&position) {
+                Some(constant) => Some(*constant),
+                None => witness_values.next(),
+            })
+            .collect()
+    }
 }
 
 fn decode_value(
@@ -519,7 +588,7 @@ pub struct ContractEvent {
 
 impl ContractEvent {
     pub fn from_struct_type(context: &Context, struct_type: &StructType) -> Self {
-        let fields = vecmap(struct_type.get_fields(&[]), |(name, typ)| {
+        let fields = vecmap(struct_type.get_fields_in_layout_order(&[]), |(name, typ)| {
             (name, AbiType::from_type(context, &typ))
         });
         // For the ABI, we always want to resolve the struct paths from the root crate
@@ -535,7 +604,10 @@ mod test {
 
     use acvm::{acir::native_types::Witness, FieldElement};
 
-    use crate::{input_parser::InputValue, Abi, AbiParameter, AbiType, AbiVisibility, InputMap};
+    use crate::{
+        input_parser::InputValue, Abi, AbiParameter, AbiStructLayout, AbiType, AbiVisibility,
+        InputMap,
+    };
 
     #[test]
     fn witness_encoding_roundtrip() {
@@ -559,6 +631,7 @@ mod test {
             ]),
             return_type: Some(AbiType::Field),
             return_witnesses: vec![Witness(3)],
+            return_constants: BTreeMap::new(),
         };
 
         // Note we omit return value from inputs
@@ -583,4 +656,93 @@ mod test {
         // We also decode the return value (we can do this immediately as we know it shares a witness with an input).
         assert_eq!(return_value.unwrap(), reconstructed_inputs["thing2"]);
     }
+
+    #[test]
+    fn return_constants_roundtrip() {
+        let abi = Abi {
+            parameters: vec![AbiParameter {
+                name: "thing".to_string(),
+                typ: AbiType::Field,
+                visibility: AbiVisibility::Private,
+            }],
+            param_witnesses: BTreeMap::from([("thing".to_string(), vec![Witness(1)])]),
+            return_type: Some(AbiType::Array { length: 3, typ: Box::new(AbiType::Field) }),
+            // Only the middle element of the return value is assigned a witness.
+            return_witnesses: vec![Witness(2)],
+            return_constants: BTreeMap::from([
+                (0, FieldElement::from(5_u128)),
+                (2, FieldElement::from(7_u128)),
+            ]),
+        };
+
+        let inputs: InputMap =
+            BTreeMap::from([("thing".to_string(), InputValue::Field(FieldElement::one()))]);
+        let return_value = InputValue::Vec(vec![
+            InputValue::Field(FieldElement::from(5_u128)),
+            InputValue::Field(FieldElement::from(6_u128)),
+            InputValue::Field(FieldElement::from(7_u128)),
+        ]);
+
+        let witness_map = abi.encode(&inputs, Some(return_value.clone())).unwrap();
+        let (_, decoded_return_value) = abi.decode(&witness_map).unwrap();
+        assert_eq!(decoded_return_value.unwrap(), return_value);
+
+        // A return value which disagrees with a constant element cannot be encoded.
+        let wrong_return_value = InputValue::Vec(vec![
+            InputValue::Field(FieldElement::from(4_u128)),
+            InputValue::Field(FieldElement::from(6_u128)),
+            InputValue::Field(FieldElement::from(7_u128)),
+        ]);
+        assert!(abi.encode(&inputs, Some(wrong_return_value)).is_err());
+    }
+
+    #[test]
+    fn struct_fields_are_encoded_in_the_order_of_their_layout() {
+        // `struct Request { nonce: Field, amount: Field, account: Field }` with `#[repr(sorted)]`
+        let typ = AbiType::Struct {
+            path: "Request".to_string(),
+            fields: vec![
+                ("account".to_string(), AbiType::Field),
+                ("amount".to_string(), AbiType::Field),
+                ("nonce".to_string(), AbiType::Field),
+            ],
+            layout: AbiStructLayout::Sorted,
+        };
+        let abi = Abi {
+            parameters: vec![AbiParameter {
+                name: "request".to_string(),
+                typ: typ.clone(),
+                visibility: AbiVisibility::Private,
+            }],
+            param_witnesses: BTreeMap::from([(
+                "request".to_string(),
+                vec![Witness(1), Witness(2), Witness(3)],
+            )]),
+            return_type: None,
+            return_witnesses: Vec::new(),
+            return_constants: BTreeMap::new(),
+        };
+
+        let field = |value: u128| InputValue::Field(FieldElement::from(value));
+        let request = InputValue::Struct(BTreeMap::from([
+            ("nonce".to_string(), field(7)),
+            ("amount".to_string(), field(100)),
+            ("account".to_string(), field(1)),
+        ]));
+        let inputs: InputMap = BTreeMap::from([("request".to_string(), request.clone())]);
+
+        let witness_map = abi.encode(&inputs, None).unwrap();
+        let values = [Witness(1), Witness(2), Witness(3)].map(|witness| witness_map[&witness]);
+        assert_eq!(values, [1_u128, 100, 7].map(FieldElement::from));
+        assert_eq!(abi.decode(&witness_map).unwrap().0["request"], request);
+
+        let json = serde_json::to_value(&typ).unwrap();
+        assert_eq!(json["layout"], "sorted");
+
+        // ABIs written before layouts were recorded list their fields in declaration order
+        let mut json = json;
+        json.as_object_mut().unwrap().remove("layout");
+        let typ: AbiType = serde_json::from_value(json).unwrap();
+        assert!(matches!(typ, AbiType::Struct { layout: AbiStructLayout::Declared, .. }));
+    }
 }
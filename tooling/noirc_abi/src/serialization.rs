@@ -43,7 +43,7 @@ where
 
 #[cfg(test)]
 mod tests {
-    use crate::{AbiParameter, AbiType, AbiVisibility, Sign};
+    use crate::{AbiParameter, AbiStructLayout, AbiType, AbiVisibility, Sign};
 
     #[test]
     fn abi_parameter_serialization() {
@@ -128,6 +128,7 @@ mod tests {
                         AbiType::Array { length: 2, typ: Box::new(AbiType::Field) },
                     ),
                 ],
+                layout: AbiStructLayout::Declared,
             },
             visibility: AbiVisibility::Private,
         };
@@ -49,6 +49,7 @@ extern "C" {
 const ABI: &'static str = r#"
 export type Visibility = "public" | "private";
 export type Sign = "unsigned" | "signed";
+export type StructLayout = "declared" | "sorted" | "packed";
 export type AbiType = 
     { kind: "field" } |
     { kind: "boolean" } |
@@ -56,7 +57,7 @@ export type AbiType =
     { kind: "integer", sign: Sign, width: number } |
     { kind: "array", length: number, type: AbiType } |
     { kind: "tuple", fields: AbiType[] } |
-    { kind: "struct", path: string, fields: [string, AbiType][] };
+    { kind: "struct", path: string, fields: [string, AbiType][], layout?: StructLayout };
     
 export type AbiParameter = {
     name: string,
@@ -69,6 +70,7 @@ export type Abi = {
     param_witnesses: Record<string, number[]>,
     return_type: AbiType | null,
     return_witnesses: number[],
+    return_constants?: Record<string, string>,
 }
 "#;
 
@@ -0,0 +1,11 @@
+//! Regenerates the golden outputs of the `execution_success` corpus with the default backend.
+
+use test_programs::{generate_golden, Corpus, HarnessError};
+
+fn main() -> Result<(), HarnessError> {
+    for program in Corpus::ExecutionSuccess.programs()? {
+        println!("Regenerating {}", program.name);
+        generate_golden(&program)?;
+    }
+    Ok(())
+}
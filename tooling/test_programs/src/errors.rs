@@ -0,0 +1,33 @@
+use std::path::PathBuf;
+
+use acvm::acir::native_types::WitnessMapError;
+use nargo::NargoError;
+use nargo_toml::ManifestError;
+use noirc_abi::errors::{AbiError, InputParserError};
+use noirc_errors::FileDiagnostic;
+use thiserror::Error;
+
+/// An error raised while running a test program.
+#[derive(Debug, Error)]
+pub enum HarnessError {
+    #[error("Could not read {}: {1}", .0.display())]
+    Io(PathBuf, std::io::Error),
+    #[error(transparent)]
+    Manifest(#[from] ManifestError),
+    #[error("No binary package was found in {}", .0.display())]
+    NoBinaryPackage(PathBuf),
+    #[error("Package `{package}` failed to compile with {} errors", .diagnostics.len())]
+    Compile { package: String, diagnostics: Vec<FileDiagnostic> },
+    #[error("Package `{package}` could not be optimized for the backend: {error}")]
+    Optimize { package: String, error: NargoError },
+    #[error(transparent)]
+    Inputs(#[from] InputParserError),
+    #[error(transparent)]
+    Abi(#[from] AbiError),
+    #[error("Package `{package}` failed to execute: {error}")]
+    Execution { package: String, error: NargoError },
+    #[error("Could not decode golden witness {}: {1}", .0.display())]
+    GoldenWitness(PathBuf, WitnessMapError),
+    #[error("Test program `{0}` was expected to fail but was executed successfully")]
+    UnexpectedSuccess(String),
+}
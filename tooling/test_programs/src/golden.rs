@@ -0,0 +1,183 @@
+use std::path::{Path, PathBuf};
+
+use acvm::acir::{circuit::Circuit, native_types::WitnessMap};
+
+use crate::{errors::HarnessError, run_program, DefaultBackend, Execution, TestProgram};
+
+/// The result of comparing an [`Execution`] against the golden outputs of its test program.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GoldenComparison {
+    /// Both the ACIR and the witness are identical to the golden outputs.
+    Matches,
+    /// The test program has no golden outputs for this execution, either because it isn't the
+    /// execution of the package named after the program or because they weren't regenerated
+    /// since the program was added.
+    Missing,
+    /// The outputs differ from the golden outputs.
+    ///
+    /// The golden ACIR is optimized for the default backend, so a backend with a different
+    /// [`Language`][acvm::Language] should expect its ACIR to differ while still solving the
+    /// same witness for the program's inputs and return value.
+    Mismatch { acir_matches: bool, witness_matches: bool },
+}
+
+/// Returns the directory holding the golden outputs of `program`, which are checked in under
+/// `tooling/test_programs/golden`.
+pub fn golden_directory(program: &TestProgram) -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("golden").join(&program.name)
+}
+
+/// Compiles and executes `program` with the [`DefaultBackend`] and writes the ACIR and witness
+/// of the package named after it to its [`golden_directory`].
+///
+/// This is only run by the `regenerate_golden` binary, so that golden outputs change only when
+/// they are deliberately regenerated and committed.
+pub fn generate_golden(program: &TestProgram) -> Result<(), HarnessError> {
+    let directory = golden_directory(program);
+    let executions = run_program(&DefaultBackend::default(), program)?;
+    for execution in executions.iter().filter(|execution| execution.package == program.name) {
+        write_golden(&directory, execution)?;
+    }
+    Ok(())
+}
+
+/// Compares `execution` against the golden ACIR and witness of `program`.
+///
+/// Only the execution of the package named after the test program has golden outputs.
+pub fn compare_with_golden(
+    program: &TestProgram,
+    execution: &Execution,
+) -> Result<GoldenComparison, HarnessError> {
+    if execution.package != program.name {
+        return Ok(GoldenComparison::Missing);
+    }
+    compare_in_directory(&golden_directory(program), execution)
+}
+
+fn compare_in_directory(
+    directory: &Path,
+    execution: &Execution,
+) -> Result<GoldenComparison, HarnessError> {
+    if !has_golden_outputs(directory) {
+        return Ok(GoldenComparison::Missing);
+    }
+
+    let acir_path = directory.join("acir.gz");
+    let witness_path = directory.join("witness.gz");
+    let golden_circuit = Circuit::read(read_file(&acir_path)?.as_slice())
+        .map_err(|error| HarnessError::Io(acir_path, error))?;
+    let golden_witness = WitnessMap::try_from(read_file(&witness_path)?.as_slice())
+        .map_err(|error| HarnessError::GoldenWitness(witness_path, error))?;
+
+    let acir_matches = golden_circuit == execution.circuit;
+    let witness_matches = golden_witness == execution.witness;
+    if acir_matches && witness_matches {
+        Ok(GoldenComparison::Matches)
+    } else {
+        Ok(GoldenComparison::Mismatch { acir_matches, witness_matches })
+    }
+}
+
+fn has_golden_outputs(directory: &Path) -> bool {
+    directory.join("acir.gz").exists() && directory.join("witness.gz").exists()
+}
+
+fn write_golden(directory: &Path, execution: &Execution) -> Result<(), HarnessError> {
+    let io_error = |path: &Path| {
+        let path = path.to_path_buf();
+        move |error| HarnessError::Io(path, error)
+    };
+    std::fs::create_dir_all(directory).map_err(io_error(directory))?;
+
+    let acir_path = directory.join("acir.gz");
+    let mut acir = Vec::new();
+    execution.circuit.write(&mut acir).map_err(io_error(&acir_path))?;
+    std::fs::write(&acir_path, acir).map_err(io_error(&acir_path))?;
+
+    let witness_path = directory.join("witness.gz");
+    let witness = Vec::<u8>::try_from(execution.witness.clone())
+        .map_err(|error| HarnessError::GoldenWitness(witness_path.clone(), error))?;
+    std::fs::write(&witness_path, witness).map_err(io_error(&witness_path))
+}
+
+fn read_file(path: &Path) -> Result<Vec<u8>, HarnessError> {
+    std::fs::read(path).map_err(|error| HarnessError::Io(path.to_path_buf(), error))
+}
+
+#[cfg(test)]
+mod tests {
+    use acvm::{acir::native_types::Witness, FieldElement};
+
+    use super::*;
+    use crate::{check_program, Corpus};
+
+    fn execute_1_mul() -> Execution {
+        let program = Corpus::ExecutionSuccess
+            .programs()
+            .unwrap()
+            .into_iter()
+            .find(|program| program.name == "1_mul")
+            .unwrap();
+        check_program(&DefaultBackend::default(), &program).unwrap().remove(0)
+    }
+
+    #[test]
+    fn compares_executions_against_written_outputs() {
+        let directory = tempfile::tempdir().unwrap();
+        let execution = execute_1_mul();
+        assert_eq!(
+            compare_in_directory(directory.path(), &execution).unwrap(),
+            GoldenComparison::Missing
+        );
+
+        write_golden(directory.path(), &execution).unwrap();
+        assert_eq!(
+            compare_in_directory(directory.path(), &execution).unwrap(),
+            GoldenComparison::Matches
+        );
+
+        let mut tampered = execution.clone();
+        tampered.witness.insert(Witness(u32::MAX), FieldElement::one());
+        assert_eq!(
+            compare_in_directory(directory.path(), &tampered).unwrap(),
+            GoldenComparison::Mismatch { acir_matches: true, witness_matches: false }
+        );
+
+        tampered.circuit.opcodes.pop();
+        assert_eq!(
+            compare_in_directory(directory.path(), &tampered).unwrap(),
+            GoldenComparison::Mismatch { acir_matches: false, witness_matches: false }
+        );
+    }
+
+    #[test]
+    fn executions_match_the_checked_in_golden_outputs() {
+        let backend = DefaultBackend::default();
+        for program in Corpus::ExecutionSuccess.programs().unwrap() {
+            for execution in check_program(&backend, &program).unwrap() {
+                if execution.package != program.name {
+                    continue;
+                }
+                assert_eq!(
+                    compare_with_golden(&program, &execution).unwrap(),
+                    GoldenComparison::Matches,
+                    "`{}` differs from its golden outputs, which are regenerated with \
+                     `cargo run -p test_programs --bin regenerate_golden`",
+                    program.name
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn only_packages_named_after_the_program_have_golden_outputs() {
+        let mut execution = execute_1_mul();
+        let program = TestProgram {
+            name: "1_mul".to_string(),
+            path: PathBuf::new(),
+            corpus: Corpus::ExecutionSuccess,
+        };
+        execution.package = "other".to_string();
+        assert_eq!(compare_with_golden(&program, &execution).unwrap(), GoldenComparison::Missing);
+    }
+}
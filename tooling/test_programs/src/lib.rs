@@ -0,0 +1,262 @@
+#![forbid(unsafe_code)]
+#![warn(unused_crate_dependencies, unused_extern_crates)]
+#![warn(unreachable_pub)]
+#![warn(clippy::semicolon_if_nothing_returned)]
+
+//! A harness for running the integration test programs of the Noir compiler against a backend.
+//!
+//! The test programs live in `tooling/nargo_cli/tests`, where they are split into corpora
+//! according to the behaviour expected from them (see [`Corpus`]). Downstream projects can use
+//! this crate to run these corpora against their own backend by implementing [`TestBackend`],
+//! and to compare the ACIR and witnesses they obtain against the golden outputs of the compiler
+//! with [`compare_with_golden`]. These outputs are checked in under `tooling/test_programs/golden`
+//! and are regenerated with [`DefaultBackend`] by running
+//! `cargo run -p test_programs --bin regenerate_golden`.
+//!
+//! ```ignore
+//! let backend = DefaultBackend::default();
+//! for program in Corpus::ExecutionSuccess.programs()? {
+//!     for execution in check_program(&backend, &program)? {
+//!         let comparison = compare_with_golden(&program, &execution)?;
+//!         assert!(!matches!(comparison, GoldenComparison::Mismatch { .. }));
+//!     }
+//! }
+//! ```
+
+mod errors;
+mod golden;
+
+use std::path::{Path, PathBuf};
+
+use acvm::{
+    acir::{
+        circuit::{Circuit, Opcode},
+        native_types::WitnessMap,
+    },
+    BlackBoxFunctionSolver, Language,
+};
+use nargo::{package::Package, prepare_package};
+use nargo_toml::{get_package_manifest, resolve_workspace_from_toml, PackageSelection};
+use noirc_abi::{input_parser::Format, MAIN_RETURN_NAME};
+use noirc_driver::{compile_main, CompileOptions};
+
+pub use errors::HarnessError;
+pub use golden::{compare_with_golden, generate_golden, golden_directory, GoldenComparison};
+
+/// A group of test programs sharing the behaviour expected from them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Corpus {
+    /// Programs which compile and execute successfully with the inputs of their `Prover.toml`.
+    ExecutionSuccess,
+    /// Programs which are either rejected by the compiler or fail when executed, as their
+    /// constraints can't be satisfied.
+    CompileFailure,
+}
+
+impl Corpus {
+    /// Returns the directory containing the test programs of this corpus.
+    pub fn directory(self) -> PathBuf {
+        let name = match self {
+            Corpus::ExecutionSuccess => "execution_success",
+            Corpus::CompileFailure => "compile_failure",
+        };
+        tests_directory().join(name)
+    }
+
+    /// Returns the test programs of this corpus, ordered by name.
+    pub fn programs(self) -> Result<Vec<TestProgram>, HarnessError> {
+        let directory = self.directory();
+        let entries =
+            std::fs::read_dir(&directory).map_err(|error| HarnessError::Io(directory, error))?;
+
+        let mut programs: Vec<_> = entries
+            .flatten()
+            .filter(|entry| entry.path().is_dir())
+            .map(|entry| TestProgram {
+                name: entry.file_name().to_string_lossy().into_owned(),
+                path: entry.path(),
+                corpus: self,
+            })
+            .collect();
+        programs.sort_by(|a, b| a.name.cmp(&b.name));
+        Ok(programs)
+    }
+}
+
+/// A test program, which may be a workspace of several packages.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TestProgram {
+    pub name: String,
+    pub path: PathBuf,
+    pub corpus: Corpus,
+}
+
+/// The backend against which test programs are compiled and executed.
+pub trait TestBackend {
+    type Solver: BlackBoxFunctionSolver;
+
+    /// The solver used to execute the black box functions of a circuit.
+    fn blackbox_solver(&self) -> &Self::Solver;
+
+    /// The language which circuits are optimized for before being executed.
+    fn np_language(&self) -> Language;
+
+    /// Returns whether the backend supports `opcode`. Unsupported opcodes are replaced by
+    /// equivalent supported opcodes when optimizing the circuit.
+    fn supports_opcode(&self, opcode: &Opcode) -> bool;
+}
+
+/// The backend the golden outputs were generated with, using the Barretenberg black box solver
+/// and targeting PLONK with a width of 3.
+#[allow(deprecated)]
+#[derive(Default)]
+pub struct DefaultBackend {
+    solver: barretenberg_blackbox_solver::BarretenbergSolver,
+}
+
+#[allow(deprecated)]
+impl TestBackend for DefaultBackend {
+    type Solver = barretenberg_blackbox_solver::BarretenbergSolver;
+
+    fn blackbox_solver(&self) -> &Self::Solver {
+        &self.solver
+    }
+
+    fn np_language(&self) -> Language {
+        Language::PLONKCSat { width: 3 }
+    }
+
+    fn supports_opcode(&self, opcode: &Opcode) -> bool {
+        acvm::pwg::default_is_opcode_supported(self.np_language())(opcode)
+    }
+}
+
+/// The optimized circuit of a binary package and the witness solved from its `Prover.toml`.
+#[derive(Debug, Clone)]
+pub struct Execution {
+    pub package: String,
+    pub circuit: Circuit,
+    pub witness: WitnessMap,
+}
+
+/// Compiles and executes each binary package of `program` with `backend`.
+pub fn run_program<B: TestBackend>(
+    backend: &B,
+    program: &TestProgram,
+) -> Result<Vec<Execution>, HarnessError> {
+    let toml_path = get_package_manifest(&program.path)?;
+    let workspace = resolve_workspace_from_toml(&toml_path, PackageSelection::DefaultOrAll)?;
+
+    let executions: Vec<_> = workspace
+        .into_iter()
+        .filter(|package| package.is_binary())
+        .map(|package| run_package(backend, package))
+        .collect::<Result<_, _>>()?;
+    if executions.is_empty() {
+        return Err(HarnessError::NoBinaryPackage(program.path.clone()));
+    }
+    Ok(executions)
+}
+
+/// Runs `program` and checks that it behaves as expected by its corpus, returning its executions
+/// if it is expected to succeed.
+///
+/// Programs of the [`Corpus::CompileFailure`] corpus are only expected to fail to compile or to
+/// execute: any other error, such as an invalid manifest or missing inputs, is returned.
+pub fn check_program<B: TestBackend>(
+    backend: &B,
+    program: &TestProgram,
+) -> Result<Vec<Execution>, HarnessError> {
+    let result = run_program(backend, program);
+    match program.corpus {
+        Corpus::ExecutionSuccess => result,
+        Corpus::CompileFailure => match result {
+            Ok(_) => Err(HarnessError::UnexpectedSuccess(program.name.clone())),
+            Err(HarnessError::Compile { .. } | HarnessError::Execution { .. }) => Ok(Vec::new()),
+            Err(error) => Err(error),
+        },
+    }
+}
+
+fn run_package<B: TestBackend>(backend: &B, package: &Package) -> Result<Execution, HarnessError> {
+    let package_name = package.name.to_string();
+    let (mut context, crate_id) =
+        prepare_package(package, Box::new(|path| std::fs::read_to_string(path)));
+
+    let compile_options = package.compile_options(&CompileOptions::default());
+    let (program, _warnings) =
+        compile_main(&mut context, crate_id, &compile_options, None, true).map_err(
+            |diagnostics| HarnessError::Compile { package: package_name.clone(), diagnostics },
+        )?;
+    let is_opcode_supported = |opcode: &Opcode| backend.supports_opcode(opcode);
+    let program =
+        nargo::ops::optimize_program(program, backend.np_language(), &is_opcode_supported)
+            .map_err(|error| HarnessError::Optimize { package: package_name.clone(), error })?;
+
+    let initial_witness = if program.abi.is_empty() {
+        WitnessMap::default()
+    } else {
+        let prover_input_path = package.prover_input_path();
+        let inputs = read_to_string(&prover_input_path)?;
+        let mut input_map = Format::Toml.parse(&inputs, &program.abi)?;
+        let return_value = input_map.remove(MAIN_RETURN_NAME);
+        program.abi.encode(&input_map, return_value)?
+    };
+
+    let witness = nargo::ops::execute_circuit(
+        backend.blackbox_solver(),
+        &program.circuit,
+        initial_witness,
+        false,
+    )
+    .map_err(|error| HarnessError::Execution { package: package_name.clone(), error })?;
+
+    Ok(Execution { package: package_name, circuit: program.circuit, witness })
+}
+
+fn read_to_string(path: &Path) -> Result<String, HarnessError> {
+    std::fs::read_to_string(path).map_err(|error| HarnessError::Io(path.to_path_buf(), error))
+}
+
+/// Returns the directory containing the corpora of test programs.
+fn tests_directory() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("../nargo_cli/tests")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn find_program(corpus: Corpus, name: &str) -> TestProgram {
+        let programs = corpus.programs().unwrap();
+        programs.into_iter().find(|program| program.name == name).unwrap()
+    }
+
+    #[test]
+    fn executes_successful_programs() {
+        let program = find_program(Corpus::ExecutionSuccess, "1_mul");
+        let executions = check_program(&DefaultBackend::default(), &program).unwrap();
+
+        assert_eq!(executions.len(), 1);
+        assert_eq!(executions[0].package, "1_mul");
+        assert!(executions[0].witness.get_index(1).is_some());
+    }
+
+    #[test]
+    fn expects_failing_programs_to_fail() {
+        let program = find_program(Corpus::CompileFailure, "brillig_assert_fail");
+        assert!(check_program(&DefaultBackend::default(), &program).unwrap().is_empty());
+    }
+
+    #[test]
+    fn harness_errors_are_not_expected_failures() {
+        let directory = tempfile::tempdir().unwrap();
+        let program = TestProgram {
+            name: "missing_manifest".to_string(),
+            path: directory.path().to_path_buf(),
+            corpus: Corpus::CompileFailure,
+        };
+        let result = check_program(&DefaultBackend::default(), &program);
+        assert!(matches!(result, Err(HarnessError::Manifest(_))));
+    }
+}